@@ -59,6 +59,13 @@ impl AccountsApi {
         ///
         /// If not provided, it will be the latest version
         ledger_version: Query<Option<U64>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<AccountData> {
         fail_point_poem("endpoint_get_account")?;
         self.context
@@ -106,6 +113,13 @@ impl AccountsApi {
         ///
         /// If not provided, defaults to default page size.
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<MoveResource>> {
         fail_point_poem("endpoint_get_account_resources")?;
         self.context
@@ -153,6 +167,13 @@ impl AccountsApi {
         ///
         /// If not provided, defaults to default page size.
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<MoveModuleBytecode>> {
         fail_point_poem("endpoint_get_account_modules")?;
         self.context
@@ -316,7 +337,7 @@ impl Account {
                     &self.latest_ledger_info,
                     BasicResponseStatus::Ok,
                 ))
-                .map(|v| v.with_cursor(next_state_key))
+                .map(|v| v.with_cursor(next_state_key.map(StateKeyWrapper::from)))
             }
             AcceptType::Bcs => {
                 // Put resources in a BTreeMap to ensure they're ordered the same every time
@@ -326,7 +347,7 @@ impl Account {
                     &self.latest_ledger_info,
                     BasicResponseStatus::Ok,
                 ))
-                .map(|v| v.with_cursor(next_state_key))
+                .map(|v| v.with_cursor(next_state_key.map(StateKeyWrapper::from)))
             }
         }
     }
@@ -388,7 +409,7 @@ impl Account {
                     &self.latest_ledger_info,
                     BasicResponseStatus::Ok,
                 ))
-                .map(|v| v.with_cursor(next_state_key))
+                .map(|v| v.with_cursor(next_state_key.map(StateKeyWrapper::from)))
             }
             AcceptType::Bcs => {
                 // Sort modules by name
@@ -401,7 +422,7 @@ impl Account {
                     &self.latest_ledger_info,
                     BasicResponseStatus::Ok,
                 ))
-                .map(|v| v.with_cursor(next_state_key))
+                .map(|v| v.with_cursor(next_state_key.map(StateKeyWrapper::from)))
             }
         }
     }
@@ -5,12 +5,13 @@ use crate::accept_type::AcceptType;
 use crate::context::Context;
 use crate::response::InternalError;
 use crate::response::ServiceUnavailableError;
+use crate::response::{BasicResponse, BasicResponseStatus, BasicResult};
 use crate::{generate_error_response, generate_success_response, ApiTags};
 use anyhow::Context as AnyhowContext;
-use aptos_api_types::AptosErrorCode;
+use aptos_api_types::{AptosErrorCode, StateSyncStatus};
+use aptos_state_sync_driver::bootstrapping_progress::bootstrapping_progress;
 use poem_openapi::{param::Query, payload::Html, Object, OpenApi};
 use serde::{Deserialize, Serialize};
-use std::ops::Sub;
 use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -32,12 +33,36 @@ pub struct BasicApi {
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, Object)]
 pub struct HealthCheckSuccess {
     message: String,
+    /// Results of the checks requested by the query params
+    checks: Vec<HealthCheck>,
 }
 
 impl HealthCheckSuccess {
-    pub fn new() -> Self {
+    pub fn new(checks: Vec<HealthCheck>) -> Self {
         Self {
             message: "aptos-node:ok".to_string(),
+            checks,
+        }
+    }
+}
+
+/// Result of a single healthcheck
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize, Object)]
+pub struct HealthCheck {
+    /// Name of the check, i.e. `ledger_staleness`, `db_writable`,
+    /// `mempool_accepting` or `min_peers`
+    name: String,
+    passed: bool,
+    /// What the check observed
+    details: String,
+}
+
+impl HealthCheck {
+    fn new(name: &str, passed: bool, details: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed,
+            details: details.into(),
         }
     }
 }
@@ -63,8 +88,12 @@ impl BasicApi {
     /// By default this endpoint just checks that it can get the latest ledger
     /// info and then returns 200.
     ///
-    /// If the duration_secs param is provided, this endpoint will return a
-    /// 200 if the following condition is true:
+    /// Further checks can be enabled with the query params, e.g. so that load
+    /// balancers can take fullnodes that fell behind out of rotation. This
+    /// endpoint returns a 200 listing the results of the checks if all of them
+    /// pass, and a 503 naming the failed checks otherwise.
+    ///
+    /// If the duration_secs param is provided, the following condition must be true:
     ///
     /// `server_latest_ledger_info_timestamp >= server_current_time_timestamp - duration_secs`
     #[oai(
@@ -78,20 +107,24 @@ impl BasicApi {
         accept_type: AcceptType,
         /// Threshold in seconds that the server can be behind to be considered healthy
         ///
-        /// If not provided, the healthcheck will always succeed
+        /// If not provided, the ledger staleness isn't checked
         duration_secs: Query<Option<u32>>,
+        /// If true, check that the storage dir of the node accepts writes
+        check_db_writable: Query<Option<bool>>,
+        /// If true, check that mempool isn't full and accepts new transactions
+        check_mempool: Query<Option<bool>>,
+        /// Minimum number of peers mempool broadcasts transactions to
+        min_peers: Query<Option<u32>>,
     ) -> HealthCheckResult<HealthCheckSuccess> {
         let ledger_info = self.context.get_latest_ledger_info()?;
+        let mut checks = vec![];
 
-        // If we have a duration, check that it's close to the current time, otherwise it's ok
+        // If we have a duration, check that it's close to the current time
         if let Some(duration) = duration_secs.0 {
-            let timestamp = ledger_info.timestamp();
-
-            let timestamp = Duration::from_micros(timestamp);
-            let expectation = SystemTime::now()
-                .sub(Duration::from_secs(duration as u64))
+            let timestamp = Duration::from_micros(ledger_info.timestamp());
+            let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
-                .context("Failed to determine absolute unix time based on given duration")
+                .context("Failed to determine the current unix time")
                 .map_err(|err| {
                     HealthCheckError::internal_with_code(
                         err,
@@ -99,20 +132,118 @@ impl BasicApi {
                         &ledger_info,
                     )
                 })?;
+            let staleness = now.saturating_sub(timestamp);
+            checks.push(HealthCheck::new(
+                "ledger_staleness",
+                staleness <= Duration::from_secs(duration as u64),
+                format!(
+                    "The latest ledger info is {}s old, the threshold is {}s",
+                    staleness.as_secs(),
+                    duration
+                ),
+            ));
+        }
 
-            if timestamp < expectation {
-                return Err(HealthCheckError::service_unavailable_with_code(
-                    "The latest ledger info timestamp is less than the expected timestamp",
-                    AptosErrorCode::HealthCheckFailed,
-                    &ledger_info,
+        if check_db_writable.0.unwrap_or(false) {
+            checks.push(match self.context.check_storage_writable() {
+                Ok(()) => HealthCheck::new("db_writable", true, "The storage dir is writable"),
+                Err(err) => HealthCheck::new("db_writable", false, format!("{:#}", err)),
+            });
+        }
+
+        let check_mempool = check_mempool.0.unwrap_or(false);
+        if check_mempool || min_peers.0.is_some() {
+            let mempool_health = self
+                .context
+                .get_mempool_health()
+                .await
+                .context("Failed to get the health of mempool")
+                .map_err(|err| {
+                    HealthCheckError::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                })?;
+            if check_mempool {
+                checks.push(HealthCheck::new(
+                    "mempool_accepting",
+                    !mempool_health.is_full,
+                    if mempool_health.is_full {
+                        "Mempool is full"
+                    } else {
+                        "Mempool accepts transactions"
+                    },
+                ));
+            }
+            if let Some(min_peers) = min_peers.0 {
+                checks.push(HealthCheck::new(
+                    "min_peers",
+                    mempool_health.num_upstream_peers >= min_peers as usize,
+                    format!(
+                        "Connected to {} peers, the minimum is {}",
+                        mempool_health.num_upstream_peers, min_peers
+                    ),
                 ));
             }
         }
+
+        let failed_checks: Vec<_> = checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| format!("{}: {}", check.name, check.details))
+            .collect();
+        if !failed_checks.is_empty() {
+            return Err(HealthCheckError::service_unavailable_with_code(
+                format!("Failed health checks: {}", failed_checks.join("; ")),
+                AptosErrorCode::HealthCheckFailed,
+                &ledger_info,
+            ));
+        }
         HealthCheckResponse::try_from_rust_value((
-            HealthCheckSuccess::new(),
+            HealthCheckSuccess::new(checks),
             &ledger_info,
             HealthCheckResponseStatus::Ok,
             &accept_type,
         ))
     }
+
+    /// Get the state sync status
+    ///
+    /// Returns the bootstrapping progress of the node: the bootstrapping mode
+    /// and phase, the synced and target versions, the number of state values
+    /// downloaded, the sync throughput and the estimated time until the
+    /// target version is reached.
+    #[oai(
+        path = "/-/state_sync",
+        method = "get",
+        operation_id = "get_state_sync_status",
+        tag = "ApiTags::General"
+    )]
+    async fn state_sync_status(&self, accept_type: AcceptType) -> BasicResult<StateSyncStatus> {
+        self.context
+            .check_api_output_enabled("Get state sync status", &accept_type)?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+
+        let progress = bootstrapping_progress();
+        let status = StateSyncStatus {
+            bootstrapping_mode: progress
+                .bootstrapping_mode
+                .map(|mode| mode.to_label().to_string()),
+            phase: progress.phase.to_label().to_string(),
+            synced_version: progress.synced_version.map(Into::into),
+            target_version: progress.target_version.map(Into::into),
+            synced_states: progress.synced_states.into(),
+            throughput_per_sec: progress
+                .throughput_per_sec
+                .map(|throughput| (throughput as u64).into()),
+            eta_secs: progress.eta_secs.map(Into::into),
+        };
+        BasicResponse::try_from_rust_value((
+            status,
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            &accept_type,
+        ))
+    }
 }
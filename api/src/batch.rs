@@ -0,0 +1,205 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    accounts::Account,
+    events::EventsApi,
+    failpoint::fail_point_poem,
+    page::Page,
+    response::{
+        AptosResponseContent, BadRequestError, BasicErrorWith404, BasicResponse,
+        BasicResponseStatus, BasicResultWith404,
+    },
+    state::StateApi,
+    transactions::TransactionsApi,
+    ApiTags, Context,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    AccountResourceRequest, AptosError, AptosErrorCode, BatchReadRequest, BatchReadResult,
+    EventsByCreationNumberRequest, MoveResource, VerifyInputWithRecursion, VersionedEvent, U64,
+};
+use aptos_types::event::EventKey;
+use poem_openapi::{param::Query, payload::Json, OpenApi};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// API for reading several kinds of data in a single request
+pub struct BatchApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl BatchApi {
+    /// Batch read
+    ///
+    /// Performs a list of reads of resources, events and transactions, and returns their
+    /// results in the same order. Each result carries the status code and the data or error
+    /// the read would have been answered with on its own, so a failing read doesn't fail the
+    /// whole batch.
+    ///
+    /// All resources are read at the same ledger version. The results are only returned
+    /// as JSON.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/batch",
+        method = "post",
+        operation_id = "batch_read",
+        tag = "ApiTags::General"
+    )]
+    async fn batch_read(
+        &self,
+        accept_type: AcceptType,
+        /// The reads to perform, in order
+        requests: Json<Vec<BatchReadRequest>>,
+        /// Ledger version to read resources at
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<BatchReadResult>> {
+        fail_point_poem("endpoint_batch_read")?;
+        self.context
+            .check_api_output_enabled("Batch read", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(BasicErrorWith404::bad_request_with_code_no_info(
+                "BCS is not supported for batch reads",
+                AptosErrorCode::InvalidInput,
+            ));
+        }
+
+        let (ledger_info, requested_version) = self
+            .context
+            .get_latest_ledger_info_and_verify_lookup_version(
+                ledger_version.0.map(|inner| inner.0),
+            )?;
+        if requests.0.len() > self.context.max_batch_read_size() {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                &format!(
+                    "Requested too many reads: {}, while limit is {}",
+                    requests.0.len(),
+                    self.context.max_batch_read_size(),
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        let mut results = Vec::with_capacity(requests.0.len());
+        for request in requests.0 {
+            results.push(self.read(request, requested_version).await);
+        }
+        BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+    }
+}
+
+impl BatchApi {
+    async fn read(&self, request: BatchReadRequest, ledger_version: u64) -> BatchReadResult {
+        match request {
+            BatchReadRequest::AccountResourceRequest(request) => {
+                into_batch_read_result(self.resource(request, ledger_version))
+            }
+            BatchReadRequest::EventsByCreationNumberRequest(request) => {
+                into_batch_read_result(self.events(request))
+            }
+            BatchReadRequest::TransactionByHashRequest(request) => into_batch_read_result(
+                TransactionsApi {
+                    context: self.context.clone(),
+                }
+                .get_transaction_by_hash_inner(&AcceptType::Json, request.hash)
+                .await,
+            ),
+            BatchReadRequest::TransactionByVersionRequest(request) => into_batch_read_result(
+                TransactionsApi {
+                    context: self.context.clone(),
+                }
+                .get_transaction_by_version_inner(&AcceptType::Json, request.version)
+                .await,
+            ),
+        }
+    }
+
+    fn resource(
+        &self,
+        request: AccountResourceRequest,
+        ledger_version: u64,
+    ) -> BasicResultWith404<MoveResource> {
+        request
+            .resource_type
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        StateApi {
+            context: self.context.clone(),
+        }
+        .resource(
+            &AcceptType::Json,
+            request.address,
+            request.resource_type,
+            Some(ledger_version),
+        )
+    }
+
+    fn events(
+        &self,
+        request: EventsByCreationNumberRequest,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let page = Page::new(
+            request.start.map(|v| v.0),
+            request.limit,
+            self.context.max_events_page_size(),
+        );
+
+        // Ensure that account exists
+        let account = Account::new(self.context.clone(), request.address, None, None, None)?;
+        account.get_account_resource()?;
+        EventsApi {
+            context: self.context.clone(),
+        }
+        .list(
+            account.latest_ledger_info,
+            AcceptType::Json,
+            page,
+            EventKey::new(request.creation_number.0, request.address.into()),
+        )
+    }
+}
+
+/// Converts the response of an individual read into the result of a batch read
+fn into_batch_read_result<T: poem_openapi::types::ToJSON + Send + Sync + Serialize>(
+    result: BasicResultWith404<T>,
+) -> BatchReadResult {
+    match result {
+        Ok(BasicResponse::Ok(AptosResponseContent::Json(Json(data)), ..)) => {
+            match serde_json::to_value(data) {
+                Ok(data) => BatchReadResult::ok(data),
+                Err(err) => BatchReadResult::error(
+                    500,
+                    AptosError::new_with_error_code(err, AptosErrorCode::InternalError),
+                ),
+            }
+        }
+        Ok(BasicResponse::Ok(AptosResponseContent::Bcs(_), ..)) => BatchReadResult::error(
+            500,
+            AptosError::new_with_error_code(
+                "Read unexpectedly returned BCS",
+                AptosErrorCode::InternalError,
+            ),
+        ),
+        Err(err) => {
+            let (status, Json(error)) = match err {
+                BasicErrorWith404::BadRequest(error, ..) => (400, error),
+                BasicErrorWith404::Forbidden(error, ..) => (403, error),
+                BasicErrorWith404::NotFound(error, ..) => (404, error),
+                BasicErrorWith404::Gone(error, ..) => (410, error),
+                BasicErrorWith404::Internal(error, ..) => (500, error),
+                BasicErrorWith404::ServiceUnavailable(error, ..) => (503, error),
+            };
+            BatchReadResult::error(status, error)
+        }
+    }
+}
@@ -132,10 +132,13 @@ impl BlocksApi {
         match accept_type {
             AcceptType::Json => {
                 let transactions = if let Some(inner) = bcs_block.transactions {
+                    let block = self
+                        .context
+                        .get_block_context(&latest_ledger_info, bcs_block.first_version)?;
                     Some(self.context.render_transactions_sequential(
                         &latest_ledger_info,
                         inner,
-                        bcs_block.block_timestamp,
+                        block,
                     )?)
                 } else {
                     None
@@ -9,13 +9,17 @@ use crate::response::{
 };
 use anyhow::{bail, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, BcsBlock, GasEstimation, LedgerInfo, TransactionOnChainData,
+    AptosErrorCode, AsConverter, BcsBlock, GasEstimation, LedgerInfo, SubmissionReceipt,
+    SubmissionReceiptMessage, TransactionBlockContext, TransactionOnChainData,
 };
-use aptos_config::config::{NodeConfig, RoleType};
-use aptos_crypto::HashValue;
+use aptos_config::config::{ApiConfig, NodeConfig, RoleType};
+use aptos_crypto::{x25519, HashValue};
 use aptos_gas::{AptosGasParameters, FromOnChainGasSchedule};
+use aptos_infallible::Mutex;
 use aptos_logger::error;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{
+    EvictedTransaction, MempoolClientRequest, MempoolClientSender, MempoolHealth, SubmissionStatus,
+};
 use aptos_state_view::TStateView;
 use aptos_storage_interface::{
     state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView},
@@ -31,15 +35,18 @@ use aptos_types::{
     contract_event::EventWithVersion,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    on_chain_config::{GasSchedule, GasScheduleV2, OnChainConfig},
+    on_chain_config::{GasPriceFloor, GasSchedule, GasScheduleV2, OnChainConfig},
     state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
-    transaction::{SignedTransaction, Transaction, TransactionWithProof, Version},
+    transaction::{EntryFunction, SignedTransaction, Transaction, TransactionWithProof, Version},
+};
+use aptos_vm::{
+    data_cache::{IntoMoveResolver, StorageAdapter, StorageAdapterOwned},
+    AptosVM, ArgumentError,
 };
-use aptos_vm::data_cache::{IntoMoveResolver, StorageAdapter, StorageAdapterOwned};
 use futures::{channel::oneshot, SinkExt};
 use itertools::Itertools;
-use move_core_types::language_storage::{ModuleId, StructTag};
-use std::sync::RwLock;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use std::sync::{RwLock, RwLockReadGuard};
 use std::{collections::HashMap, sync::Arc};
 
 // Context holds application scope context
@@ -49,8 +56,15 @@ pub struct Context {
     pub db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     pub node_config: NodeConfig,
+    // The part of `node_config.api` that can be changed while the node is running
+    api_config: Arc<RwLock<ApiConfig>>,
     gas_estimation: Arc<RwLock<GasEstimationCache>>,
     gas_schedule_cache: Arc<RwLock<GasScheduleCache>>,
+    // The peer id and network identity key of the node, if it signs submission receipts
+    receipt_signer: Option<Arc<(AccountAddress, x25519::PrivateKey)>>,
+    // The VM checking the arguments of submitted entry function calls, and the version of the
+    // state it was created for
+    argument_checker: Arc<Mutex<Option<(Option<Version>, AptosVM)>>>,
 }
 
 impl std::fmt::Debug for Context {
@@ -66,10 +80,19 @@ impl Context {
         mp_sender: MempoolClientSender,
         node_config: NodeConfig,
     ) -> Self {
+        let receipt_signer = if node_config.api.submission_receipts_enabled {
+            node_config
+                .peer_id()
+                .zip(node_config.identity_key())
+                .map(Arc::new)
+        } else {
+            None
+        };
         Self {
             chain_id,
             db,
             mp_sender,
+            api_config: Arc::new(RwLock::new(node_config.api.clone())),
             node_config,
             gas_estimation: Arc::new(RwLock::new(GasEstimationCache {
                 last_updated_version: None,
@@ -82,23 +105,38 @@ impl Context {
                 last_updated_epoch: None,
                 gas_schedule_params: None,
             })),
+            receipt_signer,
+            argument_checker: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Returns the API config the node is currently serving with, which may have been
+    /// reloaded since the node started.
+    pub fn api_config(&self) -> RwLockReadGuard<'_, ApiConfig> {
+        self.api_config.read().unwrap()
+    }
+
+    /// Returns a handle for replacing the API config while the node is running. Only the
+    /// limits and feature toggles checked per request pick up the change, the listen address,
+    /// TLS and CORS settings are fixed once the server is started.
+    pub fn api_config_handle(&self) -> Arc<RwLock<ApiConfig>> {
+        self.api_config.clone()
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
-        self.node_config.api.max_transactions_page_size
+        self.api_config().max_transactions_page_size
     }
 
     pub fn max_events_page_size(&self) -> u16 {
-        self.node_config.api.max_events_page_size
+        self.api_config().max_events_page_size
     }
 
     pub fn max_account_resources_page_size(&self) -> u16 {
-        self.node_config.api.max_account_resources_page_size
+        self.api_config().max_account_resources_page_size
     }
 
     pub fn max_account_modules_page_size(&self) -> u16 {
-        self.node_config.api.max_account_modules_page_size
+        self.api_config().max_account_modules_page_size
     }
 
     pub fn move_resolver(&self) -> Result<StorageAdapterOwned<DbStateView>> {
@@ -116,6 +154,28 @@ impl Context {
             .map_err(|e| E::internal_with_code(e, AptosErrorCode::InternalError, ledger_info))
     }
 
+    /// Checks the arguments of an entry function call against the latest state checkpoint. The
+    /// VM is reused until the checkpoint moves on, as creating it reads the on-chain configs, and
+    /// it caches the modules it loads. This reads the DB, so it must not run on an async task.
+    pub fn check_entry_function_arguments(
+        &self,
+        entry_function: &EntryFunction,
+    ) -> Result<Result<(), ArgumentError>> {
+        let state_view = self.db.latest_state_checkpoint_view()?;
+        let vm = {
+            let mut argument_checker = self.argument_checker.lock();
+            match &*argument_checker {
+                Some((version, vm)) if *version == state_view.version => vm.clone(),
+                _ => {
+                    let vm = AptosVM::new(&state_view);
+                    *argument_checker = Some((state_view.version, vm.clone()));
+                    vm
+                }
+            }
+        };
+        Ok(vm.check_entry_function_arguments(&state_view, entry_function))
+    }
+
     pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
         self.db.state_view_at_version(Some(version))
     }
@@ -136,8 +196,32 @@ impl Context {
         self.node_config.api.failpoints_enabled
     }
 
+    pub fn dev_endpoints_enabled(&self) -> bool {
+        self.node_config.api.dev_endpoints_enabled
+    }
+
     pub fn max_submit_transaction_batch_size(&self) -> usize {
-        self.node_config.api.max_submit_transaction_batch_size
+        self.api_config().max_submit_transaction_batch_size
+    }
+
+    pub fn max_batch_read_size(&self) -> usize {
+        self.api_config().max_batch_read_size
+    }
+
+    pub fn max_gas_view_function(&self) -> u64 {
+        self.api_config().max_gas_view_function
+    }
+
+    pub fn transaction_submission_enabled(&self) -> bool {
+        self.api_config().transaction_submission_enabled
+    }
+
+    pub fn transaction_simulation_enabled(&self) -> bool {
+        self.api_config().transaction_simulation_enabled
+    }
+
+    pub fn encode_submission_enabled(&self) -> bool {
+        self.api_config().encode_submission_enabled
     }
 
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
@@ -150,6 +234,31 @@ impl Context {
         callback.await?
     }
 
+    /// Whether the node has a network identity to sign submission receipts with, if it's
+    /// configured to issue them.
+    pub fn can_sign_submission_receipts(&self) -> bool {
+        !self.node_config.api.submission_receipts_enabled || self.receipt_signer.is_some()
+    }
+
+    /// Signs a receipt for a transaction this node accepted into its mempool with the network
+    /// identity key of the node, if the node is configured to issue receipts.
+    pub fn sign_submission_receipt(
+        &self,
+        transaction_hash: HashValue,
+    ) -> Result<Option<SubmissionReceipt>> {
+        let (node, identity_key) = match self.receipt_signer.as_deref() {
+            Some((node, identity_key)) => (*node, identity_key),
+            None => return Ok(None),
+        };
+        let message = SubmissionReceiptMessage {
+            chain_id: self.chain_id().id(),
+            transaction_hash,
+            accepted_at: aptos_infallible::duration_since_epoch().as_micros() as u64,
+            node,
+        };
+        SubmissionReceipt::sign(message, identity_key).map(Some)
+    }
+
     // For use from external crates where they don't want to handle
     // the API response error types.
     pub fn get_latest_ledger_info_wrapped(&self) -> anyhow::Result<LedgerInfo> {
@@ -369,14 +478,15 @@ impl Context {
         })
     }
 
-    pub fn get_block_timestamp<E: InternalError>(
+    pub fn get_block_context<E: InternalError>(
         &self,
         ledger_info: &LedgerInfo,
         version: u64,
-    ) -> Result<u64, E> {
+    ) -> Result<TransactionBlockContext, E> {
         self.db
-            .get_block_timestamp(version)
-            .context("Failed to retrieve block timestamp")
+            .get_block_info_by_version(version)
+            .map(|(_, _, new_block_event)| TransactionBlockContext::from(&new_block_event))
+            .context("Failed to retrieve block info")
             .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
     }
 
@@ -392,6 +502,10 @@ impl Context {
             return Err(block_not_found_by_height(height, latest_ledger_info));
         }
 
+        if with_transactions {
+            return self.get_block_by_height_with_transactions(height, latest_ledger_info);
+        }
+
         let (first_version, last_version, new_block_event) = self
             .db
             .get_block_info_by_height(height)
@@ -406,6 +520,61 @@ impl Context {
         )
     }
 
+    /// Reads the block along with its transactions in one pass over storage, rather than one
+    /// lookup per transaction.
+    fn get_block_by_height_with_transactions<E: StdApiError>(
+        &self,
+        height: u64,
+        latest_ledger_info: &LedgerInfo,
+    ) -> Result<BcsBlock, E> {
+        let ledger_version = latest_ledger_info.ledger_version.0;
+        let block = self
+            .db
+            .get_block_by_height_with_transactions(
+                height,
+                self.max_transactions_page_size() as u64,
+                ledger_version,
+            )
+            .map_err(|_| block_not_found_by_height(height, latest_ledger_info))?;
+
+        // We can't pull a block in the future, but this shouldn't happen
+        if block.last_version > ledger_version {
+            return Err(block_not_found_by_height(height, latest_ledger_info));
+        }
+
+        let block_hash = block
+            .new_block_event
+            .hash()
+            .context("Failed to parse block hash")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, latest_ledger_info)
+            })?;
+        let transactions = block
+            .transactions
+            .into_iter()
+            .map(|txn| {
+                (
+                    txn.version,
+                    txn.transaction,
+                    txn.info,
+                    txn.events,
+                    txn.accumulator_root_hash,
+                    txn.write_set,
+                )
+                    .into()
+            })
+            .collect();
+
+        Ok(BcsBlock {
+            block_height: block.new_block_event.height(),
+            block_hash,
+            block_timestamp: block.new_block_event.proposed_time(),
+            first_version: block.first_version,
+            last_version: block.last_version,
+            transactions: Some(transactions),
+        })
+    }
+
     pub fn get_block_by_version<E: StdApiError>(
         &self,
         version: u64,
@@ -460,7 +629,7 @@ impl Context {
 
         // We can only get the max_transactions page size
         let max_txns = std::cmp::min(
-            self.node_config.api.max_transactions_page_size,
+            self.max_transactions_page_size(),
             (last_version - first_version + 1) as u16,
         );
         let txns = if with_transactions {
@@ -493,7 +662,7 @@ impl Context {
         &self,
         ledger_info: &LedgerInfo,
         data: Vec<TransactionOnChainData>,
-        mut timestamp: u64,
+        mut block: TransactionBlockContext,
     ) -> Result<Vec<aptos_api_types::Transaction>, E> {
         if data.is_empty() {
             return Ok(vec![]);
@@ -504,11 +673,11 @@ impl Context {
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
             .map(|t| {
-                // Update the timestamp if the next block occurs
+                // Update the block if the next block occurs
                 if let Transaction::BlockMetadata(ref txn) = t.transaction {
-                    timestamp = txn.timestamp_usecs();
+                    block = TransactionBlockContext::from(txn);
                 }
-                let txn = converter.try_into_onchain_transaction(timestamp, t)?;
+                let txn = converter.try_into_onchain_transaction(block, t)?;
                 Ok(txn)
             })
             .collect::<Result<_, anyhow::Error>>()
@@ -531,11 +700,27 @@ impl Context {
 
         let resolver = self.move_resolver_poem(ledger_info)?;
         let converter = resolver.as_converter(self.db.clone());
+        // Transactions are often from the same block, so keep the last block looked up as
+        // (first version, last version, block) instead of looking it up for each transaction
+        let mut cached_block: Option<(u64, u64, TransactionBlockContext)> = None;
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
             .map(|t| {
-                let timestamp = self.db.get_block_timestamp(t.version)?;
-                let txn = converter.try_into_onchain_transaction(timestamp, t)?;
+                let block = match cached_block {
+                    Some((first_version, last_version, block))
+                        if (first_version..=last_version).contains(&t.version) =>
+                    {
+                        block
+                    }
+                    _ => {
+                        let (first_version, last_version, new_block_event) =
+                            self.db.get_block_info_by_version(t.version)?;
+                        let block = TransactionBlockContext::from(&new_block_event);
+                        cached_block = Some((first_version, last_version, block));
+                        block
+                    }
+                };
+                let txn = converter.try_into_onchain_transaction(block, t)?;
                 Ok(txn)
             })
             .collect::<Result<_, anyhow::Error>>()
@@ -679,6 +864,45 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    /// Looks up a transaction recently evicted from mempool
+    pub async fn get_evicted_transaction_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<EvictedTransaction>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetEvictedTransactionByHash(
+                hash, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_mempool_health(&self) -> Result<MempoolHealth> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetHealth(req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Checks that the storage dir accepts writes, e.g. that the disk isn't full or remounted
+    /// read-only, by writing and removing a probe file.
+    pub fn check_storage_writable(&self) -> Result<()> {
+        let probe = self.node_config.storage.dir().join(".api_health_check");
+        std::fs::write(&probe, b"ok")
+            .and_then(|_| std::fs::remove_file(&probe))
+            .with_context(|| format!("Failed to write {:?}", probe))
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -739,6 +963,18 @@ impl Context {
         }
     }
 
+    pub fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: u64,
+        start_index: u64,
+        limit: u64,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        self.db
+            .get_events_by_type(type_tag, start_version, start_index, limit, ledger_version)
+    }
+
     pub fn estimate_gas_price<E: InternalError>(
         &self,
         ledger_info: &LedgerInfo,
@@ -909,26 +1145,34 @@ impl Context {
                 })?;
             let storage_adapter = StorageAdapter::new(&state_view);
 
-            let gas_schedule_params =
-                match GasScheduleV2::fetch_config(&storage_adapter).and_then(|gas_schedule| {
+            let mut gas_schedule_params = match GasScheduleV2::fetch_config(&storage_adapter)
+                .and_then(|gas_schedule| {
                     let feature_version = gas_schedule.feature_version;
                     let gas_schedule = gas_schedule.to_btree_map();
                     AptosGasParameters::from_on_chain_gas_schedule(&gas_schedule, feature_version)
                 }) {
-                    Some(gas_schedule) => Ok(gas_schedule),
-                    None => GasSchedule::fetch_config(&storage_adapter)
-                        .and_then(|gas_schedule| {
-                            let gas_schedule = gas_schedule.to_btree_map();
-                            AptosGasParameters::from_on_chain_gas_schedule(&gas_schedule, 0)
-                        })
-                        .ok_or_else(|| {
-                            E::internal_with_code(
-                                "Failed to retrieve gas schedule",
-                                AptosErrorCode::InternalError,
-                                ledger_info,
-                            )
-                        }),
-                }?;
+                Some(gas_schedule) => Ok(gas_schedule),
+                None => GasSchedule::fetch_config(&storage_adapter)
+                    .and_then(|gas_schedule| {
+                        let gas_schedule = gas_schedule.to_btree_map();
+                        AptosGasParameters::from_on_chain_gas_schedule(&gas_schedule, 0)
+                    })
+                    .ok_or_else(|| {
+                        E::internal_with_code(
+                            "Failed to retrieve gas schedule",
+                            AptosErrorCode::InternalError,
+                            ledger_info,
+                        )
+                    }),
+            }?;
+
+            // Apply the floor scheduled through governance, so estimates aren't rejected
+            if let Some(floor) = GasPriceFloor::fetch_config(&storage_adapter) {
+                let min_gas_unit_price = floor.effective_min_gas_unit_price(ledger_info.epoch.0);
+                if min_gas_unit_price > u64::from(gas_schedule_params.txn.min_price_per_gas_unit) {
+                    gas_schedule_params.txn.min_price_per_gas_unit = min_gas_unit_price.into();
+                }
+            }
 
             // Update the cache
             cache.gas_schedule_params = Some(gas_schedule_params.clone());
@@ -947,12 +1191,12 @@ impl Context {
     ) -> Result<(), E> {
         match accept_type {
             AcceptType::Json => {
-                if !self.node_config.api.json_output_enabled {
+                if !self.api_config().json_output_enabled {
                     return Err(json_api_disabled(api_name));
                 }
             }
             AcceptType::Bcs => {
-                if !self.node_config.api.bcs_output_enabled {
+                if !self.api_config().bcs_output_enabled {
                     return Err(bcs_api_disabled(api_name));
                 }
             }
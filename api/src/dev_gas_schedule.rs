@@ -0,0 +1,169 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dev-only endpoint that overrides gas parameters on a local network, by submitting a
+//! transaction signed with the root key of the network. This lets developers experiment with
+//! pricing without generating and voting on a governance proposal.
+//!
+//! `POST /v1/dev/gas_schedule` takes a JSON object mapping the names of gas parameters to their
+//! new values, e.g. `{"txn.write_data.per_byte_in_val": 0}`, and returns the hash and sequence
+//! number of the update transaction. The overrides take effect once it's committed.
+//!
+//! The endpoint is unauthenticated, so it's only served if `ApiConfig::dev_endpoints_enabled`
+//! is set, which `aptos node run-local-testnet` does, and only to clients on the same host.
+
+use crate::context::Context;
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_gas::GasScheduleOverrides;
+use aptos_logger::info;
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{aptos_test_root_address, AccountResource},
+    chain_id::ChainId,
+    mempool_status::MempoolStatusCode,
+    on_chain_config::{GasScheduleV2, OnChainConfig},
+    state_store::state_key::StateKey,
+    transaction::{EntryFunction, RawTransaction},
+};
+use aptos_vm::data_cache::StorageAdapter;
+use move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, ResourceKey, CORE_CODE_ADDRESS},
+    move_resource::MoveStructType,
+};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, RemoteAddr},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How long the gas schedule update stays valid in mempool
+const UPDATE_EXPIRATION_SECS: u64 = 60;
+
+/// The transaction that applies the overrides, which takes effect once it's committed
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GasScheduleUpdate {
+    pub hash: String,
+    pub sequence_number: u64,
+}
+
+#[handler]
+pub async fn set_gas_schedule_overrides_poem(
+    context: Data<&Arc<Context>>,
+    remote_addr: &RemoteAddr,
+    Json(overrides): Json<GasScheduleOverrides>,
+) -> poem::Result<Json<GasScheduleUpdate>> {
+    if !context.dev_endpoints_enabled() {
+        return Err(poem::Error::from_string(
+            "Dev endpoints are disabled",
+            StatusCode::NOT_FOUND,
+        ));
+    }
+    if !is_local_client(remote_addr) {
+        return Err(poem::Error::from_string(
+            "Dev endpoints are only served to clients on the same host",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    set_gas_schedule_overrides(&context, overrides)
+        .await
+        .map(Json)
+        .map_err(|e| poem::Error::from_string(e.to_string(), StatusCode::BAD_REQUEST))
+}
+
+fn is_local_client(remote_addr: &RemoteAddr) -> bool {
+    remote_addr
+        .as_socket_addr()
+        .map_or(false, |addr| addr.ip().is_loopback())
+}
+
+async fn set_gas_schedule_overrides(
+    context: &Context,
+    overrides: GasScheduleOverrides,
+) -> Result<GasScheduleUpdate> {
+    let root_key_path = context
+        .api_config()
+        .dev_root_key_path
+        .clone()
+        .ok_or_else(|| format_err!("The root key of the network isn't configured"))?;
+    ensure!(
+        context.chain_id() != ChainId::mainnet(),
+        "Dev endpoints can't be used on mainnet"
+    );
+    let root_key: Ed25519PrivateKey = bcs::from_bytes(&std::fs::read(&root_key_path)?)?;
+
+    // Apply the overrides to the current gas schedule
+    let ledger_info = context.get_latest_ledger_info_wrapped()?;
+    let state_view = context.state_view_at_version(ledger_info.version())?;
+    let mut gas_schedule = GasScheduleV2::fetch_config(&StorageAdapter::new(&state_view))
+        .ok_or_else(|| format_err!("Failed to fetch the gas schedule"))?;
+    overrides.apply(&mut gas_schedule)?;
+
+    let root_address = aptos_test_root_address();
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        root_address,
+        AccountResource::struct_tag(),
+    )));
+    let account: AccountResource = bcs::from_bytes(
+        &context
+            .get_state_value(&state_key, ledger_info.version())?
+            .ok_or_else(|| format_err!("The root account doesn't exist"))?,
+    )?;
+
+    let gas_params = context
+        .get_gas_schedule::<crate::response::BasicError>(&ledger_info)
+        .map_err(|e| format_err!("Failed to fetch the gas parameters: {:?}", e))?
+        .1;
+    let txn = RawTransaction::new_entry_function(
+        root_address,
+        account.sequence_number(),
+        EntryFunction::new(
+            ModuleId::new(CORE_CODE_ADDRESS, ident_str!("aptos_governance").to_owned()),
+            ident_str!("set_gas_schedule_testnet_only").to_owned(),
+            vec![],
+            vec![bcs::to_bytes(&bcs::to_bytes(&gas_schedule)?)?],
+        ),
+        u64::from(gas_params.txn.maximum_number_of_gas_units),
+        u64::from(gas_params.txn.min_price_per_gas_unit),
+        ledger_info.timestamp() / 1_000_000 + UPDATE_EXPIRATION_SECS,
+        context.chain_id(),
+    )
+    .sign(&root_key, root_key.public_key())?
+    .into_inner();
+    let hash = txn.clone().committed_hash();
+
+    let (mempool_status, vm_status) = context.submit_transaction(txn).await?;
+    if mempool_status.code != MempoolStatusCode::Accepted {
+        bail!(
+            "Gas schedule update was rejected: {:?} {:?}",
+            mempool_status,
+            vm_status
+        );
+    }
+    info!(
+        "Submitted gas schedule overrides {:?} in transaction {}",
+        overrides.entries, hash
+    );
+    Ok(GasScheduleUpdate {
+        hash: hash.to_hex_literal(),
+        sequence_number: account.sequence_number(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poem::Addr;
+
+    #[test]
+    fn test_is_local_client() {
+        let remote_addr = |addr: &str| RemoteAddr(Addr::SocketAddr(addr.parse().unwrap()));
+        assert!(is_local_client(&remote_addr("127.0.0.1:5000")));
+        assert!(is_local_client(&remote_addr("[::1]:5000")));
+        assert!(!is_local_client(&remote_addr("10.0.0.1:5000")));
+        assert!(!is_local_client(&RemoteAddr(Addr::Unknown)));
+    }
+}
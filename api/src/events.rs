@@ -13,10 +13,11 @@ use crate::response::{
 use crate::ApiTags;
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
-    MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
+    verify_field_identifier, Address, AptosErrorCode, AsConverter, EventCursor, IdentifierWrapper,
+    LedgerInfo, MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::EventWithVersion, event::EventKey};
+use move_core_types::language_storage::{StructTag, TypeTag};
 use poem_openapi::param::Query;
 use poem_openapi::{param::Path, OpenApi};
 use std::sync::Arc;
@@ -58,6 +59,13 @@ impl EventsApi {
         ///
         /// If unspecified, defaults to default page size
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         fail_point_poem("endpoint_get_events_by_event_key")?;
         self.context
@@ -110,6 +118,13 @@ impl EventsApi {
         ///
         /// If unspecified, defaults to default page size
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<VersionedEvent>> {
         event_handle
             .0
@@ -135,11 +150,131 @@ impl EventsApi {
         let key = account.find_event_key(event_handle.0, field_name.0.into())?;
         self.list(account.latest_ledger_info, accept_type, page, key)
     }
+
+    /// Get events by type
+    ///
+    /// This API returns events of the given type, e.g. `0x1::coin::DepositEvent`, emitted
+    /// by any account, in the order of the transactions that emitted them. This is useful
+    /// when the event handles that emit the events are not known in advance.
+    ///
+    /// If there are more events than the returned page, the position of the next one is
+    /// returned in the X-Aptos-Cursor header, to be passed as `start` to get the next page.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Type of the events e.g. `0x1::coin::DepositEvent`
+        event_type: Path<MoveStructTag>,
+        /// Ledger version to start retrieving events from.
+        ///
+        /// If unspecified, defaults to the oldest ledger version. Can't be given along
+        /// with `start`.
+        start_version: Query<Option<U64>>,
+        /// Cursor specifying where to start for pagination
+        ///
+        /// Use the cursor returned in the X-Aptos-Cursor header of the previous page.
+        start: Query<Option<EventCursor>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        event_type
+            .0
+            .verify(0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let event_type: StructTag = event_type
+            .0
+            .try_into()
+            .context("Failed to parse given event type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_type")?;
+        self.context
+            .check_api_output_enabled("Get events by type", &accept_type)?;
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let ledger_version = latest_ledger_info.version();
+        let start = match (start.0, start_version.0) {
+            (Some(_), Some(_)) => {
+                return Err(BasicErrorWith404::bad_request_with_code(
+                    "Only one of start and start_version can be given",
+                    AptosErrorCode::InvalidInput,
+                    &latest_ledger_info,
+                ));
+            }
+            (Some(start), None) => start,
+            (None, start_version) => EventCursor {
+                version: start_version.map_or(0, |v| v.0),
+                index: 0,
+            },
+        };
+        // Events before the oldest ledger version have been pruned
+        let oldest_ledger_version = latest_ledger_info.oldest_ledger_version.0;
+        let start = if start.version < oldest_ledger_version {
+            EventCursor {
+                version: oldest_ledger_version,
+                index: 0,
+            }
+        } else {
+            start
+        };
+        if start.version > ledger_version {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                &format!(
+                    "Given start version ({}) is higher than the current ledger version ({})",
+                    start.version, ledger_version
+                ),
+                AptosErrorCode::InvalidInput,
+                &latest_ledger_info,
+            ));
+        }
+
+        let limit = Page::new(None, limit.0, self.context.max_events_page_size())
+            .limit(&latest_ledger_info)?;
+        // Get one more event than the page holds, to return its position as the cursor
+        let mut events = self
+            .context
+            .get_events_by_type(
+                &TypeTag::Struct(Box::new(event_type.clone())),
+                start.version,
+                start.index,
+                limit as u64 + 1,
+                ledger_version,
+            )
+            .context(format!("Failed to find events by type {}", event_type))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+        let cursor = if events.len() > limit as usize {
+            events.pop().map(|(index, event)| EventCursor {
+                version: event.transaction_version,
+                index,
+            })
+        } else {
+            None
+        };
+        let events = events.into_iter().map(|(_, event)| event).collect();
+        self.render(latest_ledger_info, accept_type, events)
+            .map(|v| v.with_cursor(cursor))
+    }
 }
 
 impl EventsApi {
     /// List events from an [`EventKey`]
-    fn list(
+    pub(crate) fn list(
         &self,
         latest_ledger_info: LedgerInfo,
         accept_type: AcceptType,
@@ -163,7 +298,16 @@ impl EventsApi {
                     &latest_ledger_info,
                 )
             })?;
+        self.render(latest_ledger_info, accept_type, events)
+    }
 
+    /// Render events from storage into a response
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let resolver = self.context.move_resolver_poem(&latest_ledger_info)?;
@@ -0,0 +1,214 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{AptosError, AptosErrorCode};
+use poem::{
+    http::{header, Method, StatusCode, Uri},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Projection applies to GET requests to these paths
+const PROJECTED_PATH_PREFIXES: &[&str] = &["/v1/transactions", "/v1/accounts/"];
+
+/// The query parameter holding the comma separated fields to return
+const FIELDS_PARAM: &str = "fields";
+
+/// The maximum number of fields a client can ask for
+const MAX_FIELDS: usize = 64;
+
+/// This middleware lets clients of the transaction and account endpoints ask for only the
+/// fields they need with a `fields` query parameter, e.g. `?fields=hash,success,gas_used`.
+/// Nested fields are selected with dots, e.g. `payload.function`, and fields of objects in
+/// arrays (including the top level array of a page) are selected per object. Only JSON
+/// responses are projected, BCS responses are returned as is.
+pub struct FieldProjection;
+
+impl<E: Endpoint> Middleware<E> for FieldProjection {
+    type Output = FieldProjectionEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        FieldProjectionEndpoint { inner: ep }
+    }
+}
+
+/// Endpoint for FieldProjection middleware.
+pub struct FieldProjectionEndpoint<E> {
+    inner: E,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for FieldProjectionEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let fields = match requested_fields(req.method(), req.uri()) {
+            None => return self.inner.call(req).await.map(IntoResponse::into_response),
+            Some(fields) => fields,
+        };
+        let projection = match Projection::parse(&fields) {
+            Ok(projection) => projection,
+            Err(err) => return Ok(bad_request(err)),
+        };
+
+        let mut response = self.inner.call(req).await?.into_response();
+        let is_json = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| value.starts_with("application/json"));
+        if response.status() != StatusCode::OK || !is_json {
+            return Ok(response);
+        }
+
+        let body = response.take_body().into_bytes().await?;
+        let value: Value = serde_json::from_slice(&body)
+            .map_err(|e| poem::Error::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        let body = serde_json::to_vec(&projection.apply(&value))
+            .map_err(|e| poem::Error::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        response.set_body(body);
+        Ok(response)
+    }
+}
+
+/// Returns the value of the `fields` query parameter, if projection applies to the request
+fn requested_fields(method: &Method, uri: &Uri) -> Option<String> {
+    let path = uri.path();
+    if method != Method::GET
+        || !PROJECTED_PATH_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        return None;
+    }
+    url::form_urlencoded::parse(uri.query()?.as_bytes())
+        .find(|(name, _)| name == FIELDS_PARAM)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn bad_request(message: String) -> Response {
+    let error = AptosError::new_with_error_code(message, AptosErrorCode::InvalidInput);
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .content_type("application/json; charset=utf-8")
+        .body(serde_json::to_vec(&error).expect("AptosError should serialize"))
+}
+
+/// The fields to keep in a JSON value. A projection without children keeps the whole value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Projection {
+    children: BTreeMap<String, Projection>,
+}
+
+impl Projection {
+    /// Parses a comma separated list of (possibly dotted) field names
+    pub(crate) fn parse(fields: &str) -> std::result::Result<Self, String> {
+        let fields: Vec<&str> = fields.split(',').map(str::trim).collect();
+        if fields.len() > MAX_FIELDS {
+            return Err(format!(
+                "At most {} fields can be requested, got {}",
+                MAX_FIELDS,
+                fields.len()
+            ));
+        }
+
+        let mut projection = Projection::default();
+        for field in fields {
+            if field.split('.').any(str::is_empty) {
+                return Err(format!("Invalid field name {:?} in fields", field));
+            }
+            projection.insert(field.split('.'));
+        }
+        Ok(projection)
+    }
+
+    fn insert<'a>(&mut self, mut path: impl Iterator<Item = &'a str>) {
+        let name = match path.next() {
+            Some(name) => name,
+            None => return,
+        };
+        let is_new = !self.children.contains_key(name);
+        let child = self.children.entry(name.to_string()).or_default();
+        let mut rest = path.peekable();
+        if rest.peek().is_none() {
+            // The whole field was requested, which supersedes any nested fields
+            child.children.clear();
+        } else if is_new || !child.children.is_empty() {
+            child.insert(rest);
+        }
+    }
+
+    /// Returns a copy of `value` with only the fields of this projection
+    pub(crate) fn apply(&self, value: &Value) -> Value {
+        if self.children.is_empty() {
+            return value.clone();
+        }
+        match value {
+            Value::Array(values) => Value::Array(values.iter().map(|v| self.apply(v)).collect()),
+            Value::Object(object) => Value::Object(
+                object
+                    .iter()
+                    .filter_map(|(name, value)| {
+                        self.children
+                            .get(name)
+                            .map(|child| (name.clone(), child.apply(value)))
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+            _ => value.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Projection;
+    use serde_json::json;
+
+    #[test]
+    fn test_projection() {
+        let transactions = json!([
+            {
+                "type": "user_transaction",
+                "hash": "0x1",
+                "success": true,
+                "gas_used": "10",
+                "payload": {"function": "0x1::coin::transfer", "arguments": ["0x2", "5"]},
+                "events": [{"type": "0x1::coin::WithdrawEvent", "data": {"amount": "5"}}],
+            },
+            {"type": "block_metadata_transaction", "hash": "0x2", "success": true},
+        ]);
+
+        let projection = Projection::parse("hash,success,payload.function,events.type").unwrap();
+        assert_eq!(
+            projection.apply(&transactions),
+            json!([
+                {
+                    "hash": "0x1",
+                    "success": true,
+                    "payload": {"function": "0x1::coin::transfer"},
+                    "events": [{"type": "0x1::coin::WithdrawEvent"}],
+                },
+                {"hash": "0x2", "success": true},
+            ])
+        );
+
+        // A whole field supersedes its nested fields, in either order
+        let expected =
+            json!({"payload": {"function": "0x1::coin::transfer", "arguments": ["0x2", "5"]}});
+        for fields in ["payload.function,payload", "payload,payload.function"] {
+            let projection = Projection::parse(fields).unwrap();
+            assert_eq!(projection.apply(&transactions[0]), expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        assert!(Projection::parse("").is_err());
+        assert!(Projection::parse("hash,,success").is_err());
+        assert!(Projection::parse("payload.").is_err());
+        let too_many: Vec<String> = (0..65).map(|i| format!("field_{}", i)).collect();
+        assert!(Projection::parse(&too_many.join(",")).is_err());
+    }
+}
@@ -6,18 +6,23 @@ use poem_openapi::Tags;
 mod accept_type;
 mod accounts;
 mod basic;
+mod batch;
 mod bcs_payload;
 mod blocks;
 mod check_size;
 pub mod context;
+mod dev_gas_schedule;
 mod error_converter;
 mod events;
 mod failpoint;
+mod field_projection;
 mod index;
 mod log;
 pub mod metrics;
 mod page;
+mod request_limits;
 mod response;
+mod response_integrity;
 mod runtime;
 mod set_failpoints;
 mod state;
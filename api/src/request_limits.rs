@@ -0,0 +1,191 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{AptosError, AptosErrorCode};
+use aptos_config::config::ApiConfig;
+use aptos_infallible::Mutex;
+use poem::{
+    http::{header, StatusCode},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use std::{
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+/// This middleware enforces the limits of the API config that can be reloaded while the node is
+/// running: the origins allowed to make cross origin requests, and the maximum number of
+/// requests per second. Both are read from the shared config on every request, so a reload
+/// applies to the next request. It must wrap the CORS middleware, so that requests from origins
+/// that aren't allowed are rejected before any CORS headers are added.
+pub struct RequestLimits {
+    api_config: Arc<RwLock<ApiConfig>>,
+}
+
+impl RequestLimits {
+    pub fn new(api_config: Arc<RwLock<ApiConfig>>) -> Self {
+        Self { api_config }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestLimits {
+    type Output = RequestLimitsEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestLimitsEndpoint {
+            inner: ep,
+            api_config: self.api_config.clone(),
+            rate_limiter: RateLimiter::default(),
+        }
+    }
+}
+
+/// Endpoint for RequestLimits middleware.
+pub struct RequestLimitsEndpoint<E> {
+    inner: E,
+    api_config: Arc<RwLock<ApiConfig>>,
+    rate_limiter: RateLimiter,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for RequestLimitsEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        let (origin_allowed, max_requests_per_second) = {
+            let api_config = self.api_config.read().unwrap();
+            let origin = req
+                .headers()
+                .get(header::ORIGIN)
+                .and_then(|origin| origin.to_str().ok());
+            (
+                is_origin_allowed(&api_config.cors_allowed_origins, origin),
+                api_config.max_requests_per_second,
+            )
+        };
+
+        if !origin_allowed {
+            return Ok(error_response(
+                StatusCode::FORBIDDEN,
+                "Cross origin requests from this origin are not allowed".to_string(),
+            ));
+        }
+        if let Some(max_requests_per_second) = max_requests_per_second {
+            if !self
+                .rate_limiter
+                .try_acquire(max_requests_per_second, Instant::now())
+            {
+                return Ok(error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    format!(
+                        "The API is limited to {} requests per second, try again later",
+                        max_requests_per_second
+                    ),
+                ));
+            }
+        }
+
+        self.inner.call(req).await.map(IntoResponse::into_response)
+    }
+}
+
+/// Requests without an origin aren't cross origin requests, so they're always allowed. An empty
+/// list allows every origin.
+fn is_origin_allowed(allowed_origins: &[String], origin: Option<&str>) -> bool {
+    match origin {
+        Some(origin) if !allowed_origins.is_empty() => allowed_origins
+            .iter()
+            .any(|allowed_origin| allowed_origin.trim_end_matches('/') == origin),
+        _ => true,
+    }
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    let error = AptosError::new_with_error_code(message, AptosErrorCode::WebFrameworkError);
+    Response::builder()
+        .status(status)
+        .content_type("application/json; charset=utf-8")
+        .body(serde_json::to_vec(&error).expect("AptosError should serialize"))
+}
+
+/// A token bucket holding up to a second worth of requests, shared by all the clients of the API
+#[derive(Default)]
+struct RateLimiter {
+    /// The available tokens, and when they were last refilled
+    state: Mutex<Option<(f64, Instant)>>,
+}
+
+impl RateLimiter {
+    /// Takes a token if one is available, refilling the bucket at `rate` tokens per second
+    fn try_acquire(&self, rate: u32, now: Instant) -> bool {
+        let capacity = rate as f64;
+        let mut state = self.state.lock();
+        let tokens = match *state {
+            Some((tokens, last_refill)) => {
+                let elapsed = now.saturating_duration_since(last_refill).as_secs_f64();
+                (tokens + elapsed * capacity).min(capacity)
+            }
+            None => capacity,
+        };
+        if tokens >= 1.0 {
+            *state = Some((tokens - 1.0, now));
+            true
+        } else {
+            *state = Some((tokens, now));
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_is_origin_allowed() {
+        assert!(is_origin_allowed(&[], None));
+        assert!(is_origin_allowed(&[], Some("https://example.com")));
+
+        let allowed_origins = vec![
+            "https://example.com".to_string(),
+            "http://localhost:3000/".to_string(),
+        ];
+        assert!(is_origin_allowed(&allowed_origins, None));
+        assert!(is_origin_allowed(
+            &allowed_origins,
+            Some("https://example.com")
+        ));
+        assert!(is_origin_allowed(
+            &allowed_origins,
+            Some("http://localhost:3000")
+        ));
+        assert!(!is_origin_allowed(
+            &allowed_origins,
+            Some("https://example.org")
+        ));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let rate_limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(rate_limiter.try_acquire(2, start));
+        assert!(rate_limiter.try_acquire(2, start));
+        assert!(!rate_limiter.try_acquire(2, start));
+
+        // Half a second refills one token at 2 requests per second
+        let later = start + Duration::from_millis(500);
+        assert!(rate_limiter.try_acquire(2, later));
+        assert!(!rate_limiter.try_acquire(2, later));
+
+        // The bucket never holds more than a second worth of requests
+        let much_later = later + Duration::from_secs(10);
+        assert!(rate_limiter.try_acquire(2, much_later));
+        assert!(rate_limiter.try_acquire(2, much_later));
+        assert!(!rate_limiter.try_acquire(2, much_later));
+
+        // A reloaded rate applies to the next request
+        assert!(rate_limiter.try_acquire(100, much_later + Duration::from_millis(100)));
+    }
+}
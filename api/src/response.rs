@@ -302,6 +302,18 @@ macro_rules! generate_success_response {
                 /// pagination. Pass this to the `start` field of the endpoint
                 /// on the next call to get the next page of results.
                 #[oai(header = "X-Aptos-Cursor")] Option<String>,
+                /// SHA3-256 digest of the body, set on responses that can never
+                /// change, e.g. `sha3-256=<hex>`
+                #[oai(header = "X-Aptos-Content-Digest")] Option<String>,
+                /// Signature by the node of the content digest, set on responses
+                /// that can never change if the node signs responses
+                #[oai(header = "X-Aptos-Response-Signature")] Option<String>,
+                /// Network identity public key of the node that signed the
+                /// response
+                #[oai(header = "X-Aptos-Response-Signer")] Option<String>,
+                /// Request headers the response depends on, set on responses
+                /// that can be cached
+                #[oai(header = "Vary")] Option<String>,
             ),
             )*
         }
@@ -342,6 +354,10 @@ macro_rules! generate_success_response {
                             ledger_info.block_height.into(),
                             ledger_info.oldest_block_height.into(),
                             None,
+                            None,
+                            None,
+                            None,
+                            None,
                         )
                     },
                     )*
@@ -462,11 +478,11 @@ macro_rules! generate_success_response {
                )))
             }
 
-            pub fn with_cursor(mut self, new_cursor: Option<aptos_types::state_store::state_key::StateKey>) -> Self {
+            pub fn with_cursor<C: std::fmt::Display>(mut self, new_cursor: Option<C>) -> Self {
                 match self {
                     $(
-                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut cursor) => {
-                        *cursor = new_cursor.map(|c| aptos_api_types::StateKeyWrapper::from(c).to_string());
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, ref mut cursor, ..) => {
+                        *cursor = new_cursor.map(|c| c.to_string());
                     }
                     )*
                 }
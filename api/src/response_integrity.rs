@@ -0,0 +1,161 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{
+    ResponseDigest, X_APTOS_CONTENT_DIGEST, X_APTOS_RESPONSE_SIGNATURE, X_APTOS_RESPONSE_SIGNER,
+};
+use aptos_crypto::{x25519, ValidCryptoMaterialStringExt};
+use hyper::Method;
+use poem::{
+    http::{header, HeaderValue, StatusCode, Uri},
+    Endpoint, IntoResponse, Middleware, Request, Response, Result,
+};
+use std::sync::Arc;
+
+/// Responses to these paths never change once they have been returned successfully.
+const IMMUTABLE_PATH_PREFIXES: &[&str] = &[
+    "/v1/transactions/by_version/",
+    "/v1/blocks/by_height/",
+    "/v1/blocks/by_version/",
+];
+
+/// Responses to these paths are only immutable when pinned to a ledger version.
+const VERSIONED_PATH_PREFIXES: &[&str] = &["/v1/accounts/", "/v1/tables/"];
+
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// This middleware attaches a digest of the body to successful responses that can
+/// never change, and marks them as cacheable. If response signing is enabled, the
+/// digest is also signed with the network identity key of the node, so clients can
+/// verify responses served by a CDN.
+/// It only applies to GET requests.
+pub struct ResponseIntegrity {
+    chain_id: u8,
+    identity_key: Option<Arc<x25519::PrivateKey>>,
+}
+
+impl ResponseIntegrity {
+    pub fn new(chain_id: u8, identity_key: Option<x25519::PrivateKey>) -> Self {
+        Self {
+            chain_id,
+            identity_key: identity_key.map(Arc::new),
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for ResponseIntegrity {
+    type Output = ResponseIntegrityEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        ResponseIntegrityEndpoint {
+            inner: ep,
+            chain_id: self.chain_id,
+            identity_key: self.identity_key.clone(),
+        }
+    }
+}
+
+/// Endpoint for ResponseIntegrity middleware.
+pub struct ResponseIntegrityEndpoint<E> {
+    inner: E,
+    chain_id: u8,
+    identity_key: Option<Arc<x25519::PrivateKey>>,
+}
+
+#[async_trait::async_trait]
+impl<E: Endpoint> Endpoint for ResponseIntegrityEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, req: Request) -> Result<Self::Output> {
+        if req.method() != Method::GET || !is_immutable(req.uri()) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let request = req
+            .uri()
+            .path_and_query()
+            .map(|p| p.to_string())
+            .unwrap_or_default();
+        let mut response = self.inner.call(req).await?.into_response();
+        if response.status() != StatusCode::OK {
+            return Ok(response);
+        }
+
+        let body = response.take_body().into_bytes().await?;
+        let digest = ResponseDigest::new(self.chain_id, request, &body);
+        response.set_body(body);
+
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL),
+        );
+        // The same path is served as either JSON or BCS
+        headers.insert(header::VARY, HeaderValue::from_static("Accept"));
+        headers.insert(
+            X_APTOS_CONTENT_DIGEST,
+            header_value(format!("sha3-256={}", digest.digest.to_hex())),
+        );
+        if let Some(identity_key) = &self.identity_key {
+            let (signature, identity) = digest
+                .sign(identity_key)
+                .map_err(|e| poem::Error::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            headers.insert(
+                X_APTOS_RESPONSE_SIGNATURE,
+                header_value(signature.to_encoded_string().unwrap()),
+            );
+            headers.insert(
+                X_APTOS_RESPONSE_SIGNER,
+                header_value(identity.to_encoded_string().unwrap()),
+            );
+        }
+        Ok(response)
+    }
+}
+
+fn header_value(value: String) -> HeaderValue {
+    // Hex encoded values are always valid header values
+    HeaderValue::from_str(&value).expect("Hex strings are valid header values")
+}
+
+/// Whether the response to this request can never change once it succeeds
+fn is_immutable(uri: &Uri) -> bool {
+    let path = uri.path();
+    if IMMUTABLE_PATH_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return true;
+    }
+    VERSIONED_PATH_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+        && uri
+            .query()
+            .map(|query| {
+                query
+                    .split('&')
+                    .any(|param| param.starts_with("ledger_version="))
+            })
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_immutable;
+
+    #[test]
+    fn test_is_immutable() {
+        let immutable = |uri: &str| is_immutable(&uri.parse().unwrap());
+        assert!(immutable("/v1/transactions/by_version/10"));
+        assert!(immutable("/v1/blocks/by_height/5?with_transactions=true"));
+        assert!(immutable(
+            "/v1/accounts/0x1/resource/0x1::account::Account?ledger_version=10"
+        ));
+        assert!(!immutable("/v1/transactions/by_hash/0x1"));
+        assert!(!immutable(
+            "/v1/accounts/0x1/resource/0x1::account::Account"
+        ));
+        assert!(!immutable("/v1/transactions?start=10"));
+    }
+}
@@ -1,16 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
 
 use crate::{
-    accounts::AccountsApi, basic::BasicApi, blocks::BlocksApi, check_size::PostSizeLimit,
-    context::Context, error_converter::convert_error, events::EventsApi, index::IndexApi,
-    log::middleware_log, set_failpoints, state::StateApi, transactions::TransactionsApi,
-    view_function::ViewFunctionApi,
+    accounts::AccountsApi, basic::BasicApi, batch::BatchApi, blocks::BlocksApi,
+    check_size::PostSizeLimit, context::Context, dev_gas_schedule, error_converter::convert_error,
+    events::EventsApi, field_projection::FieldProjection, index::IndexApi, log::middleware_log,
+    request_limits::RequestLimits, response_integrity::ResponseIntegrity, set_failpoints,
+    state::StateApi, transactions::TransactionsApi, view_function::ViewFunctionApi,
 };
 use anyhow::Context as AnyhowContext;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{ApiConfig, NodeConfig};
 use aptos_logger::info;
 use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
@@ -22,35 +26,28 @@ use poem::{
     EndpointExt, Route, Server,
 };
 use poem_openapi::{ContactObject, LicenseObject, OpenApiService};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::runtime::{Handle, Runtime};
 
 const VERSION: &str = include_str!("../doc/.version");
 
-/// Create a runtime and attach the Poem webserver to it.
+/// Create a runtime and attach the Poem webserver to it. Also returns the handle for changing
+/// the API config while the node is running, see `Context::api_config_handle`.
 pub fn bootstrap(
     config: &NodeConfig,
     chain_id: ChainId,
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
-) -> anyhow::Result<Runtime> {
-    let runtime = Builder::new_multi_thread()
-        .thread_name_fn(|| {
-            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-            format!("api-{}", id)
-        })
-        .disable_lifo_slot()
-        .enable_all()
-        .build()
+) -> anyhow::Result<(Runtime, Arc<RwLock<ApiConfig>>)> {
+    let runtime = aptos_runtimes::spawn_named_runtime("api", &config.thread_pools.api)
         .context("[api] failed to create runtime")?;
 
     let context = Context::new(chain_id, db, mp_sender, config.clone());
+    let api_config = context.api_config_handle();
 
     attach_poem_to_runtime(runtime.handle(), context, config, false)
         .context("Failed to attach poem to runtime")?;
 
-    Ok(runtime)
+    Ok((runtime, api_config))
 }
 
 // TODOs regarding spec generation:
@@ -66,6 +63,7 @@ pub fn get_api_service(
     (
         AccountsApi,
         BasicApi,
+        BatchApi,
         BlocksApi,
         EventsApi,
         IndexApi,
@@ -83,6 +81,9 @@ pub fn get_api_service(
         BasicApi {
             context: context.clone(),
         },
+        BatchApi {
+            context: context.clone(),
+        },
         BlocksApi {
             context: context.clone(),
         },
@@ -126,6 +127,22 @@ pub fn attach_poem_to_runtime(
     let context = Arc::new(context);
 
     let size_limit = context.content_length_limit();
+    let field_projection_enabled = config.api.field_projection_enabled;
+    let content_digest_enabled = config.api.content_digest_enabled;
+    anyhow::ensure!(
+        context.can_sign_submission_receipts(),
+        "Submission receipts are enabled, but the node has no network identity to sign them with"
+    );
+    let response_signing_key = if config.api.response_signing_enabled {
+        Some(config.identity_key().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Response signing is enabled, but the node has no network identity to sign with"
+            )
+        })?)
+    } else {
+        None
+    };
+    let response_integrity = ResponseIntegrity::new(context.chain_id().id(), response_signing_key);
 
     let api_service = get_api_service(context.clone());
 
@@ -181,22 +198,32 @@ pub fn attach_poem_to_runtime(
             .allow_headers(vec![header::CONTENT_TYPE, header::ACCEPT]);
 
         // Build routes for the API
+        let mut v1_route = Route::new()
+            .nest("/", api_service)
+            .at("/spec.json", spec_json)
+            .at("/spec.yaml", spec_yaml)
+            // TODO: We add this manually outside of the OpenAPI spec for now.
+            // https://github.com/poem-web/poem/issues/364
+            .at(
+                "/set_failpoint",
+                poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+            );
+        // The dev endpoints are only served on local networks, see
+        // `ApiConfig::dev_endpoints_enabled`
+        if context.dev_endpoints_enabled() {
+            v1_route = v1_route.at(
+                "/dev/gas_schedule",
+                poem::post(dev_gas_schedule::set_gas_schedule_overrides_poem).data(context.clone()),
+            );
+        }
         let route = Route::new()
-            .nest(
-                "/v1",
-                Route::new()
-                    .nest("/", api_service)
-                    .at("/spec.json", spec_json)
-                    .at("/spec.yaml", spec_yaml)
-                    // TODO: We add this manually outside of the OpenAPI spec for now.
-                    // https://github.com/poem-web/poem/issues/364
-                    .at(
-                        "/set_failpoint",
-                        poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
-                    ),
-            )
+            .nest("/v1", v1_route)
             .with(cors)
+            // NOTE: Make sure to keep this after the CORS middleware, see `RequestLimits`.
+            .with(RequestLimits::new(context.api_config_handle()))
             .with(PostSizeLimit::new(size_limit))
+            .with_if(field_projection_enabled, FieldProjection)
+            .with_if(content_digest_enabled, response_integrity)
             // NOTE: Make sure to keep this after all the `with` middleware.
             .catch_all_error(convert_error)
             .around(middleware_log);
@@ -66,6 +66,13 @@ impl StateApi {
         ///
         /// If not provided, it will be the latest version
         ledger_version: Query<Option<U64>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<MoveResource> {
         resource_type
             .0
@@ -109,6 +116,13 @@ impl StateApi {
         ///
         /// If not provided, it will be the latest version
         ledger_version: Query<Option<U64>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<MoveModuleBytecode> {
         verify_module_identifier(module_name.0.as_str())
             .context("'module_name' invalid")
@@ -233,7 +247,7 @@ impl StateApi {
     ///
     /// JSON: Convert to MoveResource
     /// BCS: Leave it encoded as the resource
-    fn resource(
+    pub(crate) fn resource(
         &self,
         accept_type: &AcceptType,
         address: Address,
@@ -0,0 +1,107 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::new_test_context;
+use aptos_api_test_context::current_function_name;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_read() {
+    let mut context = new_test_context(current_function_name!());
+    let account = &mut context.gen_account();
+    let txn = context.create_user_account(account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context
+        .post(
+            "/batch",
+            json!([
+                {
+                    "type": "account_resource_request",
+                    "address": account.address().to_hex_literal(),
+                    "resource_type": "0x1::account::Account",
+                },
+                {
+                    "type": "account_resource_request",
+                    "address": account.address().to_hex_literal(),
+                    "resource_type": "0x1::missing::Missing",
+                },
+                {
+                    "type": "events_by_creation_number_request",
+                    "address": "0xa550c18",
+                    "creation_number": "0",
+                    "limit": 1,
+                },
+                {
+                    "type": "transaction_by_hash_request",
+                    "hash": txn.committed_hash().to_hex_literal(),
+                },
+                {
+                    "type": "transaction_by_version_request",
+                    "version": "0",
+                },
+            ]),
+        )
+        .await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0]["status"], 200);
+    assert_eq!(results[0]["data"]["type"], "0x1::account::Account");
+    assert_eq!(results[1]["status"], 404);
+    assert_eq!(results[1]["error"]["error_code"], "resource_not_found");
+    assert_eq!(results[2]["status"], 200);
+    assert_eq!(results[2]["data"].as_array().unwrap().len(), 1);
+    assert_eq!(results[3]["status"], 200);
+    assert_eq!(results[3]["data"]["type"], "user_transaction");
+    assert_eq!(results[4]["status"], 200);
+    assert_eq!(results[4]["data"]["type"], "genesis_transaction");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_read_invalid_item() {
+    let context = new_test_context(current_function_name!());
+    // Nested deeper than the recursion limit of struct tags
+    let resource_type = format!(
+        "0x1::account::Account<{}u8{}>",
+        "vector<".repeat(20),
+        ">".repeat(20)
+    );
+    let resp = context
+        .post(
+            "/batch",
+            json!([
+                {
+                    "type": "account_resource_request",
+                    "address": "0x1",
+                    "resource_type": resource_type,
+                },
+                {
+                    "type": "transaction_by_version_request",
+                    "version": "1000000",
+                },
+            ]),
+        )
+        .await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results[0]["status"], 400);
+    assert_eq!(results[1]["status"], 404);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_batch_read_too_many_requests() {
+    let context = new_test_context(current_function_name!());
+    let max_batch_read_size = context.context.max_batch_read_size();
+    let requests = vec![
+        json!({
+            "type": "transaction_by_version_request",
+            "version": "0",
+        });
+        max_batch_read_size + 1
+    ];
+    context
+        .expect_status_code(400)
+        .post("/batch", json!(requests))
+        .await;
+}
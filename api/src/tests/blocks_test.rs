@@ -23,6 +23,37 @@ async fn test_get_unknown_block_by_height() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_block_by_height_with_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    // The block read by height matches the one read by version, which reads its transactions
+    // one by one
+    let by_height = context
+        .get(&format!("{}?with_transactions=true", blocks_by_height(1)))
+        .await;
+    let first_version: u64 = by_height["first_version"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let last_version: u64 = by_height["last_version"].as_str().unwrap().parse().unwrap();
+    let by_version = context
+        .get(&format!(
+            "/blocks/by_version/{}?with_transactions=true",
+            first_version
+        ))
+        .await;
+    assert_eq!(by_height, by_version);
+    assert_eq!(
+        by_height["transactions"].as_array().unwrap().len() as u64,
+        last_version - first_version + 1
+    );
+}
+
 fn blocks_by_height(height: u64) -> String {
     format!("/blocks/by_height/{}", height)
 }
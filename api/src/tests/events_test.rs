@@ -3,7 +3,10 @@
 
 use super::new_test_context;
 use aptos_api_test_context::current_function_name;
+use aptos_api_types::EventCursor;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use std::str::FromStr;
 
 static ACCOUNT_ADDRESS: &str = "0xa550c18";
 static CREATION_NUMBER: &str = "0";
@@ -154,3 +157,86 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type() {
+    let context = new_test_context(current_function_name!());
+    let event_type = "0x1::reconfiguration::NewEpochEvent";
+
+    let resp = context
+        .get(format!("/events/by_type/{}", event_type).as_str())
+        .await;
+    let events = resp.as_array().unwrap();
+    assert!(!events.is_empty());
+    for event in events {
+        assert_eq!(event["type"], event_type);
+    }
+
+    // genesis emits the first new epoch event
+    let resp = context
+        .get(format!("/events/by_type/{}?start_version=1", event_type).as_str())
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), events.len() - 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_with_pagination() {
+    let mut context = new_test_context(current_function_name!());
+    let event_type = "0x1::coin::DepositEvent";
+    let mut txns = vec![];
+    for _ in 0..2 {
+        let account = context.gen_account();
+        txns.push(context.create_user_account(&account));
+        txns.push(context.mint_user_account(&account));
+    }
+    context.commit_block(&txns).await;
+
+    // With no limit, all the events fit in one page, so there's no cursor
+    let path = format!("/v1/events/by_type/{}", event_type);
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(!resp.headers().contains_key("X-Aptos-Cursor"));
+    let all_events: Vec<Value> = serde_json::from_slice(resp.body()).unwrap();
+    assert!(all_events.len() >= 2);
+
+    // Pages of one event each, following the cursors, return the same events
+    let mut events = vec![];
+    let mut cursor: Option<EventCursor> = None;
+    loop {
+        let page_path = match cursor {
+            Some(cursor) => format!("{}?limit=1&start={}", path, cursor),
+            None => format!("{}?limit=1", path),
+        };
+        let resp = context
+            .reply(warp::test::request().method("GET").path(&page_path))
+            .await;
+        assert_eq!(resp.status(), 200);
+        let page: Vec<Value> = serde_json::from_slice(resp.body()).unwrap();
+        assert_eq!(page.len(), 1);
+        events.extend(page);
+        match resp.headers().get("X-Aptos-Cursor") {
+            Some(header) => {
+                cursor = Some(EventCursor::from_str(header.to_str().unwrap()).unwrap());
+            }
+            None => break,
+        }
+    }
+    assert_eq!(events, all_events);
+
+    // A cursor and a start version can't be given together
+    context
+        .expect_status_code(400)
+        .get(format!("/events/by_type/{}?start=1_0&start_version=1", event_type).as_str())
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_invalid_type() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::reconfiguration")
+        .await;
+}
@@ -42,6 +42,30 @@ async fn test_health_check() {
     assert_eq!(resp.status(), 200)
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_health_check_with_checks() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get("/-/healthy?duration_secs=4294967295&check_mempool=true&min_peers=0")
+        .await;
+    let checks = resp["checks"].as_array().unwrap();
+    assert_eq!(checks.len(), 3);
+    assert_eq!(checks[0]["name"], "ledger_staleness");
+    assert_eq!(checks[1]["name"], "mempool_accepting");
+    assert_eq!(checks[2]["name"], "min_peers");
+    assert!(checks.iter().all(|check| check["passed"] == true));
+
+    // The test node has no peers
+    let resp = context
+        .expect_status_code(503)
+        .get("/-/healthy?check_mempool=true&min_peers=1")
+        .await;
+    assert_eq!(resp["error_code"], "health_check_failed");
+    let message = resp["message"].as_str().unwrap();
+    assert!(message.contains("min_peers"));
+    assert!(!message.contains("mempool_accepting"));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_openapi_spec() {
     let context = new_test_context(current_function_name!());
@@ -121,3 +145,13 @@ async fn test_cors_on_non_200_responses() {
     let cors_header = resp.headers().get("access-control-allow-origin").unwrap();
     assert_eq!(cors_header, "test");
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_state_sync_status() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/-/state_sync").await;
+    // State sync isn't running in the test context, so bootstrapping hasn't started
+    assert_eq!(resp["phase"], "not_started");
+    assert_eq!(resp["synced_states"], "0");
+    assert!(resp["eta_secs"].is_null());
+}
@@ -430,6 +430,26 @@ async fn test_get_transaction_by_version() {
     assert_json(resp, txns[0].clone())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transaction_block_round_and_proposer() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let txns = context.get("/transactions?start=1&limit=2").await;
+    let block_metadata_txn = &txns[0];
+    let user_txn = &txns[1];
+    assert_eq!(block_metadata_txn["type"], "block_metadata_transaction");
+    assert_eq!(user_txn["type"], "user_transaction");
+    assert_eq!(user_txn["block_round"], block_metadata_txn["round"]);
+    assert_eq!(user_txn["block_proposer"], block_metadata_txn["proposer"]);
+
+    // Looking up the transaction on its own finds the same block
+    let resp = context.get("/transactions/by_version/2").await;
+    assert_json(resp, user_txn.clone());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_pending_transaction_by_hash() {
     let mut context = new_test_context(current_function_name!());
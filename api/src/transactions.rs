@@ -22,11 +22,11 @@ use crate::{
 use anyhow::{anyhow, Context as AnyhowContext};
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
-    TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
-    MAX_RECURSIVE_TYPES_ALLOWED, U64,
+    AsConverter, EncodeSubmissionRequest, EvictionReason, GasEstimation, GasEstimationBcs,
+    HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
+    Transaction, TransactionData, TransactionEviction, TransactionOnChainData,
+    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult, UserTransaction,
+    VerifyInput, VerifyInputWithRecursion, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
 use aptos_types::{
@@ -45,7 +45,7 @@ use poem_openapi::{
     payload::Json,
     ApiRequest, OpenApi,
 };
-use std::sync::Arc;
+use std::{sync::Arc, time::UNIX_EPOCH};
 
 generate_success_response!(SubmitTransactionResponse, (202, Accepted));
 
@@ -160,6 +160,13 @@ impl TransactionsApi {
         ///
         /// If not provided, defaults to default page size
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<Transaction>> {
         fail_point_poem("endpoint_get_transactions")?;
         self.context
@@ -180,6 +187,8 @@ impl TransactionsApi {
     /// When given a transaction hash, the server first looks for the transaction
     /// in storage (on-chain, committed). If no on-chain transaction is found, it
     /// looks the transaction up by hash in the mempool (pending, not yet committed).
+    /// If the transaction was recently evicted from the mempool, it's returned as
+    /// pending with the reason of the eviction (JSON only).
     ///
     /// To create a transaction hash by yourself, do the following:
     ///   1. Hash message bytes: "RawTransaction" bytes + BCS bytes of [Transaction](https://aptos-labs.github.io/aptos-core/aptos_types/transaction/enum.Transaction.html).
@@ -198,6 +207,13 @@ impl TransactionsApi {
         /// Hash of transaction to retrieve
         txn_hash: Path<HashValue>,
         // TODO: Use a new request type that can't return 507.
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Transaction> {
         fail_point_poem("endpoint_transaction_by_hash")?;
         self.context
@@ -221,6 +237,13 @@ impl TransactionsApi {
         accept_type: AcceptType,
         /// Version of transaction to retrieve
         txn_version: Path<U64>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Transaction> {
         fail_point_poem("endpoint_transaction_by_version")?;
         self.context
@@ -256,6 +279,13 @@ impl TransactionsApi {
         ///
         /// If not provided, defaults to default page size
         limit: Query<Option<u16>>,
+        /// Comma separated fields to return, e.g. `hash,success,gas_used`
+        ///
+        /// Fields of nested objects are selected with dots, e.g. `payload.function`.
+        /// If not provided, all the fields are returned. Only applies to JSON
+        /// responses, and only if field projection is enabled on the node.
+        #[oai(name = "fields")]
+        _fields: Query<Option<String>>,
     ) -> BasicResultWith404<Vec<Transaction>> {
         fail_point_poem("endpoint_get_accounts_transactions")?;
         self.context
@@ -309,11 +339,16 @@ impl TransactionsApi {
         fail_point_poem("endpoint_submit_transaction")?;
         self.context
             .check_api_output_enabled("Submit transaction", &accept_type)?;
-        if !self.context.node_config.api.transaction_submission_enabled {
+        if !self.context.transaction_submission_enabled() {
             return Err(api_disabled("Submit transaction"));
         }
         let ledger_info = self.context.get_latest_ledger_info()?;
+        let is_bcs = matches!(data, SubmitTransactionPost::Bcs(_));
         let signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
+        if is_bcs {
+            self.check_entry_function_arguments(&ledger_info, &signed_transaction)
+                .await?;
+        }
         self.create(&accept_type, &ledger_info, signed_transaction)
             .await
     }
@@ -362,7 +397,7 @@ impl TransactionsApi {
         fail_point_poem("endpoint_submit_batch_transactions")?;
         self.context
             .check_api_output_enabled("Submit batch transactions", &accept_type)?;
-        if !self.context.node_config.api.transaction_submission_enabled {
+        if !self.context.transaction_submission_enabled() {
             return Err(api_disabled("Submit batch transaction"));
         }
         let ledger_info = self.context.get_latest_ledger_info()?;
@@ -426,11 +461,16 @@ impl TransactionsApi {
         fail_point_poem("endpoint_simulate_transaction")?;
         self.context
             .check_api_output_enabled("Simulate transaction", &accept_type)?;
-        if !self.context.node_config.api.transaction_simulation_enabled {
+        if !self.context.transaction_simulation_enabled() {
             return Err(api_disabled("Simulate transaction"));
         }
         let ledger_info = self.context.get_latest_ledger_info()?;
+        let is_bcs = matches!(data, SubmitTransactionPost::Bcs(_));
         let mut signed_transaction = self.get_signed_transaction(&ledger_info, data)?;
+        if is_bcs {
+            self.check_entry_function_arguments(&ledger_info, &signed_transaction)
+                .await?;
+        }
 
         let estimated_gas_unit_price = match (
             estimate_gas_unit_price.0.unwrap_or_default(),
@@ -566,7 +606,7 @@ impl TransactionsApi {
         fail_point_poem("endpoint_encode_submission")?;
         self.context
             .check_api_output_enabled("Encode submission", &accept_type)?;
-        if !self.context.node_config.api.encode_submission_enabled {
+        if !self.context.encode_submission_enabled() {
             return Err(api_disabled("Encode submission"));
         }
         self.get_signing_message(&accept_type, data.0)
@@ -635,14 +675,14 @@ impl TransactionsApi {
 
         match accept_type {
             AcceptType::Json => {
-                let timestamp = self
+                let block = self
                     .context
-                    .get_block_timestamp(&latest_ledger_info, start_version)?;
+                    .get_block_context(&latest_ledger_info, start_version)?;
                 BasicResponse::try_from_json((
                     self.context.render_transactions_sequential(
                         &latest_ledger_info,
                         data,
-                        timestamp,
+                        block,
                     )?,
                     &latest_ledger_info,
                     BasicResponseStatus::Ok,
@@ -654,7 +694,7 @@ impl TransactionsApi {
         }
     }
 
-    async fn get_transaction_by_hash_inner(
+    pub(crate) async fn get_transaction_by_hash_inner(
         &self,
         accept_type: &AcceptType,
         hash: HashValue,
@@ -670,15 +710,83 @@ impl TransactionsApi {
                     AptosErrorCode::InternalError,
                     &ledger_info,
                 )
-            })?
-            .context(format!("Failed to find transaction with hash: {}", hash))
-            .map_err(|_| transaction_not_found_by_hash(hash, &ledger_info))?;
+            })?;
 
-        self.get_transaction_inner(accept_type, txn_data, &ledger_info)
-            .await
+        match txn_data {
+            Some(txn_data) => {
+                self.get_transaction_inner(accept_type, txn_data, &ledger_info)
+                    .await
+            }
+            None => {
+                self.get_evicted_transaction_inner(accept_type, hash, &ledger_info)
+                    .await
+            }
+        }
     }
 
-    async fn get_transaction_by_version_inner(
+    /// Returns a transaction recently evicted from mempool as pending, with the reason of the
+    /// eviction. BCS has no way to represent the eviction, so BCS requests get a 404.
+    async fn get_evicted_transaction_inner(
+        &self,
+        accept_type: &AcceptType,
+        hash: HashValue,
+        ledger_info: &LedgerInfo,
+    ) -> BasicResultWith404<Transaction> {
+        let evicted = match accept_type {
+            AcceptType::Json => self
+                .context
+                .get_evicted_transaction_by_hash(hash.into())
+                .await
+                .context(format!(
+                    "Failed to get evicted transaction by hash {}",
+                    hash
+                ))
+                .map_err(|err| {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        ledger_info,
+                    )
+                })?,
+            AcceptType::Bcs => None,
+        };
+        let evicted = evicted.ok_or_else(|| transaction_not_found_by_hash(hash, ledger_info))?;
+
+        let resolver = self.context.move_resolver_poem(ledger_info)?;
+        let mut pending_txn = resolver
+            .as_converter(self.context.db.clone())
+            .try_into_pending_transaction_poem(evicted.txn)
+            .context("Failed to convert evicted transaction to PendingTransaction")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    ledger_info,
+                )
+            })?;
+        let timestamp = evicted
+            .eviction_time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        pending_txn.eviction = Some(TransactionEviction {
+            reason: match evicted.reason {
+                aptos_mempool::EvictionReason::MempoolFull => EvictionReason::MempoolFull,
+                aptos_mempool::EvictionReason::Replaced => EvictionReason::Replaced,
+                aptos_mempool::EvictionReason::SystemTtlExpired => EvictionReason::SystemTtlExpired,
+                aptos_mempool::EvictionReason::Expired => EvictionReason::Expired,
+            },
+            timestamp: timestamp.into(),
+        });
+
+        BasicResponse::try_from_json((
+            Transaction::PendingTransaction(pending_txn),
+            ledger_info,
+            BasicResponseStatus::Ok,
+        ))
+    }
+
+    pub(crate) async fn get_transaction_by_version_inner(
         &self,
         accept_type: &AcceptType,
         version: U64,
@@ -716,11 +824,10 @@ impl TransactionsApi {
                 let resolver = self.context.move_resolver_poem(ledger_info)?;
                 let transaction = match transaction_data {
                     TransactionData::OnChain(txn) => {
-                        let timestamp =
-                            self.context.get_block_timestamp(ledger_info, txn.version)?;
+                        let block = self.context.get_block_context(ledger_info, txn.version)?;
                         resolver
                             .as_converter(self.context.db.clone())
-                            .try_into_onchain_transaction(timestamp, txn)
+                            .try_into_onchain_transaction(block, txn)
                             .context("Failed to convert on chain transaction to Transaction")
                             .map_err(|err| {
                                 BasicErrorWith404::internal_with_code(
@@ -921,6 +1028,40 @@ impl TransactionsApi {
         }
     }
 
+    /// Checks the arguments of an entry function call submitted as BCS, which unlike JSON isn't
+    /// parsed against the ABI of the function. Loading the function reads the DB, so the check
+    /// runs in a blocking task.
+    async fn check_entry_function_arguments(
+        &self,
+        ledger_info: &LedgerInfo,
+        signed_transaction: &SignedTransaction,
+    ) -> Result<(), SubmitTransactionError> {
+        let entry_function = match signed_transaction.payload() {
+            TransactionPayload::EntryFunction(entry_function) => entry_function.clone(),
+            _ => return Ok(()),
+        };
+        let context = self.context.clone();
+        tokio::task::spawn_blocking(move || context.check_entry_function_arguments(&entry_function))
+            .await
+            .context("Failed to check the entry function arguments")
+            .and_then(|result| result)
+            .map_err(|err| {
+                SubmitTransactionError::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    ledger_info,
+                )
+            })?
+            .context("Transaction entry function argument invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    ledger_info,
+                )
+            })
+    }
+
     /// Parses a batch of signed transactions
     fn get_signed_transactions_batch(
         &self,
@@ -975,9 +1116,10 @@ impl TransactionsApi {
         match mempool_status.code {
             MempoolStatusCode::Accepted => Ok(()),
             MempoolStatusCode::MempoolIsFull | MempoolStatusCode::TooManyTransactions => {
-                Err(AptosError::new_with_error_code(
+                Err(AptosError::new_with_mempool_status(
                     &mempool_status.message,
                     AptosErrorCode::MempoolIsFull,
+                    mempool_status.code,
                 ))
             }
             MempoolStatusCode::VmError => {
@@ -999,17 +1141,35 @@ impl TransactionsApi {
                     ))
                 }
             }
-            MempoolStatusCode::InvalidSeqNumber => Err(AptosError::new_with_error_code(
+            MempoolStatusCode::InvalidSeqNumber => Err(AptosError::new_with_mempool_status(
                 mempool_status.message,
                 AptosErrorCode::SequenceNumberTooOld,
+                mempool_status.code,
+            )),
+            MempoolStatusCode::SequenceNumberTooNew => Err(AptosError::new_with_mempool_status(
+                mempool_status.message,
+                AptosErrorCode::SequenceNumberTooNew,
+                mempool_status.code,
+            )),
+            MempoolStatusCode::TransactionDenied => Err(AptosError::new_with_mempool_status(
+                mempool_status.message,
+                AptosErrorCode::TransactionDenied,
+                mempool_status.code,
+            )),
+            MempoolStatusCode::NotAllowlisted => Err(AptosError::new_with_mempool_status(
+                mempool_status.message,
+                AptosErrorCode::TransactionNotAllowlisted,
+                mempool_status.code,
             )),
-            MempoolStatusCode::InvalidUpdate => Err(AptosError::new_with_error_code(
+            MempoolStatusCode::InvalidUpdate => Err(AptosError::new_with_mempool_status(
                 mempool_status.message,
                 AptosErrorCode::InvalidTransactionUpdate,
+                mempool_status.code,
             )),
-            MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_error_code(
+            MempoolStatusCode::UnknownStatus => Err(AptosError::new_with_mempool_status(
                 format!("Transaction was rejected with status {}", mempool_status,),
                 AptosErrorCode::InternalError,
+                mempool_status.code,
             )),
         }
     }
@@ -1037,7 +1197,7 @@ impl TransactionsApi {
                         })?;
 
                     // We provide the pending transaction so that users have the hash associated
-                    let pending_txn = resolver
+                    let mut pending_txn = resolver
                             .as_converter(self.context.db.clone())
                             .try_into_pending_transaction_poem(txn)
                             .context("Failed to build PendingTransaction from mempool response, even though it said the request was accepted")
@@ -1046,6 +1206,17 @@ impl TransactionsApi {
                                 AptosErrorCode::InternalError,
                                 ledger_info,
                             ))?;
+                    pending_txn.receipt = self
+                        .context
+                        .sign_submission_receipt(pending_txn.hash.into())
+                        .context("Failed to sign the submission receipt")
+                        .map_err(|err| {
+                            SubmitTransactionError::internal_with_code(
+                                err,
+                                AptosErrorCode::InternalError,
+                                ledger_info,
+                            )
+                        })?;
                     SubmitTransactionResponse::try_from_json((
                         pending_txn,
                         ledger_info,
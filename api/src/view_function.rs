@@ -83,7 +83,7 @@ impl ViewFunctionApi {
             entry_func.function().to_owned(),
             entry_func.ty_args().to_owned(),
             entry_func.args().to_owned(),
-            self.context.node_config.api.max_gas_view_function,
+            self.context.max_gas_view_function(),
         )
         .map_err(|err| {
             BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
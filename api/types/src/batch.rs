@@ -0,0 +1,80 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, AptosError, HashValue, MoveStructTag, U64};
+use poem_openapi::{Object, Union};
+use serde::{Deserialize, Serialize};
+
+// Warning: Do not add a docstring to a field that uses a type in `derives.rs`,
+// it will result in a change to the type representation. Read more about this
+// issue here: https://github.com/poem-web/poem/issues/385.
+
+/// A single read of a batch read request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
+pub enum BatchReadRequest {
+    AccountResourceRequest(AccountResourceRequest),
+    EventsByCreationNumberRequest(EventsByCreationNumberRequest),
+    TransactionByHashRequest(TransactionByHashRequest),
+    TransactionByVersionRequest(TransactionByVersionRequest),
+}
+
+/// Read an individual resource of an account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AccountResourceRequest {
+    pub address: Address,
+    pub resource_type: MoveStructTag,
+}
+
+/// Read the events of an event stream, identified by an account and a creation number
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct EventsByCreationNumberRequest {
+    pub address: Address,
+    pub creation_number: U64,
+    pub start: Option<U64>,
+    /// Max number of events to retrieve, defaults to the default page size
+    pub limit: Option<u16>,
+}
+
+/// Read a committed or pending transaction by its hash
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct TransactionByHashRequest {
+    pub hash: HashValue,
+}
+
+/// Read a committed transaction by its ledger version
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct TransactionByVersionRequest {
+    pub version: U64,
+}
+
+/// The result of a single read of a batch read request
+///
+/// Either `data` or `error` is set, depending on the status.
+#[derive(Debug, Serialize, Deserialize, Object)]
+pub struct BatchReadResult {
+    /// The HTTP status code the read would have been answered with on its own
+    pub status: u16,
+    /// The data the read would have been answered with on its own, as JSON
+    pub data: Option<serde_json::Value>,
+    pub error: Option<AptosError>,
+}
+
+impl BatchReadResult {
+    pub fn ok(data: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn error(status: u16, error: AptosError) -> Self {
+        Self {
+            status,
+            data: None,
+            error: Some(error),
+        }
+    }
+}
@@ -11,8 +11,8 @@ use crate::{
     Bytecode, DirectWriteSet, EntryFunctionId, EntryFunctionPayload, Event, HexEncodedBytes,
     MoveFunction, MoveModuleBytecode, MoveResource, MoveScriptBytecode, MoveType, MoveValue,
     PendingTransaction, ScriptPayload, ScriptWriteSet, SubmitTransactionRequest, Transaction,
-    TransactionInfo, TransactionOnChainData, TransactionPayload, UserTransactionRequest,
-    VersionedEvent, WriteSet, WriteSetChange, WriteSetPayload,
+    TransactionBlockContext, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    UserTransactionRequest, VersionedEvent, WriteSet, WriteSetChange, WriteSetPayload,
 };
 use anyhow::{bail, ensure, format_err, Context as AnyhowContext, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -96,7 +96,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
 
     pub fn try_into_onchain_transaction(
         &self,
-        timestamp: u64,
+        block: TransactionBlockContext,
         data: TransactionOnChainData,
     ) -> Result<Transaction> {
         use aptos_types::transaction::Transaction::*;
@@ -110,7 +110,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         Ok(match data.transaction {
             UserTransaction(txn) => {
                 let payload = self.try_into_transaction_payload(txn.payload().clone())?;
-                (&txn, info, payload, events, timestamp).into()
+                (&txn, info, payload, events, block).into()
             }
             GenesisTransaction(write_set) => {
                 let payload = self.try_into_write_set_payload(write_set)?;
@@ -120,7 +120,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
             StateCheckpoint(_) => {
                 Transaction::StateCheckpointTransaction(StateCheckpointTransaction {
                     info,
-                    timestamp: timestamp.into(),
+                    timestamp: block.timestamp_usecs.into(),
                 })
             }
         })
@@ -543,6 +543,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         func: MoveFunction,
         args: Vec<serde_json::Value>,
     ) -> Result<Vec<move_core_types::value::MoveValue>> {
+        let num_signers = func.params.iter().filter(|p| p.is_signer()).count();
         let arg_types = func
             .params
             .into_iter()
@@ -565,12 +566,16 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
             .zip(args.into_iter())
             .enumerate()
             .map(|(i, (arg_type, arg))| {
+                let provided = format!("{} {}", json_value_kind(&arg), arg);
                 self.try_into_vm_value(&arg_type.clone().try_into()?, arg)
                     .map_err(|e| {
                         format_err!(
-                            "parse arguments[{}] failed, expect {}, caused by error: {}",
+                            "Invalid argument for parameter {} (arguments[{}]): expected {} as JSON {}, but got {}, caused by error: {}",
+                            num_signers + i,
                             i,
+                            arg_type,
                             arg_type.json_type_name(),
+                            provided,
                             e,
                         )
                     })
@@ -778,9 +783,15 @@ pub trait ExplainVMStatus {
     fn explain_vm_status(&self, status: &ExecutionStatus) -> String {
         match status {
             ExecutionStatus::MoveAbort { location, code, info } => match &location {
-                AbortLocation::Module(_) => {
+                AbortLocation::Module(module_id) => {
                     info.as_ref().map(|i| {
                         format!("Move abort in {}: {}({:#x}): {}", abort_location_to_str(location), i.reason_name, code, i.description)
+                    }).or_else(|| {
+                        // Fall back to the framework error registry for aborts without abort info,
+                        // e.g., from modules published without an error map
+                        aptos_error_registry::lookup(module_id, *code).map(|e| {
+                            format!("Move abort in {}: {}({:#x}): {}", abort_location_to_str(location), e.name, code, e.description)
+                        })
                     }).unwrap_or_else(|| {
                         format!("Move abort in {}: {:#x}", abort_location_to_str(location), code)
                     })
@@ -824,4 +835,16 @@ pub trait ExplainVMStatus {
     }
 }
 
+/// Describes the kind of a JSON value, for error messages.
+fn json_value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
 // TODO: add caching?
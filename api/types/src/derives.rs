@@ -23,8 +23,8 @@ use serde_json::json;
 
 use crate::{
     move_types::{MoveAbility, MoveStructValue},
-    Address, EntryFunctionId, HashValue, HexEncodedBytes, IdentifierWrapper, MoveModuleId,
-    MoveStructTag, MoveType, StateKeyWrapper, U128, U256, U64,
+    Address, EntryFunctionId, EventCursor, HashValue, HexEncodedBytes, IdentifierWrapper,
+    MoveModuleId, MoveStructTag, MoveType, StateKeyWrapper, U128, U256, U64,
 };
 use indoc::indoc;
 
@@ -243,6 +243,19 @@ impl_poem_type!(
     )
 );
 
+impl_poem_type!(
+    EventCursor,
+    "string",
+    (
+        example = Some(serde_json::Value::String("32425224034_2".to_string())),
+        description = Some(indoc! {"
+          The position of an event, as the version of the transaction that emitted it and
+          the index of the event in the transaction, joined by an underscore. This is used
+          for cursor based pagination.
+        "})
+    )
+);
+
 impl_poem_type!(
     U64,
     "string",
@@ -294,6 +307,7 @@ impl_poem_type!(
 
 impl_poem_parameter!(
     Address,
+    EventCursor,
     HashValue,
     IdentifierWrapper,
     HexEncodedBytes,
@@ -1,10 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_types::vm_status::StatusCode;
+use crate::error_catalog::ErrorCodeInfo;
+use aptos_types::{mempool_status::MempoolStatusCode, vm_status::StatusCode};
 use poem_openapi::{Enum, Object};
 use serde::{Deserialize, Serialize};
-use std::fmt::Formatter;
+use std::{convert::TryFrom, fmt::Formatter};
 
 /// This is the generic struct we use for all API errors, it contains a string
 /// message and an Aptos API specific error code.
@@ -15,6 +16,10 @@ pub struct AptosError {
     pub error_code: AptosErrorCode,
     /// A code providing VM error details when submitting transactions to the VM
     pub vm_error_code: Option<u64>,
+    /// The catalog entry of the most specific cause of the error, with its category and
+    /// whether the request should be retried
+    #[serde(default)]
+    pub error_info: Option<ErrorCodeInfo>,
 }
 
 impl std::fmt::Display for AptosError {
@@ -34,6 +39,20 @@ impl AptosError {
             message: format!("{:#}", error),
             error_code,
             vm_error_code: None,
+            error_info: Some(error_code.into()),
+        }
+    }
+
+    pub fn new_with_mempool_status<ErrorType: std::fmt::Display>(
+        error: ErrorType,
+        error_code: AptosErrorCode,
+        mempool_status: MempoolStatusCode,
+    ) -> AptosError {
+        Self {
+            message: format!("{:#}", error),
+            error_code,
+            vm_error_code: None,
+            error_info: Some(mempool_status.into()),
         }
     }
 
@@ -46,8 +65,21 @@ impl AptosError {
             message: format!("{:#}", error),
             error_code,
             vm_error_code: Some(vm_error_code as u64),
+            error_info: Some(vm_error_code.into()),
         }
     }
+
+    /// The catalog entry of the error. Errors from older nodes don't carry it, in which case it
+    /// is derived from the error codes.
+    pub fn error_info(&self) -> ErrorCodeInfo {
+        if let Some(error_info) = &self.error_info {
+            return error_info.clone();
+        }
+        self.vm_error_code
+            .and_then(|vm_error_code| StatusCode::try_from(vm_error_code).ok())
+            .map(ErrorCodeInfo::from)
+            .unwrap_or_else(|| self.error_code.into())
+    }
 }
 
 /// These codes provide more granular error information beyond just the HTTP
@@ -93,6 +125,12 @@ pub enum AptosErrorCode {
     SequenceNumberTooOld = 402,
     /// The submitted transaction failed VM checks.
     VmError = 403,
+    /// The sequence number for the transaction is too far ahead of the latest sequence number.
+    SequenceNumberTooNew = 404,
+    /// The transaction was rejected by the on-chain transaction deny list.
+    TransactionDenied = 405,
+    /// The transaction was rejected by the address allowlist of a permissioned network.
+    TransactionNotAllowlisted = 406,
 
     /// Health check failed.
     HealthCheckFailed = 500,
@@ -113,6 +151,36 @@ impl AptosErrorCode {
     pub fn as_u32(&self) -> u32 {
         *self as u32
     }
+
+    /// All the error codes, in the order of their values
+    pub fn all() -> &'static [AptosErrorCode] {
+        use AptosErrorCode::*;
+        &[
+            AccountNotFound,
+            ResourceNotFound,
+            ModuleNotFound,
+            StructFieldNotFound,
+            VersionNotFound,
+            TransactionNotFound,
+            TableItemNotFound,
+            BlockNotFound,
+            VersionPruned,
+            BlockPruned,
+            InvalidInput,
+            InvalidTransactionUpdate,
+            SequenceNumberTooOld,
+            VmError,
+            SequenceNumberTooNew,
+            TransactionDenied,
+            TransactionNotAllowlisted,
+            HealthCheckFailed,
+            MempoolIsFull,
+            InternalError,
+            WebFrameworkError,
+            BcsNotSupported,
+            ApiDisabled,
+        ]
+    }
 }
 
 #[test]
@@ -131,3 +199,16 @@ fn test_serialize_deserialize() {
     let _: AptosError =
         serde_json::from_str(&serde_json::to_string(&without_code).unwrap()).unwrap();
 }
+
+#[test]
+fn test_error_info_from_older_nodes() {
+    let error: AptosError = serde_json::from_str(
+        r#"{"message": "Invalid transaction", "error_code": "vm_error", "vm_error_code": 3}"#,
+    )
+    .unwrap();
+    assert!(error.error_info.is_none());
+    assert_eq!(
+        error.error_info(),
+        ErrorCodeInfo::from(StatusCode::SEQUENCE_NUMBER_TOO_OLD)
+    );
+}
@@ -0,0 +1,277 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The catalog of the errors returned by the API, along with the mempool and VM statuses that
+//! cause a transaction submission to be rejected.
+//!
+//! Each error has a stable numeric code, unique across the three sources:
+//! * API errors use the value of their [`AptosErrorCode`], below 1000
+//! * mempool statuses are offset by [`MEMPOOL_STATUS_CODE_OFFSET`]
+//! * VM statuses are offset by [`VM_STATUS_CODE_OFFSET`]
+//!
+//! Clients should rely on the category and retry hint of an error rather than on its message.
+
+use crate::AptosErrorCode;
+use aptos_types::{
+    mempool_status::MempoolStatusCode,
+    vm_status::{StatusCode, StatusType},
+};
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The offset of the codes of mempool statuses
+pub const MEMPOOL_STATUS_CODE_OFFSET: u64 = 1_000;
+/// The offset of the codes of VM statuses
+pub const VM_STATUS_CODE_OFFSET: u64 = 1_000_000;
+
+/// The broad category of an error
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// The requested data doesn't exist
+    NotFound,
+    /// The requested data was pruned from the node
+    Pruned,
+    /// The request is malformed or invalid
+    InvalidInput,
+    /// The submitted transaction was rejected
+    TransactionRejected,
+    /// The node can't serve the request at the moment
+    Unavailable,
+    /// The node failed unexpectedly
+    Internal,
+}
+
+/// How a client should react to an error
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RetryHint {
+    /// The same request will fail again
+    DoNotRetry,
+    /// The same request may succeed later, e.g. on another node or after backing off
+    RetryLater,
+    /// The transaction must be built again, e.g. with a new sequence number or expiration
+    RebuildTransaction,
+}
+
+/// The catalog entry of an error
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ErrorCodeInfo {
+    /// The stable numeric code of the error
+    pub code: u64,
+    /// The name of the error, e.g. `sequence_number_too_old`
+    pub name: String,
+    pub category: ErrorCategory,
+    pub retry_hint: RetryHint,
+}
+
+impl ErrorCodeInfo {
+    fn new(code: u64, name: String, category: ErrorCategory, retry_hint: RetryHint) -> Self {
+        Self {
+            code,
+            name,
+            category,
+            retry_hint,
+        }
+    }
+
+    /// Whether the same request can succeed if sent again
+    pub fn is_retryable(&self) -> bool {
+        self.retry_hint == RetryHint::RetryLater
+    }
+}
+
+impl From<AptosErrorCode> for ErrorCodeInfo {
+    fn from(error_code: AptosErrorCode) -> Self {
+        use AptosErrorCode::*;
+
+        let (category, retry_hint) = match error_code {
+            AccountNotFound | ResourceNotFound | ModuleNotFound | StructFieldNotFound
+            | TableItemNotFound => (ErrorCategory::NotFound, RetryHint::DoNotRetry),
+            // The node may not have caught up to the version yet
+            VersionNotFound | TransactionNotFound | BlockNotFound => {
+                (ErrorCategory::NotFound, RetryHint::RetryLater)
+            }
+            VersionPruned | BlockPruned => (ErrorCategory::Pruned, RetryHint::DoNotRetry),
+            // Requests the web framework fails to parse or route
+            InvalidInput | BcsNotSupported | WebFrameworkError => {
+                (ErrorCategory::InvalidInput, RetryHint::DoNotRetry)
+            }
+            InvalidTransactionUpdate | VmError | TransactionDenied | TransactionNotAllowlisted => {
+                (ErrorCategory::TransactionRejected, RetryHint::DoNotRetry)
+            }
+            SequenceNumberTooOld | SequenceNumberTooNew => (
+                ErrorCategory::TransactionRejected,
+                RetryHint::RebuildTransaction,
+            ),
+            HealthCheckFailed | MempoolIsFull | ApiDisabled => {
+                (ErrorCategory::Unavailable, RetryHint::RetryLater)
+            }
+            InternalError => (ErrorCategory::Internal, RetryHint::RetryLater),
+        };
+        Self::new(
+            error_code.as_u32() as u64,
+            snake_case(&format!("{:?}", error_code)),
+            category,
+            retry_hint,
+        )
+    }
+}
+
+impl From<MempoolStatusCode> for ErrorCodeInfo {
+    fn from(status: MempoolStatusCode) -> Self {
+        use ErrorCategory::*;
+        use MempoolStatusCode::*;
+
+        let (category, retry_hint) = match status {
+            Accepted | VmError | InvalidUpdate | TransactionDenied | NotAllowlisted => {
+                (TransactionRejected, RetryHint::DoNotRetry)
+            }
+            InvalidSeqNumber | SequenceNumberTooNew => {
+                (TransactionRejected, RetryHint::RebuildTransaction)
+            }
+            MempoolIsFull | TooManyTransactions => (Unavailable, RetryHint::RetryLater),
+            UnknownStatus => (Internal, RetryHint::RetryLater),
+        };
+        Self::new(
+            MEMPOOL_STATUS_CODE_OFFSET + status as u64,
+            format!("mempool_{}", snake_case(&format!("{:?}", status))),
+            category,
+            retry_hint,
+        )
+    }
+}
+
+impl From<StatusCode> for ErrorCodeInfo {
+    fn from(status: StatusCode) -> Self {
+        let (category, retry_hint) = match status {
+            StatusCode::SEQUENCE_NUMBER_TOO_OLD
+            | StatusCode::SEQUENCE_NUMBER_TOO_NEW
+            | StatusCode::TRANSACTION_EXPIRED => (
+                ErrorCategory::TransactionRejected,
+                RetryHint::RebuildTransaction,
+            ),
+            _ => match status.status_type() {
+                StatusType::Validation | StatusType::Execution => {
+                    (ErrorCategory::TransactionRejected, RetryHint::DoNotRetry)
+                }
+                StatusType::Verification | StatusType::Deserialization => {
+                    (ErrorCategory::InvalidInput, RetryHint::DoNotRetry)
+                }
+                StatusType::InvariantViolation | StatusType::Unknown => {
+                    (ErrorCategory::Internal, RetryHint::DoNotRetry)
+                }
+            },
+        };
+        Self::new(
+            VM_STATUS_CODE_OFFSET.saturating_add(status as u64),
+            format!("vm_{}", format!("{:?}", status).to_lowercase()),
+            category,
+            retry_hint,
+        )
+    }
+}
+
+impl ErrorCodeInfo {
+    /// Looks up an error by its code in the catalog
+    pub fn from_code(code: u64) -> Option<Self> {
+        if code >= VM_STATUS_CODE_OFFSET {
+            let status = if code == u64::MAX {
+                StatusCode::UNKNOWN_STATUS
+            } else {
+                StatusCode::try_from(code - VM_STATUS_CODE_OFFSET).ok()?
+            };
+            Some(status.into())
+        } else if code >= MEMPOOL_STATUS_CODE_OFFSET {
+            MempoolStatusCode::try_from(code - MEMPOOL_STATUS_CODE_OFFSET)
+                .ok()
+                .map(Self::from)
+        } else {
+            AptosErrorCode::all()
+                .iter()
+                .find(|error_code| error_code.as_u32() as u64 == code)
+                .map(|error_code| (*error_code).into())
+        }
+    }
+}
+
+/// Returns the catalog of the API errors and mempool statuses. VM statuses are too many to be
+/// listed, use [`ErrorCodeInfo::from_code`] or the `From<StatusCode>` conversion for those.
+pub fn error_catalog() -> Vec<ErrorCodeInfo> {
+    use MempoolStatusCode::*;
+
+    let mempool_statuses = [
+        InvalidSeqNumber,
+        MempoolIsFull,
+        TooManyTransactions,
+        InvalidUpdate,
+        VmError,
+        UnknownStatus,
+        SequenceNumberTooNew,
+        TransactionDenied,
+        NotAllowlisted,
+    ];
+    AptosErrorCode::all()
+        .iter()
+        .map(|error_code| ErrorCodeInfo::from(*error_code))
+        .chain(mempool_statuses.iter().map(|status| (*status).into()))
+        .collect()
+}
+
+/// Converts a CamelCase name to snake_case
+fn snake_case(name: &str) -> String {
+    let mut snake_case = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake_case.push('_');
+            }
+            snake_case.extend(c.to_lowercase());
+        } else {
+            snake_case.push(c);
+        }
+    }
+    snake_case
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_codes_are_unique() {
+        let catalog = error_catalog();
+        let codes: HashSet<_> = catalog.iter().map(|info| info.code).collect();
+        assert_eq!(codes.len(), catalog.len());
+        for info in catalog {
+            assert_eq!(ErrorCodeInfo::from_code(info.code), Some(info));
+        }
+    }
+
+    #[test]
+    fn test_names_match_serialization() {
+        let info = ErrorCodeInfo::from(AptosErrorCode::SequenceNumberTooOld);
+        assert_eq!(
+            serde_json::to_value(AptosErrorCode::SequenceNumberTooOld).unwrap(),
+            serde_json::Value::String(info.name)
+        );
+        assert_eq!(info.code, 402);
+        assert_eq!(info.retry_hint, RetryHint::RebuildTransaction);
+    }
+
+    #[test]
+    fn test_vm_status_codes() {
+        let info = ErrorCodeInfo::from(StatusCode::TRANSACTION_EXPIRED);
+        assert_eq!(info.name, "vm_transaction_expired");
+        assert_eq!(info.retry_hint, RetryHint::RebuildTransaction);
+        assert_eq!(ErrorCodeInfo::from_code(info.code), Some(info));
+
+        let unknown = ErrorCodeInfo::from(StatusCode::UNKNOWN_STATUS);
+        assert_eq!(unknown.code, u64::MAX);
+        assert_eq!(ErrorCodeInfo::from_code(u64::MAX), Some(unknown));
+    }
+}
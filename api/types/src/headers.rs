@@ -17,3 +17,9 @@ pub const X_APTOS_OLDEST_BLOCK_HEIGHT: &str = "X-Aptos-Oldest-Block-Height";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
 /// Cursor used for pagination.
 pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
+/// SHA3-256 digest of the body of an immutable response
+pub const X_APTOS_CONTENT_DIGEST: &str = "X-Aptos-Content-Digest";
+/// Signature over the `ResponseDigest` of an immutable response
+pub const X_APTOS_RESPONSE_SIGNATURE: &str = "X-Aptos-Response-Signature";
+/// Network identity public key of the node that signed the response
+pub const X_APTOS_RESPONSE_SIGNER: &str = "X-Aptos-Response-Signer";
@@ -3,17 +3,22 @@
 
 mod account;
 mod address;
+mod batch;
 mod block;
 mod bytecode;
 mod convert;
 mod derives;
 mod error;
+mod error_catalog;
 mod hash;
 mod headers;
 mod index;
 mod ledger_info;
 pub mod mime_types;
 mod move_types;
+mod response_digest;
+mod state_sync;
+mod submission_receipt;
 mod table;
 mod transaction;
 mod view;
@@ -21,10 +26,18 @@ mod wrappers;
 
 pub use account::AccountData;
 pub use address::Address;
+pub use batch::{
+    AccountResourceRequest, BatchReadRequest, BatchReadResult, EventsByCreationNumberRequest,
+    TransactionByHashRequest, TransactionByVersionRequest,
+};
 pub use block::{BcsBlock, Block};
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_utf8_string, AsConverter, ExplainVMStatus, MoveConverter};
 pub use error::{AptosError, AptosErrorCode};
+pub use error_catalog::{
+    error_catalog, ErrorCategory, ErrorCodeInfo, RetryHint, MEMPOOL_STATUS_CODE_OFFSET,
+    VM_STATUS_CODE_OFFSET,
+};
 pub use hash::HashValue;
 pub use headers::*;
 pub use index::{IndexResponse, IndexResponseBcs};
@@ -36,22 +49,26 @@ pub use move_types::{
     MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType, MoveValue,
     MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
 };
+pub use response_digest::ResponseDigest;
 use serde::{Deserialize, Deserializer};
+pub use state_sync::StateSyncStatus;
 use std::str::FromStr;
+pub use submission_receipt::{SubmissionReceipt, SubmissionReceiptMessage};
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
     AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource, DeleteTableItem,
     DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest, EntryFunctionPayload, Event,
-    GasEstimation, GasEstimationBcs, GenesisPayload, GenesisTransaction, ModuleBundlePayload,
-    MultiAgentSignature, MultiEd25519Signature, PendingTransaction, ScriptPayload, ScriptWriteSet,
-    SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
-    TransactionOnChainData, TransactionPayload, TransactionSignature, TransactionSigningMessage,
+    EvictionReason, GasEstimation, GasEstimationBcs, GenesisPayload, GenesisTransaction,
+    ModuleBundlePayload, MultiAgentSignature, MultiEd25519Signature, PendingTransaction,
+    ScriptPayload, ScriptWriteSet, SubmitTransactionRequest, Transaction, TransactionBlockContext,
+    TransactionData, TransactionEviction, TransactionId, TransactionInfo, TransactionOnChainData,
+    TransactionPayload, TransactionSignature, TransactionSigningMessage,
     TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult,
     UserCreateSigningMessageRequest, UserTransaction, UserTransactionRequest, VersionedEvent,
     WriteModule, WriteResource, WriteSet, WriteSetChange, WriteSetPayload, WriteTableItem,
 };
 pub use view::ViewRequest;
-pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
+pub use wrappers::{EventCursor, EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
@@ -0,0 +1,67 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::{ed25519::Ed25519Signature, hash::HashValue, x25519, Signature};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use serde::{Deserialize, Serialize};
+
+/// The message a node signs for an immutable response, binding the body digest to the
+/// chain and the request it answers. Caching proxies can serve the response as is, and
+/// clients can still check that it came unmodified from a node they trust.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+pub struct ResponseDigest {
+    pub chain_id: u8,
+    /// Path and query of the request, e.g. `/v1/transactions/by_version/10`
+    pub request: String,
+    /// SHA3-256 of the response body
+    pub digest: HashValue,
+}
+
+impl ResponseDigest {
+    pub fn new(chain_id: u8, request: String, body: &[u8]) -> Self {
+        Self {
+            chain_id,
+            request,
+            digest: HashValue::sha3_256_of(body),
+        }
+    }
+
+    /// Signs the digest with the network identity key of the node, returning the signature
+    /// along with the identity it can be checked against.
+    pub fn sign(
+        &self,
+        identity_key: &x25519::PrivateKey,
+    ) -> anyhow::Result<(Ed25519Signature, x25519::PublicKey)> {
+        Ok((identity_key.sign_ed25519(self)?, identity_key.public_key()))
+    }
+
+    /// Verifies the signature against the network identity of the node.
+    pub fn verify(
+        &self,
+        signature: &Ed25519Signature,
+        identity: &x25519::PublicKey,
+    ) -> anyhow::Result<()> {
+        signature.verify(self, &identity.to_ed25519_public_key()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::Uniform;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = x25519::PrivateKey::generate_for_testing();
+        let digest = ResponseDigest::new(4, "/v1/transactions/by_version/1".to_string(), b"{}");
+        let (signature, identity) = digest.sign(&key).unwrap();
+        assert_eq!(identity, key.public_key());
+        digest.verify(&signature, &identity).unwrap();
+
+        let other_key = x25519::PrivateKey::generate_for_testing();
+        assert!(digest.verify(&signature, &other_key.public_key()).is_err());
+
+        let tampered = ResponseDigest::new(4, "/v1/transactions/by_version/1".to_string(), b"[]");
+        assert!(tampered.verify(&signature, &identity).is_err());
+    }
+}
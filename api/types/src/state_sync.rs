@@ -0,0 +1,29 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use poem_openapi::Object as PoemObject;
+use serde::{Deserialize, Serialize};
+
+/// The bootstrapping progress of the node, as reported by state sync
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, PoemObject, Serialize)]
+pub struct StateSyncStatus {
+    /// The bootstrapping mode of the node, e.g. `download_latest_states`.
+    /// Not set before the bootstrapper has started.
+    pub bootstrapping_mode: Option<String>,
+    /// The current bootstrapping phase: `not_started`,
+    /// `fetching_epoch_ending_ledger_infos`, `downloading_states`,
+    /// `syncing_transactions` or `bootstrapped`
+    pub phase: String,
+    /// The highest version synced by the bootstrapper
+    pub synced_version: Option<U64>,
+    /// The version the bootstrapper is syncing to
+    pub target_version: Option<U64>,
+    /// The number of state values downloaded for the state snapshot
+    pub synced_states: U64,
+    /// The number of versions (or state values, when downloading states)
+    /// synced per second, over the last minute
+    pub throughput_per_sec: Option<U64>,
+    /// The estimated number of seconds until the target version is reached
+    pub eta_secs: Option<U64>,
+}
@@ -0,0 +1,104 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HashValue, HexEncodedBytes, U64};
+use aptos_crypto::{ed25519::Ed25519Signature, x25519, Signature};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_types::account_address::AccountAddress;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The message a node signs when it accepts a transaction into its mempool. It proves that
+/// the transaction was submitted, even if it's never committed.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+pub struct SubmissionReceiptMessage {
+    pub chain_id: u8,
+    pub transaction_hash: aptos_crypto::HashValue,
+    /// Timestamp of the acceptance in microseconds
+    pub accepted_at: u64,
+    /// Peer id of the node that accepted the transaction
+    pub node: AccountAddress,
+}
+
+/// A receipt signed by the node, acknowledging that it accepted a transaction into its mempool
+///
+/// The signature is over the BCS encoded `SubmissionReceiptMessage`. It's made with the network
+/// identity key of the node, so it can be checked by anyone who knows the node's identity, e.g.
+/// from its network address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SubmissionReceipt {
+    pub chain_id: u8,
+    pub transaction_hash: HashValue,
+    /// Timestamp of the acceptance in microseconds
+    pub accepted_at: U64,
+    /// Peer id of the node that accepted the transaction
+    pub node: Address,
+    /// X25519 network identity public key of the node
+    pub public_key: HexEncodedBytes,
+    /// Ed25519 signature of the receipt, by the Ed25519 form of the identity key
+    pub signature: HexEncodedBytes,
+}
+
+impl SubmissionReceipt {
+    /// Signs the receipt with the network identity key of the node.
+    pub fn sign(
+        message: SubmissionReceiptMessage,
+        identity_key: &x25519::PrivateKey,
+    ) -> anyhow::Result<Self> {
+        let signature = identity_key.sign_ed25519(&message)?;
+        Ok(Self {
+            chain_id: message.chain_id,
+            transaction_hash: message.transaction_hash.into(),
+            accepted_at: message.accepted_at.into(),
+            node: message.node.into(),
+            public_key: identity_key.public_key().as_slice().to_vec().into(),
+            signature: signature.to_bytes().to_vec().into(),
+        })
+    }
+
+    pub fn message(&self) -> SubmissionReceiptMessage {
+        SubmissionReceiptMessage {
+            chain_id: self.chain_id,
+            transaction_hash: self.transaction_hash.into(),
+            accepted_at: self.accepted_at.0,
+            node: self.node.into(),
+        }
+    }
+
+    /// Verifies the receipt against the expected network identity of the node.
+    pub fn verify(&self, identity: &x25519::PublicKey) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.public_key.0 == identity.as_slice(),
+            "Receipt wasn't signed by the expected node"
+        );
+        let signature = Ed25519Signature::try_from(self.signature.0.as_slice())?;
+        signature.verify(&self.message(), &identity.to_ed25519_public_key()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::Uniform;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = x25519::PrivateKey::generate_for_testing();
+        let message = SubmissionReceiptMessage {
+            chain_id: 4,
+            transaction_hash: aptos_crypto::HashValue::random(),
+            accepted_at: 1_665_000_000_000_000,
+            node: AccountAddress::random(),
+        };
+        let receipt = SubmissionReceipt::sign(message, &key).unwrap();
+        receipt.verify(&key.public_key()).unwrap();
+
+        let other_key = x25519::PrivateKey::generate_for_testing();
+        assert!(receipt.verify(&other_key.public_key()).is_err());
+
+        let mut tampered = receipt;
+        tampered.accepted_at = U64(0);
+        assert!(tampered.verify(&key.public_key()).is_err());
+    }
+}
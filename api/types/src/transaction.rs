@@ -4,7 +4,7 @@
 use crate::{
     Address, AptosError, EntryFunctionId, EventGuid, HashValue, HexEncodedBytes,
     MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveType,
-    MoveValue, VerifyInput, VerifyInputWithRecursion, U64,
+    MoveValue, SubmissionReceipt, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use anyhow::{bail, Context as AnyhowContext};
 use aptos_crypto::ed25519::{ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH};
@@ -16,6 +16,7 @@ use aptos_crypto::{
 use aptos_types::transaction::authenticator::MAX_NUM_OF_SIGS;
 use aptos_types::{
     account_address::AccountAddress,
+    account_config::NewBlockEvent,
     block_metadata::BlockMetadata,
     contract_event::{ContractEvent, EventWithVersion},
     transaction::{
@@ -23,7 +24,7 @@ use aptos_types::{
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
     },
 };
-use poem_openapi::{Object, Union};
+use poem_openapi::{Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::{
     boxed::Box,
@@ -153,6 +154,34 @@ impl
     }
 }
 
+/// The block a committed transaction belongs to, as needed to render the transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionBlockContext {
+    pub timestamp_usecs: u64,
+    pub round: u64,
+    pub proposer: AccountAddress,
+}
+
+impl From<&BlockMetadata> for TransactionBlockContext {
+    fn from(block_metadata: &BlockMetadata) -> Self {
+        Self {
+            timestamp_usecs: block_metadata.timestamp_usecs(),
+            round: block_metadata.round(),
+            proposer: block_metadata.proposer(),
+        }
+    }
+}
+
+impl From<&NewBlockEvent> for TransactionBlockContext {
+    fn from(new_block_event: &NewBlockEvent) -> Self {
+        Self {
+            timestamp_usecs: new_block_event.proposed_time(),
+            round: new_block_event.round(),
+            proposer: new_block_event.proposer(),
+        }
+    }
+}
+
 /// Enum of the different types of transactions in Aptos
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -239,6 +268,8 @@ impl From<(SignedTransaction, TransactionPayload)> for Transaction {
         Transaction::PendingTransaction(PendingTransaction {
             request: (&txn, payload).into(),
             hash: txn.committed_hash().into(),
+            eviction: None,
+            receipt: None,
         })
     }
 }
@@ -249,23 +280,25 @@ impl
         TransactionInfo,
         TransactionPayload,
         Vec<Event>,
-        u64,
+        TransactionBlockContext,
     )> for Transaction
 {
     fn from(
-        (txn, info, payload, events, timestamp): (
+        (txn, info, payload, events, block): (
             &SignedTransaction,
             TransactionInfo,
             TransactionPayload,
             Vec<Event>,
-            u64,
+            TransactionBlockContext,
         ),
     ) -> Self {
         Transaction::UserTransaction(Box::new(UserTransaction {
             info,
             request: (txn, payload).into(),
             events,
-            timestamp: timestamp.into(),
+            timestamp: block.timestamp_usecs.into(),
+            block_round: block.round.into(),
+            block_proposer: block.proposer.into(),
         }))
     }
 }
@@ -343,6 +376,13 @@ pub struct PendingTransaction {
     #[serde(flatten)]
     #[oai(flatten)]
     pub request: UserTransactionRequest,
+    /// Set if the transaction was recently evicted from the mempool of the node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eviction: Option<TransactionEviction>,
+    /// Receipt signed by the node when it accepted the transaction, only returned on
+    /// submission if the node issues receipts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<SubmissionReceipt>,
 }
 
 impl From<(SignedTransaction, TransactionPayload)> for PendingTransaction {
@@ -350,10 +390,39 @@ impl From<(SignedTransaction, TransactionPayload)> for PendingTransaction {
         PendingTransaction {
             request: (&txn, payload).into(),
             hash: txn.committed_hash().into(),
+            eviction: None,
+            receipt: None,
         }
     }
 }
 
+/// The eviction of a transaction from mempool before it was committed
+///
+/// An evicted transaction won't be committed, unless it's submitted again or it's still in the
+/// mempool of other nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct TransactionEviction {
+    pub reason: EvictionReason,
+    /// Timestamp of the eviction in microseconds
+    pub timestamp: U64,
+}
+
+/// The reason a transaction was evicted from mempool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum EvictionReason {
+    /// Mempool was full, and the transaction was waiting on a transaction with a lower sequence
+    /// number, so it made space for a transaction that could be committed right away
+    MempoolFull,
+    /// The same transaction was submitted with a higher gas unit price
+    Replaced,
+    /// The transaction stayed in mempool for too long
+    SystemTtlExpired,
+    /// The expiration timestamp of the transaction passed
+    Expired,
+}
+
 /// A transaction submitted by a user to change the state of the blockchain
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct UserTransaction {
@@ -366,6 +435,8 @@ pub struct UserTransaction {
     /// Events generated by the transaction
     pub events: Vec<Event>,
     pub timestamp: U64,
+    pub block_round: U64,
+    pub block_proposer: Address,
 }
 
 /// A state checkpoint transaction
@@ -15,7 +15,7 @@ use anyhow::{bail, Context};
 use aptos_types::{event::EventKey, state_store::state_key::StateKey};
 use move_core_types::identifier::{IdentStr, Identifier};
 use poem_openapi::Object;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use std::{convert::From, fmt, ops::Deref, str::FromStr};
 
 use crate::{Address, U64};
@@ -154,3 +154,50 @@ impl From<StateKeyWrapper> for StateKey {
         value.0
     }
 }
+
+/// The position of an event among the events of its type: the version of the transaction that
+/// emitted it, and its index among the events of the transaction. This is used for cursor based
+/// pagination of events by type.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EventCursor {
+    pub version: u64,
+    pub index: u64,
+}
+
+impl fmt::Display for EventCursor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}_{}", self.version, self.index)
+    }
+}
+
+impl FromStr for EventCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self, anyhow::Error> {
+        let (version, index) = s
+            .split_once('_')
+            .context("EventCursor must be of the form <version>_<index>")?;
+        Ok(EventCursor {
+            version: version
+                .parse()
+                .context("Failed to parse EventCursor version")?,
+            index: index.parse().context("Failed to parse EventCursor index")?,
+        })
+    }
+}
+
+impl Serialize for EventCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_string().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EventCursor {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let cursor = <String>::deserialize(deserializer)?;
+        cursor.parse().map_err(D::Error::custom)
+    }
+}
@@ -2,26 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{format_err, Result};
+use aptos_aggregator::transaction::TransactionOutputExt;
+use aptos_crypto::HashValue;
 use aptos_gas::{
-    AbstractValueSizeGasParameters, ChangeSetConfigs, NativeGasParameters,
+    AbstractValueSizeGasParameters, AptosGasMeter, ChangeSetConfigs, NativeGasParameters,
     LATEST_GAS_FEATURE_VERSION,
 };
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AptosValueAnnotator};
 use aptos_rest_client::Client;
+use aptos_state_view::StateView;
 use aptos_types::{
     account_address::AccountAddress,
     chain_id::ChainId,
-    transaction::{ChangeSet, Transaction, TransactionOutput, Version},
+    on_chain_config::{Features, OnChainConfig},
+    transaction::{
+        ChangeSet, ExecutionStatus, Script, Transaction, TransactionOutput, TransactionStatus,
+        Version,
+    },
 };
 use aptos_validator_interface::{
     AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
 };
 use aptos_vm::{
     data_cache::StorageAdapter,
+    logging::AdapterLogSchema,
     move_vm_ext::{MoveVmExt, SessionExt, SessionId},
     AptosVM, VMExecutor,
 };
-use move_binary_format::errors::VMResult;
+use move_binary_format::{
+    errors::VMResult,
+    file_format::{CompiledScript, SignatureToken},
+};
+use move_core_types::{transaction_argument::convert_txn_args, value::MoveValue};
 use std::{path::Path, sync::Arc};
 
 pub struct AptosDebugger {
@@ -154,17 +166,16 @@ impl AptosDebugger {
     where
         F: FnOnce(&mut SessionExt<StorageAdapter<DebuggerStateView>>) -> VMResult<()>,
     {
+        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
+        let state_view_storage = StorageAdapter::new(&state_view);
         let move_vm = MoveVmExt::new(
             NativeGasParameters::zeros(),
             AbstractValueSizeGasParameters::zeros(),
             LATEST_GAS_FEATURE_VERSION,
-            true,
-            true,
             ChainId::test().id(),
+            Features::fetch_config(&state_view_storage).unwrap_or_default(),
         )
         .unwrap();
-        let state_view = DebuggerStateView::new(self.debugger.clone(), version);
-        let state_view_storage = StorageAdapter::new(&state_view);
         let mut session = move_vm.new_session(&state_view_storage, SessionId::Void);
         f(&mut session).map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?;
         session
@@ -179,6 +190,87 @@ impl AptosDebugger {
     }
 }
 
+/// Executes a script sent by `sender` on top of `state_view`, without the prologue and epilogue
+/// of a transaction: the sender is neither authenticated nor charged. The script sees its own
+/// hash as a transaction would, so that it can resolve the governance proposal it's approved
+/// by. Used to dry-run scripts against a forked state, e.g. governance proposals before they're
+/// submitted.
+pub fn execute_script_as(
+    state_view: &impl StateView,
+    sender: AccountAddress,
+    script: &Script,
+    gas_budget: u64,
+) -> Result<TransactionOutput> {
+    let vm = AptosVM::new(state_view);
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let internals = vm.internals();
+    let mut gas_meter = AptosGasMeter::new(
+        internals.gas_feature_version(),
+        internals
+            .gas_params(&log_context)
+            .map_err(|err| format_err!("Failed to load the gas schedule: {:?}", err))?
+            .clone(),
+        internals
+            .storage_gas_params(&log_context)
+            .map_err(|err| format_err!("Failed to load the storage gas schedule: {:?}", err))?
+            .clone(),
+        gas_budget,
+    );
+
+    // As for a transaction, the sender is the signer of the scripts taking one
+    let compiled_script = CompiledScript::deserialize(script.code())
+        .map_err(|err| format_err!("Failed to deserialize the script: {:?}", err))?;
+    let takes_signer = compiled_script
+        .signature_at(compiled_script.parameters)
+        .0
+        .first()
+        .map_or(false, |ty| match ty {
+            SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+            ty => *ty == SignatureToken::Signer,
+        });
+    let mut args = vec![];
+    if takes_signer {
+        args.push(MoveValue::Signer(sender).simple_serialize().unwrap());
+    }
+    args.extend(convert_txn_args(script.args()));
+
+    let resolver = StorageAdapter::new(state_view);
+    let mut session = internals.move_vm().new_session(
+        &resolver,
+        SessionId::Txn {
+            sender,
+            sequence_number: 0,
+            script_hash: HashValue::sha3_256_of(script.code()).to_vec(),
+        },
+    );
+    session
+        .execute_script(
+            script.code(),
+            script.ty_args().to_vec(),
+            args,
+            &mut gas_meter,
+        )
+        .map_err(|err| format_err!("Failed to execute the script: {:?}", err))?;
+
+    let (delta_change_set, change_set) = session
+        .finish()
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+        .into_change_set(
+            &mut (),
+            &ChangeSetConfigs::unlimited_at_gas_feature_version(internals.gas_feature_version()),
+        )
+        .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+        .into_inner();
+    let (write_set, events) = change_set.into_inner();
+    let output = TransactionOutput::new(
+        write_set,
+        events,
+        gas_budget - u64::from(gas_meter.balance()),
+        TransactionStatus::Keep(ExecutionStatus::Success),
+    );
+    Ok(TransactionOutputExt::new(delta_change_set, output).into_transaction_output(state_view))
+}
+
 fn is_reconfiguration(vm_output: &TransactionOutput) -> bool {
     let new_epoch_event_key = aptos_types::on_chain_config::new_epoch_event_key();
     vm_output
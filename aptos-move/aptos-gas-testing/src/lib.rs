@@ -0,0 +1,134 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for tests that change the gas schedule of a running network.
+//!
+//! A typical test builds a modified gas schedule with [`gas_schedule_with`], submits it with
+//! [`update_gas_schedule`] using the core resources account of a testnet, and then checks the
+//! result with [`assert_gas_schedule_on_chain`].
+
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_framework::{BuildOptions, BuiltPackage};
+use aptos_gas::{
+    AptosGasParameters, InitialGasSchedule, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
+};
+use aptos_release_builder::components::gas::generate_gas_upgrade_proposal;
+use aptos_rest_client::Client;
+use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    chain_id::ChainId,
+    on_chain_config::GasScheduleV2,
+    transaction::{Script, TransactionPayload},
+};
+use std::fs;
+
+/// The gas schedule governance scripts are large, so they need a higher gas limit than usual.
+const MAX_GAS_AMOUNT: u64 = 2_000_000;
+
+/// Builds a gas schedule from the initial gas parameters, modified by `modify`.
+pub fn gas_schedule_with(modify: impl FnOnce(&mut AptosGasParameters)) -> GasScheduleV2 {
+    let mut gas_parameters = AptosGasParameters::initial();
+    modify(&mut gas_parameters);
+    GasScheduleV2 {
+        feature_version: LATEST_GAS_FEATURE_VERSION,
+        entries: gas_parameters.to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION),
+    }
+}
+
+/// Generates the testnet governance script that sets `gas_schedule`, and compiles it against
+/// the local aptos framework.
+pub fn compile_gas_schedule_script(gas_schedule: &GasScheduleV2) -> Result<Vec<u8>> {
+    let (_, script) = generate_gas_upgrade_proposal(gas_schedule, true, "".to_owned())?
+        .pop()
+        .ok_or_else(|| format_err!("No gas schedule proposal generated"))?;
+
+    let package_dir = TempPath::new();
+    package_dir.create_as_dir()?;
+    let sources_dir = package_dir.path().join("sources");
+    fs::create_dir(&sources_dir)?;
+    fs::write(
+        package_dir.path().join("Move.toml"),
+        format!(
+            "[package]\nname = \"GasScheduleProposal\"\nversion = \"1.0.0\"\n\n\
+             [dependencies]\nAptosFramework = {{ local = \"{}\" }}\n",
+            aptos_framework::path_in_crate("aptos-framework").display()
+        ),
+    )?;
+    fs::write(sources_dir.join("gas_schedule.move"), script)?;
+
+    let package = BuiltPackage::build(
+        package_dir.path().to_path_buf(),
+        BuildOptions {
+            with_srcs: false,
+            with_abis: false,
+            with_source_maps: false,
+            with_error_map: false,
+            skip_fetch_latest_git_deps: true,
+            ..BuildOptions::default()
+        },
+    )?;
+    match package.extract_script_code().pop() {
+        Some(code) => Ok(code),
+        None => bail!("Gas schedule proposal did not compile to a script"),
+    }
+}
+
+/// Sets the on-chain gas schedule through a testnet governance script signed by `signer`,
+/// which must be the core resources account, and waits for it to be committed.
+pub async fn update_gas_schedule(
+    client: &Client,
+    signer: &mut LocalAccount,
+    gas_schedule: &GasScheduleV2,
+) -> Result<()> {
+    let code = compile_gas_schedule_script(gas_schedule)?;
+    let chain_id = client.get_index().await?.inner().chain_id;
+    let txn = signer.sign_with_transaction_builder(
+        TransactionFactory::new(ChainId::new(chain_id))
+            .with_max_gas_amount(MAX_GAS_AMOUNT)
+            .payload(TransactionPayload::Script(Script::new(
+                code,
+                vec![],
+                vec![],
+            ))),
+    );
+    client.submit_and_wait(&txn).await?;
+    Ok(())
+}
+
+/// Fetches the gas schedule currently on chain.
+pub async fn get_gas_schedule(client: &Client) -> Result<GasScheduleV2> {
+    Ok(client
+        .get_account_resource_bcs::<GasScheduleV2>(
+            CORE_CODE_ADDRESS,
+            "0x1::gas_schedule::GasScheduleV2",
+        )
+        .await?
+        .into_inner())
+}
+
+/// Checks that every entry of `expected` is on chain with the same value.
+pub async fn assert_gas_schedule_on_chain(client: &Client, expected: &GasScheduleV2) -> Result<()> {
+    let on_chain = get_gas_schedule(client).await?;
+    ensure!(
+        on_chain.feature_version == expected.feature_version,
+        "Gas feature version mismatch: expected {}, found {}",
+        expected.feature_version,
+        on_chain.feature_version
+    );
+    let on_chain = on_chain.to_btree_map();
+    for (name, value) in &expected.entries {
+        match on_chain.get(name) {
+            Some(on_chain_value) => ensure!(
+                on_chain_value == value,
+                "Gas parameter {} mismatch: expected {}, found {}",
+                name,
+                value,
+                on_chain_value
+            ),
+            None => bail!("Gas parameter {} is missing on chain", name),
+        }
+    }
+    Ok(())
+}
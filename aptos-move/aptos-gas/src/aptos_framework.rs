@@ -100,6 +100,8 @@ crate::natives::define_gas_parameters_for_natives!(GasParameters, "aptos_framewo
 
     [.transaction_context.get_script_hash.base, "transaction_context.get_script_hash.base", 200 * MUL],
 
+    [.block.current_block_info.base, { 6.. => "block.current_block_info.base" }, 200 * MUL],
+
     [.code.request_publish.base, "code.request_publish.base", 500 * MUL],
     [.code.request_publish.per_byte, "code.request_publish.per_byte", 2 * MUL],
 
@@ -114,5 +116,10 @@ crate::natives::define_gas_parameters_for_natives!(GasParameters, "aptos_framewo
     [.aggregator.read.base, "aggregator.read.base", 300 * MUL],
     [.aggregator.sub.base, "aggregator.sub.base", 300 * MUL],
     [.aggregator.destroy.base, "aggregator.destroy.base", 500 * MUL],
-    [.aggregator_factory.new_aggregator.base, "aggregator_factory.new_aggregator.base", 500 * MUL]
+    [.aggregator_factory.new_aggregator.base, "aggregator_factory.new_aggregator.base", 500 * MUL],
+
+    // Coarse costs until experimental natives are calibrated, a step is about hashing 64 bytes
+    [.experimental.call.base, { 6.. => "experimental.call.base" }, 3_000],
+    [.experimental.call.per_byte, { 6.. => "experimental.call.per_byte" }, 50],
+    [.experimental.call.per_step, { 6.. => "experimental.call.per_step" }, 3_000],
 ]);
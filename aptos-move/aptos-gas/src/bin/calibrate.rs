@@ -0,0 +1,12 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_gas::calibration::{calibrate_current_gas_schedule, CalibrationArgs};
+use clap::Parser;
+
+fn main() -> Result<()> {
+    let args = CalibrationArgs::parse();
+
+    calibrate_current_gas_schedule(&args)
+}
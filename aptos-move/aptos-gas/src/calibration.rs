@@ -0,0 +1,589 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Calibration of the gas parameters against benchmarks of the instructions and native functions
+//! they price, run by the Move VM.
+//!
+//! Each benchmark runs a function of a benchmark module, whose loop repeats the operation priced
+//! by one parameter of the on-chain gas schedule, and a control function whose loop only lacks
+//! that operation. The difference between the two is the time of the operation, along with the
+//! few instructions it needs, e.g., to load its operands, which are priced at their baseline
+//! values. One of the parameters is the reference: its baseline value is kept, and sets how much
+//! gas a nanosecond costs. The candidate value of every other benchmarked parameter is its
+//! measured time at that rate, so that the relative costs of the parameters match the relative
+//! costs of the operations on the benchmarking machine.
+//!
+//! Benchmarking needs the Move IR compiler and the Move VM runtime, so this module is only compiled
+//! with the `calibration` feature, e.g.,
+//! `cargo run -p aptos-gas --features calibration --bin aptos-gas-calibrate`.
+
+use crate::{
+    gas_meter::{AptosGasParameters, FromOnChainGasSchedule},
+    gen::current_gas_schedule,
+    GasScheduleDiff, NativeGasParameters,
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_types::{account_config::CORE_CODE_ADDRESS, on_chain_config::GasScheduleV2};
+use clap::Parser;
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use move_ir_compiler::Compiler;
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Number of times the loop of a benchmark function repeats its statement per iteration, so
+/// that the time of the loop itself is small next to the time of the statements
+const REPETITIONS: u64 = 10;
+
+/// The functions of the benchmark module, with the statement their loop repeats. They all take
+/// the number of iterations of their loop and a vector of bytes, so that their arguments cost
+/// the same to pass.
+const BENCHMARK_FUNCTIONS: &[(&str, &str)] = &[
+    ("empty", ""),
+    ("ld_u64", "_ = 7;"),
+    ("copy_loc", "x = copy(y);"),
+    ("add", "x = copy(y) + 3;"),
+    ("sub", "x = copy(y) - 3;"),
+    ("mul", "x = copy(y) * 3;"),
+    ("div", "x = copy(y) / 3;"),
+    ("modulo", "x = copy(y) % 3;"),
+    ("lt", "b = copy(y) < 3;"),
+    ("call_function", "Self.nop();"),
+    ("bcs_to_bytes", "_ = bcs.to_bytes<vector<u8>>(&data);"),
+    (
+        "sha2_256",
+        "_ = hash.sha2_256(bcs.to_bytes<vector<u8>>(&data));",
+    ),
+    (
+        "sha3_256",
+        "_ = hash.sha3_256(bcs.to_bytes<vector<u8>>(&data));",
+    ),
+];
+
+fn benchmark_module_code() -> String {
+    let functions: String = BENCHMARK_FUNCTIONS
+        .iter()
+        .map(|(name, statement)| {
+            format!(
+                "
+    public {}(n: u64, data: vector<u8>) {{
+        let i: u64;
+        let x: u64;
+        let y: u64;
+        let b: bool;
+    label b0:
+        i = 0;
+        y = 1000;
+    label b1:
+        jump_if (copy(i) >= copy(n)) b3;
+    label b2:
+        {}
+        i = move(i) + 1;
+        jump b1;
+    label b3:
+        return;
+    }}
+",
+                name,
+                vec![*statement; REPETITIONS as usize].join("\n        ")
+            )
+        })
+        .collect();
+    format!(
+        "
+module 0xcafe.benchmarks {{
+    import 0x1.bcs;
+    import 0x1.hash;
+
+    nop() {{
+    label b0:
+        return;
+    }}
+{}
+}}
+",
+        functions
+    )
+}
+
+/// A Move VM with the natives of the Move standard library, and storage holding the framework
+/// along with the benchmark module. It runs the benchmark functions without metering them.
+struct BenchmarkVm {
+    vm: MoveVM,
+    storage: InMemoryStorage,
+    module_id: ModuleId,
+}
+
+impl BenchmarkVm {
+    fn new() -> Result<Self> {
+        let vm = MoveVM::new(move_stdlib::natives::all_natives(
+            CORE_CODE_ADDRESS,
+            NativeGasParameters::zeros().move_stdlib,
+        ))
+        .map_err(|err| anyhow!("Failed to create the VM: {:?}", err))?;
+
+        let framework = aptos_framework::testnet_release_bundle();
+        let mut storage = InMemoryStorage::new();
+        for (code, module) in framework.code_and_compiled_modules() {
+            storage.publish_or_overwrite_module(module.self_id(), code.to_vec());
+        }
+        let deps = framework.compiled_modules();
+        let module = Compiler {
+            deps: deps.iter().collect(),
+        }
+        .into_compiled_module(&benchmark_module_code())?;
+        let mut code = vec![];
+        module.serialize(&mut code)?;
+        let module_id = module.self_id();
+        storage.publish_or_overwrite_module(module_id.clone(), code);
+
+        Ok(Self {
+            vm,
+            storage,
+            module_id,
+        })
+    }
+
+    /// Returns how long the function takes to run its loop for the given number of iterations
+    fn run(&self, function: &str, iterations: u64, data: &[u8]) -> Result<Duration> {
+        let args = vec![bcs::to_bytes(&iterations)?, bcs::to_bytes(data)?];
+        let mut session = self.vm.new_session(&self.storage);
+        let start = Instant::now();
+        session
+            .execute_function_bypass_visibility(
+                &self.module_id,
+                IdentStr::new(function)?,
+                vec![],
+                args,
+                &mut UnmeteredGasMeter,
+            )
+            .map_err(|err| anyhow!("Failed to run benchmark function {}: {:?}", function, err))?;
+        Ok(start.elapsed())
+    }
+}
+
+/// How long and how many times each benchmark runs
+#[derive(Clone, Debug)]
+pub struct CalibrationConfig {
+    /// Time each benchmark runs for before being measured
+    pub warm_up_time: Duration,
+    /// Number of measurements of each benchmark, of which the median is kept
+    pub samples: usize,
+    /// Number of iterations of the loop of the benchmark functions in each measurement
+    pub iterations_per_sample: u64,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            warm_up_time: Duration::from_millis(500),
+            samples: 50,
+            iterations_per_sample: 1_000,
+        }
+    }
+}
+
+/// A benchmark of the operation priced by a gas parameter, as the difference between a function
+/// of the benchmark module and its control function
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    /// Name of the parameter in the on-chain gas schedule, e.g., `instr.add`
+    parameter: String,
+    /// Units of the parameter a statement of the function costs more than a statement of the
+    /// control, e.g., the number of bytes it processes
+    units: u64,
+    /// Units of the other parameters a statement of the function costs more than a statement of
+    /// the control
+    others: Vec<(String, u64)>,
+    function: String,
+    data: Vec<u8>,
+    control: String,
+    control_data: Vec<u8>,
+}
+
+impl Benchmark {
+    /// A statement of `function` costs a unit of the parameter more than a statement of
+    /// `control`
+    pub fn new(
+        parameter: impl Into<String>,
+        function: impl Into<String>,
+        control: impl Into<String>,
+    ) -> Self {
+        Self {
+            parameter: parameter.into(),
+            units: 1,
+            others: vec![],
+            function: function.into(),
+            data: vec![],
+            control: control.into(),
+            control_data: vec![],
+        }
+    }
+
+    /// Runs the function with `data` instead of no data, which costs `units` of the parameter
+    pub fn with_data(mut self, data: Vec<u8>, units: u64) -> Self {
+        self.data = data;
+        self.units = units.max(1);
+        self
+    }
+
+    /// A statement of the function also costs `units` of another parameter more than a statement
+    /// of the control
+    pub fn with_other(mut self, parameter: impl Into<String>, units: u64) -> Self {
+        self.others.push((parameter.into(), units));
+        self
+    }
+
+    /// Returns the median difference between the time of a statement of the function and a
+    /// statement of the control, over the samples
+    fn measure(&self, vm: &BenchmarkVm, config: &CalibrationConfig) -> Result<Measurement> {
+        let iterations = config.iterations_per_sample.max(1);
+        let warm_up_end = Instant::now() + config.warm_up_time;
+        loop {
+            vm.run(&self.function, iterations, &self.data)?;
+            vm.run(&self.control, iterations, &self.control_data)?;
+            if Instant::now() >= warm_up_end {
+                break;
+            }
+        }
+
+        let mut samples = (0..config.samples.max(1))
+            .map(|_| {
+                let time = vm.run(&self.function, iterations, &self.data)?;
+                let control_time = vm.run(&self.control, iterations, &self.control_data)?;
+                Ok((time.as_nanos() as f64 - control_time.as_nanos() as f64)
+                    / (iterations * REPETITIONS) as f64)
+            })
+            .collect::<Result<Vec<f64>>>()?;
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Ok(Measurement {
+            parameter: self.parameter.clone(),
+            units: self.units,
+            others: self.others.clone(),
+            nanos: samples[samples.len() / 2],
+        })
+    }
+}
+
+/// The benchmarks of the instructions and native functions whose costs can be told apart from
+/// the instructions feeding them
+pub fn default_benchmarks() -> Vec<Benchmark> {
+    let bytes = vec![7u8; 1024];
+    // The serialized bytes are prefixed with their length, which takes one more byte than the
+    // length of no bytes
+    let serialized_bytes = bytes.len() as u64 + 1;
+
+    let mut benchmarks = vec![
+        Benchmark::new("instr.ld_u64", "ld_u64", "empty").with_other("instr.pop", 1),
+        Benchmark::new("instr.call.base", "call_function", "empty").with_other("instr.ret", 1),
+    ];
+    // The arithmetic and comparison instructions have a constant as their second operand
+    for (parameter, function) in [
+        ("instr.add", "add"),
+        ("instr.sub", "sub"),
+        ("instr.mul", "mul"),
+        ("instr.div", "div"),
+        ("instr.mod", "modulo"),
+        ("instr.lt", "lt"),
+    ] {
+        benchmarks
+            .push(Benchmark::new(parameter, function, "copy_loc").with_other("instr.ld_u64", 1));
+    }
+    // The natives are run with bytes and with no bytes, so that only their costs per byte differ
+    benchmarks.push(
+        Benchmark::new(
+            "move_stdlib.bcs.to_bytes.per_byte_serialized",
+            "bcs_to_bytes",
+            "bcs_to_bytes",
+        )
+        .with_data(bytes.clone(), serialized_bytes),
+    );
+    for (parameter, function) in [
+        ("move_stdlib.hash.sha2_256.per_byte", "sha2_256"),
+        ("move_stdlib.hash.sha3_256.per_byte", "sha3_256"),
+    ] {
+        benchmarks.push(
+            Benchmark::new(parameter, function, function)
+                .with_data(bytes.clone(), serialized_bytes)
+                .with_other(
+                    "move_stdlib.bcs.to_bytes.per_byte_serialized",
+                    serialized_bytes,
+                ),
+        );
+    }
+    benchmarks
+}
+
+/// The measured time of a benchmark
+#[derive(Clone, Debug)]
+struct Measurement {
+    parameter: String,
+    units: u64,
+    others: Vec<(String, u64)>,
+    /// Time a statement of the function takes more than a statement of the control
+    nanos: f64,
+}
+/// A benchmarked gas parameter, along with its current and candidate values
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CalibratedParameter {
+    pub name: String,
+    pub nanos_per_unit: f64,
+    pub current: u64,
+    pub candidate: u64,
+}
+
+/// The result of running the benchmarks against a baseline gas schedule
+#[derive(Clone, Debug, Serialize)]
+pub struct CalibrationReport {
+    pub baseline: GasScheduleV2,
+    /// The parameter whose current value sets the cost of a nanosecond
+    pub reference: String,
+    /// Internal gas units a nanosecond costs
+    pub gas_per_nano: f64,
+    pub parameters: Vec<CalibratedParameter>,
+}
+
+impl CalibrationReport {
+    /// The baseline gas schedule, with the candidate values of the benchmarked parameters
+    pub fn candidate_gas_schedule(&self) -> GasScheduleV2 {
+        let candidates: BTreeMap<_, _> = self
+            .parameters
+            .iter()
+            .map(|parameter| (parameter.name.as_str(), parameter.candidate))
+            .collect();
+        GasScheduleV2 {
+            feature_version: self.baseline.feature_version,
+            entries: self
+                .baseline
+                .entries
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.clone(),
+                        candidates.get(name.as_str()).copied().unwrap_or(*value),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    pub fn candidate_gas_parameters(&self) -> Result<AptosGasParameters> {
+        let gas_schedule = self.candidate_gas_schedule();
+        AptosGasParameters::from_on_chain_gas_schedule(
+            &gas_schedule.clone().to_btree_map(),
+            gas_schedule.feature_version,
+        )
+        .ok_or_else(|| anyhow!("The candidate gas schedule misses some parameters"))
+    }
+
+    /// The parameters whose candidate values differ from the baseline
+    pub fn delta(&self) -> GasScheduleDiff {
+        AptosGasParameters::diff(
+            &self.baseline.entries,
+            &self.candidate_gas_schedule().entries,
+        )
+    }
+}
+
+/// Runs the benchmarks and fits their parameters to the baseline gas schedule through the
+/// reference parameter, which has to be benchmarked too
+pub fn calibrate(
+    benchmarks: Vec<Benchmark>,
+    baseline: &GasScheduleV2,
+    reference: &str,
+    config: &CalibrationConfig,
+) -> Result<CalibrationReport> {
+    let vm = BenchmarkVm::new()?;
+    let measurements = benchmarks
+        .iter()
+        .map(|benchmark| benchmark.measure(&vm, config))
+        .collect::<Result<Vec<_>>>()?;
+    fit(measurements, baseline, reference)
+}
+
+/// Sets the cost of a nanosecond so that the benchmark of the reference parameter costs as much
+/// as in the baseline, and prices the other parameters at that rate
+fn fit(
+    measurements: Vec<Measurement>,
+    baseline: &GasScheduleV2,
+    reference: &str,
+) -> Result<CalibrationReport> {
+    let baseline_values = baseline.clone().to_btree_map();
+    let current = |parameter: &str| match baseline_values.get(parameter) {
+        Some(current) => Ok(*current),
+        None => Err(anyhow!(
+            "Gas parameter {} doesn't exist at gas feature version {}",
+            parameter,
+            baseline.feature_version
+        )),
+    };
+    let others_gas = |measurement: &Measurement| {
+        measurement
+            .others
+            .iter()
+            .map(|(parameter, units)| Ok(current(parameter)? * units))
+            .sum::<Result<u64>>()
+    };
+
+    let gas_per_nano = match measurements
+        .iter()
+        .find(|measurement| measurement.parameter == reference)
+    {
+        Some(measurement) if measurement.nanos > 0.0 => {
+            (current(reference)? * measurement.units + others_gas(measurement)?) as f64
+                / measurement.nanos
+        }
+        Some(_) => bail!(
+            "The reference parameter {} took no measurable time",
+            reference
+        ),
+        None => bail!("The reference parameter {} isn't benchmarked", reference),
+    };
+
+    let parameters = measurements
+        .iter()
+        .map(|measurement| {
+            let current = current(&measurement.parameter)?;
+            let nanos_per_unit = (measurement.nanos
+                - others_gas(measurement)? as f64 / gas_per_nano)
+                / measurement.units as f64;
+            Ok(CalibratedParameter {
+                name: measurement.parameter.clone(),
+                nanos_per_unit,
+                current,
+                candidate: if measurement.parameter == reference {
+                    current
+                } else {
+                    (nanos_per_unit * gas_per_nano).round().max(1.0) as u64
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(CalibrationReport {
+        baseline: baseline.clone(),
+        reference: reference.to_string(),
+        gas_per_nano,
+        parameters,
+    })
+}
+
+#[derive(Debug, Parser)]
+pub struct CalibrationArgs {
+    /// The parameter whose current value is kept, and sets the cost of the others
+    #[clap(long, default_value = "instr.add")]
+    pub reference: String,
+    /// Number of measurements of each benchmark
+    #[clap(long, default_value_t = 50)]
+    pub samples: usize,
+    /// File to write the candidate gas schedule to, as YAML
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// Calibrates the current gas schedule with the default benchmarks, and prints the changes of
+/// the candidate gas schedule
+pub fn calibrate_current_gas_schedule(args: &CalibrationArgs) -> Result<()> {
+    let report = calibrate(
+        default_benchmarks(),
+        &current_gas_schedule(),
+        &args.reference,
+        &CalibrationConfig {
+            samples: args.samples,
+            ..CalibrationConfig::default()
+        },
+    )?;
+    for parameter in &report.parameters {
+        println!(
+            "{}: {:.3}ns per unit, {} -> {}",
+            parameter.name, parameter.nanos_per_unit, parameter.current, parameter.candidate
+        );
+    }
+    println!("{}", report.delta());
+
+    if let Some(output) = &args.output {
+        std::fs::write(
+            output,
+            serde_yaml::to_string(&report.candidate_gas_schedule())?,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(parameter: &str, others: &[(&str, u64)], nanos: f64) -> Measurement {
+        Measurement {
+            parameter: parameter.to_string(),
+            units: 1,
+            others: others
+                .iter()
+                .map(|(parameter, units)| (parameter.to_string(), *units))
+                .collect(),
+            nanos,
+        }
+    }
+
+    #[test]
+    fn test_fit() {
+        let baseline = GasScheduleV2 {
+            feature_version: 5,
+            entries: vec![
+                ("instr.add".to_string(), 3200),
+                ("instr.ld_u64".to_string(), 1200),
+                ("instr.mul".to_string(), 3200),
+                ("instr.nop".to_string(), 200),
+                ("instr.pop".to_string(), 800),
+            ],
+        };
+        // A nanosecond costs 1100 internal gas units, as the addition along with the load of
+        // its operand costs 4400 in the baseline
+        let measurements = || {
+            vec![
+                measurement("instr.add", &[("instr.ld_u64", 1)], 4.0),
+                measurement("instr.mul", &[("instr.ld_u64", 1)], 8.0),
+                measurement("instr.ld_u64", &[("instr.pop", 1)], 1.0),
+            ]
+        };
+
+        let report = fit(measurements(), &baseline, "instr.add").unwrap();
+        assert_eq!(report.gas_per_nano, 1100.0);
+        let candidate = report.candidate_gas_schedule().to_btree_map();
+        assert_eq!(candidate["instr.add"], 3200);
+        assert_eq!(candidate["instr.mul"], 7600);
+        assert_eq!(candidate["instr.ld_u64"], 300);
+        assert_eq!(candidate["instr.pop"], 800);
+        assert_eq!(candidate["instr.nop"], 200);
+        assert_eq!(
+            report
+                .delta()
+                .changes
+                .iter()
+                .map(|change| change.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["instr.ld_u64", "instr.mul"]
+        );
+
+        assert!(fit(measurements(), &baseline, "instr.nop").is_err());
+        let unknown = vec![measurement("instr.add", &[("instr.unknown", 1)], 4.0)];
+        assert!(fit(unknown, &baseline, "instr.add").is_err());
+    }
+
+    #[test]
+    fn test_benchmarks_run() {
+        let vm = BenchmarkVm::new().unwrap();
+        for benchmark in default_benchmarks() {
+            vm.run(&benchmark.function, 2, &benchmark.data).unwrap();
+            vm.run(&benchmark.control, 2, &benchmark.control_data)
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Differences between two lists of entries of the on-chain gas schedule, so that reviewers of a
+//! gas schedule proposal can see exactly which parameters it changes.
+
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// A gas parameter whose value differs between two gas schedules
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GasParameterChange {
+    /// Name of the parameter in the on-chain gas schedule, e.g., `txn.write_data.per_op`
+    pub name: String,
+    /// `None` if the parameter is added
+    pub old: Option<u64>,
+    /// `None` if the parameter is removed
+    pub new: Option<u64>,
+    /// Change relative to the old value, in percents. `None` if the parameter is added, removed
+    /// or was zero.
+    pub percentage_change: Option<f64>,
+}
+
+/// The parameters changed between two gas schedules, ordered by name
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct GasScheduleDiff {
+    pub changes: Vec<GasParameterChange>,
+}
+
+impl GasScheduleDiff {
+    pub fn new(old_entries: &[(String, u64)], new_entries: &[(String, u64)]) -> Self {
+        let old: BTreeMap<_, _> = old_entries.iter().cloned().collect();
+        let mut new: BTreeMap<_, _> = new_entries.iter().cloned().collect();
+
+        let mut changes: Vec<_> = old
+            .into_iter()
+            .filter_map(|(name, old_value)| {
+                let new_value = new.remove(&name);
+                (new_value != Some(old_value)).then(|| GasParameterChange {
+                    percentage_change: new_value.filter(|_| old_value != 0).map(|new_value| {
+                        (new_value as f64 - old_value as f64) * 100.0 / old_value as f64
+                    }),
+                    name,
+                    old: Some(old_value),
+                    new: new_value,
+                })
+            })
+            .collect();
+        changes.extend(new.into_iter().map(|(name, new_value)| GasParameterChange {
+            name,
+            old: None,
+            new: Some(new_value),
+            percentage_change: None,
+        }));
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the change of the parameter with the given name, if it changed
+    pub fn get(&self, name: &str) -> Option<&GasParameterChange> {
+        self.changes.iter().find(|change| change.name == name)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Gas schedule diffs must serialize to JSON")
+    }
+}
+
+impl fmt::Display for GasParameterChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.old, self.new) {
+            (Some(old), Some(new)) => {
+                write!(f, "{}: {} -> {}", self.name, old, new)?;
+                if let Some(percentage_change) = self.percentage_change {
+                    write!(f, " ({:+.2}%)", percentage_change)?;
+                }
+                Ok(())
+            }
+            (None, Some(new)) => write!(f, "{}: added with {}", self.name, new),
+            (Some(old), None) => write!(f, "{}: removed, was {}", self.name, old),
+            (None, None) => write!(f, "{}: unchanged", self.name),
+        }
+    }
+}
+
+impl fmt::Display for GasScheduleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No gas parameter changed");
+        }
+        for (index, change) in self.changes.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_gas_schedule_diff() {
+        let diff = GasScheduleDiff::new(
+            &entries(&[
+                ("txn.write_data.per_op", 300),
+                ("txn.min_transaction_gas_units", 1_500_000),
+                ("instr.nop", 0),
+                ("instr.removed", 7),
+            ]),
+            &entries(&[
+                ("txn.min_transaction_gas_units", 1_500_000),
+                ("txn.write_data.per_op", 450),
+                ("instr.nop", 10),
+                ("instr.added", 5),
+            ]),
+        );
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|change| change.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "instr.added",
+                "instr.nop",
+                "instr.removed",
+                "txn.write_data.per_op"
+            ]
+        );
+        assert_eq!(
+            diff.get("txn.write_data.per_op").unwrap().percentage_change,
+            Some(50.0)
+        );
+        assert_eq!(diff.get("instr.nop").unwrap().percentage_change, None);
+        assert_eq!(
+            diff.to_string(),
+            "instr.added: added with 5\n\
+             instr.nop: 0 -> 10\n\
+             instr.removed: removed, was 7\n\
+             txn.write_data.per_op: 300 -> 450 (+50.00%)"
+        );
+        assert!(diff.to_json().contains("\"percentage_change\": 50.0"));
+
+        let same = entries(&[("txn.write_data.per_op", 300)]);
+        assert!(GasScheduleDiff::new(&same, &same).is_empty());
+    }
+}
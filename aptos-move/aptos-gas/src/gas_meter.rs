@@ -5,15 +5,18 @@
 //! parameters and traits to help manipulate them.
 
 use crate::transaction::ChangeSetConfigs;
+#[cfg(feature = "gas-profiling")]
+use crate::GasProfiler;
 use crate::{
     algebra::{AbstractValueSize, Gas},
     instr::InstructionGasParameters,
     misc::MiscGasParameters,
     transaction::TransactionGasParameters,
-    StorageGasParameters,
+    GasScheduleDiff, StorageGasParameters,
 };
 use aptos_types::{
-    account_config::CORE_CODE_ADDRESS, state_store::state_key::StateKey, write_set::WriteOp,
+    account_config::CORE_CODE_ADDRESS, on_chain_config::GasScheduleV2,
+    state_store::state_key::StateKey, write_set::WriteOp,
 };
 use move_binary_format::errors::{Location, PartialVMError, PartialVMResult, VMResult};
 use move_core_types::{
@@ -28,6 +31,9 @@ use move_vm_types::{
 use std::collections::BTreeMap;
 
 // Change log:
+// - V6
+//   - Sandbox for experimental natives
+//   - Natives exposing the info of the current block
 // - V5
 //   - u16, u32, u256
 //   - free_write_bytes_quota
@@ -46,7 +52,14 @@ use std::collections::BTreeMap;
 //       global operations.
 // - V1
 //   - TBA
-pub const LATEST_GAS_FEATURE_VERSION: u64 = 5;
+pub const LATEST_GAS_FEATURE_VERSION: u64 = 6;
+
+/// The gas schedules set at genesis at past feature versions, which only record the parameters
+/// existing at their version.
+const RECORDED_GAS_SCHEDULES: &[(u64, &str)] = &[
+    (0, include_str!("../gas_schedules/v0.yaml")),
+    (3, include_str!("../gas_schedules/v3.yaml")),
+];
 
 pub(crate) const EXECUTION_GAS_MULTIPLIER: u64 = 20;
 
@@ -66,6 +79,8 @@ pub trait ToOnChainGasSchedule {
     /// Converts `self` into a list of entries of the on-chain gas schedule.
     /// Each entry is a key-value pair where the key is a string representing the name of the
     /// parameter, where the value is the gas parameter itself.
+    /// Only the parameters that exist at the given feature version are listed, so that the
+    /// entries can be read back with `FromOnChainGasSchedule` at that version.
     fn to_on_chain_gas_schedule(&self, feature_version: u64) -> Vec<(String, u64)>;
 }
 
@@ -189,6 +204,37 @@ impl AptosGasParameters {
             natives: NativeGasParameters::zeros(),
         }
     }
+
+    /// Returns the gas parameters set at genesis at the given feature version: the parameters
+    /// that don't exist at that version are zeros, as if they were loaded from an on-chain gas
+    /// schedule of that version. Returns `None` if no gas schedule was recorded at that version,
+    /// only the latest version, whose values are the initial ones, and the versions of
+    /// `RECORDED_GAS_SCHEDULES` having one.
+    pub fn for_feature_version(feature_version: u64) -> Option<Self> {
+        let mut entries: BTreeMap<_, _> = Self::initial()
+            .to_on_chain_gas_schedule(feature_version)
+            .into_iter()
+            .collect();
+        if feature_version != LATEST_GAS_FEATURE_VERSION {
+            let (_, recorded) = RECORDED_GAS_SCHEDULES
+                .iter()
+                .find(|(version, _)| *version == feature_version)?;
+            let recorded: GasScheduleV2 =
+                serde_yaml::from_str(recorded).expect("Recorded gas schedules must parse");
+            for (name, value) in recorded.entries {
+                if let Some(entry) = entries.get_mut(&name) {
+                    *entry = value;
+                }
+            }
+        }
+        Self::from_on_chain_gas_schedule(&entries, feature_version)
+    }
+
+    /// Pairs the entries of two on-chain gas schedules by name, and returns the parameters whose
+    /// values differ between them.
+    pub fn diff(old_entries: &[(String, u64)], new_entries: &[(String, u64)]) -> GasScheduleDiff {
+        GasScheduleDiff::new(old_entries, new_entries)
+    }
 }
 
 impl InitialGasSchedule for AptosGasParameters {
@@ -213,6 +259,9 @@ pub struct AptosGasMeter {
     memory_quota: AbstractValueSize,
 
     should_leak_memory_for_native: bool,
+
+    #[cfg(feature = "gas-profiling")]
+    profiler: Option<GasProfiler>,
 }
 
 impl AptosGasMeter {
@@ -232,9 +281,23 @@ impl AptosGasMeter {
             balance,
             memory_quota,
             should_leak_memory_for_native: false,
+            #[cfg(feature = "gas-profiling")]
+            profiler: None,
         }
     }
 
+    /// Starts recording where the gas is charged, `root` being the name of the script or entry
+    /// function executed.
+    #[cfg(feature = "gas-profiling")]
+    pub fn enable_profiling(&mut self, root: impl Into<String>) {
+        self.profiler = Some(GasProfiler::new(root));
+    }
+
+    #[cfg(feature = "gas-profiling")]
+    pub fn take_profiler(&mut self) -> Option<GasProfiler> {
+        self.profiler.take()
+    }
+
     pub fn balance(&self) -> Gas {
         self.balance
             .to_unit_round_down_with_params(&self.gas_params.txn)
@@ -254,6 +317,15 @@ impl AptosGasMeter {
         }
     }
 
+    #[inline]
+    fn charge_instr(&mut self, instr: &str, amount: InternalGas) -> PartialVMResult<()> {
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.charge_instruction(instr, amount);
+        }
+        self.charge(amount)
+    }
+
     #[inline]
     fn use_heap_memory(&mut self, amount: AbstractValueSize) -> PartialVMResult<()> {
         if self.feature_version >= 3 {
@@ -292,6 +364,13 @@ impl GasMeter for AptosGasMeter {
     #[inline]
     fn charge_simple_instr(&mut self, instr: SimpleInstruction) -> PartialVMResult<()> {
         let cost = self.gas_params.instr.simple_instr_cost(instr)?;
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.charge_instruction(&format!("{:?}", instr).to_lowercase(), cost);
+            if instr == SimpleInstruction::Ret {
+                profiler.exit();
+            }
+        }
         self.charge(cost)
     }
 
@@ -333,6 +412,10 @@ impl GasMeter for AptosGasMeter {
             }))?;
         }
 
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.charge_native_function(amount);
+        }
         self.charge(amount)
     }
 
@@ -356,7 +439,7 @@ impl GasMeter for AptosGasMeter {
             .storage_gas_params
             .pricing
             .calculate_read_gas(loaded.map(|(num_bytes, _)| num_bytes));
-        self.charge(cost)
+        self.charge_instr("load_resource", cost)
     }
 
     #[inline]
@@ -368,14 +451,15 @@ impl GasMeter for AptosGasMeter {
                 .abstract_heap_size(popped_val, self.feature_version),
         );
 
-        self.charge(self.gas_params.instr.pop)
+        self.charge_instr("pop", self.gas_params.instr.pop)
     }
 
     #[inline]
+    #[cfg_attr(not(feature = "gas-profiling"), allow(unused_variables))]
     fn charge_call(
         &mut self,
-        _module_id: &ModuleId,
-        _func_name: &str,
+        module_id: &ModuleId,
+        func_name: &str,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         num_locals: NumArgs,
     ) -> PartialVMResult<()> {
@@ -386,14 +470,20 @@ impl GasMeter for AptosGasMeter {
             cost += params.call_per_local * num_locals;
         }
 
-        self.charge(cost)
+        self.charge_instr("call", cost)?;
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.enter(module_id, func_name);
+        }
+        Ok(())
     }
 
     #[inline]
+    #[cfg_attr(not(feature = "gas-profiling"), allow(unused_variables))]
     fn charge_call_generic(
         &mut self,
         module_id: &ModuleId,
-        _func_name: &str,
+        func_name: &str,
         ty_args: impl ExactSizeIterator<Item = impl TypeView>,
         args: impl ExactSizeIterator<Item = impl ValueView>,
         num_locals: NumArgs,
@@ -414,13 +504,21 @@ impl GasMeter for AptosGasMeter {
             cost += params.call_generic_per_local * num_locals;
         }
 
-        self.charge(cost)
+        self.charge_instr("call_generic", cost)?;
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &mut self.profiler {
+            profiler.enter(module_id, func_name);
+        }
+        Ok(())
     }
 
     #[inline]
     fn charge_ld_const(&mut self, size: NumBytes) -> PartialVMResult<()> {
         let instr = &self.gas_params.instr;
-        self.charge(instr.ld_const_base + instr.ld_const_per_byte * size)
+        self.charge_instr(
+            "ld_const",
+            instr.ld_const_base + instr.ld_const_per_byte * size,
+        )
     }
 
     #[inline]
@@ -452,17 +550,17 @@ impl GasMeter for AptosGasMeter {
         let cost = instr_params.copy_loc_base
             + instr_params.copy_loc_per_abs_val_unit * (stack_size + heap_size);
 
-        self.charge(cost)
+        self.charge_instr("copy_loc", cost)
     }
 
     #[inline]
     fn charge_move_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.move_loc_base)
+        self.charge_instr("move_loc", self.gas_params.instr.move_loc_base)
     }
 
     #[inline]
     fn charge_store_loc(&mut self, _val: impl ValueView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.st_loc_base)
+        self.charge_instr("st_loc", self.gas_params.instr.st_loc_base)
     }
 
     #[inline]
@@ -486,7 +584,7 @@ impl GasMeter for AptosGasMeter {
             false => params.pack_base + params.pack_per_field * num_args,
             true => params.pack_generic_base + params.pack_generic_per_field * num_args,
         };
-        self.charge(cost)
+        self.charge_instr("pack", cost)
     }
 
     #[inline]
@@ -510,7 +608,7 @@ impl GasMeter for AptosGasMeter {
             false => params.unpack_base + params.unpack_per_field * num_args,
             true => params.unpack_generic_base + params.unpack_generic_per_field * num_args,
         };
-        self.charge(cost)
+        self.charge_instr("unpack", cost)
     }
 
     #[inline]
@@ -527,7 +625,7 @@ impl GasMeter for AptosGasMeter {
         let instr_params = &self.gas_params.instr;
         let cost = instr_params.read_ref_base
             + instr_params.read_ref_per_abs_val_unit * (stack_size + heap_size);
-        self.charge(cost)
+        self.charge_instr("read_ref", cost)
     }
 
     #[inline]
@@ -543,7 +641,7 @@ impl GasMeter for AptosGasMeter {
                 .abstract_heap_size(old_val, self.feature_version),
         );
 
-        self.charge(self.gas_params.instr.write_ref_base)
+        self.charge_instr("write_ref", self.gas_params.instr.write_ref_base)
     }
 
     #[inline]
@@ -570,7 +668,7 @@ impl GasMeter for AptosGasMeter {
                 * (abs_val_params.abstract_value_size_dereferenced(lhs, self.feature_version)
                     + abs_val_params.abstract_value_size_dereferenced(rhs, self.feature_version));
 
-        self.charge(cost)
+        self.charge_instr("eq", cost)
     }
 
     #[inline]
@@ -597,7 +695,7 @@ impl GasMeter for AptosGasMeter {
                 * (abs_val_params.abstract_value_size_dereferenced(lhs, self.feature_version)
                     + abs_val_params.abstract_value_size_dereferenced(rhs, self.feature_version));
 
-        self.charge(cost)
+        self.charge_instr("neq", cost)
     }
 
     #[inline]
@@ -615,7 +713,7 @@ impl GasMeter for AptosGasMeter {
             (true, false) => params.mut_borrow_global_base,
             (true, true) => params.mut_borrow_global_generic_base,
         };
-        self.charge(cost)
+        self.charge_instr("borrow_global", cost)
     }
 
     #[inline]
@@ -630,7 +728,7 @@ impl GasMeter for AptosGasMeter {
             false => params.exists_base,
             true => params.exists_generic_base,
         };
-        self.charge(cost)
+        self.charge_instr("exists", cost)
     }
 
     #[inline]
@@ -645,7 +743,7 @@ impl GasMeter for AptosGasMeter {
             false => params.move_from_base,
             true => params.move_from_generic_base,
         };
-        self.charge(cost)
+        self.charge_instr("move_from", cost)
     }
 
     #[inline]
@@ -661,7 +759,7 @@ impl GasMeter for AptosGasMeter {
             false => params.move_to_base,
             true => params.move_to_generic_base,
         };
-        self.charge(cost)
+        self.charge_instr("move_to", cost)
     }
 
     #[inline]
@@ -678,7 +776,7 @@ impl GasMeter for AptosGasMeter {
 
         let params = &self.gas_params.instr;
         let cost = params.vec_pack_base + params.vec_pack_per_elem * num_args;
-        self.charge(cost)
+        self.charge_instr("vec_pack", cost)
     }
 
     #[inline]
@@ -695,12 +793,12 @@ impl GasMeter for AptosGasMeter {
         let params = &self.gas_params.instr;
         let cost =
             params.vec_unpack_base + params.vec_unpack_per_expected_elem * expect_num_elements;
-        self.charge(cost)
+        self.charge_instr("vec_unpack", cost)
     }
 
     #[inline]
     fn charge_vec_len(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_len_base)
+        self.charge_instr("vec_len", self.gas_params.instr.vec_len_base)
     }
 
     #[inline]
@@ -715,7 +813,7 @@ impl GasMeter for AptosGasMeter {
             false => params.vec_imm_borrow_base,
             true => params.vec_mut_borrow_base,
         };
-        self.charge(cost)
+        self.charge_instr("vec_borrow", cost)
     }
 
     #[inline]
@@ -726,7 +824,7 @@ impl GasMeter for AptosGasMeter {
     ) -> PartialVMResult<()> {
         self.use_heap_memory(self.gas_params.misc.abs_val.abstract_packed_size(val))?;
 
-        self.charge(self.gas_params.instr.vec_push_back_base)
+        self.charge_instr("vec_push_back", self.gas_params.instr.vec_push_back_base)
     }
 
     #[inline]
@@ -739,12 +837,12 @@ impl GasMeter for AptosGasMeter {
             self.release_heap_memory(self.gas_params.misc.abs_val.abstract_packed_size(val));
         }
 
-        self.charge(self.gas_params.instr.vec_pop_back_base)
+        self.charge_instr("vec_pop_back", self.gas_params.instr.vec_pop_back_base)
     }
 
     #[inline]
     fn charge_vec_swap(&mut self, _ty: impl TypeView) -> PartialVMResult<()> {
-        self.charge(self.gas_params.instr.vec_swap_base)
+        self.charge_instr("vec_swap", self.gas_params.instr.vec_swap_base)
     }
 
     #[inline]
@@ -767,7 +865,8 @@ impl GasMeter for AptosGasMeter {
 impl AptosGasMeter {
     pub fn charge_intrinsic_gas_for_transaction(&mut self, txn_size: NumBytes) -> VMResult<()> {
         let cost = self.gas_params.txn.calculate_intrinsic_gas(txn_size);
-        self.charge(cost).map_err(|e| e.finish(Location::Undefined))
+        self.charge_instr("intrinsic", cost)
+            .map_err(|e| e.finish(Location::Undefined))
     }
 
     pub fn charge_write_set_gas<'a>(
@@ -775,6 +874,7 @@ impl AptosGasMeter {
         ops: impl IntoIterator<Item = (&'a StateKey, &'a WriteOp)>,
     ) -> VMResult<()> {
         let cost = self.storage_gas_params.pricing.calculate_write_set_gas(ops);
-        self.charge(cost).map_err(|e| e.finish(Location::Undefined))
+        self.charge_instr("write_set", cost)
+            .map_err(|e| e.finish(Location::Undefined))
     }
 }
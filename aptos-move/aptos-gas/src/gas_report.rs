@@ -0,0 +1,136 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module accumulates the gas charged per bytecode instruction and per native function over
+//! many transactions, so that changes to the gas schedule can be backed by the gas actually
+//! charged by real workloads. The VM only profiles transactions, and so returns their reports,
+//! when built with the `gas-profiling` feature.
+
+use crate::{GasProfiler, GasUsage};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// The gas charged per instruction and per native function, cumulated over transactions
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GasReport {
+    pub transactions: u64,
+    /// In internal gas units
+    pub total: u64,
+    pub instructions: BTreeMap<String, GasUsage>,
+    pub natives: BTreeMap<String, GasUsage>,
+}
+
+impl From<&GasProfiler> for GasReport {
+    /// The report of the single transaction recorded by the profiler
+    fn from(profiler: &GasProfiler) -> Self {
+        Self {
+            transactions: 1,
+            total: profiler.total(),
+            instructions: profiler.instructions().clone(),
+            natives: profiler.natives().clone(),
+        }
+    }
+}
+
+impl GasReport {
+    pub fn merge(&mut self, other: GasReport) {
+        self.transactions += other.transactions;
+        self.total += other.total;
+        for (usages, other_usages) in [
+            (&mut self.instructions, other.instructions),
+            (&mut self.natives, other.natives),
+        ] {
+            for (name, other_usage) in other_usages {
+                let usage = usages.entry(name).or_default();
+                usage.count += other_usage.count;
+                usage.gas += other_usage.gas;
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transactions == 0
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Gas reports must serialize to JSON")
+    }
+}
+
+impl fmt::Display for GasReport {
+    /// Lists the instructions then the native functions, most expensive first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} transactions, {} internal gas units",
+            self.transactions, self.total
+        )?;
+        for (title, usages) in [
+            ("Instructions", &self.instructions),
+            ("Native functions", &self.natives),
+        ] {
+            if usages.is_empty() {
+                continue;
+            }
+            write!(f, "\n{}:", title)?;
+            let mut usages: Vec<_> = usages.iter().collect();
+            usages.sort_by(|(a_name, a), (b_name, b)| b.gas.cmp(&a.gas).then(a_name.cmp(b_name)));
+            for (name, usage) in usages {
+                write!(
+                    f,
+                    "\n  {}: {} gas in {} charges ({:.2}%)",
+                    name,
+                    usage.gas,
+                    usage.count,
+                    usage.gas as f64 * 100.0 / self.total.max(1) as f64
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+
+    #[test]
+    fn test_gas_report() {
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("hash").unwrap());
+        let mut profiler = GasProfiler::new("script");
+        profiler.charge_instruction("ld_u64", 2.into());
+        profiler.charge_instruction("call", 10.into());
+        profiler.enter(&module_id, "sha3_256");
+        profiler.charge_native_function(8.into());
+        let mut report = GasReport::from(&profiler);
+
+        let mut profiler = GasProfiler::new("script");
+        profiler.charge_instruction("ld_u64", 2.into());
+        profiler.enter(&module_id, "sha3_256");
+        profiler.charge_native_function(8.into());
+        report.merge(GasReport::from(&profiler));
+
+        assert_eq!(report.transactions, 2);
+        assert_eq!(report.total, 30);
+        assert_eq!(
+            report.instructions.get("ld_u64"),
+            Some(&GasUsage { count: 2, gas: 4 })
+        );
+        assert_eq!(
+            report.natives.get("0x1::hash::sha3_256"),
+            Some(&GasUsage { count: 2, gas: 16 })
+        );
+        assert_eq!(
+            report.to_string(),
+            "2 transactions, 30 internal gas units\n\
+             Instructions:\n  \
+             call: 10 gas in 1 charges (33.33%)\n  \
+             ld_u64: 4 gas in 2 charges (13.33%)\n\
+             Native functions:\n  \
+             0x1::hash::sha3_256: 16 gas in 2 charges (53.33%)"
+        );
+    }
+}
@@ -1,10 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::gas_meter::{
-    AptosGasParameters, InitialGasSchedule, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
-};
-use anyhow::Result;
+use crate::gas_meter::{AptosGasParameters, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION};
+use anyhow::{anyhow, Result};
 use aptos_package_builder::PackageBuilder;
 use aptos_types::on_chain_config::GasScheduleV2;
 use clap::Parser;
@@ -13,10 +11,18 @@ use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
 use std::path::{Path, PathBuf};
 
 pub fn current_gas_schedule() -> GasScheduleV2 {
-    GasScheduleV2 {
-        feature_version: LATEST_GAS_FEATURE_VERSION,
-        entries: AptosGasParameters::initial().to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION),
-    }
+    gas_schedule_for_feature_version(LATEST_GAS_FEATURE_VERSION)
+        .expect("The latest gas feature version must have a gas schedule")
+}
+
+/// Returns the gas schedule set at genesis at the given feature version, if one was recorded at
+/// that version
+pub fn gas_schedule_for_feature_version(feature_version: u64) -> Option<GasScheduleV2> {
+    Some(GasScheduleV2 {
+        feature_version,
+        entries: AptosGasParameters::for_feature_version(feature_version)?
+            .to_on_chain_gas_schedule(feature_version),
+    })
 }
 
 fn generate_blob(writer: &CodeWriter, data: &[u8]) {
@@ -107,15 +113,24 @@ fn aptos_framework_path() -> PathBuf {
 pub struct GenArgs {
     #[clap(short, long)]
     pub output: Option<String>,
+    /// Feature version of the proposed gas schedule, the latest one by default. Only the feature
+    /// versions whose gas schedule was recorded are supported.
+    #[clap(long)]
+    pub feature_version: Option<u64>,
 }
 
 pub fn generate_update_proposal(args: &GenArgs) -> Result<()> {
+    let feature_version = args.feature_version.unwrap_or(LATEST_GAS_FEATURE_VERSION);
+    let gas_schedule = gas_schedule_for_feature_version(feature_version).ok_or_else(|| {
+        anyhow!(
+            "No gas schedule was recorded at gas feature version {}",
+            feature_version
+        )
+    })?;
+
     let mut pack = PackageBuilder::new("GasScheduleUpdate");
 
-    pack.add_source(
-        "update_gas_schedule.move",
-        &generate_script(&current_gas_schedule())?,
-    );
+    pack.add_source("update_gas_schedule.move", &generate_script(&gas_schedule)?);
     // TODO: use relative path here
     pack.add_local_dep("AptosFramework", &aptos_framework_path().to_string_lossy());
 
@@ -26,23 +26,33 @@ mod params;
 
 mod algebra;
 mod aptos_framework;
+#[cfg(feature = "calibration")]
+pub mod calibration;
+mod diff;
 mod gas_meter;
+mod gas_report;
 pub mod gen;
 mod instr;
 mod misc;
 mod move_stdlib;
+mod overrides;
+mod profiler;
 mod table;
 mod transaction;
 
 pub use algebra::*;
+pub use diff::{GasParameterChange, GasScheduleDiff};
 pub use gas_meter::{
     AptosGasMeter, AptosGasParameters, FromOnChainGasSchedule, InitialGasSchedule,
     NativeGasParameters, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
 };
+pub use gas_report::GasReport;
 pub use instr::InstructionGasParameters;
 pub use misc::{AbstractValueSizeGasParameters, MiscGasParameters};
 pub use move_core_types::gas_algebra::{
     Arg, Byte, GasQuantity, InternalGas, InternalGasPerArg, InternalGasPerByte, InternalGasUnit,
     NumArgs, NumBytes, UnitDiv,
 };
+pub use overrides::GasScheduleOverrides;
+pub use profiler::{GasProfile, GasProfiler, GasUsage};
 pub use transaction::{ChangeSetConfigs, StorageGasParameters, TransactionGasParameters};
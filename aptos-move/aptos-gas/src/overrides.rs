@@ -0,0 +1,87 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Overrides of individual gas parameters, so that local networks can experiment with pricing
+//! (e.g., zero-cost storage) without generating and voting on a full governance proposal.
+//!
+//! The overrides are keyed by the names of the parameters in the on-chain gas schedule, e.g.,
+//! `txn.write_data.per_byte_in_val` or `instr.ld_u64`. They can only replace parameters that
+//! exist in the gas schedule at its feature version, so that typos don't go unnoticed.
+
+use anyhow::{bail, Context, Result};
+use aptos_types::on_chain_config::GasScheduleV2;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct GasScheduleOverrides {
+    pub entries: BTreeMap<String, u64>,
+}
+
+impl GasScheduleOverrides {
+    /// Loads the overrides from a YAML file mapping parameter names to their values.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read gas overrides at {}", path.display()))?;
+        serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse gas overrides at {}", path.display()))
+    }
+
+    /// Replaces the overridden parameters of the gas schedule.
+    pub fn apply(&self, gas_schedule: &mut GasScheduleV2) -> Result<()> {
+        for (name, value) in &self.entries {
+            match gas_schedule
+                .entries
+                .iter_mut()
+                .find(|(entry_name, _)| entry_name == name)
+            {
+                Some((_, entry_value)) => *entry_value = *value,
+                None => bail!(
+                    "Gas parameter {} doesn't exist at gas feature version {}",
+                    name,
+                    gas_schedule.feature_version
+                ),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas_schedule() -> GasScheduleV2 {
+        GasScheduleV2 {
+            feature_version: 5,
+            entries: vec![
+                ("txn.min_transaction_gas_units".to_string(), 1_500_000),
+                ("txn.write_data.per_op".to_string(), 300),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let overrides: GasScheduleOverrides =
+            serde_yaml::from_str("txn.write_data.per_op: 0\n").unwrap();
+        let mut gas_schedule = gas_schedule();
+        overrides.apply(&mut gas_schedule).unwrap();
+        assert_eq!(
+            gas_schedule.entries,
+            vec![
+                ("txn.min_transaction_gas_units".to_string(), 1_500_000),
+                ("txn.write_data.per_op".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_unknown_override() {
+        let overrides: GasScheduleOverrides =
+            serde_yaml::from_str("txn.write_data.per_opp: 0\n").unwrap();
+        assert!(overrides.apply(&mut gas_schedule()).is_err());
+    }
+}
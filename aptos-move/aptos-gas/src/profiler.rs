@@ -0,0 +1,170 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module records where the gas charged by the gas meter goes, so that module authors can
+//! find the hot spots of a transaction before publishing.
+
+use move_core_types::{gas_algebra::InternalGas, language_storage::ModuleId};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Records the gas charged while executing a transaction, per instruction, per native function
+/// and per call stack.
+#[derive(Clone, Debug)]
+pub struct GasProfiler {
+    /// The functions currently being executed, the first one being the script or entry function
+    stack: Vec<String>,
+    /// Gas charged per call stack, with the instruction or native function charged last
+    folded_stacks: BTreeMap<String, InternalGas>,
+    instructions: BTreeMap<String, GasUsage>,
+    natives: BTreeMap<String, GasUsage>,
+}
+
+/// How many times an instruction or a native function was charged, and how much
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GasUsage {
+    pub count: u64,
+    /// In internal gas units
+    pub gas: u64,
+}
+
+impl GasUsage {
+    fn record(&mut self, amount: InternalGas) {
+        self.count += 1;
+        self.gas += u64::from(amount);
+    }
+}
+
+/// The gas charged per instruction and per native function, in internal gas units
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct GasProfile {
+    pub total: u64,
+    pub instructions: BTreeMap<String, u64>,
+    pub natives: BTreeMap<String, u64>,
+}
+
+impl GasProfiler {
+    /// Starts profiling the execution of the given script or entry function
+    pub fn new(root: impl Into<String>) -> Self {
+        Self {
+            stack: vec![root.into()],
+            folded_stacks: BTreeMap::new(),
+            instructions: BTreeMap::new(),
+            natives: BTreeMap::new(),
+        }
+    }
+
+    fn record_stack(&mut self, leaf: &str, amount: InternalGas) {
+        let mut stack = self.stack.join(";");
+        stack.push(';');
+        stack.push_str(leaf);
+        *self.folded_stacks.entry(stack).or_insert_with(|| 0.into()) += amount;
+    }
+
+    /// Records the gas charged by an instruction of the function on top of the stack
+    pub(crate) fn charge_instruction(&mut self, instr: &str, amount: InternalGas) {
+        self.record_stack(instr, amount);
+        self.instructions
+            .entry(instr.to_string())
+            .or_default()
+            .record(amount);
+    }
+
+    /// Records a function being called, until it returns
+    pub(crate) fn enter(&mut self, module_id: &ModuleId, func_name: &str) {
+        self.stack
+            .push(format!("{}::{}", module_id.short_str_lossless(), func_name));
+    }
+
+    /// Records the function on top of the stack returning. The root function is kept, as the
+    /// transaction may still be charged after it returns.
+    pub(crate) fn exit(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+        }
+    }
+
+    /// Records the gas charged by the native function on top of the stack, which returns right
+    /// after being charged. Natives have no frame of their own: the gas is charged to their
+    /// caller, with the native as the leaf of the stack.
+    pub(crate) fn charge_native_function(&mut self, amount: InternalGas) {
+        let native = self.stack.last().cloned().unwrap_or_default();
+        self.exit();
+        self.record_stack(&native, amount);
+        self.natives.entry(native).or_default().record(amount);
+    }
+
+    /// Returns the gas charged per call stack in the folded stack format of flamegraph tools,
+    /// i.e. one line per stack of `;` separated frames followed by the gas it was charged.
+    pub fn to_folded_stacks(&self) -> String {
+        self.folded_stacks
+            .iter()
+            .map(|(stack, amount)| format!("{} {}\n", stack, u64::from(*amount)))
+            .collect()
+    }
+
+    /// Total gas charged, in internal gas units
+    pub fn total(&self) -> u64 {
+        self.folded_stacks
+            .values()
+            .map(|amount| u64::from(*amount))
+            .sum()
+    }
+
+    /// How many times each instruction was charged, and how much
+    pub fn instructions(&self) -> &BTreeMap<String, GasUsage> {
+        &self.instructions
+    }
+
+    /// How many times each native function was charged, and how much
+    pub fn natives(&self) -> &BTreeMap<String, GasUsage> {
+        &self.natives
+    }
+
+    pub fn profile(&self) -> GasProfile {
+        let to_gas = |usages: &BTreeMap<String, GasUsage>| {
+            usages
+                .iter()
+                .map(|(name, usage)| (name.clone(), usage.gas))
+                .collect::<BTreeMap<_, _>>()
+        };
+        GasProfile {
+            total: self.total(),
+            instructions: to_gas(&self.instructions),
+            natives: to_gas(&self.natives),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    #[test]
+    fn test_gas_profile() {
+        let module_id = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+        let mut profiler = GasProfiler::new("script");
+        profiler.charge_instruction("call", 10.into());
+        profiler.enter(&module_id, "transfer");
+        profiler.charge_instruction("ld_u64", 2.into());
+        profiler.enter(&module_id, "hash");
+        profiler.charge_native_function(5.into());
+        profiler.charge_instruction("ret", 1.into());
+        profiler.exit();
+        profiler.charge_instruction("ld_u64", 2.into());
+
+        assert_eq!(
+            profiler.to_folded_stacks(),
+            "script;0x1::coin::transfer;0x1::coin::hash 5\n\
+             script;0x1::coin::transfer;ld_u64 2\n\
+             script;0x1::coin::transfer;ret 1\n\
+             script;call 10\n\
+             script;ld_u64 2\n"
+        );
+        let profile = profiler.profile();
+        assert_eq!(profile.total, 20);
+        assert_eq!(profile.instructions["ld_u64"], 4);
+        assert_eq!(profile.natives["0x1::coin::hash"], 5);
+    }
+}
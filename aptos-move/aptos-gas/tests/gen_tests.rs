@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_framework::{BuildOptions, BuiltPackage};
-use aptos_gas::gen::{generate_update_proposal, GenArgs};
+use aptos_gas::{
+    gen::{gas_schedule_for_feature_version, generate_update_proposal, GenArgs},
+    AptosGasParameters, FromOnChainGasSchedule, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
+};
 
 #[test]
 fn can_generate_and_build_update_proposal() {
@@ -10,8 +13,56 @@ fn can_generate_and_build_update_proposal() {
 
     generate_update_proposal(&GenArgs {
         output: Some(output_dir.path().to_string_lossy().to_string()),
+        feature_version: None,
     })
     .unwrap();
 
     BuiltPackage::build(output_dir.path().to_path_buf(), BuildOptions::default()).unwrap();
 }
+
+#[test]
+fn gas_schedules_of_all_feature_versions_round_trip() {
+    for feature_version in 0..=LATEST_GAS_FEATURE_VERSION {
+        let gas_schedule = match gas_schedule_for_feature_version(feature_version) {
+            Some(gas_schedule) => gas_schedule,
+            None => continue,
+        };
+        let gas_params = AptosGasParameters::from_on_chain_gas_schedule(
+            &gas_schedule.clone().to_btree_map(),
+            feature_version,
+        )
+        .unwrap();
+        assert_eq!(
+            gas_params.to_on_chain_gas_schedule(feature_version),
+            gas_schedule.entries
+        );
+    }
+
+    // Only the versions whose gas schedule was recorded have one
+    for feature_version in [1, 2, 4, 5, LATEST_GAS_FEATURE_VERSION + 1] {
+        assert!(AptosGasParameters::for_feature_version(feature_version).is_none());
+        assert!(gas_schedule_for_feature_version(feature_version).is_none());
+    }
+
+    // Parameters that don't exist yet at a feature version are zeros
+    let v3 = AptosGasParameters::for_feature_version(3).unwrap();
+    assert_eq!(u64::from(v3.instr.ld_u16), 0);
+    let latest = AptosGasParameters::for_feature_version(LATEST_GAS_FEATURE_VERSION).unwrap();
+    assert_ne!(u64::from(latest.instr.ld_u16), 0);
+    assert!(!gas_schedule_for_feature_version(3)
+        .unwrap()
+        .entries
+        .iter()
+        .any(|(name, _)| name == "instr.ld_u16"));
+
+    // The values are the ones set at genesis at each version
+    let v0 = AptosGasParameters::for_feature_version(0).unwrap();
+    assert_eq!(u64::from(v0.instr.nop), 1);
+    assert_eq!(u64::from(v0.txn.gas_unit_scaling_factor), 1_000);
+    assert_eq!(u64::from(v0.misc.abs_val.u64), 8);
+    assert_eq!(u64::from(v3.instr.nop), 10);
+    assert_eq!(u64::from(v3.instr.branch), 20);
+    assert_eq!(u64::from(v3.txn.maximum_number_of_gas_units), 10_000_000);
+    assert_eq!(u64::from(v3.natives.move_stdlib.hash.sha3_256.base), 4_000);
+    assert_eq!(u64::from(latest.instr.nop), 200);
+}
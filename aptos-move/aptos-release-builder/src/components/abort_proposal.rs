@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::*;
+use anyhow::Result;
+use move_model::{code_writer::CodeWriter, emitln, model::Loc};
+
+/// Generates a proposal aborting the multi-step proposal `proposal_id`, which failed mid-chain.
+/// Once executed, none of the remaining steps of the aborted proposal can be executed, and the
+/// hash of its next step is no longer approved.
+pub fn generate_abort_proposal(
+    proposal_id: u64,
+    is_testnet: bool,
+    next_execution_hash: String,
+) -> Result<Vec<(String, String)>> {
+    let mut result = vec![];
+
+    let writer = CodeWriter::new(Loc::default());
+
+    emitln!(writer, "// Abort of multi-step proposal {}\n", proposal_id);
+
+    let proposal = generate_governance_proposal(
+        &writer,
+        is_testnet,
+        &next_execution_hash,
+        "aptos_framework::aptos_governance",
+        |writer| {
+            emitln!(
+                writer,
+                "aptos_governance::abort_multi_step_proposal(framework_signer, {});",
+                proposal_id
+            );
+        },
+    );
+
+    result.push(("abort-proposal".to_string(), proposal));
+    Ok(result)
+}
@@ -1,25 +1,38 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::utils::*;
-use anyhow::Result;
+use crate::{components::framework, network_config::Network, utils::*};
+use anyhow::{bail, Result};
+use aptos_crypto::HashValue;
 use aptos_types::on_chain_config::{FeatureFlag as AptosFeatureFlag, Features as AptosFeatures};
 use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct Features {
     pub enabled: Vec<FeatureFlag>,
     pub disabled: Vec<FeatureFlag>,
+    // Features that are being staged: enabled on devnet and testnet and disabled on mainnet, so
+    // the same release config can be used to generate the scripts for all networks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub testnet_only: Vec<FeatureFlag>,
 }
 
-#[derive(Clone, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[allow(non_camel_case_types)]
 #[serde(rename_all = "snake_case")]
 pub enum FeatureFlag {
     CodeDependencyCheck,
     TreatFriendAsPrivate,
+    Sha512AndRipeMd160Natives,
+    AptosStdChainIdNatives,
     VMBinaryFormatV6,
+    CollectAndDistributeGasFees,
+    MultiEd25519PkValidateV2Natives,
+    Blake2b256Native,
+    ExperimentalNatives,
+    TransactionDenyList,
+    BlockInfo,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -88,12 +101,56 @@ pub fn generate_feature_upgrade_proposal(
     Ok(result)
 }
 
+/// Generates the framework upgrade proposals followed by the proposal enabling `features`, so
+/// that framework modules relying on natives or bytecode gated behind the features are published
+/// before the features are enabled. As with the other components, the proposals are returned in
+/// reverse order when generating a multi-step proposal.
+pub fn generate_feature_activation_proposals(
+    features: &Features,
+    is_testnet: bool,
+    next_execution_hash: String,
+) -> Result<Vec<(String, String)>> {
+    if next_execution_hash.is_empty() {
+        let mut result = framework::generate_upgrade_proposals(is_testnet, "".to_owned(), &[])?;
+        result.append(&mut generate_feature_upgrade_proposal(
+            features,
+            is_testnet,
+            "".to_owned(),
+        )?);
+        Ok(result)
+    } else {
+        let mut result =
+            generate_feature_upgrade_proposal(features, is_testnet, next_execution_hash)?;
+        let features_hash = HashValue::sha3_256_of(result.last().unwrap().1.as_bytes());
+        result.append(&mut framework::generate_upgrade_proposals(
+            is_testnet,
+            features_hash.to_string(),
+            &[],
+        )?);
+        Ok(result)
+    }
+}
+
 impl From<FeatureFlag> for AptosFeatureFlag {
     fn from(f: FeatureFlag) -> Self {
         match f {
             FeatureFlag::CodeDependencyCheck => AptosFeatureFlag::CODE_DEPENDENCY_CHECK,
             FeatureFlag::TreatFriendAsPrivate => AptosFeatureFlag::TREAT_FRIEND_AS_PRIVATE,
+            FeatureFlag::Sha512AndRipeMd160Natives => {
+                AptosFeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES
+            }
+            FeatureFlag::AptosStdChainIdNatives => AptosFeatureFlag::APTOS_STD_CHAIN_ID_NATIVES,
             FeatureFlag::VMBinaryFormatV6 => AptosFeatureFlag::VM_BINARY_FORMAT_V6,
+            FeatureFlag::CollectAndDistributeGasFees => {
+                AptosFeatureFlag::COLLECT_AND_DISTRIBUTE_GAS_FEES
+            }
+            FeatureFlag::MultiEd25519PkValidateV2Natives => {
+                AptosFeatureFlag::MULTI_ED25519_PK_VALIDATE_V2_NATIVES
+            }
+            FeatureFlag::Blake2b256Native => AptosFeatureFlag::BLAKE2B_256_NATIVE,
+            FeatureFlag::ExperimentalNatives => AptosFeatureFlag::EXPERIMENTAL_NATIVES,
+            FeatureFlag::TransactionDenyList => AptosFeatureFlag::TRANSACTION_DENY_LIST,
+            FeatureFlag::BlockInfo => AptosFeatureFlag::BLOCK_INFO,
         }
     }
 }
@@ -104,12 +161,56 @@ impl From<AptosFeatureFlag> for FeatureFlag {
         match f {
             AptosFeatureFlag::CODE_DEPENDENCY_CHECK => FeatureFlag::CodeDependencyCheck,
             AptosFeatureFlag::TREAT_FRIEND_AS_PRIVATE => FeatureFlag::TreatFriendAsPrivate,
+            AptosFeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES => {
+                FeatureFlag::Sha512AndRipeMd160Natives
+            }
+            AptosFeatureFlag::APTOS_STD_CHAIN_ID_NATIVES => FeatureFlag::AptosStdChainIdNatives,
             AptosFeatureFlag::VM_BINARY_FORMAT_V6 => FeatureFlag::VMBinaryFormatV6,
+            AptosFeatureFlag::COLLECT_AND_DISTRIBUTE_GAS_FEES => {
+                FeatureFlag::CollectAndDistributeGasFees
+            }
+            AptosFeatureFlag::MULTI_ED25519_PK_VALIDATE_V2_NATIVES => {
+                FeatureFlag::MultiEd25519PkValidateV2Natives
+            }
+            AptosFeatureFlag::BLAKE2B_256_NATIVE => FeatureFlag::Blake2b256Native,
+            AptosFeatureFlag::EXPERIMENTAL_NATIVES => FeatureFlag::ExperimentalNatives,
+            AptosFeatureFlag::TRANSACTION_DENY_LIST => FeatureFlag::TransactionDenyList,
+            AptosFeatureFlag::BLOCK_INFO => FeatureFlag::BlockInfo,
         }
     }
 }
 
 impl Features {
+    // Resolve the feature flags to be set on the given network, with the testnet only features
+    // enabled on devnet and testnet and disabled on mainnet.
+    pub fn for_network(&self, network: Network) -> Result<Features> {
+        for flag in &self.enabled {
+            if self.disabled.contains(flag) {
+                bail!("Feature {:?} is both enabled and disabled", flag);
+            }
+        }
+        for flag in &self.testnet_only {
+            if self.enabled.contains(flag) || self.disabled.contains(flag) {
+                bail!(
+                    "Feature {:?} is testnet only and can't also be listed as enabled or disabled",
+                    flag
+                );
+            }
+        }
+
+        let mut features = Features {
+            enabled: self.enabled.clone(),
+            disabled: self.disabled.clone(),
+            testnet_only: vec![],
+        };
+        if network != Network::Mainnet {
+            features.enabled.extend(self.testnet_only.iter().cloned());
+        } else {
+            features.disabled.extend(self.testnet_only.iter().cloned());
+        }
+        Ok(features)
+    }
+
     // Compare if the current feature set is different from features that has been enabled on chain.
     pub(crate) fn has_modified(&self, on_chain_features: &AptosFeatures) -> bool {
         self.enabled
@@ -1,21 +1,134 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
-use aptos_crypto::HashValue;
+use crate::utils::script_execution_hash;
+use anyhow::{anyhow, bail, Result};
 use aptos_temppath::TempPath;
-use std::process::Command;
+use serde::Deserialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The framework packages, along with the account they're published at
+const FRAMEWORK_PACKAGES: [(&str, &str); 4] = [
+    ("0x1", "move-stdlib"),
+    ("0x1", "aptos-stdlib"),
+    ("0x1", "aptos-framework"),
+    ("0x3", "aptos-token"),
+];
+
+/// The part of the `Move.toml` of a package the release relies on
+#[derive(Deserialize)]
+struct PackageManifest {
+    package: PackageInfo,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct PackageInfo {
+    name: String,
+}
+
+fn framework_path() -> PathBuf {
+    let mut framework_path = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf();
+    framework_path.pop();
+    framework_path.push("framework");
+    framework_path
+}
+
+fn load_manifest(framework_dir: &Path, package: &str) -> Result<PackageManifest> {
+    let manifest_path = framework_dir.join(package).join("Move.toml");
+    let contents = std::fs::read_to_string(&manifest_path)
+        .map_err(|err| anyhow!("Failed to read {}: {:?}", manifest_path.display(), err))?;
+    toml::from_str(&contents)
+        .map_err(|err| anyhow!("Failed to parse {}: {:?}", manifest_path.display(), err))
+}
+
+/// Returns the framework packages to publish, with their account, such that each package comes
+/// after the packages it depends on. `packages` are the names of the package directories, e.g.
+/// `aptos-token`, all the packages being published if it's empty. Dependencies left out of the
+/// release are expected to be on chain already.
+pub fn release_packages(packages: &[String]) -> Result<Vec<(&'static str, &'static str)>> {
+    release_packages_in(framework_path().as_path(), packages)
+}
+
+fn release_packages_in(
+    framework_dir: &Path,
+    packages: &[String],
+) -> Result<Vec<(&'static str, &'static str)>> {
+    for package in packages {
+        if !FRAMEWORK_PACKAGES.iter().any(|(_, name)| name == package) {
+            bail!("Unknown framework package {}", package);
+        }
+    }
+    let mut remaining: Vec<_> = FRAMEWORK_PACKAGES
+        .iter()
+        .filter(|(_, name)| packages.is_empty() || packages.iter().any(|package| package == name))
+        .map(|(address, name)| Ok((*address, *name, load_manifest(framework_dir, name)?)))
+        .collect::<Result<_>>()?;
+
+    // Publish the packages whose dependencies in the release are all published, in turn
+    let mut ordered = vec![];
+    let mut published = BTreeSet::new();
+    while !remaining.is_empty() {
+        let in_release: BTreeSet<_> = remaining
+            .iter()
+            .map(|(_, _, manifest)| manifest.package.name.clone())
+            .collect();
+        let next = remaining
+            .iter()
+            .position(|(_, _, manifest)| {
+                manifest.dependencies.keys().all(|dependency| {
+                    published.contains(dependency) || !in_release.contains(dependency)
+                })
+            })
+            .ok_or_else(|| anyhow!("Cyclic dependency between the framework packages"))?;
+        let (address, name, manifest) = remaining.remove(next);
+        published.insert(manifest.package.name);
+        ordered.push((address, name));
+    }
+    Ok(ordered)
+}
+
+/// Returns the names of the framework packages published by the release, as declared in their
+/// `Move.toml`, e.g. `AptosToken`.
+pub fn release_package_names(packages: &[String]) -> Result<Vec<String>> {
+    release_packages(packages)?
+        .into_iter()
+        .map(|(_, name)| {
+            Ok(load_manifest(framework_path().as_path(), name)?
+                .package
+                .name)
+        })
+        .collect()
+}
 
 pub fn generate_upgrade_proposals(
     is_testnet: bool,
     next_execution_hash: String,
+    packages: &[String],
+) -> Result<Vec<(String, String)>> {
+    generate_upgrade_proposals_from(
+        framework_path().as_path(),
+        is_testnet,
+        next_execution_hash,
+        packages,
+    )
+}
+
+/// Same as `generate_upgrade_proposals`, but publishing the packages found in `framework_dir`
+/// rather than the framework of this build, e.g. a checkout of the framework of a previous
+/// release.
+pub fn generate_upgrade_proposals_from(
+    framework_dir: &Path,
+    is_testnet: bool,
+    next_execution_hash: String,
+    packages: &[String],
 ) -> Result<Vec<(String, String)>> {
-    let mut package_path_list = vec![
-        ("0x1", "aptos-move/framework/move-stdlib"),
-        ("0x1", "aptos-move/framework/aptos-stdlib"),
-        ("0x1", "aptos-move/framework/aptos-framework"),
-        ("0x3", "aptos-move/framework/aptos-token"),
-    ];
+    let mut package_path_list = release_packages_in(framework_dir, packages)?;
 
     let mut result: Vec<(String, String)> = vec![];
 
@@ -30,14 +143,13 @@ pub fn generate_upgrade_proposals(
         package_path_list.reverse();
     }
 
-    for (publish_addr, relative_package_path) in package_path_list.iter() {
+    for (publish_addr, package_name) in package_path_list.iter() {
         let temp_script_path = TempPath::new();
         temp_script_path.create_as_file()?;
         let mut move_script_path = temp_script_path.path().to_path_buf();
         move_script_path.set_extension("move");
 
-        let mut package_path = root_path.clone();
-        package_path.push(relative_package_path);
+        let package_path = framework_dir.join(package_name);
 
         let script_name = package_path
             .file_name()
@@ -80,8 +192,7 @@ pub fn generate_upgrade_proposals(
             if result.is_empty() {
                 args.push(&next_execution_hash);
             } else {
-                _execution_hash =
-                    HashValue::sha3_256_of(result.last().unwrap().1.as_bytes()).to_string();
+                _execution_hash = script_execution_hash(&result.last().unwrap().1)?.to_hex();
                 args.push(&_execution_hash);
             }
         }
@@ -100,3 +211,22 @@ pub fn generate_upgrade_proposals(
     }
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_release_packages_order() {
+        assert_eq!(release_packages(&[]).unwrap(), FRAMEWORK_PACKAGES.to_vec());
+        assert_eq!(
+            release_packages(&["aptos-token".to_string(), "move-stdlib".to_string()]).unwrap(),
+            vec![("0x1", "move-stdlib"), ("0x3", "aptos-token")]
+        );
+        assert_eq!(
+            release_package_names(&["aptos-token".to_string()]).unwrap(),
+            vec!["AptosToken".to_string()]
+        );
+        assert!(release_packages(&["aptos-tokens".to_string()]).is_err());
+    }
+}
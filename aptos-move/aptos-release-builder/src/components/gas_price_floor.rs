@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::*;
+use anyhow::Result;
+use move_model::{code_writer::CodeWriter, emitln, model::Loc};
+use serde::{Deserialize, Serialize};
+
+/// A change of the gas unit price floor, taking effect at the start of `activation_epoch`. The
+/// proposal starts a new epoch, so `activation_epoch` must be at least two epochs after the one
+/// the proposal is executed in.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct GasPriceFloorConfig {
+    pub min_gas_unit_price: u64,
+    pub activation_epoch: u64,
+}
+
+pub fn generate_gas_price_floor_proposal(
+    config: &GasPriceFloorConfig,
+    is_testnet: bool,
+    next_execution_hash: String,
+) -> Result<Vec<(String, String)>> {
+    let mut result = vec![];
+
+    let writer = CodeWriter::new(Loc::default());
+
+    emitln!(writer, "// Gas price floor update proposal\n");
+
+    let proposal = generate_governance_proposal(
+        &writer,
+        is_testnet,
+        &next_execution_hash,
+        "aptos_framework::gas_price_floor",
+        |writer| {
+            emitln!(
+                writer,
+                "gas_price_floor::set(framework_signer, {}, {});",
+                config.min_gas_unit_price,
+                config.activation_epoch
+            );
+        },
+    );
+
+    result.push(("gas-price-floor".to_string(), proposal));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_price_floor_proposal() {
+        let config = GasPriceFloorConfig {
+            min_gas_unit_price: 150,
+            activation_epoch: 10,
+        };
+        for is_testnet in [true, false] {
+            let proposals =
+                generate_gas_price_floor_proposal(&config, is_testnet, "".to_owned()).unwrap();
+            assert_eq!(proposals.len(), 1);
+            let (name, script) = &proposals[0];
+            assert_eq!(name, "gas-price-floor");
+            assert!(script.contains("use aptos_framework::gas_price_floor;"));
+            assert!(script.contains("gas_price_floor::set(framework_signer, 150, 10);"));
+            // the proposal compiles against the framework of this build
+            script_execution_hash(script).unwrap();
+        }
+    }
+}
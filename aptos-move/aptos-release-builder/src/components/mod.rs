@@ -1,13 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::components::feature_flags::Features;
+use crate::{
+    components::{
+        feature_flags::Features, gas_price_floor::GasPriceFloorConfig, rollback::RollbackConfig,
+    },
+    metadata::{ProposalMetadata, ProposalMetadataConfig, METADATA_EXTENSION},
+    network_config::Network,
+    upgrade_readiness::UpgradeReadinessCheck,
+    utils::script_execution_hash,
+    verification::{ExecutionManifest, ExpectedState, MANIFEST_EXTENSION},
+};
 use anyhow::{anyhow, Result};
-use aptos_crypto::HashValue;
 use aptos_rest_client::Client;
 use aptos_types::{
     account_config::CORE_CODE_ADDRESS,
-    on_chain_config::{GasScheduleV2, OnChainConfig, OnChainConsensusConfig, Version},
+    on_chain_config::{
+        GasPriceFloor, GasScheduleV2, OnChainConfig, OnChainConsensusConfig, TransactionDenyList,
+        Version,
+    },
 };
 use futures::executor::block_on;
 use serde::{Deserialize, Serialize};
@@ -18,17 +29,29 @@ use std::{
 };
 use url::Url;
 
+pub mod abort_proposal;
 pub mod consensus_config;
 pub mod feature_flags;
 pub mod framework;
 pub mod gas;
+pub mod gas_price_floor;
+pub mod rollback;
+pub mod transaction_deny_list;
 pub mod version;
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct ReleaseConfig {
     pub testnet: bool,
+    /// The network the release is generated for, which decides whether the testnet only
+    /// features are enabled. If unset, they're enabled if `testnet` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<Network>,
     pub remote_endpoint: Option<Url>,
     pub framework_release: bool,
+    /// The framework packages published by the release, by directory name (e.g. `aptos-token`),
+    /// so that a hotfix doesn't republish the whole framework. All of them if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub framework_packages: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gas_schedule: Option<GasScheduleV2>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -37,8 +60,48 @@ pub struct ReleaseConfig {
     pub feature_flags: Option<Features>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub consensus_config: Option<OnChainConsensusConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_deny_list: Option<TransactionDenyList>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price_floor: Option<GasPriceFloorConfig>,
     #[serde(default)]
     pub is_multi_step: bool,
+    /// A multi-step proposal that failed mid-chain and is superseded by this release. It's
+    /// aborted by the first script of the release, before any other change is applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_proposal_id: Option<u64>,
+    /// Checked before generating the scripts enabling feature flags, so that features are only
+    /// enabled once enough of the stake runs builds supporting them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_readiness_check: Option<UpgradeReadinessCheck>,
+    /// If set, the metadata of each proposal is generated next to its script, to be uploaded
+    /// to the metadata URL of the proposal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proposal_metadata: Option<ProposalMetadataConfig>,
+    /// If set, the scripts reverting the release are generated along with the release scripts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback: Option<RollbackConfig>,
+}
+
+// Fetch the config currently recorded on chain.
+fn fetch_config<T: OnChainConfig>(client: &Client) -> Result<T> {
+    T::deserialize_into_config(
+        block_on(async {
+            client
+                .get_account_resource_bytes(
+                    CORE_CODE_ADDRESS,
+                    format!(
+                        "{}::{}::{}",
+                        T::ADDRESS,
+                        T::MODULE_IDENTIFIER,
+                        T::TYPE_IDENTIFIER
+                    )
+                    .as_str(),
+                )
+                .await
+        })?
+        .inner(),
+    )
 }
 
 // Compare the current on chain config with the value recorded on chain. Return false if there's a difference.
@@ -47,27 +110,7 @@ fn fetch_and_equals<T: OnChainConfig + PartialEq>(
     expected: &T,
 ) -> Result<bool> {
     match client {
-        Some(client) => {
-            let config = T::deserialize_into_config(
-                block_on(async {
-                    client
-                        .get_account_resource_bytes(
-                            CORE_CODE_ADDRESS,
-                            format!(
-                                "{}::{}::{}",
-                                T::ADDRESS,
-                                T::MODULE_IDENTIFIER,
-                                T::TYPE_IDENTIFIER
-                            )
-                            .as_str(),
-                        )
-                        .await
-                })?
-                .inner(),
-            )?;
-
-            Ok(&config == expected)
-        }
+        Some(client) => Ok(&fetch_config::<T>(client)? == expected),
         None => Ok(false),
     }
 }
@@ -78,11 +121,14 @@ impl ReleaseConfig {
         let mut release_generation_functions: Vec<
             &dyn Fn(&Self, &Option<Client>, &mut Vec<(String, String)>) -> Result<()>,
         > = vec![
+            &Self::generate_abort_proposal_file,
             &Self::generate_framework_release,
             &Self::generate_gas_schedule,
             &Self::generate_version_file,
             &Self::generate_feature_flag_file,
             &Self::generate_consensus_file,
+            &Self::generate_transaction_deny_list_file,
+            &Self::generate_gas_price_floor_file,
         ];
         let client = self
             .remote_endpoint
@@ -104,6 +150,47 @@ impl ReleaseConfig {
             result.reverse();
         }
 
+        self.write_proposal_scripts(result, base_path)?;
+
+        // The rollback is generated last, so the scripts of the release are kept even if the
+        // previous state can't be snapshotted
+        if let Some(rollback) = &self.rollback {
+            self.generate_rollback_proposal_scripts(rollback, &client, base_path)?;
+        }
+        Ok(())
+    }
+
+    /// Generates the scripts that upgrade the framework and then enable the features of this
+    /// release, for framework modules that rely on natives or bytecode gated behind them. Unlike
+    /// `generate_release_proposal_scripts`, the features are enabled even if no remote endpoint
+    /// is set, and always after the framework packages are published.
+    pub fn generate_feature_activation_proposal_scripts(&self, base_path: &Path) -> Result<()> {
+        let feature_flags = self
+            .feature_flags
+            .as_ref()
+            .ok_or_else(|| anyhow!("The release doesn't enable any feature"))?
+            .for_network(self.network())?;
+        self.check_upgrade_readiness()?;
+        let mut result = feature_flags::generate_feature_activation_proposals(
+            &feature_flags,
+            self.testnet,
+            if self.is_multi_step {
+                "vector::empty<u8>()".to_owned()
+            } else {
+                "".to_owned()
+            },
+        )?;
+        if self.is_multi_step {
+            result.reverse();
+        }
+        self.write_proposal_scripts(result, base_path)
+    }
+
+    fn write_proposal_scripts(
+        &self,
+        result: Vec<(String, String)>,
+        base_path: &Path,
+    ) -> Result<()> {
         for (idx, (script_name, script)) in result.into_iter().enumerate() {
             let mut script_path = base_path.to_path_buf();
             let proposal_name = format!("{}-{}", idx, script_name);
@@ -112,8 +199,133 @@ impl ReleaseConfig {
 
             std::fs::write(script_path.as_path(), script.as_bytes())
                 .map_err(|err| anyhow!("Failed to write to file: {:?}", err))?;
+
+            // The state expected after the script is executed, to be checked with
+            // `aptos governance verify-execution`
+            let manifest = ExecutionManifest {
+                expected: self.expected_states(&script_name)?,
+                proposal: proposal_name,
+            };
+            script_path.set_extension(MANIFEST_EXTENSION);
+            manifest.save(script_path.as_path())?;
+
+            if let Some(metadata_config) = &self.proposal_metadata {
+                script_path.set_extension("move");
+                let metadata =
+                    ProposalMetadata::generate(metadata_config, &proposal_name, &script_path)?;
+                script_path.set_extension(METADATA_EXTENSION);
+                metadata.save(script_path.as_path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The on chain state expected after executing the script with the given name. Framework
+    /// upgrades have no expectations, as the packages are checked when they're published.
+    pub(crate) fn expected_states(&self, script_name: &str) -> Result<Vec<ExpectedState>> {
+        let expected = match script_name {
+            "gas-schedule" => self
+                .gas_schedule
+                .iter()
+                .map(|gas_schedule| ExpectedState::GasSchedule(gas_schedule.clone()))
+                .collect(),
+            "version" => self
+                .version
+                .iter()
+                .map(|version| ExpectedState::Version(version.clone()))
+                .collect(),
+            "features" => match &self.feature_flags {
+                Some(feature_flags) => {
+                    let feature_flags = feature_flags.for_network(self.network())?;
+                    let enabled = feature_flags.enabled.into_iter().map(|flag| (flag, true));
+                    let disabled = feature_flags.disabled.into_iter().map(|flag| (flag, false));
+                    enabled
+                        .chain(disabled)
+                        .map(|(flag, enabled)| ExpectedState::FeatureFlag { flag, enabled })
+                        .collect()
+                }
+                None => vec![],
+            },
+            "consensus-config" => self
+                .consensus_config
+                .iter()
+                .map(|config| ExpectedState::ConsensusConfig(config.clone()))
+                .collect(),
+            "transaction-deny-list" => self
+                .transaction_deny_list
+                .iter()
+                .map(|deny_list| ExpectedState::TransactionDenyList(deny_list.clone()))
+                .collect(),
+            "gas-price-floor" => self
+                .gas_price_floor
+                .iter()
+                .map(|floor| ExpectedState::GasPriceFloor(floor.clone()))
+                .collect(),
+            "abort-proposal" => self
+                .superseded_proposal_id
+                .iter()
+                .map(|proposal_id| ExpectedState::ProposalAborted {
+                    proposal_id: *proposal_id,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        Ok(expected)
+    }
+
+    /// The network the release is generated for, see `network`
+    pub fn network(&self) -> Network {
+        match self.network {
+            Some(network) => network,
+            None if self.testnet => Network::Devnet,
+            None => Network::Mainnet,
         }
+    }
+
+    /// The release config of each network. Only devnet scripts are executed by the core
+    /// resources account; testnet gets governance proposals like mainnet, but with the staged
+    /// features enabled.
+    pub fn for_each_network(&self) -> Vec<ReleaseConfig> {
+        Network::ALL
+            .iter()
+            .map(|network| Self {
+                testnet: network.has_core_resources(),
+                network: Some(*network),
+                ..self.clone()
+            })
+            .collect()
+    }
 
+    /// Generates the scripts of this release for each network, under the `devnet`, `testnet`
+    /// and `mainnet` subdirectories of `base_path`. Staged features are enabled in the devnet
+    /// and testnet scripts only.
+    pub fn generate_release_proposal_scripts_for_networks(&self, base_path: &Path) -> Result<()> {
+        for config in self.for_each_network() {
+            let network_path = base_path.join(config.network().as_str());
+            std::fs::create_dir_all(network_path.as_path())
+                .map_err(|err| anyhow!("Failed to create directory: {:?}", err))?;
+            config.generate_release_proposal_scripts(network_path.as_path())?;
+        }
+        Ok(())
+    }
+
+    fn generate_abort_proposal_file(
+        &self,
+        _client: &Option<Client>,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let Some(proposal_id) = self.superseded_proposal_id {
+            result.append(&mut abort_proposal::generate_abort_proposal(
+                proposal_id,
+                self.testnet,
+                if self.is_multi_step {
+                    Self::get_execution_hash(result)?
+                } else {
+                    "".to_owned()
+                },
+            )?);
+        }
         Ok(())
     }
 
@@ -126,10 +338,11 @@ impl ReleaseConfig {
             result.append(&mut framework::generate_upgrade_proposals(
                 self.testnet,
                 if self.is_multi_step {
-                    Self::get_execution_hash(result)
+                    Self::get_execution_hash(result)?
                 } else {
                     "".to_owned()
                 },
+                &self.framework_packages,
             )?);
         }
         Ok(())
@@ -146,7 +359,7 @@ impl ReleaseConfig {
                     gas_schedule,
                     self.testnet,
                     if self.is_multi_step {
-                        Self::get_execution_hash(result)
+                        Self::get_execution_hash(result)?
                     } else {
                         "".to_owned()
                     },
@@ -167,7 +380,7 @@ impl ReleaseConfig {
                     version,
                     self.testnet,
                     if self.is_multi_step {
-                        Self::get_execution_hash(result)
+                        Self::get_execution_hash(result)?
                     } else {
                         "".to_owned()
                     },
@@ -183,6 +396,7 @@ impl ReleaseConfig {
         result: &mut Vec<(String, String)>,
     ) -> Result<()> {
         if let Some(feature_flags) = &self.feature_flags {
+            let feature_flags = &feature_flags.for_network(self.network())?;
             let mut needs_update = false;
             if let Some(client) = client {
                 let features = block_on(async {
@@ -197,11 +411,12 @@ impl ReleaseConfig {
                 needs_update = feature_flags.has_modified(features.inner());
             }
             if needs_update {
+                self.check_upgrade_readiness()?;
                 result.append(&mut feature_flags::generate_feature_upgrade_proposal(
                     feature_flags,
                     self.testnet,
                     if self.is_multi_step {
-                        Self::get_execution_hash(result)
+                        Self::get_execution_hash(result)?
                     } else {
                         "".to_owned()
                     },
@@ -211,18 +426,106 @@ impl ReleaseConfig {
         Ok(())
     }
 
+    fn check_upgrade_readiness(&self) -> Result<()> {
+        match &self.upgrade_readiness_check {
+            Some(check) => check.check(),
+            None => Ok(()),
+        }
+    }
+
     fn generate_consensus_file(
         &self,
         client: &Option<Client>,
         result: &mut Vec<(String, String)>,
     ) -> Result<()> {
         if let Some(consensus_config) = &self.consensus_config {
-            if fetch_and_equals(client, consensus_config)? {
+            if !fetch_and_equals(client, consensus_config)? {
                 result.append(&mut consensus_config::generate_consensus_upgrade_proposal(
                     consensus_config,
                     self.testnet,
                     if self.is_multi_step {
-                        Self::get_execution_hash(result)
+                        Self::get_execution_hash(result)?
+                    } else {
+                        "".to_owned()
+                    },
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_transaction_deny_list_file(
+        &self,
+        client: &Option<Client>,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let Some(deny_list) = &self.transaction_deny_list {
+            let needs_update = match client {
+                Some(client) => {
+                    // The deny list is only published once governance first sets it
+                    let on_chain = block_on(async {
+                        client
+                            .get_account_resource_bcs::<TransactionDenyList>(
+                                CORE_CODE_ADDRESS,
+                                "0x1::transaction_deny_list::TransactionDenyList",
+                            )
+                            .await
+                    })
+                    .map(|response| response.into_inner())
+                    .unwrap_or_default();
+                    &on_chain != deny_list
+                }
+                None => true,
+            };
+            if needs_update {
+                result.append(
+                    &mut transaction_deny_list::generate_transaction_deny_list_proposal(
+                        deny_list,
+                        self.testnet,
+                        if self.is_multi_step {
+                            Self::get_execution_hash(result)?
+                        } else {
+                            "".to_owned()
+                        },
+                    )?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_gas_price_floor_file(
+        &self,
+        client: &Option<Client>,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let Some(gas_price_floor) = &self.gas_price_floor {
+            let needs_update = match client {
+                Some(client) => {
+                    // The floor is only published once governance first schedules it
+                    let on_chain = block_on(async {
+                        client
+                            .get_account_resource_bcs::<GasPriceFloor>(
+                                CORE_CODE_ADDRESS,
+                                "0x1::gas_price_floor::GasPriceFloor",
+                            )
+                            .await
+                    })
+                    .map(|response| response.into_inner())
+                    .ok();
+                    on_chain.map_or(true, |on_chain| {
+                        on_chain.next_min_gas_unit_price != gas_price_floor.min_gas_unit_price
+                            || on_chain.activation_epoch != gas_price_floor.activation_epoch
+                    })
+                }
+                None => true,
+            };
+            if needs_update {
+                result.append(&mut gas_price_floor::generate_gas_price_floor_proposal(
+                    gas_price_floor,
+                    self.testnet,
+                    if self.is_multi_step {
+                        Self::get_execution_hash(result)?
                     } else {
                         "".to_owned()
                     },
@@ -269,11 +572,12 @@ impl ReleaseConfig {
         serde_yaml::from_str(serialized).map_err(|e| anyhow!("Failed to parse the config: {:?}", e))
     }
 
-    fn get_execution_hash(result: &Vec<(String, String)>) -> String {
-        if result.is_empty() {
-            "vector::empty<u8>()".to_owned()
-        } else {
-            HashValue::sha3_256_of(result.last().unwrap().1.to_owned().as_bytes()).to_string()
+    /// The hash the script generated before the given ones resolves a multi-step proposal with:
+    /// the hash of the bytecode of the last generated script, i.e. the next step to execute.
+    fn get_execution_hash(result: &Vec<(String, String)>) -> Result<String> {
+        match result.last() {
+            Some((_, script)) => Ok(script_execution_hash(script)?.to_hex()),
+            None => Ok("vector::empty<u8>()".to_owned()),
         }
     }
 }
@@ -282,13 +586,21 @@ impl Default for ReleaseConfig {
     fn default() -> Self {
         ReleaseConfig {
             testnet: true,
+            network: None,
             framework_release: true,
+            framework_packages: vec![],
             gas_schedule: Some(aptos_gas::gen::current_gas_schedule()),
             version: None,
             feature_flags: None,
             consensus_config: Some(OnChainConsensusConfig::default()),
+            transaction_deny_list: None,
+            gas_price_floor: None,
             is_multi_step: false,
+            superseded_proposal_id: None,
             remote_endpoint: None,
+            upgrade_readiness_check: None,
+            proposal_metadata: None,
+            rollback: None,
         }
     }
 }
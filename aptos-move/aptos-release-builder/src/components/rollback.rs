@@ -0,0 +1,418 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::components::{
+    consensus_config, feature_flags, feature_flags::Features, fetch_config, framework, gas,
+    transaction_deny_list, ReleaseConfig,
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{
+        FeatureFlag as AptosFeatureFlag, Features as AptosFeatures, GasScheduleV2,
+        OnChainConsensusConfig, TransactionDenyList,
+    },
+};
+use futures::executor::block_on;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The directory, under the directory of the release scripts, the rollback scripts are written to
+pub const ROLLBACK_DIR: &str = "rollback";
+
+/// Generates, next to the release scripts, the scripts reverting the release to the state on
+/// chain when the scripts were generated. The version isn't rolled back, as it can only go up.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct RollbackConfig {
+    /// The `aptos-move/framework` directory of the release currently on chain, whose packages
+    /// are republished by the rollback of a framework release.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_framework_path: Option<PathBuf>,
+}
+
+/// The on chain state the release changes, as it was when the rollback scripts were generated.
+/// Only the configs the release sets are snapshotted.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PreviousState {
+    pub transaction_deny_list: Option<TransactionDenyList>,
+    pub consensus_config: Option<OnChainConsensusConfig>,
+    pub features: Option<AptosFeatures>,
+    pub gas_schedule: Option<GasScheduleV2>,
+}
+
+impl PreviousState {
+    /// Snapshots the state of the chain the release changes
+    fn fetch(config: &ReleaseConfig, client: &Client) -> Result<Self> {
+        let mut previous = PreviousState::default();
+        if config.transaction_deny_list.is_some() {
+            // The deny list isn't published until governance first sets it, in which case the
+            // rollback clears it
+            previous.transaction_deny_list = Some(
+                block_on(async {
+                    client
+                        .get_account_resource_bcs::<TransactionDenyList>(
+                            CORE_CODE_ADDRESS,
+                            "0x1::transaction_deny_list::TransactionDenyList",
+                        )
+                        .await
+                })
+                .map(|response| response.into_inner())
+                .unwrap_or_default(),
+            );
+        }
+        if config.consensus_config.is_some() {
+            previous.consensus_config = Some(fetch_config::<OnChainConsensusConfig>(client)?);
+        }
+        if config.feature_flags.is_some() {
+            previous.features = Some(
+                block_on(async {
+                    client
+                        .get_account_resource_bcs::<AptosFeatures>(
+                            CORE_CODE_ADDRESS,
+                            "0x1::features::Features",
+                        )
+                        .await
+                })?
+                .into_inner(),
+            );
+        }
+        if config.gas_schedule.is_some() {
+            previous.gas_schedule = Some(fetch_config::<GasScheduleV2>(client)?);
+        }
+        Ok(previous)
+    }
+}
+
+type RollbackGenerationFunction = dyn Fn(
+    &ReleaseConfig,
+    &RollbackConfig,
+    &PreviousState,
+    &mut Vec<(String, String)>,
+) -> Result<()>;
+
+impl ReleaseConfig {
+    /// Generates the rollback scripts of the release into the `rollback` subdirectory of
+    /// `base_path`, in the reverse order of the release scripts. The previous state is
+    /// snapshotted from the remote endpoint, so it has to be set.
+    pub(crate) fn generate_rollback_proposal_scripts(
+        &self,
+        rollback: &RollbackConfig,
+        client: &Option<Client>,
+        base_path: &Path,
+    ) -> Result<()> {
+        let client = client.as_ref().ok_or_else(|| {
+            anyhow!(
+                "Generating rollback scripts requires a remote endpoint to snapshot the on chain state"
+            )
+        })?;
+        let previous = PreviousState::fetch(self, client)?;
+        self.write_rollback_proposal_scripts(rollback, &previous, base_path)
+    }
+
+    /// Writes the scripts reverting the release to the given previous state into the `rollback`
+    /// subdirectory of `base_path`.
+    pub(crate) fn write_rollback_proposal_scripts(
+        &self,
+        rollback: &RollbackConfig,
+        previous: &PreviousState,
+        base_path: &Path,
+    ) -> Result<()> {
+        let mut result: Vec<(String, String)> = vec![];
+        let mut rollback_generation_functions: Vec<&RollbackGenerationFunction> = vec![
+            &Self::generate_transaction_deny_list_rollback,
+            &Self::generate_consensus_rollback,
+            &Self::generate_feature_flag_rollback,
+            &Self::generate_gas_schedule_rollback,
+            &Self::generate_framework_rollback,
+        ];
+
+        // Same as for the release scripts, multi-step proposals are generated in reverse order
+        if self.is_multi_step {
+            rollback_generation_functions.reverse();
+        }
+
+        for f in &rollback_generation_functions {
+            (f)(self, rollback, previous, &mut result)?;
+        }
+
+        if self.is_multi_step {
+            result.reverse();
+        }
+
+        let rollback_path = base_path.join(ROLLBACK_DIR);
+        std::fs::create_dir_all(rollback_path.as_path())
+            .map_err(|err| anyhow!("Failed to create directory: {:?}", err))?;
+        for (idx, (script_name, script)) in result.into_iter().enumerate() {
+            let mut script_path = rollback_path.join(format!("{}-{}", idx, script_name));
+            script_path.set_extension("move");
+
+            std::fs::write(script_path.as_path(), script.as_bytes())
+                .map_err(|err| anyhow!("Failed to write to file: {:?}", err))?;
+        }
+
+        Ok(())
+    }
+
+    fn next_rollback_execution_hash(&self, result: &Vec<(String, String)>) -> Result<String> {
+        if self.is_multi_step {
+            Self::get_execution_hash(result)
+        } else {
+            Ok("".to_owned())
+        }
+    }
+
+    fn generate_transaction_deny_list_rollback(
+        &self,
+        _rollback: &RollbackConfig,
+        previous: &PreviousState,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let Some(on_chain) = &previous.transaction_deny_list {
+            if self.transaction_deny_list.as_ref() != Some(on_chain) {
+                result.append(
+                    &mut transaction_deny_list::generate_transaction_deny_list_proposal(
+                        on_chain,
+                        self.testnet,
+                        self.next_rollback_execution_hash(result)?,
+                    )?,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_consensus_rollback(
+        &self,
+        _rollback: &RollbackConfig,
+        previous: &PreviousState,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let (Some(consensus_config), Some(on_chain)) =
+            (&self.consensus_config, &previous.consensus_config)
+        {
+            if on_chain != consensus_config {
+                result.append(&mut consensus_config::generate_consensus_upgrade_proposal(
+                    on_chain,
+                    self.testnet,
+                    self.next_rollback_execution_hash(result)?,
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_feature_flag_rollback(
+        &self,
+        _rollback: &RollbackConfig,
+        previous: &PreviousState,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let (Some(feature_flags), Some(on_chain)) = (&self.feature_flags, &previous.features) {
+            let feature_flags = feature_flags.for_network(self.network())?;
+
+            // Only the flags the release actually changes are restored
+            let reverted = Features {
+                enabled: feature_flags
+                    .disabled
+                    .into_iter()
+                    .filter(|f| on_chain.is_enabled(AptosFeatureFlag::from(f.clone())))
+                    .collect(),
+                disabled: feature_flags
+                    .enabled
+                    .into_iter()
+                    .filter(|f| !on_chain.is_enabled(AptosFeatureFlag::from(f.clone())))
+                    .collect(),
+                testnet_only: vec![],
+            };
+            if !reverted.enabled.is_empty() || !reverted.disabled.is_empty() {
+                result.append(&mut feature_flags::generate_feature_upgrade_proposal(
+                    &reverted,
+                    self.testnet,
+                    self.next_rollback_execution_hash(result)?,
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_gas_schedule_rollback(
+        &self,
+        _rollback: &RollbackConfig,
+        previous: &PreviousState,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if let (Some(gas_schedule), Some(on_chain)) = (&self.gas_schedule, &previous.gas_schedule) {
+            if on_chain != gas_schedule {
+                result.append(&mut gas::generate_gas_upgrade_proposal(
+                    on_chain,
+                    self.testnet,
+                    self.next_rollback_execution_hash(result)?,
+                )?);
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_framework_rollback(
+        &self,
+        rollback: &RollbackConfig,
+        _previous: &PreviousState,
+        result: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        if self.framework_release {
+            let previous_framework_path = match &rollback.previous_framework_path {
+                Some(path) => path,
+                None => bail!(
+                    "The rollback of a framework release requires the path of the previous framework"
+                ),
+            };
+            result.append(&mut framework::generate_upgrade_proposals_from(
+                previous_framework_path,
+                self.testnet,
+                self.next_rollback_execution_hash(result)?,
+                &self.framework_packages,
+            )?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        components::feature_flags::FeatureFlag, propose::proposal_scripts,
+        verification::verify_multi_step_proposal_chain,
+    };
+    use aptos_temppath::TempPath;
+
+    fn release_config(is_multi_step: bool) -> ReleaseConfig {
+        ReleaseConfig {
+            framework_release: false,
+            is_multi_step,
+            gas_schedule: Some(GasScheduleV2 {
+                feature_version: 7,
+                entries: vec![("txn.min_transaction_gas_units".to_string(), 2)],
+            }),
+            feature_flags: Some(Features {
+                enabled: vec![FeatureFlag::BlockInfo],
+                disabled: vec![FeatureFlag::TransactionDenyList],
+                testnet_only: vec![],
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// The state before `release_config`, which it changes except for the consensus config
+    fn previous_state(config: &ReleaseConfig) -> PreviousState {
+        let mut features = AptosFeatures::default();
+        features.enable(AptosFeatureFlag::TRANSACTION_DENY_LIST);
+        PreviousState {
+            transaction_deny_list: None,
+            consensus_config: config.consensus_config.clone(),
+            features: Some(features),
+            gas_schedule: Some(GasScheduleV2 {
+                feature_version: 6,
+                entries: vec![("txn.min_transaction_gas_units".to_string(), 1)],
+            }),
+        }
+    }
+
+    /// Generates the rollback scripts, returning the directory they're in, which is removed once
+    /// dropped, and the paths and contents of the scripts
+    fn generate(
+        config: &ReleaseConfig,
+        previous: &PreviousState,
+    ) -> Result<(TempPath, Vec<(PathBuf, String)>)> {
+        let scripts_dir = TempPath::new();
+        scripts_dir.create_as_dir().unwrap();
+        config.write_rollback_proposal_scripts(
+            &RollbackConfig::default(),
+            previous,
+            scripts_dir.path(),
+        )?;
+        let scripts = proposal_scripts(&scripts_dir.path().join(ROLLBACK_DIR))?
+            .into_iter()
+            .map(|path| {
+                let script = std::fs::read_to_string(&path).unwrap();
+                (path, script)
+            })
+            .collect();
+        Ok((scripts_dir, scripts))
+    }
+
+    fn file_name(path: &Path) -> &str {
+        path.file_name().unwrap().to_str().unwrap()
+    }
+
+    #[test]
+    fn test_rollback_restores_previous_state() {
+        let config = release_config(false);
+        let previous = previous_state(&config);
+        let (_scripts_dir, scripts) = generate(&config, &previous).unwrap();
+
+        // The consensus config isn't changed by the release, so it isn't rolled back
+        assert_eq!(scripts.len(), 2);
+        assert_eq!(file_name(&scripts[0].0), "0-features.move");
+        assert_eq!(
+            scripts[0].1,
+            feature_flags::generate_feature_upgrade_proposal(
+                &Features {
+                    enabled: vec![FeatureFlag::TransactionDenyList],
+                    disabled: vec![FeatureFlag::BlockInfo],
+                    testnet_only: vec![],
+                },
+                true,
+                "".to_owned(),
+            )
+            .unwrap()[0]
+                .1
+        );
+        assert_eq!(file_name(&scripts[1].0), "1-gas-schedule.move");
+        assert_eq!(
+            scripts[1].1,
+            gas::generate_gas_upgrade_proposal(
+                previous.gas_schedule.as_ref().unwrap(),
+                true,
+                "".to_owned()
+            )
+            .unwrap()[0]
+                .1
+        );
+    }
+
+    #[test]
+    fn test_rollback_of_applied_release_is_empty() {
+        let config = release_config(false);
+        let mut features = AptosFeatures::default();
+        features.enable(AptosFeatureFlag::BLOCK_INFO);
+        let previous = PreviousState {
+            features: Some(features),
+            gas_schedule: config.gas_schedule.clone(),
+            ..Default::default()
+        };
+        assert!(generate(&config, &previous).unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn test_multi_step_rollback_is_chained() {
+        let config = release_config(true);
+        let previous = previous_state(&config);
+        let (_scripts_dir, scripts) = generate(&config, &previous).unwrap();
+        assert_eq!(scripts.len(), 2);
+
+        let paths: Vec<_> = scripts.into_iter().map(|(path, _)| path).collect();
+        verify_multi_step_proposal_chain(&paths).unwrap();
+    }
+
+    #[test]
+    fn test_framework_rollback_requires_previous_framework() {
+        let config = ReleaseConfig {
+            framework_release: true,
+            ..release_config(false)
+        };
+        let previous = previous_state(&config);
+        assert!(generate(&config, &previous).is_err());
+    }
+}
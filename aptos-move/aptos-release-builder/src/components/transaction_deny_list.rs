@@ -0,0 +1,114 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::*;
+use anyhow::Result;
+use aptos_types::on_chain_config::TransactionDenyList;
+use move_model::{code_writer::CodeWriter, emit, emitln, model::Loc};
+
+/// Emits the strings as a vector of Move strings, from hex literals so that any bytes are kept
+/// as they are.
+fn generate_strings(writer: &CodeWriter, strings: &[&str]) {
+    emit!(writer, "vector[");
+    for s in strings {
+        emit!(writer, "std::string::utf8(x\"{}\"), ", hex::encode(s));
+    }
+    emit!(writer, "]")
+}
+
+pub fn generate_transaction_deny_list_proposal(
+    deny_list: &TransactionDenyList,
+    is_testnet: bool,
+    next_execution_hash: String,
+) -> Result<Vec<(String, String)>> {
+    let mut result = vec![];
+
+    let writer = CodeWriter::new(Loc::default());
+
+    emitln!(writer, "// Transaction deny list update proposal\n");
+
+    let proposal = generate_governance_proposal(
+        &writer,
+        is_testnet,
+        &next_execution_hash,
+        "aptos_framework::transaction_deny_list",
+        |writer| {
+            emit!(writer, "let senders: vector<address> = vector[");
+            for sender in &deny_list.senders {
+                emit!(writer, "@{}, ", sender.to_hex_literal());
+            }
+            emitln!(writer, "];");
+
+            emit!(writer, "let module_addresses: vector<address> = vector[");
+            for function in &deny_list.entry_functions {
+                emit!(writer, "@{}, ", function.module_address.to_hex_literal());
+            }
+            emitln!(writer, "];");
+
+            let module_names: Vec<_> = deny_list
+                .entry_functions
+                .iter()
+                .map(|f| f.module_name.as_str())
+                .collect();
+            emit!(writer, "let module_names = ");
+            generate_strings(writer, &module_names);
+            emitln!(writer, ";");
+
+            let function_names: Vec<_> = deny_list
+                .entry_functions
+                .iter()
+                .map(|f| f.function_name.as_str())
+                .collect();
+            emit!(writer, "let function_names = ");
+            generate_strings(writer, &function_names);
+            emitln!(writer, ";\n");
+
+            emitln!(
+                writer,
+                "transaction_deny_list::set(framework_signer, senders, module_addresses, module_names, function_names);"
+            );
+        },
+    );
+
+    result.push(("transaction-deny-list".to_string(), proposal));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{account_address::AccountAddress, on_chain_config::DeniedFunction};
+
+    #[test]
+    fn test_transaction_deny_list_proposal() {
+        let deny_list = TransactionDenyList {
+            senders: vec![AccountAddress::from_hex_literal("0xbad").unwrap()],
+            entry_functions: vec![
+                DeniedFunction {
+                    module_address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+                    module_name: "coin".to_string(),
+                    function_name: "".to_string(),
+                },
+                DeniedFunction {
+                    module_address: AccountAddress::from_hex_literal("0xcafe").unwrap(),
+                    module_name: "vault".to_string(),
+                    function_name: "withdraw".to_string(),
+                },
+            ],
+        };
+        let proposals =
+            generate_transaction_deny_list_proposal(&deny_list, true, "".to_owned()).unwrap();
+        assert_eq!(proposals.len(), 1);
+        let (name, script) = &proposals[0];
+        assert_eq!(name, "transaction-deny-list");
+        assert!(script.contains("let senders: vector<address> = vector[@0xbad, ];"));
+        assert!(script.contains(
+            "let module_names = vector[std::string::utf8(x\"636f696e\"), std::string::utf8(x\"7661756c74\"), ];"
+        ));
+        assert!(script.contains(
+            "let function_names = vector[std::string::utf8(x\"\"), std::string::utf8(x\"7769746864726177\"), ];"
+        ));
+        // the proposal compiles against the framework of this build
+        script_execution_hash(script).unwrap();
+    }
+}
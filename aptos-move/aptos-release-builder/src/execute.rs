@@ -0,0 +1,225 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    propose::proposal_scripts,
+    utils::compile_script,
+    verification::{
+        is_voting_closed, verify_multi_step_proposal_chain, ExecutionManifest,
+        GOVERNANCE_VOTING_FORUM, MANIFEST_EXTENSION,
+    },
+    ReleaseConfig,
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_rest_client::Client;
+use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    chain_id::ChainId,
+    transaction::{Script, TransactionArgument, TransactionPayload},
+};
+use std::{path::Path, time::Duration};
+
+/// The gas budget of each script, large enough to publish the framework packages
+const MAX_GAS_AMOUNT: u64 = 2_000_000;
+
+/// The coins minted to each voter to pay for creating the proposal and voting on it
+const VOTER_FUNDS: u64 = 1_000_000_000;
+
+impl ReleaseConfig {
+    /// Generates the scripts of the release and executes them in order on a test network,
+    /// checking after each script that the on chain state matches its manifest. Returns the names
+    /// of the executed scripts.
+    ///
+    /// The scripts of a single-step release are executed with the core resources account. A
+    /// multi-step release goes through governance: the voters, which own stake pools, are funded
+    /// by the core resources account, the first one creates the proposal and all of them vote for
+    /// it. Once voting is closed, the core resources account executes the steps in order.
+    pub async fn execute_release(
+        &self,
+        root_account: &mut LocalAccount,
+        voters: &mut [LocalAccount],
+        client: &Client,
+    ) -> Result<Vec<String>> {
+        let scripts_dir = TempPath::new();
+        scripts_dir.create_as_dir()?;
+        Self {
+            testnet: true,
+            ..self.clone()
+        }
+        .generate_release_proposal_scripts(scripts_dir.path())?;
+        let script_paths = proposal_scripts(scripts_dir.path())?;
+
+        let chain_id = client.get_ledger_information().await?.into_inner().chain_id;
+        let transaction_factory = TransactionFactory::new(ChainId::new(chain_id));
+
+        let proposal_id = if self.is_multi_step && !script_paths.is_empty() {
+            Some(
+                create_and_approve_proposal(
+                    root_account,
+                    voters,
+                    client,
+                    &transaction_factory,
+                    &script_paths,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        let transaction_factory = transaction_factory.with_max_gas_amount(MAX_GAS_AMOUNT);
+        let mut executed = vec![];
+        for script_path in script_paths {
+            let script_name = script_path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string();
+
+            // The steps of a multi-step proposal are given the id of the proposal they resolve
+            let args = proposal_id
+                .map(|proposal_id| vec![TransactionArgument::U64(proposal_id)])
+                .unwrap_or_default();
+            let script = Script::new(compile_script(&script_path)?, vec![], args);
+            submit_transaction(
+                root_account,
+                client,
+                &transaction_factory,
+                TransactionPayload::Script(script),
+            )
+            .await
+            .map_err(|err| anyhow!("Failed to execute {}: {:?}", script_name, err))?;
+
+            let mismatches =
+                ExecutionManifest::load(script_path.with_extension(MANIFEST_EXTENSION))?
+                    .verify(client)
+                    .await?;
+            if !mismatches.is_empty() {
+                bail!(
+                    "Execution of {} didn't have the intended effect: {:?}",
+                    script_name,
+                    mismatches
+                );
+            }
+            executed.push(script_name);
+        }
+        Ok(executed)
+    }
+}
+
+/// Creates the governance proposal of the given multi-step scripts from the first voter, and has
+/// every voter vote for it with its stake pool, after extending its lockup beyond the voting
+/// period. Returns the id of the proposal once voting is closed.
+async fn create_and_approve_proposal(
+    root_account: &mut LocalAccount,
+    voters: &mut [LocalAccount],
+    client: &Client,
+    transaction_factory: &TransactionFactory,
+    script_paths: &[impl AsRef<Path>],
+) -> Result<u64> {
+    if voters.is_empty() {
+        bail!("Multi-step releases must be approved by voters owning stake pools");
+    }
+    let script_paths: Vec<_> = script_paths
+        .iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect();
+    let execution_hash = verify_multi_step_proposal_chain(&script_paths)?[0];
+
+    for voter in voters.iter_mut() {
+        submit_transaction(
+            root_account,
+            client,
+            transaction_factory,
+            aptos_stdlib::aptos_coin_mint(voter.address(), VOTER_FUNDS),
+        )
+        .await?;
+        submit_transaction(
+            voter,
+            client,
+            transaction_factory,
+            aptos_stdlib::stake_increase_lockup(),
+        )
+        .await?;
+    }
+
+    let proposal_id = next_proposal_id(client).await?;
+    let proposer = &mut voters[0];
+    let stake_pool = proposer.address();
+    submit_transaction(
+        proposer,
+        client,
+        transaction_factory,
+        aptos_stdlib::aptos_governance_create_proposal_v2(
+            stake_pool,
+            execution_hash.to_vec(),
+            vec![],
+            vec![],
+            true,
+        ),
+    )
+    .await?;
+    for voter in voters.iter_mut() {
+        let stake_pool = voter.address();
+        submit_transaction(
+            voter,
+            client,
+            transaction_factory,
+            aptos_stdlib::aptos_governance_vote(stake_pool, proposal_id, true),
+        )
+        .await?;
+    }
+
+    // Voting closes at the end of the voting period, or earlier once a majority voted
+    while !is_voting_closed(client, proposal_id).await? {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    Ok(proposal_id)
+}
+
+/// Returns the id the next governance proposal is created with
+async fn next_proposal_id(client: &Client) -> Result<u64> {
+    let forum = client
+        .get_account_resource(CORE_CODE_ADDRESS, GOVERNANCE_VOTING_FORUM)
+        .await?
+        .into_inner()
+        .ok_or_else(|| anyhow!("The governance voting forum doesn't exist"))?;
+    forum.data["next_proposal_id"]
+        .as_str()
+        .and_then(|proposal_id| proposal_id.parse().ok())
+        .ok_or_else(|| anyhow!("Failed to parse the next governance proposal id"))
+}
+
+/// Signs the payload with the current sequence number of the account and waits for the
+/// transaction to be committed. The sequence number is only incremented once the transaction
+/// succeeds; on failure it's reloaded from chain, as a transaction that aborted still used it.
+async fn submit_transaction(
+    account: &mut LocalAccount,
+    client: &Client,
+    transaction_factory: &TransactionFactory,
+    payload: TransactionPayload,
+) -> Result<()> {
+    let raw_txn = transaction_factory
+        .payload(payload)
+        .sender(account.address())
+        .sequence_number(account.sequence_number())
+        .build();
+    let txn = account.sign_transaction(raw_txn);
+    match client.submit_and_wait(&txn).await {
+        Ok(_) => {
+            *account.sequence_number_mut() += 1;
+            Ok(())
+        }
+        Err(err) => {
+            *account.sequence_number_mut() = client
+                .get_account(account.address())
+                .await?
+                .into_inner()
+                .sequence_number;
+            Err(err.into())
+        }
+    }
+}
@@ -2,6 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod components;
+pub mod execute;
+pub mod metadata;
+pub mod network_config;
+pub mod propose;
+pub mod simulate;
+pub mod upgrade_readiness;
 mod utils;
+pub mod validate;
+pub mod verification;
 
 pub use components::ReleaseConfig;
+pub use verification::ExecutionManifest;
@@ -1,9 +1,18 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use aptos_release_builder::{
+    metadata::verify_proposal_metadata,
+    network_config::Network,
+    propose::{proposal_scripts, voting_instructions, ProposeOptions},
+    simulate::SimulationSource,
+    verification::verify_multi_step_proposal_chain,
+};
 use clap::{Parser, Subcommand};
+use move_core_types::account_address::AccountAddress;
 use std::path::PathBuf;
+use url::Url;
 
 #[derive(Parser)]
 pub struct Argument {
@@ -18,11 +27,103 @@ pub enum Commands {
         release_config: PathBuf,
         #[clap(short, long)]
         output_dir: PathBuf,
+        /// Generate a set of scripts for each of devnet, testnet and mainnet, in separate
+        /// subdirectories.
+        #[clap(long)]
+        per_network: bool,
+        /// Generate the scripts of the given network, with the overrides of its section of the
+        /// release config applied.
+        #[clap(long, arg_enum, conflicts_with = "per-network")]
+        network: Option<Network>,
+    },
+    /// Generate the framework upgrade followed by the proposal enabling the features of the
+    /// release config, that the upgraded framework relies on.
+    GenerateFeatureActivation {
+        #[clap(short, long)]
+        release_config: PathBuf,
+        #[clap(short, long)]
+        output_dir: PathBuf,
     },
     WriteDefault {
         #[clap(short, long)]
         output_path: PathBuf,
     },
+    /// Generate the scripts of the release and create the governance proposals executing them,
+    /// then print the commands to vote on and execute the proposals.
+    Propose {
+        #[clap(short, long)]
+        release_config: PathBuf,
+        #[clap(short, long)]
+        output_dir: PathBuf,
+        /// REST endpoint of the network to submit the proposals to
+        #[clap(long)]
+        endpoint: Url,
+        /// The stake pool backing the proposals
+        #[clap(long, parse(try_from_str = AccountAddress::from_hex_literal))]
+        pool_address: AccountAddress,
+        /// The `aptos` CLI profile of the delegated voter of the stake pool
+        #[clap(long)]
+        profile: String,
+        /// Location of the JSON metadata of the proposals
+        #[clap(long)]
+        metadata_url: Url,
+        /// Path of the `aptos` CLI binary
+        #[clap(long, default_value = "aptos")]
+        aptos_cli: PathBuf,
+    },
+    /// Generate the scripts of the release and execute them on top of the state of a network,
+    /// without submitting anything. Prints the gas used and the events emitted by each script,
+    /// along with the resulting on chain configs.
+    Simulate {
+        #[clap(short, long)]
+        release_config: PathBuf,
+        #[clap(short, long)]
+        output_dir: PathBuf,
+        /// REST endpoint of the network whose latest state is forked
+        #[clap(long, required_unless_present = "db-path")]
+        endpoint: Option<Url>,
+        /// Path of a local AptosDB checkpoint whose latest state is forked
+        #[clap(long, conflicts_with = "endpoint")]
+        db_path: Option<PathBuf>,
+    },
+    /// Check that the scripts of a multi-step proposal are chained through their execution
+    /// hashes, and print the hash the proposal must be created with.
+    VerifyMultiStepProposal {
+        #[clap(short, long)]
+        scripts_dir: PathBuf,
+    },
+    /// Check that a proposal on chain was created with the given generated metadata file
+    VerifyProposalMetadata {
+        /// REST endpoint of the network of the proposal
+        #[clap(long)]
+        endpoint: Url,
+        #[clap(long)]
+        proposal_id: u64,
+        /// The `.metadata.json` file generated next to the proposal script
+        #[clap(long)]
+        metadata_path: PathBuf,
+    },
+    /// Compare the release config against the state of a network, and print what the release
+    /// would change on chain. Fails if anything differs.
+    Validate {
+        #[clap(short, long)]
+        release_config: PathBuf,
+        /// REST endpoint of the network to compare against
+        #[clap(long)]
+        endpoint: Url,
+    },
+    /// Print the gas parameters the gas schedule of the release config changes on a network,
+    /// with their old and new values.
+    GasScheduleDiff {
+        #[clap(short, long)]
+        release_config: PathBuf,
+        /// REST endpoint of the network to compare against
+        #[clap(long)]
+        endpoint: Url,
+        /// Print the changes as JSON
+        #[clap(long)]
+        json: bool,
+    },
 }
 
 #[tokio::main]
@@ -34,10 +135,133 @@ async fn main() -> Result<()> {
         Commands::GenerateProposals {
             release_config,
             output_dir,
+            per_network,
+            network,
+        } => {
+            if let Some(network) = network {
+                return aptos_release_builder::ReleaseConfig::load_from_file(
+                    release_config.as_path(),
+                    network,
+                )?
+                .generate_release_proposal_scripts(output_dir.as_path());
+            }
+            let config =
+                aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?;
+            if per_network {
+                config.generate_release_proposal_scripts_for_networks(output_dir.as_path())
+            } else {
+                config.generate_release_proposal_scripts(output_dir.as_path())
+            }
+        }
+        Commands::GenerateFeatureActivation {
+            release_config,
+            output_dir,
         } => aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?
-            .generate_release_proposal_scripts(output_dir.as_path()),
+            .generate_feature_activation_proposal_scripts(output_dir.as_path()),
         Commands::WriteDefault { output_path } => {
             aptos_release_builder::ReleaseConfig::default().save_config(output_path.as_path())
         }
+        Commands::Propose {
+            release_config,
+            output_dir,
+            endpoint,
+            pool_address,
+            profile,
+            metadata_url,
+            aptos_cli,
+        } => {
+            let options = ProposeOptions {
+                aptos_cli,
+                endpoint,
+                pool_address,
+                profile,
+                metadata_url,
+            };
+            let proposals =
+                aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?
+                    .propose(output_dir.as_path(), &options)?;
+            println!("{}", voting_instructions(&proposals, &options));
+            Ok(())
+        }
+        Commands::Simulate {
+            release_config,
+            output_dir,
+            endpoint,
+            db_path,
+        } => {
+            let source = match (endpoint, db_path) {
+                (Some(endpoint), _) => SimulationSource::Rest(endpoint),
+                (None, Some(db_path)) => SimulationSource::Db(db_path),
+                (None, None) => bail!("Either an endpoint or a db path must be given"),
+            };
+            let report =
+                aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?
+                    .simulate(output_dir.as_path(), source)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            Ok(())
+        }
+        Commands::VerifyMultiStepProposal { scripts_dir } => {
+            let hashes =
+                verify_multi_step_proposal_chain(&proposal_scripts(scripts_dir.as_path())?)?;
+            match hashes.first() {
+                Some(hash) => println!("Proposal execution hash: {}", hash.to_hex()),
+                None => bail!("No script found in {}", scripts_dir.display()),
+            }
+            Ok(())
+        }
+        Commands::VerifyProposalMetadata {
+            endpoint,
+            proposal_id,
+            metadata_path,
+        } => {
+            let mismatches = verify_proposal_metadata(
+                &aptos_rest_client::Client::new(endpoint),
+                proposal_id,
+                metadata_path.as_path(),
+            )
+            .await?;
+            if !mismatches.is_empty() {
+                bail!("Proposal metadata doesn't match: {:?}", mismatches);
+            }
+            println!(
+                "Proposal {} matches {}",
+                proposal_id,
+                metadata_path.display()
+            );
+            Ok(())
+        }
+        Commands::Validate {
+            release_config,
+            endpoint,
+        } => {
+            let report =
+                aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?
+                    .validate_against_rest_endpoint(endpoint)
+                    .await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.is_empty() {
+                bail!(
+                    "{} differences found between the release config and the network",
+                    report.differences.len()
+                );
+            }
+            Ok(())
+        }
+        Commands::GasScheduleDiff {
+            release_config,
+            endpoint,
+            json,
+        } => {
+            let diff = aptos_release_builder::ReleaseConfig::load_config(release_config.as_path())?
+                .gas_schedule_diff(endpoint)
+                .await?
+                .ok_or_else(|| anyhow!("The release config doesn't set the gas schedule"))?;
+            if json {
+                println!("{}", diff.to_json());
+            } else {
+                println!("{}", diff);
+            }
+            Ok(())
+        }
     }
 }
@@ -0,0 +1,263 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{utils::compile_script, verification::fetch_governance_proposal};
+use anyhow::{anyhow, bail, Result};
+use aptos_crypto::HashValue;
+use aptos_rest_client::Client;
+use aptos_types::account_config::CORE_CODE_ADDRESS;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// The extension of the metadata generated next to each proposal script
+pub const METADATA_EXTENSION: &str = "metadata.json";
+
+/// The description of a release, from which the metadata of its proposals are generated
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposalMetadataConfig {
+    pub title: String,
+    pub description: String,
+    pub source_code_url: String,
+    pub discussion_url: String,
+}
+
+/// The metadata of a proposal, to be uploaded to the metadata URL of the proposal. It's a
+/// superset of the metadata expected by `aptos governance propose`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProposalMetadata {
+    pub title: String,
+    pub description: String,
+    pub source_code_url: String,
+    pub discussion_url: String,
+    /// The sha3-256 of the compiled script, i.e. the execution hash of the proposal
+    pub execution_hash: String,
+    /// The source of the script
+    pub source: String,
+    /// The aptos-core commit of the Move compiler the script is compiled with
+    pub compiler_version: String,
+}
+
+impl ProposalMetadata {
+    /// Generates the metadata of the given proposal script
+    pub fn generate(
+        config: &ProposalMetadataConfig,
+        proposal_name: &str,
+        script_path: &Path,
+    ) -> Result<Self> {
+        let code = compile_script(script_path)?;
+        Ok(Self {
+            title: format!("{}: {}", config.title, proposal_name),
+            description: config.description.clone(),
+            source_code_url: config.source_code_url.clone(),
+            discussion_url: config.discussion_url.clone(),
+            execution_hash: HashValue::sha3_256_of(&code).to_hex(),
+            source: fs::read_to_string(script_path)?,
+            compiler_version: aptos_build_info::get_git_hash(),
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let contents = serde_json::to_vec_pretty(self)
+            .map_err(|e| anyhow!("failed to generate metadata: {:?}", e))?;
+        fs::write(path.as_ref(), contents).map_err(|e| anyhow!("failed to write file: {:?}", e))
+    }
+}
+
+/// Checks that a proposal on chain was created with the given metadata file: the metadata hash
+/// of the proposal must be the hash of the file, and the execution hash the proposal was created
+/// with the one of the metadata. Returns the differences found.
+pub async fn verify_proposal_metadata(
+    client: &Client,
+    proposal_id: u64,
+    metadata_path: &Path,
+) -> Result<Vec<String>> {
+    let bytes = fs::read(metadata_path).map_err(|err| {
+        anyhow!(
+            "Failed to read metadata {}: {:?}",
+            metadata_path.display(),
+            err
+        )
+    })?;
+    let proposal = fetch_governance_proposal(client, proposal_id).await?;
+    let metadata_hash = proposal_metadata_value(&proposal, "metadata_hash")?;
+    let execution_hash = created_execution_hash(client, proposal_id).await?;
+    metadata_mismatches(
+        proposal_id,
+        &bytes,
+        metadata_hash.as_deref(),
+        &execution_hash,
+    )
+}
+
+/// Compares the metadata file of a proposal with the metadata hash and the execution hash the
+/// proposal was created with
+fn metadata_mismatches(
+    proposal_id: u64,
+    bytes: &[u8],
+    on_chain_metadata_hash: Option<&str>,
+    on_chain_execution_hash: &str,
+) -> Result<Vec<String>> {
+    let metadata: ProposalMetadata = serde_json::from_slice(bytes)?;
+
+    let mut mismatches = vec![];
+    let metadata_hash = HashValue::sha3_256_of(bytes).to_hex();
+    if on_chain_metadata_hash != Some(metadata_hash.as_str()) {
+        mismatches.push(format!(
+            "Proposal {} has metadata hash {:?} but the metadata hashes to {}",
+            proposal_id, on_chain_metadata_hash, metadata_hash
+        ));
+    }
+    let execution_hash = on_chain_execution_hash.trim_start_matches("0x");
+    if execution_hash != metadata.execution_hash {
+        mismatches.push(format!(
+            "Proposal {} has execution hash {} but the metadata has {}",
+            proposal_id, execution_hash, metadata.execution_hash
+        ));
+    }
+    Ok(mismatches)
+}
+
+/// Returns the execution hash the given governance proposal was created with. The execution hash
+/// of a multi-step proposal is replaced on chain by the hash of the next step each time a step is
+/// executed, so it's read from the event emitted when the proposal was created.
+async fn created_execution_hash(client: &Client, proposal_id: u64) -> Result<String> {
+    let events = client.events_stream(
+        CORE_CODE_ADDRESS,
+        "0x1::aptos_governance::GovernanceEvents",
+        "create_proposal_events",
+        None,
+        None,
+    );
+    futures::pin_mut!(events);
+    while let Some(event) = events.next().await {
+        let event = event?;
+        let event_proposal_id = event.data["proposal_id"]
+            .as_str()
+            .and_then(|id| id.parse::<u64>().ok());
+        if event_proposal_id == Some(proposal_id) {
+            return event.data["execution_hash"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    anyhow!("Failed to parse the creation of proposal {}", proposal_id)
+                });
+        }
+    }
+    bail!("Governance proposal {} was never created", proposal_id)
+}
+
+/// Returns the value of the given key of the metadata of a proposal, a `SimpleMap` whose values
+/// are UTF-8 strings.
+fn proposal_metadata_value(proposal: &serde_json::Value, key: &str) -> Result<Option<String>> {
+    let entries = proposal["metadata"]["data"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Failed to parse the metadata of the proposal"))?;
+    for entry in entries {
+        if entry["key"].as_str() == Some(key) {
+            let value = entry["value"]
+                .as_str()
+                .ok_or_else(|| anyhow!("Failed to parse the metadata value {}", key))?;
+            let bytes = hex::decode(value.trim_start_matches("0x"))?;
+            return Ok(Some(String::from_utf8(bytes)?));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use serde_json::json;
+
+    fn metadata_config() -> ProposalMetadataConfig {
+        ProposalMetadataConfig {
+            title: "Release".to_string(),
+            description: "Sets the version".to_string(),
+            source_code_url: "https://github.com/aptos-labs/aptos-core".to_string(),
+            discussion_url: "https://github.com/aptos-labs/aptos-core/discussions".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_metadata_mismatches() {
+        let scripts_dir = TempPath::new();
+        scripts_dir.create_as_dir().unwrap();
+        let script_path = scripts_dir.path().join("0-version.move");
+        fs::write(&script_path, "script {\n    fun main() {}\n}\n").unwrap();
+        let metadata =
+            ProposalMetadata::generate(&metadata_config(), "version", &script_path).unwrap();
+        assert_eq!(metadata.title, "Release: version");
+        assert_eq!(
+            metadata.execution_hash,
+            HashValue::sha3_256_of(&compile_script(&script_path).unwrap()).to_hex()
+        );
+        let bytes = serde_json::to_vec_pretty(&metadata).unwrap();
+        let metadata_hash = HashValue::sha3_256_of(&bytes).to_hex();
+        let execution_hash = format!("0x{}", metadata.execution_hash);
+
+        assert!(
+            metadata_mismatches(1, &bytes, Some(&metadata_hash), &execution_hash)
+                .unwrap()
+                .is_empty()
+        );
+        // Once its first step is executed, a multi-step proposal is resolved with the hash of the
+        // next step, which isn't the one of the metadata
+        let next_step_hash = format!("0x{}", HashValue::zero().to_hex());
+        assert_eq!(
+            metadata_mismatches(1, &bytes, Some(&metadata_hash), &next_step_hash)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            metadata_mismatches(1, &bytes, None, &execution_hash)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            metadata_mismatches(
+                1,
+                &bytes,
+                Some(&HashValue::zero().to_hex()),
+                &next_step_hash
+            )
+            .unwrap()
+            .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_proposal_metadata_value() {
+        let proposal = json!({
+            "metadata": {
+                "data": [
+                    {
+                        "key": "metadata_location",
+                        "value": format!("0x{}", hex::encode("https://aptos.dev")),
+                    },
+                    {
+                        "key": "metadata_hash",
+                        "value": format!("0x{}", hex::encode("abcd")),
+                    },
+                ]
+            }
+        });
+        assert_eq!(
+            proposal_metadata_value(&proposal, "metadata_hash").unwrap(),
+            Some("abcd".to_string())
+        );
+        assert_eq!(
+            proposal_metadata_value(&proposal, "metadata_location").unwrap(),
+            Some("https://aptos.dev".to_string())
+        );
+        assert_eq!(
+            proposal_metadata_value(&proposal, "RESOLVABLE_TIME_METADATA_KEY").unwrap(),
+            None
+        );
+        assert!(proposal_metadata_value(&json!({}), "metadata_hash").is_err());
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{components::feature_flags::Features, ReleaseConfig};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+use url::Url;
+
+/// The networks a release is rolled out to
+#[derive(
+    Clone, Copy, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd, clap::ArgEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Devnet,
+    Testnet,
+    Mainnet,
+}
+
+impl Network {
+    pub const ALL: [Network; 3] = [Network::Devnet, Network::Testnet, Network::Mainnet];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Network::Devnet => "devnet",
+            Network::Testnet => "testnet",
+            Network::Mainnet => "mainnet",
+        }
+    }
+
+    /// Whether the scripts of the network are executed by the core resources account, rather
+    /// than through governance proposals
+    pub fn has_core_resources(&self) -> bool {
+        *self == Network::Devnet
+    }
+}
+
+/// The parts of a release that can differ between networks
+#[derive(Clone, Debug, Default, Deserialize, Serialize, Eq, PartialEq)]
+pub struct NetworkOverrides {
+    /// Replaces the feature flags of the release
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature_flags: Option<Features>,
+    /// Values of gas parameters replacing the ones of the gas schedule of the release, by name
+    /// (e.g. `txn.max_transaction_size_in_bytes`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub gas_parameters: BTreeMap<String, u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_multi_step: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_endpoint: Option<Url>,
+}
+
+/// A release config as checked into a repo: the config shared by all networks, along with
+/// sections overriding it per network, e.g.
+/// ```yaml
+/// testnet: true
+/// framework_release: true
+/// is_multi_step: false
+/// networks:
+///   mainnet:
+///     is_multi_step: true
+///     gas_parameters:
+///       txn.min_price_per_gas_unit: 100
+/// ```
+#[derive(Clone, Deserialize, Serialize, Eq, PartialEq)]
+pub struct ReleaseConfigFile {
+    #[serde(flatten)]
+    pub base: ReleaseConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub networks: BTreeMap<Network, NetworkOverrides>,
+}
+
+impl ReleaseConfigFile {
+    pub fn parse(serialized: &str) -> Result<Self> {
+        serde_yaml::from_str(serialized)
+            .map_err(|e| anyhow!("Failed to parse the release config file: {:?}", e))
+    }
+
+    /// Resolves the release config of the given network. The `testnet` field of the base config
+    /// is ignored, as it's implied by the network.
+    pub fn for_network(&self, network: Network) -> Result<ReleaseConfig> {
+        let mut config = ReleaseConfig {
+            testnet: network.has_core_resources(),
+            network: Some(network),
+            ..self.base.clone()
+        };
+        let overrides = match self.networks.get(&network) {
+            Some(overrides) => overrides,
+            None => return Ok(config),
+        };
+
+        if let Some(feature_flags) = &overrides.feature_flags {
+            config.feature_flags = Some(feature_flags.clone());
+        }
+        if let Some(is_multi_step) = overrides.is_multi_step {
+            config.is_multi_step = is_multi_step;
+        }
+        if let Some(remote_endpoint) = &overrides.remote_endpoint {
+            config.remote_endpoint = Some(remote_endpoint.clone());
+        }
+        if !overrides.gas_parameters.is_empty() {
+            let gas_schedule = config.gas_schedule.as_mut().ok_or_else(|| {
+                anyhow!(
+                    "Gas parameters are overridden for {:?} but the release has no gas schedule",
+                    network
+                )
+            })?;
+            for (name, value) in &overrides.gas_parameters {
+                let entry = gas_schedule
+                    .entries
+                    .iter_mut()
+                    .find(|(entry_name, _)| entry_name == name)
+                    .ok_or_else(|| anyhow!("Unknown gas parameter {} for {:?}", name, network))?;
+                entry.1 = *value;
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl ReleaseConfig {
+    /// Loads the release config of the given network from a release config file, see
+    /// `ReleaseConfigFile`.
+    pub fn load_from_file<P: AsRef<Path>>(path: P, network: Network) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref()).map_err(|error| {
+            anyhow!(
+                "Failed to read the release config file {:?}. Error: {:?}",
+                path.as_ref(),
+                error
+            )
+        })?;
+        ReleaseConfigFile::parse(&contents)?.for_network(network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{components::feature_flags::FeatureFlag, verification::ExpectedState};
+
+    const RELEASE_CONFIG_FILE: &str = r#"
+testnet: true
+framework_release: false
+is_multi_step: false
+gas_schedule:
+  feature_version: 4
+  entries:
+    - - txn.min_price_per_gas_unit
+      - 1
+    - - txn.max_price_per_gas_unit
+      - 10000
+feature_flags:
+  enabled:
+    - code_dependency_check
+  disabled: []
+networks:
+  mainnet:
+    is_multi_step: true
+    gas_parameters:
+      txn.min_price_per_gas_unit: 100
+    feature_flags:
+      enabled: []
+      disabled:
+        - code_dependency_check
+"#;
+
+    #[test]
+    fn test_network_overrides() {
+        let file = ReleaseConfigFile::parse(RELEASE_CONFIG_FILE).unwrap();
+
+        // Networks without a section get the base config
+        let devnet = file.for_network(Network::Devnet).unwrap();
+        assert!(devnet.testnet);
+        assert!(!devnet.is_multi_step);
+        assert_eq!(devnet.gas_schedule.as_ref().unwrap().entries[0].1, 1);
+        assert_eq!(
+            devnet.feature_flags.as_ref().unwrap().enabled,
+            vec![FeatureFlag::CodeDependencyCheck]
+        );
+
+        let mainnet = file.for_network(Network::Mainnet).unwrap();
+        assert!(!mainnet.testnet);
+        assert!(mainnet.is_multi_step);
+        let gas_schedule = mainnet.gas_schedule.as_ref().unwrap();
+        assert_eq!(gas_schedule.entries[0].1, 100);
+        assert_eq!(gas_schedule.entries[1].1, 10000);
+        assert_eq!(
+            mainnet.feature_flags.as_ref().unwrap().disabled,
+            vec![FeatureFlag::CodeDependencyCheck]
+        );
+    }
+
+    #[test]
+    fn test_unknown_gas_parameter() {
+        let file = ReleaseConfigFile::parse(
+            &RELEASE_CONFIG_FILE.replace("txn.min_price_per_gas_unit: 100", "txn.unknown: 100"),
+        )
+        .unwrap();
+        assert!(file.for_network(Network::Mainnet).is_err());
+        assert!(file.for_network(Network::Testnet).is_ok());
+    }
+
+    #[test]
+    fn test_testnet_only_features() {
+        let features = Features {
+            enabled: vec![FeatureFlag::CodeDependencyCheck],
+            disabled: vec![],
+            testnet_only: vec![FeatureFlag::TreatFriendAsPrivate],
+        };
+        for network in [Network::Devnet, Network::Testnet] {
+            let resolved = features.for_network(network).unwrap();
+            assert_eq!(
+                resolved.enabled,
+                vec![
+                    FeatureFlag::CodeDependencyCheck,
+                    FeatureFlag::TreatFriendAsPrivate
+                ]
+            );
+            assert!(resolved.disabled.is_empty());
+        }
+        let mainnet = features.for_network(Network::Mainnet).unwrap();
+        assert_eq!(mainnet.enabled, vec![FeatureFlag::CodeDependencyCheck]);
+        assert_eq!(mainnet.disabled, vec![FeatureFlag::TreatFriendAsPrivate]);
+
+        // A feature can't be both staged and enabled or disabled everywhere
+        let conflicting = Features {
+            disabled: vec![FeatureFlag::TreatFriendAsPrivate],
+            ..features
+        };
+        assert!(conflicting.for_network(Network::Testnet).is_err());
+    }
+
+    #[test]
+    fn test_for_each_network() {
+        let config = ReleaseConfig {
+            feature_flags: Some(Features {
+                enabled: vec![],
+                disabled: vec![],
+                testnet_only: vec![FeatureFlag::TreatFriendAsPrivate],
+            }),
+            ..Default::default()
+        };
+        let configs = config.for_each_network();
+        assert_eq!(
+            configs.iter().map(|c| c.network()).collect::<Vec<_>>(),
+            Network::ALL.to_vec()
+        );
+
+        // Only devnet is upgraded by the core resources account, but testnet still stages the
+        // features
+        let staged = |config: &ReleaseConfig| {
+            config.expected_states("features").unwrap()
+                == vec![ExpectedState::FeatureFlag {
+                    flag: FeatureFlag::TreatFriendAsPrivate,
+                    enabled: true,
+                }]
+        };
+        let (devnet, testnet, mainnet) = (&configs[0], &configs[1], &configs[2]);
+        assert!(devnet.testnet && staged(devnet));
+        assert!(!testnet.testnet && staged(testnet));
+        assert!(!mainnet.testnet && !staged(mainnet));
+
+        // Without a network, `testnet` decides
+        assert_eq!(config.network(), Network::Devnet);
+        assert!(staged(&config));
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ReleaseConfig;
+use anyhow::{anyhow, bail, Result};
+use move_core_types::account_address::AccountAddress;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use url::Url;
+
+/// Options to submit the proposals of a release on chain with the `aptos` CLI
+pub struct ProposeOptions {
+    /// Path of the `aptos` CLI binary
+    pub aptos_cli: PathBuf,
+    /// REST endpoint of the network to submit the proposals to
+    pub endpoint: Url,
+    /// The stake pool backing the proposals, whose delegated voter is the proposer
+    pub pool_address: AccountAddress,
+    /// The CLI profile of the proposer, i.e. the delegated voter of the stake pool
+    pub profile: String,
+    /// Location of the JSON metadata of the proposals
+    pub metadata_url: Url,
+}
+
+/// A proposal created for a release, along with the scripts to execute once it's approved
+#[derive(Debug)]
+pub struct SubmittedProposal {
+    pub proposal_id: u64,
+    pub scripts: Vec<PathBuf>,
+}
+
+/// Returns the proposal scripts in the given directory, in the order they must be executed.
+/// The manifests written next to the scripts are skipped.
+pub fn proposal_scripts(scripts_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut scripts = fs::read_dir(scripts_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == "move")
+        })
+        .collect::<Vec<_>>();
+    // Scripts are prefixed with their index
+    scripts.sort_by_key(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('-').next())
+            .and_then(|index| index.parse::<usize>().ok())
+    });
+    Ok(scripts)
+}
+
+impl ReleaseConfig {
+    /// Generates the scripts of the release into `output_dir`, and creates the governance
+    /// proposals executing them. A multi-step release is a single proposal, whose scripts are
+    /// executed in order; otherwise each script is a proposal of its own.
+    pub fn propose(
+        &self,
+        output_dir: &Path,
+        options: &ProposeOptions,
+    ) -> Result<Vec<SubmittedProposal>> {
+        // Configs already set on chain are skipped
+        let config = Self {
+            remote_endpoint: Some(
+                self.remote_endpoint
+                    .clone()
+                    .unwrap_or_else(|| options.endpoint.clone()),
+            ),
+            ..self.clone()
+        };
+        config.generate_release_proposal_scripts(output_dir)?;
+
+        let scripts = proposal_scripts(output_dir)?;
+        if scripts.is_empty() {
+            bail!("The release doesn't change anything on chain, no proposal to submit");
+        }
+
+        if self.is_multi_step {
+            let proposal_id = submit_proposal(options, &scripts[0], true)?;
+            Ok(vec![SubmittedProposal {
+                proposal_id,
+                scripts,
+            }])
+        } else {
+            scripts
+                .into_iter()
+                .map(|script| {
+                    let proposal_id = submit_proposal(options, &script, false)?;
+                    Ok(SubmittedProposal {
+                        proposal_id,
+                        scripts: vec![script],
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Creates a proposal for the given script with `aptos governance propose`, which computes the
+/// execution hash of the script and the hash of the metadata. Returns the proposal id.
+fn submit_proposal(options: &ProposeOptions, script: &Path, is_multi_step: bool) -> Result<u64> {
+    let mut args = vec![
+        "governance".to_string(),
+        "propose".to_string(),
+        "--script-path".to_string(),
+        script.display().to_string(),
+        "--pool-address".to_string(),
+        options.pool_address.to_hex_literal(),
+        "--metadata-url".to_string(),
+        options.metadata_url.to_string(),
+        "--profile".to_string(),
+        options.profile.clone(),
+        "--url".to_string(),
+        options.endpoint.to_string(),
+        "--assume-yes".to_string(),
+    ];
+    if is_multi_step {
+        args.push("--is-multi-step".to_string());
+    }
+
+    let output = Command::new(&options.aptos_cli).args(&args).output()?;
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).map_err(|err| {
+        anyhow!(
+            "Failed to parse the output of the proposal of {}: {:?}\n{}",
+            script.display(),
+            err,
+            String::from_utf8_lossy(&output.stderr)
+        )
+    })?;
+    if !output.status.success() {
+        bail!(
+            "Failed to create the proposal of {}: {}",
+            script.display(),
+            response["Error"]
+        );
+    }
+    response["Result"]["proposal_id"].as_u64().ok_or_else(|| {
+        anyhow!(
+            "No proposal id in the output of the proposal of {}: {}",
+            script.display(),
+            response
+        )
+    })
+}
+
+/// Returns the commands voters and the proposer run to vote on and execute the proposals
+pub fn voting_instructions(proposals: &[SubmittedProposal], options: &ProposeOptions) -> String {
+    let mut instructions = String::new();
+    for proposal in proposals {
+        instructions += &format!(
+            "Proposal {}:\n  Vote with:\n    aptos governance vote --proposal-id {} --pool-addresses <POOL_ADDRESS> --yes --url {}\n  Once approved, execute with:\n",
+            proposal.proposal_id, proposal.proposal_id, options.endpoint
+        );
+        for script in &proposal.scripts {
+            instructions += &format!(
+                "    aptos governance execute-proposal --proposal-id {} --script-path {} --profile {} --url {}\n",
+                proposal.proposal_id,
+                script.display(),
+                options.profile,
+                options.endpoint
+            );
+        }
+    }
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_proposal_scripts_order() {
+        let scripts_dir = TempPath::new();
+        scripts_dir.create_as_dir().unwrap();
+        for name in [
+            "10-gas-price-floor.move",
+            "2-version.move",
+            "2-version.manifest.yaml",
+            "0-framework.move",
+        ] {
+            fs::write(scripts_dir.path().join(name), "").unwrap();
+        }
+
+        let scripts: Vec<_> = proposal_scripts(scripts_dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            scripts,
+            vec![
+                "0-framework.move",
+                "2-version.move",
+                "10-gas-price-floor.move"
+            ]
+        );
+    }
+}
@@ -0,0 +1,460 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    propose::proposal_scripts, utils::compile_script, verification::GOVERNANCE_VOTING_FORUM,
+    ReleaseConfig,
+};
+use anyhow::{anyhow, Result};
+use aptos_crypto::HashValue;
+use aptos_debugger::execute_script_as;
+use aptos_rest_client::Client;
+use aptos_state_view::{StateView, StateViewId, TStateView};
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{aptos_test_root_address, CORE_CODE_ADDRESS},
+    event::EventHandle,
+    on_chain_config::{Features, GasScheduleV2, OnChainConfig, OnChainConsensusConfig, Version},
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, table::TableHandle,
+    },
+    transaction::{Script, TransactionArgument},
+    write_set::{WriteOp, WriteSet},
+};
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
+};
+use aptos_vm::data_cache::StorageAdapter;
+use futures::executor::block_on;
+use move_core_types::{account_address::AccountAddress, parser::parse_struct_tag};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use url::Url;
+
+/// The gas budget of each script, i.e. the default maximum gas of a transaction
+const SIMULATION_GAS_BUDGET: u64 = 2_000_000;
+
+/// The state a release is simulated against
+pub enum SimulationSource {
+    /// The latest state of the network behind the REST endpoint
+    Rest(Url),
+    /// The latest state of a local AptosDB checkpoint
+    Db(PathBuf),
+}
+
+/// The outcome of the simulation of a release
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    /// The ledger version the release is simulated on top of
+    pub version: u64,
+    pub scripts: Vec<ScriptSimulation>,
+    /// The on chain configs once all the scripts are executed
+    pub resulting_configs: ResultingConfigs,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptSimulation {
+    pub script: String,
+    pub gas_used: u64,
+    /// The types of the events emitted by the script
+    pub events: Vec<String>,
+    pub num_writes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultingConfigs {
+    pub gas_schedule: Option<GasScheduleV2>,
+    pub version: Option<u64>,
+    pub enabled_features: Vec<u64>,
+    pub consensus_config: Option<OnChainConsensusConfig>,
+}
+
+/// A view of a forked state, with the writes of the simulated scripts applied on top
+struct ForkedStateView<S> {
+    base: S,
+    writes: HashMap<StateKey, Option<Vec<u8>>>,
+}
+
+impl<S: StateView> TStateView for ForkedStateView<S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        match self.writes.get(state_key) {
+            Some(value) => Ok(value.clone()),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage> {
+        Ok(StateStorageUsage::new_untracked())
+    }
+}
+
+impl<S: StateView> ForkedStateView<S> {
+    fn new(base: S) -> Self {
+        Self {
+            base,
+            writes: HashMap::new(),
+        }
+    }
+
+    fn apply(&mut self, write_set: &WriteSet) {
+        for (state_key, write_op) in write_set.iter() {
+            let value = match write_op {
+                WriteOp::Creation(data) | WriteOp::Modification(data) => Some(data.clone()),
+                WriteOp::Deletion => None,
+            };
+            self.writes.insert(state_key.clone(), value);
+        }
+    }
+
+    /// Adds a governance proposal executing the script of the given hash, in the state it'd be
+    /// in once voted for and resolvable, and returns its id. A multi-step proposal is chained
+    /// by its scripts, so it only needs to be approved for its first one.
+    fn approve_proposal(&mut self, execution_hash: Vec<u8>, is_multi_step: bool) -> Result<u64> {
+        let forum_key = StateKey::access_path(AccessPath::new(
+            CORE_CODE_ADDRESS,
+            AccessPath::resource_path_vec(parse_struct_tag(GOVERNANCE_VOTING_FORUM)?),
+        ));
+        let mut forum: VotingForum = bcs::from_bytes(
+            &self
+                .get_state_value(&forum_key)?
+                .ok_or_else(|| anyhow!("The governance voting forum doesn't exist"))?,
+        )?;
+        let proposal_id = forum.next_proposal_id;
+
+        let mut metadata = vec![(
+            RESOLVABLE_TIME_METADATA_KEY.to_string(),
+            bcs::to_bytes(&0u64)?,
+        )];
+        if is_multi_step {
+            metadata.push((
+                IS_MULTI_STEP_PROPOSAL_KEY.to_string(),
+                bcs::to_bytes(&true)?,
+            ));
+            metadata.push((
+                IS_MULTI_STEP_PROPOSAL_IN_EXECUTION_KEY.to_string(),
+                bcs::to_bytes(&false)?,
+            ));
+        }
+        let proposal = Proposal {
+            proposer: CORE_CODE_ADDRESS,
+            execution_content: Some(GovernanceProposal { dummy_field: false }),
+            metadata,
+            creation_time_secs: 0,
+            execution_hash,
+            min_vote_threshold: 0,
+            // The voting period is over, and the single vote passes the proposal
+            expiration_secs: 0,
+            early_resolution_vote_threshold: None,
+            yes_votes: 1,
+            no_votes: 0,
+            is_resolved: false,
+            resolution_time_secs: 0,
+        };
+        self.writes.insert(
+            StateKey::table_item(forum.proposals, bcs::to_bytes(&proposal_id)?),
+            Some(bcs::to_bytes(&proposal)?),
+        );
+        forum.next_proposal_id += 1;
+        self.writes.insert(forum_key, Some(bcs::to_bytes(&forum)?));
+        Ok(proposal_id)
+    }
+}
+
+const RESOLVABLE_TIME_METADATA_KEY: &str = "RESOLVABLE_TIME_METADATA_KEY";
+const IS_MULTI_STEP_PROPOSAL_KEY: &str = "IS_MULTI_STEP_PROPOSAL_KEY";
+const IS_MULTI_STEP_PROPOSAL_IN_EXECUTION_KEY: &str = "IS_MULTI_STEP_PROPOSAL_IN_EXECUTION";
+
+/// `0x1::voting::VotingForum<GovernanceProposal>`
+#[derive(Deserialize, Serialize)]
+struct VotingForum {
+    proposals: TableHandle,
+    events: VotingEvents,
+    next_proposal_id: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+struct VotingEvents {
+    create_proposal_events: EventHandle,
+    register_forum_events: EventHandle,
+    resolve_proposal_events: EventHandle,
+    vote_events: EventHandle,
+}
+
+/// `0x1::voting::Proposal<GovernanceProposal>`
+#[derive(Deserialize, Serialize)]
+struct Proposal {
+    proposer: AccountAddress,
+    execution_content: Option<GovernanceProposal>,
+    /// The entries of the `SimpleMap`
+    metadata: Vec<(String, Vec<u8>)>,
+    creation_time_secs: u64,
+    execution_hash: Vec<u8>,
+    min_vote_threshold: u128,
+    expiration_secs: u64,
+    early_resolution_vote_threshold: Option<u128>,
+    yes_votes: u128,
+    no_votes: u128,
+    is_resolved: bool,
+    resolution_time_secs: u64,
+}
+
+/// `0x1::governance_proposal::GovernanceProposal`, whose only field is the one the compiler adds
+/// to structs without fields
+#[derive(Deserialize, Serialize)]
+struct GovernanceProposal {
+    dummy_field: bool,
+}
+
+impl ReleaseConfig {
+    /// Generates the scripts of the release into `output_dir` and executes them in order on top
+    /// of the given state, without submitting anything. The scripts are those of the network
+    /// of the release: scripts run by the core resources account are executed by it, while
+    /// governance proposals are approved in the forked state, as if they had been voted for,
+    /// and resolved by their scripts.
+    pub fn simulate(
+        &self,
+        output_dir: &Path,
+        source: SimulationSource,
+    ) -> Result<SimulationReport> {
+        let (interface, remote_endpoint): (Arc<dyn AptosValidatorInterface + Send>, _) =
+            match source {
+                SimulationSource::Rest(endpoint) => (
+                    Arc::new(RestDebuggerInterface::new(Client::new(endpoint.clone()))),
+                    Some(endpoint),
+                ),
+                SimulationSource::Db(path) => (Arc::new(DBDebuggerInterface::open(path)?), None),
+            };
+        let version = block_on(interface.get_latest_version())?;
+
+        Self {
+            remote_endpoint: remote_endpoint.or_else(|| self.remote_endpoint.clone()),
+            ..self.clone()
+        }
+        .generate_release_proposal_scripts(output_dir)?;
+
+        let mut state_view = ForkedStateView::new(DebuggerStateView::new(interface, version));
+        let scripts = self.simulate_scripts(&proposal_scripts(output_dir)?, &mut state_view)?;
+        Ok(SimulationReport {
+            version,
+            scripts,
+            resulting_configs: resulting_configs(&state_view),
+        })
+    }
+
+    fn simulate_scripts<S: StateView>(
+        &self,
+        script_paths: &[PathBuf],
+        state_view: &mut ForkedStateView<S>,
+    ) -> Result<Vec<ScriptSimulation>> {
+        // Multi-step proposals are always resolved through governance
+        let by_core_resources = self.testnet && !self.is_multi_step;
+        let mut proposal_id = None;
+        let mut scripts = vec![];
+        for script_path in script_paths {
+            let code = compile_script(script_path)?;
+            let (sender, args) = if by_core_resources {
+                (aptos_test_root_address(), vec![])
+            } else {
+                let id = match proposal_id {
+                    Some(id) if self.is_multi_step => id,
+                    _ => state_view.approve_proposal(
+                        HashValue::sha3_256_of(&code).to_vec(),
+                        self.is_multi_step,
+                    )?,
+                };
+                proposal_id = Some(id);
+                // Anyone can resolve an approved proposal
+                (CORE_CODE_ADDRESS, vec![TransactionArgument::U64(id)])
+            };
+            let output = execute_script_as(
+                &*state_view,
+                sender,
+                &Script::new(code, vec![], args),
+                SIMULATION_GAS_BUDGET,
+            )
+            .map_err(|err| anyhow!("Failed to execute {}: {:?}", script_path.display(), err))?;
+
+            state_view.apply(output.write_set());
+            scripts.push(ScriptSimulation {
+                script: script_path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                gas_used: output.gas_used(),
+                events: output
+                    .events()
+                    .iter()
+                    .map(|event| event.type_tag().to_string())
+                    .collect(),
+                num_writes: output.write_set().iter().count(),
+            });
+        }
+        Ok(scripts)
+    }
+}
+
+fn resulting_configs(state_view: &impl StateView) -> ResultingConfigs {
+    let storage = StorageAdapter::new(state_view);
+    let enabled_features = Features::fetch_config(&storage)
+        .map(|features| {
+            features
+                .features
+                .iter()
+                .enumerate()
+                .flat_map(|(byte_index, byte)| {
+                    (0..8)
+                        .filter(move |bit| byte & (1 << bit) != 0)
+                        .map(move |bit| (byte_index * 8 + bit) as u64)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    ResultingConfigs {
+        gas_schedule: GasScheduleV2::fetch_config(&storage),
+        version: Version::fetch_config(&storage).map(|version| version.major),
+        enabled_features,
+        consensus_config: OnChainConsensusConfig::fetch_config(&storage),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network_config::Network;
+    use aptos_language_e2e_tests::data_store::FakeDataStore;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        timestamp::{Timestamp, TimestampResource},
+        transaction::ChangeSet,
+    };
+    use aptos_vm_genesis::{
+        generate_genesis_change_set_for_mainnet, generate_genesis_change_set_for_testing,
+        GenesisOptions,
+    };
+    use move_core_types::move_resource::MoveStructType;
+
+    /// The state after genesis, a second later, so that proposals can be resolved
+    fn genesis_state(genesis: ChangeSet) -> ForkedStateView<FakeDataStore> {
+        let mut state = FakeDataStore::default();
+        state.add_write_set(genesis.write_set());
+        state.set(
+            StateKey::access_path(AccessPath::new(
+                CORE_CODE_ADDRESS,
+                AccessPath::resource_path_vec(TimestampResource::struct_tag()),
+            )),
+            bcs::to_bytes(&TimestampResource {
+                timestamp: Timestamp {
+                    microseconds: 1_000_000,
+                },
+            })
+            .unwrap(),
+        );
+        ForkedStateView::new(state)
+    }
+
+    /// Generates the scripts of the release, and simulates them on top of `state_view`
+    fn simulate(
+        config: &ReleaseConfig,
+        state_view: &mut ForkedStateView<FakeDataStore>,
+    ) -> Vec<ScriptSimulation> {
+        let output_dir = TempPath::new();
+        output_dir.create_as_dir().unwrap();
+        config
+            .generate_release_proposal_scripts(output_dir.path())
+            .unwrap();
+        config
+            .simulate_scripts(&proposal_scripts(output_dir.path()).unwrap(), state_view)
+            .unwrap()
+    }
+
+    fn version_bump(state_view: &impl StateView, network: Network) -> ReleaseConfig {
+        let major = Version::fetch_config(&StorageAdapter::new(state_view))
+            .unwrap()
+            .major;
+        ReleaseConfig {
+            testnet: network.has_core_resources(),
+            network: Some(network),
+            framework_release: false,
+            gas_schedule: None,
+            version: Some(Version { major: major + 1 }),
+            consensus_config: None,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_simulate_mainnet_release() {
+        let mut state_view = genesis_state(generate_genesis_change_set_for_mainnet(
+            GenesisOptions::Head,
+        ));
+        let config = version_bump(&state_view, Network::Mainnet);
+        let scripts = simulate(&config, &mut state_view);
+
+        assert_eq!(scripts.len(), 1);
+        assert!(scripts[0]
+            .events
+            .iter()
+            .any(|event| event == "0x1::voting::ResolveProposal"));
+        assert_eq!(
+            resulting_configs(&state_view).version,
+            config.version.map(|version| version.major)
+        );
+    }
+
+    #[test]
+    fn test_simulate_multi_step_mainnet_release() {
+        let mut state_view = genesis_state(generate_genesis_change_set_for_mainnet(
+            GenesisOptions::Head,
+        ));
+        let config = ReleaseConfig {
+            is_multi_step: true,
+            consensus_config: Some(OnChainConsensusConfig::default()),
+            ..version_bump(&state_view, Network::Mainnet)
+        };
+        let scripts = simulate(&config, &mut state_view);
+
+        // Both steps resolve the single proposal approved for the first one
+        assert_eq!(scripts.len(), 2);
+        let resulting_configs = resulting_configs(&state_view);
+        assert_eq!(
+            resulting_configs.version,
+            config.version.map(|version| version.major)
+        );
+        assert_eq!(resulting_configs.consensus_config, config.consensus_config);
+    }
+
+    #[test]
+    fn test_simulate_devnet_release() {
+        let mut state_view = genesis_state(generate_genesis_change_set_for_testing(
+            GenesisOptions::Head,
+        ));
+        let config = version_bump(&state_view, Network::Devnet);
+        let scripts = simulate(&config, &mut state_view);
+
+        // The core resources account doesn't go through governance
+        assert_eq!(scripts.len(), 1);
+        assert!(!scripts[0]
+            .events
+            .iter()
+            .any(|event| event == "0x1::voting::ResolveProposal"));
+        assert_eq!(
+            resulting_configs(&state_view).version,
+            config.version.map(|version| version.major)
+        );
+    }
+}
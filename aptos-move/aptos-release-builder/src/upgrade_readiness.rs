@@ -0,0 +1,66 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail, Result};
+use futures::executor::block_on;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The inspection service endpoint reporting the upgrade readiness of the validators
+const UPGRADE_READINESS_PATH: &str = "upgrade_readiness";
+
+/// Requires a share of the stake to run builds supporting a gas feature version before the
+/// feature flags of a release are enabled. The readiness is read from the inspection service of
+/// a validator, which learns the builds of the other validators through the peer monitoring
+/// service.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct UpgradeReadinessCheck {
+    /// The inspection service of a validator, e.g. http://validator:9101
+    pub inspection_endpoint: Url,
+    /// The gas feature version the validators must support
+    pub gas_feature_version: u64,
+    /// The minimum percentage of the stake that must be ready
+    pub min_ready_stake_percent: u64,
+}
+
+/// The part of the upgrade readiness report the check relies on
+#[derive(Deserialize)]
+struct UpgradeReadinessReport {
+    total_voting_power: u128,
+    ready_voting_power: u128,
+    unknown_voting_power: u128,
+}
+
+impl UpgradeReadinessCheck {
+    pub fn check(&self) -> Result<()> {
+        let mut url = self.inspection_endpoint.join(UPGRADE_READINESS_PATH)?;
+        url.query_pairs_mut()
+            .append_pair("gas_feature_version", &self.gas_feature_version.to_string());
+        let report: UpgradeReadinessReport = block_on(async {
+            reqwest::get(url.clone())
+                .await?
+                .error_for_status()?
+                .json()
+                .await
+        })
+        .map_err(|e| anyhow!("Failed to fetch the upgrade readiness from {}: {}", url, e))?;
+
+        if report.total_voting_power == 0 {
+            bail!(
+                "The upgrade readiness report from {} has no stake, is it a validator?",
+                url
+            );
+        }
+        let ready_stake_percent = report.ready_voting_power * 100 / report.total_voting_power;
+        if ready_stake_percent < self.min_ready_stake_percent as u128 {
+            bail!(
+                "Only {}% of the stake runs builds supporting gas feature version {} ({}% unknown), {}% is required",
+                ready_stake_percent,
+                self.gas_feature_version,
+                report.unknown_voting_power * 100 / report.total_voting_power,
+                self.min_ready_stake_percent,
+            );
+        }
+        Ok(())
+    }
+}
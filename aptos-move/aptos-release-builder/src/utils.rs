@@ -1,8 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::{bail, Result};
+use aptos_crypto::HashValue;
+use aptos_framework::{unzip_metadata_str, BuildOptions, BuiltPackage, ReleaseBundle};
+use aptos_temppath::TempPath;
 use move_core_types::account_address::AccountAddress;
 use move_model::{code_writer::CodeWriter, emit, emitln};
+use std::{fs, path::Path};
 
 pub(crate) fn generate_blob(writer: &CodeWriter, data: &[u8]) {
     emitln!(writer, "vector[");
@@ -35,7 +40,6 @@ pub(crate) fn generate_next_execution_hash_blob(
                 next_execution_hash,
             );
     } else {
-        let next_execution_hash_bytes = next_execution_hash.as_bytes();
         emitln!(
             writer,
             "let framework_signer = aptos_governance::resolve_multi_step_proposal("
@@ -43,13 +47,17 @@ pub(crate) fn generate_next_execution_hash_blob(
         writer.indent();
         emitln!(writer, "proposal_id,");
         emitln!(writer, "@{},", for_address);
-        emit!(writer, "vector[");
-        for (_, b) in next_execution_hash_bytes.iter().enumerate() {
-            emit!(writer, "{}u8,", b);
-        }
-        emitln!(writer, "],");
+        emitln!(writer, "x\"{}\",", next_execution_hash);
         writer.unindent();
-        emitln!(writer, "};");
+        emitln!(writer, ");");
+    }
+}
+
+/// Emits the `use` of the module the proposal depends on, unless it's the governance module which
+/// every proposal already uses.
+fn emit_deps(writer: &CodeWriter, deps_name: &str) {
+    if deps_name != "aptos_framework::aptos_governance" {
+        emitln!(writer, "use {};", deps_name);
     }
 }
 
@@ -63,7 +71,7 @@ pub(crate) fn generate_governance_proposal_header(
     writer.indent();
 
     emitln!(writer, "use aptos_framework::aptos_governance;");
-    emitln!(writer, "use {};", deps_name);
+    emit_deps(writer, deps_name);
     emitln!(writer);
 
     emitln!(writer, "fun main(proposal_id: u64) {");
@@ -89,7 +97,7 @@ pub(crate) fn generate_testnet_header(writer: &CodeWriter, deps_name: &str) {
     writer.indent();
 
     emitln!(writer, "use aptos_framework::aptos_governance;");
-    emitln!(writer, "use {};", deps_name);
+    emit_deps(writer, deps_name);
     emitln!(writer);
 
     emitln!(writer, "fun main(core_resources: &signer) {");
@@ -136,3 +144,92 @@ where
     body(writer);
     finish_with_footer(writer)
 }
+
+/// Compiles a proposal script against the framework of this build. The framework sources are
+/// taken from the release bundle built into the binary, so that scripts can be compiled without
+/// a checkout of the repository.
+pub(crate) fn compile_script(script_path: &Path) -> Result<Vec<u8>> {
+    let package_dir = TempPath::new();
+    package_dir.create_as_dir()?;
+    let framework_dir = package_dir.path().join("framework");
+    write_bundle_sources(aptos_cached_packages::head_release_bundle(), &framework_dir)?;
+
+    let script_dir = package_dir.path().join("script");
+    let sources_dir = script_dir.join("sources");
+    fs::create_dir_all(&sources_dir)?;
+    fs::write(
+        script_dir.join("Move.toml"),
+        format!(
+            "[package]\nname = \"ReleaseScript\"\nversion = \"1.0.0\"\n\n[dependencies]\nAptosFramework = {{ local = \"{}\" }}\n",
+            framework_dir.join("AptosFramework").display()
+        ),
+    )?;
+    fs::copy(script_path, sources_dir.join("script.move"))?;
+
+    let package = BuiltPackage::build(
+        script_dir,
+        BuildOptions {
+            with_srcs: false,
+            with_abis: false,
+            with_source_maps: false,
+            with_error_map: false,
+            skip_fetch_latest_git_deps: true,
+            ..BuildOptions::default()
+        },
+    )?;
+    match package.extract_script_code().pop() {
+        Some(code) => Ok(code),
+        None => bail!("No script found in {}", script_path.display()),
+    }
+}
+
+/// Returns the hash a governance proposal must approve to be resolved by the given script, i.e.
+/// the hash of its bytecode.
+pub(crate) fn script_execution_hash(script: &str) -> Result<HashValue> {
+    let script_path = TempPath::new();
+    script_path.create_as_file()?;
+    fs::write(script_path.path(), script)?;
+    Ok(HashValue::sha3_256_of(&compile_script(script_path.path())?))
+}
+
+/// Writes the sources of each package of the bundle into a directory named after the package,
+/// with the dependencies of their manifest pointing to the packages next to them.
+fn write_bundle_sources(bundle: &ReleaseBundle, dir: &Path) -> Result<()> {
+    for package in &bundle.packages {
+        let metadata = package.package_metadata();
+        let package_dir = dir.join(&metadata.name);
+        let sources_dir = package_dir.join("sources");
+        fs::create_dir_all(&sources_dir)?;
+
+        let mut manifest: toml::Value = toml::from_str(&unzip_metadata_str(&metadata.manifest)?)?;
+        if let Some(dependencies) = manifest
+            .get_mut("dependencies")
+            .and_then(|dependencies| dependencies.as_table_mut())
+        {
+            for (name, dependency) in dependencies.iter_mut() {
+                let mut local = toml::value::Table::new();
+                local.insert(
+                    "local".to_string(),
+                    toml::Value::String(format!("../{}", name)),
+                );
+                *dependency = toml::Value::Table(local);
+            }
+        }
+        fs::write(package_dir.join("Move.toml"), toml::to_string(&manifest)?)?;
+
+        for module in &metadata.modules {
+            if module.source.is_empty() {
+                bail!(
+                    "The sources of {}::{} aren't in the release bundle",
+                    metadata.name,
+                    module.name
+                );
+            }
+            fs::write(
+                sources_dir.join(format!("{}.move", module.name)),
+                unzip_metadata_str(&module.source)?,
+            )?;
+        }
+    }
+    Ok(())
+}
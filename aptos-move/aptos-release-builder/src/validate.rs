@@ -0,0 +1,147 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{components::framework, verification::Difference, ReleaseConfig};
+use anyhow::Result;
+use aptos_framework::natives::code::PackageRegistry;
+use aptos_gas::{AptosGasParameters, GasScheduleDiff};
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_address::AccountAddress, account_config::CORE_CODE_ADDRESS,
+    on_chain_config::GasScheduleV2,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// The scripts whose expected states are compared against the chain. The proposal aborting a
+/// superseded proposal isn't a config and is left out.
+const CONFIG_COMPONENTS: [&str; 6] = [
+    "gas-schedule",
+    "version",
+    "features",
+    "consensus-config",
+    "transaction-deny-list",
+    "gas-price-floor",
+];
+
+/// The accounts the framework packages are published at
+const FRAMEWORK_PACKAGE_ADDRESSES: [&str; 2] = ["0x1", "0x3"];
+
+/// The differences between a release config and the state of a network
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidationReport {
+    pub endpoint: Url,
+    pub differences: Vec<ComponentDifference>,
+}
+
+/// A part of the release that isn't in effect on chain
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ComponentDifference {
+    /// The name of the script of the release applying the component, e.g. `gas-schedule`
+    pub component: String,
+    pub difference: Difference,
+}
+
+impl ValidationReport {
+    /// Whether everything in the release is already in effect on chain
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+impl ReleaseConfig {
+    /// Compares the release against the state of the network behind the given REST endpoint,
+    /// i.e. the gas schedule, feature flags and other configs, and the framework packages if the
+    /// release upgrades the framework. Differences are what the release would change on chain.
+    pub async fn validate_against_rest_endpoint(&self, endpoint: Url) -> Result<ValidationReport> {
+        let client = Client::new(endpoint.clone());
+        let mut differences = vec![];
+
+        if self.framework_release {
+            let packages = framework::release_package_names(&self.framework_packages)?;
+            for difference in framework_differences(&client, &packages).await? {
+                differences.push(ComponentDifference {
+                    component: "framework".to_string(),
+                    difference,
+                });
+            }
+        }
+
+        for component in CONFIG_COMPONENTS {
+            for expected in self.expected_states(component)? {
+                if let Some(difference) = expected.verify(&client).await? {
+                    differences.push(ComponentDifference {
+                        component: component.to_string(),
+                        difference,
+                    });
+                }
+            }
+        }
+
+        Ok(ValidationReport {
+            endpoint,
+            differences,
+        })
+    }
+
+    /// Returns the gas parameters the release would change on the network behind the given REST
+    /// endpoint, or `None` if the release doesn't set the gas schedule.
+    pub async fn gas_schedule_diff(&self, endpoint: Url) -> Result<Option<GasScheduleDiff>> {
+        let gas_schedule = match &self.gas_schedule {
+            Some(gas_schedule) => gas_schedule,
+            None => return Ok(None),
+        };
+        let on_chain = Client::new(endpoint)
+            .get_account_resource_bcs::<GasScheduleV2>(
+                CORE_CODE_ADDRESS,
+                "0x1::gas_schedule::GasScheduleV2",
+            )
+            .await?
+            .into_inner();
+        Ok(Some(AptosGasParameters::diff(
+            &on_chain.entries,
+            &gas_schedule.entries,
+        )))
+    }
+}
+
+/// Compares the given framework packages of this build against the packages published on chain,
+/// through the digest of their sources.
+async fn framework_differences(client: &Client, packages: &[String]) -> Result<Vec<Difference>> {
+    let mut on_chain_packages = BTreeMap::new();
+    for address in FRAMEWORK_PACKAGE_ADDRESSES {
+        let address = AccountAddress::from_hex_literal(address)?;
+        let registry = client
+            .get_account_resource_bcs::<PackageRegistry>(address, "0x1::code::PackageRegistry")
+            .await?
+            .into_inner();
+        for package in registry.packages {
+            on_chain_packages.insert(package.name.clone(), (address, package));
+        }
+    }
+
+    let mut differences = vec![];
+    for package in &aptos_cached_packages::head_release_bundle().packages {
+        let expected = &package.metadata;
+        if !packages.contains(&expected.name) {
+            continue;
+        }
+        match on_chain_packages.get(&expected.name) {
+            None => differences.push(Difference::PackageNotPublished {
+                package: expected.name.clone(),
+            }),
+            Some((address, on_chain)) if on_chain.source_digest != expected.source_digest => {
+                differences.push(Difference::PackageDiffers {
+                    package: expected.name.clone(),
+                    address: *address,
+                    expected_source_digest: expected.source_digest.clone(),
+                    found_source_digest: on_chain.source_digest.clone(),
+                    upgrade_number: on_chain.upgrade_number,
+                })
+            }
+            Some(_) => (),
+        }
+    }
+    Ok(differences)
+}
@@ -0,0 +1,523 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    components::{feature_flags::FeatureFlag, gas_price_floor::GasPriceFloorConfig},
+    utils::compile_script,
+};
+use anyhow::{anyhow, bail, Result};
+use aptos_crypto::HashValue;
+use aptos_gas::{AptosGasParameters, GasScheduleDiff};
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{
+        FeatureFlag as AptosFeatureFlag, Features as AptosFeatures, GasPriceFloor, GasScheduleV2,
+        OnChainConfig, OnChainConsensusConfig, TransactionDenyList, Version,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// The extension of the manifest generated next to each proposal script
+pub const MANIFEST_EXTENSION: &str = "manifest.yaml";
+
+/// The on chain state expected once a proposal script has been executed, used to check that
+/// the execution had the intended effect.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ExecutionManifest {
+    /// The name of the proposal script the manifest belongs to
+    pub proposal: String,
+    pub expected: Vec<ExpectedState>,
+}
+
+/// A single expectation on the on chain state
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpectedState {
+    GasSchedule(GasScheduleV2),
+    Version(Version),
+    FeatureFlag {
+        flag: FeatureFlag,
+        enabled: bool,
+    },
+    ConsensusConfig(OnChainConsensusConfig),
+    TransactionDenyList(TransactionDenyList),
+    GasPriceFloor(GasPriceFloorConfig),
+    /// The multi-step proposal was aborted: it's resolved and none of its steps is approved
+    ProposalAborted {
+        proposal_id: u64,
+    },
+}
+
+/// The execution hashes approved by governance, see `aptos_governance::ApprovedExecutionHashes`
+#[derive(Deserialize)]
+struct ApprovedExecutionHashes {
+    hashes: Vec<(u64, Vec<u8>)>,
+}
+
+/// The hash a multi-step proposal script resolves the proposal with when it's the last step
+const EMPTY_EXECUTION_HASH: &str = "vector::empty<u8>()";
+
+pub(crate) const GOVERNANCE_VOTING_FORUM: &str =
+    "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>";
+const GOVERNANCE_PROPOSAL: &str =
+    "0x1::voting::Proposal<0x1::governance_proposal::GovernanceProposal>";
+
+/// Returns the execution hash currently approved for the given governance proposal, i.e. the hash
+/// of the script that can execute its next step.
+pub async fn approved_execution_hash(client: &Client, proposal_id: u64) -> Result<Option<Vec<u8>>> {
+    let approved_hashes = client
+        .get_account_resource_bcs::<ApprovedExecutionHashes>(
+            CORE_CODE_ADDRESS,
+            "0x1::aptos_governance::ApprovedExecutionHashes",
+        )
+        .await?
+        .into_inner();
+    Ok(approved_hashes
+        .hashes
+        .into_iter()
+        .find(|(id, _)| *id == proposal_id)
+        .map(|(_, hash)| hash))
+}
+
+/// Returns whether the given governance proposal is resolved, i.e. none of its steps can be
+/// executed anymore.
+pub async fn is_proposal_resolved(client: &Client, proposal_id: u64) -> Result<bool> {
+    let proposal = fetch_governance_proposal(client, proposal_id).await?;
+    proposal["is_resolved"]
+        .as_bool()
+        .ok_or_else(|| anyhow!("Failed to parse governance proposal {}", proposal_id))
+}
+
+/// Returns whether voting on the given governance proposal is closed, i.e. its voting period is
+/// over or enough votes were cast to resolve it early, as `0x1::voting::is_voting_closed`.
+pub async fn is_voting_closed(client: &Client, proposal_id: u64) -> Result<bool> {
+    let proposal = fetch_governance_proposal(client, proposal_id).await?;
+    let parse_u128 = |value: &serde_json::Value| {
+        value
+            .as_str()
+            .and_then(|value| value.parse::<u128>().ok())
+            .ok_or_else(|| anyhow!("Failed to parse governance proposal {}", proposal_id))
+    };
+    // Options are represented as vectors of at most one element
+    if let Some(threshold) = proposal["early_resolution_vote_threshold"]["vec"]
+        .as_array()
+        .and_then(|threshold| threshold.first())
+    {
+        let threshold = parse_u128(threshold)?;
+        if parse_u128(&proposal["yes_votes"])? >= threshold
+            || parse_u128(&proposal["no_votes"])? >= threshold
+        {
+            return Ok(true);
+        }
+    }
+    let expiration_secs = parse_u128(&proposal["expiration_secs"])? as u64;
+    let now_secs = client
+        .get_ledger_information()
+        .await?
+        .into_inner()
+        .timestamp_usecs
+        / 1_000_000;
+    Ok(now_secs > expiration_secs)
+}
+
+/// Returns the JSON representation of the given governance proposal
+pub(crate) async fn fetch_governance_proposal(
+    client: &Client,
+    proposal_id: u64,
+) -> Result<serde_json::Value> {
+    let forum = client
+        .get_account_resource(CORE_CODE_ADDRESS, GOVERNANCE_VOTING_FORUM)
+        .await?
+        .into_inner()
+        .ok_or_else(|| anyhow!("The governance voting forum doesn't exist"))?;
+    let table_handle: AccountAddress = forum.data["proposals"]["handle"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Failed to find the governance proposals table"))?
+        .parse()?;
+    Ok(client
+        .get_table_item(
+            table_handle,
+            "u64",
+            GOVERNANCE_PROPOSAL,
+            proposal_id.to_string(),
+        )
+        .await?
+        .into_inner())
+}
+
+async fn fetch_config<T: OnChainConfig>(client: &Client) -> Result<T> {
+    let bytes = client
+        .get_account_resource_bytes(
+            CORE_CODE_ADDRESS,
+            format!(
+                "{}::{}::{}",
+                T::ADDRESS,
+                T::MODULE_IDENTIFIER,
+                T::TYPE_IDENTIFIER
+            )
+            .as_str(),
+        )
+        .await?;
+    T::deserialize_into_config(bytes.inner())
+}
+
+/// How the state of the chain differs from what a release expects
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difference {
+    GasSchedule {
+        expected_feature_version: u64,
+        found_feature_version: u64,
+        /// The entries the release would change, from the on chain values to the expected ones
+        entries: GasScheduleDiff,
+    },
+    Version {
+        expected: u64,
+        found: u64,
+    },
+    FeatureFlag {
+        flag: FeatureFlag,
+        expected_enabled: bool,
+        found_enabled: bool,
+    },
+    ConsensusConfig {
+        expected: OnChainConsensusConfig,
+        found: OnChainConsensusConfig,
+    },
+    TransactionDenyList {
+        expected: TransactionDenyList,
+        found: TransactionDenyList,
+    },
+    GasPriceFloor {
+        expected: GasPriceFloorConfig,
+        found: GasPriceFloorConfig,
+    },
+    /// The multi-step proposal isn't aborted: a step is still approved, or it isn't resolved
+    ProposalNotAborted {
+        proposal_id: u64,
+        approved_execution_hash: Option<Vec<u8>>,
+    },
+    /// A framework package of the release isn't published
+    PackageNotPublished {
+        package: String,
+    },
+    /// A framework package of the release is published with other sources
+    PackageDiffers {
+        package: String,
+        address: AccountAddress,
+        expected_source_digest: String,
+        found_source_digest: String,
+        upgrade_number: u64,
+    },
+}
+
+fn enabled_or_disabled(enabled: bool) -> &'static str {
+    if enabled {
+        "enabled"
+    } else {
+        "disabled"
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difference::GasSchedule {
+                expected_feature_version,
+                found_feature_version,
+                entries,
+            } => write!(
+                f,
+                "Gas schedule differs, expected feature version {} but found {}, {} entries differ:\n{}",
+                expected_feature_version,
+                found_feature_version,
+                entries.changes.len(),
+                entries
+            ),
+            Difference::Version { expected, found } => {
+                write!(f, "Version differs, expected {} but found {}", expected, found)
+            }
+            Difference::FeatureFlag {
+                flag,
+                expected_enabled,
+                found_enabled,
+            } => write!(
+                f,
+                "Feature {:?} is expected to be {} but is {}",
+                flag,
+                enabled_or_disabled(*expected_enabled),
+                enabled_or_disabled(*found_enabled)
+            ),
+            Difference::ConsensusConfig { expected, found } => write!(
+                f,
+                "Consensus config differs, expected {:?} but found {:?}",
+                expected, found
+            ),
+            Difference::TransactionDenyList { expected, found } => write!(
+                f,
+                "Transaction deny list differs, expected {:?} but found {:?}",
+                expected, found
+            ),
+            Difference::GasPriceFloor { expected, found } => write!(
+                f,
+                "Gas price floor differs, expected {} from epoch {} but found {} from epoch {}",
+                expected.min_gas_unit_price,
+                expected.activation_epoch,
+                found.min_gas_unit_price,
+                found.activation_epoch
+            ),
+            Difference::ProposalNotAborted {
+                proposal_id,
+                approved_execution_hash: Some(hash),
+            } => write!(
+                f,
+                "Proposal {} is expected to be aborted but the execution hash {} is still approved",
+                proposal_id,
+                hex::encode(hash)
+            ),
+            Difference::ProposalNotAborted {
+                proposal_id,
+                approved_execution_hash: None,
+            } => write!(
+                f,
+                "Proposal {} is expected to be aborted but it's not resolved",
+                proposal_id
+            ),
+            Difference::PackageNotPublished { package } => {
+                write!(f, "Package {} isn't published", package)
+            }
+            Difference::PackageDiffers {
+                package,
+                address,
+                expected_source_digest,
+                found_source_digest,
+                upgrade_number,
+            } => write!(
+                f,
+                "Package {} at {} differs, expected source digest {} but found {} (upgrade number {})",
+                package,
+                address.to_hex_literal(),
+                expected_source_digest,
+                found_source_digest,
+                upgrade_number
+            ),
+        }
+    }
+}
+
+impl ExpectedState {
+    /// Checks the expectation against the chain, returning how the chain differs if it doesn't
+    /// hold.
+    pub async fn verify(&self, client: &Client) -> Result<Option<Difference>> {
+        let difference = match self {
+            ExpectedState::GasSchedule(expected) => {
+                let on_chain = fetch_config::<GasScheduleV2>(client).await?;
+                (&on_chain != expected).then(|| Difference::GasSchedule {
+                    expected_feature_version: expected.feature_version,
+                    found_feature_version: on_chain.feature_version,
+                    entries: AptosGasParameters::diff(&on_chain.entries, &expected.entries),
+                })
+            }
+            ExpectedState::Version(expected) => {
+                let on_chain = fetch_config::<Version>(client).await?;
+                (&on_chain != expected).then(|| Difference::Version {
+                    expected: expected.major,
+                    found: on_chain.major,
+                })
+            }
+            ExpectedState::FeatureFlag { flag, enabled } => {
+                let on_chain = fetch_config::<AptosFeatures>(client).await?;
+                let is_enabled = on_chain.is_enabled(AptosFeatureFlag::from(flag.clone()));
+                (is_enabled != *enabled).then(|| Difference::FeatureFlag {
+                    flag: flag.clone(),
+                    expected_enabled: *enabled,
+                    found_enabled: is_enabled,
+                })
+            }
+            ExpectedState::ConsensusConfig(expected) => {
+                let on_chain = fetch_config::<OnChainConsensusConfig>(client).await?;
+                (&on_chain != expected).then(|| Difference::ConsensusConfig {
+                    expected: expected.clone(),
+                    found: on_chain,
+                })
+            }
+            ExpectedState::TransactionDenyList(expected) => {
+                let on_chain = fetch_config::<TransactionDenyList>(client).await?;
+                (&on_chain != expected).then(|| Difference::TransactionDenyList {
+                    expected: expected.clone(),
+                    found: on_chain,
+                })
+            }
+            ExpectedState::GasPriceFloor(expected) => {
+                let on_chain = fetch_config::<GasPriceFloor>(client).await?;
+                let found = GasPriceFloorConfig {
+                    min_gas_unit_price: on_chain.next_min_gas_unit_price,
+                    activation_epoch: on_chain.activation_epoch,
+                };
+                (&found != expected).then(|| Difference::GasPriceFloor {
+                    expected: expected.clone(),
+                    found,
+                })
+            }
+            ExpectedState::ProposalAborted { proposal_id } => {
+                let approved_hash = approved_execution_hash(client, *proposal_id).await?;
+                let is_resolved = is_proposal_resolved(client, *proposal_id).await?;
+                (approved_hash.is_some() || !is_resolved).then(|| Difference::ProposalNotAborted {
+                    proposal_id: *proposal_id,
+                    approved_execution_hash: approved_hash,
+                })
+            }
+        };
+        Ok(difference)
+    }
+}
+
+impl ExecutionManifest {
+    /// Checks all the expectations of the manifest against the chain, returning how the chain
+    /// differs from the ones that don't hold.
+    pub async fn verify(&self, client: &Client) -> Result<Vec<Difference>> {
+        let mut differences = vec![];
+        for expected in &self.expected {
+            if let Some(difference) = expected.verify(client).await? {
+                differences.push(difference);
+            }
+        }
+        Ok(differences)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref()).map_err(|error| {
+            anyhow!(
+                "Failed to read manifest {:?}. Error: {:?}",
+                path.as_ref(),
+                error
+            )
+        })?;
+        serde_yaml::from_str(&contents)
+            .map_err(|e| anyhow!("Failed to parse the manifest: {:?}", e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let contents = serde_yaml::to_vec(self)
+            .map_err(|e| anyhow!("failed to generate manifest: {:?}", e))?;
+        fs::write(path.as_ref(), contents).map_err(|e| anyhow!("failed to write file: {:?}", e))
+    }
+}
+
+/// Checks that the scripts of a multi-step proposal, given in execution order, are chained: each
+/// step resolves the proposal with the execution hash of the next script, i.e. the hash of its
+/// bytecode, and the last step with an empty hash. Returns the execution hash of each script, the
+/// first one being the hash the proposal is created with.
+pub fn verify_multi_step_proposal_chain(scripts: &[PathBuf]) -> Result<Vec<HashValue>> {
+    let hashes = scripts
+        .iter()
+        .map(|path| {
+            compile_script(path)
+                .map(|code| HashValue::sha3_256_of(&code))
+                .map_err(|err| anyhow!("Failed to compile script {}: {:?}", path.display(), err))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    verify_multi_step_proposal_hashes(scripts, &hashes)?;
+    Ok(hashes)
+}
+
+/// Checks that the scripts of a multi-step proposal are chained by the given execution hashes,
+/// i.e. the hashes of the bytecode of the scripts as compiled by the caller, which are the ones
+/// the chain checks when the steps are executed.
+pub fn verify_multi_step_proposal_hashes(scripts: &[PathBuf], hashes: &[HashValue]) -> Result<()> {
+    if scripts.len() != hashes.len() {
+        bail!(
+            "Got {} execution hashes for {} scripts",
+            hashes.len(),
+            scripts.len()
+        );
+    }
+    let sources = scripts
+        .iter()
+        .map(|path| {
+            fs::read_to_string(path)
+                .map_err(|err| anyhow!("Failed to read script {}: {:?}", path.display(), err))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for (idx, (path, source)) in scripts.iter().zip(&sources).enumerate() {
+        let next_execution_hash = next_execution_hash(source).ok_or_else(|| {
+            anyhow!(
+                "Script {} isn't a step of a multi-step proposal",
+                path.display()
+            )
+        })?;
+        let expected = match hashes.get(idx + 1) {
+            Some(hash) => hash.to_hex(),
+            None => EMPTY_EXECUTION_HASH.to_string(),
+        };
+        if next_execution_hash != expected {
+            bail!(
+                "Step {} ({}) is followed by a script of hash {} but resolves the proposal with {}",
+                idx,
+                path.display(),
+                expected,
+                next_execution_hash
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Extracts the next execution hash a multi-step proposal script resolves the proposal with,
+/// i.e. the hex encoded hash of the next script, or `vector::empty<u8>()` for the last step.
+fn next_execution_hash(source: &str) -> Option<String> {
+    let args = &source[source.find("resolve_multi_step_proposal(")?..];
+    let args = &args[..args.find(';')?];
+    if args.contains(EMPTY_EXECUTION_HASH) {
+        return Some(EMPTY_EXECUTION_HASH.to_string());
+    }
+    let hash = &args[args.find("x\"")? + "x\"".len()..];
+    Some(hash[..hash.find('"')?].to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{propose::proposal_scripts, ReleaseConfig};
+    use aptos_temppath::TempPath;
+    use aptos_types::on_chain_config::Version;
+
+    #[test]
+    fn test_verify_multi_step_proposal_chain() {
+        let scripts_dir = TempPath::new();
+        scripts_dir.create_as_dir().unwrap();
+        ReleaseConfig {
+            framework_release: false,
+            is_multi_step: true,
+            version: Some(Version { major: 10 }),
+            ..Default::default()
+        }
+        .generate_release_proposal_scripts(scripts_dir.path())
+        .unwrap();
+        let scripts = proposal_scripts(scripts_dir.path()).unwrap();
+        assert_eq!(scripts.len(), 3);
+
+        // The proposal is created with the hash of the bytecode of the first step
+        let hashes = verify_multi_step_proposal_chain(&scripts).unwrap();
+        assert_eq!(
+            hashes[0],
+            HashValue::sha3_256_of(&compile_script(&scripts[0]).unwrap())
+        );
+
+        // Steps can't be skipped nor reordered
+        assert!(verify_multi_step_proposal_chain(&scripts[1..]).is_ok());
+        assert!(
+            verify_multi_step_proposal_chain(&[scripts[0].clone(), scripts[2].clone()]).is_err()
+        );
+        assert!(
+            verify_multi_step_proposal_chain(&[scripts[1].clone(), scripts[0].clone()]).is_err()
+        );
+    }
+}
@@ -9,8 +9,10 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_aggregator::transaction::TransactionOutputExt;
+use aptos_gas::GasReport;
 use aptos_state_view::StateView;
 use aptos_types::{
+    account_config::CurrentBlockInfo,
     block_metadata::BlockMetadata,
     transaction::{SignatureCheckedTransaction, SignedTransaction, VMValidatorResult},
     transaction::{Transaction, TransactionOutput, TransactionStatus, WriteSetPayload},
@@ -49,13 +51,24 @@ pub trait VMAdapter {
     /// TODO: maybe remove this after more refactoring of execution logic.
     fn should_restart_execution(output: &TransactionOutput) -> bool;
 
-    /// Execute a single transaction.
+    /// Execute a single transaction, of the block described by `block_info` if it's available.
+    /// The gas report is only returned for the user transactions the VM profiled.
+    #[allow(clippy::type_complexity)]
     fn execute_single_transaction<S: MoveResolverExt + StateView>(
         &self,
         txn: &PreprocessedTransaction,
         data_cache: &S,
+        block_info: Option<CurrentBlockInfo>,
         log_context: &AdapterLogSchema,
-    ) -> Result<(VMStatus, TransactionOutputExt, Option<String>), VMStatus>;
+    ) -> Result<
+        (
+            VMStatus,
+            TransactionOutputExt,
+            Option<String>,
+            Option<GasReport>,
+        ),
+        VMStatus,
+    >;
 }
 
 /// Validate a signed transaction by performing the following:
@@ -16,7 +16,8 @@ use crate::{
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
-    verifier, VMExecutor, VMValidator,
+    verifier::{self, transaction_arg_validation::ArgumentError},
+    VMExecutor, VMValidator,
 };
 use anyhow::{anyhow, Result};
 use aptos_aggregator::{
@@ -25,18 +26,18 @@ use aptos_aggregator::{
 };
 use aptos_crypto::HashValue;
 use aptos_framework::natives::code::PublishRequest;
-use aptos_gas::{AptosGasMeter, ChangeSetConfigs};
+use aptos_gas::{AptosGasMeter, ChangeSetConfigs, GasProfiler, GasReport};
 use aptos_logger::prelude::*;
 use aptos_state_view::StateView;
 use aptos_types::{
     account_config,
-    account_config::new_block_event_key,
+    account_config::{new_block_event_key, CurrentBlockInfo},
     block_metadata::BlockMetadata,
-    on_chain_config::{new_epoch_event_key, FeatureFlag},
+    on_chain_config::{new_epoch_event_key, FeatureFlag, Features, OnChainConfig},
     transaction::{
-        ChangeSet, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction, SignedTransaction,
-        Transaction, TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        ChangeSet, EntryFunction, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{AbortLocation, DiscardedVMStatus, StatusCode, VMStatus},
     write_set::WriteSet,
@@ -51,7 +52,7 @@ use move_binary_format::{
 use move_core_types::{
     account_address::AccountAddress,
     ident_str,
-    identifier::Identifier,
+    identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, TypeTag},
     transaction_argument::convert_txn_args,
     value::{serialize_values, MoveValue},
@@ -73,6 +74,7 @@ static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static PARANOID_TYPE_CHECKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static ALLOWLISTED_ADDRESSES: OnceCell<BTreeSet<AccountAddress>> = OnceCell::new();
 
 /// Remove this once the bundle is removed from the code.
 static MODULE_BUNDLE_DISALLOWED: AtomicBool = AtomicBool::new(true);
@@ -80,6 +82,27 @@ pub fn allow_module_bundle_for_test() {
     MODULE_BUNDLE_DISALLOWED.store(false, Ordering::Relaxed);
 }
 
+/// Whether calling the entry function publishes modules on behalf of the sender.
+fn is_publish_function(module: &ModuleId, function: &IdentStr) -> bool {
+    (module == &*CODE_MODULE && function == PUBLISH_PACKAGE_TXN)
+        || (module == &*RESOURCE_ACCOUNT_MODULE
+            && function == CREATE_RESOURCE_ACCOUNT_AND_PUBLISH_PACKAGE)
+}
+
+/// Name of the root of the call stacks the gas profiler records for the payload.
+#[cfg(feature = "gas-profiling")]
+fn profiling_root(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => format!(
+            "{}::{}",
+            entry_function.module().short_str_lossless(),
+            entry_function.function()
+        ),
+        TransactionPayload::Script(_) => "script".to_string(),
+        TransactionPayload::ModuleBundle(_) => "module_bundle".to_string(),
+    }
+}
+
 #[derive(Clone)]
 pub struct AptosVM(pub(crate) AptosVMImpl);
 
@@ -98,6 +121,33 @@ impl AptosVM {
         Self::new(state)
     }
 
+    /// Returns the info of the block each of the transactions belongs to, once the block info
+    /// feature is enabled. It's taken from the block metadata transactions, so that it doesn't have
+    /// to be read from storage for every transaction. The transactions preceding the first block
+    /// metadata transaction belong to the block the state view is in the middle of, whose info was
+    /// stored on chain by its prologue.
+    pub(crate) fn block_infos<S: StateView>(
+        transactions: &[PreprocessedTransaction],
+        state_view: &S,
+    ) -> Vec<Option<CurrentBlockInfo>> {
+        let storage = state_view.as_move_resolver();
+        let features = Features::fetch_config(&storage).unwrap_or_default();
+        if !features.is_enabled(FeatureFlag::BLOCK_INFO) {
+            return vec![None; transactions.len()];
+        }
+
+        let mut block_info = AptosVMImpl::fetch_current_block_info(&features, &storage);
+        transactions
+            .iter()
+            .map(|txn| {
+                if let PreprocessedTransaction::BlockMetadata(block_metadata) = txn {
+                    block_info = Some(CurrentBlockInfo::from(block_metadata));
+                }
+                block_info
+            })
+            .collect()
+    }
+
     /// Sets execution concurrency level when invoked the first time.
     pub fn set_concurrency_level_once(mut concurrency_level: usize) {
         concurrency_level = min(concurrency_level, num_cpus::get());
@@ -105,6 +155,14 @@ impl AptosVM {
         EXECUTION_CONCURRENCY_LEVEL.set(concurrency_level).ok();
     }
 
+    /// Sets the thread pool used for parallel execution. Fails if a pool was already set or a
+    /// block was already executed, so it has to be invoked before any block is executed.
+    pub fn set_rayon_exec_pool_once(
+        pool: Arc<rayon::ThreadPool>,
+    ) -> Result<(), Arc<rayon::ThreadPool>> {
+        aptos_block_executor::executor::set_rayon_exec_pool_once(pool)
+    }
+
     /// Get the concurrency level if already set, otherwise return default 1
     /// (sequential execution).
     pub fn get_concurrency_level() -> usize {
@@ -114,6 +172,54 @@ impl AptosVM {
         }
     }
 
+    /// Restricts entry functions to modules published at the given addresses, and module
+    /// publishing and scripts to senders at the given addresses, when invoked the first time.
+    /// The framework is always allowed. This is only enforced when validating transactions.
+    pub fn set_allowlisted_addresses_once(addresses: Vec<AccountAddress>) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        ALLOWLISTED_ADDRESSES
+            .set(addresses.into_iter().collect())
+            .ok();
+    }
+
+    /// Returns whether the transaction passes the address allowlist, if one is set. Mempool
+    /// checks this before validating transactions, to reject them with its own `NotAllowlisted`
+    /// status rather than the closest status the Move VM defines.
+    pub fn is_allowlisted(txn: &SignedTransaction) -> bool {
+        ALLOWLISTED_ADDRESSES.get().map_or(true, |allowlist| {
+            Self::check_allowlisted_addresses(allowlist, txn).is_ok()
+        })
+    }
+
+    /// Checks the transaction against the address allowlist.
+    fn check_allowlisted_addresses(
+        allowlist: &BTreeSet<AccountAddress>,
+        txn: &SignedTransaction,
+    ) -> Result<(), VMStatus> {
+        let is_allowed = |address: &AccountAddress| {
+            *address == account_config::CORE_CODE_ADDRESS || allowlist.contains(address)
+        };
+        let allowed = match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                let module = entry_function.module();
+                if is_publish_function(module, entry_function.function()) {
+                    is_allowed(&txn.sender())
+                } else {
+                    is_allowed(module.address())
+                }
+            }
+            // Scripts can call into any module, so they are treated like publishing.
+            TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => {
+                is_allowed(&txn.sender())
+            }
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING))
+        }
+    }
+
     /// Sets runtime config when invoked the first time.
     pub fn set_paranoid_type_checks(enable: bool) {
         // Only the first call succeeds, due to OnceCell semantics.
@@ -680,23 +786,31 @@ impl AptosVM {
             .finish(Location::Undefined)
     }
 
+    /// Executes the user transaction, returning its gas report if the transaction was profiled,
+    /// i.e., with the `gas-profiling` feature and unless it was discarded before execution.
     pub(crate) fn execute_user_transaction<S: MoveResolverExt + StateView>(
         &self,
         storage: &S,
         txn: &SignatureCheckedTransaction,
+        block_info: Option<CurrentBlockInfo>,
         log_context: &AdapterLogSchema,
-    ) -> (VMStatus, TransactionOutputExt) {
+    ) -> (VMStatus, TransactionOutputExt, Option<GasReport>) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
                 match $res {
                     Ok(s) => s,
-                    Err(e) => return discard_error_vm_status(e),
+                    Err(e) => {
+                        let (vm_status, output) = discard_error_vm_status(e);
+                        return (vm_status, output, None);
+                    }
                 }
             };
         }
 
         // Revalidate the transaction.
-        let mut session = self.0.new_session(storage, SessionId::txn(txn));
+        let mut session =
+            self.0
+                .new_session_with_block_info(storage, SessionId::txn(txn), block_info);
         if let Err(err) = validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
@@ -705,7 +819,8 @@ impl AptosVM {
             false,
             log_context,
         ) {
-            return discard_error_vm_status(err);
+            let (vm_status, output) = discard_error_vm_status(err);
+            return (vm_status, output, None);
         };
 
         if self.0.get_gas_feature_version() >= 1 {
@@ -714,7 +829,9 @@ impl AptosVM {
             // have been previously cached in the prologue.
             //
             // TODO(Gas): Do this in a better way in the future, perhaps without forcing the data cache to be flushed.
-            session = self.0.new_session(storage, SessionId::txn(txn));
+            session = self
+                .0
+                .new_session_with_block_info(storage, SessionId::txn(txn), block_info);
         }
 
         let gas_params = unwrap_or_discard!(self.0.get_gas_parameters(log_context));
@@ -726,6 +843,8 @@ impl AptosVM {
             storage_gas_params.clone(),
             txn_data.max_gas_amount(),
         );
+        #[cfg(feature = "gas-profiling")]
+        gas_meter.enable_profiling(profiling_root(txn.payload()));
 
         let result = match txn.payload() {
             payload @ TransactionPayload::Script(_)
@@ -749,7 +868,7 @@ impl AptosVM {
             .expect("Balance should always be less than or equal to max gas amount set");
         TXN_GAS_USAGE.observe(u64::from(gas_usage) as f64);
 
-        match result {
+        let (vm_status, output) = match result {
             Ok(output) => output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -765,7 +884,14 @@ impl AptosVM {
                     )
                 }
             }
-        }
+        };
+        #[cfg(feature = "gas-profiling")]
+        let gas_report = gas_meter
+            .take_profiler()
+            .map(|profiler| GasReport::from(&profiler));
+        #[cfg(not(feature = "gas-profiling"))]
+        let gas_report = None;
+        (vm_status, output, gas_report)
     }
 
     fn execute_writeset<S: MoveResolverExt>(
@@ -953,7 +1079,31 @@ impl AptosVM {
         let vm = AptosVM::new(state_view);
         let simulation_vm = AptosSimulationVM(vm);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
-        simulation_vm.simulate_signed_transaction(&state_view.as_move_resolver(), txn, &log_context)
+        let (vm_status, output, _) = simulation_vm.simulate_signed_transaction(
+            &state_view.as_move_resolver(),
+            txn,
+            &log_context,
+            false,
+        );
+        (vm_status, output)
+    }
+
+    /// Simulates a transaction like `simulate_signed_transaction`, recording where the gas is
+    /// charged. The profiler is only missing if the transaction was discarded before execution.
+    #[cfg(feature = "gas-profiling")]
+    pub fn simulate_signed_transaction_with_gas_profiling(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutputExt, Option<GasProfiler>) {
+        let vm = AptosVM::new(state_view);
+        let simulation_vm = AptosSimulationVM(vm);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        simulation_vm.simulate_signed_transaction(
+            &state_view.as_move_resolver(),
+            txn,
+            &log_context,
+            true,
+        )
     }
 
     pub fn execute_view_function(
@@ -1000,6 +1150,32 @@ impl AptosVM {
             .collect::<Vec<_>>())
     }
 
+    /// Checks the arguments of an entry function call against the signature of the function, so
+    /// that invalid arguments can be reported with the parameter they're for before the
+    /// transaction is submitted. Only the arguments are checked: if the function can't be loaded,
+    /// the execution reports it. The VM must have been created for `state_view`, and can be reused
+    /// for any number of checks against it.
+    pub fn check_entry_function_arguments(
+        &self,
+        state_view: &impl StateView,
+        entry_function: &EntryFunction,
+    ) -> Result<(), ArgumentError> {
+        let resolver = &state_view.as_move_resolver();
+        let session = self.new_session(resolver, SessionId::Void);
+        match session.load_function(
+            entry_function.module(),
+            entry_function.function(),
+            entry_function.ty_args(),
+        ) {
+            Ok(func_inst) => verifier::transaction_arg_validation::check_entry_function_args(
+                &session,
+                entry_function.args(),
+                &func_inst,
+            ),
+            Err(_) => Ok(()),
+        }
+    }
+
     fn run_prologue_with_payload<S: MoveResolverExt>(
         &self,
         session: &mut SessionExt<S>,
@@ -1008,6 +1184,7 @@ impl AptosVM {
         txn_data: &TransactionMetadata,
         log_context: &AdapterLogSchema,
     ) -> Result<(), VMStatus> {
+        self.0.check_deny_list(txn_data, payload, log_context)?;
         match payload {
             TransactionPayload::Script(_) => {
                 self.0.check_gas(storage, txn_data, log_context)?;
@@ -1054,13 +1231,24 @@ impl VMExecutor for AptosVM {
         );
 
         let count = transactions.len();
-        let ret =
-            BlockAptosVM::execute_block(transactions, state_view, Self::get_concurrency_level());
+        let ret = BlockAptosVM::execute_block_with_gas_report(
+            transactions,
+            state_view,
+            Self::get_concurrency_level(),
+        );
         if ret.is_ok() {
             // Record the histogram count for transactions per block.
             BLOCK_TRANSACTION_COUNT.observe(count as f64);
         }
-        ret
+        ret.map(|(outputs, gas_report)| {
+            if !gas_report.is_empty() {
+                info!(
+                    log_context,
+                    "Gas charged per instruction and native function by the block:\n{}", gas_report
+                );
+            }
+            outputs
+        })
     }
 }
 
@@ -1082,6 +1270,11 @@ impl VMValidator for AptosVM {
         transaction: SignedTransaction,
         state_view: &impl StateView,
     ) -> VMValidatorResult {
+        if let Some(allowlist) = ALLOWLISTED_ADDRESSES.get() {
+            if let Err(err) = Self::check_allowlisted_addresses(allowlist, &transaction) {
+                return VMValidatorResult::error(err.status_code());
+            }
+        }
         validate_signed_transaction(self, transaction, state_view)
     }
 }
@@ -1137,14 +1330,23 @@ impl VMAdapter for AptosVM {
         &self,
         txn: &PreprocessedTransaction,
         data_cache: &S,
+        block_info: Option<CurrentBlockInfo>,
         log_context: &AdapterLogSchema,
-    ) -> Result<(VMStatus, TransactionOutputExt, Option<String>), VMStatus> {
+    ) -> Result<
+        (
+            VMStatus,
+            TransactionOutputExt,
+            Option<String>,
+            Option<GasReport>,
+        ),
+        VMStatus,
+    > {
         Ok(match txn {
             PreprocessedTransaction::BlockMetadata(block_metadata) => {
                 fail_point!("aptos_vm::execution::block_metadata");
                 let (vm_status, output) =
                     self.process_block_prologue(data_cache, block_metadata.clone(), log_context)?;
-                (vm_status, output, Some("block_prologue".to_string()))
+                (vm_status, output, Some("block_prologue".to_string()), None)
             }
             PreprocessedTransaction::WaypointWriteSet(write_set_payload) => {
                 let (vm_status, output) = self.process_waypoint_change_set(
@@ -1152,14 +1354,19 @@ impl VMAdapter for AptosVM {
                     write_set_payload.clone(),
                     log_context,
                 )?;
-                (vm_status, output, Some("waypoint_write_set".to_string()))
+                (
+                    vm_status,
+                    output,
+                    Some("waypoint_write_set".to_string()),
+                    None,
+                )
             }
             PreprocessedTransaction::UserTransaction(txn) => {
                 fail_point!("aptos_vm::execution::user_transaction");
                 let sender = txn.sender().to_string();
                 let _timer = TXN_TOTAL_SECONDS.start_timer();
-                let (vm_status, output) =
-                    self.execute_user_transaction(data_cache, txn, log_context);
+                let (vm_status, output, gas_report) =
+                    self.execute_user_transaction(data_cache, txn, block_info, log_context);
 
                 if let Err(DiscardedVMStatus::UNKNOWN_INVARIANT_VIOLATION_ERROR) =
                     vm_status.clone().keep_or_discard()
@@ -1181,12 +1388,12 @@ impl VMAdapter for AptosVM {
                 if let Some(label) = counter_label {
                     USER_TRANSACTIONS_EXECUTED.with_label_values(&[label]).inc();
                 }
-                (vm_status, output, Some(sender))
+                (vm_status, output, Some(sender), gas_report)
             }
             PreprocessedTransaction::InvalidSignature => {
                 let (vm_status, output) =
                     discard_error_vm_status(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
-                (vm_status, output, None)
+                (vm_status, output, None, None)
             }
             PreprocessedTransaction::StateCheckpoint => {
                 let output = TransactionOutput::new(
@@ -1199,6 +1406,7 @@ impl VMAdapter for AptosVM {
                     VMStatus::Executed,
                     TransactionOutputExt::from(output),
                     Some("state_checkpoint".into()),
+                    None,
                 )
             }
         })
@@ -1244,16 +1452,27 @@ impl AptosSimulationVM {
         storage: &S,
         txn: &SignedTransaction,
         log_context: &AdapterLogSchema,
-    ) -> (VMStatus, TransactionOutputExt) {
+        profile_gas: bool,
+    ) -> (VMStatus, TransactionOutputExt, Option<GasProfiler>) {
+        let discard = |err| {
+            let (vm_status, output) = discard_error_vm_status(err);
+            (vm_status, output, None)
+        };
+
         // simulation transactions should not carry valid signatures, otherwise malicious fullnodes
         // may execute them without user's explicit permission.
         if txn.signature_is_valid() {
-            return discard_error_vm_status(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
+            return discard(VMStatus::Error(StatusCode::INVALID_SIGNATURE));
         }
 
-        // Revalidate the transaction.
+        // Revalidate the transaction. It's simulated as if it were part of the last block.
         let txn_data = TransactionMetadata::new(txn);
-        let mut session = self.0.new_session(storage, SessionId::txn_meta(&txn_data));
+        let block_info = AptosVMImpl::fetch_current_block_info(self.0 .0.get_features(), storage);
+        let mut session = self.0 .0.new_session_with_block_info(
+            storage,
+            SessionId::txn_meta(&txn_data),
+            block_info,
+        );
         if let Err(err) = self.validate_simulated_transaction::<S>(
             &mut session,
             storage,
@@ -1261,15 +1480,15 @@ impl AptosSimulationVM {
             &txn_data,
             log_context,
         ) {
-            return discard_error_vm_status(err);
+            return discard(err);
         };
 
         let gas_params = match self.0 .0.get_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => return discard(err),
             Ok(s) => s,
         };
         let storage_gas_params = match self.0 .0.get_storage_gas_parameters(log_context) {
-            Err(err) => return discard_error_vm_status(err),
+            Err(err) => return discard(err),
             Ok(s) => s,
         };
 
@@ -1279,6 +1498,10 @@ impl AptosSimulationVM {
             storage_gas_params.clone(),
             txn_data.max_gas_amount(),
         );
+        #[cfg(feature = "gas-profiling")]
+        if profile_gas {
+            gas_meter.enable_profiling(profiling_root(txn.payload()));
+        }
 
         let result = match txn.payload() {
             payload @ TransactionPayload::Script(_)
@@ -1298,7 +1521,7 @@ impl AptosSimulationVM {
             }
         };
 
-        match result {
+        let (vm_status, output) = match result {
             Ok(output) => output,
             Err(err) => {
                 let txn_status = TransactionStatus::from(err.clone());
@@ -1315,6 +1538,125 @@ impl AptosSimulationVM {
                     (vm_status, output)
                 }
             }
+        };
+        #[cfg(feature = "gas-profiling")]
+        let profiler = gas_meter.take_profiler();
+        #[cfg(feature = "gas-profiling")]
+        if let Some(profiler) = &profiler {
+            info!(
+                log_context,
+                "Gas charged per instruction and native function by the simulated transaction:\n{}",
+                GasReport::from(profiler)
+            );
         }
+        // Only the gas meters of builds with the `gas-profiling` feature can record the gas
+        #[cfg(not(feature = "gas-profiling"))]
+        let profiler = {
+            debug_assert!(!profile_gas);
+            None
+        };
+        (vm_status, output, profiler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+    use aptos_types::{
+        test_helpers::transaction_test_helpers::{
+            get_test_signed_module_publishing_transaction, get_test_signed_transaction,
+        },
+        transaction::{EntryFunction, Module},
+    };
+
+    fn entry_function_txn(
+        sender: AccountAddress,
+        module: ModuleId,
+        function: &str,
+    ) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        get_test_signed_transaction(
+            sender,
+            0,
+            &private_key,
+            private_key.public_key(),
+            Some(TransactionPayload::EntryFunction(EntryFunction::new(
+                module,
+                Identifier::new(function).unwrap(),
+                vec![],
+                vec![],
+            ))),
+            u64::MAX,
+            100,
+            None,
+        )
+    }
+
+    fn script_txn(sender: AccountAddress) -> SignedTransaction {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        get_test_signed_transaction(
+            sender,
+            0,
+            &private_key,
+            private_key.public_key(),
+            None,
+            u64::MAX,
+            100,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_allowlisted_addresses() {
+        let allowed = AccountAddress::from_hex_literal("0xa").unwrap();
+        let denied = AccountAddress::from_hex_literal("0xd").unwrap();
+        let allowlist: BTreeSet<_> = vec![allowed].into_iter().collect();
+        let check = |txn: SignedTransaction| {
+            AptosVM::check_allowlisted_addresses(&allowlist, &txn).map_err(|e| e.status_code())
+        };
+        let module_at = |address| ModuleId::new(address, Identifier::new("m").unwrap());
+
+        // Entry functions are allowed by the address of their module, whoever the sender is
+        assert_eq!(
+            check(entry_function_txn(denied, module_at(allowed), "f")),
+            Ok(())
+        );
+        assert_eq!(
+            check(entry_function_txn(
+                denied,
+                module_at(account_config::CORE_CODE_ADDRESS),
+                "f"
+            )),
+            Ok(())
+        );
+        assert_eq!(
+            check(entry_function_txn(allowed, module_at(denied), "f")),
+            Err(StatusCode::FEATURE_UNDER_GATING)
+        );
+
+        // Publishing and scripts are allowed by the sender
+        let publish =
+            |sender| entry_function_txn(sender, CODE_MODULE.clone(), PUBLISH_PACKAGE_TXN.as_str());
+        assert_eq!(check(publish(allowed)), Ok(()));
+        assert_eq!(
+            check(publish(denied)),
+            Err(StatusCode::FEATURE_UNDER_GATING)
+        );
+        assert_eq!(check(script_txn(allowed)), Ok(()));
+        assert_eq!(
+            check(script_txn(denied)),
+            Err(StatusCode::FEATURE_UNDER_GATING)
+        );
+
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let bundle = get_test_signed_module_publishing_transaction(
+            denied,
+            0,
+            &private_key,
+            private_key.public_key(),
+            Module::new(vec![]),
+        );
+        assert_eq!(check(bundle), Err(StatusCode::FEATURE_UNDER_GATING));
     }
 }
@@ -19,15 +19,17 @@ use aptos_gas::{
 use aptos_logger::prelude::*;
 use aptos_state_view::StateView;
 use aptos_types::{
-    account_config::{TransactionValidation, APTOS_TRANSACTION_VALIDATION, CORE_CODE_ADDRESS},
+    account_config::{
+        CurrentBlockInfo, TransactionValidation, APTOS_TRANSACTION_VALIDATION, CORE_CODE_ADDRESS,
+    },
     chain_id::ChainId,
     on_chain_config::{
-        ApprovedExecutionHashes, GasSchedule, GasScheduleV2, OnChainConfig, StorageGasSchedule,
+        ApprovedExecutionHashes, ConfigurationResource, FeatureFlag, Features, GasPriceFloor,
+        GasSchedule, GasScheduleV2, OnChainConfig, StorageGasSchedule, TransactionDenyList,
         Version,
     },
-    on_chain_config::{FeatureFlag, Features},
     transaction::AbortInfo,
-    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    transaction::{ExecutionStatus, TransactionOutput, TransactionPayload, TransactionStatus},
     vm_status::{StatusCode, VMStatus},
 };
 use fail::fail_point;
@@ -54,6 +56,8 @@ pub struct AptosVMImpl {
     version: Option<Version>,
     transaction_validation: Option<TransactionValidation>,
     features: Features,
+    transaction_deny_list: TransactionDenyList,
+    min_gas_unit_price_floor: u64,
 }
 
 impl AptosVMImpl {
@@ -115,6 +119,10 @@ impl AptosVMImpl {
         };
 
         let features = Features::fetch_config(&storage).unwrap_or_default();
+        let transaction_deny_list = TransactionDenyList::fetch_config(&storage).unwrap_or_default();
+        let min_gas_unit_price_floor = GasPriceFloor::fetch_config(&storage)
+            .map(|floor| floor.effective_min_gas_unit_price(Self::get_current_epoch(&storage)))
+            .unwrap_or_default();
 
         // If no chain ID is in storage, we assume we are in a testing environment and use ChainId::TESTING
         let chain_id = ChainId::fetch_config(&storage).unwrap_or_else(ChainId::test);
@@ -123,9 +131,8 @@ impl AptosVMImpl {
             native_gas_params,
             abs_val_size_gas_params,
             gas_feature_version,
-            features.is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
-            features.is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6),
             chain_id.id(),
+            features.clone(),
         )
         .expect("should be able to create Move VM; check if there are duplicated natives");
 
@@ -137,6 +144,8 @@ impl AptosVMImpl {
             version: None,
             transaction_validation: None,
             features,
+            transaction_deny_list,
+            min_gas_unit_price_floor,
         };
         vm.version = Version::fetch_config(&storage);
         vm.transaction_validation = Self::get_transaction_validation(&StorageAdapter::new(state));
@@ -171,6 +180,33 @@ impl AptosVMImpl {
         }
     }
 
+    /// Returns the info of the block the storage is in, as stored by its prologue, if the block
+    /// info feature is enabled. The VM only needs it when it starts executing in the middle of a
+    /// block, as it otherwise takes the info from the block metadata transaction.
+    pub(crate) fn fetch_current_block_info<S: ResourceResolver>(
+        features: &Features,
+        remote_cache: &S,
+    ) -> Option<CurrentBlockInfo> {
+        if !features.is_enabled(FeatureFlag::BLOCK_INFO) {
+            return None;
+        }
+        remote_cache
+            .get_resource(&CORE_CODE_ADDRESS, &CurrentBlockInfo::struct_tag())
+            .ok()
+            .flatten()
+            .and_then(|blob| bcs::from_bytes::<CurrentBlockInfo>(&blob).ok())
+    }
+
+    fn get_current_epoch<S: ResourceResolver>(remote_cache: &S) -> u64 {
+        remote_cache
+            .get_resource(&CORE_CODE_ADDRESS, &ConfigurationResource::struct_tag())
+            .ok()
+            .flatten()
+            .and_then(|blob| bcs::from_bytes::<ConfigurationResource>(&blob).ok())
+            .map(|configuration| configuration.epoch())
+            .unwrap_or_default()
+    }
+
     pub fn get_gas_parameters(
         &self,
         log_context: &AdapterLogSchema,
@@ -212,6 +248,32 @@ impl AptosVMImpl {
         &self.features
     }
 
+    /// Rejects transactions from denied senders, or calling denied entry functions, once the
+    /// deny list is enabled. Mempool rejects these with its own `TransactionDenied` status before
+    /// they're validated, so this only discards the ones denied after they were accepted, with
+    /// the closest status the Move VM defines.
+    pub fn check_deny_list(
+        &self,
+        txn_data: &TransactionMetadata,
+        payload: &TransactionPayload,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(), VMStatus> {
+        if !self.features.is_enabled(FeatureFlag::TRANSACTION_DENY_LIST) {
+            return Ok(());
+        }
+        if self.transaction_deny_list.denies_sender(&txn_data.sender())
+            || self.transaction_deny_list.denies_payload(payload)
+        {
+            warn!(
+                *log_context,
+                "[VM] Transaction from {} rejected by the transaction deny list",
+                txn_data.sender(),
+            );
+            return Err(VMStatus::Error(StatusCode::FEATURE_UNDER_GATING));
+        }
+        Ok(())
+    }
+
     pub fn check_gas<S: MoveResolverExt>(
         &self,
         storage: &S,
@@ -294,16 +356,16 @@ impl AptosVMImpl {
             ));
         }
 
-        // The submitted gas price is less than the minimum gas unit price set by the VM.
-        // NB: MIN_PRICE_PER_GAS_UNIT may equal zero, but need not in the future. Hence why
-        // we turn off the clippy warning.
-        #[allow(clippy::absurd_extreme_comparisons)]
-        let below_min_bound = txn_data.gas_unit_price() < txn_gas_params.min_price_per_gas_unit;
+        // The submitted gas price is less than the minimum gas unit price set by the VM, or
+        // the floor scheduled through governance for the current epoch.
+        let min_price_per_gas_unit =
+            u64::from(txn_gas_params.min_price_per_gas_unit).max(self.min_gas_unit_price_floor);
+        let below_min_bound = u64::from(txn_data.gas_unit_price()) < min_price_per_gas_unit;
         if below_min_bound {
             warn!(
                 *log_context,
                 "[VM] Gas unit error; min {}, submitted {}",
-                txn_gas_params.min_price_per_gas_unit,
+                min_price_per_gas_unit,
                 txn_data.gas_unit_price(),
             );
             return Err(VMStatus::Error(StatusCode::GAS_UNIT_PRICE_BELOW_MIN_BOUND));
@@ -530,6 +592,16 @@ impl AptosVMImpl {
         self.move_vm.new_session(r, session_id)
     }
 
+    pub fn new_session_with_block_info<'r, R: MoveResolverExt>(
+        &self,
+        r: &'r R,
+        session_id: SessionId,
+        block_info: Option<CurrentBlockInfo>,
+    ) -> SessionExt<'r, '_, R> {
+        self.move_vm
+            .new_session_with_block_info(r, session_id, block_info)
+    }
+
     pub fn load_module<'r, R: MoveResolverExt>(
         &self,
         module_id: &ModuleId,
@@ -561,6 +633,19 @@ impl<'a> AptosVMInternals<'a> {
         self.0.get_gas_parameters(log_context)
     }
 
+    /// Returns the internal storage gas schedule if it has been loaded, or an error if it hasn't.
+    pub fn storage_gas_params(
+        self,
+        log_context: &AdapterLogSchema,
+    ) -> Result<&'a StorageGasParameters, VMStatus> {
+        self.0.get_storage_gas_parameters(log_context)
+    }
+
+    /// Returns the feature version of the gas schedule.
+    pub fn gas_feature_version(self) -> u64 {
+        self.0.get_gas_feature_version()
+    }
+
     /// Returns the version of Move Runtime.
     pub fn version(self) -> Result<Version, VMStatus> {
         self.0.get_version()
@@ -11,7 +11,7 @@ use crate::{
 use aptos_aggregator::{delta_change_set::DeltaOp, transaction::TransactionOutputExt};
 use aptos_block_executor::{
     errors::Error,
-    executor::{BlockExecutor, RAYON_EXEC_POOL},
+    executor::{rayon_exec_pool, BlockExecutor},
     output_delta_resolver::OutputDeltaResolver,
     task::{
         Transaction as BlockExecutorTransaction,
@@ -19,6 +19,7 @@ use aptos_block_executor::{
     },
     view::ResolvedData,
 };
+use aptos_gas::GasReport;
 use aptos_logger::debug;
 use aptos_state_view::StateView;
 use aptos_types::{
@@ -36,19 +37,23 @@ impl BlockExecutorTransaction for PreprocessedTransaction {
 }
 
 // Wrapper to avoid orphan rule
-pub(crate) struct AptosTransactionOutput(TransactionOutputExt);
+pub(crate) struct AptosTransactionOutput {
+    output: TransactionOutputExt,
+    /// The gas report of the execution, if the VM profiled it
+    gas_report: Option<GasReport>,
+}
 
 impl AptosTransactionOutput {
-    pub fn new(output: TransactionOutputExt) -> Self {
-        Self(output)
+    pub fn new(output: TransactionOutputExt, gas_report: Option<GasReport>) -> Self {
+        Self { output, gas_report }
     }
 
     pub fn into(self) -> TransactionOutputExt {
-        self.0
+        self.output
     }
 
     pub fn as_ref(&self) -> &TransactionOutputExt {
-        &self.0
+        &self.output
     }
 }
 
@@ -56,7 +61,7 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
     type Txn = PreprocessedTransaction;
 
     fn get_writes(&self) -> Vec<(StateKey, WriteOp)> {
-        self.0
+        self.output
             .txn_output()
             .write_set()
             .iter()
@@ -65,7 +70,7 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
     }
 
     fn get_deltas(&self) -> Vec<(StateKey, DeltaOp)> {
-        self.0
+        self.output
             .delta_change_set()
             .iter()
             .map(|(key, op)| (key.clone(), *op))
@@ -74,18 +79,31 @@ impl BlockExecutorTransactionOutput for AptosTransactionOutput {
 
     /// Execution output for transactions that comes after SkipRest signal.
     fn skip_output() -> Self {
-        Self(TransactionOutputExt::from(TransactionOutput::new(
-            WriteSet::default(),
-            vec![],
-            0,
-            TransactionStatus::Retry,
-        )))
+        Self::new(
+            TransactionOutputExt::from(TransactionOutput::new(
+                WriteSet::default(),
+                vec![],
+                0,
+                TransactionStatus::Retry,
+            )),
+            None,
+        )
     }
 }
 
 pub struct BlockAptosVM();
 
 impl BlockAptosVM {
+    /// Merges the gas reports of the committed executions, the block executor only keeping the
+    /// output of the last execution of each transaction.
+    fn block_gas_report(results: &[AptosTransactionOutput]) -> GasReport {
+        let mut gas_report = GasReport::default();
+        for report in results.iter().filter_map(|res| res.gas_report.as_ref()) {
+            gas_report.merge(report.clone());
+        }
+        gas_report
+    }
+
     fn process_parallel_block_output<S: StateView>(
         results: Vec<AptosTransactionOutput>,
         delta_resolver: OutputDeltaResolver<StateKey, WriteOp>,
@@ -133,45 +151,80 @@ impl BlockAptosVM {
         state_view: &S,
         concurrency_level: usize,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        Self::execute_block_with_gas_report(transactions, state_view, concurrency_level)
+            .map(|(outputs, _)| outputs)
+    }
+
+    /// Executes the block like `execute_block`, also returning the gas report of its
+    /// transactions, which is only populated if the VM is built with the `gas-profiling` feature.
+    pub fn execute_block_with_gas_report<S: StateView>(
+        transactions: Vec<Transaction>,
+        state_view: &S,
+        concurrency_level: usize,
+    ) -> Result<(Vec<TransactionOutput>, GasReport), VMStatus> {
         // Verify the signatures of all the transactions in parallel.
         // This is time consuming so don't wait and do the checking
         // sequentially while executing the transactions.
         let signature_verified_block: Vec<PreprocessedTransaction> =
-            RAYON_EXEC_POOL.install(|| {
+            rayon_exec_pool().install(|| {
                 transactions
                     .into_par_iter()
                     .map(preprocess_transaction::<AptosVM>)
                     .collect()
             });
 
+        let block_infos = AptosVM::block_infos(&signature_verified_block, state_view);
+
         let executor = BlockExecutor::<PreprocessedTransaction, AptosExecutorTask<S>, S>::new(
             concurrency_level,
         );
 
         let mut ret = if concurrency_level > 1 {
             executor
-                .execute_transactions_parallel(state_view, &signature_verified_block, state_view)
+                .execute_transactions_parallel(
+                    (state_view, &block_infos),
+                    &signature_verified_block,
+                    state_view,
+                )
                 .map(|(results, delta_resolver)| {
-                    Self::process_parallel_block_output(results, delta_resolver, state_view)
+                    let gas_report = Self::block_gas_report(&results);
+                    (
+                        Self::process_parallel_block_output(results, delta_resolver, state_view),
+                        gas_report,
+                    )
                 })
         } else {
             executor
-                .execute_transactions_sequential(state_view, &signature_verified_block, state_view)
-                .map(Self::process_sequential_block_output)
+                .execute_transactions_sequential(
+                    (state_view, &block_infos),
+                    &signature_verified_block,
+                    state_view,
+                )
+                .map(|results| {
+                    let gas_report = Self::block_gas_report(&results);
+                    (Self::process_sequential_block_output(results), gas_report)
+                })
         };
 
         if ret == Err(Error::ModulePathReadWrite) {
             debug!("[Execution]: Module read & written, sequential fallback");
 
             ret = executor
-                .execute_transactions_sequential(state_view, &signature_verified_block, state_view)
-                .map(Self::process_sequential_block_output);
+                .execute_transactions_sequential(
+                    (state_view, &block_infos),
+                    &signature_verified_block,
+                    state_view,
+                )
+                .map(|results| {
+                    let gas_report = Self::block_gas_report(&results);
+                    (Self::process_sequential_block_output(results), gas_report)
+                });
         }
 
         // Explicit async drop. Happens here because we can't currently move to
         // BlockExecutor due to the Module publishing fallback. TODO: fix after
         // module publishing fallback is removed.
-        RAYON_EXEC_POOL.spawn(move || {
+        rayon_exec_pool().spawn(move || {
             // Explicit async drops.
             drop(signature_verified_block);
         });
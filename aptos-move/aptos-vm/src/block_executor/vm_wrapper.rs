@@ -12,6 +12,7 @@ use aptos_aggregator::{delta_change_set::DeltaChangeSet, transaction::Transactio
 use aptos_block_executor::task::{ExecutionStatus, ExecutorTask};
 use aptos_logger::prelude::*;
 use aptos_state_view::StateView;
+use aptos_types::account_config::CurrentBlockInfo;
 use move_core_types::{
     ident_str,
     language_storage::{ModuleId, CORE_CODE_ADDRESS},
@@ -21,15 +22,17 @@ use move_core_types::{
 pub(crate) struct AptosExecutorTask<'a, S> {
     vm: AptosVM,
     base_view: &'a S,
+    /// The info of the block each transaction belongs to, indexed by transaction
+    block_infos: &'a [Option<CurrentBlockInfo>],
 }
 
 impl<'a, S: 'a + StateView> ExecutorTask for AptosExecutorTask<'a, S> {
     type Txn = PreprocessedTransaction;
     type Output = AptosTransactionOutput;
     type Error = VMStatus;
-    type Argument = &'a S;
+    type Argument = (&'a S, &'a [Option<CurrentBlockInfo>]);
 
-    fn init(argument: &'a S) -> Self {
+    fn init((argument, block_infos): (&'a S, &'a [Option<CurrentBlockInfo>])) -> Self {
         let vm = AptosVM::new(argument);
 
         // Loading `0x1::account` and its transitive dependency into the code cache.
@@ -48,6 +51,7 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosExecutorTask<'a, S> {
         Self {
             vm,
             base_view: argument,
+            block_infos,
         }
     }
 
@@ -63,11 +67,13 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosExecutorTask<'a, S> {
     ) -> ExecutionStatus<AptosTransactionOutput, VMStatus> {
         let log_context = AdapterLogSchema::new(self.base_view.id(), txn_idx);
 
-        match self
-            .vm
-            .execute_single_transaction(txn, &view.as_move_resolver(), &log_context)
-        {
-            Ok((vm_status, mut output_ext, sender)) => {
+        match self.vm.execute_single_transaction(
+            txn,
+            &view.as_move_resolver(),
+            self.block_infos.get(txn_idx).copied().flatten(),
+            &log_context,
+        ) {
+            Ok((vm_status, mut output_ext, sender, gas_report)) => {
                 if materialize_deltas {
                     // Keep TransactionOutputExt type for wrapper.
                     output_ext = TransactionOutputExt::new(
@@ -91,9 +97,9 @@ impl<'a, S: 'a + StateView> ExecutorTask for AptosExecutorTask<'a, S> {
                 }
                 if AptosVM::should_restart_execution(output_ext.txn_output()) {
                     info!(log_context, "Reconfiguration occurred: restart required",);
-                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(output_ext))
+                    ExecutionStatus::SkipRest(AptosTransactionOutput::new(output_ext, gas_report))
                 } else {
-                    ExecutionStatus::Success(AptosTransactionOutput::new(output_ext))
+                    ExecutionStatus::Success(AptosTransactionOutput::new(output_ext, gas_report))
                 }
             }
             Err(err) => ExecutionStatus::Abort(err),
@@ -122,7 +122,7 @@ pub mod system_module_names;
 pub mod transaction_metadata;
 mod verifier;
 
-pub use crate::aptos_vm::AptosVM;
+pub use crate::{aptos_vm::AptosVM, verifier::transaction_arg_validation::ArgumentError};
 
 use aptos_state_view::StateView;
 use aptos_types::{
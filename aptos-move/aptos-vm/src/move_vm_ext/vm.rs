@@ -6,11 +6,16 @@ use crate::{
     natives::aptos_natives,
 };
 use aptos_framework::natives::{
-    aggregator_natives::NativeAggregatorContext, code::NativeCodeContext,
-    cryptography::ristretto255_point::NativeRistrettoPointContext,
-    state_storage::NativeStateStorageContext, transaction_context::NativeTransactionContext,
+    aggregator_natives::NativeAggregatorContext, block::NativeBlockContext,
+    code::NativeCodeContext, cryptography::ristretto255_point::NativeRistrettoPointContext,
+    features::NativeFeaturesContext, state_storage::NativeStateStorageContext,
+    transaction_context::NativeTransactionContext,
 };
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
+use aptos_types::{
+    account_config::CurrentBlockInfo,
+    on_chain_config::{FeatureFlag, Features},
+};
 use move_binary_format::errors::VMResult;
 use move_bytecode_verifier::VerifierConfig;
 use move_table_extension::NativeTableContext;
@@ -21,6 +26,7 @@ use std::ops::Deref;
 pub struct MoveVmExt {
     inner: MoveVM,
     chain_id: u8,
+    gas_feature_version: u64,
 }
 
 impl MoveVmExt {
@@ -28,18 +34,19 @@ impl MoveVmExt {
         native_gas_params: NativeGasParameters,
         abs_val_size_gas_params: AbstractValueSizeGasParameters,
         gas_feature_version: u64,
-        treat_friend_as_private: bool,
-        allow_binary_format_v6: bool,
         chain_id: u8,
+        features: Features,
     ) -> VMResult<Self> {
         // Note: binary format v6 adds a few new integer types and their corresponding instructions.
         //       Therefore it depends on a new version of the gas schedule and cannot be allowed if
         //       the gas schedule hasn't been updated yet.
-        let max_binary_format_version = if allow_binary_format_v6 && gas_feature_version >= 5 {
-            6
-        } else {
-            5
-        };
+        let max_binary_format_version =
+            if features.is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6) && gas_feature_version >= 5 {
+                6
+            } else {
+                5
+            };
+        let treat_friend_as_private = features.is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE);
 
         Ok(Self {
             inner: MoveVM::new_with_config(
@@ -55,6 +62,7 @@ impl MoveVmExt {
                 },
             )?,
             chain_id,
+            gas_feature_version,
         })
     }
 
@@ -62,6 +70,17 @@ impl MoveVmExt {
         &self,
         remote: &'r S,
         session_id: SessionId,
+    ) -> SessionExt<'r, '_, S> {
+        self.new_session_with_block_info(remote, session_id, None)
+    }
+
+    /// Creates a session exposing the info of the block it executes a transaction of to the
+    /// natives of `0x1::block`.
+    pub fn new_session_with_block_info<'r, S: MoveResolverExt>(
+        &self,
+        remote: &'r S,
+        session_id: SessionId,
+        block_info: Option<CurrentBlockInfo>,
     ) -> SessionExt<'r, '_, S> {
         let mut extensions = NativeContextExtensions::default();
         let txn_hash: [u8; 32] = session_id
@@ -86,6 +105,8 @@ impl MoveVmExt {
         extensions.add(NativeTransactionContext::new(script_hash, self.chain_id));
         extensions.add(NativeCodeContext::default());
         extensions.add(NativeStateStorageContext::new(remote));
+        extensions.add(NativeFeaturesContext::new(remote, self.gas_feature_version));
+        extensions.add(NativeBlockContext::new(block_info));
 
         // The VM code loader has bugs around module upgrade. After a module upgrade, the internal
         // cache needs to be flushed to work around those bugs.
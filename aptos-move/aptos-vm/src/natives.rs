@@ -10,8 +10,10 @@ use aptos_types::chain_id::ChainId;
 #[cfg(feature = "testing")]
 use {
     aptos_framework::natives::{
-        aggregator_natives::NativeAggregatorContext, code::NativeCodeContext,
+        aggregator_natives::NativeAggregatorContext,
+        code::NativeCodeContext,
         cryptography::ristretto255_point::NativeRistrettoPointContext,
+        features::{AllFeaturesEnabled, NativeFeaturesContext},
         transaction_context::NativeTransactionContext,
     },
     move_vm_runtime::native_extensions::NativeContextExtensions,
@@ -92,4 +94,10 @@ fn unit_test_extensions_hook(exts: &mut NativeContextExtensions) {
     exts.add(NativeTransactionContext::new(vec![1], ChainId::test().id())); // We use the testing environment chain ID here
     exts.add(NativeAggregatorContext::new([0; 32], &*DUMMY_RESOLVER));
     exts.add(NativeRistrettoPointContext::new());
+    // Unit tests toggle features on chain, which the Move wrappers of gated natives check, so the
+    // natives themselves are all available
+    exts.add(NativeFeaturesContext::new(
+        &AllFeaturesEnabled,
+        LATEST_GAS_FEATURE_VERSION,
+    ));
 }
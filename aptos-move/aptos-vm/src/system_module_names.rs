@@ -15,6 +15,27 @@ pub static BLOCK_MODULE: Lazy<ModuleId> = Lazy::new(|| {
     )
 });
 
+/// The ModuleId for the aptos code module
+pub static CODE_MODULE: Lazy<ModuleId> = Lazy::new(|| {
+    ModuleId::new(
+        account_config::CORE_CODE_ADDRESS,
+        ident_str!("code").to_owned(),
+    )
+});
+
+/// The ModuleId for the aptos resource account module
+pub static RESOURCE_ACCOUNT_MODULE: Lazy<ModuleId> = Lazy::new(|| {
+    ModuleId::new(
+        account_config::CORE_CODE_ADDRESS,
+        ident_str!("resource_account").to_owned(),
+    )
+});
+
+// Names of the entry functions that publish modules
+pub const PUBLISH_PACKAGE_TXN: &IdentStr = ident_str!("publish_package_txn");
+pub const CREATE_RESOURCE_ACCOUNT_AND_PUBLISH_PACKAGE: &IdentStr =
+    ident_str!("create_resource_account_and_publish_package");
+
 // TZ: TODO: remove these except for the block-related names
 // Names for special functions and structs
 pub const SCRIPT_PROLOGUE_NAME: &IdentStr = ident_str!("script_prologue");
@@ -11,18 +11,55 @@ use crate::{
     VMStatus,
 };
 use move_binary_format::file_format_common::read_uleb128_as_u64;
-use move_core_types::{account_address::AccountAddress, value::MoveValue, vm_status::StatusCode};
+use move_core_types::{
+    account_address::AccountAddress,
+    value::{MoveStructLayout, MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
+};
 use move_vm_runtime::session::LoadedFunctionInstantiation;
 use move_vm_types::loaded_data::runtime_types::Type;
 use once_cell::sync::Lazy;
 use std::collections::BTreeMap;
+use std::fmt;
 use std::io::{Cursor, Read};
 
 // A map which contains the structs allowed as transaction input and the
 // validation function for those, if one was needed (None otherwise).
 // The validation function takes the serialized argument and returns
-// an error if the validation fails.
-type ValidateArg = fn(&[u8]) -> Result<(), VMStatus>;
+// a description of the provided value if the validation fails.
+type ValidateArg = fn(&[u8]) -> Result<(), &'static str>;
+
+/// An invalid argument of an entry function or view function call, describing which parameter
+/// of the function it was passed for and what was wrong with it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArgumentError {
+    /// Index of the parameter in the signature of the function, counting signers.
+    pub param_index: usize,
+    /// The Move type of the parameter, e.g. `vector<0x1::string::String>`.
+    pub expected_type: String,
+    /// What was provided instead, e.g. `an invalid UTF-8 string`.
+    pub provided: String,
+    /// The status code the transaction fails with because of the argument.
+    pub status_code: StatusCode,
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid argument for parameter {}: expected {}, but got {}",
+            self.param_index, self.expected_type, self.provided
+        )
+    }
+}
+
+impl std::error::Error for ArgumentError {}
+
+impl From<ArgumentError> for VMStatus {
+    fn from(err: ArgumentError) -> Self {
+        VMStatus::Error(err.status_code)
+    }
+}
 
 static ALLOWED_STRUCTS: Lazy<BTreeMap<String, Option<ValidateArg>>> = Lazy::new(|| {
     [("0x1::string::String", Some(check_string as ValidateArg))]
@@ -48,19 +85,7 @@ pub(crate) fn validate_combine_signer_and_txn_args<S: MoveResolverExt>(
     if !func.return_.is_empty() {
         return Err(VMStatus::Error(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE));
     }
-    let mut signer_param_cnt = 0;
-    // find all signer params at the beginning
-    for ty in func.parameters.iter() {
-        match ty {
-            Type::Signer => signer_param_cnt += 1,
-            Type::Reference(inner_type) => {
-                if matches!(&**inner_type, Type::Signer) {
-                    signer_param_cnt += 1;
-                }
-            }
-            _ => (),
-        }
-    }
+    let signer_param_cnt = num_signer_params(func);
     // validate all non_signer params
     let mut needs_validation = vec![];
     for (idx, ty) in func.parameters[signer_param_cnt..].iter().enumerate() {
@@ -100,6 +125,145 @@ pub(crate) fn validate_combine_signer_and_txn_args<S: MoveResolverExt>(
     Ok(combined_args)
 }
 
+/// Checks the arguments of an entry function call ahead of execution, to tell the sender which
+/// argument is invalid instead of just the status code the transaction would fail with. Besides
+/// what `validate_combine_signer_and_txn_args` validates, this checks that every argument is a
+/// valid BCS encoding of its parameter type. Functions whose signature doesn't allow them to be
+/// called by transactions are left to the execution to reject.
+pub(crate) fn check_entry_function_args<S: MoveResolverExt>(
+    session: &SessionExt<S>,
+    args: &[Vec<u8>],
+    func: &LoadedFunctionInstantiation,
+) -> Result<(), ArgumentError> {
+    let signer_param_cnt = num_signer_params(func);
+    let params = &func.parameters[signer_param_cnt..];
+    let mut layouts = vec![];
+    for ty in params {
+        match type_layout(session, ty) {
+            Some(layout) => layouts.push(layout),
+            None => return Ok(()),
+        }
+    }
+
+    if args.len() < params.len() {
+        return Err(ArgumentError {
+            param_index: signer_param_cnt + args.len(),
+            expected_type: type_name(session, &params[args.len()]),
+            provided: "no argument".to_string(),
+            status_code: StatusCode::NUMBER_OF_ARGUMENTS_MISMATCH,
+        });
+    }
+    if args.len() > params.len() {
+        return Err(ArgumentError {
+            param_index: signer_param_cnt + params.len(),
+            expected_type: format!("no more than {} arguments", params.len()),
+            provided: format!("{} arguments", args.len()),
+            status_code: StatusCode::NUMBER_OF_ARGUMENTS_MISMATCH,
+        });
+    }
+
+    for (idx, ((ty, layout), arg)) in params.iter().zip(&layouts).zip(args).enumerate() {
+        if MoveValue::simple_deserialize(arg, layout).is_err() {
+            return Err(ArgumentError {
+                param_index: signer_param_cnt + idx,
+                expected_type: type_name(session, ty),
+                provided: format!("{} bytes that aren't a valid BCS encoding of it", arg.len()),
+                status_code: StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT,
+            });
+        }
+    }
+    let needs_validation: Vec<_> = func
+        .parameters
+        .iter()
+        .enumerate()
+        .skip(signer_param_cnt)
+        .filter(|(_, ty)| is_valid_txn_arg(session, ty).1)
+        .map(|(idx, _)| idx)
+        .collect();
+    if !needs_validation.is_empty() {
+        // `validate_args` expects the signers in front of the arguments, but doesn't look at them
+        let combined_args: Vec<_> = std::iter::repeat(vec![])
+            .take(signer_param_cnt)
+            .chain(args.iter().cloned())
+            .collect();
+        validate_args(session, &needs_validation, &combined_args, func)?;
+    }
+    Ok(())
+}
+
+// Return the number of signer params, which come first in the signature.
+fn num_signer_params(func: &LoadedFunctionInstantiation) -> usize {
+    let mut signer_param_cnt = 0;
+    // find all signer params at the beginning
+    for ty in func.parameters.iter() {
+        match ty {
+            Type::Signer => signer_param_cnt += 1,
+            Type::Reference(inner_type) => {
+                if matches!(&**inner_type, Type::Signer) {
+                    signer_param_cnt += 1;
+                }
+            }
+            _ => (),
+        }
+    }
+    signer_param_cnt
+}
+
+// Return the Move type as written in source, for error messages.
+pub(crate) fn type_name<S: MoveResolverExt>(session: &SessionExt<S>, typ: &Type) -> String {
+    use move_vm_types::loaded_data::runtime_types::Type::*;
+
+    match typ {
+        Bool => "bool".to_string(),
+        U8 => "u8".to_string(),
+        U16 => "u16".to_string(),
+        U32 => "u32".to_string(),
+        U64 => "u64".to_string(),
+        U128 => "u128".to_string(),
+        U256 => "u256".to_string(),
+        Address => "address".to_string(),
+        Signer => "signer".to_string(),
+        Vector(inner) => format!("vector<{}>", type_name(session, inner)),
+        Struct(idx) | StructInstantiation(idx, _) => match session.get_struct_type(*idx) {
+            Some(st) => format!("{}::{}", st.module.short_str_lossless(), st.name),
+            None => "unknown struct".to_string(),
+        },
+        Reference(inner) => format!("&{}", type_name(session, inner)),
+        MutableReference(inner) => format!("&mut {}", type_name(session, inner)),
+        TyParam(idx) => format!("T{}", idx),
+    }
+}
+
+// Return the layout of a type allowed as transaction argument, i.e. None if the type isn't
+// allowed.
+fn type_layout<S: MoveResolverExt>(session: &SessionExt<S>, typ: &Type) -> Option<MoveTypeLayout> {
+    use move_vm_types::loaded_data::runtime_types::Type::*;
+
+    Some(match typ {
+        Bool => MoveTypeLayout::Bool,
+        U8 => MoveTypeLayout::U8,
+        U16 => MoveTypeLayout::U16,
+        U32 => MoveTypeLayout::U32,
+        U64 => MoveTypeLayout::U64,
+        U128 => MoveTypeLayout::U128,
+        U256 => MoveTypeLayout::U256,
+        Address => MoveTypeLayout::Address,
+        Vector(inner) => MoveTypeLayout::Vector(Box::new(type_layout(session, inner)?)),
+        // Strings are the only structs allowed, and they wrap their bytes
+        Struct(_) | StructInstantiation(_, _) if is_valid_txn_arg(session, typ).0 => {
+            MoveTypeLayout::Struct(MoveStructLayout::new(vec![MoveTypeLayout::Vector(
+                Box::new(MoveTypeLayout::U8),
+            )]))
+        }
+        Struct(_)
+        | StructInstantiation(_, _)
+        | Signer
+        | Reference(_)
+        | MutableReference(_)
+        | TyParam(_) => return None,
+    })
+}
+
 // Return whether the argument is valid/allowed and whether it needs validation.
 // Validation is only needed for String arguments at the moment and vectors of them.
 pub(crate) fn is_valid_txn_arg<S: MoveResolverExt>(
@@ -137,32 +301,38 @@ pub(crate) fn validate_args<S: MoveResolverExt>(
     idxs: &[usize],
     args: &[Vec<u8>],
     func: &LoadedFunctionInstantiation,
-) -> Result<(), VMStatus> {
+) -> Result<(), ArgumentError> {
     for (idx, (ty, arg)) in func.parameters.iter().zip(args.iter()).enumerate() {
         if !idxs.contains(&idx) {
             continue;
         }
         let arg_len = arg.len();
         let mut cursor = Cursor::new(&arg[..]);
-        validate_arg(session, ty, &mut cursor, arg_len)?;
+        validate_arg(session, ty, &mut cursor, arg_len).map_err(|provided| ArgumentError {
+            param_index: idx,
+            expected_type: type_name(session, ty),
+            provided: provided.to_string(),
+            status_code: StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT,
+        })?;
     }
     Ok(())
 }
 
 // Validate a single arg. A Cursor is used to walk the serialized arg manually and correctly.
-// Only Strings and nested vector of them are validated.
+// Only Strings and nested vector of them are validated. On failure, returns a description of
+// the provided value.
 fn validate_arg<S: MoveResolverExt>(
     session: &SessionExt<S>,
     ty: &Type,
     cursor: &mut Cursor<&[u8]>,
     arg_len: usize,
-) -> Result<(), VMStatus> {
+) -> Result<(), &'static str> {
     use move_vm_types::loaded_data::runtime_types::Type::*;
 
     Ok(match ty {
         Vector(inner) => {
             // get the vector length and iterate over each element
-            let mut len = get_len(cursor)?;
+            let mut len = get_len(cursor).ok_or("a vector with a malformed length")?;
             while len > 0 {
                 validate_arg(session, inner, cursor, arg_len)?;
                 len -= 1;
@@ -173,21 +343,21 @@ fn validate_arg<S: MoveResolverExt>(
         Struct(idx) | StructInstantiation(idx, _) => {
             // load the struct name, we use `expect()` because that check was already
             // performed in `is_valid_txn_arg`
-            let len = get_len(cursor)?;
+            let len = get_len(cursor).ok_or("a string with a malformed length")?;
             let current_pos = cursor.position() as usize;
             match current_pos.checked_add(len) {
                 Some(size) => {
                     if size > arg_len {
-                        return Err(VMStatus::Error(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT));
+                        return Err("a string longer than the argument");
                     }
                 }
-                None => return Err(VMStatus::Error(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)),
+                None => return Err("a string longer than the argument"),
             }
             // load the serialized string
             let mut s = vec![0u8; len];
             cursor
                 .read_exact(&mut s)
-                .map_err(|_| VMStatus::Error(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT))?;
+                .map_err(|_| "a string longer than the argument")?;
             // validate the struct value, we use `expect()` because that check was already
             // performed in `is_valid_txn_arg`
             let st = session
@@ -213,11 +383,8 @@ fn validate_arg<S: MoveResolverExt>(
 
 // String is a vector of bytes, so both string and vector carry a length in the serialized format.
 // Length of vectors in BCS uses uleb128 as a compression format.
-fn get_len(cursor: &mut Cursor<&[u8]>) -> Result<usize, VMStatus> {
-    match read_uleb128_as_u64(cursor) {
-        Err(_) => Err(VMStatus::Error(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)),
-        Ok(len) => Ok(len as usize),
-    }
+fn get_len(cursor: &mut Cursor<&[u8]>) -> Option<usize> {
+    read_uleb128_as_u64(cursor).ok().map(|len| len as usize)
 }
 
 //
@@ -226,9 +393,9 @@ fn get_len(cursor: &mut Cursor<&[u8]>) -> Result<usize, VMStatus> {
 
 // Check if a string is valid. This code is copied from string.rs in the stdlib.
 // TODO: change the move VM code (string.rs) to expose a function that does validation.
-fn check_string(s: &[u8]) -> Result<(), VMStatus> {
+fn check_string(s: &[u8]) -> Result<(), &'static str> {
     match std::str::from_utf8(s) {
         Ok(_) => Ok(()),
-        Err(_) => Err(VMStatus::Error(StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT)),
+        Err(_) => Err("an invalid UTF-8 string"),
     }
 }
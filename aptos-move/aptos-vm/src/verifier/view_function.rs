@@ -55,7 +55,12 @@ pub(crate) fn validate_view_function<S: MoveResolverExt>(
                 if !valid {
                     return Err(
                         PartialVMError::new(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE)
-                            .with_message("invalid view function argument".to_string()),
+                            .with_message(format!(
+                                "invalid view function argument: parameter {} has type {}, \
+                                which can't be passed to view functions",
+                                idx,
+                                transaction_arg_validation::type_name(session, ty)
+                            )),
                     );
                 }
                 if validation {
@@ -64,14 +69,15 @@ pub(crate) fn validate_view_function<S: MoveResolverExt>(
             }
         }
     }
-    if !needs_validation.is_empty()
-        && transaction_arg_validation::validate_args(session, &needs_validation, &args, fun_inst)
-            .is_err()
-    {
-        return Err(
-            PartialVMError::new(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE)
-                .with_message("invalid view function argument: failed validation".to_string()),
-        );
+    if !needs_validation.is_empty() {
+        if let Err(err) =
+            transaction_arg_validation::validate_args(session, &needs_validation, &args, fun_inst)
+        {
+            return Err(
+                PartialVMError::new(StatusCode::INVALID_MAIN_FUNCTION_SIGNATURE)
+                    .with_message(format!("invalid view function argument: {}", err)),
+            );
+        }
     }
     Ok(args)
 }
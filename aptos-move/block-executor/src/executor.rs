@@ -13,16 +13,32 @@ use crate::{
 use aptos_mvhashmap::{MVHashMap, MVHashMapError, MVHashMapOutput};
 use aptos_state_view::TStateView;
 use num_cpus;
-use once_cell::sync::Lazy;
-use std::{collections::btree_map::BTreeMap, marker::PhantomData};
+use once_cell::sync::OnceCell;
+use std::{collections::btree_map::BTreeMap, marker::PhantomData, sync::Arc};
+
+static RAYON_EXEC_POOL: OnceCell<Arc<rayon::ThreadPool>> = OnceCell::new();
+
+/// The thread pool used for execution: the one set by `set_rayon_exec_pool_once`, or a pool
+/// with one thread per core if no pool was set before the first block was executed.
+pub fn rayon_exec_pool() -> &'static rayon::ThreadPool {
+    RAYON_EXEC_POOL.get_or_init(|| {
+        Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(num_cpus::get())
+                .thread_name(|index| format!("par_exec_{}", index))
+                .build()
+                .unwrap(),
+        )
+    })
+}
 
-pub static RAYON_EXEC_POOL: Lazy<rayon::ThreadPool> = Lazy::new(|| {
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num_cpus::get())
-        .thread_name(|index| format!("par_exec_{}", index))
-        .build()
-        .unwrap()
-});
+/// Sets the thread pool used for execution. Fails, returning the given pool, if a pool was
+/// already set or a block was already executed with the default pool.
+pub fn set_rayon_exec_pool_once(
+    pool: Arc<rayon::ThreadPool>,
+) -> Result<(), Arc<rayon::ThreadPool>> {
+    RAYON_EXEC_POOL.set(pool)
+}
 
 pub struct BlockExecutor<T, E, S> {
     // number of active concurrent tasks, corresponding to the maximum number of rayon
@@ -243,7 +259,7 @@ where
         let last_input_output = TxnLastInputOutput::new(num_txns);
         let scheduler = Scheduler::new(num_txns);
 
-        RAYON_EXEC_POOL.scope(|s| {
+        rayon_exec_pool().scope(|s| {
             for _ in 0..self.concurrency_level {
                 s.spawn(|_| {
                     self.work_task_with_scope(
@@ -283,7 +299,7 @@ where
             ret
         };
 
-        RAYON_EXEC_POOL.spawn(move || {
+        rayon_exec_pool().spawn(move || {
             // Explicit async drops.
             drop(last_input_output);
             drop(scheduler);
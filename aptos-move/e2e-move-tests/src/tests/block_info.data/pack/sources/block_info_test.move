@@ -0,0 +1,26 @@
+module 0xcafe::block_info_test {
+    use aptos_framework::block;
+    use std::signer;
+
+    /// Stores the info of the block the last `store_block_info` transaction was executed in, so
+    /// that the e2e tests can read it back.
+    struct BlockInfoStore has key {
+        round: u64,
+        proposer: address,
+        timestamp: u64,
+    }
+
+    public entry fun store_block_info(account: &signer) acquires BlockInfoStore {
+        let round = block::get_current_block_round();
+        let proposer = block::get_current_block_proposer();
+        let timestamp = block::get_current_block_timestamp();
+        if (exists<BlockInfoStore>(signer::address_of(account))) {
+            let store = borrow_global_mut<BlockInfoStore>(signer::address_of(account));
+            store.round = round;
+            store.proposer = proposer;
+            store.timestamp = timestamp;
+        } else {
+            move_to(account, BlockInfoStore { round, proposer, timestamp });
+        }
+    }
+}
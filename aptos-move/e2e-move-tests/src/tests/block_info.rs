@@ -0,0 +1,124 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_abort, assert_success, tests::common, MoveHarness};
+use aptos_crypto::HashValue;
+use aptos_language_e2e_tests::account::Account;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::BlockMetadata,
+    on_chain_config::{FeatureFlag, OnChainConfig, ValidatorSet},
+    transaction::{Transaction, TransactionStatus},
+};
+use aptos_vm::data_cache::AsMoveResolver;
+use move_core_types::parser::parse_struct_tag;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
+struct BlockInfoStore {
+    round: u64,
+    proposer: AccountAddress,
+    timestamp: u64,
+}
+
+fn setup(enabled: bool) -> (MoveHarness, Account) {
+    let mut h = if enabled {
+        MoveHarness::new_with_features(vec![FeatureFlag::BLOCK_INFO], vec![])
+    } else {
+        MoveHarness::new_with_features(vec![], vec![FeatureFlag::BLOCK_INFO])
+    };
+    let account = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(h.publish_package(&account, &common::test_dir_path("block_info.data/pack")));
+    (h, account)
+}
+
+fn proposer(h: &MoveHarness) -> AccountAddress {
+    let validator_set = ValidatorSet::fetch_config(&h.executor.get_state_view().as_move_resolver())
+        .expect("Unable to retrieve the validator set from storage");
+    *validator_set.payload().next().unwrap().account_address()
+}
+
+fn read_block_info(h: &MoveHarness, account: &Account) -> BlockInfoStore {
+    h.read_resource::<BlockInfoStore>(
+        account.address(),
+        parse_struct_tag("0xcafe::block_info_test::BlockInfoStore").unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_block_info_of_previous_block() {
+    let (mut h, account) = setup(true);
+    let proposer = proposer(&h);
+    h.new_block_with_metadata(proposer, vec![]);
+
+    // The transaction is executed on its own, so the block info comes from the block prologue.
+    assert_success!(h.run_entry_function(
+        &account,
+        str::parse("0xcafe::block_info_test::store_block_info").unwrap(),
+        vec![],
+        vec![],
+    ));
+    assert_eq!(
+        read_block_info(&h, &account),
+        BlockInfoStore {
+            round: 0,
+            proposer,
+            timestamp: h.executor.get_block_time(),
+        }
+    );
+}
+
+#[test]
+fn test_block_info_within_block() {
+    let (mut h, account) = setup(true);
+    let proposer = proposer(&h);
+    let timestamp = h.executor.get_block_time() + 1_000_000;
+    let block_metadata =
+        BlockMetadata::new(HashValue::zero(), 0, 7, proposer, vec![], vec![], timestamp);
+    let txn = h.create_entry_function(
+        &account,
+        str::parse("0xcafe::block_info_test::store_block_info").unwrap(),
+        vec![],
+        vec![],
+    );
+
+    let outputs = h
+        .executor
+        .execute_transaction_block(vec![
+            Transaction::BlockMetadata(block_metadata),
+            Transaction::UserTransaction(txn),
+        ])
+        .unwrap();
+    for output in &outputs {
+        assert!(matches!(output.status(), TransactionStatus::Keep(_)));
+        h.executor.apply_write_set(output.write_set());
+    }
+    assert_success!(outputs[1].status().to_owned());
+    assert_eq!(
+        read_block_info(&h, &account),
+        BlockInfoStore {
+            round: 7,
+            proposer,
+            timestamp,
+        }
+    );
+}
+
+#[test]
+fn test_block_info_disabled() {
+    let (mut h, account) = setup(false);
+    let proposer = proposer(&h);
+    h.new_block_with_metadata(proposer, vec![]);
+
+    // error::invalid_state(EBLOCK_INFO_NOT_AVAILABLE)
+    assert_abort!(
+        h.run_entry_function(
+            &account,
+            str::parse("0xcafe::block_info_test::store_block_info").unwrap(),
+            vec![],
+            vec![],
+        ),
+        0x3_0004
+    );
+}
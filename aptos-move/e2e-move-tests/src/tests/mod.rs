@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aggregator;
+mod block_info;
 mod chain_id;
 mod code_publishing;
 mod common;
@@ -16,6 +17,7 @@ mod lazy_natives;
 mod max_loop_depth;
 mod memory_quota;
 mod mint_nft;
+mod native_gating;
 mod new_integer_types;
 mod offer_signer_capability;
 mod rotate_auth_key;
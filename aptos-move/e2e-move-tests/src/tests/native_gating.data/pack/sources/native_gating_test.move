@@ -0,0 +1,30 @@
+module 0x1::native_gating_test {
+    use aptos_std::aptos_hash;
+    use std::features;
+    use std::signer;
+
+    /// Entry functions can't return values, so the hash is stored here for the e2e test to read.
+    struct HashStore has key {
+        hash: vector<u8>,
+    }
+
+    /// Enables or disables the BLAKE2B-256 native, which needs the signer of the framework.
+    public entry fun set_blake2b_256_enabled(framework: &signer, enabled: bool) {
+        let flag = features::get_blake2b_256_feature();
+        if (enabled) {
+            features::change_feature_flags(framework, vector[flag], vector[]);
+        } else {
+            features::change_feature_flags(framework, vector[], vector[flag]);
+        }
+    }
+
+    public entry fun store_blake2b_256(account: &signer, bytes: vector<u8>) acquires HashStore {
+        let hash = aptos_hash::blake2b_256(bytes);
+        let addr = signer::address_of(account);
+        if (exists<HashStore>(addr)) {
+            borrow_global_mut<HashStore>(addr).hash = hash;
+        } else {
+            move_to(account, HashStore { hash });
+        }
+    }
+}
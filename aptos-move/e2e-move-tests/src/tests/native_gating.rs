@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{assert_abort, assert_success, tests::common, MoveHarness};
+use aptos_framework::natives::features::ENATIVE_FUN_NOT_AVAILABLE;
+use aptos_language_e2e_tests::account::Account;
+use aptos_types::{
+    account_address::AccountAddress, on_chain_config::FeatureFlag, transaction::SignedTransaction,
+};
+use move_core_types::parser::parse_struct_tag;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct HashStore {
+    hash: Vec<u8>,
+}
+
+/// BLAKE2B-256 of `testing`
+const TESTING_HASH: &str = "99397ff32ae348b8b6536d5c213f343d7e9fdeaa10e8a23a9f90ab21a1658565";
+
+fn setup() -> (MoveHarness, Account) {
+    let mut h = MoveHarness::new_with_features(vec![], vec![FeatureFlag::BLAKE2B_256_NATIVE]);
+    // The test module changes features, so it's published by the framework
+    let account = h.new_account_at(AccountAddress::ONE);
+    assert_success!(h.publish_package(&account, &common::test_dir_path("native_gating.data/pack")));
+    (h, account)
+}
+
+fn set_enabled(h: &mut MoveHarness, account: &Account, enabled: bool) -> SignedTransaction {
+    h.create_entry_function(
+        account,
+        str::parse("0x1::native_gating_test::set_blake2b_256_enabled").unwrap(),
+        vec![],
+        vec![bcs::to_bytes(&enabled).unwrap()],
+    )
+}
+
+fn store_hash(h: &mut MoveHarness, account: &Account) -> SignedTransaction {
+    h.create_entry_function(
+        account,
+        str::parse("0x1::native_gating_test::store_blake2b_256").unwrap(),
+        vec![],
+        vec![bcs::to_bytes(&b"testing".to_vec()).unwrap()],
+    )
+}
+
+fn stored_hash(h: &MoveHarness, account: &Account) -> String {
+    let store = h
+        .read_resource::<HashStore>(
+            account.address(),
+            parse_struct_tag("0x1::native_gating_test::HashStore").unwrap(),
+        )
+        .unwrap();
+    hex::encode(store.hash)
+}
+
+#[test]
+fn test_gated_native_follows_feature_flag() {
+    let (mut h, account) = setup();
+
+    let txn = store_hash(&mut h, &account);
+    assert_abort!(h.run(txn), ENATIVE_FUN_NOT_AVAILABLE);
+
+    h.enable_features(vec![FeatureFlag::BLAKE2B_256_NATIVE], vec![]);
+    let txn = store_hash(&mut h, &account);
+    assert_success!(h.run(txn));
+    assert_eq!(stored_hash(&h, &account), TESTING_HASH);
+
+    h.enable_features(vec![], vec![FeatureFlag::BLAKE2B_256_NATIVE]);
+    let txn = store_hash(&mut h, &account);
+    assert_abort!(h.run(txn), ENATIVE_FUN_NOT_AVAILABLE);
+}
+
+#[test]
+fn test_gated_native_enabled_within_block() {
+    let (mut h, account) = setup();
+
+    // The natives read the features when they're called, so enabling the feature applies to the
+    // rest of the block, without waiting for a new epoch
+    let txns = vec![
+        set_enabled(&mut h, &account, true),
+        store_hash(&mut h, &account),
+        set_enabled(&mut h, &account, false),
+        store_hash(&mut h, &account),
+    ];
+    let statuses = h.run_block(txns);
+    assert_success!(statuses[0].clone());
+    assert_success!(statuses[1].clone());
+    assert_success!(statuses[2].clone());
+    assert_abort!(statuses[3].clone(), ENATIVE_FUN_NOT_AVAILABLE);
+    assert_eq!(stored_hash(&h, &account), TESTING_HASH);
+}
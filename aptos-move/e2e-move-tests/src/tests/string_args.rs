@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{assert_success, assert_vm_status, tests::common, MoveHarness};
-use aptos_types::account_address::AccountAddress;
+use aptos_types::{account_address::AccountAddress, transaction::EntryFunction};
+use aptos_vm::AptosVM;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::{StructTag, TypeTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::{parser::parse_struct_tag, vm_status::StatusCode};
 use serde::{Deserialize, Serialize};
 
@@ -654,3 +655,65 @@ fn string_args_generic_instantiation() {
 
     fail_generic(vec![address_type, string_type], tests);
 }
+
+#[test]
+fn string_args_check_names_parameter() {
+    let mut h = MoveHarness::new();
+    let acc = h.new_account_at(AccountAddress::from_hex_literal("0xcafe").unwrap());
+    assert_success!(h.publish_package(&acc, &common::test_dir_path("string_args.data/pack")));
+
+    let state_view = h.executor.get_state_view();
+    let vm = AptosVM::new(state_view);
+    let check = |function: &str, args: Vec<Vec<u8>>| {
+        let entry_function = EntryFunction::new(
+            ModuleId::new(*acc.address(), Identifier::new("test").unwrap()),
+            Identifier::new(function).unwrap(),
+            vec![],
+            args,
+        );
+        vm.check_entry_function_arguments(state_view, &entry_function)
+    };
+
+    let s_vec = vec!["hello".as_bytes(), "world".as_bytes()];
+    let good_args = vec![
+        bcs::to_bytes(&s_vec).unwrap(),
+        bcs::to_bytes(&0u64).unwrap(),
+    ];
+    assert_eq!(check("str_vec", good_args), Ok(()));
+
+    // Parameters are counted from the signer
+    let bad = vec![0xc3u8, 0x28u8];
+    let s_vec = vec!["hello".as_bytes(), &bad[..]];
+    let err = check(
+        "str_vec",
+        vec![
+            bcs::to_bytes(&s_vec).unwrap(),
+            bcs::to_bytes(&0u64).unwrap(),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(err.param_index, 1);
+    assert_eq!(err.expected_type, "vector<0x1::string::String>");
+    assert_eq!(err.provided, "an invalid UTF-8 string");
+    assert_eq!(err.status_code, StatusCode::FAILED_TO_DESERIALIZE_ARGUMENT);
+
+    let err = check(
+        "str_vec",
+        vec![
+            bcs::to_bytes(&s_vec).unwrap(),
+            bcs::to_bytes(&0u32).unwrap(),
+        ],
+    )
+    .unwrap_err();
+    assert_eq!(err.param_index, 2);
+    assert_eq!(err.expected_type, "u64");
+    assert_eq!(
+        err.provided,
+        "4 bytes that aren't a valid BCS encoding of it"
+    );
+
+    let err = check("str_vec", vec![bcs::to_bytes(&s_vec).unwrap()]).unwrap_err();
+    assert_eq!(err.param_index, 2);
+    assert_eq!(err.provided, "no argument");
+    assert_eq!(err.status_code, StatusCode::NUMBER_OF_ARGUMENTS_MISMATCH);
+}
@@ -29,7 +29,7 @@ use aptos_gas::{
 use aptos_keygen::KeyGen;
 use aptos_state_view::TStateView;
 use aptos_types::chain_id::ChainId;
-use aptos_types::on_chain_config::{FeatureFlag, Features};
+use aptos_types::on_chain_config::Features;
 use aptos_types::{
     access_path::AccessPath,
     account_config::{
@@ -560,10 +560,8 @@ impl FakeExecutor {
                 NativeGasParameters::zeros(),
                 AbstractValueSizeGasParameters::zeros(),
                 LATEST_GAS_FEATURE_VERSION,
-                self.features
-                    .is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
-                self.features.is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6),
                 self.chain_id,
+                self.features.clone(),
             )
             .unwrap();
             let remote_view = StorageAdapter::new(&self.data_store);
@@ -611,10 +609,8 @@ impl FakeExecutor {
             NativeGasParameters::zeros(),
             AbstractValueSizeGasParameters::zeros(),
             LATEST_GAS_FEATURE_VERSION,
-            self.features
-                .is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
-            self.features.is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6),
             self.chain_id,
+            self.features.clone(),
         )
         .unwrap();
         let remote_view = StorageAdapter::new(&self.data_store);
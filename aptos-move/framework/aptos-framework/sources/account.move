@@ -15,6 +15,7 @@ module aptos_framework::account {
     use aptos_std::multi_ed25519;
 
     friend aptos_framework::aptos_account;
+    friend aptos_framework::block;
     friend aptos_framework::coin;
     friend aptos_framework::genesis;
     friend aptos_framework::resource_account;
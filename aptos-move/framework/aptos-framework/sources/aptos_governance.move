@@ -24,6 +24,7 @@ module aptos_framework::aptos_governance {
     use aptos_framework::account::{Self, SignerCapability, create_signer_with_capability};
     use aptos_framework::coin;
     use aptos_framework::event::{Self, EventHandle};
+    use aptos_framework::gas_schedule;
     use aptos_framework::governance_proposal::{Self, GovernanceProposal};
     use aptos_framework::reconfiguration;
     use aptos_framework::stake;
@@ -382,6 +383,13 @@ module aptos_framework::aptos_governance {
         get_signer(signer_address)
     }
 
+    /// Only called in testnet, to update the gas schedule without going through a proposal.
+    public entry fun set_gas_schedule_testnet_only(
+        core_resources: &signer, gas_schedule_blob: vector<u8>) acquires GovernanceResponsbility {
+        let aptos_framework = get_signer_testnet_only(core_resources, @aptos_framework);
+        gas_schedule::set_gas_schedule(&aptos_framework, gas_schedule_blob);
+    }
+
     /// Resolve a successful multi-step proposal. This would fail if the proposal is not successful.
     public fun resolve_multi_step_proposal(proposal_id: u64, signer_address: address, next_execution_hash: vector<u8>): signer acquires GovernanceResponsbility, ApprovedExecutionHashes {
         voting::resolve_proposal_v2<GovernanceProposal>(@aptos_framework, proposal_id, next_execution_hash);
@@ -398,6 +406,19 @@ module aptos_framework::aptos_governance {
         get_signer(signer_address)
     }
 
+    /// Abort a multi-step proposal that failed mid-chain, e.g. because one of its steps cannot be executed. The
+    /// execution hash of its next step is removed from the approved list, and none of its remaining steps can be
+    /// executed anymore. This is meant to be called by the first step of the proposal superseding it.
+    public fun abort_multi_step_proposal(aptos_framework: &signer, proposal_id: u64) acquires ApprovedExecutionHashes {
+        system_addresses::assert_aptos_framework(aptos_framework);
+        voting::abort_multi_step_proposal<GovernanceProposal>(
+            &governance_proposal::create_empty_proposal(),
+            aptos_framework,
+            proposal_id,
+        );
+        remove_approved_hash(proposal_id);
+    }
+
     /// Remove an approved proposal's execution script hash.
     public fun remove_approved_hash(proposal_id: u64) acquires ApprovedExecutionHashes {
         assert!(
@@ -636,6 +657,54 @@ module aptos_framework::aptos_governance {
         test_can_remove_approved_hash_if_executed_directly_via_voting_generic(aptos_framework, proposer, yes_voter, no_voter, true);
     }
 
+    #[test(aptos_framework = @aptos_framework, proposer = @0x123, yes_voter = @0x234, no_voter = @345)]
+    public entry fun test_abort_multi_step_proposal(
+        aptos_framework: signer,
+        proposer: signer,
+        yes_voter: signer,
+        no_voter: signer,
+    ) acquires ApprovedExecutionHashes, GovernanceConfig, GovernanceEvents, GovernanceResponsbility, VotingRecords {
+        setup_voting(&aptos_framework, &proposer, &yes_voter, &no_voter);
+
+        create_proposal_for_test(proposer, true);
+        vote(&yes_voter, signer::address_of(&yes_voter), 0, true);
+        vote(&no_voter, signer::address_of(&no_voter), 0, false);
+
+        // Execute the first step of the proposal, which approves the hash of the next step.
+        timestamp::update_global_time_for_test(100001000000);
+        add_approved_script_hash(0);
+        let framework_signer = resolve_proposal_for_test(0, @aptos_framework, true, false);
+        let approved_hashes = borrow_global<ApprovedExecutionHashes>(@aptos_framework).hashes;
+        assert!(simple_map::contains_key(&approved_hashes, &0), 0);
+
+        // Abort the remaining steps.
+        abort_multi_step_proposal(&framework_signer, 0);
+        assert!(voting::is_resolved<GovernanceProposal>(@aptos_framework, 0), 1);
+        assert!(!voting::is_multi_step_proposal_in_execution<GovernanceProposal>(@aptos_framework, 0), 2);
+        let approved_hashes = borrow_global<ApprovedExecutionHashes>(@aptos_framework).hashes;
+        assert!(!simple_map::contains_key(&approved_hashes, &0), 3);
+    }
+
+    #[test(aptos_framework = @aptos_framework, proposer = @0x123, yes_voter = @0x234, no_voter = @345)]
+    #[expected_failure(abort_code = 0x50003, location = aptos_framework::system_addresses)]
+    public entry fun test_abort_multi_step_proposal_unauthorized_should_fail(
+        aptos_framework: signer,
+        proposer: signer,
+        yes_voter: signer,
+        no_voter: signer,
+    ) acquires ApprovedExecutionHashes, GovernanceConfig, GovernanceEvents, GovernanceResponsbility, VotingRecords {
+        setup_voting(&aptos_framework, &proposer, &yes_voter, &no_voter);
+
+        create_proposal_for_test(proposer, true);
+        vote(&yes_voter, signer::address_of(&yes_voter), 0, true);
+
+        timestamp::update_global_time_for_test(100001000000);
+        add_approved_script_hash(0);
+        resolve_proposal_for_test(0, @aptos_framework, true, false);
+
+        abort_multi_step_proposal(&yes_voter, 0);
+    }
+
     #[test(aptos_framework = @aptos_framework, proposer = @0x123, voter_1 = @0x234, voter_2 = @345)]
     #[expected_failure(abort_code = 0x10004, location = aptos_framework::voting)]
     public entry fun test_cannot_double_vote(
@@ -762,4 +831,11 @@ module aptos_framework::aptos_governance {
     ) {
         initialize(aptos_framework, min_voting_threshold, required_proposer_stake, voting_duration_secs);
     }
+
+    #[test(account = @0x123)]
+    #[expected_failure(abort_code = 0x50001, location = aptos_framework::system_addresses)]
+    public entry fun test_set_gas_schedule_testnet_only_requires_core_resources(
+        account: signer) acquires GovernanceResponsbility {
+        set_gas_schedule_testnet_only(&account, vector[1]);
+    }
 }
@@ -42,6 +42,16 @@ module aptos_framework::block {
         time_microseconds: u64,
     }
 
+    /// Round, proposer and timestamp of the block being executed, kept up to date by the block prologue
+    /// once the block info feature is enabled. The VM caches them for the transactions of the block, and
+    /// only reads them from here when it starts executing in the middle of a block.
+    /// Should be in-sync with CurrentBlockInfo rust struct in new_block.rs
+    struct CurrentBlockInfo has key {
+        round: u64,
+        proposer: address,
+        timestamp: u64,
+    }
+
     /// Event emitted when a proposal is created.
     struct UpdateEpochIntervalEvent has drop, store {
         old_epoch_interval: u64,
@@ -54,6 +64,8 @@ module aptos_framework::block {
     const EINVALID_PROPOSER: u64 = 2;
     /// Epoch interval cannot be 0.
     const EZERO_EPOCH_INTERVAL: u64 = 3;
+    /// The info of the block being executed isn't available, as the block info feature isn't enabled.
+    const EBLOCK_INFO_NOT_AVAILABLE: u64 = 4;
 
     /// This can only be called during Genesis.
     public(friend) fun initialize(aptos_framework: &signer, epoch_interval_microsecs: u64) {
@@ -106,7 +118,7 @@ module aptos_framework::block {
         failed_proposer_indices: vector<u64>,
         previous_block_votes_bitvec: vector<u8>,
         timestamp: u64
-    ) acquires BlockResource {
+    ) acquires BlockResource, CurrentBlockInfo {
         // Operational constraint: can only be invoked by the VM.
         system_addresses::assert_vm(&vm);
 
@@ -136,6 +148,10 @@ module aptos_framework::block {
         };
         emit_new_block_event(&vm, &mut block_metadata_ref.new_block_events, new_block_event);
 
+        if (features::block_info_enabled()) {
+            set_current_block_info(round, proposer, timestamp);
+        };
+
         if (features::collect_and_distribute_gas_fees()) {
             // Assign the fees collected from the previous block to the previous block proposer.
             // If for any reason the fees cannot be assigned, this function burns the collected coins.
@@ -160,6 +176,45 @@ module aptos_framework::block {
         borrow_global<BlockResource>(@aptos_framework).height
     }
 
+    /// Get the round of the block being executed.
+    /// Aborts with `EBLOCK_INFO_NOT_AVAILABLE` until the block info feature is enabled.
+    public fun get_current_block_round(): u64 {
+        let (round, _, _) = current_block_info();
+        round
+    }
+
+    /// Get the proposer of the block being executed, `@vm_reserved` for NIL blocks.
+    /// Aborts with `EBLOCK_INFO_NOT_AVAILABLE` until the block info feature is enabled.
+    public fun get_current_block_proposer(): address {
+        let (_, proposer, _) = current_block_info();
+        proposer
+    }
+
+    /// Get the timestamp of the block being executed, in microseconds. Unlike
+    /// `timestamp::now_microseconds`, this doesn't read any resource.
+    /// Aborts with `EBLOCK_INFO_NOT_AVAILABLE` until the block info feature is enabled.
+    public fun get_current_block_timestamp(): u64 {
+        let (_, _, timestamp) = current_block_info();
+        timestamp
+    }
+
+    /// Returns the round, proposer and timestamp of the block being executed, as cached by the VM.
+    native fun current_block_info(): (u64, address, u64);
+
+    fun set_current_block_info(round: u64, proposer: address, timestamp: u64) acquires CurrentBlockInfo {
+        if (exists<CurrentBlockInfo>(@aptos_framework)) {
+            let current_block_info = borrow_global_mut<CurrentBlockInfo>(@aptos_framework);
+            current_block_info.round = round;
+            current_block_info.proposer = proposer;
+            current_block_info.timestamp = timestamp;
+        } else {
+            move_to(
+                &account::create_signer(@aptos_framework),
+                CurrentBlockInfo { round, proposer, timestamp },
+            );
+        }
+    }
+
     /// Emit the event and update height and global timestamp
     fun emit_new_block_event(vm: &signer, event_handle: &mut EventHandle<NewBlockEvent>, new_block_event: NewBlockEvent) {
         timestamp::update_global_time(vm, new_block_event.proposer, new_block_event.time_microseconds);
@@ -17,6 +17,15 @@ spec aptos_framework::block {
         aborts_if false;
     }
 
+    spec current_block_info {
+        pragma opaque;
+    }
+
+    spec set_current_block_info {
+        aborts_if false;
+        ensures exists<CurrentBlockInfo>(@aptos_framework);
+    }
+
     spec emit_genesis_block_event {
         use aptos_framework::chain_status;
 
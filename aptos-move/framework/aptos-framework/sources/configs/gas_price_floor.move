@@ -0,0 +1,118 @@
+/// Maintains a minimum gas unit price on top of the one in the gas schedule. Changes are
+/// scheduled for a future epoch, so wallets and other clients can adjust their gas prices before
+/// transactions priced below the new floor start being rejected. This may only be updated by
+/// on-chain governance.
+module aptos_framework::gas_price_floor {
+    use std::error;
+
+    use aptos_framework::reconfiguration;
+    use aptos_framework::system_addresses;
+
+    struct GasPriceFloor has key {
+        /// The floor in effect before `activation_epoch`
+        min_gas_unit_price: u64,
+        /// The floor in effect from `activation_epoch` on
+        next_min_gas_unit_price: u64,
+        activation_epoch: u64,
+    }
+
+    /// The activation epoch must be later than the epoch the change starts, at the end of the
+    /// current epoch
+    const EACTIVATION_EPOCH_NOT_IN_FUTURE: u64 = 1;
+
+    /// Schedules a new gas unit price floor, taking effect at `activation_epoch`. This can be
+    /// called by on-chain governance. A change that was scheduled before but has not activated
+    /// yet is replaced.
+    public fun set(
+        aptos_framework: &signer,
+        min_gas_unit_price: u64,
+        activation_epoch: u64,
+    ) acquires GasPriceFloor {
+        system_addresses::assert_aptos_framework(aptos_framework);
+        let current_epoch = reconfiguration::current_epoch();
+        // The reconfiguration below starts epoch `current_epoch + 1`, which must still run with
+        // the floor in effect so that clients get a full epoch to adjust.
+        assert!(
+            activation_epoch > current_epoch + 1,
+            error::invalid_argument(EACTIVATION_EPOCH_NOT_IN_FUTURE),
+        );
+
+        if (exists<GasPriceFloor>(@aptos_framework)) {
+            let floor = borrow_global_mut<GasPriceFloor>(@aptos_framework);
+            if (current_epoch >= floor.activation_epoch) {
+                floor.min_gas_unit_price = floor.next_min_gas_unit_price;
+            };
+            floor.next_min_gas_unit_price = min_gas_unit_price;
+            floor.activation_epoch = activation_epoch;
+        } else {
+            move_to(aptos_framework, GasPriceFloor {
+                min_gas_unit_price: 0,
+                next_min_gas_unit_price: min_gas_unit_price,
+                activation_epoch,
+            });
+        };
+
+        // Need to trigger reconfiguration so validator nodes can sync on the scheduled floor.
+        reconfiguration::reconfigure();
+    }
+
+    /// Returns the gas unit price floor in effect in the current epoch.
+    public fun min_gas_unit_price(): u64 acquires GasPriceFloor {
+        if (!exists<GasPriceFloor>(@aptos_framework)) {
+            return 0
+        };
+        let floor = borrow_global<GasPriceFloor>(@aptos_framework);
+        if (reconfiguration::current_epoch() >= floor.activation_epoch) {
+            floor.next_min_gas_unit_price
+        } else {
+            floor.min_gas_unit_price
+        }
+    }
+
+    #[test_only]
+    use aptos_framework::account;
+
+    #[test_only]
+    fun initialize_for_test(aptos_framework: &signer) {
+        account::create_account_for_test(@aptos_framework);
+        reconfiguration::initialize_for_test(aptos_framework);
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    fun test_set(aptos_framework: signer) acquires GasPriceFloor {
+        initialize_for_test(&aptos_framework);
+        assert!(min_gas_unit_price() == 0, 0);
+
+        let activation_epoch = reconfiguration::current_epoch() + 2;
+        set(&aptos_framework, 100, activation_epoch);
+        let floor = borrow_global<GasPriceFloor>(@aptos_framework);
+        assert!(floor.min_gas_unit_price == 0, 1);
+        assert!(floor.next_min_gas_unit_price == 100, 2);
+        assert!(floor.activation_epoch == activation_epoch, 3);
+        assert!(min_gas_unit_price() == 0, 4);
+
+        // Rescheduling before the activation replaces the pending change
+        let activation_epoch = reconfiguration::current_epoch() + 3;
+        set(&aptos_framework, 150, activation_epoch);
+        let floor = borrow_global<GasPriceFloor>(@aptos_framework);
+        assert!(floor.min_gas_unit_price == 0, 5);
+        assert!(floor.next_min_gas_unit_price == 150, 6);
+        assert!(floor.activation_epoch == activation_epoch, 7);
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    #[expected_failure(abort_code = 0x10001, location = Self)]
+    fun test_set_activation_epoch_not_in_future(aptos_framework: signer) acquires GasPriceFloor {
+        initialize_for_test(&aptos_framework);
+        set(&aptos_framework, 100, reconfiguration::current_epoch());
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    #[expected_failure(abort_code = 0x10001, location = Self)]
+    fun test_set_activation_epoch_of_reconfiguration(
+        aptos_framework: signer,
+    ) acquires GasPriceFloor {
+        initialize_for_test(&aptos_framework);
+        set(&aptos_framework, 100, reconfiguration::current_epoch() + 1);
+    }
+}
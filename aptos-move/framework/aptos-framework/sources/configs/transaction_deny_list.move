@@ -0,0 +1,94 @@
+/// Maintains a list of senders and entry functions that mempool and the VM reject. This is an
+/// emergency brake for exploited modules, and may only be updated by on-chain governance. It is
+/// only enforced while the `TRANSACTION_DENY_LIST` feature is enabled.
+module aptos_framework::transaction_deny_list {
+    use std::error;
+    use std::string::String;
+    use std::vector;
+
+    use aptos_framework::reconfiguration;
+    use aptos_framework::system_addresses;
+
+    /// An entry function that may not be called. An empty function name denies every entry
+    /// function of the module.
+    struct DeniedFunction has copy, drop, store {
+        module_address: address,
+        module_name: String,
+        function_name: String,
+    }
+
+    struct TransactionDenyList has key {
+        senders: vector<address>,
+        entry_functions: vector<DeniedFunction>,
+    }
+
+    /// The module addresses, module names and function names must have the same length
+    const EMISMATCHED_LENGTHS: u64 = 1;
+
+    /// Replaces the deny list. This can be called by on-chain governance, and takes effect in
+    /// the next epoch.
+    public fun set(
+        aptos_framework: &signer,
+        senders: vector<address>,
+        module_addresses: vector<address>,
+        module_names: vector<String>,
+        function_names: vector<String>,
+    ) acquires TransactionDenyList {
+        system_addresses::assert_aptos_framework(aptos_framework);
+        let len = vector::length(&module_addresses);
+        assert!(
+            vector::length(&module_names) == len && vector::length(&function_names) == len,
+            error::invalid_argument(EMISMATCHED_LENGTHS),
+        );
+
+        let entry_functions = vector::empty<DeniedFunction>();
+        let i = 0;
+        while (i < len) {
+            vector::push_back(&mut entry_functions, DeniedFunction {
+                module_address: *vector::borrow(&module_addresses, i),
+                module_name: *vector::borrow(&module_names, i),
+                function_name: *vector::borrow(&function_names, i),
+            });
+            i = i + 1;
+        };
+
+        if (exists<TransactionDenyList>(@aptos_framework)) {
+            let deny_list = borrow_global_mut<TransactionDenyList>(@aptos_framework);
+            deny_list.senders = senders;
+            deny_list.entry_functions = entry_functions;
+        } else {
+            move_to(aptos_framework, TransactionDenyList { senders, entry_functions });
+        };
+
+        // Need to trigger reconfiguration so validator nodes can sync on the updated deny list.
+        reconfiguration::reconfigure();
+    }
+
+    #[test_only]
+    use std::string;
+
+    #[test(aptos_framework = @aptos_framework)]
+    fun test_set(aptos_framework: signer) acquires TransactionDenyList {
+        set(
+            &aptos_framework,
+            vector[@0xbad],
+            vector[@0xcafe],
+            vector[string::utf8(b"pool")],
+            vector[string::utf8(b"")],
+        );
+        let deny_list = borrow_global<TransactionDenyList>(@aptos_framework);
+        assert!(deny_list.senders == vector[@0xbad], 0);
+        assert!(vector::length(&deny_list.entry_functions) == 1, 1);
+
+        set(&aptos_framework, vector[], vector[], vector[], vector[]);
+        let deny_list = borrow_global<TransactionDenyList>(@aptos_framework);
+        assert!(vector::is_empty(&deny_list.senders), 2);
+        assert!(vector::is_empty(&deny_list.entry_functions), 3);
+    }
+
+    #[test(aptos_framework = @aptos_framework)]
+    #[expected_failure(abort_code = 0x10001, location = Self)]
+    fun test_set_mismatched_lengths(aptos_framework: signer) acquires TransactionDenyList {
+        set(&aptos_framework, vector[], vector[@0xcafe], vector[], vector[]);
+    }
+}
@@ -17,8 +17,10 @@ module aptos_framework::reconfiguration {
     friend aptos_framework::aptos_governance;
     friend aptos_framework::block;
     friend aptos_framework::consensus_config;
+    friend aptos_framework::gas_price_floor;
     friend aptos_framework::gas_schedule;
     friend aptos_framework::genesis;
+    friend aptos_framework::transaction_deny_list;
     friend aptos_framework::version;
 
     /// Event that signals consensus to start a new epoch,
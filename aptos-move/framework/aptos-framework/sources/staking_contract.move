@@ -500,6 +500,12 @@ module aptos_framework::staking_contract {
         distribute_internal(staker, operator, staking_contract, &mut store.distribute_events);
     }
 
+    /// Staker can call this to withdraw the unlocked funds of their staking contract with `operator`. The funds are
+    /// distributed as in `distribute`, so the operator also receives their pending commission.
+    public entry fun withdraw(staker: &signer, operator: address) acquires Store {
+        distribute(signer::address_of(staker), operator);
+    }
+
     /// Distribute all unlocked (inactive) funds according to distribution shares.
     fun distribute_internal(
         staker: address,
@@ -1118,6 +1124,27 @@ module aptos_framework::staking_contract {
         assert!(staker_balance == withdrawn_stake, staker_balance);
     }
 
+    #[test(aptos_framework = @0x1, staker = @0x123, operator = @0x234)]
+    public entry fun test_staker_can_withdraw_unlocked_stake(
+        aptos_framework: &signer, staker: &signer, operator: &signer) acquires Store {
+        let initial_balance = INITIAL_BALANCE * 2;
+        setup_staking_contract(aptos_framework, staker, operator, initial_balance, 10);
+        let staker_address = signer::address_of(staker);
+        let operator_address = signer::address_of(operator);
+        let pool_address = stake_pool_address(staker_address, operator_address);
+
+        let withdrawn_stake = initial_balance / 4;
+        unlock_stake(staker, operator_address, withdrawn_stake);
+        stake::assert_stake_pool(pool_address, initial_balance - withdrawn_stake, 0, 0, withdrawn_stake);
+
+        // The operator never joined the validator set, so the stake is withdrawable once the lockup expires.
+        stake::fast_forward_to_unlock(pool_address);
+        withdraw(staker, operator_address);
+        assert_no_pending_distributions(staker_address, operator_address);
+        let staker_balance = coin::balance<AptosCoin>(staker_address);
+        assert!(staker_balance == withdrawn_stake, staker_balance);
+    }
+
     #[test(aptos_framework = @0x1, staker = @0x123, operator = @0x234)]
     public entry fun test_multiple_distributions_added_before_distribute(
         aptos_framework: &signer, staker: &signer, operator: &signer) acquires Store {
@@ -64,6 +64,8 @@ module aptos_framework::voting {
     const ESINGLE_STEP_PROPOSAL_CANNOT_HAVE_NEXT_EXECUTION_HASH: u64 = 11;
     /// Cannot call `is_multi_step_proposal_in_execution()` on single-step proposals.
     const EPROPOSAL_IS_SINGLE_STEP: u64 = 12;
+    /// Only a multi-step proposal that is in execution can be aborted.
+    const EMULTI_STEP_PROPOSAL_NOT_IN_EXECUTION: u64 = 13;
 
     /// ProposalStateEnum representing proposal state.
     const PROPOSAL_STATE_PENDING: u64 = 0;
@@ -166,6 +168,18 @@ module aptos_framework::voting {
         resolved_early: bool
     }
 
+    /// Events of the aborted multi-step proposals of a forum, stored next to it. They are kept out of VotingEvents,
+    /// which can't get another event handle without changing the layout of the existing forums.
+    struct AbortProposalEvents<phantom ProposalType: store> has key {
+        abort_proposal_events: EventHandle<AbortProposalEvent>,
+    }
+
+    struct AbortProposalEvent has drop, store {
+        proposal_id: u64,
+        yes_votes: u128,
+        no_votes: u128,
+    }
+
     public fun register<ProposalType: store>(account: &signer) {
         let addr = signer::address_of(account);
         assert!(!exists<VotingForum<ProposalType>>(addr), error::already_exists(EVOTING_FORUM_ALREADY_REGISTERED));
@@ -487,6 +501,48 @@ module aptos_framework::voting {
         );
     }
 
+    /// Abort a multi-step proposal that is in execution, e.g. because one of its remaining steps cannot be executed.
+    /// The proposal is marked as resolved, so none of its remaining steps can be executed anymore.
+    ///
+    /// @param _proof Required so only the governance module that defines ProposalType can abort its proposals.
+    /// @param account The account hosting the forum where the proposals are stored, which keeps the abort events.
+    /// @param proposal_id The proposal id.
+    public fun abort_multi_step_proposal<ProposalType: store>(
+        _proof: &ProposalType,
+        account: &signer,
+        proposal_id: u64,
+    ) acquires AbortProposalEvents, VotingForum {
+        let voting_forum_address = signer::address_of(account);
+        let voting_forum = borrow_global_mut<VotingForum<ProposalType>>(voting_forum_address);
+        let proposal = table::borrow_mut(&mut voting_forum.proposals, proposal_id);
+        assert!(!proposal.is_resolved, error::invalid_state(EPROPOSAL_ALREADY_RESOLVED));
+
+        let multi_step_in_execution_key = utf8(IS_MULTI_STEP_PROPOSAL_IN_EXECUTION_KEY);
+        assert!(simple_map::contains_key(&proposal.metadata, &multi_step_in_execution_key), error::invalid_argument(EPROPOSAL_IS_SINGLE_STEP));
+        let is_multi_step_proposal_in_execution_value = simple_map::borrow_mut(&mut proposal.metadata, &multi_step_in_execution_key);
+        assert!(
+            from_bcs::to_bool(*is_multi_step_proposal_in_execution_value),
+            error::invalid_state(EMULTI_STEP_PROPOSAL_NOT_IN_EXECUTION),
+        );
+        *is_multi_step_proposal_in_execution_value = to_bytes(&false);
+
+        proposal.is_resolved = true;
+        proposal.resolution_time_secs = timestamp::now_seconds();
+        let yes_votes = proposal.yes_votes;
+        let no_votes = proposal.no_votes;
+
+        if (!exists<AbortProposalEvents<ProposalType>>(voting_forum_address)) {
+            move_to(account, AbortProposalEvents<ProposalType> {
+                abort_proposal_events: account::new_event_handle<AbortProposalEvent>(account),
+            });
+        };
+        let events = borrow_global_mut<AbortProposalEvents<ProposalType>>(voting_forum_address);
+        event::emit_event<AbortProposalEvent>(
+            &mut events.abort_proposal_events,
+            AbortProposalEvent { proposal_id, yes_votes, no_votes },
+        );
+    }
+
     public fun is_voting_closed<ProposalType: store>(voting_forum_address: address, proposal_id: u64): bool acquires VotingForum {
         let voting_forum = borrow_global_mut<VotingForum<ProposalType>>(voting_forum_address);
         let proposal = table::borrow_mut(&mut voting_forum.proposals, proposal_id);
@@ -958,6 +1014,80 @@ module aptos_framework::voting {
         let TestProposal {} = proof;
     }
 
+    #[test(aptos_framework = @aptos_framework, governance = @0x123)]
+    public entry fun test_abort_multi_step_proposal_in_execution(
+        aptos_framework: signer,
+        governance: signer
+    ) acquires AbortProposalEvents, VotingForum {
+        account::create_account_for_test(@aptos_framework);
+        timestamp::set_time_has_started_for_testing(&aptos_framework);
+
+        // Register voting forum and create a proposal.
+        let governance_address = signer::address_of(&governance);
+        account::create_account_for_test(governance_address);
+        let proposal_id = create_test_proposal_generic(&governance, option::some(100), true);
+
+        // Vote.
+        let proof = TestProposal {};
+        vote<TestProposal>(&proof, governance_address, proposal_id, 100, true);
+
+        // Resolve the first step, then abort the remaining ones.
+        timestamp::fast_forward_seconds(1);
+        resolve_proposal_for_test<TestProposal>(governance_address, proposal_id, true, false);
+        assert!(is_multi_step_proposal_in_execution<TestProposal>(governance_address, proposal_id), 0);
+        abort_multi_step_proposal<TestProposal>(&proof, &governance, proposal_id);
+        let TestProposal {} = proof;
+
+        assert!(is_resolved<TestProposal>(governance_address, proposal_id), 1);
+        assert!(!is_multi_step_proposal_in_execution<TestProposal>(governance_address, proposal_id), 2);
+        let events = borrow_global<AbortProposalEvents<TestProposal>>(governance_address);
+        assert!(event::counter(&events.abort_proposal_events) == 1, 3);
+    }
+
+    #[test(aptos_framework = @aptos_framework, governance = @0x123)]
+    #[expected_failure(abort_code=0x3000d, location = Self)]
+    public entry fun test_cannot_abort_multi_step_proposal_not_in_execution(
+        aptos_framework: signer,
+        governance: signer
+    ) acquires AbortProposalEvents, VotingForum {
+        account::create_account_for_test(@aptos_framework);
+        timestamp::set_time_has_started_for_testing(&aptos_framework);
+
+        // Register voting forum and create a proposal.
+        let governance_address = signer::address_of(&governance);
+        account::create_account_for_test(governance_address);
+        let proposal_id = create_test_proposal_generic(&governance, option::some(100), true);
+
+        let proof = TestProposal {};
+        abort_multi_step_proposal<TestProposal>(&proof, &governance, proposal_id);
+        let TestProposal {} = proof;
+    }
+
+    #[test(aptos_framework = @aptos_framework, governance = @0x123)]
+    #[expected_failure(abort_code=0x30003, location = Self)]
+    public entry fun test_cannot_resolve_aborted_multi_step_proposal(
+        aptos_framework: signer,
+        governance: signer
+    ) acquires AbortProposalEvents, VotingForum {
+        account::create_account_for_test(@aptos_framework);
+        timestamp::set_time_has_started_for_testing(&aptos_framework);
+
+        // Register voting forum and create a proposal.
+        let governance_address = signer::address_of(&governance);
+        account::create_account_for_test(governance_address);
+        let proposal_id = create_test_proposal_generic(&governance, option::some(100), true);
+
+        let proof = TestProposal {};
+        vote<TestProposal>(&proof, governance_address, proposal_id, 100, true);
+        timestamp::fast_forward_seconds(1);
+        resolve_proposal_for_test<TestProposal>(governance_address, proposal_id, true, false);
+        abort_multi_step_proposal<TestProposal>(&proof, &governance, proposal_id);
+        let TestProposal {} = proof;
+
+        // None of the remaining steps can be executed anymore.
+        resolve_proposal_v2<TestProposal>(governance_address, proposal_id, vector::empty<u8>());
+    }
+
     #[test_only]
     public entry fun test_voting_failed_early_generic(aptos_framework: &signer, governance: &signer, is_multi_step: bool) acquires VotingForum {
         account::create_account_for_test(@aptos_framework);
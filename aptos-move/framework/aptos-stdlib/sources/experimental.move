@@ -0,0 +1,68 @@
+/// Calls experimental natives, so that they can be trialed on test networks before their gas costs
+/// are calibrated and they get a module of their own.
+///
+/// The natives run in a sandbox with bounded steps and memory and no access to the state, and are
+/// only available where the `EXPERIMENTAL_NATIVES` feature is enabled.
+module aptos_std::experimental {
+    use std::features;
+
+    //
+    // Constants
+    //
+
+    /// A newly-added native function is not yet enabled.
+    const E_NATIVE_FUN_NOT_AVAILABLE: u64 = 1;
+    /// There is no experimental native with the given name.
+    const EUNKNOWN_EXPERIMENTAL_NATIVE: u64 = 2;
+    /// The native did more steps or used more memory than the sandbox allows.
+    const ESANDBOX_LIMIT_EXCEEDED: u64 = 3;
+    /// The native rejected its input.
+    const EEXPERIMENTAL_NATIVE_FAILED: u64 = 4;
+
+    //
+    // Functions
+    //
+
+    /// Calls the experimental native `name` on `input`, returning its output.
+    public fun call(name: vector<u8>, input: vector<u8>): vector<u8> {
+        if(!features::experimental_natives_enabled()) {
+            abort(std::error::invalid_state(E_NATIVE_FUN_NOT_AVAILABLE))
+        };
+
+        call_internal(name, input)
+    }
+
+    //
+    // Native functions
+    //
+
+    native fun call_internal(name: vector<u8>, input: vector<u8>): vector<u8>;
+
+    //
+    // Testing
+    //
+
+    #[test(fx = @aptos_std)]
+    #[expected_failure(abort_code = 196609, location = Self)]
+    fun call_aborts_when_disabled(fx: signer) {
+        features::change_feature_flags(&fx, vector[], vector[features::get_experimental_natives_feature()]);
+
+        call(b"sha2_256", b"This will abort");
+    }
+
+    #[test(fx = @aptos_std)]
+    fun call_sha2_256(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_experimental_natives_feature()], vector[]);
+
+        let input = b"testing";
+        assert!(call(b"sha2_256", input) == std::hash::sha2_256(input), 1);
+    }
+
+    #[test(fx = @aptos_std)]
+    #[expected_failure(abort_code = 393218, location = Self)]
+    fun call_unknown_native(fx: signer) {
+        features::change_feature_flags(&fx, vector[features::get_experimental_natives_feature()], vector[]);
+
+        call(b"does_not_exist", b"");
+    }
+}
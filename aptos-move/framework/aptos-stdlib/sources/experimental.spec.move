@@ -0,0 +1,5 @@
+spec aptos_std::experimental {
+    spec call_internal(name: vector<u8>, input: vector<u8>): vector<u8> {
+        pragma opaque;
+    }
+}
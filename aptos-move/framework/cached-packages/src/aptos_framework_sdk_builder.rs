@@ -386,6 +386,12 @@ pub enum EntryFunctionCall {
         new_voter: AccountAddress,
     },
 
+    /// Staker can call this to withdraw the unlocked funds of their staking contract with `operator`. The funds are
+    /// distributed as in `distribute`, so the operator also receives their pending commission.
+    StakingContractWithdraw {
+        operator: AccountAddress,
+    },
+
     StakingProxySetOperator {
         old_operator: AccountAddress,
         new_operator: AccountAddress,
@@ -720,6 +726,7 @@ impl EntryFunctionCall {
                 operator,
                 new_voter,
             } => staking_contract_update_voter(operator, new_voter),
+            StakingContractWithdraw { operator } => staking_contract_withdraw(operator),
             StakingProxySetOperator {
                 old_operator,
                 new_operator,
@@ -1851,6 +1858,23 @@ pub fn staking_contract_update_voter(
     ))
 }
 
+/// Staker can call this to withdraw the unlocked funds of their staking contract with `operator`. The funds are
+/// distributed as in `distribute`, so the operator also receives their pending commission.
+pub fn staking_contract_withdraw(operator: AccountAddress) -> TransactionPayload {
+    TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(
+            AccountAddress::new([
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                0, 0, 0, 1,
+            ]),
+            ident_str!("staking_contract").to_owned(),
+        ),
+        ident_str!("withdraw").to_owned(),
+        vec![],
+        vec![bcs::to_bytes(&operator).unwrap()],
+    ))
+}
+
 pub fn staking_proxy_set_operator(
     old_operator: AccountAddress,
     new_operator: AccountAddress,
@@ -2874,6 +2898,16 @@ mod decoder {
         }
     }
 
+    pub fn staking_contract_withdraw(payload: &TransactionPayload) -> Option<EntryFunctionCall> {
+        if let TransactionPayload::EntryFunction(script) = payload {
+            Some(EntryFunctionCall::StakingContractWithdraw {
+                operator: bcs::from_bytes(script.args().get(0)?).ok()?,
+            })
+        } else {
+            None
+        }
+    }
+
     pub fn staking_proxy_set_operator(payload: &TransactionPayload) -> Option<EntryFunctionCall> {
         if let TransactionPayload::EntryFunction(script) = payload {
             Some(EntryFunctionCall::StakingProxySetOperator {
@@ -3334,6 +3368,10 @@ static SCRIPT_FUNCTION_DECODER_MAP: once_cell::sync::Lazy<EntryFunctionDecoderMa
             "staking_contract_update_voter".to_string(),
             Box::new(decoder::staking_contract_update_voter),
         );
+        map.insert(
+            "staking_contract_withdraw".to_string(),
+            Box::new(decoder::staking_contract_withdraw),
+        );
         map.insert(
             "staking_proxy_set_operator".to_string(),
             Box::new(decoder::staking_proxy_set_operator),
@@ -0,0 +1,15 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_framework::generate_error_registry;
+use std::path::PathBuf;
+
+fn main() {
+    // The registry is generated from the released framework bundle rather than the head
+    // sources, so that it describes the errors of the framework actually deployed.
+    println!("cargo:rerun-if-changed=../releases/testnet.mrb");
+    let registry = generate_error_registry(aptos_framework::testnet_release_bundle());
+    let out = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR defined")).join("errors.rs");
+    std::fs::write(&out, registry)
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", out.display(), e));
+}
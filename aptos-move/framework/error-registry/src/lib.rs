@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed registry of the error constants declared by the framework modules. The registry is
+//! generated at build time from the released framework bundle, so the constants and
+//! descriptions match the framework deployed on chain, e.g.,
+//! `aptos_error_registry::aptos_framework::coin::EINSUFFICIENT_BALANCE`.
+
+#![forbid(unsafe_code)]
+
+use move_core_types::language_storage::ModuleId;
+
+/// An error constant declared by a framework module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameworkError {
+    /// Name of the package declaring the module, e.g., `AptosFramework`
+    pub package: &'static str,
+    /// Address of the module as a hex literal, e.g., `0x1`
+    pub address: &'static str,
+    pub module: &'static str,
+    /// The error reason, without the error category
+    pub code: u64,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/errors.rs"));
+
+/// Returns the error a module aborted with. As framework aborts usually carry an error
+/// category in the upper bits, the reason in the lower 12 bits is matched first, then the
+/// full code. This mirrors how the VM resolves the abort info from the module metadata.
+pub fn lookup(module_id: &ModuleId, code: u64) -> Option<&'static FrameworkError> {
+    let address = module_id.address().to_hex_literal();
+    let module = module_id.name().as_str();
+    let find = |code: u64| {
+        FRAMEWORK_ERRORS
+            .iter()
+            .find(|error| error.address == address && error.module == module && error.code == code)
+    };
+    find(code & 0xfff).or_else(|| find(code))
+}
+
+/// Returns the source digest of a framework package the registry was generated from.
+pub fn package_source_digest(package: &str) -> Option<&'static str> {
+    FRAMEWORK_PACKAGES
+        .iter()
+        .find(|(name, _)| *name == package)
+        .map(|(_, digest)| *digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_framework::extract_error_entries;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+
+    #[test]
+    fn test_registry_matches_released_framework() {
+        let bundle = aptos_framework::testnet_release_bundle();
+        let expected = extract_error_entries(bundle);
+        assert_eq!(FRAMEWORK_ERRORS.len(), expected.len());
+        for (error, entry) in FRAMEWORK_ERRORS.iter().zip(expected.iter()) {
+            assert_eq!(error.package, entry.package);
+            assert_eq!(error.address, entry.module_id.address().to_hex_literal());
+            assert_eq!(error.module, entry.module_id.name().as_str());
+            assert_eq!(error.code, entry.code);
+            assert_eq!(error.name, entry.name);
+            assert_eq!(error.description, entry.description);
+        }
+        for package in &bundle.packages {
+            assert_eq!(
+                package_source_digest(package.name()),
+                Some(package.package_metadata().source_digest.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let coin = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+        let error = FRAMEWORK_ERRORS
+            .iter()
+            .find(|error| error.address == "0x1" && error.module == "coin")
+            .expect("coin declares errors");
+        // Aborts raised through `error::invalid_argument` and friends carry a category
+        assert_eq!(lookup(&coin, (1 << 16) | error.code), Some(error));
+        assert_eq!(lookup(&coin, error.code), Some(error));
+
+        let unknown = ModuleId::new(AccountAddress::ONE, Identifier::new("unknown").unwrap());
+        assert_eq!(lookup(&unknown, error.code), None);
+    }
+}
@@ -111,6 +111,39 @@ module std::features {
         is_enabled(BLAKE2B_256_NATIVE)
     }
 
+    /// Whether experimental natives can be called through `aptos_std::experimental`.
+    /// Their gas costs aren't calibrated, so this is only meant to be enabled on test networks.
+    /// Lifetime: ephemeral
+    const EXPERIMENTAL_NATIVES: u64 = 9;
+
+    public fun get_experimental_natives_feature(): u64 { EXPERIMENTAL_NATIVES }
+
+    public fun experimental_natives_enabled(): bool acquires Features {
+        is_enabled(EXPERIMENTAL_NATIVES)
+    }
+
+    /// Whether mempool and the VM reject the transactions denied by
+    /// `aptos_framework::transaction_deny_list`.
+    /// Lifetime: permanent
+    const TRANSACTION_DENY_LIST: u64 = 10;
+
+    public fun get_transaction_deny_list_feature(): u64 { TRANSACTION_DENY_LIST }
+
+    public fun transaction_deny_list_enabled(): bool acquires Features {
+        is_enabled(TRANSACTION_DENY_LIST)
+    }
+
+    /// Whether the round, proposer and timestamp of the block being executed are available
+    /// through `aptos_framework::block`.
+    /// Lifetime: permanent
+    const BLOCK_INFO: u64 = 11;
+
+    public fun get_block_info_feature(): u64 { BLOCK_INFO }
+
+    public fun block_info_enabled(): bool acquires Features {
+        is_enabled(BLOCK_INFO)
+    }
+
     // ============================================================================================
     // Feature Flag Implementation
 
@@ -4,7 +4,7 @@
 #![forbid(unsafe_code)]
 
 use crate::docgen::DocgenOptions;
-use crate::release_builder::RELEASE_BUNDLE_EXTENSION;
+use crate::release_builder::{prove_package, RELEASE_BUNDLE_EXTENSION};
 use crate::release_bundle::ReleaseBundle;
 use crate::{path_in_crate, BuildOptions, ReleaseOptions};
 use clap::ArgEnum;
@@ -134,9 +134,19 @@ impl ReleaseTarget {
                 // Place in current directory
                 PathBuf::from(self.file_name())
             },
+            prove: false,
         }
     }
 
+    /// Verifies the specs of the packages of this target with the Move prover.
+    pub fn prove(self) -> anyhow::Result<()> {
+        let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        for (path, _) in self.packages() {
+            prove_package(&crate_dir.join(path), BTreeMap::new())?;
+        }
+        Ok(())
+    }
+
     pub fn create_release(self, with_srcs: bool, out: Option<PathBuf>) -> anyhow::Result<()> {
         let options = self.create_release_options(with_srcs, out);
         #[cfg(unix)]
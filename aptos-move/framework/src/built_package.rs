@@ -94,6 +94,7 @@ pub(crate) fn build_model(
     package_path: &Path,
     additional_named_addresses: BTreeMap<String, AccountAddress>,
     target_filter: Option<String>,
+    all_files_as_targets: bool,
 ) -> anyhow::Result<GlobalEnv> {
     let build_config = BuildConfig {
         dev_mode: false,
@@ -111,7 +112,7 @@ pub(crate) fn build_model(
         package_path,
         ModelConfig {
             target_filter,
-            all_files_as_targets: false,
+            all_files_as_targets,
         },
     )
 }
@@ -143,6 +144,7 @@ impl BuiltPackage {
             package_path.as_path(),
             options.named_addresses.clone(),
             None,
+            false,
         )?;
         let runtime_metadata = extended_checks::run_extended_checks(model);
         if model.diag_count(Severity::Warning) > 0 {
@@ -0,0 +1,206 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{get_module_metadata, ReleaseBundle};
+use move_binary_format::access::ModuleAccess;
+use move_core_types::language_storage::ModuleId;
+use move_model::code_writer::CodeWriter;
+use move_model::emitln;
+use move_model::model::Loc;
+
+/// An error constant declared by a module of a release bundle, as recorded in the error map
+/// of the module metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleErrorEntry {
+    pub package: String,
+    pub module_id: ModuleId,
+    pub code: u64,
+    pub name: String,
+    pub description: String,
+}
+
+/// Returns the error constants of all modules in the bundle, ordered by package, module and code.
+pub fn extract_error_entries(bundle: &ReleaseBundle) -> Vec<ModuleErrorEntry> {
+    let mut result = vec![];
+    for package in &bundle.packages {
+        for (_, module) in package.sorted_code_and_modules() {
+            let metadata = match get_module_metadata(&module) {
+                Some(metadata) => metadata,
+                None => continue,
+            };
+            let module_id = module.self_id();
+            for (code, descr) in metadata.error_map {
+                result.push(ModuleErrorEntry {
+                    package: package.name().to_string(),
+                    module_id: module_id.clone(),
+                    code,
+                    name: descr.code_name,
+                    description: descr.code_description,
+                });
+            }
+        }
+    }
+    result.sort_by(|a, b| {
+        (&a.package, a.module_id.name(), a.code).cmp(&(&b.package, b.module_id.name(), b.code))
+    });
+    result
+}
+
+/// Generates the Rust source of the error registry for the bundle. It declares the package
+/// source digests, a `FRAMEWORK_ERRORS` table of `FrameworkError` entries, and a `u64`
+/// constant per error under `<package>::<module>`. The including crate must define the
+/// `FrameworkError` struct.
+pub fn generate_error_registry(bundle: &ReleaseBundle) -> String {
+    let entries = extract_error_entries(bundle);
+    let writer = CodeWriter::new(Loc::default());
+    emitln!(
+        writer,
+        "// Generated from the framework release bundle, don't edit.\n"
+    );
+
+    emitln!(
+        writer,
+        "/// Names and source digests of the packages the registry was generated from."
+    );
+    emitln!(writer, "pub const FRAMEWORK_PACKAGES: &[(&str, &str)] = &[");
+    writer.indent();
+    for package in &bundle.packages {
+        emitln!(
+            writer,
+            "({:?}, {:?}),",
+            package.name(),
+            package.package_metadata().source_digest
+        );
+    }
+    writer.unindent();
+    emitln!(writer, "];\n");
+
+    emitln!(writer, "/// All error constants of the framework modules.");
+    emitln!(writer, "pub const FRAMEWORK_ERRORS: &[FrameworkError] = &[");
+    writer.indent();
+    for entry in &entries {
+        emitln!(
+            writer,
+            "FrameworkError {{ package: {:?}, address: {:?}, module: {:?}, code: {}, name: {:?}, description: {:?} }},",
+            entry.package,
+            entry.module_id.address().to_hex_literal(),
+            entry.module_id.name().as_str(),
+            entry.code,
+            entry.name,
+            entry.description
+        );
+    }
+    writer.unindent();
+    emitln!(writer, "];");
+
+    let mut current_package: Option<&str> = None;
+    let mut current_module: Option<&str> = None;
+    for entry in &entries {
+        if current_package != Some(entry.package.as_str()) {
+            if current_module.take().is_some() {
+                writer.unindent();
+                emitln!(writer, "}");
+            }
+            if current_package.is_some() {
+                writer.unindent();
+                emitln!(writer, "}");
+            }
+            emitln!(writer);
+            emitln!(
+                writer,
+                "/// Error constants of the `{}` package.",
+                entry.package
+            );
+            emitln!(
+                writer,
+                "pub mod {} {{",
+                rust_ident(&to_snake_case(&entry.package))
+            );
+            writer.indent();
+            current_package = Some(entry.package.as_str());
+        }
+        if current_module != Some(entry.module_id.name().as_str()) {
+            if current_module.is_some() {
+                writer.unindent();
+                emitln!(writer, "}");
+            }
+            emitln!(
+                writer,
+                "pub mod {} {{",
+                rust_ident(entry.module_id.name().as_str())
+            );
+            writer.indent();
+            current_module = Some(entry.module_id.name().as_str());
+        }
+        for line in entry.description.lines() {
+            emitln!(writer, "/// {}", line.trim());
+        }
+        emitln!(
+            writer,
+            "pub const {}: u64 = {};",
+            rust_ident(&entry.name),
+            entry.code
+        );
+    }
+    if current_module.is_some() {
+        writer.unindent();
+        emitln!(writer, "}");
+    }
+    if current_package.is_some() {
+        writer.unindent();
+        emitln!(writer, "}");
+    }
+
+    writer.process_result(|s| s.to_string())
+}
+
+/// Converts a package name such as `AptosFramework` into `aptos_framework`.
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            result.push(c);
+        } else {
+            result.push('_');
+        }
+    }
+    result
+}
+
+/// Escapes names which are Rust keywords but valid Move identifiers.
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else",
+        "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+        "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type",
+        "unsafe", "use", "where", "while", "yield",
+    ];
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("AptosFramework"), "aptos_framework");
+        assert_eq!(to_snake_case("MoveStdlib"), "move_stdlib");
+        assert_eq!(to_snake_case("my-package"), "my_package");
+    }
+
+    #[test]
+    fn test_rust_ident() {
+        assert_eq!(rust_ident("coin"), "coin");
+        assert_eq!(rust_ident("type"), "r#type");
+    }
+}
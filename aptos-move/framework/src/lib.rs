@@ -11,6 +11,9 @@ use std::io::{Read, Write};
 mod built_package;
 pub use built_package::*;
 
+mod error_registry;
+pub use error_registry::*;
+
 mod module_metadata;
 pub use module_metadata::*;
 
@@ -57,10 +57,17 @@ struct StandardRelease {
     /// Remove the source code from the release package to shrink its size.
     #[clap(long)]
     without_source_code: bool,
+
+    /// Verify the specs of the packages with the Move prover before creating the release.
+    #[clap(long)]
+    prove: bool,
 }
 
 impl StandardRelease {
     fn execute(self) -> anyhow::Result<()> {
+        if self.prove {
+            self.target.prove()?;
+        }
         self.target.create_release(!self.without_source_code, None)
     }
 }
@@ -0,0 +1,88 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::account_config::CurrentBlockInfo;
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::InternalGas;
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, values::Value,
+};
+use smallvec::smallvec;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Abort code of `current_block_info` when the info of the block isn't available, i.e.,
+/// `std::error::invalid_state(EBLOCK_INFO_NOT_AVAILABLE)`.
+pub const EBLOCK_INFO_NOT_AVAILABLE: u64 = 0x3_0004;
+
+/// The native block context extension. It carries the info of the block the session executes a
+/// transaction of, as cached by the VM, so that the natives don't have to read it from storage.
+/// The info is only available once the block info feature is enabled.
+#[derive(Tid)]
+pub struct NativeBlockContext {
+    block_info: Option<CurrentBlockInfo>,
+}
+
+impl NativeBlockContext {
+    pub fn new(block_info: Option<CurrentBlockInfo>) -> Self {
+        Self { block_info }
+    }
+}
+
+/***************************************************************************************************
+ * native fun current_block_info
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+#[derive(Clone, Debug)]
+pub struct CurrentBlockInfoGasParameters {
+    pub base: InternalGas,
+}
+
+fn native_current_block_info(
+    gas_params: &CurrentBlockInfoGasParameters,
+    context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    _args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    let block_context = context.extensions().get::<NativeBlockContext>();
+
+    Ok(match block_context.block_info {
+        Some(block_info) => NativeResult::ok(
+            gas_params.base,
+            smallvec![
+                Value::u64(block_info.round()),
+                Value::address(block_info.proposer()),
+                Value::u64(block_info.timestamp()),
+            ],
+        ),
+        None => NativeResult::err(gas_params.base, EBLOCK_INFO_NOT_AVAILABLE),
+    })
+}
+
+pub fn make_native_current_block_info(gas_params: CurrentBlockInfoGasParameters) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        native_current_block_info(&gas_params, context, ty_args, args)
+    })
+}
+
+/***************************************************************************************************
+ * module
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub current_block_info: CurrentBlockInfoGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [(
+        "current_block_info",
+        make_native_current_block_info(gas_params.current_block_info),
+    )];
+
+    crate::natives::helpers::make_module_natives(natives)
+}
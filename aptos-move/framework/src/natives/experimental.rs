@@ -0,0 +1,217 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A sandbox to trial experimental natives on test networks before their gas costs are
+//! calibrated.
+//!
+//! Experimental natives are plain functions from bytes to bytes, called by name through
+//! `aptos_std::experimental::call`. They don't get a `NativeContext`, so they can't reach the
+//! state, the other native extensions or any IO, and they run under deterministic limits instead
+//! of a timeout: they declare the steps of work they do and the memory they use to the sandbox,
+//! which aborts them once the limits are exceeded. Gas is charged for the bytes in and out and the
+//! steps done, so natives can be metered coarsely until they get gas parameters of their own.
+
+use crate::natives::{features::gate_native, util::make_native_from_func};
+use aptos_types::on_chain_config::FeatureFlag;
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::gas_algebra::{
+    InternalGas, InternalGasPerArg, InternalGasPerByte, NumArgs, NumBytes,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
+};
+use sha2::Digest;
+use smallvec::smallvec;
+use std::collections::VecDeque;
+
+/// Limits of the sandbox. These are part of the protocol, as they decide whether a call aborts.
+pub const MAX_STEPS: u64 = 100_000;
+pub const MAX_MEMORY_BYTES: u64 = 1 << 20;
+
+/// Abort code when there is no experimental native with the given name (0x06 == NOT_FOUND)
+pub const EUNKNOWN_EXPERIMENTAL_NATIVE: u64 = 0x06_0002;
+/// Abort code when a native exceeds the limits of the sandbox (0x09 == RESOURCE_EXHAUSTED)
+pub const ESANDBOX_LIMIT_EXCEEDED: u64 = 0x09_0003;
+/// Abort code when a native rejects its input (0x01 == INVALID_ARGUMENT)
+pub const EEXPERIMENTAL_NATIVE_FAILED: u64 = 0x01_0004;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SandboxError {
+    /// The native did more steps or used more memory than allowed
+    LimitExceeded,
+    /// The native rejected its input
+    InvalidInput,
+}
+
+/// The limits an experimental native runs under, and what it used so far.
+#[derive(Debug)]
+pub struct Sandbox {
+    max_steps: u64,
+    max_memory_bytes: u64,
+    steps: u64,
+    memory_bytes: u64,
+}
+
+impl Sandbox {
+    pub fn new(max_steps: u64, max_memory_bytes: u64) -> Self {
+        Self {
+            max_steps,
+            max_memory_bytes,
+            steps: 0,
+            memory_bytes: 0,
+        }
+    }
+
+    /// Records steps of work, which must be declared before doing the work.
+    pub fn step(&mut self, steps: u64) -> Result<(), SandboxError> {
+        self.steps = self.steps.saturating_add(steps);
+        if self.steps > self.max_steps {
+            return Err(SandboxError::LimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Records memory the native allocates, which must be declared before allocating it. Memory
+    /// is never given back, so this bounds the total allocated during a call.
+    pub fn alloc(&mut self, bytes: usize) -> Result<(), SandboxError> {
+        self.memory_bytes = self.memory_bytes.saturating_add(bytes as u64);
+        if self.memory_bytes > self.max_memory_bytes {
+            return Err(SandboxError::LimitExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps.min(self.max_steps)
+    }
+}
+
+/// An experimental native. It must be deterministic and only work on its input.
+pub type ExperimentalNative = fn(&mut Sandbox, &[u8]) -> Result<Vec<u8>, SandboxError>;
+
+/// The experimental natives, by the name they are called with.
+const EXPERIMENTAL_NATIVES: &[(&str, ExperimentalNative)] = &[("sha2_256", sha2_256)];
+
+pub fn experimental_native(name: &[u8]) -> Option<ExperimentalNative> {
+    EXPERIMENTAL_NATIVES
+        .iter()
+        .find(|(native_name, _)| native_name.as_bytes() == name)
+        .map(|(_, native)| *native)
+}
+
+/// Runs an experimental native in a new sandbox, returning the steps it did along with its output.
+pub fn run_sandboxed(
+    native: ExperimentalNative,
+    input: &[u8],
+    max_steps: u64,
+    max_memory_bytes: u64,
+) -> (u64, Result<Vec<u8>, SandboxError>) {
+    let mut sandbox = Sandbox::new(max_steps, max_memory_bytes);
+    let output = sandbox.alloc(input.len()).and_then(|()| {
+        let output = native(&mut sandbox, input)?;
+        // The output is copied into a Move value
+        sandbox.alloc(output.len())?;
+        Ok(output)
+    });
+    (sandbox.steps(), output)
+}
+
+/// Example experimental native, with one step per block of the input.
+fn sha2_256(sandbox: &mut Sandbox, input: &[u8]) -> Result<Vec<u8>, SandboxError> {
+    sandbox.step(input.len() as u64 / 64 + 1)?;
+    sandbox.alloc(32)?;
+    Ok(sha2::Sha256::digest(input).to_vec())
+}
+
+/***************************************************************************************************
+ * native fun call_internal
+ *
+ *   gas cost: base + per_byte * (input_length + output_length) + per_step * steps
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct CallGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+    pub per_step: InternalGasPerArg,
+}
+
+fn native_call(
+    gas_params: &CallGasParameters,
+    _context: &mut NativeContext,
+    _ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(_ty_args.is_empty());
+    debug_assert!(args.len() == 2);
+
+    let input = pop_arg!(args, Vec<u8>);
+    let name = pop_arg!(args, Vec<u8>);
+
+    let mut cost = gas_params.base + gas_params.per_byte * NumBytes::new(input.len() as u64);
+    let native = match experimental_native(&name) {
+        Some(native) => native,
+        None => return Ok(NativeResult::err(cost, EUNKNOWN_EXPERIMENTAL_NATIVE)),
+    };
+
+    let (steps, output) = run_sandboxed(native, &input, MAX_STEPS, MAX_MEMORY_BYTES);
+    cost += gas_params.per_step * NumArgs::new(steps);
+    match output {
+        Ok(output) => {
+            cost += gas_params.per_byte * NumBytes::new(output.len() as u64);
+            Ok(NativeResult::ok(cost, smallvec![Value::vector_u8(output)]))
+        }
+        Err(SandboxError::LimitExceeded) => Ok(NativeResult::err(cost, ESANDBOX_LIMIT_EXCEEDED)),
+        Err(SandboxError::InvalidInput) => Ok(NativeResult::err(cost, EEXPERIMENTAL_NATIVE_FAILED)),
+    }
+}
+
+/***************************************************************************************************
+ * module
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub call: CallGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [(
+        "call_internal",
+        gate_native(
+            FeatureFlag::EXPERIMENTAL_NATIVES,
+            6,
+            make_native_from_func(gas_params.call, native_call),
+        ),
+    )];
+
+    crate::natives::helpers::make_module_natives(natives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sandbox_enforces_limits() {
+        let native = experimental_native(b"sha2_256").unwrap();
+        let input = vec![0u8; 1000];
+
+        let (steps, output) = run_sandboxed(native, &input, 100, 2000);
+        assert_eq!(steps, 16);
+        assert_eq!(output.unwrap(), sha2::Sha256::digest(&input).to_vec());
+
+        let (steps, output) = run_sandboxed(native, &input, 10, 2000);
+        assert_eq!(steps, 10);
+        assert_eq!(output, Err(SandboxError::LimitExceeded));
+
+        let (_, output) = run_sandboxed(native, &input, 100, 1050);
+        assert_eq!(output, Err(SandboxError::LimitExceeded));
+    }
+
+    #[test]
+    fn unknown_native() {
+        assert!(experimental_native(b"does_not_exist").is_none());
+    }
+}
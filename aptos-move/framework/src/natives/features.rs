@@ -0,0 +1,151 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{FeatureFlag, Features, OnChainConfig},
+};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{resolver::ResourceResolver, vm_status::StatusCode};
+use move_vm_runtime::native_functions::NativeFunction;
+use move_vm_types::natives::function::NativeResult;
+use std::{fmt::Debug, sync::Arc};
+
+/// Abort code of a native called before the feature it belongs to is enabled on chain. This is
+/// `std::error::invalid_state(E_NATIVE_FUN_NOT_AVAILABLE)`, the code the Move wrappers of gated
+/// natives abort with.
+pub const ENATIVE_FUN_NOT_AVAILABLE: u64 = 0x3_0001;
+
+/// Ability to read the on-chain features as of the state a native is called on.
+pub trait FeaturesResolver {
+    fn get_features(&self) -> anyhow::Result<Features>;
+}
+
+impl<R: ResourceResolver + ?Sized> FeaturesResolver for R
+where
+    R::Error: Debug,
+{
+    fn get_features(&self) -> anyhow::Result<Features> {
+        let bytes = self
+            .get_resource(&CORE_CODE_ADDRESS, &Features::struct_tag())
+            .map_err(|err| anyhow::format_err!("{:?}", err))?;
+        match bytes {
+            Some(bytes) => Features::deserialize_into_config(&bytes),
+            // Nothing is enabled before genesis initializes the features
+            None => Ok(Features { features: vec![] }),
+        }
+    }
+}
+
+/// Every feature is enabled for Move unit tests, which toggle features through the Move wrappers
+/// of gated natives within a single session, where the natives can't see the change.
+pub struct AllFeaturesEnabled;
+
+impl FeaturesResolver for AllFeaturesEnabled {
+    fn get_features(&self) -> anyhow::Result<Features> {
+        Ok(Features {
+            features: vec![u8::MAX; 8],
+        })
+    }
+}
+
+/// Exposes the on-chain features to native functions. They're read when a gated native is
+/// called, so a feature enabled in the middle of an epoch applies to the next transaction, as it
+/// does for the Move wrappers of the natives.
+#[derive(Tid)]
+pub struct NativeFeaturesContext<'a> {
+    resolver: &'a dyn FeaturesResolver,
+    gas_feature_version: u64,
+}
+
+impl<'a> NativeFeaturesContext<'a> {
+    pub fn new(resolver: &'a dyn FeaturesResolver, gas_feature_version: u64) -> Self {
+        Self {
+            resolver,
+            gas_feature_version,
+        }
+    }
+
+    /// Whether a native gated on `flag`, whose gas parameters were added at
+    /// `min_gas_feature_version`, can be called.
+    pub fn is_enabled(
+        &self,
+        flag: FeatureFlag,
+        min_gas_feature_version: u64,
+    ) -> PartialVMResult<bool> {
+        if self.gas_feature_version < min_gas_feature_version {
+            return Ok(false);
+        }
+        let features = self.resolver.get_features().map_err(|err| {
+            PartialVMError::new(StatusCode::VM_EXTENSION_ERROR)
+                .with_message(format!("Failed to get the on-chain features: {}", err))
+        })?;
+        Ok(features.is_enabled(flag))
+    }
+}
+
+/// Gates a native function behind a feature flag, and the gas feature version that introduced
+/// its gas parameters. Until both are enabled, calling the native aborts with
+/// `ENATIVE_FUN_NOT_AVAILABLE` in the module declaring it, without charging gas, instead of
+/// failing with an invariant violation.
+pub fn gate_native(
+    flag: FeatureFlag,
+    min_gas_feature_version: u64,
+    native: NativeFunction,
+) -> NativeFunction {
+    Arc::new(move |context, ty_args, args| {
+        if !context
+            .extensions()
+            .get::<NativeFeaturesContext>()
+            .is_enabled(flag, min_gas_feature_version)?
+        {
+            return Ok(NativeResult::err(0.into(), ENATIVE_FUN_NOT_AVAILABLE));
+        }
+        native(context, ty_args, args)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedFeatures(Vec<FeatureFlag>);
+
+    impl FeaturesResolver for FixedFeatures {
+        fn get_features(&self) -> anyhow::Result<Features> {
+            let mut features = Features { features: vec![] };
+            for flag in &self.0 {
+                features.enable(*flag);
+            }
+            Ok(features)
+        }
+    }
+
+    #[test]
+    fn test_is_enabled() {
+        let enabled = FixedFeatures(vec![FeatureFlag::BLAKE2B_256_NATIVE]);
+        let context = NativeFeaturesContext::new(&enabled, 6);
+        assert!(context
+            .is_enabled(FeatureFlag::BLAKE2B_256_NATIVE, 4)
+            .unwrap());
+        assert!(!context
+            .is_enabled(FeatureFlag::EXPERIMENTAL_NATIVES, 4)
+            .unwrap());
+        // The gas parameters of the native don't exist yet
+        assert!(!context
+            .is_enabled(FeatureFlag::BLAKE2B_256_NATIVE, 7)
+            .unwrap());
+
+        let disabled = FixedFeatures(vec![]);
+        let context = NativeFeaturesContext::new(&disabled, 6);
+        assert!(!context
+            .is_enabled(FeatureFlag::BLAKE2B_256_NATIVE, 4)
+            .unwrap());
+
+        let context = NativeFeaturesContext::new(&AllFeaturesEnabled, 6);
+        assert!(context
+            .is_enabled(FeatureFlag::EXPERIMENTAL_NATIVES, 6)
+            .unwrap());
+    }
+}
@@ -1,8 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::natives::util::make_native_from_func;
+use crate::natives::{features::gate_native, util::make_native_from_func};
 
+use aptos_types::on_chain_config::FeatureFlag;
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::gas_algebra::{InternalGas, InternalGasPerByte, NumBytes};
 use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
@@ -206,19 +207,35 @@ pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, Nati
         ),
         (
             "sha2_512_internal",
-            make_native_from_func(gas_params.sha2_512, native_sha2_512),
+            gate_native(
+                FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES,
+                4,
+                make_native_from_func(gas_params.sha2_512, native_sha2_512),
+            ),
         ),
         (
             "sha3_512_internal",
-            make_native_from_func(gas_params.sha3_512, native_sha3_512),
+            gate_native(
+                FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES,
+                4,
+                make_native_from_func(gas_params.sha3_512, native_sha3_512),
+            ),
         ),
         (
             "ripemd160_internal",
-            make_native_from_func(gas_params.ripemd160, native_ripemd160),
+            gate_native(
+                FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES,
+                4,
+                make_native_from_func(gas_params.ripemd160, native_ripemd160),
+            ),
         ),
         (
             "blake2b_256_internal",
-            make_native_from_func(gas_params.blake2b_256, native_blake2b_256),
+            gate_native(
+                FeatureFlag::BLAKE2B_256_NATIVE,
+                4,
+                make_native_from_func(gas_params.blake2b_256, native_blake2b_256),
+            ),
         ),
     ];
 
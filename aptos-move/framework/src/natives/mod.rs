@@ -4,9 +4,12 @@
 pub mod account;
 pub mod aggregator_natives;
 pub mod any;
+pub mod block;
 pub mod code;
 pub mod cryptography;
 pub mod event;
+pub mod experimental;
+pub mod features;
 pub mod hash;
 mod helpers;
 pub mod state_storage;
@@ -33,6 +36,7 @@ pub mod status {
 #[derive(Debug, Clone)]
 pub struct GasParameters {
     pub account: account::GasParameters,
+    pub block: block::GasParameters,
     pub ed25519: ed25519::GasParameters,
     pub bls12381: cryptography::bls12381::GasParameters,
     pub secp256k1: cryptography::secp256k1::GasParameters,
@@ -46,6 +50,7 @@ pub struct GasParameters {
     pub state_storage: state_storage::GasParameters,
     pub aggregator: aggregator::GasParameters,
     pub aggregator_factory: aggregator_factory::GasParameters,
+    pub experimental: experimental::GasParameters,
 }
 
 impl GasParameters {
@@ -55,6 +60,9 @@ impl GasParameters {
                 create_address: account::CreateAddressGasParameters { base: 0.into() },
                 create_signer: account::CreateSignerGasParameters { base: 0.into() },
             },
+            block: block::GasParameters {
+                current_block_info: block::CurrentBlockInfoGasParameters { base: 0.into() },
+            },
             bls12381: cryptography::bls12381::GasParameters {
                 base: 0.into(),
                 per_pubkey_deserialize: 0.into(),
@@ -181,6 +189,13 @@ impl GasParameters {
             aggregator_factory: aggregator_factory::GasParameters {
                 new_aggregator: aggregator_factory::NewAggregatorGasParameters { base: 0.into() },
             },
+            experimental: experimental::GasParameters {
+                call: experimental::CallGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                    per_step: 0.into(),
+                },
+            },
         }
     }
 }
@@ -201,6 +216,7 @@ pub fn all_natives(
     }
 
     add_natives_from_module!("account", account::make_all(gas_params.account.clone()));
+    add_natives_from_module!("block", block::make_all(gas_params.block));
     add_natives_from_module!("ed25519", ed25519::make_all(gas_params.ed25519.clone()));
     add_natives_from_module!("genesis", account::make_all(gas_params.account));
     add_natives_from_module!("multi_ed25519", multi_ed25519::make_all(gas_params.ed25519));
@@ -238,6 +254,10 @@ pub fn all_natives(
         "aggregator_factory",
         aggregator_factory::make_all(gas_params.aggregator_factory)
     );
+    add_natives_from_module!(
+        "experimental",
+        experimental::make_all(gas_params.experimental)
+    );
 
     make_table_from_iter(framework_addr, natives)
 }
@@ -2,16 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::build_model;
-use codespan_reporting::diagnostic::Severity;
-use codespan_reporting::term::termcolor::{ColorChoice, StandardStream};
+use anyhow::{bail, Context};
+use codespan_reporting::diagnostic::{LabelStyle, Severity};
+use codespan_reporting::term::termcolor::{ColorChoice, NoColor, StandardStream};
 use log::LevelFilter;
 use move_core_types::account_address::AccountAddress;
+use move_package::source_package::manifest_parser::parse_move_manifest_string;
 use std::collections::BTreeMap;
 use std::path::Path;
 use std::time::Instant;
 use tempfile::TempDir;
 
-#[derive(Debug, Clone, clap::Parser, serde::Serialize, serde::Deserialize)]
+/// Name of the file next to `Move.toml` holding the prover options of a package. They aren't
+/// kept in `Move.toml`, where the package system warns about sections it doesn't know.
+pub const PROVER_OPTIONS_FILE: &str = "Prover.toml";
+
+/// Options of the Move prover
+///
+/// They can also be set per package in the `Prover.toml` file next to its `Move.toml`, e.g.
+///
+/// ```toml
+/// vc_timeout = 80
+/// with_dependencies = true
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, clap::Parser, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct ProverOptions {
     /// Verbosity level
     #[clap(long, short)]
@@ -32,21 +47,23 @@ pub struct ProverOptions {
     #[clap(long)]
     pub cvc5: bool,
 
-    /// The depth until which stratified functions are expanded.
-    #[clap(long, default_value_t = 6)]
-    pub stratification_depth: usize,
+    /// The depth until which stratified functions are expanded. Defaults to 6.
+    #[clap(long)]
+    pub stratification_depth: Option<usize>,
 
-    /// A seed for the prover.
-    #[clap(long, default_value_t = 0)]
-    pub random_seed: usize,
+    /// A seed for the prover. Defaults to 0.
+    #[clap(long)]
+    pub random_seed: Option<usize>,
 
     /// The number of cores to use for parallel processing of verification conditions.
-    #[clap(long, default_value_t = 4)]
-    pub proc_cores: usize,
+    /// Defaults to 4.
+    #[clap(long)]
+    pub proc_cores: Option<usize>,
 
-    /// A (soft) timeout for the solver, per verification condition, in seconds.
-    #[clap(long, default_value_t = 40)]
-    pub vc_timeout: usize,
+    /// A (soft) timeout for the solver, per verification condition, in seconds. Defaults to
+    /// 40.
+    #[clap(long)]
+    pub vc_timeout: Option<usize>,
 
     /// Whether to check consistency of specs by injecting impossible assertions.
     #[clap(long)]
@@ -69,41 +86,100 @@ pub struct ProverOptions {
     #[clap(long)]
     pub dump: bool,
 
+    /// Whether to also verify the dependencies of the package, instead of only its own
+    /// modules.
+    #[clap(long)]
+    pub with_dependencies: bool,
+
     #[clap(skip)]
+    #[serde(skip)]
     pub for_test: bool,
 }
 
-impl Default for ProverOptions {
-    fn default() -> Self {
-        Self {
-            verbosity: None,
-            filter: None,
-            trace: false,
-            cvc5: false,
-            stratification_depth: 6,
-            random_seed: 0,
-            proc_cores: 4,
-            vc_timeout: 40,
-            check_inconsistency: false,
-            keep_loops: false,
-            loop_unroll: None,
-            stable_test_output: false,
-            dump: false,
-            for_test: false,
-        }
+/// A diagnostic reported by the prover, e.g. a spec which doesn't hold
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProverDiagnostic {
+    /// One of `bug`, `error`, `warning`, `note` or `help`
+    pub severity: String,
+    pub message: String,
+    /// File of the primary location of the diagnostic, if any
+    pub file: Option<String>,
+    /// Line of the primary location of the diagnostic, starting at 1
+    pub line: Option<usize>,
+    pub notes: Vec<String>,
+}
+
+impl ProverDiagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == "error" || self.severity == "bug"
     }
 }
 
 impl ProverOptions {
+    /// Reads the options of the `Prover.toml` file of the package, if there is one.
+    pub fn from_package(package_path: &Path) -> anyhow::Result<Option<Self>> {
+        let path = package_path.join(PROVER_OPTIONS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let options = parse_move_manifest_string(std::fs::read_to_string(&path)?)
+            .and_then(|options| Ok(options.try_into()?))
+            .with_context(|| format!("failed to parse `{}`", path.display()))?;
+        Ok(Some(options))
+    }
+
+    /// Overrides these options with the ones set in `overrides`, e.g. options of the package
+    /// with the ones given on the command line.  Flags can only be turned on.
+    pub fn merge(self, overrides: Self) -> Self {
+        Self {
+            verbosity: overrides.verbosity.or(self.verbosity),
+            filter: overrides.filter.or(self.filter),
+            trace: self.trace || overrides.trace,
+            cvc5: self.cvc5 || overrides.cvc5,
+            stratification_depth: overrides.stratification_depth.or(self.stratification_depth),
+            random_seed: overrides.random_seed.or(self.random_seed),
+            proc_cores: overrides.proc_cores.or(self.proc_cores),
+            vc_timeout: overrides.vc_timeout.or(self.vc_timeout),
+            check_inconsistency: self.check_inconsistency || overrides.check_inconsistency,
+            keep_loops: self.keep_loops || overrides.keep_loops,
+            loop_unroll: overrides.loop_unroll.or(self.loop_unroll),
+            stable_test_output: self.stable_test_output || overrides.stable_test_output,
+            dump: self.dump || overrides.dump,
+            with_dependencies: self.with_dependencies || overrides.with_dependencies,
+            for_test: self.for_test || overrides.for_test,
+        }
+    }
+
     /// Runs the move prover on the package.
     pub fn prove(
         self,
         package_path: &Path,
         named_addresses: BTreeMap<String, AccountAddress>,
     ) -> anyhow::Result<()> {
+        let diagnostics = self.prove_with_diagnostics(package_path, named_addresses)?;
+        if diagnostics.iter().any(ProverDiagnostic::is_error) {
+            bail!("exiting with verification errors")
+        }
+        Ok(())
+    }
+
+    /// Runs the move prover on the package, and returns the diagnostics it reported.
+    ///
+    /// The diagnostics are also printed to stderr.  Verification errors are returned as
+    /// diagnostics, while failures to run the prover are returned as errors.
+    pub fn prove_with_diagnostics(
+        self,
+        package_path: &Path,
+        named_addresses: BTreeMap<String, AccountAddress>,
+    ) -> anyhow::Result<Vec<ProverDiagnostic>> {
         let now = Instant::now();
         let for_test = self.for_test;
-        let model = build_model(package_path, named_addresses, self.filter.clone())?;
+        let model = build_model(
+            package_path,
+            named_addresses,
+            self.filter.clone(),
+            self.with_dependencies,
+        )?;
         let mut options = self.convert_options();
         // Need to ensure a distinct output.bpl file for concurrent execution. In non-test
         // mode, we actually want to use the static output.bpl for debugging purposes
@@ -124,8 +200,51 @@ impl ProverOptions {
             None
         };
         let mut writer = StandardStream::stderr(ColorChoice::Auto);
-        move_prover::run_move_prover_with_model(&model, &mut writer, options, Some(now))?;
-        Ok(())
+        let result =
+            move_prover::run_move_prover_with_model(&model, &mut writer, options, Some(now));
+
+        // Diagnostics are collected from the model, where the prover reported them
+        let mut diagnostics = vec![];
+        model.report_diag_with_filter(&mut NoColor::new(std::io::sink()), |diagnostic| {
+            let location = diagnostic
+                .labels
+                .iter()
+                .find(|label| label.style == LabelStyle::Primary)
+                .map(|label| {
+                    let file = model.get_file(label.file_id).to_string_lossy().to_string();
+                    let line = std::fs::read_to_string(&file).ok().map(|source| {
+                        source[..label.range.start.min(source.len())]
+                            .matches('\n')
+                            .count()
+                            + 1
+                    });
+                    (file, line)
+                });
+            diagnostics.push(ProverDiagnostic {
+                severity: format!("{:?}", diagnostic.severity).to_lowercase(),
+                message: diagnostic.message.clone(),
+                file: location.as_ref().map(|(file, _)| file.clone()),
+                line: location.and_then(|(_, line)| line),
+                notes: diagnostic.notes.clone(),
+            });
+            false
+        });
+        match result {
+            Ok(()) => Ok(diagnostics),
+            Err(err) if model.has_errors() => {
+                if !diagnostics.iter().any(ProverDiagnostic::is_error) {
+                    diagnostics.push(ProverDiagnostic {
+                        severity: "error".to_string(),
+                        message: format!("{:#}", err),
+                        file: None,
+                        line: None,
+                        notes: vec![],
+                    });
+                }
+                Ok(diagnostics)
+            }
+            Err(err) => Err(err),
+        }
     }
 
     fn convert_options(self) -> move_prover::cli::Options {
@@ -157,9 +276,9 @@ impl ProverOptions {
                 use_cvc5: self.cvc5,
                 boogie_flags: vec![],
                 generate_smt: self.dump,
-                stratification_depth: self.stratification_depth,
-                proc_cores: self.proc_cores,
-                vc_timeout: self.vc_timeout,
+                stratification_depth: self.stratification_depth.unwrap_or(6),
+                proc_cores: self.proc_cores.unwrap_or(4),
+                vc_timeout: self.vc_timeout.unwrap_or(40),
                 keep_artifacts: self.dump,
                 stable_test_output: self.stable_test_output,
                 z3_trace_file: if self.dump {
@@ -3,11 +3,14 @@
 
 use crate::built_package::{BuildOptions, BuiltPackage};
 use crate::path_relative_to_crate;
+use crate::prover::ProverOptions;
 use crate::release_bundle::{ReleaseBundle, ReleasePackage};
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use aptos_sdk_builder::rust;
 use aptos_types::transaction::EntryABI;
 use clap::Parser;
+use move_core_types::account_address::AccountAddress;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 pub const RELEASE_BUNDLE_EXTENSION: &str = "mrb";
@@ -29,6 +32,10 @@ pub struct ReleaseOptions {
     /// The path to the file where to place the release bundle.
     #[clap(long, default_value = "head.mrb", parse(from_os_str))]
     pub output: PathBuf,
+    /// Whether to verify the specs of each package with the Move prover before releasing it,
+    /// with the prover options of its `Move.toml`.
+    #[clap(long)]
+    pub prove: bool,
 }
 
 impl ReleaseOptions {
@@ -40,11 +47,15 @@ impl ReleaseOptions {
             packages,
             rust_bindings,
             output,
+            prove,
         } = self;
         let mut released_packages = vec![];
         let mut source_paths = vec![];
         for (package_path, rust_binding_path) in packages.into_iter().zip(rust_bindings.into_iter())
         {
+            if prove {
+                prove_package(&package_path, build_options.named_addresses.clone())?;
+            }
             let built = BuiltPackage::build(package_path.clone(), build_options.clone())?;
             if !rust_binding_path.is_empty() {
                 let abis = built
@@ -82,3 +93,15 @@ impl ReleaseOptions {
         Ok(())
     }
 }
+
+/// Verifies the specs of a package with the Move prover, with the prover options of its
+/// `Prover.toml`, failing if any of them doesn't hold.
+pub fn prove_package(
+    package_path: &Path,
+    named_addresses: BTreeMap<String, AccountAddress>,
+) -> anyhow::Result<()> {
+    ProverOptions::from_package(package_path)?
+        .unwrap_or_default()
+        .prove(package_path, named_addresses)
+        .with_context(|| format!("failed to verify `{}`", package_path.display()))
+}
@@ -328,7 +328,6 @@ impl ReleasePackage {
                 next_execution_hash,
             );
         } else {
-            let next_execution_hash_bytes = next_execution_hash.as_bytes();
             emitln!(
                 writer,
                 "let framework_signer = aptos_governance::resolve_multi_step_proposal("
@@ -336,16 +335,9 @@ impl ReleasePackage {
             writer.indent();
             emitln!(writer, "proposal_id,");
             emitln!(writer, "@{},", for_address);
-            emit!(writer, "vector[");
-            for (i, b) in next_execution_hash_bytes.iter().enumerate() {
-                if (i + 1) % 20 == 0 {
-                    emitln!(writer);
-                }
-                emit!(writer, "{}u8,", b);
-            }
-            emitln!(writer, "],");
+            emitln!(writer, "x\"{}\",", next_execution_hash);
             writer.unindent();
-            emitln!(writer, "};");
+            emitln!(writer, ");");
         }
     }
 }
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_framework::prover::ProverOptions;
+use aptos_framework::prover::{ProverOptions, PROVER_OPTIONS_FILE};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
@@ -20,12 +20,42 @@ where
 
 pub fn run_prover_for_pkg(path_to_pkg: impl Into<String>) {
     let pkg_path = path_in_crate(path_to_pkg);
-    let options = ProverOptions::default_for_test();
+    let options = ProverOptions::from_package(pkg_path.as_path())
+        .unwrap()
+        .unwrap_or_default()
+        .merge(ProverOptions::default_for_test());
     options
         .prove(pkg_path.as_path(), BTreeMap::default())
         .unwrap()
 }
 
+#[test]
+fn prover_options_from_package() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(ProverOptions::from_package(dir.path()).unwrap(), None);
+
+    let options_file = dir.path().join(PROVER_OPTIONS_FILE);
+    std::fs::write(&options_file, "vc_timeout = 80\nwith_dependencies = true\n").unwrap();
+    let options = ProverOptions::from_package(dir.path()).unwrap().unwrap();
+    assert_eq!(options.vc_timeout, Some(80));
+    assert!(options.with_dependencies);
+    assert_eq!(options.proc_cores, None);
+
+    // Options set on the command line take precedence over the package, even when they're
+    // set to the default value
+    let options = options.merge(ProverOptions {
+        vc_timeout: Some(40),
+        proc_cores: Some(8),
+        ..ProverOptions::default()
+    });
+    assert_eq!(options.vc_timeout, Some(40));
+    assert_eq!(options.proc_cores, Some(8));
+    assert!(options.with_dependencies);
+
+    std::fs::write(&options_file, "vc_timeut = 80\n").unwrap();
+    assert!(ProverOptions::from_package(dir.path()).is_err());
+}
+
 #[ignore]
 #[test]
 fn move_framework_prover_tests() {
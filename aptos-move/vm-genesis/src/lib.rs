@@ -68,6 +68,8 @@ pub struct GenesisConfiguration {
     pub voting_power_increase_limit: u64,
     pub employee_vesting_start: u64,
     pub employee_vesting_period_duration: u64,
+    /// The feature flags enabled at genesis.
+    pub initial_features: Vec<FeatureFlag>,
 }
 
 pub static GENESIS_KEYPAIR: Lazy<(Ed25519PrivateKey, Ed25519PublicKey)> = Lazy::new(|| {
@@ -77,11 +79,25 @@ pub static GENESIS_KEYPAIR: Lazy<(Ed25519PrivateKey, Ed25519PublicKey)> = Lazy::
     (private_key, public_key)
 });
 
+/// The feature flags enabled at genesis by default.
+pub fn default_features() -> Vec<FeatureFlag> {
+    vec![
+        FeatureFlag::CODE_DEPENDENCY_CHECK,
+        FeatureFlag::TREAT_FRIEND_AS_PRIVATE,
+        FeatureFlag::VM_BINARY_FORMAT_V6,
+    ]
+}
+
 // Cannot be impl Default in GasScheduleV2, due to circular dependencies.
 pub fn default_gas_schedule() -> GasScheduleV2 {
+    gas_schedule_for_feature_version(LATEST_GAS_FEATURE_VERSION)
+}
+
+/// The initial gas schedule, as it was at the given gas feature version.
+pub fn gas_schedule_for_feature_version(feature_version: u64) -> GasScheduleV2 {
     GasScheduleV2 {
-        feature_version: aptos_gas::LATEST_GAS_FEATURE_VERSION,
-        entries: AptosGasParameters::initial().to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION),
+        feature_version,
+        entries: AptosGasParameters::initial().to_on_chain_gas_schedule(feature_version),
     }
 }
 
@@ -106,9 +122,8 @@ pub fn encode_aptos_mainnet_genesis_transaction(
         NativeGasParameters::zeros(),
         AbstractValueSizeGasParameters::zeros(),
         LATEST_GAS_FEATURE_VERSION,
-        Features::default().is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
-        Features::default().is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6),
         ChainId::test().id(),
+        Features::default(),
     )
     .unwrap();
     let id1 = HashValue::zero();
@@ -124,7 +139,7 @@ pub fn encode_aptos_mainnet_genesis_transaction(
         &consensus_config,
         &gas_schedule,
     );
-    initialize_features(&mut session);
+    initialize_features(&mut session, &genesis_config.initial_features);
     initialize_aptos_coin(&mut session);
     initialize_on_chain_governance(&mut session, genesis_config);
     create_accounts(&mut session, accounts);
@@ -215,9 +230,8 @@ pub fn encode_genesis_change_set(
         NativeGasParameters::zeros(),
         AbstractValueSizeGasParameters::zeros(),
         LATEST_GAS_FEATURE_VERSION,
-        Features::default().is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE),
-        Features::default().is_enabled(FeatureFlag::VM_BINARY_FORMAT_V6),
         ChainId::test().id(),
+        Features::default(),
     )
     .unwrap();
     let id1 = HashValue::zero();
@@ -231,7 +245,7 @@ pub fn encode_genesis_change_set(
         consensus_config,
         gas_schedule,
     );
-    initialize_features(&mut session);
+    initialize_features(&mut session, &genesis_config.initial_features);
     if genesis_config.is_test {
         initialize_core_resources_and_aptos_coin(&mut session, core_resources_key);
     } else {
@@ -398,8 +412,8 @@ fn initialize(
     );
 }
 
-fn initialize_features(session: &mut SessionExt<impl MoveResolver>) {
-    let features: Vec<u64> = vec![1, 2, 5];
+fn initialize_features(session: &mut SessionExt<impl MoveResolver>, features: &[FeatureFlag]) {
+    let features: Vec<u64> = features.iter().map(|feature| *feature as u64).collect();
 
     let mut serialized_values = serialize_values(&vec![MoveValue::Signer(CORE_CODE_ADDRESS)]);
     serialized_values.push(bcs::to_bytes(&features).unwrap());
@@ -782,6 +796,7 @@ pub fn generate_test_genesis(
             voting_power_increase_limit: 50,
             employee_vesting_start: 1663456089,
             employee_vesting_period_duration: 5 * 60, // 5 minutes
+            initial_features: default_features(),
         },
         &OnChainConsensusConfig::default(),
         &default_gas_schedule(),
@@ -827,6 +842,7 @@ fn mainnet_genesis_config() -> GenesisConfiguration {
         voting_power_increase_limit: 30,
         employee_vesting_start: 1663456089,
         employee_vesting_period_duration: 5 * 60, // 5 minutes
+        initial_features: default_features(),
     }
 }
 
@@ -869,9 +885,8 @@ pub fn test_genesis_module_publishing() {
         NativeGasParameters::zeros(),
         AbstractValueSizeGasParameters::zeros(),
         LATEST_GAS_FEATURE_VERSION,
-        false,
-        true,
         ChainId::test().id(),
+        Features::default(),
     )
     .unwrap();
     let id1 = HashValue::zero();
@@ -0,0 +1,351 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Context, Result};
+use aptos_config::config::{ApiConfig, NodeConfig};
+use aptos_logger::{error, info, warn, LoggerFilterUpdater};
+use aptos_mempool::MempoolCapacityUpdater;
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+use tokio::runtime::{Builder, Runtime};
+
+/// A config field that was changed by a reload
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConfigChange {
+    pub field: &'static str,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+macro_rules! copy_changed_fields {
+    ($current:ident, $new:ident, $changes:ident, $($section:ident . $field:ident),* $(,)?) => {
+        $(
+        if $current.$section.$field != $new.$section.$field {
+            $changes.push(ConfigChange {
+                field: concat!(stringify!($section), ".", stringify!($field)),
+                old_value: format!("{:?}", $current.$section.$field),
+                new_value: format!("{:?}", $new.$section.$field),
+            });
+            $current.$section.$field = $new.$section.$field.clone();
+        }
+        )*
+    };
+}
+
+/// Copies the fields that can be changed on a running node from `new` into `current`, and
+/// returns the ones that changed. Everything else in `new` is ignored.
+pub fn apply_reloadable_fields(current: &mut NodeConfig, new: &NodeConfig) -> Vec<ConfigChange> {
+    let mut changes = vec![];
+    copy_changed_fields!(
+        current,
+        new,
+        changes,
+        logger.level,
+        api.json_output_enabled,
+        api.bcs_output_enabled,
+        api.encode_submission_enabled,
+        api.transaction_submission_enabled,
+        api.transaction_simulation_enabled,
+        api.max_submit_transaction_batch_size,
+        api.max_batch_read_size,
+        api.max_transactions_page_size,
+        api.max_events_page_size,
+        api.max_account_resources_page_size,
+        api.max_account_modules_page_size,
+        api.max_gas_view_function,
+        api.cors_allowed_origins,
+        api.max_requests_per_second,
+        mempool.capacity,
+        mempool.capacity_bytes,
+        mempool.capacity_per_user,
+    );
+    changes
+}
+
+/// Whether `new` differs from `old` in any field that can only be changed by a restart
+pub fn has_restart_only_changes(old: &NodeConfig, new: &NodeConfig) -> bool {
+    let mut old = old.clone();
+    apply_reloadable_fields(&mut old, new);
+    old != *new
+}
+
+/// Checks the reloadable fields of `config`, so that a bad edit is rejected as a whole instead
+/// of being partially applied.
+pub fn validate_reloadable_fields(config: &NodeConfig) -> Result<()> {
+    let api = &config.api;
+    ensure!(
+        api.max_transactions_page_size > 0
+            && api.max_events_page_size > 0
+            && api.max_account_resources_page_size > 0
+            && api.max_account_modules_page_size > 0,
+        "API page sizes must be greater than 0"
+    );
+    ensure!(
+        api.max_submit_transaction_batch_size > 0 && api.max_batch_read_size > 0,
+        "API batch sizes must be greater than 0"
+    );
+    ensure!(
+        api.max_gas_view_function > 0,
+        "api.max_gas_view_function must be greater than 0"
+    );
+    ensure!(
+        api.max_requests_per_second != Some(0),
+        "api.max_requests_per_second must be greater than 0"
+    );
+    for origin in &api.cors_allowed_origins {
+        let url = url::Url::parse(origin)
+            .with_context(|| format!("Invalid origin {:?} in api.cors_allowed_origins", origin))?;
+        ensure!(
+            url.path() == "/" && url.query().is_none() && url.fragment().is_none(),
+            "The origin {:?} in api.cors_allowed_origins can't have a path",
+            origin
+        );
+    }
+
+    let mempool = &config.mempool;
+    ensure!(
+        mempool.capacity > 0 && mempool.capacity_bytes > 0 && mempool.capacity_per_user > 0,
+        "Mempool capacities must be greater than 0"
+    );
+    ensure!(
+        mempool.capacity_per_user <= mempool.capacity,
+        "mempool.capacity_per_user ({}) can't be larger than mempool.capacity ({})",
+        mempool.capacity_per_user,
+        mempool.capacity
+    );
+    Ok(())
+}
+
+/// Handles to the running components that pick up reloaded config values
+#[derive(Clone)]
+pub struct ReloadableComponents {
+    /// Not set when the API is disabled
+    pub api_config: Option<Arc<RwLock<ApiConfig>>>,
+    pub logger_filter_updater: Option<LoggerFilterUpdater>,
+    pub mempool_capacity_updater: MempoolCapacityUpdater,
+}
+
+/// Reloads the node config file on SIGHUP and applies the fields that don't need a restart:
+/// the log level, the API limits, CORS origins and feature toggles, and the mempool capacities.
+/// Every applied change is logged with its old and new value.
+pub struct ConfigReloader {
+    config_path: PathBuf,
+    current_config: NodeConfig,
+    /// The config file as it was last read. The running config can't tell whether an edit
+    /// needs a restart, because the node adjusts it at startup.
+    loaded_config: Option<NodeConfig>,
+    components: ReloadableComponents,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        config_path: PathBuf,
+        current_config: NodeConfig,
+        components: ReloadableComponents,
+    ) -> Self {
+        let loaded_config = NodeConfig::load(&config_path).ok();
+        Self {
+            config_path,
+            current_config,
+            loaded_config,
+            components,
+        }
+    }
+
+    /// Reads the config file again and applies the reloadable fields that changed. Nothing is
+    /// applied if the file can't be loaded or any of the new values is invalid.
+    pub fn reload(&mut self) -> Result<Vec<ConfigChange>> {
+        let new_config = NodeConfig::load(&self.config_path).with_context(|| {
+            format!(
+                "Failed to load node config from {}",
+                self.config_path.display()
+            )
+        })?;
+        validate_reloadable_fields(&new_config)?;
+
+        if let Some(loaded_config) = &self.loaded_config {
+            if has_restart_only_changes(loaded_config, &new_config) {
+                warn!("The node config file has changes that only take effect after a restart, they were ignored");
+            }
+        }
+        let mut updated_config = self.current_config.clone();
+        let changes = apply_reloadable_fields(&mut updated_config, &new_config);
+        if !changes.is_empty() {
+            self.apply(&updated_config);
+            self.current_config = updated_config;
+        }
+        self.loaded_config = Some(new_config);
+        Ok(changes)
+    }
+
+    fn apply(&self, config: &NodeConfig) {
+        if let Some(api_config) = &self.components.api_config {
+            *api_config.write().unwrap() = config.api.clone();
+        }
+        if let Some(logger_filter_updater) = &self.components.logger_filter_updater {
+            logger_filter_updater.set_level(config.logger.level);
+        }
+        self.components
+            .mempool_capacity_updater
+            .update(&config.mempool);
+    }
+
+    fn reload_and_log(&mut self) {
+        info!(
+            config_path = self.config_path.display().to_string(),
+            "Reloading node config"
+        );
+        match self.reload() {
+            Ok(changes) => {
+                for change in &changes {
+                    info!(
+                        field = change.field,
+                        old_value = change.old_value,
+                        new_value = change.new_value,
+                        "Applied node config change"
+                    );
+                }
+                info!(
+                    num_changes = changes.len(),
+                    "Finished reloading node config"
+                );
+            }
+            Err(err) => error!(
+                error = format!("{:?}", err),
+                "Failed to reload node config, keeping the current one"
+            ),
+        }
+    }
+
+    #[cfg(unix)]
+    async fn run(mut self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangups = match signal(SignalKind::hangup()) {
+            Ok(hangups) => hangups,
+            Err(err) => {
+                error!(
+                    error = format!("{:?}", err),
+                    "Failed to listen for SIGHUP, node config reloads are disabled"
+                );
+                return;
+            }
+        };
+        while hangups.recv().await.is_some() {
+            self.reload_and_log();
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn run(self) {
+        warn!("Node config reloads are only supported on unix");
+    }
+}
+
+/// Starts a runtime that reloads the node config whenever the process receives SIGHUP
+pub fn start_config_reloader(reloader: ConfigReloader) -> Runtime {
+    let runtime = Builder::new_multi_thread()
+        .thread_name("config-reload")
+        .worker_threads(1)
+        .enable_all()
+        .build()
+        .expect("Failed to create the config reload runtime!");
+    runtime.spawn(reloader.run());
+    runtime
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_logger::Level;
+
+    #[test]
+    fn test_apply_reloadable_fields() {
+        let mut current = NodeConfig::default();
+        let mut new = current.clone();
+        assert!(apply_reloadable_fields(&mut current, &new).is_empty());
+
+        new.logger.level = Level::Debug;
+        new.api.max_transactions_page_size = 25;
+        new.mempool.capacity_per_user = 10;
+        new.api.max_requests_per_second = Some(50);
+        new.api.address = "0.0.0.0:1234".parse().unwrap();
+
+        let changes = apply_reloadable_fields(&mut current, &new);
+        assert_eq!(
+            changes,
+            vec![
+                ConfigChange {
+                    field: "logger.level",
+                    old_value: "Info".to_string(),
+                    new_value: "Debug".to_string(),
+                },
+                ConfigChange {
+                    field: "api.max_transactions_page_size",
+                    old_value: "100".to_string(),
+                    new_value: "25".to_string(),
+                },
+                ConfigChange {
+                    field: "api.max_requests_per_second",
+                    old_value: "None".to_string(),
+                    new_value: "Some(50)".to_string(),
+                },
+                ConfigChange {
+                    field: "mempool.capacity_per_user",
+                    old_value: NodeConfig::default().mempool.capacity_per_user.to_string(),
+                    new_value: "10".to_string(),
+                },
+            ]
+        );
+        assert_eq!(current.logger.level, Level::Debug);
+        assert_eq!(current.api.max_transactions_page_size, 25);
+        assert_eq!(current.mempool.capacity_per_user, 10);
+        assert_eq!(current.api.max_requests_per_second, Some(50));
+
+        // The listen address needs a restart, so it isn't picked up
+        assert_eq!(current.api.address, NodeConfig::default().api.address);
+        assert_ne!(current, new);
+    }
+
+    #[test]
+    fn test_has_restart_only_changes() {
+        let old = NodeConfig::default();
+        let mut new = old.clone();
+        assert!(!has_restart_only_changes(&old, &new));
+
+        new.logger.level = Level::Debug;
+        new.mempool.capacity = 10;
+        assert!(!has_restart_only_changes(&old, &new));
+
+        new.api.address = "0.0.0.0:1234".parse().unwrap();
+        assert!(has_restart_only_changes(&old, &new));
+    }
+
+    #[test]
+    fn test_validate_reloadable_fields() {
+        let config = NodeConfig::default();
+        validate_reloadable_fields(&config).unwrap();
+
+        let mut bad_config = config.clone();
+        bad_config.api.max_events_page_size = 0;
+        assert!(validate_reloadable_fields(&bad_config).is_err());
+
+        let mut bad_config = config.clone();
+        bad_config.mempool.capacity_per_user = config.mempool.capacity + 1;
+        assert!(validate_reloadable_fields(&bad_config).is_err());
+
+        let mut bad_config = config.clone();
+        bad_config.api.max_requests_per_second = Some(0);
+        assert!(validate_reloadable_fields(&bad_config).is_err());
+
+        let mut good_config = config.clone();
+        good_config.api.cors_allowed_origins = vec!["https://example.com".to_string()];
+        validate_reloadable_fields(&good_config).unwrap();
+
+        let mut bad_config = config;
+        bad_config.api.cors_allowed_origins = vec!["https://example.com/path".to_string()];
+        assert!(validate_reloadable_fields(&bad_config).is_err());
+    }
+}
@@ -3,6 +3,7 @@
 
 #![forbid(unsafe_code)]
 
+mod config_reload;
 mod log_build_information;
 
 use anyhow::{anyhow, Context};
@@ -11,9 +12,10 @@ use aptos_backup_service::start_backup_service;
 use aptos_build_info::build_information;
 use aptos_config::{
     config::{
-        AptosDataClientConfig, BaseConfig, NetworkConfig, NodeConfig, PersistableConfig,
-        RocksdbConfigs, StateSyncConfig, StorageServiceConfig, BUFFERED_STATE_TARGET_ITEMS,
-        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+        AptosDataClientConfig, BaseConfig, NetworkConfig, NodeConfig, PeerMonitoringServiceConfig,
+        PersistableConfig, RocksdbConfigs, StateSyncConfig, StorageServiceConfig, ThreadPoolConfig,
+        BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        NO_OP_STORAGE_PRUNER_CONFIG,
     },
     network_id::NetworkId,
     utils::get_genesis_txn,
@@ -30,17 +32,33 @@ use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, Logger
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_time_service::TimeService;
 use aptos_types::{
-    account_config::CORE_CODE_ADDRESS, account_view::AccountView, chain_id::ChainId,
-    on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
+    account_config::CORE_CODE_ADDRESS,
+    account_view::AccountView,
+    chain_id::ChainId,
+    on_chain_config::{GasScheduleV2, ON_CHAIN_CONFIG_REGISTRY},
+    waypoint::Waypoint,
 };
 
 use aptos_db::AptosDB;
-use aptos_event_notifications::EventSubscriptionService;
+use aptos_event_notifications::{EventSubscriptionService, ReconfigNotificationListener};
 use aptos_executor::{chunk_executor::ChunkExecutor, db_bootstrapper::maybe_bootstrap};
 use aptos_framework::ReleaseBundle;
+use aptos_gas::{
+    AptosGasParameters, GasScheduleOverrides, InitialGasSchedule, ToOnChainGasSchedule,
+    LATEST_GAS_FEATURE_VERSION,
+};
+use aptos_genesis::presets::NetworkConfigs;
 use aptos_mempool_notifications::MempoolNotificationSender;
 use aptos_network::application::storage::PeerMetadataStorage;
 use aptos_network_builder::builder::NetworkBuilder;
+use aptos_peer_monitoring_service_client::{
+    PeerMonitoringServiceClient, PeerMonitoringServiceMultiSender,
+    PeerMonitoringServiceNetworkSender,
+};
+use aptos_peer_monitoring_service_server::{
+    network::PeerMonitoringServiceNetworkEvents, PeerMonitoringServiceServer,
+};
+use aptos_peer_monitoring_service_types::NodeInformationResponse;
 use aptos_state_sync_driver::{
     driver_factory::{DriverFactory, StateSyncRuntimes},
     metadata_storage::PersistentMetadataStorage,
@@ -52,24 +70,27 @@ use aptos_storage_service_server::{
 };
 use aptos_vm::AptosVM;
 use clap::Parser;
+use config_reload::{start_config_reloader, ConfigReloader, ReloadableComponents};
 use futures::channel::mpsc;
 use hex::FromHex;
 use log_build_information::log_build_information;
+use move_binary_format::file_format_common::VERSION_MAX;
 use rand::{rngs::StdRng, SeedableRng};
 use std::{
     boxed::Box,
+    cmp::min,
     collections::{HashMap, HashSet},
     fs,
     io::Write,
     path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicBool, Ordering},
         Arc,
     },
     thread,
     time::Instant,
 };
-use tokio::runtime::{Builder, Runtime};
+use tokio::runtime::Runtime;
 
 use aptos_mempool::MempoolClientSender;
 
@@ -114,10 +135,18 @@ pub struct AptosNodeArgs {
     /// only commit a block when there is user transaction in mempool.
     #[clap(long, requires("test"))]
     lazy: bool,
+
+    /// Path to a YAML file overriding gas parameters of the single validator testnet
+    ///
+    /// Maps the names of on-chain gas parameters (e.g. `txn.write_data.per_byte_in_val`) to
+    /// their values. The overrides are applied at genesis, so this only applies when starting
+    /// a new chain. Use the `/v1/dev/gas_schedule` endpoint to change them afterwards.
+    #[clap(long, parse(from_os_str), requires("test"))]
+    gas_overrides: Option<PathBuf>,
 }
 
 impl AptosNodeArgs {
-    pub fn run(self) {
+    pub fn run(self) -> anyhow::Result<()> {
         if self.test {
             println!("Entering test mode, this should never be used in production!");
             let rng = self
@@ -129,11 +158,17 @@ impl AptosNodeArgs {
             } else {
                 aptos_cached_packages::head_release_bundle().clone()
             };
+            let gas_overrides = self
+                .gas_overrides
+                .map(GasScheduleOverrides::load)
+                .transpose()?;
             load_test_environment(
                 self.config,
                 self.test_dir,
                 self.random_ports,
                 self.lazy,
+                None,
+                gas_overrides,
                 &genesis_framework,
                 rng,
             )
@@ -159,8 +194,9 @@ impl AptosNodeArgs {
 
             // Start the node
             println!("Using node config {:?}", &config);
-            start(config, None, true).expect("Node should start correctly");
+            start(config, Some(config_path), None, true).expect("Node should start correctly");
         };
+        Ok(())
     }
 }
 
@@ -172,13 +208,17 @@ pub struct AptosHandle {
     _mempool: Runtime,
     _network_runtimes: Vec<Runtime>,
     _index_runtime: Option<Runtime>,
+    _peer_monitoring_service_runtime: Runtime,
     _state_sync_runtimes: StateSyncRuntimes,
     _telemetry_runtime: Option<Runtime>,
+    reloadable_components: ReloadableComponents,
 }
 
-/// Start an aptos node
+/// Start an aptos node. If `config_path` is given, the node reloads part of its config from
+/// that file when it receives SIGHUP.
 pub fn start(
     config: NodeConfig,
+    config_path: Option<PathBuf>,
     log_file: Option<PathBuf>,
     create_global_rayon_pool: bool,
 ) -> anyhow::Result<()> {
@@ -231,7 +271,15 @@ pub fn start(
         warn!("failpoints is set in config, but the binary doesn't compile with this feature");
     }
 
-    let _node_handle = setup_environment(config, remote_log_rx, Some(logger_filter_update_job))?;
+    let reload_config = config_path.map(|config_path| (config_path, config.clone()));
+    let node_handle = setup_environment(config, remote_log_rx, Some(logger_filter_update_job))?;
+    let _config_reload_runtime = reload_config.map(|(config_path, config)| {
+        start_config_reloader(ConfigReloader::new(
+            config_path,
+            config,
+            node_handle.reloadable_components.clone(),
+        ))
+    });
 
     let term = Arc::new(AtomicBool::new(false));
 
@@ -248,6 +296,8 @@ pub fn load_test_environment<R>(
     test_dir: Option<PathBuf>,
     random_ports: bool,
     lazy: bool,
+    network_configs: Option<NetworkConfigs>,
+    gas_overrides: Option<GasScheduleOverrides>,
     framework: &ReleaseBundle,
     rng: R,
 ) -> anyhow::Result<()>
@@ -272,7 +322,7 @@ where
     let aptos_root_key_path = test_dir.join("mint.key");
 
     // If there's already a config, use it
-    let config = if validator_config_path.exists() {
+    let mut config = if validator_config_path.exists() {
         NodeConfig::load(&validator_config_path)
             .map_err(|err| anyhow!("Unable to load config: {}", err))?
     } else {
@@ -312,12 +362,32 @@ where
             template.consensus.quorum_store_poll_count = u64::MAX;
         }
 
+        // Check that the overrides apply before building genesis, which can't fail gracefully
+        if let Some(gas_overrides) = &gas_overrides {
+            gas_overrides.apply(&mut match &network_configs {
+                Some(network_configs) => network_configs.gas_schedule.clone(),
+                None => GasScheduleV2 {
+                    feature_version: LATEST_GAS_FEATURE_VERSION,
+                    entries: AptosGasParameters::initial()
+                        .to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION),
+                },
+            })?;
+        }
+
         // Build genesis and validator node
         let builder = aptos_genesis::builder::Builder::new(&test_dir, framework.clone())?
             .with_init_config(Some(Arc::new(move |_, config, _| {
                 *config = template.clone();
             })))
-            .with_init_genesis_config(Some(Arc::new(|genesis_config| {
+            .with_init_genesis_config(Some(Arc::new(move |genesis_config| {
+                if let Some(network_configs) = &network_configs {
+                    network_configs.apply(genesis_config);
+                }
+                if let Some(gas_overrides) = &gas_overrides {
+                    gas_overrides
+                        .apply(&mut genesis_config.gas_schedule)
+                        .expect("Gas overrides should apply to the genesis gas schedule");
+                }
                 genesis_config.allow_new_validators = true;
                 genesis_config.epoch_duration_secs = EPOCH_LENGTH_SECS;
                 genesis_config.recurring_lockup_duration_secs = 7200;
@@ -341,6 +411,10 @@ where
         validators[0].config.clone()
     };
 
+    // Serve the dev endpoints, which sign gas schedule overrides with the root key
+    config.api.dev_endpoints_enabled = true;
+    config.api.dev_root_key_path = Some(aptos_root_key_path.to_string_lossy().to_string());
+
     // Prepare log file since we cannot automatically route logs to stderr
     let log_file = test_dir.join("validator.log");
 
@@ -362,10 +436,14 @@ where
     if lazy {
         println!("\tLazy mode is enabled");
     }
+    println!(
+        "\tGas schedule overrides endpoint: http://{}/v1/dev/gas_schedule",
+        &config.api.address
+    );
 
     println!("\nAptos is running, press ctrl-c to exit\n");
 
-    start(config, Some(log_file), false)
+    start(config, None, Some(log_file), false)
 }
 
 // Fetch chain ID from on-chain resource
@@ -399,6 +477,7 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
     // Start the state sync storage service
     let storage_service_runtime = setup_state_sync_storage_service(
         node_config.state_sync.storage_service,
+        &node_config.thread_pools.state_sync,
         storage_service_server_network_handles,
         &db_rw,
     )?;
@@ -408,13 +487,17 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
         node_config.state_sync.storage_service,
         node_config.state_sync.aptos_data_client,
         node_config.base.clone(),
+        &node_config.thread_pools.state_sync,
         storage_service_client_network_handles,
         peer_metadata_storage,
     )?;
 
     // Start the data streaming service
-    let (streaming_service_client, streaming_service_runtime) =
-        setup_data_streaming_service(node_config.state_sync.clone(), aptos_data_client.clone())?;
+    let (streaming_service_client, streaming_service_runtime) = setup_data_streaming_service(
+        node_config.state_sync.clone(),
+        &node_config.thread_pools.state_sync,
+        aptos_data_client.clone(),
+    )?;
 
     // Create the chunk executor and persistent storage
     let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(db_rw.clone()));
@@ -447,6 +530,7 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
 
 fn setup_data_streaming_service(
     state_sync_config: StateSyncConfig,
+    thread_pool_config: &ThreadPoolConfig,
     aptos_data_client: AptosNetDataClient,
 ) -> anyhow::Result<(StreamingServiceClient, Runtime)> {
     // Create the data streaming service
@@ -460,16 +544,9 @@ fn setup_data_streaming_service(
     );
 
     // Start the data streaming service
-    let streaming_service_runtime = Builder::new_multi_thread()
-        .thread_name_fn(|| {
-            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-            format!("stream-serv-{}", id)
-        })
-        .disable_lifo_slot()
-        .enable_all()
-        .build()
-        .map_err(|err| anyhow!("Failed to create data streaming service {}", err))?;
+    let streaming_service_runtime =
+        aptos_runtimes::spawn_named_runtime("stream-serv", thread_pool_config)
+            .map_err(|err| anyhow!("Failed to create data streaming service {}", err))?;
     streaming_service_runtime.spawn(data_streaming_service.start_service());
 
     Ok((streaming_service_client, streaming_service_runtime))
@@ -479,6 +556,7 @@ fn setup_aptos_data_client(
     storage_service_config: StorageServiceConfig,
     aptos_data_client_config: AptosDataClientConfig,
     base_config: BaseConfig,
+    thread_pool_config: &ThreadPoolConfig,
     network_handles: HashMap<NetworkId, aptos_storage_service_client::StorageServiceNetworkSender>,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
 ) -> anyhow::Result<(AptosNetDataClient, Runtime)> {
@@ -489,16 +567,9 @@ fn setup_aptos_data_client(
     );
 
     // Create a new runtime for the data client
-    let aptos_data_client_runtime = Builder::new_multi_thread()
-        .thread_name_fn(|| {
-            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-            format!("data-client-{}", id)
-        })
-        .disable_lifo_slot()
-        .enable_all()
-        .build()
-        .map_err(|err| anyhow!("Failed to create aptos data client {}", err))?;
+    let aptos_data_client_runtime =
+        aptos_runtimes::spawn_named_runtime("data-client", thread_pool_config)
+            .map_err(|err| anyhow!("Failed to create aptos data client {}", err))?;
 
     // Create the data client and spawn the data poller
     let (aptos_data_client, data_summary_poller) = AptosNetDataClient::new(
@@ -516,20 +587,14 @@ fn setup_aptos_data_client(
 
 fn setup_state_sync_storage_service(
     config: StorageServiceConfig,
+    thread_pool_config: &ThreadPoolConfig,
     network_handles: Vec<StorageServiceNetworkEvents>,
     db_rw: &DbReaderWriter,
 ) -> anyhow::Result<Runtime> {
     // Create a new state sync storage service runtime
-    let storage_service_runtime = Builder::new_multi_thread()
-        .thread_name_fn(|| {
-            static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-            let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-            format!("stor-server-{}", id)
-        })
-        .disable_lifo_slot()
-        .enable_all()
-        .build()
-        .map_err(|err| anyhow!("Failed to start state sync storage service {}", err))?;
+    let storage_service_runtime =
+        aptos_runtimes::spawn_named_runtime("stor-server", thread_pool_config)
+            .map_err(|err| anyhow!("Failed to start state sync storage service {}", err))?;
 
     // Spawn all state sync storage service servers on the same runtime
     let storage_reader = StorageReader::new(config, Arc::clone(&db_rw.reader));
@@ -547,6 +612,48 @@ fn setup_state_sync_storage_service(
     Ok(storage_service_runtime)
 }
 
+fn setup_peer_monitoring_service(
+    config: PeerMonitoringServiceConfig,
+    thread_pool_config: &ThreadPoolConfig,
+    network_service_events: Vec<PeerMonitoringServiceNetworkEvents>,
+    network_senders: HashMap<NetworkId, PeerMonitoringServiceNetworkSender>,
+    peer_metadata_storage: Arc<PeerMetadataStorage>,
+    node_information: NodeInformationResponse,
+    reconfig_events: ReconfigNotificationListener,
+) -> anyhow::Result<Runtime> {
+    // Create a new peer monitoring service runtime
+    let peer_monitoring_service_runtime =
+        aptos_runtimes::spawn_named_runtime("peer-mon", thread_pool_config)
+            .map_err(|err| anyhow!("Failed to start peer monitoring service {}", err))?;
+
+    // Spawn all peer monitoring service servers on the same runtime
+    for events in network_service_events {
+        let service = PeerMonitoringServiceServer::new(
+            config.clone(),
+            peer_monitoring_service_runtime.handle().clone(),
+            events,
+            node_information.clone(),
+            peer_metadata_storage.clone(),
+        );
+        peer_monitoring_service_runtime.spawn(service.start());
+    }
+
+    // Spawn the poller that gathers the node information of the peers
+    let peer_monitoring_client = PeerMonitoringServiceClient::new(
+        PeerMonitoringServiceMultiSender::new(network_senders),
+        peer_metadata_storage,
+    );
+    peer_monitoring_service_runtime.spawn(
+        aptos_peer_monitoring_service_client::node_information::start_node_information_poller(
+            config,
+            peer_monitoring_client,
+            reconfig_events,
+        ),
+    );
+
+    Ok(peer_monitoring_service_runtime)
+}
+
 #[cfg(feature = "indexer")]
 fn bootstrap_indexer(
     node_config: &NodeConfig,
@@ -612,6 +719,9 @@ pub fn setup_environment(
     remote_log_rx: Option<mpsc::Receiver<TelemetryLog>>,
     logger_filter_update_job: Option<LoggerFilterUpdater>,
 ) -> anyhow::Result<AptosHandle> {
+    // Spread the thread pools that aren't explicitly pinned across the NUMA nodes
+    aptos_runtimes::apply_numa_defaults(&mut node_config.thread_pools);
+
     // Start the node inspection service
     let node_config_clone = node_config.clone();
     thread::spawn(move || {
@@ -644,6 +754,15 @@ pub fn setup_environment(
         Arc::clone(&aptos_db),
     );
 
+    // The execution pool has to be set before genesis is executed, otherwise the default pool
+    // is used
+    let rayon_exec_pool =
+        aptos_runtimes::spawn_named_rayon_pool("par_exec", &node_config.thread_pools.execution)
+            .map_err(|err| anyhow!("Failed to create the execution thread pool {}", err))?;
+    let num_exec_threads = rayon_exec_pool.current_num_threads();
+    AptosVM::set_rayon_exec_pool_once(rayon_exec_pool)
+        .map_err(|_| anyhow!("The execution thread pool was already initialized"))?;
+
     let genesis_waypoint = node_config.base.waypoint.genesis_waypoint();
     // if there's genesis txn and waypoint, commit it if the result matches.
     if let Some(genesis) = get_genesis_txn(&node_config) {
@@ -653,7 +772,14 @@ pub fn setup_environment(
         info!("Genesis txn not provided, it's fine if you don't expect to apply it otherwise please double check config");
     }
     AptosVM::set_paranoid_type_checks(node_config.execution.paranoid_type_verification);
-    AptosVM::set_concurrency_level_once(node_config.execution.concurrency_level as usize);
+    if let Some(addresses) = &node_config.execution.allowlisted_addresses {
+        AptosVM::set_allowlisted_addresses_once(addresses.clone());
+    }
+    // Executing with more concurrent tasks than the pool has threads doesn't add parallelism.
+    AptosVM::set_concurrency_level_once(min(
+        node_config.execution.concurrency_level as usize,
+        num_exec_threads,
+    ));
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
@@ -674,6 +800,8 @@ pub fn setup_environment(
     let mut consensus_network_handles = None;
     let mut storage_service_server_network_handles = vec![];
     let mut storage_service_client_network_handles = HashMap::new();
+    let mut peer_monitoring_service_network_handles = vec![];
+    let mut peer_monitoring_client_network_handles = HashMap::new();
 
     // Create an event subscription service so that components can be notified of events and reconfigs
     let mut event_subscription_service = EventSubscriptionService::new(
@@ -682,6 +810,8 @@ pub fn setup_environment(
     );
     let mempool_reconfig_subscription =
         event_subscription_service.subscribe_to_reconfigurations()?;
+    let peer_monitoring_reconfig_subscription =
+        event_subscription_service.subscribe_to_reconfigurations()?;
 
     // Create a consensus subscription for reconfiguration events (if this node is a validator).
     let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
@@ -719,8 +849,23 @@ pub fn setup_environment(
     let chain_id = fetch_chain_id(&db_rw)?;
 
     let build_info = build_information!();
+
+    // Gossip the build and supported versions of this node to its peers
+    let node_information = NodeInformationResponse {
+        build_information: build_info.clone(),
+        gas_feature_version: LATEST_GAS_FEATURE_VERSION,
+        binary_format_version: VERSION_MAX,
+    };
+    if let Some(peer_id) = node_config.peer_id() {
+        aptos_peer_monitoring_service_client::node_information::set_local_node_information(
+            peer_id,
+            node_information.clone(),
+        );
+    }
+
     // Start the telemetry service as early as possible and before any blocking calls
     // We have all the necesary info here to start the telemetry service
+    let logger_filter_updater = logger_filter_update_job.clone();
     let telemetry_runtime = aptos_telemetry::service::start_telemetry_service(
         node_config.clone(),
         chain_id,
@@ -732,23 +877,18 @@ pub fn setup_environment(
     for network_config in network_configs.into_iter() {
         let network_id = network_config.network_id;
         debug!("Creating runtime for {}", network_id);
-        let mut runtime_builder = Builder::new_multi_thread();
-        runtime_builder
-            .disable_lifo_slot()
-            .thread_name_fn(move || {
-                static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-                let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-                format!(
-                    "network-{}-{}",
-                    network_id.as_str().chars().take(3).collect::<String>(),
-                    id
-                )
-            })
-            .enable_all();
+        let mut thread_pool_config = node_config.thread_pools.network.clone();
         if let Some(runtime_threads) = network_config.runtime_threads {
-            runtime_builder.worker_threads(runtime_threads);
+            thread_pool_config.num_threads = Some(runtime_threads);
         }
-        let runtime = runtime_builder.build().map_err(|err| {
+        let runtime = aptos_runtimes::spawn_named_runtime(
+            &format!(
+                "network-{}",
+                network_id.as_str().chars().take(3).collect::<String>()
+            ),
+            &thread_pool_config,
+        )
+        .map_err(|err| {
             anyhow!(
                 "Failed to start runtime.  Won't be able to start networking. {}",
                 err
@@ -786,6 +926,22 @@ pub fn setup_environment(
             network_builder.add_client(&aptos_storage_service_client::network_endpoint_config());
         storage_service_client_network_handles.insert(network_id, storage_service_sender);
 
+        // Register the peer monitoring service (both client and server) with Network. The
+        // service reveals the peers of the node, so it's only offered to the validators and VFNs,
+        // and never on the public network.
+        if network_id.is_validator_network() || network_id.is_vfn_network() {
+            let peer_monitoring_service_events = network_builder.add_service(
+                &aptos_peer_monitoring_service_server::network::network_endpoint_config(
+                    node_config.peer_monitoring_service.clone(),
+                ),
+            );
+            peer_monitoring_service_network_handles.push(peer_monitoring_service_events);
+            let peer_monitoring_service_sender = network_builder
+                .add_client(&aptos_peer_monitoring_service_client::network_endpoint_config());
+            peer_monitoring_client_network_handles
+                .insert(network_id, peer_monitoring_service_sender);
+        }
+
         // Create the endpoints to connect the Network to mempool.
         let (mempool_sender, mempool_events) = network_builder.add_p2p_service(
             &aptos_mempool::network::network_endpoint_config(MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE),
@@ -816,6 +972,17 @@ pub fn setup_environment(
     // TODO set up on-chain discovery network based on UpstreamConfig.fallback_network
     // and pass network handles to mempool/state sync
 
+    // Start the peer monitoring service
+    let peer_monitoring_service_runtime = setup_peer_monitoring_service(
+        node_config.peer_monitoring_service.clone(),
+        &node_config.thread_pools.network,
+        peer_monitoring_service_network_handles,
+        peer_monitoring_client_network_handles,
+        peer_metadata_storage.clone(),
+        node_information,
+        peer_monitoring_reconfig_subscription,
+    )?;
+
     // For state sync to send notifications to mempool and receive notifications from consensus.
     let (mempool_notifier, mempool_listener) =
         aptos_mempool_notifications::new_mempool_notifier_listener_pair();
@@ -842,15 +1009,16 @@ pub fn setup_environment(
 
     let (mp_client_sender, mp_client_events) = mpsc::channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
-    let api_runtime = if node_config.api.enabled {
-        Some(bootstrap_api(
+    let (api_runtime, api_config) = if node_config.api.enabled {
+        let (api_runtime, api_config) = bootstrap_api(
             &node_config,
             chain_id,
             aptos_db.clone(),
             mp_client_sender.clone(),
-        )?)
+        )?;
+        (Some(api_runtime), Some(api_config))
     } else {
-        None
+        (None, None)
     };
 
     let index_runtime = bootstrap_indexer(&node_config, chain_id, aptos_db, mp_client_sender)?;
@@ -860,7 +1028,7 @@ pub fn setup_environment(
         mpsc::channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
 
     instant = Instant::now();
-    let mempool = aptos_mempool::bootstrap(
+    let (mempool, mempool_capacity_updater) = aptos_mempool::bootstrap(
         &node_config,
         Arc::clone(&db_rw.reader),
         mempool_network_handles,
@@ -907,8 +1075,14 @@ pub fn setup_environment(
         _mempool: mempool,
         _network_runtimes: network_runtimes,
         _index_runtime: index_runtime,
+        _peer_monitoring_service_runtime: peer_monitoring_service_runtime,
         _state_sync_runtimes: state_sync_runtimes,
         _telemetry_runtime: telemetry_runtime,
+        reloadable_components: ReloadableComponents {
+            api_config,
+            logger_filter_updater,
+            mempool_capacity_updater,
+        },
     })
 }
 
@@ -10,7 +10,7 @@ use clap::Parser;
 #[global_allocator]
 static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     // Check that we are not including any Move test natives
     aptos_vm::natives::assert_no_test_natives(ERROR_MSG_BAD_FEATURE_FLAGS);
     AptosNodeArgs::parse().run()
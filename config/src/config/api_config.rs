@@ -32,6 +32,8 @@ pub struct ApiConfig {
     pub transaction_simulation_enabled: bool,
 
     pub max_submit_transaction_batch_size: usize,
+    /// Maximum number of reads in a batch read request
+    pub max_batch_read_size: usize,
 
     /// Maximum page size for paginated APIs
     pub max_transactions_page_size: u16,
@@ -41,12 +43,47 @@ pub struct ApiConfig {
 
     /// Max gas unit for view function.
     pub max_gas_view_function: u64,
+
+    /// Origins allowed to make cross origin requests, e.g. `https://example.com`. Every origin
+    /// is allowed if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cors_allowed_origins: Vec<String>,
+    /// Maximum number of requests per second the API serves, across all clients. Unlimited if
+    /// not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_requests_per_second: Option<u32>,
+
+    /// Attach a content digest to responses that can never change (e.g. transactions by
+    /// version, or state at an explicit ledger version), and mark them as cacheable.
+    #[serde(default = "default_disabled")]
+    pub content_digest_enabled: bool,
+    /// Also sign the content digest of responses with the network identity key of the node,
+    /// so clients can check that a cached response came from a node they trust.
+    #[serde(default = "default_disabled")]
+    pub response_signing_enabled: bool,
+    /// Return a receipt signed with the network identity key of the node when a transaction
+    /// submitted as JSON is accepted into mempool, so clients can prove that they submitted it.
+    #[serde(default = "default_disabled")]
+    pub submission_receipts_enabled: bool,
+    /// Allow clients to select the fields returned by the transaction and account endpoints
+    /// with the `fields` query parameter.
+    #[serde(default = "default_enabled")]
+    pub field_projection_enabled: bool,
+    /// Serve the dev-only endpoints, e.g. `/v1/dev/gas_schedule`, to clients on the same host.
+    /// They are unauthenticated, so never enable them outside of local networks.
+    #[serde(default = "default_disabled")]
+    pub dev_endpoints_enabled: bool,
+    /// Path to the BCS encoded root key of a local network, which the dev endpoints sign their
+    /// transactions with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev_root_key_path: Option<String>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+pub const DEFAULT_MAX_BATCH_READ_SIZE: usize = 50;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 pub const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 pub const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
@@ -77,11 +114,20 @@ impl Default for ApiConfig {
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            max_batch_read_size: DEFAULT_MAX_BATCH_READ_SIZE,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            cors_allowed_origins: vec![],
+            max_requests_per_second: None,
+            content_digest_enabled: default_disabled(),
+            response_signing_enabled: default_disabled(),
+            submission_receipts_enabled: default_disabled(),
+            field_projection_enabled: default_enabled(),
+            dev_endpoints_enabled: default_disabled(),
+            dev_root_key_path: None,
         }
     }
 }
@@ -13,6 +13,11 @@ pub struct ConsensusConfig {
     pub max_receiving_block_txns: u64,
     pub max_receiving_block_bytes: u64,
     pub max_pruned_blocks_in_mem: usize,
+    // Number of the newest uncommitted blocks replayed from the ConsensusDB on startup, along
+    // with their ancestors. Blocks on other forks are pruned.
+    pub max_recovery_blocks: usize,
+    // Number of blocks pruned from the ConsensusDB between compactions
+    pub db_compaction_interval_blocks: usize,
     // Timeout for consensus to get an ack from mempool for executed transactions (in milliseconds)
     pub mempool_executed_txn_timeout_ms: u64,
     // Timeout for consensus to pull transactions from mempool and get a response (in milliseconds)
@@ -35,6 +40,10 @@ pub struct ConsensusConfig {
     // must match one of the CHAIN_HEALTH_WINDOW_SIZES values.
     pub window_for_chain_health: usize,
     pub chain_health_backoff: Vec<ChainHealthBackoffValues>,
+    // Number of recent rounds kept as postmortem records for the inspection service
+    pub max_round_postmortems: usize,
+    // Reduces the quorum store batch quota of validators that send low-quality batches
+    pub quorum_store_batch_reputation: QuorumStoreBatchReputationConfig,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
@@ -45,6 +54,50 @@ pub struct ChainHealthBackoffValues {
     pub max_sending_block_bytes_override: u64,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuorumStoreBatchReputationConfig {
+    // Number of recent batches of each author used to compute its reputation
+    pub window_num_batches: usize,
+    // Authors with fewer batches in the window keep the full quota
+    pub min_num_batches: usize,
+    // Maximum number of transactions of an author held by the quorum store at once
+    pub batch_quota_txns: u64,
+    pub quota_backoff: Vec<BatchQuotaBackoffValues>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct BatchQuotaBackoffValues {
+    // Percentage of expired, duplicate or invalid transactions in the recent batches of an author
+    pub backoff_if_above_bad_txn_percentage: usize,
+
+    pub quota_percentage: usize,
+}
+
+impl Default for QuorumStoreBatchReputationConfig {
+    fn default() -> QuorumStoreBatchReputationConfig {
+        QuorumStoreBatchReputationConfig {
+            window_num_batches: 100,
+            min_num_batches: 10,
+            batch_quota_txns: 10000,
+            quota_backoff: vec![
+                BatchQuotaBackoffValues {
+                    backoff_if_above_bad_txn_percentage: 10,
+                    quota_percentage: 50,
+                },
+                BatchQuotaBackoffValues {
+                    backoff_if_above_bad_txn_percentage: 25,
+                    quota_percentage: 20,
+                },
+                BatchQuotaBackoffValues {
+                    backoff_if_above_bad_txn_percentage: 50,
+                    quota_percentage: 5,
+                },
+            ],
+        }
+    }
+}
+
 impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
@@ -55,6 +108,8 @@ impl Default for ConsensusConfig {
             max_receiving_block_txns: 10000,
             max_receiving_block_bytes: 3 * 1024 * 1024, // 3MB
             max_pruned_blocks_in_mem: 100,
+            max_recovery_blocks: 1000,
+            db_compaction_interval_blocks: 10000,
             mempool_executed_txn_timeout_ms: 1000,
             mempool_txn_pull_timeout_ms: 1000,
             round_initial_timeout_ms: 1500,
@@ -100,6 +155,8 @@ impl Default for ConsensusConfig {
                     max_sending_block_bytes_override: 25 * 1024,
                 },
             ],
+            max_round_postmortems: 1000,
+            quorum_store_batch_reputation: QuorumStoreBatchReputationConfig::default(),
         }
     }
 }
@@ -15,6 +15,8 @@ pub enum Error {
     Yaml(String, #[source] serde_yaml::Error),
     #[error("Config is missing expected value: {0}")]
     Missing(&'static str),
+    #[error("Error resolving secret {0}: {1}")]
+    Secret(String, String),
     #[error("Unexpected error: {0}")]
     Unexpected(String),
 }
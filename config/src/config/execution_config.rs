@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::{Error, RootPath};
-use aptos_types::transaction::Transaction;
+use aptos_types::{account_address::AccountAddress, transaction::Transaction};
 use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
@@ -23,6 +23,10 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     pub paranoid_hot_potato_verification: bool,
     pub processed_transactions_detailed_counters: bool,
+    /// For permissioned networks: if set, only entry functions in modules published at these
+    /// addresses (or the framework) may be called, and only these addresses may publish
+    /// modules or run scripts. Enforced when validating transactions.
+    pub allowlisted_addresses: Option<Vec<AccountAddress>>,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -52,6 +56,7 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             processed_transactions_detailed_counters: false,
+            allowlisted_addresses: None,
         }
     }
 }
@@ -7,6 +7,8 @@ pub const DEFAULT_BATCH_SIZE: u16 = 500;
 pub const DEFAULT_FETCH_TASKS: u8 = 5;
 pub const DEFAULT_PROCESSOR_TASKS: u8 = 5;
 pub const DEFAULT_EMIT_EVERY: u64 = 1000;
+pub const DEFAULT_LAG_CHECK_INTERVAL_SECS: u64 = 10;
+pub const DEFAULT_GRAPHQL_MAX_PAGE_SIZE: u64 = 100;
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -69,6 +71,29 @@ pub struct IndexerConfig {
     /// Which address does the ans contract live at. Only available for token_processor. If null, disable ANS indexing
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ans_contract_address: Option<String>,
+
+    /// How often to compare the processed version against the head of the chain, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag_check_interval_secs: Option<u64>,
+
+    /// How many versions the processor may fall behind the head of the chain before an alert is
+    /// raised. If null, lag is only reported through metrics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag_alert_threshold_versions: Option<u64>,
+
+    /// If set, lag alerts are posted as JSON to this url when they start firing and when they resolve
+    /// Alternatively can set the `LAG_ALERT_WEBHOOK_URL` env var
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag_alert_webhook_url: Option<String>,
+
+    /// If set, the processed tables are served over GraphQL at this address, ex: "0.0.0.0:8090"
+    /// Alternatively can set the `INDEXER_GRAPHQL_ADDRESS` env var
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql_address: Option<String>,
+
+    /// The maximum number of rows a GraphQL query can return at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql_max_page_size: Option<u64>,
 }
 
 pub fn env_or_default<T: std::str::FromStr>(
@@ -11,6 +11,10 @@ pub struct InspectionServiceConfig {
     pub port: u16,
     pub expose_configuration: bool,
     pub expose_system_information: bool,
+    pub expose_consensus_round_postmortems: bool,
+    pub expose_thread_dump: bool,
+    pub expose_task_dump: bool,
+    pub expose_upgrade_readiness: bool,
 }
 
 impl Default for InspectionServiceConfig {
@@ -20,6 +24,10 @@ impl Default for InspectionServiceConfig {
             port: 9101,
             expose_configuration: false,
             expose_system_information: true,
+            expose_consensus_round_postmortems: true,
+            expose_thread_dump: false,
+            expose_task_dump: false,
+            expose_upgrade_readiness: true,
         }
     }
 }
@@ -26,6 +26,16 @@ pub struct MempoolConfig {
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub broadcast_buckets: Vec<u64>,
+    // max number of sequence numbers a transaction can be ahead of its account's sequence number
+    pub max_sequence_number_horizon: u64,
+    // optional URL that is POSTed to when a parked transaction becomes ready or is evicted
+    pub parked_transaction_webhook_url: Option<String>,
+    // how long evicted transactions are remembered, so that clients looking them up learn why
+    // they were evicted. 0 disables it.
+    pub evicted_transaction_retention_secs: u64,
+    // the maximum memory used by the remembered evicted transactions, the oldest ones are
+    // forgotten first
+    pub evicted_transactions_capacity_bytes: usize,
 }
 
 impl Default for MempoolConfig {
@@ -46,6 +56,10 @@ impl Default for MempoolConfig {
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             broadcast_buckets: DEFAULT_BROADCAST_BUCKETS.to_vec(),
+            max_sequence_number_horizon: 100,
+            parked_transaction_webhook_url: None,
+            evicted_transaction_retention_secs: 300,
+            evicted_transactions_capacity_bytes: 64 * 1024 * 1024,
         }
     }
 }
@@ -44,6 +44,8 @@ mod safety_rules_config;
 pub use safety_rules_config::*;
 mod test_config;
 pub use test_config::*;
+mod thread_pool_config;
+pub use thread_pool_config::*;
 mod api_config;
 pub use api_config::*;
 use aptos_crypto::{bls12381, ed25519::Ed25519PrivateKey, x25519};
@@ -92,6 +94,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub test: Option<TestConfig>,
     #[serde(default)]
+    pub thread_pools: ThreadPoolsConfig,
+    #[serde(default)]
     pub validator_network: Option<NetworkConfig>,
     #[serde(default)]
     pub failpoints: Option<HashMap<String, String>>,
@@ -293,6 +297,7 @@ impl NodeConfig {
         let mut config = config
             .validate_indexer_configs()?
             .validate_network_configs()?;
+        config.thread_pools.validate()?;
         config.set_data_dir(config.data_dir().to_path_buf());
         Ok(config)
     }
@@ -379,6 +384,20 @@ impl NodeConfig {
             self.indexer.gap_lookback_versions.or(Some(1_500_000)),
             None,
         );
+        self.indexer.lag_check_interval_secs = default_if_zero(
+            self.indexer.lag_check_interval_secs,
+            DEFAULT_LAG_CHECK_INTERVAL_SECS,
+        );
+        self.indexer.lag_alert_webhook_url = std::env::var("LAG_ALERT_WEBHOOK_URL")
+            .ok()
+            .or(self.indexer.lag_alert_webhook_url);
+        self.indexer.graphql_address = std::env::var("INDEXER_GRAPHQL_ADDRESS")
+            .ok()
+            .or(self.indexer.graphql_address);
+        self.indexer.graphql_max_page_size = default_if_zero(
+            self.indexer.graphql_max_page_size,
+            DEFAULT_GRAPHQL_MAX_PAGE_SIZE,
+        );
 
         Ok(self)
     }
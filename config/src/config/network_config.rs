@@ -333,6 +333,8 @@ impl NetworkConfig {
 pub struct PeerMonitoringServiceConfig {
     pub max_concurrent_requests: u64, // Max num of concurrent server tasks
     pub max_network_channel_size: u64, // Max num of pending network messages
+    pub node_information_poll_interval_ms: u64, // Interval to fetch the node information of peers
+    pub node_information_request_timeout_ms: u64, // Timeout for node information requests
 }
 
 impl Default for PeerMonitoringServiceConfig {
@@ -340,6 +342,8 @@ impl Default for PeerMonitoringServiceConfig {
         Self {
             max_concurrent_requests: 1000,
             max_network_channel_size: 1000,
+            node_information_poll_interval_ms: 60_000, // 1 minute
+            node_information_request_timeout_ms: 10_000, // 10 seconds
         }
     }
 }
@@ -25,6 +25,11 @@ pub struct SafetyRulesConfig {
     pub network_timeout_ms: u64,
     pub enable_cached_safety_data: bool,
     pub initial_safety_rules_config: InitialSafetyRulesConfig,
+    // Storages, independent of the safety rules backend, that witness the highest voted round.
+    // Before voting after a restart, safety rules checks with a majority of them that it didn't
+    // already vote in the round (e.g., before being restored from an old backup). Empty disables
+    // the witness.
+    pub voting_witness_backends: Vec<SecureBackend>,
 }
 
 impl Default for SafetyRulesConfig {
@@ -38,6 +43,7 @@ impl Default for SafetyRulesConfig {
             network_timeout_ms: 30_000,
             enable_cached_safety_data: true,
             initial_safety_rules_config: InitialSafetyRulesConfig::None,
+            voting_witness_backends: vec![],
         }
     }
 }
@@ -25,6 +25,9 @@ pub struct RocksdbConfig {
     pub block_cache_size: u64,
     pub block_size: u64,
     pub cache_index_and_filter_blocks: bool,
+    pub bloom_filter_bits_per_key: u32,
+    pub row_cache_size: u64,
+    pub enable_statistics: bool,
 }
 
 impl Default for RocksdbConfig {
@@ -44,6 +47,16 @@ impl Default for RocksdbConfig {
             block_size: 4 * (1u64 << 10),
             // Whether cache index and filter blocks into block cache.
             cache_index_and_filter_blocks: false,
+            // Bits per key of the bloom filters of the column families serving state lookups, i.e.
+            // state values and Jellyfish Merkle nodes. 0 disables the bloom filters.
+            bloom_filter_bits_per_key: 10,
+            // Size of the row cache, which caches the results of point lookups (but not of
+            // iterator seeks). 0 disables the row cache.
+            row_cache_size: 0,
+            // Whether to collect rocksdb statistics, e.g. block cache, row cache and bloom filter
+            // hits and misses, which are reported as metrics. Collecting them slows down every
+            // read and write, so they are off by default.
+            enable_statistics: false,
         }
     }
 }
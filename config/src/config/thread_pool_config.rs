@@ -0,0 +1,164 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{invariant, Error};
+use serde::{Deserialize, Serialize};
+
+/// Threads can only be pinned to the cores that fit in a cpu set, i.e., below CPU_SETSIZE
+pub const MAX_CORE_ID: usize = 1023;
+
+/// Sizing and CPU pinning of the thread pools used by each subsystem of the node.
+/// On machines with several NUMA nodes, the pools that aren't explicitly pinned are spread
+/// across the nodes by default (see `apply_numa_defaults`). Otherwise, every pool uses one
+/// thread per core and isn't pinned, and operators can isolate subsystems from each other by
+/// giving them separate cores.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThreadPoolsConfig {
+    /// The tokio runtime serving the REST API
+    pub api: ThreadPoolConfig,
+    /// The rayon pool used for parallel transaction execution
+    pub execution: ThreadPoolConfig,
+    /// The tokio runtimes of the state sync driver, data client, streaming and storage services
+    pub state_sync: ThreadPoolConfig,
+    /// The tokio runtimes of the networks. A network's `runtime_threads` takes precedence.
+    pub network: ThreadPoolConfig,
+    /// Spreads the pools that aren't explicitly pinned across the NUMA nodes of the machine
+    pub numa_aware_defaults: bool,
+}
+
+impl Default for ThreadPoolsConfig {
+    fn default() -> Self {
+        Self {
+            api: ThreadPoolConfig::default(),
+            execution: ThreadPoolConfig::default(),
+            state_sync: ThreadPoolConfig::default(),
+            network: ThreadPoolConfig::default(),
+            numa_aware_defaults: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThreadPoolConfig {
+    /// The number of threads in the pool. Defaults to the number of cores the pool is pinned
+    /// to, or to the number of cores of the machine if it isn't pinned.
+    pub num_threads: Option<usize>,
+    /// Pins the threads of the pool to the cores of this NUMA node.
+    pub numa_node: Option<usize>,
+    /// Pins the threads of the pool to these cores. Takes precedence over `numa_node`.
+    pub core_ids: Vec<usize>,
+}
+
+impl ThreadPoolsConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        self.api.validate("api")?;
+        self.execution.validate("execution")?;
+        self.state_sync.validate("state_sync")?;
+        self.network.validate("network")
+    }
+
+    /// Pins every pool that isn't explicitly pinned to one of the given NUMA nodes, so that
+    /// subsystems don't compete for the same cores. Execution gets the first node to itself,
+    /// and the other pools share the remaining ones. Does nothing on a single node machine.
+    pub fn apply_numa_defaults(&mut self, numa_nodes: &[usize]) {
+        if !self.numa_aware_defaults || numa_nodes.len() < 2 {
+            return;
+        }
+        self.execution.set_default_numa_node(numa_nodes[0]);
+        let other_nodes = &numa_nodes[1..];
+        for (i, pool) in [&mut self.api, &mut self.state_sync, &mut self.network]
+            .into_iter()
+            .enumerate()
+        {
+            pool.set_default_numa_node(other_nodes[i % other_nodes.len()]);
+        }
+    }
+}
+
+impl ThreadPoolConfig {
+    fn set_default_numa_node(&mut self, numa_node: usize) {
+        if self.core_ids.is_empty() && self.numa_node.is_none() {
+            self.numa_node = Some(numa_node);
+        }
+    }
+
+    fn validate(&self, name: &str) -> Result<(), Error> {
+        invariant(
+            self.num_threads != Some(0),
+            format!("The {} thread pool must have at least one thread", name),
+        )?;
+        invariant(
+            self.core_ids.is_empty() || self.numa_node.is_none(),
+            format!(
+                "The {} thread pool can't be pinned to both a NUMA node and a set of cores",
+                name
+            ),
+        )?;
+        invariant(
+            self.core_ids.iter().all(|core_id| *core_id <= MAX_CORE_ID),
+            format!(
+                "The {} thread pool can only be pinned to cores up to {}",
+                name, MAX_CORE_ID
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_thread_pools() {
+        assert!(ThreadPoolsConfig::default().validate().is_ok());
+
+        let mut config = ThreadPoolsConfig::default();
+        config.api.num_threads = Some(0);
+        assert!(config.validate().is_err());
+
+        let mut config = ThreadPoolsConfig::default();
+        config.execution.numa_node = Some(1);
+        assert!(config.validate().is_ok());
+        config.execution.core_ids = vec![0, 1];
+        assert!(config.validate().is_err());
+
+        let mut config = ThreadPoolsConfig::default();
+        config.network.core_ids = vec![0, MAX_CORE_ID];
+        assert!(config.validate().is_ok());
+        config.network.core_ids = vec![0, MAX_CORE_ID + 1];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_apply_numa_defaults() {
+        let mut config = ThreadPoolsConfig::default();
+        config.apply_numa_defaults(&[0]);
+        assert_eq!(config, ThreadPoolsConfig::default());
+
+        let mut config = ThreadPoolsConfig::default();
+        config.apply_numa_defaults(&[0, 2]);
+        assert_eq!(config.execution.numa_node, Some(0));
+        assert_eq!(config.api.numa_node, Some(2));
+        assert_eq!(config.state_sync.numa_node, Some(2));
+        assert_eq!(config.network.numa_node, Some(2));
+
+        let mut config = ThreadPoolsConfig::default();
+        config.execution.core_ids = vec![0, 1];
+        config.network.numa_node = Some(0);
+        config.apply_numa_defaults(&[0, 1, 2, 3]);
+        assert_eq!(config.execution.numa_node, None);
+        assert_eq!(config.api.numa_node, Some(1));
+        assert_eq!(config.state_sync.numa_node, Some(2));
+        assert_eq!(config.network.numa_node, Some(0));
+        assert!(config.validate().is_ok());
+
+        let mut config = ThreadPoolsConfig {
+            numa_aware_defaults: false,
+            ..Default::default()
+        };
+        config.apply_numa_defaults(&[0, 1]);
+        assert_eq!(config.execution.numa_node, None);
+    }
+}
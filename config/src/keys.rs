@@ -11,26 +11,35 @@
 //! The public key part is dynamically derived during deserialization,
 //! while ignored during serialization.
 //!
+//! In human readable formats, the private key can also be a reference to a
+//! secret stored outside of the config, see `crate::secrets`.
+//!
 
+use crate::secrets::SecretRef;
 use aptos_crypto::{
     CryptoMaterialError, PrivateKey, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde::{
+    de::{self, DeserializeOwned},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 /// ConfigKey places a clonable wrapper around PrivateKeys for config purposes only. The only time
 /// configs have keys is either for testing or for low security requirements. We recommend that
 /// keys be stored in key managers. If we make keys unclonable, then the configs must be mutable
 /// and that becomes a requirement strictly as a result of supporting test environments, which is
 /// undesirable. Hence this internal wrapper allows for keys to be clonable but only from configs.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug)]
 pub struct ConfigKey<T: PrivateKey + Serialize> {
-    #[serde(bound(deserialize = "T: Deserialize<'de>"))]
     key: T,
+    /// Where the key was read from, if it wasn't inlined in the config
+    secret: Option<SecretRef>,
 }
 
 impl<T: DeserializeOwned + PrivateKey + ValidCryptoMaterial + Serialize> ConfigKey<T> {
     pub fn new(key: T) -> Self {
-        Self { key }
+        Self { key, secret: None }
     }
 
     pub fn private_key(&self) -> T {
@@ -48,7 +57,55 @@ impl<T: DeserializeOwned + PrivateKey + ValidCryptoMaterial + Serialize> ConfigK
 
 impl<T: DeserializeOwned + PrivateKey + Serialize> Clone for ConfigKey<T> {
     fn clone(&self) -> Self {
-        bcs::from_bytes(&bcs::to_bytes(self).unwrap()).unwrap()
+        let mut key: Self = bcs::from_bytes(&bcs::to_bytes(self).unwrap()).unwrap();
+        key.secret = self.secret.clone();
+        key
+    }
+}
+
+impl<T: PrivateKey + Serialize> Serialize for ConfigKey<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let secret = self
+            .secret
+            .as_ref()
+            .filter(|_| serializer.is_human_readable());
+        let mut state = serializer.serialize_struct("ConfigKey", 1)?;
+        match secret {
+            // Never write out a key that was read from a secret
+            Some(secret) => state.serialize_field("key", &secret.to_string())?,
+            None => state.serialize_field("key", &self.key)?,
+        }
+        state.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for ConfigKey<T>
+where
+    T: PrivateKey + ValidCryptoMaterial + Serialize + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "ConfigKey")]
+        struct Value<K> {
+            key: K,
+        }
+
+        if !deserializer.is_human_readable() {
+            let value = Value::<T>::deserialize(deserializer)?;
+            return Ok(Self {
+                key: value.key,
+                secret: None,
+            });
+        }
+
+        let value = Value::<String>::deserialize(deserializer)?;
+        let secret = SecretRef::parse(&value.key);
+        let encoded_key = match &secret {
+            Some(secret) => secret.resolve().map_err(de::Error::custom)?,
+            None => value.key,
+        };
+        let key = T::from_encoded_string(&encoded_key).map_err(de::Error::custom)?;
+        Ok(Self { key, secret })
     }
 }
 
@@ -57,6 +114,7 @@ impl<T: PrivateKey + Serialize + aptos_crypto::Uniform> Default for ConfigKey<T>
     fn default() -> Self {
         Self {
             key: aptos_crypto::Uniform::generate_for_testing(),
+            secret: None,
         }
     }
 }
@@ -66,3 +124,5 @@ impl<T: PrivateKey + Serialize> PartialEq for ConfigKey<T> {
         bcs::to_bytes(&self).unwrap() == bcs::to_bytes(&other).unwrap()
     }
 }
+
+impl<T: PrivateKey + Serialize> Eq for ConfigKey<T> {}
@@ -0,0 +1,148 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file implements references to secrets that live outside of the config.
+//!
+//! Instead of inlining a private key, a config can refer to it with one of:
+//! * `env:<VARIABLE>`: the value of an environment variable,
+//! * `file:<PATH>`: the contents of a file,
+//! * `vault:<SECRET>`: a secret in Vault, read from the server in `VAULT_ADDR` with the token in
+//!   `VAULT_TOKEN` and, if set, the CA certificate at the path in `VAULT_CACERT`.
+//!
+//! References are resolved when the config is loaded, and serialized back as references so that
+//! saving a config never writes out the secrets.
+
+use crate::config::Error;
+use aptos_secure_storage::{KVStorage, VaultStorage};
+use std::{fmt, fs, path::PathBuf};
+
+const ENV_PREFIX: &str = "env:";
+const FILE_PREFIX: &str = "file:";
+const VAULT_PREFIX: &str = "vault:";
+
+const VAULT_ADDR: &str = "VAULT_ADDR";
+const VAULT_TOKEN: &str = "VAULT_TOKEN";
+const VAULT_CACERT: &str = "VAULT_CACERT";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SecretRef {
+    Env(String),
+    File(PathBuf),
+    Vault(String),
+}
+
+impl SecretRef {
+    /// Parses a reference to a secret, returns None if the value isn't one.
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(name) = value.strip_prefix(ENV_PREFIX) {
+            Some(SecretRef::Env(name.to_string()))
+        } else if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+            Some(SecretRef::File(PathBuf::from(path)))
+        } else {
+            value
+                .strip_prefix(VAULT_PREFIX)
+                .map(|secret| SecretRef::Vault(secret.to_string()))
+        }
+    }
+
+    /// Reads the secret, with surrounding whitespace (e.g. a trailing newline in a file) removed.
+    pub fn resolve(&self) -> Result<String, Error> {
+        let secret =
+            match self {
+                SecretRef::Env(name) => std::env::var(name)
+                    .map_err(|e| Error::Secret(self.to_string(), e.to_string()))?,
+                SecretRef::File(path) => fs::read_to_string(path)
+                    .map_err(|e| Error::IO(path.display().to_string(), e))?,
+                SecretRef::Vault(secret) => Self::read_from_vault(secret)
+                    .map_err(|e| Error::Secret(self.to_string(), e.to_string()))?,
+            };
+        Ok(secret.trim().to_string())
+    }
+
+    fn read_from_vault(secret: &str) -> anyhow::Result<String> {
+        let host = std::env::var(VAULT_ADDR)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", VAULT_ADDR, e))?;
+        let token = std::env::var(VAULT_TOKEN)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", VAULT_TOKEN, e))?;
+        let certificate = match std::env::var_os(VAULT_CACERT) {
+            Some(path) => Some(fs::read_to_string(path)?),
+            None => None,
+        };
+        let storage = VaultStorage::new(host, token, certificate, None, true, None, None);
+        Ok(storage.get::<String>(secret)?.value)
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecretRef::Env(name) => write!(f, "{}{}", ENV_PREFIX, name),
+            SecretRef::File(path) => write!(f, "{}{}", FILE_PREFIX, path.display()),
+            SecretRef::Vault(secret) => write!(f, "{}{}", VAULT_PREFIX, secret),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{config::IdentityFromConfig, keys::ConfigKey};
+    use aptos_crypto::{x25519, Uniform, ValidCryptoMaterialStringExt};
+    use aptos_temppath::TempPath;
+    use aptos_types::PeerId;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn parse_secret_refs() {
+        assert_eq!(
+            SecretRef::parse("env:NODE_KEY"),
+            Some(SecretRef::Env("NODE_KEY".to_string()))
+        );
+        assert_eq!(
+            SecretRef::parse("file:/opt/aptos/key"),
+            Some(SecretRef::File(PathBuf::from("/opt/aptos/key")))
+        );
+        assert_eq!(
+            SecretRef::parse("vault:network_key"),
+            Some(SecretRef::Vault("network_key".to_string()))
+        );
+        assert_eq!(SecretRef::parse("0x1234"), None);
+    }
+
+    #[test]
+    fn resolve_key_from_file() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let key = x25519::PrivateKey::generate(&mut rng);
+        let path = TempPath::new();
+        path.create_as_file().unwrap();
+        fs::write(
+            path.path(),
+            format!("{}\n", key.to_encoded_string().unwrap()),
+        )
+        .unwrap();
+
+        let yaml = format!(
+            "key: \"file:{}\"\npeer_id: \"{}\"\n",
+            path.path().display(),
+            PeerId::ZERO
+        );
+        let identity: IdentityFromConfig = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(identity.key, ConfigKey::new(key));
+
+        // The secret isn't written back out, nor lost when cloning
+        let serialized = serde_yaml::to_string(&identity.clone()).unwrap();
+        assert!(serialized.contains(&format!("file:{}", path.path().display())));
+    }
+
+    #[test]
+    fn resolve_missing_env_var() {
+        let yaml = format!(
+            "key: \"env:APTOS_CONFIG_TEST_MISSING_KEY\"\npeer_id: \"{}\"\n",
+            PeerId::ZERO
+        );
+        let err = serde_yaml::from_str::<IdentityFromConfig>(&yaml).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("env:APTOS_CONFIG_TEST_MISSING_KEY"));
+    }
+}
@@ -10,8 +10,10 @@ use aptos_types::{
 };
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use get_if_addrs::get_if_addrs;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
 use std::fs::{File, OpenOptions};
 use std::io::Seek;
 use std::net::{TcpListener, TcpStream};
@@ -41,6 +43,11 @@ static PORT_VECTOR: Lazy<Vec<u16>> = Lazy::new(|| {
     ports
 });
 
+thread_local! {
+    /// Draws the ports returned by `get_available_port` while a seed is set by `with_port_seed`
+    static SEEDED_PORTS: RefCell<Option<StdRng>> = RefCell::new(None);
+}
+
 struct PortCounterFiles {
     counter_file: File,
     _lock_file: File,
@@ -62,13 +69,37 @@ impl Drop for PortCounterFiles {
 }
 
 pub fn get_available_port() -> u16 {
-    if NEXTEST_RUN_ID.is_some() {
+    if let Some(port) = SEEDED_PORTS.with(|rng| rng.borrow_mut().as_mut().map(get_seeded_port)) {
+        port
+    } else if NEXTEST_RUN_ID.is_some() {
         get_unique_port()
     } else {
         get_random_port()
     }
 }
 
+/// Runs `f` with the ports returned by `get_available_port` drawn from `seed`, so that the nodes
+/// configured by `f` listen on the same ports in every run, as long as these are available.
+/// Concurrent runs with the same seed would try the same ports, so this is meant to reproduce a
+/// run rather than to be used by default.
+pub fn with_port_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let previous = SEEDED_PORTS.with(|rng| rng.replace(Some(StdRng::seed_from_u64(seed))));
+    let result = f();
+    SEEDED_PORTS.with(|rng| rng.replace(previous));
+    result
+}
+
+fn get_seeded_port(rng: &mut StdRng) -> u16 {
+    for _ in 0..MAX_PORT_RETRIES {
+        let port = rng.gen_range(UNIQUE_PORT_RANGE.start, UNIQUE_PORT_RANGE.end);
+        if let Ok(port) = try_bind(Some(port)) {
+            return port;
+        }
+    }
+
+    panic!("Error: could not find an available port from the seed");
+}
+
 /// Return an ephemeral, available port. On unix systems, the port returned will be in the
 /// TIME_WAIT state ensuring that the OS won't hand out this port for some grace period.
 /// Callers should be able to bind to this port given they use SO_REUSEADDR.
@@ -227,3 +258,18 @@ pub fn get_available_port_in_multiaddr(is_ipv4: bool) -> NetworkAddress {
 pub fn get_genesis_txn(config: &NodeConfig) -> Option<&Transaction> {
     config.execution.genesis.as_ref()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seeded_ports() {
+        let ports = || with_port_seed(7, || (get_available_port(), get_available_port()));
+        let (first, second) = ports();
+        assert!(UNIQUE_PORT_RANGE.contains(&first));
+        assert_ne!(first, second);
+        assert_eq!(ports(), (first, second));
+        assert!(SEEDED_PORTS.with(|rng| rng.borrow().is_none()));
+    }
+}
@@ -55,6 +55,12 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error(
+        "The voting witness has a vote for epoch {0} round {1}, not voting for epoch {2} round {3}"
+    )]
+    VotingWitnessAhead(u64, u64, u64, u64),
+    #[error("Voting witness unavailable: {0} of {1} witness storages responded")]
+    VotingWitnessUnavailable(usize, usize),
 }
 
 impl From<serde_json::Error> for Error {
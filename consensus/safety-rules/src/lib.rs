@@ -17,12 +17,13 @@ mod safety_rules_manager;
 mod serializer;
 mod t_safety_rules;
 mod thread;
+mod voting_witness;
 
 pub use crate::{
     consensus_state::ConsensusState, error::Error,
     persistent_safety_storage::PersistentSafetyStorage, process::Process,
     safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
-    t_safety_rules::TSafetyRules,
+    t_safety_rules::TSafetyRules, voting_witness::VotingWitness,
 };
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -4,7 +4,7 @@
 use crate::{
     counters,
     logging::{self, LogEntry, LogEvent},
-    Error,
+    Error, VotingWitness,
 };
 use aptos_consensus_types::{common::Author, safety_data::SafetyData};
 use aptos_crypto::{bls12381, PrivateKey};
@@ -25,6 +25,7 @@ pub struct PersistentSafetyStorage {
     enable_cached_safety_data: bool,
     cached_safety_data: Option<SafetyData>,
     internal_store: Storage,
+    voting_witness: Option<VotingWitness>,
 }
 
 impl PersistentSafetyStorage {
@@ -47,6 +48,7 @@ impl PersistentSafetyStorage {
             enable_cached_safety_data,
             cached_safety_data: Some(safety_data.clone()),
             internal_store,
+            voting_witness: None,
         };
 
         // Initialize the safety data and waypoint
@@ -87,9 +89,16 @@ impl PersistentSafetyStorage {
             enable_cached_safety_data,
             cached_safety_data: None,
             internal_store,
+            voting_witness: None,
         }
     }
 
+    /// Consults the given witness before every vote, see [`VotingWitness`].
+    pub fn with_voting_witness(mut self, voting_witness: VotingWitness) -> Self {
+        self.voting_witness = Some(voting_witness);
+        self
+    }
+
     pub fn author(&self) -> Result<Author, Error> {
         let _timer = counters::start_timer("get", OWNER_ACCOUNT);
         Ok(self.internal_store.get(OWNER_ACCOUNT).map(|v| v.value)?)
@@ -144,6 +153,15 @@ impl PersistentSafetyStorage {
         }
     }
 
+    /// Checks the vote for `round` of `epoch` with the voting witness (if any), and records it.
+    /// This must be called before the vote is signed.
+    pub fn witness_vote(&mut self, author: &Author, epoch: u64, round: u64) -> Result<(), Error> {
+        match &mut self.voting_witness {
+            Some(voting_witness) => voting_witness.witness_vote(author, epoch, round),
+            None => Ok(()),
+        }
+    }
+
     pub fn waypoint(&self) -> Result<Waypoint, Error> {
         let _timer = counters::start_timer("get", WAYPOINT);
         Ok(self.internal_store.get(WAYPOINT).map(|v| v.value)?)
@@ -183,6 +183,17 @@ impl SafetyRules {
         Ok(())
     }
 
+    /// Checks the vote for the last voted round with the voting witness (if any), to guard
+    /// against voting twice in a round after safety data was restored from an old backup.
+    pub(crate) fn witness_vote(&mut self, safety_data: &SafetyData) -> Result<(), Error> {
+        let author = self.signer()?.author();
+        self.persistent_storage.witness_vote(
+            &author,
+            safety_data.epoch,
+            safety_data.last_voted_round,
+        )
+    }
+
     /// This verifies a QC has valid signatures.
     pub(crate) fn verify_qc(&self, qc: &QuorumCert) -> Result<(), Error> {
         let epoch_state = self.epoch_state()?;
@@ -38,6 +38,7 @@ impl SafetyRules {
         }
         if timeout.round() > safety_data.last_voted_round {
             self.verify_and_update_last_vote_round(timeout.round(), &mut safety_data)?;
+            self.witness_vote(&safety_data)?;
             self.persistent_storage.set_safety_data(safety_data)?;
         }
 
@@ -74,6 +75,7 @@ impl SafetyRules {
             &mut safety_data,
         )?;
         self.safe_to_vote(proposed_block, timeout_cert)?;
+        self.witness_vote(&safety_data)?;
 
         // Record 1-chain data
         self.observe_qc(proposed_block.quorum_cert(), &mut safety_data);
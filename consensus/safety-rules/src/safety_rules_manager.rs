@@ -8,7 +8,7 @@ use crate::{
     remote_service::RemoteService,
     serializer::{SerializerClient, SerializerService},
     thread::ThreadService,
-    SafetyRules, TSafetyRules,
+    SafetyRules, TSafetyRules, VotingWitness,
 };
 use aptos_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
 use aptos_infallible::RwLock;
@@ -16,6 +16,24 @@ use aptos_secure_storage::{KVStorage, Storage};
 use std::{convert::TryInto, net::SocketAddr, sync::Arc};
 
 pub fn storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
+    let storage = persistent_storage(config);
+    if config.voting_witness_backends.is_empty() {
+        return storage;
+    }
+
+    let witness_storages = config
+        .voting_witness_backends
+        .iter()
+        .map(|backend| {
+            backend
+                .try_into()
+                .expect("Unable to initialize voting witness storage")
+        })
+        .collect();
+    storage.with_voting_witness(VotingWitness::new(witness_storages))
+}
+
+fn persistent_storage(config: &SafetyRulesConfig) -> PersistentSafetyStorage {
     let backend = &config.backend;
     let internal_storage: Storage = backend.try_into().expect("Unable to initialize storage");
     if let Err(error) = internal_storage.available() {
@@ -8,3 +8,4 @@ mod serializer;
 mod suite;
 mod thread;
 mod vault;
+mod voting_witness;
@@ -0,0 +1,141 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    test_utils,
+    tests::suite,
+    voting_witness::{witnessed_round_key, WitnessRecord, WitnessedRound},
+    Error, SafetyRules, TSafetyRules, VotingWitness,
+};
+use aptos_consensus_types::common::Payload;
+use aptos_secure_storage::{InMemoryStorage, KVStorage, OnDiskStorage, Storage};
+use aptos_temppath::TempPath;
+use aptos_types::validator_signer::ValidatorSigner;
+
+#[test]
+fn test() {
+    suite::run_test_suite(&safety_rules());
+}
+
+fn safety_rules() -> suite::Callback {
+    Box::new(move || {
+        let signer = ValidatorSigner::from_int(0);
+        let witness = VotingWitness::new(vec![
+            Storage::from(InMemoryStorage::new()),
+            Storage::from(InMemoryStorage::new()),
+            Storage::from(InMemoryStorage::new()),
+        ]);
+        let storage = test_utils::test_storage(&signer).with_voting_witness(witness);
+        let safety_rules = Box::new(SafetyRules::new(storage));
+        (safety_rules, signer)
+    })
+}
+
+#[test]
+fn test_restored_safety_data() {
+    let signer = ValidatorSigner::from_int(0);
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer);
+    let a2 =
+        test_utils::make_proposal_with_parent(Payload::empty(false), round + 2, &a1, None, &signer);
+    let a3 =
+        test_utils::make_proposal_with_parent(Payload::empty(false), round + 3, &a2, None, &signer);
+    let epoch = a1.block().epoch();
+
+    // The validator voted for a2 before its safety data was restored from a backup, and a
+    // majority of the witness storages saw the vote
+    let witness_record = WitnessRecord {
+        version: 0,
+        round: WitnessedRound {
+            epoch,
+            round: round + 2,
+        },
+    };
+    let mut storages = vec![];
+    for i in 0..3 {
+        let mut storage = Storage::from(InMemoryStorage::new());
+        if i < 2 {
+            storage
+                .set(&witnessed_round_key(&signer.author()), witness_record)
+                .unwrap();
+        }
+        storages.push(storage);
+    }
+    let storage =
+        test_utils::test_storage(&signer).with_voting_witness(VotingWitness::new(storages));
+    let mut safety_rules = SafetyRules::new(storage);
+    safety_rules.initialize(&proof).unwrap();
+
+    assert_eq!(
+        safety_rules.construct_and_sign_vote_two_chain(&a1, None),
+        Err(Error::VotingWitnessAhead(
+            epoch,
+            round + 2,
+            epoch,
+            round + 1
+        ))
+    );
+    assert_eq!(
+        safety_rules.construct_and_sign_vote_two_chain(&a2, None),
+        Err(Error::VotingWitnessAhead(
+            epoch,
+            round + 2,
+            epoch,
+            round + 2
+        ))
+    );
+    safety_rules
+        .construct_and_sign_vote_two_chain(&a3, None)
+        .unwrap();
+}
+
+#[test]
+fn test_witness_unavailable() {
+    let signer = ValidatorSigner::from_int(0);
+    let (proof, genesis_qc) = test_utils::make_genesis(&signer);
+    let round = genesis_qc.certified_block().round();
+    let a1 = test_utils::make_proposal_with_qc(round + 1, genesis_qc, &signer);
+
+    let storage = test_utils::test_storage(&signer).with_voting_witness(VotingWitness::new(vec![]));
+    let mut safety_rules = SafetyRules::new(storage);
+    safety_rules.initialize(&proof).unwrap();
+
+    assert_eq!(
+        safety_rules.construct_and_sign_vote_two_chain(&a1, None),
+        Err(Error::VotingWitnessUnavailable(0, 0))
+    );
+}
+
+#[test]
+fn test_concurrent_witness_updates() {
+    // Two copies of a validator, e.g., the validator and a copy restored from a backup, share
+    // the witness storages
+    let author = ValidatorSigner::from_int(0).author();
+    let paths: Vec<_> = (0..3).map(|_| TempPath::new()).collect();
+    let witness = || {
+        VotingWitness::new(
+            paths
+                .iter()
+                .map(|path| Storage::from(OnDiskStorage::new(path.path().to_path_buf())))
+                .collect(),
+        )
+    };
+    let mut witness_1 = witness();
+    let mut witness_2 = witness();
+
+    witness_1.witness_vote(&author, 1, 1).unwrap();
+    witness_2.witness_vote(&author, 1, 2).unwrap();
+
+    // The first copy already checked the witnessed round, but its vote doesn't overwrite the
+    // vote of the second copy in the same round
+    assert_eq!(
+        witness_1.witness_vote(&author, 1, 2),
+        Err(Error::VotingWitnessAhead(1, 2, 1, 2))
+    );
+    assert_eq!(
+        witness_1.witnessed_round(&author).unwrap(),
+        Some(WitnessedRound { epoch: 1, round: 2 })
+    );
+    witness_1.witness_vote(&author, 1, 3).unwrap();
+}
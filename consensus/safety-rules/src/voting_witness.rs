@@ -0,0 +1,186 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    logging::{LogEntry, LogEvent, SafetyLogSchema},
+    Error,
+};
+use aptos_consensus_types::common::{Author, Round};
+use aptos_logger::prelude::*;
+use aptos_secure_storage::{KVStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+/// The highest round a validator voted for, as recorded by the witnesses
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct WitnessedRound {
+    pub epoch: u64,
+    pub round: Round,
+}
+
+/// The record of a witness storage. The version is incremented on every update, and updates
+/// compare and set the whole record, so concurrent updaters (e.g., a validator and a stale copy
+/// of it restored from a backup) can't overwrite each other's votes.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub(crate) struct WitnessRecord {
+    pub(crate) version: u64,
+    pub(crate) round: WitnessedRound,
+}
+
+/// The number of times the record of a storage is read and updated before giving up on it, if
+/// it keeps being updated concurrently
+const MAX_UPDATE_ATTEMPTS: usize = 3;
+
+/// The key under which the witnessed round of `author` is stored, so that a witness storage can
+/// be shared by the validators of an operator.
+pub(crate) fn witnessed_round_key(author: &Author) -> String {
+    format!("highest_voted_round_{}", author)
+}
+
+/// An external record of the highest round this validator voted for, kept in storages that are
+/// independent of the safety rules storage (e.g., Vault instances run by the operator on other
+/// hosts). If the validator is restored from an old backup of its safety data, its local last
+/// voted round is stale and it could vote again in a round it already voted in. The witness
+/// catches this: before the first vote after a start, the witnessed round is read from a quorum
+/// of the storages, and no vote at or below it is allowed. Every vote is then recorded with a
+/// quorum of the storages before it's signed. A storage only records a vote above the round it
+/// holds, so two updaters can't both get a quorum for the same round.
+pub struct VotingWitness {
+    storages: Vec<Storage>,
+    quorum: usize,
+    // Whether the witnessed round was checked since the start
+    checked: bool,
+}
+
+impl VotingWitness {
+    /// Creates a witness requiring a majority of the given storages to agree
+    pub fn new(storages: Vec<Storage>) -> Self {
+        let quorum = storages.len() / 2 + 1;
+        Self {
+            storages,
+            quorum,
+            checked: false,
+        }
+    }
+
+    /// Reads the highest witnessed round of `author` from a quorum of the storages
+    pub fn witnessed_round(&self, author: &Author) -> Result<Option<WitnessedRound>, Error> {
+        let key = witnessed_round_key(author);
+        let mut num_responses = 0;
+        let mut highest_round = None;
+        for storage in &self.storages {
+            match storage.get::<WitnessRecord>(&key) {
+                Ok(response) => {
+                    num_responses += 1;
+                    highest_round = highest_round.max(Some(response.value.round));
+                }
+                Err(aptos_secure_storage::Error::KeyNotSet(_)) => num_responses += 1,
+                Err(error) => {
+                    let error = Error::SecureStorageUnexpectedError(error.to_string());
+                    warn!(
+                        SafetyLogSchema::new(LogEntry::VotingWitness, LogEvent::Error)
+                            .author(*author)
+                            .error(&error)
+                    );
+                }
+            }
+        }
+        self.check_quorum(num_responses)?;
+        Ok(highest_round)
+    }
+
+    /// Checks that voting for `round` of `epoch` is allowed by the witness, and records it with a
+    /// quorum of the storages. Fails if the quorum can't be reached because some of the storages
+    /// hold a vote at or above the round.
+    pub fn witness_vote(&mut self, author: &Author, epoch: u64, round: Round) -> Result<(), Error> {
+        let vote_round = WitnessedRound { epoch, round };
+        if !self.checked {
+            if let Some(witnessed_round) = self.witnessed_round(author)? {
+                if witnessed_round >= vote_round {
+                    return Err(Error::VotingWitnessAhead(
+                        witnessed_round.epoch,
+                        witnessed_round.round,
+                        epoch,
+                        round,
+                    ));
+                }
+            }
+            self.checked = true;
+        }
+
+        let key = witnessed_round_key(author);
+        let mut num_responses = 0;
+        let mut highest_round = None;
+        for storage in &mut self.storages {
+            match record_vote(storage, &key, vote_round) {
+                Ok(None) => num_responses += 1,
+                Ok(Some(witnessed_round)) => {
+                    highest_round = highest_round.max(Some(witnessed_round));
+                }
+                Err(error) => {
+                    let error = Error::SecureStorageUnexpectedError(error.to_string());
+                    warn!(
+                        SafetyLogSchema::new(LogEntry::VotingWitness, LogEvent::Error)
+                            .author(*author)
+                            .error(&error)
+                    );
+                }
+            }
+        }
+        if num_responses < self.quorum {
+            if let Some(witnessed_round) = highest_round {
+                return Err(Error::VotingWitnessAhead(
+                    witnessed_round.epoch,
+                    witnessed_round.round,
+                    epoch,
+                    round,
+                ));
+            }
+        }
+        self.check_quorum(num_responses)
+    }
+
+    fn check_quorum(&self, num_responses: usize) -> Result<(), Error> {
+        if num_responses < self.quorum {
+            return Err(Error::VotingWitnessUnavailable(
+                num_responses,
+                self.storages.len(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Records `vote_round` in the storage, unless the storage holds a vote at or above it, which is
+/// returned instead.
+fn record_vote(
+    storage: &mut Storage,
+    key: &str,
+    vote_round: WitnessedRound,
+) -> Result<Option<WitnessedRound>, aptos_secure_storage::Error> {
+    for _ in 0..MAX_UPDATE_ATTEMPTS {
+        let record = match storage.get::<WitnessRecord>(key) {
+            Ok(response) => Some(response.value),
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => None,
+            Err(error) => return Err(error),
+        };
+        if let Some(record) = record {
+            if record.round >= vote_round {
+                return Ok(Some(record.round));
+            }
+        }
+
+        let new_record = WitnessRecord {
+            version: record.map_or(0, |record| record.version + 1),
+            round: vote_round,
+        };
+        match storage.compare_and_set(key, record, new_record) {
+            Ok(()) => return Ok(None),
+            // The record was updated since it was read, check it again
+            Err(aptos_secure_storage::Error::CompareAndSetFailed(_)) => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Err(aptos_secure_storage::Error::CompareAndSetFailed(
+        key.to_string(),
+    ))
+}
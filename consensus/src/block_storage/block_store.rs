@@ -27,7 +27,10 @@ use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionStatus};
 use futures::executor::block_on;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 #[cfg(test)]
 use std::collections::VecDeque;
@@ -41,6 +44,9 @@ mod block_store_test;
 #[path = "sync_manager.rs"]
 pub mod sync_manager;
 
+// Number of recovered blocks replayed between progress logs
+const REPLAY_PROGRESS_INTERVAL_BLOCKS: usize = 100;
+
 fn update_counters_for_ordered_blocks(ordered_blocks: &[Arc<ExecutedBlock>]) {
     for block in ordered_blocks {
         observe_block(block.block().timestamp_usecs(), BlockStage::ORDERED);
@@ -230,14 +236,7 @@ impl BlockStore {
             back_pressure_for_test: AtomicBool::new(false),
         };
 
-        for block in blocks {
-            block_store
-                .execute_and_insert_block(block)
-                .await
-                .unwrap_or_else(|e| {
-                    panic!("[BlockStore] failed to insert block during build {:?}", e)
-                });
-        }
+        block_store.replay_blocks(blocks).await;
         for qc in quorum_certs {
             block_store
                 .insert_single_quorum_cert(qc)
@@ -250,6 +249,28 @@ impl BlockStore {
         block_store
     }
 
+    /// Executes and inserts the recovered blocks one by one, in round order so that parents are
+    /// inserted before their children.
+    async fn replay_blocks(&self, blocks: Vec<Block>) {
+        let total = blocks.len();
+        let start = Instant::now();
+        for (replayed, block) in blocks.into_iter().enumerate() {
+            self.execute_and_insert_block(block)
+                .await
+                .unwrap_or_else(|e| {
+                    panic!("[BlockStore] failed to insert block during build {:?}", e)
+                });
+            if (replayed + 1) % REPLAY_PROGRESS_INTERVAL_BLOCKS == 0 || replayed + 1 == total {
+                info!(
+                    "Replayed {}/{} recovered blocks in {} ms",
+                    replayed + 1,
+                    total,
+                    start.elapsed().as_millis()
+                );
+            }
+        }
+    }
+
     /// Commit the given block id with the proof, returns () on success or error
     pub async fn commit(&self, finality_proof: QuorumCert) -> anyhow::Result<()> {
         let block_id_to_commit = finality_proof.commit_info().id();
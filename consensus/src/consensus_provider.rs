@@ -18,6 +18,7 @@ use aptos_executor::block_executor::BlockExecutor;
 use aptos_logger::prelude::*;
 use aptos_mempool::QuorumStoreRequest;
 use aptos_network::application::storage::PeerMetadataStorage;
+use aptos_runtimes::task_dump::track;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_vm::AptosVM;
 use futures::channel::mpsc;
@@ -82,8 +83,11 @@ pub fn start_consensus(
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
 
-    runtime.spawn(network_task.start());
-    runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
+    runtime.spawn(track("consensus-network-task", network_task.start()));
+    runtime.spawn(track(
+        "consensus-epoch-manager",
+        epoch_mgr.start(timeout_receiver, network_receiver),
+    ));
 
     debug!("Consensus started.");
     runtime
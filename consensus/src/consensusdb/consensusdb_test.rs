@@ -69,3 +69,26 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_compact() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+
+    let blocks = vec![Block::make_genesis_block()];
+    let qcs = vec![certificate_for_genesis()];
+    db.save_blocks_and_quorum_certificates(blocks.clone(), qcs.clone())
+        .unwrap();
+    db.compact().unwrap();
+
+    // compaction keeps all live data
+    let (_, _, blocks_1, qcs_1) = db.get_data().unwrap();
+    assert_eq!(blocks, blocks_1);
+    assert_eq!(qcs, qcs_1);
+
+    db.delete_blocks_and_quorum_certificates(vec![blocks[0].id()])
+        .unwrap();
+    db.compact().unwrap();
+    assert_eq!(db.get_blocks().unwrap().len(), 0);
+    assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
+}
@@ -144,6 +144,19 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Compacts the block and QC column families, so that the data deleted by pruning no longer
+    /// has to be skipped over when reading the db during recovery.
+    pub fn compact(&self) -> Result<(), DbError> {
+        let instant = Instant::now();
+        self.db.compact_cf(BLOCK_CF_NAME)?;
+        self.db.compact_cf(QC_CF_NAME)?;
+        info!(
+            "Compacted ConsensusDB in {} ms",
+            instant.elapsed().as_millis()
+        );
+        Ok(())
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledger
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<(), DbError> {
@@ -451,6 +451,16 @@ pub static BUFFER_MANAGER_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counters(scheduled,hit,failed,discarded) of the speculative executions of proposed blocks
+pub static OPTIMISTIC_EXECUTION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_optimistic_execution_count",
+        "Counters(scheduled,hit,failed,discarded) of the speculative executions of proposed blocks",
+        &["state"]
+    )
+    .unwrap()
+});
+
 /// Counters(queued,dequeued,dropped) related to consensus channel
 pub static CONSENSUS_CHANNEL_MSGS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
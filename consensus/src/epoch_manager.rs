@@ -11,6 +11,7 @@ use crate::{
     experimental::{
         buffer_manager::{OrderedBlocks, ResetRequest},
         decoupled_execution_utils::prepare_phases_and_buffer_manager,
+        optimistic_execution::OptimisticExecution,
         ordering_state_computer::OrderingStateComputer,
     },
     liveness::{
@@ -33,7 +34,9 @@ use crate::{
     payload_client::QuorumStoreClient,
     payload_manager::PayloadManager,
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
-    quorum_store::direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    quorum_store::{
+        batch_coordinator::BatchCoordinator, direct_mempool_quorum_store::DirectMempoolQuorumStore,
+    },
     recovery_manager::RecoveryManager,
     round_manager::{RoundManager, UnverifiedEvent, VerifiedEvent},
     state_replication::StateComputer,
@@ -45,6 +48,7 @@ use aptos_config::config::{ConsensusConfig, NodeConfig};
 use aptos_consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
+    proof_of_store::LogicalTime,
     request_response::PayloadRequest,
 };
 use aptos_event_notifications::ReconfigNotificationListener;
@@ -105,6 +109,8 @@ pub struct EpochManager {
     timeout_sender: aptos_channels::Sender<Round>,
     quorum_store_enabled: bool,
     quorum_store_to_mempool_sender: Sender<QuorumStoreRequest>,
+    // Receives the batches of other validators while quorum store is enabled
+    batch_coordinator: Option<BatchCoordinator>,
     commit_state_computer: Arc<dyn StateComputer>,
     storage: Arc<dyn PersistentLivenessStorage>,
     safety_rules_manager: SafetyRulesManager,
@@ -148,6 +154,7 @@ impl EpochManager {
             // This default value is updated at epoch start
             quorum_store_enabled: false,
             quorum_store_to_mempool_sender,
+            batch_coordinator: None,
             commit_state_computer,
             storage,
             safety_rules_manager,
@@ -469,6 +476,7 @@ impl EpochManager {
         &mut self,
         safety_rules_container: Arc<Mutex<MetricsSafetyRules>>,
         verifier: ValidatorVerifier,
+        optimistic_execution: bool,
     ) -> OrderingStateComputer {
         let network_sender = NetworkSender::new(
             self.author,
@@ -489,6 +497,9 @@ impl EpochManager {
         self.buffer_manager_msg_tx = Some(commit_msg_tx);
         self.buffer_manager_reset_tx = Some(reset_tx.clone());
 
+        let optimistic_execution = optimistic_execution
+            .then(|| Arc::new(OptimisticExecution::new(self.commit_state_computer.clone())));
+
         let (execution_phase, signing_phase, persisting_phase, buffer_manager) =
             prepare_phases_and_buffer_manager(
                 self.author,
@@ -500,6 +511,7 @@ impl EpochManager {
                 block_rx,
                 reset_rx,
                 verifier,
+                optimistic_execution.clone(),
             );
 
         tokio::spawn(execution_phase.start());
@@ -507,7 +519,12 @@ impl EpochManager {
         tokio::spawn(persisting_phase.start());
         tokio::spawn(buffer_manager.start());
 
-        OrderingStateComputer::new(block_tx, self.commit_state_computer.clone(), reset_tx)
+        OrderingStateComputer::new(
+            block_tx,
+            self.commit_state_computer.clone(),
+            reset_tx,
+            optimistic_execution,
+        )
     }
 
     async fn shutdown_current_processor(&mut self) {
@@ -540,6 +557,8 @@ impl EpochManager {
 
         // Shutdown the block retrieval task by dropping the sender
         self.block_retrieval_tx = None;
+
+        self.batch_coordinator = None;
     }
 
     async fn start_recovery_manager(
@@ -624,6 +643,7 @@ impl EpochManager {
             Arc::new(self.spawn_decoupled_execution(
                 safety_rules_container.clone(),
                 epoch_state.verifier.clone(),
+                onchain_config.optimistic_execution(),
             ))
         } else {
             self.commit_state_computer.clone()
@@ -640,6 +660,13 @@ impl EpochManager {
             payload_manager,
         ));
 
+        if self.quorum_store_enabled {
+            self.batch_coordinator = Some(BatchCoordinator::new(
+                epoch,
+                &self.config.quorum_store_batch_reputation,
+            ));
+        }
+
         info!(epoch = epoch, "Start DirectMempoolQuorumStore");
         let (consensus_to_quorum_store_tx, consensus_to_quorum_store_rx) =
             mpsc::channel(self.config.intra_consensus_channel_buffer_size);
@@ -877,6 +904,14 @@ impl EpochManager {
                 proposal.proposal().timestamp_usecs(),
                 BlockStage::EPOCH_MANAGER_VERIFIED,
             );
+            // Batches that expired before the round certified by the proposal can be released
+            if let Some(batch_coordinator) = &mut self.batch_coordinator {
+                let certified_block = proposal.proposal().quorum_cert().certified_block();
+                batch_coordinator.update_certified_time(LogicalTime::new(
+                    certified_block.epoch(),
+                    certified_block.round(),
+                ));
+            }
         }
         match event {
             buffer_manager_event @ (VerifiedEvent::CommitVote(_)
@@ -887,6 +922,11 @@ impl EpochManager {
                     bail!("Commit Phase not started but received Commit Message (CommitVote/CommitDecision)");
                 }
             }
+            VerifiedEvent::FragmentMsg(fragment) => {
+                if let Some(batch_coordinator) = &mut self.batch_coordinator {
+                    batch_coordinator.handle_fragment(*fragment);
+                }
+            }
             round_manager_event => {
                 self.forward_to_round_manager(peer_id, round_manager_event);
             }
@@ -5,6 +5,7 @@ use crate::{
     experimental::{
         buffer_manager::{create_channel, BufferManager, OrderedBlocks, ResetRequest},
         execution_phase::{ExecutionPhase, ExecutionRequest, ExecutionResponse},
+        optimistic_execution::OptimisticExecution,
         persisting_phase::{PersistingPhase, PersistingRequest},
         pipeline_phase::{CountedRequest, PipelinePhase},
         signing_phase::{SigningPhase, SigningRequest, SigningResponse},
@@ -32,6 +33,7 @@ pub fn prepare_phases_and_buffer_manager(
     block_rx: UnboundedReceiver<OrderedBlocks>,
     sync_rx: UnboundedReceiver<ResetRequest>,
     verifier: ValidatorVerifier,
+    optimistic_execution: Option<Arc<OptimisticExecution>>,
 ) -> (
     PipelinePhase<ExecutionPhase>,
     PipelinePhase<SigningPhase>,
@@ -46,7 +48,7 @@ pub fn prepare_phases_and_buffer_manager(
 
     let ongoing_tasks = Arc::new(AtomicU64::new(0));
 
-    let execution_phase_processor = ExecutionPhase::new(execution_proxy);
+    let execution_phase_processor = ExecutionPhase::new(execution_proxy, optimistic_execution);
     let execution_phase = PipelinePhase::new(
         execution_phase_request_rx,
         Some(execution_phase_response_tx),
@@ -1,11 +1,14 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{experimental::pipeline_phase::StatelessPipeline, state_replication::StateComputer};
+use crate::{
+    experimental::{optimistic_execution::OptimisticExecution, pipeline_phase::StatelessPipeline},
+    state_replication::StateComputer,
+};
 use anyhow::Result;
 use aptos_consensus_types::executed_block::ExecutedBlock;
 use aptos_crypto::HashValue;
-use aptos_executor_types::Error as ExecutionError;
+use aptos_executor_types::{Error as ExecutionError, StateComputeResult};
 use async_trait::async_trait;
 use std::{
     fmt::{Debug, Display, Formatter},
@@ -16,6 +19,8 @@ use std::{
 /// ExecutionPhase is a singleton that receives ordered blocks from
 /// the buffer manager and execute them. After the execution is done,
 /// ExecutionPhase sends the ordered blocks back to the buffer manager.
+/// Blocks that were executed speculatively by OptimisticExecution
+/// reuse the result of the speculative execution.
 ///
 
 pub struct ExecutionRequest {
@@ -41,11 +46,25 @@ pub struct ExecutionResponse {
 
 pub struct ExecutionPhase {
     execution_proxy: Arc<dyn StateComputer>,
+    optimistic_execution: Option<Arc<OptimisticExecution>>,
 }
 
 impl ExecutionPhase {
-    pub fn new(execution_proxy: Arc<dyn StateComputer>) -> Self {
-        Self { execution_proxy }
+    pub fn new(
+        execution_proxy: Arc<dyn StateComputer>,
+        optimistic_execution: Option<Arc<OptimisticExecution>>,
+    ) -> Self {
+        Self {
+            execution_proxy,
+            optimistic_execution,
+        }
+    }
+
+    async fn speculative_result(&self, block_id: HashValue) -> Option<StateComputeResult> {
+        match &self.optimistic_execution {
+            Some(optimistic_execution) => optimistic_execution.take(block_id).await,
+            None => None,
+        }
     }
 }
 
@@ -65,10 +84,15 @@ impl StatelessPipeline for ExecutionPhase {
         }
 
         let block_id = ordered_blocks.last().unwrap().id();
+        let round = ordered_blocks.last().unwrap().round();
         let mut result = vec![];
 
         for b in ordered_blocks {
-            match self.execution_proxy.compute(b.block(), b.parent_id()).await {
+            let compute_result = match self.speculative_result(b.id()).await {
+                Some(compute_result) => Ok(compute_result),
+                None => self.execution_proxy.compute(b.block(), b.parent_id()).await,
+            };
+            match compute_result {
                 Ok(compute_result) => {
                     result.push(ExecutedBlock::new(b.block().clone(), compute_result));
                 }
@@ -81,6 +105,11 @@ impl StatelessPipeline for ExecutionPhase {
             }
         }
 
+        // Blocks of earlier rounds that were executed speculatively will never be ordered
+        if let Some(optimistic_execution) = &self.optimistic_execution {
+            optimistic_execution.discard_up_to(round);
+        }
+
         ExecutionResponse {
             block_id,
             inner: Ok(result),
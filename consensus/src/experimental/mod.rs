@@ -28,6 +28,7 @@ pub mod decoupled_execution_utils;
 pub mod errors;
 pub mod execution_phase;
 pub mod hashable;
+pub mod optimistic_execution;
 pub mod ordering_state_computer;
 pub mod persisting_phase;
 pub mod pipeline_phase;
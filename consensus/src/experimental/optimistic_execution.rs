@@ -0,0 +1,152 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{counters, state_replication::StateComputer};
+use aptos_consensus_types::{block::Block, common::Round};
+use aptos_crypto::HashValue;
+use aptos_executor_types::StateComputeResult;
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use futures::{
+    future::{AbortHandle, Abortable, BoxFuture, Shared},
+    FutureExt,
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// The result of a speculative execution, None if it failed or was aborted
+type SpeculativeResult = Shared<BoxFuture<'static, Option<StateComputeResult>>>;
+
+struct SpeculativeExecution {
+    round: Round,
+    result: SpeculativeResult,
+    abort_handle: AbortHandle,
+}
+
+/// [ This class is used when consensus.decoupled = true and optimistic execution is enabled
+/// on-chain ]
+/// OptimisticExecution starts executing proposed blocks as soon as they are inserted into the
+/// block store, while the votes are being collected. The execution phase reuses the result once
+/// the block is ordered, instead of executing the block from scratch.
+///
+/// Speculative executions of blocks that are not ordered (e.g. because their round timed out)
+/// are discarded once a block of a later round is executed, or when the pipeline is reset.
+/// Discarded executions are aborted, so that they don't keep executing on top of stale parents.
+/// The executor keeps the output of the executed blocks in its block tree, so executing a block
+/// twice is harmless; the executor prunes the abandoned branches on commit.
+pub struct OptimisticExecution {
+    execution_proxy: Arc<dyn StateComputer>,
+    speculative_executions: Mutex<HashMap<HashValue, SpeculativeExecution>>,
+}
+
+impl OptimisticExecution {
+    pub fn new(execution_proxy: Arc<dyn StateComputer>) -> Self {
+        Self {
+            execution_proxy,
+            speculative_executions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts executing a proposed block in the background. If the parent is being executed
+    /// speculatively too, the execution starts once the parent is executed.
+    pub fn schedule(&self, block: Block, parent_block_id: HashValue) {
+        let mut speculative_executions = self.speculative_executions.lock();
+        if speculative_executions.contains_key(&block.id()) {
+            return;
+        }
+        let parent_execution = speculative_executions
+            .get(&parent_block_id)
+            .map(|execution| execution.result.clone());
+
+        let block_id = block.id();
+        let round = block.round();
+        let execution_proxy = self.execution_proxy.clone();
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let execution = async move {
+            if let Some(parent_execution) = parent_execution {
+                parent_execution.await?;
+            }
+            match execution_proxy.compute(&block, parent_block_id).await {
+                Ok(compute_result) => Some(compute_result),
+                Err(e) => {
+                    // The parent may not be executed yet, the block is executed again once ordered
+                    debug!(
+                        block_id = block_id,
+                        error = ?e,
+                        "Speculative execution failed",
+                    );
+                    None
+                }
+            }
+        };
+        let result = Abortable::new(execution, abort_registration)
+            .map(|result| result.ok().flatten())
+            .boxed()
+            .shared();
+
+        // Drive the execution without waiting for the block to be ordered
+        tokio::spawn(result.clone());
+        speculative_executions.insert(
+            block_id,
+            SpeculativeExecution {
+                round,
+                result,
+                abort_handle,
+            },
+        );
+        counters::OPTIMISTIC_EXECUTION_COUNT
+            .with_label_values(&["scheduled"])
+            .inc();
+    }
+
+    /// Waits for the speculative execution of a block, returns None if the block wasn't
+    /// executed speculatively or the execution failed.
+    pub async fn take(&self, block_id: HashValue) -> Option<StateComputeResult> {
+        let execution = self.speculative_executions.lock().remove(&block_id);
+        let compute_result = execution?.result.await;
+        let state = if compute_result.is_some() {
+            "hit"
+        } else {
+            "failed"
+        };
+        counters::OPTIMISTIC_EXECUTION_COUNT
+            .with_label_values(&[state])
+            .inc();
+        compute_result
+    }
+
+    /// Aborts the speculative executions of blocks up to the given round, which can't be
+    /// ordered anymore.
+    pub fn discard_up_to(&self, round: Round) {
+        let mut speculative_executions = self.speculative_executions.lock();
+        let before = speculative_executions.len();
+        speculative_executions.retain(|_, execution| {
+            if execution.round > round {
+                return true;
+            }
+            execution.abort_handle.abort();
+            false
+        });
+        counters::OPTIMISTIC_EXECUTION_COUNT
+            .with_label_values(&["discarded"])
+            .inc_by((before - speculative_executions.len()) as u64);
+    }
+
+    /// Aborts all speculative executions, e.g. when the pipeline is reset to sync to a new
+    /// commit
+    pub fn discard_all(&self) {
+        let mut speculative_executions = self.speculative_executions.lock();
+        counters::OPTIMISTIC_EXECUTION_COUNT
+            .with_label_values(&["discarded"])
+            .inc_by(speculative_executions.len() as u64);
+        for (_, execution) in speculative_executions.drain() {
+            execution.abort_handle.abort();
+        }
+    }
+}
+
+impl Drop for OptimisticExecution {
+    /// Aborts the speculative executions left when the epoch ends
+    fn drop(&mut self) {
+        self.discard_all();
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     experimental::{
         buffer_manager::{OrderedBlocks, ResetAck, ResetRequest},
         errors::Error,
+        optimistic_execution::OptimisticExecution,
     },
     state_replication::{StateComputer, StateComputerCommitCallBackType},
 };
@@ -32,6 +33,9 @@ pub struct OrderingStateComputer {
     executor_channel: UnboundedSender<OrderedBlocks>,
     state_computer_for_sync: Arc<dyn StateComputer>,
     reset_event_channel_tx: UnboundedSender<ResetRequest>,
+    // executes the proposed blocks while the votes are collected,
+    // if optimistic execution is enabled on-chain.
+    optimistic_execution: Option<Arc<OptimisticExecution>>,
 }
 
 impl OrderingStateComputer {
@@ -39,11 +43,13 @@ impl OrderingStateComputer {
         executor_channel: UnboundedSender<OrderedBlocks>,
         state_computer_for_sync: Arc<dyn StateComputer>,
         reset_event_channel_tx: UnboundedSender<ResetRequest>,
+        optimistic_execution: Option<Arc<OptimisticExecution>>,
     ) -> Self {
         Self {
             executor_channel,
             state_computer_for_sync,
             reset_event_channel_tx,
+            optimistic_execution,
         }
     }
 }
@@ -53,10 +59,16 @@ impl StateComputer for OrderingStateComputer {
     async fn compute(
         &self,
         // The block to be executed.
-        _block: &Block,
+        block: &Block,
         // The parent block id.
-        _parent_block_id: HashValue,
+        parent_block_id: HashValue,
     ) -> Result<StateComputeResult, ExecutionError> {
+        // Start executing the block speculatively, the execution phase
+        // picks up the result once the block is ordered.
+        if let Some(optimistic_execution) = &self.optimistic_execution {
+            optimistic_execution.schedule(block.clone(), parent_block_id);
+        }
+
         // Return dummy block and bypass the execution phase.
         // This will break the e2e smoke test (for now because
         // no one is actually handling the next phase) if the
@@ -111,6 +123,9 @@ impl StateComputer for OrderingStateComputer {
             .await
             .map_err(|_| Error::ResetDropped)?;
         rx.await.map_err(|_| Error::ResetDropped)?;
+        if let Some(optimistic_execution) = &self.optimistic_execution {
+            optimistic_execution.discard_all();
+        }
 
         // TODO: handle the sync error, should re-push the ordered blocks to buffer manager
         // when it's reset but sync fails.
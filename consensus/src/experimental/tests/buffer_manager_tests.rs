@@ -108,6 +108,7 @@ pub fn prepare_buffer_manager() -> (
         result_tx,
         Arc::new(EmptyStateComputer),
         reset_tx,
+        None,
     ));
 
     let (block_tx, block_rx) = create_channel::<OrderedBlocks>();
@@ -131,6 +132,7 @@ pub fn prepare_buffer_manager() -> (
         block_rx,
         buffer_reset_rx,
         validators.clone(),
+        None,
     );
 
     (
@@ -24,7 +24,7 @@ use std::sync::Arc;
 pub fn prepare_execution_phase() -> (HashValue, ExecutionPhase) {
     let execution_proxy = Arc::new(RandomComputeResultStateComputer::new());
     let random_hash_value = execution_proxy.get_root_hash();
-    let execution_phase = ExecutionPhase::new(execution_proxy);
+    let execution_phase = ExecutionPhase::new(execution_proxy, None);
     (random_hash_value, execution_phase)
 }
 
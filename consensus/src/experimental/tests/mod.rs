@@ -4,6 +4,7 @@
 mod buffer_manager_tests;
 mod execution_phase_tests;
 mod integration_tests;
+mod optimistic_execution_tests;
 mod ordering_state_computer_tests;
 mod phase_tester;
 mod signing_phase_tests;
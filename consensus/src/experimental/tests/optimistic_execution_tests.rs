@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    experimental::optimistic_execution::OptimisticExecution,
+    test_utils::{consensus_runtime, RandomComputeResultStateComputer},
+};
+use aptos_consensus_types::{
+    block::{block_test_utils::certificate_for_genesis, Block},
+    common::Payload,
+};
+use aptos_crypto::HashValue;
+use aptos_types::validator_verifier::random_validator_verifier;
+use std::sync::Arc;
+
+#[test]
+fn optimistic_execution_tests() {
+    let runtime = consensus_runtime();
+    let execution_proxy = Arc::new(RandomComputeResultStateComputer::new());
+    let random_hash_value = execution_proxy.get_root_hash();
+    let optimistic_execution = OptimisticExecution::new(execution_proxy);
+
+    let (signers, _validators) = random_validator_verifier(1, None, false);
+    let genesis_qc = certificate_for_genesis();
+    let proposal = |round| {
+        Block::new_proposal(
+            Payload::empty(false),
+            round,
+            1,
+            genesis_qc.clone(),
+            &signers[0],
+            Vec::new(),
+        )
+        .unwrap()
+    };
+
+    runtime.block_on(async {
+        // the speculative result is reused once
+        let block = proposal(1);
+        optimistic_execution.schedule(block.clone(), HashValue::zero());
+        let compute_result = optimistic_execution.take(block.id()).await.unwrap();
+        assert_eq!(compute_result.root_hash(), random_hash_value);
+        assert!(optimistic_execution.take(block.id()).await.is_none());
+
+        // failed speculative executions fall back to regular execution
+        let bad_block = proposal(2);
+        optimistic_execution.schedule(bad_block.clone(), random_hash_value);
+        assert!(optimistic_execution.take(bad_block.id()).await.is_none());
+
+        // blocks that can't be ordered anymore are discarded
+        let (old_block, new_block) = (proposal(3), proposal(4));
+        optimistic_execution.schedule(old_block.clone(), HashValue::zero());
+        optimistic_execution.schedule(new_block.clone(), HashValue::zero());
+        optimistic_execution.discard_up_to(3);
+        assert!(optimistic_execution.take(old_block.id()).await.is_none());
+        assert!(optimistic_execution.take(new_block.id()).await.is_some());
+
+        // resetting the pipeline discards all speculative executions
+        let block = proposal(5);
+        optimistic_execution.schedule(block.clone(), HashValue::zero());
+        optimistic_execution.discard_all();
+        assert!(optimistic_execution.take(block.id()).await.is_none());
+    });
+}
@@ -47,6 +47,7 @@ pub mod counters;
 /// AptosNet interface.
 pub mod network_interface;
 mod payload_manager;
+pub mod round_postmortem;
 
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
@@ -8,13 +8,23 @@ use aptos_consensus_types::{
     block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
 };
 use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_storage_interface::DbReader;
 use aptos_types::{
     block_info::Round, epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures,
     proof::TransactionAccumulatorSummary, transaction::Version,
 };
-use std::{cmp::max, collections::HashSet, sync::Arc};
+use std::{
+    cmp::max,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{sync_channel, SyncSender, TrySendError},
+        Arc,
+    },
+    thread,
+};
 
 /// PersistentLivenessStorage is essential for maintaining liveness when a node crashes.  Specifically,
 /// upon a restart, a correct node will recover.  Even if all nodes crash, liveness is
@@ -268,6 +278,51 @@ impl RecoveryData {
         self.highest_2chain_timeout_certificate.clone()
     }
 
+    /// Keeps the `max_blocks` newest blocks beyond the root and their ancestors, so that recovery
+    /// doesn't replay the stale forks left behind in the ConsensusDB. The newest blocks carry the
+    /// highest certified and ordered blocks, which the sync info is built from, so they're never
+    /// pruned.
+    pub fn retain_blocks(&mut self, max_blocks: usize) {
+        if self.blocks.len() <= max_blocks {
+            return;
+        }
+        let parent_ids: HashMap<_, _> = self
+            .blocks
+            .iter()
+            .map(|block| (block.id(), block.parent_id()))
+            .collect();
+        let mut retained_ids = HashSet::new();
+        // blocks are sorted by round, so the newest ones are last
+        for block in self.blocks.iter().rev().take(max_blocks) {
+            // walk up until the root or a block already retained
+            let mut id = block.id();
+            while retained_ids.insert(id) {
+                match parent_ids.get(&id) {
+                    Some(parent_id) => id = *parent_id,
+                    None => break,
+                }
+            }
+        }
+        let mut removed_ids = HashSet::new();
+        self.blocks.retain(|block| {
+            retained_ids.contains(&block.id()) || {
+                removed_ids.insert(block.id());
+                false
+            }
+        });
+        self.quorum_certs
+            .retain(|qc| !removed_ids.contains(&qc.certified_block().id()));
+        info!(
+            "Pruning {} blocks off the forks of the {} newest during recovery",
+            removed_ids.len(),
+            max_blocks
+        );
+        self.blocks_to_prune
+            .as_mut()
+            .expect("blocks_to_prune already taken")
+            .extend(removed_ids);
+    }
+
     fn find_blocks_to_prune(
         root_id: HashValue,
         blocks: &mut Vec<Block>,
@@ -303,12 +358,54 @@ impl RecoveryData {
 pub struct StorageWriteProxy {
     db: Arc<ConsensusDB>,
     aptos_db: Arc<dyn DbReader>,
+    max_recovery_blocks: usize,
+    db_compaction_interval_blocks: usize,
+    pruned_blocks_since_compaction: AtomicUsize,
+    // Requests compactions from the compaction thread, holding at most one pending request
+    compaction_tx: Mutex<SyncSender<()>>,
 }
 
 impl StorageWriteProxy {
     pub fn new(config: &NodeConfig, aptos_db: Arc<dyn DbReader>) -> Self {
         let db = Arc::new(ConsensusDB::new(config.storage.dir()));
-        StorageWriteProxy { db, aptos_db }
+        let (compaction_tx, compaction_rx) = sync_channel(1);
+        let compaction_db = db.clone();
+        // The thread exits once the proxy, and so the sender, is dropped
+        thread::Builder::new()
+            .name("consensusdb-compaction".into())
+            .spawn(move || {
+                while compaction_rx.recv().is_ok() {
+                    if let Err(e) = compaction_db.compact() {
+                        warn!(error = ?e, "Failed to compact ConsensusDB");
+                    }
+                }
+            })
+            .expect("unable to spawn the ConsensusDB compaction thread");
+        StorageWriteProxy {
+            db,
+            aptos_db,
+            max_recovery_blocks: config.consensus.max_recovery_blocks,
+            db_compaction_interval_blocks: config.consensus.db_compaction_interval_blocks,
+            pruned_blocks_since_compaction: AtomicUsize::new(0),
+            compaction_tx: Mutex::new(compaction_tx),
+        }
+    }
+
+    /// Compacts the ConsensusDB on the compaction thread once enough blocks have been pruned
+    /// since the last compaction. A request is dropped if a compaction is already pending.
+    fn maybe_compact(&self, num_pruned_blocks: usize) {
+        let pruned_blocks = self
+            .pruned_blocks_since_compaction
+            .fetch_add(num_pruned_blocks, Ordering::Relaxed)
+            + num_pruned_blocks;
+        if pruned_blocks < self.db_compaction_interval_blocks {
+            return;
+        }
+        self.pruned_blocks_since_compaction
+            .store(0, Ordering::Relaxed);
+        if let Err(TrySendError::Disconnected(())) = self.compaction_tx.lock().try_send(()) {
+            warn!("The ConsensusDB compaction thread has stopped");
+        }
     }
 }
 
@@ -321,8 +418,10 @@ impl PersistentLivenessStorage for StorageWriteProxy {
 
     fn prune_tree(&self, block_ids: Vec<HashValue>) -> Result<()> {
         if !block_ids.is_empty() {
+            let num_pruned_blocks = block_ids.len();
             // quorum certs that certified the block_ids will get removed
             self.db.delete_blocks_and_quorum_certificates(block_ids)?;
+            self.maybe_compact(num_pruned_blocks);
         }
         Ok(())
     }
@@ -389,9 +488,17 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             highest_2chain_timeout_cert,
         ) {
             Ok(mut initial_data) => {
-                (self as &dyn PersistentLivenessStorage)
-                    .prune_tree(initial_data.take_blocks_to_prune())
-                    .expect("unable to prune dangling blocks during restart");
+                initial_data.retain_blocks(self.max_recovery_blocks);
+                let blocks_to_prune = initial_data.take_blocks_to_prune();
+                if !blocks_to_prune.is_empty() {
+                    self.db
+                        .delete_blocks_and_quorum_certificates(blocks_to_prune)
+                        .expect("unable to prune dangling blocks during restart");
+                    // only the data needed for recovery is left, so reclaim the rest now
+                    if let Err(e) = self.db.compact() {
+                        warn!(error = ?e, "Failed to compact ConsensusDB during restart");
+                    }
+                }
                 if initial_data.last_vote.is_none() {
                     self.db
                         .delete_last_vote_msg()
@@ -439,3 +546,116 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         self.aptos_db.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_consensus_types::block::block_test_utils::{
+        certificate_for_genesis, placeholder_certificate_for_block,
+    };
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, ledger_info::LedgerInfo,
+        validator_signer::ValidatorSigner,
+    };
+
+    /// Recovery data of nil blocks on top of genesis, each certified. The block of each round
+    /// in `rounds` extends the block of the round `parent_rounds` maps it to, or genesis.
+    fn recovery_data_for_tree(
+        rounds: &[(Round, Option<Round>)],
+    ) -> (RecoveryData, HashMap<Round, Block>) {
+        let signer = ValidatorSigner::random(None);
+        let mut blocks = HashMap::new();
+        let mut quorum_certs: HashMap<Round, QuorumCert> = HashMap::new();
+        for (round, parent_round) in rounds {
+            let parent_qc = match parent_round {
+                Some(parent_round) => quorum_certs[parent_round].clone(),
+                None => certificate_for_genesis(),
+            };
+            let block = Block::new_nil(*round, parent_qc.clone(), vec![]);
+            quorum_certs.insert(
+                *round,
+                placeholder_certificate_for_block(
+                    &[signer.clone()],
+                    block.id(),
+                    *round,
+                    parent_qc.certified_block().id(),
+                    parent_qc.certified_block().round(),
+                ),
+            );
+            blocks.insert(*round, block);
+        }
+
+        let recovery_data = RecoveryData::new(
+            None,
+            LedgerRecoveryData::new(LedgerInfoWithSignatures::new(
+                LedgerInfo::mock_genesis(None),
+                AggregateSignature::empty(),
+            )),
+            blocks.values().cloned().collect(),
+            RootMetadata::new_empty(),
+            quorum_certs.into_values().collect(),
+            None,
+        )
+        .unwrap();
+        (recovery_data, blocks)
+    }
+
+    #[test]
+    fn test_retain_blocks() {
+        // 1 <- 2 (fork)
+        //   <- 3 <- 4 <- 5
+        // 6 (fork of genesis)
+        let (mut recovery_data, blocks) = recovery_data_for_tree(&[
+            (1, None),
+            (2, Some(1)),
+            (3, Some(1)),
+            (4, Some(3)),
+            (5, Some(4)),
+            (6, None),
+        ]);
+        recovery_data.retain_blocks(2);
+
+        let blocks_to_prune: HashSet<_> =
+            recovery_data.take_blocks_to_prune().into_iter().collect();
+        let (_, _, retained_blocks, quorum_certs) = recovery_data.take();
+        // the newest blocks are retained along with their ancestors
+        assert_eq!(
+            retained_blocks,
+            [1, 3, 4, 5, 6]
+                .iter()
+                .map(|round| blocks[round].clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(blocks_to_prune, HashSet::from([blocks[&2].id()]));
+        // only the quorum certs of the root and the retained blocks are left
+        assert_eq!(quorum_certs.len(), 6);
+        assert!(quorum_certs
+            .iter()
+            .all(|qc| !blocks_to_prune.contains(&qc.certified_block().id())));
+    }
+
+    #[test]
+    fn test_retain_blocks_keeps_ancestors() {
+        let (mut recovery_data, blocks) =
+            recovery_data_for_tree(&[(1, None), (2, Some(1)), (3, Some(2)), (4, Some(3))]);
+        recovery_data.retain_blocks(1);
+
+        // the ancestors of the newest block can't be pruned
+        assert!(recovery_data.take_blocks_to_prune().is_empty());
+        let (_, _, retained_blocks, quorum_certs) = recovery_data.take();
+        assert_eq!(retained_blocks.len(), blocks.len());
+        assert_eq!(quorum_certs.len(), 5);
+    }
+
+    #[test]
+    fn test_retain_blocks_within_limit() {
+        let (mut recovery_data, blocks) =
+            recovery_data_for_tree(&[(1, None), (2, Some(1)), (3, None)]);
+        recovery_data.retain_blocks(3);
+
+        assert!(recovery_data.take_blocks_to_prune().is_empty());
+        let (_, _, retained_blocks, quorum_certs) = recovery_data.take();
+        assert_eq!(retained_blocks.len(), blocks.len());
+        assert_eq!(quorum_certs.len(), 4);
+    }
+}
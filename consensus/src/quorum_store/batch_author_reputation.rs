@@ -0,0 +1,139 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::counters;
+use aptos_config::config::{BatchQuotaBackoffValues, QuorumStoreBatchReputationConfig};
+use aptos_logger::{info, warn};
+use aptos_types::PeerId;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// The quality of a single batch, as observed when its transactions were processed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchQuality {
+    pub num_txns: u64,
+    // Transactions that had already expired
+    pub num_expired_txns: u64,
+    // Transactions that were already included in another batch
+    pub num_duplicate_txns: u64,
+    // Transactions that failed verification, e.g. with a bad signature
+    pub num_invalid_txns: u64,
+}
+
+impl BatchQuality {
+    pub fn num_bad_txns(&self) -> u64 {
+        self.num_expired_txns + self.num_duplicate_txns + self.num_invalid_txns
+    }
+}
+
+/// Tracks the quality of the recent batches of each author, and reduces the batch quota of
+/// authors that relay a high ratio of expired, duplicate or invalid transactions. This keeps
+/// validators relaying junk from taking up quorum store capacity and block space.
+pub struct BatchAuthorReputation {
+    window_num_batches: usize,
+    min_num_batches: usize,
+    batch_quota_txns: u64,
+    backoffs: BTreeMap<usize, BatchQuotaBackoffValues>,
+    recent_batches: HashMap<PeerId, VecDeque<BatchQuality>>,
+}
+
+impl BatchAuthorReputation {
+    pub fn new(config: &QuorumStoreBatchReputationConfig) -> Self {
+        let original_len = config.quota_backoff.len();
+        let backoffs = config
+            .quota_backoff
+            .iter()
+            .map(|v| (v.backoff_if_above_bad_txn_percentage, v.clone()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(original_len, backoffs.len());
+        Self {
+            window_num_batches: config.window_num_batches,
+            min_num_batches: config.min_num_batches,
+            batch_quota_txns: config.batch_quota_txns,
+            backoffs,
+            recent_batches: HashMap::new(),
+        }
+    }
+
+    /// Records the quality of a batch sent by `author`
+    pub fn record_batch(&mut self, author: PeerId, quality: BatchQuality) {
+        counters::BATCH_AUTHOR_TXNS
+            .with_label_values(&["total"])
+            .inc_by(quality.num_txns);
+        counters::BATCH_AUTHOR_TXNS
+            .with_label_values(&["expired"])
+            .inc_by(quality.num_expired_txns);
+        counters::BATCH_AUTHOR_TXNS
+            .with_label_values(&["duplicate"])
+            .inc_by(quality.num_duplicate_txns);
+        counters::BATCH_AUTHOR_TXNS
+            .with_label_values(&["invalid"])
+            .inc_by(quality.num_invalid_txns);
+
+        let quota_before = self.batch_quota(&author);
+        let recent_batches = self.recent_batches.entry(author).or_default();
+        recent_batches.push_back(quality);
+        while recent_batches.len() > self.window_num_batches {
+            recent_batches.pop_front();
+        }
+
+        let quota_after = self.batch_quota(&author);
+        if quota_after < quota_before {
+            warn!(
+                "QS: reducing batch quota of {} from {} to {} txns, bad txn ratio {:?}",
+                author,
+                quota_before,
+                quota_after,
+                self.bad_txn_ratio(&author)
+            );
+        } else if quota_after > quota_before {
+            info!(
+                "QS: restoring batch quota of {} from {} to {} txns",
+                author, quota_before, quota_after
+            );
+        }
+        counters::NUM_AUTHORS_WITH_REDUCED_BATCH_QUOTA.set(self.num_authors_with_reduced_quota());
+    }
+
+    /// Returns the ratio of bad transactions in the recent batches of `author`, or None if
+    /// too few of its batches have been seen to judge it.
+    pub fn bad_txn_ratio(&self, author: &PeerId) -> Option<f64> {
+        let recent_batches = self.recent_batches.get(author)?;
+        if recent_batches.len() < self.min_num_batches {
+            return None;
+        }
+        let (num_txns, num_bad_txns) =
+            recent_batches
+                .iter()
+                .fold((0, 0), |(num_txns, num_bad_txns), quality| {
+                    (
+                        num_txns + quality.num_txns,
+                        num_bad_txns + quality.num_bad_txns(),
+                    )
+                });
+        if num_txns == 0 {
+            return None;
+        }
+        Some(num_bad_txns as f64 / num_txns as f64)
+    }
+
+    /// Returns the maximum number of transactions of `author` the quorum store should hold
+    pub fn batch_quota(&self, author: &PeerId) -> u64 {
+        let bad_txn_percentage = match self.bad_txn_ratio(author) {
+            Some(ratio) => (ratio * 100.0).floor() as usize,
+            None => return self.batch_quota_txns,
+        };
+        match self.backoffs.range(..bad_txn_percentage).next_back() {
+            Some((_, backoff)) => {
+                self.batch_quota_txns * backoff.quota_percentage.min(100) as u64 / 100
+            }
+            None => self.batch_quota_txns,
+        }
+    }
+
+    fn num_authors_with_reduced_quota(&self) -> i64 {
+        self.recent_batches
+            .keys()
+            .filter(|author| self.batch_quota(author) < self.batch_quota_txns)
+            .count() as i64
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    batch_author_reputation::{BatchAuthorReputation, BatchQuality},
+    counters,
+    types::{BatchId, Fragment, SerializedTransaction},
+};
+use aptos_config::config::QuorumStoreBatchReputationConfig;
+use aptos_consensus_types::proof_of_store::LogicalTime;
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::debug;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction, PeerId};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A batch whose fragments are being received. Fragments are sent in order, and the last one
+/// carries the expiration of the batch.
+struct IncompleteBatch {
+    batch_id: BatchId,
+    next_fragment_id: usize,
+    txns: Vec<SerializedTransaction>,
+}
+
+/// Receives the batches that other validators broadcast as fragments, judges the quality of
+/// their transactions, and holds the transactions of each author until its batches expire, up
+/// to the batch quota `BatchAuthorReputation` gives the author. Batches that would take an author
+/// over its quota are dropped.
+pub struct BatchCoordinator {
+    epoch: u64,
+    reputation: BatchAuthorReputation,
+    certified_time: LogicalTime,
+    incomplete_batches: HashMap<PeerId, IncompleteBatch>,
+    // Held batches, ordered by expiration so that expired ones can be released in one go
+    held_batches: BTreeMap<(LogicalTime, PeerId, BatchId), Vec<SignedTransaction>>,
+    num_held_txns: HashMap<PeerId, u64>,
+    // Sender and sequence number of the held transactions, to find duplicates
+    held_txns: HashSet<(AccountAddress, u64)>,
+}
+
+impl BatchCoordinator {
+    pub fn new(epoch: u64, config: &QuorumStoreBatchReputationConfig) -> Self {
+        Self {
+            epoch,
+            reputation: BatchAuthorReputation::new(config),
+            certified_time: LogicalTime::new(epoch, 0),
+            incomplete_batches: HashMap::new(),
+            held_batches: BTreeMap::new(),
+            num_held_txns: HashMap::new(),
+            held_txns: HashSet::new(),
+        }
+    }
+
+    /// Adds a verified fragment to the batch of its author, and handles the batch once its last
+    /// fragment is received
+    pub fn handle_fragment(&mut self, fragment: Fragment) {
+        if fragment.epoch() != self.epoch {
+            return;
+        }
+        let author = fragment.source();
+        let batch_id = fragment.batch_id();
+        let fragment_id = fragment.fragment_id();
+        let maybe_expiration = fragment.maybe_expiration();
+
+        if fragment_id == 0 {
+            if self.incomplete_batches.remove(&author).is_some() {
+                counters::RECEIVED_BATCHES
+                    .with_label_values(&[counters::INCOMPLETE_LABEL])
+                    .inc();
+            }
+            self.incomplete_batches.insert(
+                author,
+                IncompleteBatch {
+                    batch_id,
+                    next_fragment_id: 0,
+                    txns: Vec::new(),
+                },
+            );
+        }
+        let batch = match self.incomplete_batches.get_mut(&author) {
+            Some(batch) if batch.batch_id == batch_id && batch.next_fragment_id == fragment_id => {
+                batch
+            }
+            _ => {
+                debug!(
+                    "QS: dropping out of order fragment {} of batch {} from {}",
+                    fragment_id, batch_id, author
+                );
+                if self.incomplete_batches.remove(&author).is_some() {
+                    counters::RECEIVED_BATCHES
+                        .with_label_values(&[counters::INCOMPLETE_LABEL])
+                        .inc();
+                }
+                return;
+            }
+        };
+        batch.next_fragment_id += 1;
+        batch.txns.extend(fragment.into_transactions());
+
+        if let Some(expiration) = maybe_expiration {
+            if let Some(batch) = self.incomplete_batches.remove(&author) {
+                self.handle_batch(author, batch.batch_id, expiration, batch.txns);
+            }
+        }
+    }
+
+    fn handle_batch(
+        &mut self,
+        author: PeerId,
+        batch_id: BatchId,
+        expiration: LogicalTime,
+        serialized_txns: Vec<SerializedTransaction>,
+    ) {
+        if expiration < self.certified_time {
+            counters::RECEIVED_BATCHES
+                .with_label_values(&[counters::EXPIRED_LABEL])
+                .inc();
+            return;
+        }
+
+        let now_secs = duration_since_epoch().as_secs();
+        let mut quality = BatchQuality {
+            num_txns: serialized_txns.len() as u64,
+            ..BatchQuality::default()
+        };
+        let mut batch_txn_keys = HashSet::new();
+        let mut txns = Vec::new();
+        for serialized_txn in serialized_txns {
+            let txn = match bcs::from_bytes::<SignedTransaction>(serialized_txn.bytes()) {
+                Ok(txn) if txn.clone().check_signature().is_ok() => txn,
+                _ => {
+                    quality.num_invalid_txns += 1;
+                    continue;
+                }
+            };
+            if txn.expiration_timestamp_secs() <= now_secs {
+                quality.num_expired_txns += 1;
+                continue;
+            }
+            let key = (txn.sender(), txn.sequence_number());
+            if self.held_txns.contains(&key) || !batch_txn_keys.insert(key) {
+                quality.num_duplicate_txns += 1;
+                continue;
+            }
+            txns.push(txn);
+        }
+        self.reputation.record_batch(author, quality);
+
+        let num_held_txns = self.num_held_txns.get(&author).copied().unwrap_or(0);
+        if num_held_txns + txns.len() as u64 > self.reputation.batch_quota(&author) {
+            debug!(
+                "QS: dropping batch {} from {}, over its batch quota of {} txns",
+                batch_id,
+                author,
+                self.reputation.batch_quota(&author)
+            );
+            counters::RECEIVED_BATCHES
+                .with_label_values(&[counters::OVER_QUOTA_LABEL])
+                .inc();
+            return;
+        }
+
+        counters::RECEIVED_BATCHES
+            .with_label_values(&[counters::HELD_LABEL])
+            .inc();
+        *self.num_held_txns.entry(author).or_default() += txns.len() as u64;
+        self.held_txns.extend(batch_txn_keys);
+        self.held_batches
+            .insert((expiration, author, batch_id), txns);
+    }
+
+    /// Releases the batches that expired before `certified_time`, freeing the quota of their
+    /// authors
+    pub fn update_certified_time(&mut self, certified_time: LogicalTime) {
+        if certified_time <= self.certified_time {
+            return;
+        }
+        self.certified_time = certified_time;
+
+        // Batches that haven't expired have keys from this one on
+        let unexpired_batches = self
+            .held_batches
+            .split_off(&(certified_time, PeerId::ZERO, 0));
+        let expired_batches = std::mem::replace(&mut self.held_batches, unexpired_batches);
+        for ((_, author, _), txns) in expired_batches {
+            if let Some(num_held_txns) = self.num_held_txns.get_mut(&author) {
+                *num_held_txns = num_held_txns.saturating_sub(txns.len() as u64);
+            }
+            for txn in txns {
+                self.held_txns
+                    .remove(&(txn.sender(), txn.sequence_number()));
+            }
+        }
+        self.num_held_txns
+            .retain(|_, num_held_txns| *num_held_txns > 0);
+    }
+
+    /// Returns the number of transactions of `author` that are held
+    #[cfg(test)]
+    pub fn num_held_txns(&self, author: &PeerId) -> u64 {
+        self.num_held_txns.get(author).copied().unwrap_or(0)
+    }
+}
@@ -1,7 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 use aptos_metrics_core::{
-    op_counters::DurationHistogram, register_histogram, register_histogram_vec, HistogramVec,
+    op_counters::DurationHistogram, register_histogram, register_histogram_vec,
+    register_int_counter_vec, register_int_gauge, HistogramVec, IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 use std::time::Duration;
@@ -15,6 +16,11 @@ pub const REQUEST_SUCCESS_LABEL: &str = "success";
 pub const CALLBACK_FAIL_LABEL: &str = "callback_fail";
 pub const CALLBACK_SUCCESS_LABEL: &str = "callback_success";
 
+pub const HELD_LABEL: &str = "held";
+pub const OVER_QUOTA_LABEL: &str = "over_quota";
+pub const EXPIRED_LABEL: &str = "expired";
+pub const INCOMPLETE_LABEL: &str = "incomplete";
+
 /// Counter for tracking latency of quorum store processing requests from consensus
 /// A 'fail' result means the quorum store's callback response to consensus failed.
 static QUORUM_STORE_SERVICE_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
@@ -42,3 +48,32 @@ pub static MAIN_LOOP: Lazy<DurationHistogram> = Lazy::new(|| {
         .unwrap(),
     )
 });
+
+/// Count of the transactions in batches received from other validators, by quality.
+pub static BATCH_AUTHOR_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_batch_author_txns",
+        "Count of the transactions in received batches, by type (total, expired, duplicate, invalid)",
+        &["type"]
+    )
+    .unwrap()
+});
+
+/// Number of batch authors whose batch quota is reduced because of low-quality batches.
+pub static NUM_AUTHORS_WITH_REDUCED_BATCH_QUOTA: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_num_authors_with_reduced_batch_quota",
+        "Number of batch authors whose batch quota is reduced because of low-quality batches"
+    )
+    .unwrap()
+});
+
+/// Count of the batches received from other validators, by what happened to them.
+pub static RECEIVED_BATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "quorum_store_received_batches",
+        "Count of the received batches, by result (held, over_quota, expired, incomplete)",
+        &["result"]
+    )
+    .unwrap()
+});
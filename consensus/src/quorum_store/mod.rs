@@ -4,6 +4,8 @@
 /// Equivalent to directly fetching blocks from mempool without a quorum store.
 pub mod direct_mempool_quorum_store;
 
+pub(crate) mod batch_author_reputation;
+pub(crate) mod batch_coordinator;
 pub(crate) mod batch_reader;
 // TODO: remove allow(dead_code) when quorum store implementation is added
 #[allow(dead_code)]
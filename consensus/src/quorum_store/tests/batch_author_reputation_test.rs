@@ -0,0 +1,75 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::batch_author_reputation::{BatchAuthorReputation, BatchQuality};
+use aptos_config::config::QuorumStoreBatchReputationConfig;
+use aptos_types::PeerId;
+
+fn batch_with_bad_txns(num_bad_txns: u64) -> BatchQuality {
+    BatchQuality {
+        num_txns: 100,
+        num_expired_txns: num_bad_txns / 2,
+        num_duplicate_txns: num_bad_txns - num_bad_txns / 2,
+        num_invalid_txns: 0,
+    }
+}
+
+#[test]
+fn test_new_author_keeps_full_quota() {
+    let config = QuorumStoreBatchReputationConfig::default();
+    let mut reputation = BatchAuthorReputation::new(&config);
+    let author = PeerId::random();
+    assert_eq!(reputation.batch_quota(&author), config.batch_quota_txns);
+
+    // Too few batches to judge the author, even if they are all bad
+    for _ in 0..config.min_num_batches - 1 {
+        reputation.record_batch(author, batch_with_bad_txns(100));
+    }
+    assert_eq!(reputation.bad_txn_ratio(&author), None);
+    assert_eq!(reputation.batch_quota(&author), config.batch_quota_txns);
+}
+
+#[test]
+fn test_quota_backoff() {
+    let config = QuorumStoreBatchReputationConfig::default();
+    let base_quota = config.batch_quota_txns;
+    let mut reputation = BatchAuthorReputation::new(&config);
+    let good_author = PeerId::random();
+    let bad_author = PeerId::random();
+
+    for _ in 0..config.window_num_batches {
+        reputation.record_batch(good_author, batch_with_bad_txns(5));
+        reputation.record_batch(bad_author, batch_with_bad_txns(30));
+    }
+    assert_eq!(reputation.batch_quota(&good_author), base_quota);
+    assert_eq!(reputation.bad_txn_ratio(&bad_author), Some(0.3));
+    assert_eq!(reputation.batch_quota(&bad_author), base_quota * 20 / 100);
+
+    // Only relaying junk gets the author the smallest quota
+    for _ in 0..config.window_num_batches {
+        reputation.record_batch(bad_author, batch_with_bad_txns(100));
+    }
+    assert_eq!(reputation.batch_quota(&bad_author), base_quota * 5 / 100);
+}
+
+#[test]
+fn test_quota_recovers_with_good_batches() {
+    let config = QuorumStoreBatchReputationConfig::default();
+    let mut reputation = BatchAuthorReputation::new(&config);
+    let author = PeerId::random();
+
+    for _ in 0..config.window_num_batches {
+        reputation.record_batch(author, batch_with_bad_txns(15));
+    }
+    assert_eq!(
+        reputation.batch_quota(&author),
+        config.batch_quota_txns * 50 / 100
+    );
+
+    // Once the bad batches fall out of the window, the full quota is restored
+    for _ in 0..config.window_num_batches {
+        reputation.record_batch(author, batch_with_bad_txns(0));
+    }
+    assert_eq!(reputation.bad_txn_ratio(&author), Some(0.0));
+    assert_eq!(reputation.batch_quota(&author), config.batch_quota_txns);
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::quorum_store::{
+    batch_coordinator::BatchCoordinator,
+    types::{Fragment, SerializedTransaction},
+};
+use aptos_config::config::QuorumStoreBatchReputationConfig;
+use aptos_consensus_types::proof_of_store::LogicalTime;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use aptos_infallible::duration_since_epoch;
+use aptos_types::{
+    account_address::AccountAddress, test_helpers::transaction_test_helpers, PeerId,
+};
+
+const EPOCH: u64 = 1;
+
+fn config() -> QuorumStoreBatchReputationConfig {
+    QuorumStoreBatchReputationConfig {
+        window_num_batches: 10,
+        min_num_batches: 2,
+        batch_quota_txns: 10,
+        ..QuorumStoreBatchReputationConfig::default()
+    }
+}
+
+fn txn(sender: AccountAddress, sequence_number: u64, expired: bool) -> SerializedTransaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let now_secs = duration_since_epoch().as_secs();
+    let txn = transaction_test_helpers::get_test_signed_transaction(
+        sender,
+        sequence_number,
+        &private_key,
+        private_key.public_key(),
+        None,
+        if expired {
+            now_secs - 10
+        } else {
+            now_secs + 100
+        },
+        100,
+        None,
+    );
+    SerializedTransaction::from_signed_txn(&txn)
+}
+
+fn good_txns(
+    sender: AccountAddress,
+    sequence_numbers: std::ops::Range<u64>,
+) -> Vec<SerializedTransaction> {
+    sequence_numbers
+        .map(|sequence_number| txn(sender, sequence_number, false))
+        .collect()
+}
+
+/// Sends the transactions as a batch of two fragments, expiring at `expiration_round`
+fn send_batch(
+    batch_coordinator: &mut BatchCoordinator,
+    author: PeerId,
+    batch_id: u64,
+    mut txns: Vec<SerializedTransaction>,
+    expiration_round: u64,
+) {
+    let last_fragment_txns = txns.split_off(txns.len() / 2);
+    batch_coordinator.handle_fragment(Fragment::new(EPOCH, batch_id, 0, txns, None, author));
+    batch_coordinator.handle_fragment(Fragment::new(
+        EPOCH,
+        batch_id,
+        1,
+        last_fragment_txns,
+        Some(LogicalTime::new(EPOCH, expiration_round)),
+        author,
+    ));
+}
+
+#[test]
+fn test_holds_batches_within_quota() {
+    let mut batch_coordinator = BatchCoordinator::new(EPOCH, &config());
+    let author = PeerId::random();
+    let sender = AccountAddress::random();
+
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        0,
+        good_txns(sender, 0..4),
+        10,
+    );
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        1,
+        good_txns(sender, 4..8),
+        20,
+    );
+    assert_eq!(batch_coordinator.num_held_txns(&author), 8);
+
+    // The author would hold more transactions than its quota
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        2,
+        good_txns(sender, 8..12),
+        20,
+    );
+    assert_eq!(batch_coordinator.num_held_txns(&author), 8);
+
+    // Expired batches free the quota of the author
+    batch_coordinator.update_certified_time(LogicalTime::new(EPOCH, 11));
+    assert_eq!(batch_coordinator.num_held_txns(&author), 4);
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        3,
+        good_txns(sender, 8..12),
+        20,
+    );
+    assert_eq!(batch_coordinator.num_held_txns(&author), 8);
+
+    // Batches that already expired aren't held
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        4,
+        good_txns(sender, 12..14),
+        5,
+    );
+    assert_eq!(batch_coordinator.num_held_txns(&author), 8);
+}
+
+#[test]
+fn test_low_quality_author_gets_smaller_quota() {
+    let mut batch_coordinator = BatchCoordinator::new(EPOCH, &config());
+    let good_author = PeerId::random();
+    let bad_author = PeerId::random();
+    let sender = AccountAddress::random();
+
+    for batch_id in 0..2 {
+        let sequence_number = batch_id * 2;
+        send_batch(
+            &mut batch_coordinator,
+            good_author,
+            batch_id,
+            good_txns(sender, 100 + sequence_number..102 + sequence_number),
+            10,
+        );
+
+        // Half of the transactions of the bad author are expired or invalid
+        let mut txns = good_txns(sender, sequence_number..sequence_number + 2);
+        txns.push(txn(sender, 1000 + sequence_number, true));
+        txns.push(SerializedTransaction {
+            bytes: vec![1, 2, 3],
+        });
+        send_batch(&mut batch_coordinator, bad_author, batch_id, txns, 10);
+    }
+    assert_eq!(batch_coordinator.num_held_txns(&good_author), 4);
+    // The second batch takes the bad author over its reduced quota of 2 transactions
+    assert_eq!(batch_coordinator.num_held_txns(&bad_author), 2);
+}
+
+#[test]
+fn test_duplicate_and_incomplete_batches() {
+    let mut batch_coordinator = BatchCoordinator::new(EPOCH, &config());
+    let author = PeerId::random();
+    let other_author = PeerId::random();
+    let sender = AccountAddress::random();
+
+    send_batch(
+        &mut batch_coordinator,
+        author,
+        0,
+        good_txns(sender, 0..4),
+        10,
+    );
+    // Only the transactions that aren't held yet are kept
+    send_batch(
+        &mut batch_coordinator,
+        other_author,
+        0,
+        good_txns(sender, 2..6),
+        10,
+    );
+    assert_eq!(batch_coordinator.num_held_txns(&other_author), 2);
+
+    // A batch missing its first fragment is dropped
+    batch_coordinator.handle_fragment(Fragment::new(
+        EPOCH,
+        1,
+        1,
+        good_txns(sender, 6..8),
+        Some(LogicalTime::new(EPOCH, 10)),
+        author,
+    ));
+    assert_eq!(batch_coordinator.num_held_txns(&author), 4);
+}
@@ -1,5 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(test)]
+mod batch_author_reputation_test;
+#[cfg(test)]
+mod batch_coordinator_test;
 #[cfg(test)]
 mod direct_mempool_quorum_store_test;
@@ -149,6 +149,10 @@ impl Fragment {
     pub fn batch_id(&self) -> BatchId {
         self.fragment_info.batch_id()
     }
+
+    pub fn maybe_expiration(&self) -> Option<LogicalTime> {
+        self.fragment_info.maybe_expiration()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -22,6 +22,9 @@ use crate::{
     network_interface::ConsensusMsg,
     pending_votes::VoteReceptionResult,
     persistent_liveness_storage::PersistentLivenessStorage,
+    round_postmortem::{
+        self, RoundEnd, RoundPostmortemTracker, TimeoutCertificateSummary, VoteKind, VoteSummary,
+    },
 };
 use anyhow::{bail, ensure, Context, Result};
 use aptos_channels::aptos_channel;
@@ -201,6 +204,7 @@ pub struct RoundManager {
     round_manager_tx:
         aptos_channel::Sender<(Author, Discriminant<VerifiedEvent>), (Author, VerifiedEvent)>,
     local_config: ConsensusConfig,
+    round_postmortem: RoundPostmortemTracker,
 }
 
 impl RoundManager {
@@ -228,6 +232,7 @@ impl RoundManager {
         counters::OP_COUNTERS
             .gauge("decoupled_execution")
             .set(onchain_config.decoupled_execution() as i64);
+        round_postmortem::set_max_round_postmortems(local_config.max_round_postmortems);
         Self {
             epoch_state,
             block_store,
@@ -240,6 +245,7 @@ impl RoundManager {
             onchain_config,
             round_manager_tx,
             local_config,
+            round_postmortem: RoundPostmortemTracker::default(),
         }
     }
 
@@ -289,6 +295,7 @@ impl RoundManager {
             self.new_log(LogEvent::NewRound),
             reason = new_round_event.reason
         );
+        self.record_round_postmortem(&new_round_event);
 
         if self
             .proposer_election
@@ -311,6 +318,74 @@ impl RoundManager {
         Ok(())
     }
 
+    /// Stores the postmortem record of the round that just ended, and starts the one of the new
+    /// round.
+    fn record_round_postmortem(&mut self, new_round_event: &NewRoundEvent) {
+        let previous_round = new_round_event.round.saturating_sub(1);
+        let proposer = self
+            .proposer_election
+            .get_valid_proposer(new_round_event.round);
+        let votes_received = (proposer == self.proposal_generator.author()).then(|| {
+            self.vote_summary(
+                new_round_event
+                    .prev_round_votes
+                    .iter()
+                    .flat_map(|(_, li_with_sig)| li_with_sig.signatures().keys()),
+            )
+        });
+        let timeout_votes_received = new_round_event
+            .prev_round_timeout_votes
+            .as_ref()
+            .map(|timeout_votes| self.vote_summary(timeout_votes.signers()))
+            .unwrap_or_default();
+        let timeout_certificate = self
+            .block_store
+            .highest_2chain_timeout_cert()
+            .filter(|tc| tc.round() == previous_round)
+            .map(|tc| {
+                let validators = self
+                    .epoch_state
+                    .verifier
+                    .get_ordered_account_addresses_iter()
+                    .collect::<Vec<_>>();
+                let signers = tc
+                    .signatures_with_rounds()
+                    .get_voters_and_rounds(&validators)
+                    .into_iter()
+                    .map(|(author, _)| author)
+                    .collect::<Vec<_>>();
+                TimeoutCertificateSummary {
+                    signers: self.vote_summary(signers.iter()),
+                    highest_hqc_round: tc.highest_hqc_round(),
+                }
+            });
+
+        self.round_postmortem.new_round(
+            self.epoch_state.epoch,
+            new_round_event.round,
+            proposer,
+            RoundEnd {
+                certified: matches!(new_round_event.reason, NewRoundReason::QCReady),
+                votes_received,
+                timeout_votes_received,
+                timeout_certificate,
+            },
+        );
+    }
+
+    fn vote_summary<'a>(&self, authors: impl Iterator<Item = &'a Author>) -> VoteSummary {
+        let authors: Vec<_> = authors.cloned().collect();
+        let voting_power = authors
+            .iter()
+            .filter_map(|author| self.epoch_state.verifier.get_voting_power(author))
+            .map(|voting_power| voting_power as u128)
+            .sum();
+        VoteSummary {
+            authors,
+            voting_power,
+        }
+    }
+
     fn log_collected_vote_stats(&self, new_round_event: &NewRoundEvent) {
         let prev_round_votes_for_li = new_round_event
             .prev_round_votes
@@ -560,6 +635,7 @@ impl RoundManager {
         if !self.round_state.process_local_timeout(round) {
             return Ok(());
         }
+        self.round_postmortem.local_timeout(round);
 
         if self.sync_only() {
             self.network
@@ -583,6 +659,7 @@ impl RoundManager {
                 );
                 counters::VOTE_NIL_COUNT.inc();
                 let nil_vote = self.execute_and_vote(nil_block).await?;
+                self.round_postmortem.voted(round, VoteKind::Nil);
                 (true, nil_vote)
             }
         };
@@ -632,6 +709,8 @@ impl RoundManager {
         let author = proposal
             .author()
             .expect("Proposal should be verified having an author");
+        self.round_postmortem
+            .proposal_received(proposal.round(), proposal.id());
 
         let payload_len = proposal.payload().map_or(0, |payload| payload.len());
         let payload_size = proposal.payload().map_or(0, |payload| payload.size());
@@ -729,6 +808,8 @@ impl RoundManager {
             .execute_and_vote(proposal)
             .await
             .context("[RoundManager] Process proposal")?;
+        self.round_postmortem
+            .voted(proposal_round, VoteKind::Proposal);
 
         let recipient = self
             .proposer_election
@@ -0,0 +1,380 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compact records of what this node saw in each consensus round: who proposed, which votes
+//! and timeouts arrived and why the round timed out. The most recent rounds are kept in memory
+//! and exposed by the inspection service, so that missed rounds can be analyzed after the fact
+//! without running with debug logging.
+
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{collections::VecDeque, time::Instant};
+
+// Used until the consensus config has been applied
+const DEFAULT_MAX_ROUND_POSTMORTEMS: usize = 1000;
+
+static ROUND_POSTMORTEMS: Lazy<Mutex<RoundPostmortemBuffer>> =
+    Lazy::new(|| Mutex::new(RoundPostmortemBuffer::new(DEFAULT_MAX_ROUND_POSTMORTEMS)));
+
+/// Returns the records of the most recent rounds, oldest first.
+pub fn recent_round_postmortems() -> Vec<RoundPostmortem> {
+    ROUND_POSTMORTEMS.lock().records.iter().cloned().collect()
+}
+
+pub(crate) fn set_max_round_postmortems(max_records: usize) {
+    ROUND_POSTMORTEMS.lock().set_max_records(max_records);
+}
+
+fn record_round_postmortem(postmortem: RoundPostmortem) {
+    ROUND_POSTMORTEMS.lock().push(postmortem);
+}
+
+struct RoundPostmortemBuffer {
+    max_records: usize,
+    records: VecDeque<RoundPostmortem>,
+}
+
+impl RoundPostmortemBuffer {
+    fn new(max_records: usize) -> Self {
+        Self {
+            max_records,
+            records: VecDeque::new(),
+        }
+    }
+
+    fn set_max_records(&mut self, max_records: usize) {
+        self.max_records = max_records;
+        self.truncate();
+    }
+
+    fn push(&mut self, postmortem: RoundPostmortem) {
+        self.records.push_back(postmortem);
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        while self.records.len() > self.max_records {
+            self.records.pop_front();
+        }
+    }
+}
+
+/// How a round ended, as seen by this node
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundOutcome {
+    /// The next round started with a quorum certificate for this round
+    Certified,
+    /// The next round started with a timeout certificate for this round
+    TimedOut,
+    /// This node moved past the round by syncing to a later one
+    Skipped,
+}
+
+/// Why the round timer of this node expired
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundTimeoutReason {
+    /// No proposal for the round was received
+    NoProposal,
+    /// A proposal was received, but this node didn't vote for it, e.g. because it was invalid,
+    /// failed to execute or was held back by back pressure
+    ProposalNotVoted,
+    /// This node voted for the proposal, but no quorum certificate followed in time
+    NoQuorumCertificate,
+}
+
+/// What this node voted for in a round
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteKind {
+    /// A vote for the proposal of the round
+    Proposal,
+    /// A vote for a NIL block, sent when the round timed out before voting for a proposal
+    Nil,
+}
+
+/// Votes or timeouts received for a round, with their combined voting power
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct VoteSummary {
+    /// The validators the votes came from
+    pub authors: Vec<Author>,
+    /// The combined voting power of `authors`
+    pub voting_power: u128,
+}
+
+/// The timeout certificate that ended a round
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TimeoutCertificateSummary {
+    /// The validators that signed the certificate, with their combined voting power
+    pub signers: VoteSummary,
+    /// The highest quorum certificate round known to the signers
+    pub highest_hqc_round: Round,
+}
+
+/// What this node saw in a single round
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RoundPostmortem {
+    /// The epoch of the round
+    pub epoch: u64,
+    /// The round number
+    pub round: Round,
+    /// The validator elected to propose in the round
+    pub proposer: Author,
+    /// The id of the proposal received for the round, if any
+    pub proposal_id: Option<HashValue>,
+    /// What this node voted for, if it voted
+    pub vote: Option<VoteKind>,
+    /// How the round ended
+    pub outcome: RoundOutcome,
+    /// Why the round timer of this node expired, if it did
+    pub local_timeout_reason: Option<RoundTimeoutReason>,
+    /// The votes for the round. Votes are only sent to the proposer of the next round, so this
+    /// is only set on that proposer.
+    pub votes_received: Option<VoteSummary>,
+    /// The timeout votes for the round
+    pub timeout_votes_received: VoteSummary,
+    /// The timeout certificate for the round, if the round timed out
+    pub timeout_certificate: Option<TimeoutCertificateSummary>,
+    /// How long this node spent in the round
+    pub duration_ms: u64,
+}
+
+/// How the previous round ended, known when the next round starts
+pub(crate) struct RoundEnd {
+    pub certified: bool,
+    pub votes_received: Option<VoteSummary>,
+    pub timeout_votes_received: VoteSummary,
+    pub timeout_certificate: Option<TimeoutCertificateSummary>,
+}
+
+struct RoundInProgress {
+    epoch: u64,
+    round: Round,
+    proposer: Author,
+    proposal_id: Option<HashValue>,
+    vote: Option<VoteKind>,
+    local_timeout_reason: Option<RoundTimeoutReason>,
+    started_at: Instant,
+}
+
+/// Builds the record of the current round from the events seen by the round manager, and
+/// stores it once the next round starts.
+#[derive(Default)]
+pub(crate) struct RoundPostmortemTracker {
+    current: Option<RoundInProgress>,
+}
+
+impl RoundPostmortemTracker {
+    fn current_round(&mut self, round: Round) -> Option<&mut RoundInProgress> {
+        self.current
+            .as_mut()
+            .filter(|current| current.round == round)
+    }
+
+    pub fn proposal_received(&mut self, round: Round, proposal_id: HashValue) {
+        if let Some(current) = self.current_round(round) {
+            current.proposal_id.get_or_insert(proposal_id);
+        }
+    }
+
+    pub fn voted(&mut self, round: Round, vote: VoteKind) {
+        if let Some(current) = self.current_round(round) {
+            current.vote.get_or_insert(vote);
+        }
+    }
+
+    /// Records why the round timed out. Only the first timeout of a round counts, the timer
+    /// keeps firing until the round ends.
+    pub fn local_timeout(&mut self, round: Round) {
+        if let Some(current) = self.current_round(round) {
+            if current.local_timeout_reason.is_none() {
+                current.local_timeout_reason = Some(match (current.proposal_id, current.vote) {
+                    (None, _) => RoundTimeoutReason::NoProposal,
+                    (Some(_), Some(VoteKind::Proposal)) => RoundTimeoutReason::NoQuorumCertificate,
+                    (Some(_), _) => RoundTimeoutReason::ProposalNotVoted,
+                });
+            }
+        }
+    }
+
+    /// Starts tracking `round`, and stores the record of the round before it. `previous_round_end`
+    /// describes round `round - 1`; if this node was in an earlier round, that round is recorded
+    /// as skipped.
+    pub fn new_round(
+        &mut self,
+        epoch: u64,
+        round: Round,
+        proposer: Author,
+        previous_round_end: RoundEnd,
+    ) -> Option<RoundPostmortem> {
+        let now = Instant::now();
+        let postmortem = self.current.take().map(|previous| {
+            let ended_by_new_round = previous.round + 1 == round;
+            let outcome = match (ended_by_new_round, previous_round_end.certified) {
+                (false, _) => RoundOutcome::Skipped,
+                (true, true) => RoundOutcome::Certified,
+                (true, false) => RoundOutcome::TimedOut,
+            };
+            let RoundEnd {
+                votes_received,
+                timeout_votes_received,
+                timeout_certificate,
+                ..
+            } = if ended_by_new_round {
+                previous_round_end
+            } else {
+                RoundEnd {
+                    certified: false,
+                    votes_received: None,
+                    timeout_votes_received: VoteSummary::default(),
+                    timeout_certificate: None,
+                }
+            };
+            RoundPostmortem {
+                epoch: previous.epoch,
+                round: previous.round,
+                proposer: previous.proposer,
+                proposal_id: previous.proposal_id,
+                vote: previous.vote,
+                outcome,
+                local_timeout_reason: previous.local_timeout_reason,
+                votes_received,
+                timeout_votes_received,
+                timeout_certificate,
+                duration_ms: now.duration_since(previous.started_at).as_millis() as u64,
+            }
+        });
+        if let Some(postmortem) = &postmortem {
+            record_round_postmortem(postmortem.clone());
+        }
+
+        self.current = Some(RoundInProgress {
+            epoch,
+            round,
+            proposer,
+            proposal_id: None,
+            vote: None,
+            local_timeout_reason: None,
+            started_at: now,
+        });
+        postmortem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certified() -> RoundEnd {
+        RoundEnd {
+            certified: true,
+            votes_received: None,
+            timeout_votes_received: VoteSummary::default(),
+            timeout_certificate: None,
+        }
+    }
+
+    #[test]
+    fn test_round_outcomes() {
+        let proposer = Author::random();
+        let mut tracker = RoundPostmortemTracker::default();
+        assert_eq!(tracker.new_round(1, 1, proposer, certified()), None);
+
+        // Proposal received and voted for
+        tracker.proposal_received(1, HashValue::random());
+        tracker.voted(1, VoteKind::Proposal);
+        let postmortem = tracker.new_round(1, 2, proposer, certified()).unwrap();
+        assert_eq!(postmortem.round, 1);
+        assert_eq!(postmortem.outcome, RoundOutcome::Certified);
+        assert_eq!(postmortem.vote, Some(VoteKind::Proposal));
+        assert_eq!(postmortem.local_timeout_reason, None);
+
+        // No proposal, the round times out
+        tracker.local_timeout(2);
+        tracker.voted(2, VoteKind::Nil);
+        let timeout_votes = VoteSummary {
+            authors: vec![proposer],
+            voting_power: 1,
+        };
+        let postmortem = tracker
+            .new_round(
+                1,
+                3,
+                proposer,
+                RoundEnd {
+                    certified: false,
+                    votes_received: None,
+                    timeout_votes_received: timeout_votes.clone(),
+                    timeout_certificate: Some(TimeoutCertificateSummary {
+                        signers: timeout_votes.clone(),
+                        highest_hqc_round: 1,
+                    }),
+                },
+            )
+            .unwrap();
+        assert_eq!(postmortem.outcome, RoundOutcome::TimedOut);
+        assert_eq!(
+            postmortem.local_timeout_reason,
+            Some(RoundTimeoutReason::NoProposal)
+        );
+        assert_eq!(postmortem.vote, Some(VoteKind::Nil));
+        assert_eq!(postmortem.timeout_votes_received, timeout_votes);
+
+        // Jumping ahead skips the round, and events for other rounds are ignored
+        tracker.proposal_received(4, HashValue::random());
+        let postmortem = tracker.new_round(1, 5, proposer, certified()).unwrap();
+        assert_eq!(postmortem.round, 3);
+        assert_eq!(postmortem.outcome, RoundOutcome::Skipped);
+        assert_eq!(postmortem.proposal_id, None);
+    }
+
+    #[test]
+    fn test_timeout_reasons() {
+        let proposer = Author::random();
+        let mut tracker = RoundPostmortemTracker::default();
+        tracker.new_round(1, 1, proposer, certified());
+
+        // A proposal that wasn't voted for
+        tracker.proposal_received(1, HashValue::random());
+        tracker.local_timeout(1);
+        // Later timeouts of the same round don't change the reason
+        tracker.voted(1, VoteKind::Nil);
+        tracker.local_timeout(1);
+        let postmortem = tracker.new_round(1, 2, proposer, certified()).unwrap();
+        assert_eq!(
+            postmortem.local_timeout_reason,
+            Some(RoundTimeoutReason::ProposalNotVoted)
+        );
+
+        // A proposal that was voted for, but didn't get a quorum
+        tracker.proposal_received(2, HashValue::random());
+        tracker.voted(2, VoteKind::Proposal);
+        tracker.local_timeout(2);
+        let postmortem = tracker.new_round(1, 3, proposer, certified()).unwrap();
+        assert_eq!(
+            postmortem.local_timeout_reason,
+            Some(RoundTimeoutReason::NoQuorumCertificate)
+        );
+    }
+
+    #[test]
+    fn test_buffer_keeps_most_recent_records() {
+        let mut buffer = RoundPostmortemBuffer::new(2);
+        let mut tracker = RoundPostmortemTracker::default();
+        for round in 1..=4 {
+            if let Some(postmortem) = tracker.new_round(1, round, Author::random(), certified()) {
+                buffer.push(postmortem);
+            }
+        }
+        let rounds: Vec<_> = buffer.records.iter().map(|record| record.round).collect();
+        assert_eq!(rounds, vec![2, 3]);
+
+        buffer.set_max_records(1);
+        assert_eq!(buffer.records.len(), 1);
+        assert_eq!(buffer.records[0].round, 3);
+    }
+}
@@ -103,7 +103,6 @@ impl Ed25519PublicKey {
     ///               convention, if you expect to ever convert this back to an
     ///               x25519 public key, you should pass `false` for this
     ///               argument.
-    #[cfg(test)]
     pub(crate) fn from_x25519_public_bytes(
         x25519_bytes: &[u8],
         negative: bool,
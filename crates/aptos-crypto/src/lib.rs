@@ -12,6 +12,7 @@ pub mod error;
 pub mod hash;
 pub mod hkdf;
 pub mod multi_ed25519;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod noise;
 pub mod test_utils;
 pub mod traits;
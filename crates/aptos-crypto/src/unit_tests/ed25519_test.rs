@@ -267,6 +267,23 @@ proptest! {
         // key mangles the ed25519 private key bits irreversibly !
     }
 
+    #[test]
+    fn x25519_sign_ed25519(
+        keypair in uniform_keypair_strategy::<x25519::PrivateKey, x25519::PublicKey>(),
+        other_keypair in uniform_keypair_strategy::<x25519::PrivateKey, x25519::PublicKey>(),
+        message in random_serializable_struct(),
+    ){
+        let signature = keypair.private_key.sign_ed25519(&message).unwrap();
+        let public_key = keypair.public_key.to_ed25519_public_key().unwrap();
+        prop_assert!(signature.verify(&message, &public_key).is_ok());
+        // The signature is deterministic, as in Ed25519
+        prop_assert_eq!(&signature, &keypair.private_key.sign_ed25519(&message).unwrap());
+
+        let other_public_key = other_keypair.public_key.to_ed25519_public_key().unwrap();
+        prop_assume!(other_public_key != public_key);
+        prop_assert!(signature.verify(&message, &other_public_key).is_err());
+    }
+
     #[test]
     fn ed25519_to_x25519_roundtrip(keypair in uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>()){
         let ed25519_bytes = keypair.public_key.to_bytes();
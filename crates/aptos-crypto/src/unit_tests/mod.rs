@@ -10,4 +10,5 @@ mod ed25519_test;
 mod hash_test;
 mod hkdf_test;
 mod multi_ed25519_test;
+#[cfg(not(target_arch = "wasm32"))]
 mod noise_test;
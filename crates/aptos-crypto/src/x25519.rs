@@ -31,11 +31,19 @@
 //!
 
 use crate::{
-    traits::{self, CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt},
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+    traits::{
+        self, signing_message, CryptoMaterialError, ValidCryptoMaterial,
+        ValidCryptoMaterialStringExt,
+    },
     x25519,
 };
 use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
+use curve25519_dalek::{constants::ED25519_BASEPOINT_TABLE, scalar::Scalar};
 use rand::{CryptoRng, RngCore};
+use serde::Serialize;
+use sha2::{Digest, Sha512};
 use std::convert::{TryFrom, TryInto};
 
 #[cfg(any(test, feature = "fuzzing"))]
@@ -65,6 +73,9 @@ pub const PUBLIC_KEY_SIZE: usize = 32;
 /// Size of a X25519 shared secret
 pub const SHARED_SECRET_SIZE: usize = 32;
 
+/// Domain separation of the nonces of `PrivateKey::sign_ed25519`
+const ED25519_NONCE_DOMAIN: &[u8] = b"APTOS::X25519::ED25519_NONCE";
+
 /// This type should be used to deserialize a received private key
 #[derive(DeserializeKey, SilentDisplay, SilentDebug, SerializeKey)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Clone))]
@@ -123,6 +134,40 @@ impl PrivateKey {
             Ok(potential_x25519)
         }
     }
+
+    /// Signs a message with the Ed25519 key that is equivalent to this key, following the
+    /// XEdDSA approach, so that the signature can be checked against the X25519 public key
+    /// with `PublicKey::to_ed25519_public_key`. This lets a node sign with its network
+    /// identity key. Unlike XEdDSA, the nonce is derived from the key and the message, as in
+    /// Ed25519, rather than from fresh randomness.
+    pub fn sign_ed25519<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+    ) -> Result<Ed25519Signature, CryptoMaterialError> {
+        // XEdDSA signs with whichever of the scalar or its negation gives a public point with
+        // a positive sign, as the Montgomery form of the public key erases the sign
+        let scalar = Scalar::from_bytes_mod_order(self.0.to_bytes());
+        let point = &scalar * &ED25519_BASEPOINT_TABLE;
+        let (scalar, point) = if point.compress().as_bytes()[31] & 0x80 == 0 {
+            (scalar, point)
+        } else {
+            (-scalar, -point)
+        };
+
+        let nonce = Sha512::new()
+            .chain(ED25519_NONCE_DOMAIN)
+            .chain(scalar.as_bytes())
+            .finalize();
+        let mut expanded_key_bytes = [0u8; 64];
+        expanded_key_bytes[..32].copy_from_slice(scalar.as_bytes());
+        expanded_key_bytes[32..].copy_from_slice(&nonce[..32]);
+        let expanded_key = ed25519_dalek::ExpandedSecretKey::from_bytes(&expanded_key_bytes)
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        let public_key = ed25519_dalek::PublicKey::from_bytes(point.compress().as_bytes())
+            .map_err(|_| CryptoMaterialError::DeserializationError)?;
+        let signature = expanded_key.sign(&signing_message(message)?, &public_key);
+        Ok(Ed25519Signature(signature))
+    }
 }
 
 impl PublicKey {
@@ -146,6 +191,12 @@ impl PublicKey {
 
         Ok(x25519::PublicKey::from(ed_point.to_montgomery().to_bytes()))
     }
+
+    /// The Ed25519 public key that `PrivateKey::sign_ed25519` signs for, i.e. the Edwards form
+    /// of this key with a positive sign.
+    pub fn to_ed25519_public_key(&self) -> Result<Ed25519PublicKey, CryptoMaterialError> {
+        Ed25519PublicKey::from_x25519_public_bytes(&self.0, false)
+    }
 }
 
 //
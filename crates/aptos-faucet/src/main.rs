@@ -209,6 +209,8 @@ mod tests {
         let pending_txn = PendingTransaction {
             hash: HashValue::zero().into(),
             request: (&txn, dummy_payload()).into(),
+            eviction: None,
+            receipt: None,
         };
 
         *last_txn.lock().unwrap() = Some(Transaction::UserTransaction(txn));
@@ -27,11 +27,11 @@ use aptos_keygen::KeyGen;
 use aptos_logger::prelude::*;
 use aptos_types::{
     chain_id::ChainId,
-    on_chain_config::{GasScheduleV2, OnChainConsensusConfig},
+    on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig},
     transaction::Transaction,
     waypoint::Waypoint,
 };
-use aptos_vm_genesis::default_gas_schedule;
+use aptos_vm_genesis::{default_features, default_gas_schedule};
 use rand::Rng;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
@@ -409,6 +409,7 @@ pub struct GenesisConfiguration {
     pub employee_vesting_period_duration: Option<u64>,
     pub consensus_config: OnChainConsensusConfig,
     pub gas_schedule: GasScheduleV2,
+    pub initial_features: Vec<FeatureFlag>,
 }
 
 pub type InitConfigFn = Arc<dyn Fn(usize, &mut NodeConfig, &mut u64) + Send + Sync>;
@@ -608,6 +609,7 @@ impl Builder {
             employee_vesting_period_duration: None,
             consensus_config: OnChainConsensusConfig::default(),
             gas_schedule: default_gas_schedule(),
+            initial_features: default_features(),
         };
         if let Some(init_genesis_config) = &self.init_genesis_config {
             (init_genesis_config)(&mut genesis_config);
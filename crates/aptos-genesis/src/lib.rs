@@ -7,6 +7,7 @@ pub mod builder;
 pub mod config;
 pub mod keys;
 pub mod mainnet;
+pub mod presets;
 
 #[cfg(any(test, feature = "testing"))]
 pub mod test_utils;
@@ -23,7 +24,7 @@ use aptos_storage_interface::DbReaderWriter;
 use aptos_temppath::TempPath;
 use aptos_types::{
     chain_id::ChainId,
-    on_chain_config::{GasScheduleV2, OnChainConsensusConfig},
+    on_chain_config::{FeatureFlag, GasScheduleV2, OnChainConsensusConfig},
     transaction::Transaction,
     waypoint::Waypoint,
 };
@@ -69,6 +70,7 @@ pub struct GenesisInfo {
 
     pub consensus_config: OnChainConsensusConfig,
     pub gas_schedule: GasScheduleV2,
+    pub initial_features: Vec<FeatureFlag>,
 }
 
 impl GenesisInfo {
@@ -104,6 +106,7 @@ impl GenesisInfo {
             voting_power_increase_limit: genesis_config.voting_power_increase_limit,
             consensus_config: genesis_config.consensus_config.clone(),
             gas_schedule: genesis_config.gas_schedule.clone(),
+            initial_features: genesis_config.initial_features.clone(),
         })
     }
 
@@ -136,6 +139,7 @@ impl GenesisInfo {
                 voting_power_increase_limit: self.voting_power_increase_limit,
                 employee_vesting_start: 1663456089,
                 employee_vesting_period_duration: 5 * 60, // 5 minutes
+                initial_features: self.initial_features.clone(),
             },
             &self.consensus_config,
             &self.gas_schedule,
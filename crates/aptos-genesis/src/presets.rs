@@ -0,0 +1,147 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::builder::GenesisConfiguration;
+use anyhow::{anyhow, bail, Result};
+use aptos_gas::LATEST_GAS_FEATURE_VERSION;
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{
+        FeatureFlag, Features, GasScheduleV2, OnChainConfig, OnChainConsensusConfig,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use url::Url;
+
+/// Presets for the on-chain configs of a local network, so that it behaves like one of the
+/// public networks. The feature flags, gas schedule and consensus config of the network are read
+/// from its REST API when the local network is created, so they're always the ones the network
+/// currently runs with.
+///
+/// The presets don't change the chain id of the local network, which stays the test chain id
+/// so that transactions signed for a local network can never be replayed on a public one.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GenesisPreset {
+    MainnetParity,
+    TestnetParity,
+}
+
+impl GenesisPreset {
+    pub const ALL: [GenesisPreset; 2] =
+        [GenesisPreset::MainnetParity, GenesisPreset::TestnetParity];
+
+    /// The REST API of the network the preset captures
+    pub fn rest_url(&self) -> Url {
+        let url = match self {
+            GenesisPreset::MainnetParity => "https://fullnode.mainnet.aptoslabs.com",
+            GenesisPreset::TestnetParity => "https://fullnode.testnet.aptoslabs.com",
+        };
+        Url::parse(url).expect("The REST URLs of the public networks are valid")
+    }
+
+    /// Reads the current on-chain configs of the network
+    pub async fn fetch_network_configs(&self) -> Result<NetworkConfigs> {
+        NetworkConfigs::fetch(&Client::new(self.rest_url()))
+            .await
+            .map_err(|err| anyhow!("Failed to read the on-chain configs of {}: {}", self, err))
+    }
+}
+
+impl fmt::Display for GenesisPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GenesisPreset::MainnetParity => "mainnet-parity",
+            GenesisPreset::TestnetParity => "testnet-parity",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for GenesisPreset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GenesisPreset::ALL
+            .into_iter()
+            .find(|preset| preset.to_string() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Invalid genesis preset {}, expected one of: {}",
+                    s,
+                    GenesisPreset::ALL
+                        .map(|preset| preset.to_string())
+                        .join(", ")
+                )
+            })
+    }
+}
+
+/// The on-chain configs of a network that a local network can be started with
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NetworkConfigs {
+    pub consensus_config: OnChainConsensusConfig,
+    pub gas_schedule: GasScheduleV2,
+    pub features: Vec<FeatureFlag>,
+}
+
+impl NetworkConfigs {
+    /// Reads the on-chain configs of the network served by `client`. Fails if the network runs
+    /// with configs this build doesn't know of, as a local network couldn't reproduce it.
+    pub async fn fetch(client: &Client) -> Result<Self> {
+        let consensus_config = fetch_config::<OnChainConsensusConfig>(client).await?;
+        let gas_schedule = fetch_config::<GasScheduleV2>(client).await?;
+        let features = fetch_config::<Features>(client).await?;
+        Self::new(consensus_config, gas_schedule, &features)
+    }
+
+    pub fn new(
+        consensus_config: OnChainConsensusConfig,
+        gas_schedule: GasScheduleV2,
+        features: &Features,
+    ) -> Result<Self> {
+        if gas_schedule.feature_version > LATEST_GAS_FEATURE_VERSION {
+            bail!(
+                "The network runs gas feature version {}, but this build only supports up to {}",
+                gas_schedule.feature_version,
+                LATEST_GAS_FEATURE_VERSION
+            );
+        }
+
+        let known_features: Vec<_> = FeatureFlag::ALL
+            .into_iter()
+            .filter(|flag| features.is_enabled(*flag))
+            .collect();
+        let num_enabled_features: u32 =
+            features.features.iter().map(|byte| byte.count_ones()).sum();
+        if known_features.len() as u32 != num_enabled_features {
+            bail!(
+                "The network enables feature flags this build doesn't know of, it only knows {:?}",
+                FeatureFlag::ALL
+            );
+        }
+
+        Ok(Self {
+            consensus_config,
+            gas_schedule,
+            features: known_features,
+        })
+    }
+
+    /// Overrides the on-chain configs of `genesis_config` with the ones of the network
+    pub fn apply(&self, genesis_config: &mut GenesisConfiguration) {
+        genesis_config.consensus_config = self.consensus_config.clone();
+        genesis_config.gas_schedule = self.gas_schedule.clone();
+        genesis_config.initial_features = self.features.clone();
+    }
+}
+
+async fn fetch_config<T: OnChainConfig>(client: &Client) -> Result<T> {
+    let bytes = client
+        .get_account_resource_bytes(CORE_CODE_ADDRESS, &T::struct_tag().to_string())
+        .await?
+        .into_inner();
+    T::deserialize_into_config(&bytes)
+}
@@ -0,0 +1,264 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signs with the Aptos app of a Ledger hardware wallet, so that keys never leave the device.
+//!
+//! The device is reached over USB HID, and every signature has to be confirmed on the device.
+
+#![forbid(unsafe_code)]
+
+use aptos_crypto::ed25519::{Ed25519PublicKey, Ed25519Signature};
+use ledger_apdu::APDUCommand;
+use ledger_transport_hid::{hidapi::HidApi, TransportNativeHID};
+use std::convert::TryFrom;
+use thiserror::Error;
+
+/// Derivation path of the first Aptos account, `637` being the coin type of Aptos
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
+
+const APTOS_CLA: u8 = 0x5B;
+const INS_GET_PUBLIC_KEY: u8 = 0x05;
+const INS_SIGN_TXN: u8 = 0x06;
+
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_START: u8 = 0x00;
+const P2_MORE: u8 = 0x80;
+const P2_LAST: u8 = 0x00;
+
+const MAX_APDU_LEN: usize = 255;
+const APDU_CODE_SUCCESS: u16 = 0x9000;
+const HARDENED: u32 = 0x8000_0000;
+
+#[derive(Debug, Error)]
+pub enum AptosLedgerError {
+    #[error("Unable to connect to the Ledger device: {0}")]
+    DeviceNotFound(String),
+    #[error("Invalid derivation path {0}")]
+    InvalidDerivationPath(String),
+    #[error("Failed to communicate with the Ledger device: {0}")]
+    Transport(String),
+    #[error("The Aptos app returned {0:#06x}, make sure it's open and the request was approved")]
+    AppError(u16),
+    #[error("Unexpected response from the Aptos app: {0}")]
+    UnexpectedResponse(String),
+    #[error("The message of {0} bytes is too long to be signed by the Aptos app")]
+    MessageTooLong(usize),
+}
+
+pub type Result<T> = std::result::Result<T, AptosLedgerError>;
+
+/// A connection to the Aptos app of a Ledger device
+pub struct AptosLedger {
+    transport: TransportNativeHID,
+}
+
+impl AptosLedger {
+    /// Connects to the first Ledger device plugged in
+    pub fn connect() -> Result<Self> {
+        let api = HidApi::new().map_err(|err| AptosLedgerError::DeviceNotFound(err.to_string()))?;
+        let transport = TransportNativeHID::new(&api)
+            .map_err(|err| AptosLedgerError::DeviceNotFound(err.to_string()))?;
+        Ok(Self { transport })
+    }
+
+    /// Returns the public key of the account at the given derivation path
+    pub fn get_public_key(&self, derivation_path: &str) -> Result<Ed25519PublicKey> {
+        let response = self.exchange(&get_public_key_command(derivation_path)?)?;
+        parse_public_key(&response)
+    }
+
+    /// Signs the message with the key at the given derivation path, once approved on the device
+    pub fn sign_message(&self, derivation_path: &str, message: &[u8]) -> Result<Ed25519Signature> {
+        // Only the response to the last chunk of the message holds the signature
+        let mut response = vec![];
+        for command in sign_message_commands(derivation_path, message)? {
+            response = self.exchange(&command)?;
+        }
+        parse_signature(&response)
+    }
+
+    fn exchange(&self, command: &APDUCommand<Vec<u8>>) -> Result<Vec<u8>> {
+        let answer = self
+            .transport
+            .exchange(command)
+            .map_err(|err| AptosLedgerError::Transport(err.to_string()))?;
+        if answer.retcode() != APDU_CODE_SUCCESS {
+            return Err(AptosLedgerError::AppError(answer.retcode()));
+        }
+        Ok(answer.data().to_vec())
+    }
+}
+
+fn command(ins: u8, p1: u8, p2: u8, data: Vec<u8>) -> APDUCommand<Vec<u8>> {
+    APDUCommand {
+        cla: APTOS_CLA,
+        ins,
+        p1,
+        p2,
+        data,
+    }
+}
+
+fn get_public_key_command(derivation_path: &str) -> Result<APDUCommand<Vec<u8>>> {
+    Ok(command(
+        INS_GET_PUBLIC_KEY,
+        P1_NON_CONFIRM,
+        P2_LAST,
+        serialize_derivation_path(derivation_path)?,
+    ))
+}
+
+/// The derivation path is sent first, then the message in as many chunks as necessary, the
+/// index of each chunk being its `p1`
+fn sign_message_commands(
+    derivation_path: &str,
+    message: &[u8],
+) -> Result<Vec<APDUCommand<Vec<u8>>>> {
+    let mut commands = vec![command(
+        INS_SIGN_TXN,
+        P1_START,
+        P2_MORE,
+        serialize_derivation_path(derivation_path)?,
+    )];
+    let chunks: Vec<_> = message.chunks(MAX_APDU_LEN).collect();
+    if chunks.len() >= u8::MAX as usize {
+        return Err(AptosLedgerError::MessageTooLong(message.len()));
+    }
+    for (index, chunk) in chunks.iter().enumerate() {
+        let p2 = if index + 1 == chunks.len() {
+            P2_LAST
+        } else {
+            P2_MORE
+        };
+        commands.push(command(INS_SIGN_TXN, (index + 1) as u8, p2, chunk.to_vec()));
+    }
+    Ok(commands)
+}
+
+/// Returns the bytes prefixed by their length at the start of a response. The Aptos app may
+/// append more data to them, e.g., the chain code after a public key.
+fn length_prefixed(response: &[u8]) -> Option<&[u8]> {
+    let (len, data) = response.split_first()?;
+    data.get(..*len as usize)
+}
+
+/// The public key is prefixed by a byte giving its format
+fn parse_public_key(response: &[u8]) -> Result<Ed25519PublicKey> {
+    let key = length_prefixed(response)
+        .and_then(|key| key.get(1..))
+        .ok_or_else(|| {
+            AptosLedgerError::UnexpectedResponse(format!("public key of {:?}", response))
+        })?;
+    Ed25519PublicKey::try_from(key)
+        .map_err(|err| AptosLedgerError::UnexpectedResponse(err.to_string()))
+}
+
+fn parse_signature(response: &[u8]) -> Result<Ed25519Signature> {
+    let signature = length_prefixed(response).ok_or_else(|| {
+        AptosLedgerError::UnexpectedResponse(format!("signature of {:?}", response))
+    })?;
+    Ed25519Signature::try_from(signature)
+        .map_err(|err| AptosLedgerError::UnexpectedResponse(err.to_string()))
+}
+
+/// Serializes a BIP32 derivation path e.g. `m/44'/637'/0'/0'/0'` as the number of its indices
+/// followed by each index, big endian
+pub fn serialize_derivation_path(derivation_path: &str) -> Result<Vec<u8>> {
+    let invalid = || AptosLedgerError::InvalidDerivationPath(derivation_path.to_string());
+    let mut indices = derivation_path.split('/');
+    if indices.next() != Some("m") {
+        return Err(invalid());
+    }
+
+    let indices = indices
+        .map(|index| match index.strip_suffix('\'') {
+            Some(index) => index.parse::<u32>().map(|index| index | HARDENED),
+            None => index.parse::<u32>(),
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    if indices.is_empty() || indices.len() > u8::MAX as usize {
+        return Err(invalid());
+    }
+
+    let mut bytes = vec![indices.len() as u8];
+    for index in indices {
+        bytes.extend_from_slice(&index.to_be_bytes());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+
+    #[test]
+    fn test_serialize_derivation_path() {
+        assert_eq!(
+            serialize_derivation_path(DEFAULT_DERIVATION_PATH).unwrap(),
+            vec![
+                5, 0x80, 0, 0, 44, 0x80, 0, 0x02, 0x7d, 0x80, 0, 0, 0, 0x80, 0, 0, 0, 0x80, 0, 0, 0
+            ]
+        );
+        assert_eq!(
+            serialize_derivation_path("m/44'/637'/1'/0/2").unwrap()[9..],
+            [0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2]
+        );
+        assert!(serialize_derivation_path("44'/637'").is_err());
+        assert!(serialize_derivation_path("m").is_err());
+        assert!(serialize_derivation_path("m/44'/x").is_err());
+    }
+
+    #[test]
+    fn test_sign_message_commands() {
+        let message = vec![7u8; MAX_APDU_LEN + 10];
+        let commands = sign_message_commands(DEFAULT_DERIVATION_PATH, &message).unwrap();
+        let headers: Vec<_> = commands
+            .iter()
+            .map(|command| (command.cla, command.ins, command.p1, command.p2))
+            .collect();
+        assert_eq!(
+            headers,
+            vec![
+                (APTOS_CLA, INS_SIGN_TXN, P1_START, P2_MORE),
+                (APTOS_CLA, INS_SIGN_TXN, 1, P2_MORE),
+                (APTOS_CLA, INS_SIGN_TXN, 2, P2_LAST),
+            ]
+        );
+        assert_eq!(
+            commands[0].data,
+            serialize_derivation_path(DEFAULT_DERIVATION_PATH).unwrap()
+        );
+        assert_eq!(commands[1].data, message[..MAX_APDU_LEN]);
+        assert_eq!(commands[2].data, message[MAX_APDU_LEN..]);
+
+        let command = get_public_key_command(DEFAULT_DERIVATION_PATH).unwrap();
+        assert_eq!(
+            (command.cla, command.ins, command.p1, command.p2),
+            (APTOS_CLA, INS_GET_PUBLIC_KEY, P1_NON_CONFIRM, P2_LAST)
+        );
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        let private_key = Ed25519PrivateKey::generate_for_testing();
+        let public_key = private_key.public_key();
+
+        // The key is prefixed by its format, and followed by the chain code
+        let mut response = vec![33, 0x04];
+        response.extend_from_slice(&public_key.to_bytes());
+        assert_eq!(parse_public_key(&response).unwrap(), public_key);
+        response.extend_from_slice(&[32; 33]);
+        assert_eq!(parse_public_key(&response).unwrap(), public_key);
+        assert!(parse_public_key(&response[..20]).is_err());
+        assert!(parse_public_key(&[]).is_err());
+
+        let signature = private_key.sign_arbitrary_message(b"message");
+        let mut response = vec![64];
+        response.extend_from_slice(&signature.to_bytes());
+        assert_eq!(parse_signature(&response).unwrap(), signature);
+        assert!(parse_signature(&response[..40]).is_err());
+        assert!(parse_signature(&[0]).is_err());
+    }
+}
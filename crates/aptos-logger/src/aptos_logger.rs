@@ -688,16 +688,17 @@ fn json_format(entry: &LogEntry) -> Result<String, fmt::Error> {
 /// Periodically rebuilds the filter and replaces the current logger filter.
 /// This is useful for dynamically changing log levels at runtime via existing
 /// environment variables such as `RUST_LOG_TELEMETRY`.
+#[derive(Clone)]
 pub struct LoggerFilterUpdater {
     logger: Arc<AptosData>,
-    logger_builder: AptosDataBuilder,
+    logger_builder: Arc<RwLock<AptosDataBuilder>>,
 }
 
 impl LoggerFilterUpdater {
     pub fn new(logger: Arc<AptosData>, logger_builder: AptosDataBuilder) -> Self {
         Self {
             logger,
-            logger_builder,
+            logger_builder: Arc::new(RwLock::new(logger_builder)),
         }
     }
 
@@ -710,9 +711,16 @@ impl LoggerFilterUpdater {
         }
     }
 
+    /// Changes the local log level and applies it right away. The level is kept for later
+    /// rebuilds of the filter, but is still overridden by `RUST_LOG` if that is set.
+    pub fn set_level(&self, level: Level) {
+        self.logger_builder.write().level(level);
+        self.update_filter();
+    }
+
     fn update_filter(&self) {
         // TODO: check for change to env var before rebuilding filter.
-        let filter = self.logger_builder.build_filter();
+        let filter = self.logger_builder.read().build_filter();
         self.logger.set_filter(filter);
     }
 }
@@ -721,7 +729,7 @@ impl LoggerFilterUpdater {
 mod tests {
     use super::{AptosData, LogEntry};
     use crate::{
-        aptos_logger::{json_format, RUST_LOG_TELEMETRY},
+        aptos_logger::{json_format, RUST_LOG, RUST_LOG_TELEMETRY},
         debug, error, info,
         logger::Logger,
         trace, warn, AptosDataBuilder, Event, Key, KeyValue, Level, LoggerFilterUpdater, Metadata,
@@ -974,6 +982,24 @@ mod tests {
             .enabled(debug_metadata));
     }
 
+    #[test]
+    fn test_logger_filter_updater_set_level() {
+        let (logger_builder, logger) = new_async_logger();
+        let debug_metadata = &Metadata::new(Level::Debug, "target", "module_path", "source_path");
+
+        std::env::remove_var(RUST_LOG);
+        let updater = LoggerFilterUpdater::new(logger.clone(), logger_builder);
+        updater.update_filter();
+        assert!(!logger.filter.read().local_filter.enabled(debug_metadata));
+
+        updater.set_level(Level::Debug);
+        assert!(logger.filter.read().local_filter.enabled(debug_metadata));
+
+        // The new level survives the periodic rebuild of the filter
+        updater.clone().update_filter();
+        assert!(logger.filter.read().local_filter.enabled(debug_metadata));
+    }
+
     #[test]
     fn test_logger_filter_updater_invalid_value() {
         let (logger_builder, logger) = new_async_logger();
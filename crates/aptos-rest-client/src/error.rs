@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::State;
-use aptos_api_types::AptosError;
+use aptos_api_types::{AptosError, AptosErrorCode, ErrorCategory, ErrorCodeInfo, RetryHint};
+use aptos_types::vm_status::StatusCode as VmStatusCode;
 use reqwest::StatusCode;
 use thiserror::Error;
 
@@ -155,12 +156,63 @@ pub enum RestError {
     UrlParse(url::ParseError),
     #[error("Timeout waiting for transaction {0}")]
     Timeout(&'static str),
+    #[error("{0}")]
+    TransactionExpired(&'static str),
     #[error("Unknown error {0}")]
     Unknown(anyhow::Error),
     #[error("HTTP error {0}: {1}")]
     Http(StatusCode, reqwest::Error),
 }
 
+impl RestError {
+    /// Whether the transaction expired before it was committed, either when it was submitted or
+    /// while waiting for it. It is then guaranteed it will not be committed on chain.
+    pub fn is_transaction_expired(&self) -> bool {
+        match self {
+            RestError::TransactionExpired(_) => true,
+            RestError::Api(inner) => {
+                matches!(inner.error.error_code, AptosErrorCode::VmError)
+                    && inner.error.vm_error_code == Some(VmStatusCode::TRANSACTION_EXPIRED as u64)
+            }
+            _ => false,
+        }
+    }
+
+    /// The catalog entry of an error returned by the API
+    pub fn error_info(&self) -> Option<ErrorCodeInfo> {
+        match self {
+            RestError::Api(inner) => Some(inner.error.error_info()),
+            _ => None,
+        }
+    }
+
+    /// The category of an error returned by the API
+    pub fn category(&self) -> Option<ErrorCategory> {
+        self.error_info().map(|error_info| error_info.category)
+    }
+
+    /// How the request that failed should be retried, if at all
+    pub fn retry_hint(&self) -> RetryHint {
+        match self {
+            RestError::Api(inner) => inner.error.error_info().retry_hint,
+            RestError::TransactionExpired(_) => RetryHint::RebuildTransaction,
+            RestError::Timeout(_) | RestError::Unknown(_) => RetryHint::RetryLater,
+            RestError::Http(status_code, _)
+                if status_code.is_server_error()
+                    || *status_code == StatusCode::TOO_MANY_REQUESTS =>
+            {
+                RetryHint::RetryLater
+            }
+            _ => RetryHint::DoNotRetry,
+        }
+    }
+
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        self.retry_hint() == RetryHint::RetryLater
+    }
+}
+
 impl From<(AptosError, Option<State>, StatusCode)> for RestError {
     fn from((error, state, status_code): (AptosError, Option<State>, StatusCode)) -> Self {
         Self::Api(AptosErrorResponse {
@@ -25,8 +25,8 @@ use aptos_api_types::{
     deserialize_from_string,
     mime_types::{BCS, BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE},
     AptosError, BcsBlock, Block, GasEstimation, HexEncodedBytes, IndexResponse, MoveModuleId,
-    TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult, UserTransaction,
-    VersionedEvent,
+    StateSyncStatus, TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult,
+    UserTransaction, VersionedEvent,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -36,6 +36,7 @@ use aptos_types::{
     contract_event::EventWithVersion,
     transaction::SignedTransaction,
 };
+use futures::{stream, Stream, TryStreamExt};
 use move_core_types::language_storage::StructTag;
 use reqwest::header::ACCEPT;
 use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
@@ -56,6 +57,9 @@ static DEFAULT_INTERVAL_DURATION: Duration = Duration::from_millis(DEFAULT_INTER
 const DEFAULT_MAX_SERVER_LAG_WAIT_DURATION: Duration = Duration::from_secs(60);
 const RESOURCES_PER_CALL_PAGINATION: u64 = 9999;
 const MODULES_PER_CALL_PAGINATION: u64 = 1000;
+const DEFAULT_STREAM_PAGE_SIZE: u16 = 100;
+/// The largest page the node serves for endpoints paginated by sequence number
+const MAX_STREAM_PAGE_SIZE: u16 = 100;
 
 type AptosResult<T> = Result<T, RestError>;
 
@@ -279,6 +283,10 @@ impl Client {
         self.get(self.build_path("")?).await
     }
 
+    pub async fn get_state_sync_status(&self) -> AptosResult<Response<StateSyncStatus>> {
+        self.get(self.build_path("-/state_sync")?).await
+    }
+
     pub async fn get_index_bcs(&self) -> AptosResult<Response<IndexResponseBcs>> {
         let url = self.build_path("")?;
         let response = self.get_bcs(url).await?;
@@ -581,7 +589,7 @@ impl Client {
                 WaitForTransactionResult::Pending(state) => {
                     reached_mempool = true;
                     if expiration_timestamp_secs <= state.timestamp_usecs / 1_000_000 {
-                        return Err(anyhow!("Transaction expired. It is guaranteed it will not be committed on chain.").into());
+                        return Err(RestError::TransactionExpired("Transaction expired. It is guaranteed it will not be committed on chain."));
                     }
                     chain_timestamp_usecs = Some(state.timestamp_usecs);
                 }
@@ -590,7 +598,7 @@ impl Client {
                         if let Some(state) = aptos_error_response.state {
                             if expiration_timestamp_secs <= state.timestamp_usecs / 1_000_000 {
                                 if reached_mempool {
-                                    return Err(anyhow!("Transaction expired. It is guaranteed it will not be committed on chain.").into());
+                                    return Err(RestError::TransactionExpired("Transaction expired. It is guaranteed it will not be committed on chain."));
                                 } else {
                                     // We want to know whether we ever got Pending state from the mempool,
                                     // to warn in case we didn't.
@@ -602,7 +610,7 @@ impl Client {
                                     // At the end, when the expiration happens, we might get NotFound or Pending
                                     // based on whether GC run on the full node to remove expired transaction,
                                     // so that information is not useful. So we need to keep this variable as state.
-                                    return Err(anyhow!("Transaction expired, without being seen in mempool. It is guaranteed it will not be committed on chain.").into());
+                                    return Err(RestError::TransactionExpired("Transaction expired, without being seen in mempool. It is guaranteed it will not be committed on chain."));
                                 }
                             }
                             chain_timestamp_usecs = Some(state.timestamp_usecs);
@@ -899,6 +907,21 @@ impl Client {
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
+    /// Streams the transactions sent by the account, starting at the given sequence number, or
+    /// at its first transaction. See `paginate_stream` for how pages are fetched.
+    pub fn account_transactions_stream(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = AptosResult<Transaction>> + '_ {
+        self.paginate_stream(start, page_size, move |start, limit| async move {
+            self.get_account_transactions(address, Some(start), Some(limit as u64))
+                .await
+                .map(Response::into_inner)
+        })
+    }
+
     pub async fn get_account_resources(
         &self,
         address: AccountAddress,
@@ -911,6 +934,22 @@ impl Client {
         .await
     }
 
+    /// Streams the resources of the account. See `paginate_with_cursor_stream` for how pages are
+    /// fetched.
+    pub fn account_resources_stream(
+        &self,
+        address: AccountAddress,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = AptosResult<Resource>> + '_ {
+        self.paginate_with_cursor_stream(
+            format!("accounts/{}/resources", address),
+            page_size.map_or(RESOURCES_PER_CALL_PAGINATION, |page_size| {
+                u64::from(page_size.max(1))
+            }),
+            None,
+        )
+    }
+
     pub async fn get_account_resources_bcs(
         &self,
         address: AccountAddress,
@@ -1064,6 +1103,22 @@ impl Client {
         .await
     }
 
+    /// Streams the modules of the account. See `paginate_with_cursor_stream` for how pages are
+    /// fetched.
+    pub fn account_modules_stream(
+        &self,
+        address: AccountAddress,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = AptosResult<MoveModuleBytecode>> + '_ {
+        self.paginate_with_cursor_stream(
+            format!("accounts/{}/modules", address),
+            page_size.map_or(MODULES_PER_CALL_PAGINATION, |page_size| {
+                u64::from(page_size.max(1))
+            }),
+            None,
+        )
+    }
+
     pub async fn get_account_modules_bcs(
         &self,
         address: AccountAddress,
@@ -1140,6 +1195,23 @@ impl Client {
         Ok(response.and_then(|inner| bcs::from_bytes(&inner))?)
     }
 
+    /// Streams the events of an event handle of the account, starting at the given sequence
+    /// number, or at its first event. See `paginate_stream` for how pages are fetched.
+    pub fn events_stream<'a>(
+        &'a self,
+        address: AccountAddress,
+        struct_tag: &'a str,
+        field_name: &'a str,
+        start: Option<u64>,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = AptosResult<VersionedEvent>> + 'a {
+        self.paginate_stream(start, page_size, move |start, limit| async move {
+            self.get_account_events(address, struct_tag, field_name, Some(start), Some(limit))
+                .await
+                .map(Response::into_inner)
+        })
+    }
+
     pub async fn get_new_block_events_bcs(
         &self,
         start: Option<u64>,
@@ -1404,7 +1476,7 @@ impl Client {
                     | RestError::Json(_)
                     | RestError::Timeout(_)
                     | RestError::Unknown(_) => true,
-                    RestError::UrlParse(_) => false,
+                    RestError::UrlParse(_) | RestError::TransactionExpired(_) => false,
                 },
             };
 
@@ -1514,6 +1586,87 @@ impl Client {
             }
         }
     }
+
+    /// Turns an endpoint paginated by cursor into a stream of its items. Unlike
+    /// `paginate_with_cursor`, pages are only fetched once the previous one has been consumed, so
+    /// callers can stop early without fetching everything. The stream ends once a page comes
+    /// without a cursor, so pages the node shortens to its own maximum size don't end it early.
+    ///
+    /// The returned stream isn't `Unpin`, so it has to be pinned (e.g. with `Box::pin`) before
+    /// calling `next` on it.
+    pub fn paginate_with_cursor_stream<'a, T: for<'de> Deserialize<'de> + 'a>(
+        &'a self,
+        base_path: String,
+        limit_per_request: u64,
+        ledger_version: Option<u64>,
+    ) -> impl Stream<Item = AptosResult<T>> + 'a {
+        // The state is the cursor of the next page, or None once the last page has been fetched
+        stream::try_unfold(Some(None), move |cursor: Option<Option<String>>| {
+            let base_path = base_path.clone();
+            async move {
+                let cursor = match cursor {
+                    Some(cursor) => cursor,
+                    None => return Ok(None),
+                };
+                let url = self.build_url_for_pagination(
+                    &base_path,
+                    limit_per_request,
+                    ledger_version,
+                    cursor,
+                )?;
+                let raw_response = self.inner.get(url).send().await?;
+                let response: Response<Vec<T>> = self.json(raw_response).await?;
+                let next_cursor = response.state().cursor.clone().map(Some);
+                Ok::<_, RestError>(Some((response.into_inner(), next_cursor)))
+            }
+        })
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Turns an endpoint paginated by sequence number into a stream of its items. Pages are
+    /// only fetched once the previous one has been consumed, and requests that fail with a
+    /// retriable error (e.g. because of rate limiting) are retried with backoff, as in
+    /// `try_until_ok`. The page size is clamped to what the node serves, and the stream ends at
+    /// the first empty page, as the node may return fewer items than requested.
+    ///
+    /// The returned stream isn't `Unpin`, so it has to be pinned (e.g. with `Box::pin`) before
+    /// calling `next` on it.
+    fn paginate_stream<'a, T, F, Fut>(
+        &'a self,
+        start: Option<u64>,
+        page_size: Option<u16>,
+        fetch_page: F,
+    ) -> impl Stream<Item = AptosResult<T>> + 'a
+    where
+        T: 'a,
+        F: Fn(u64, u16) -> Fut + 'a,
+        Fut: Future<Output = AptosResult<Vec<T>>> + 'a,
+    {
+        let page_size = page_size
+            .unwrap_or(DEFAULT_STREAM_PAGE_SIZE)
+            .clamp(1, MAX_STREAM_PAGE_SIZE);
+        stream::try_unfold(
+            (Some(start.unwrap_or(0)), fetch_page),
+            move |(start, fetch_page)| async move {
+                let start = match start {
+                    Some(start) => start,
+                    None => return Ok(None),
+                };
+                let page =
+                    Client::try_until_ok(None, None, retriable, || fetch_page(start, page_size))
+                        .await?;
+                let next_start = if page.is_empty() {
+                    None
+                } else {
+                    Some(start + page.len() as u64)
+                };
+                Ok::<_, RestError>(Some((page, (next_start, fetch_page))))
+            },
+        )
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
+    }
 }
 
 pub fn retriable_with_404(status_code: StatusCode, aptos_error: Option<AptosError>) -> bool {
@@ -1582,3 +1735,32 @@ enum WaitForTransactionResult<T> {
     Pending(State),
     Success(Response<T>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fetches pages of the numbers below `len`, capping them at `max_page_size` as the node does
+    async fn collect_stream(page_size: Option<u16>, max_page_size: u64, len: u64) -> Vec<u64> {
+        let client = Client::new(Url::parse("http://localhost:8080").unwrap());
+        let stream = client.paginate_stream(Some(0), page_size, move |start, limit| async move {
+            assert!(limit > 0 && limit <= MAX_STREAM_PAGE_SIZE);
+            let end = len.min(start + (limit as u64).min(max_page_size));
+            Ok::<Vec<u64>, RestError>((start..end).collect())
+        });
+        stream.try_collect().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_paginate_stream() {
+        let all: Vec<u64> = (0..250).collect();
+        assert_eq!(collect_stream(None, 100, 250).await, all);
+        assert_eq!(collect_stream(Some(7), 100, 250).await, all);
+        // Page sizes the node doesn't serve are clamped
+        assert_eq!(collect_stream(Some(0), 100, 250).await, all);
+        assert_eq!(collect_stream(Some(1000), 100, 250).await, all);
+        // Short pages don't end the stream
+        assert_eq!(collect_stream(Some(100), 30, 250).await, all);
+        assert!(collect_stream(None, 100, 0).await.is_empty());
+    }
+}
@@ -283,7 +283,13 @@ impl From<RestError> for ApiError {
                 AptosErrorCode::SequenceNumberTooOld => {
                     ApiError::SequenceNumberTooOld(Some(err.error.message))
                 }
+                AptosErrorCode::SequenceNumberTooNew => {
+                    ApiError::InvalidInput(Some(err.error.message))
+                }
                 AptosErrorCode::VmError => ApiError::VmError(Some(err.error.message)),
+                AptosErrorCode::TransactionDenied | AptosErrorCode::TransactionNotAllowlisted => {
+                    ApiError::InvalidInput(Some(err.error.message))
+                }
                 AptosErrorCode::HealthCheckFailed => {
                     ApiError::InternalError(Some(err.error.message))
                 }
@@ -303,6 +309,7 @@ impl From<RestError> for ApiError {
             ))),
             RestError::UrlParse(err) => ApiError::InternalError(Some(err.to_string())),
             RestError::Timeout(err) => ApiError::InternalError(Some(err.to_string())),
+            RestError::TransactionExpired(err) => ApiError::InternalError(Some(err.to_string())),
             RestError::Unknown(err) => ApiError::InternalError(Some(err.to_string())),
         }
     }
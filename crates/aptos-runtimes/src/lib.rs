@@ -0,0 +1,301 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builds the tokio runtimes and rayon pools of the node from their `ThreadPoolConfig`, so
+//! that each subsystem can be sized and pinned to its own cores. Every pool built here reports
+//! its size and how long tasks wait in its queue before they start running.
+
+pub mod task_dump;
+pub mod thread_dump;
+
+use aptos_config::config::{ThreadPoolConfig, ThreadPoolsConfig};
+use aptos_logger::{info, warn};
+use aptos_metrics_core::{
+    register_histogram_vec, register_int_gauge_vec, HistogramVec, IntGaugeVec,
+};
+use once_cell::sync::Lazy;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tokio::runtime::{Builder, Runtime};
+
+/// How often a probe task is queued on each pool to measure its queueing latency
+const QUEUE_LATENCY_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+static THREAD_POOL_NUM_THREADS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_thread_pool_num_threads",
+        "Number of threads in each thread pool",
+        &["pool"]
+    )
+    .unwrap()
+});
+
+static THREAD_POOL_QUEUE_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_thread_pool_queue_latency_seconds",
+        "Time a task waits in the queue of a thread pool before it starts running",
+        &["pool"]
+    )
+    .unwrap()
+});
+
+/// Spreads the pools that aren't explicitly pinned across the NUMA nodes of the machine, see
+/// `ThreadPoolsConfig::apply_numa_defaults`.
+pub fn apply_numa_defaults(config: &mut ThreadPoolsConfig) {
+    let numa_nodes = online_numa_nodes();
+    config.apply_numa_defaults(&numa_nodes);
+    if config.numa_aware_defaults && numa_nodes.len() > 1 {
+        info!(
+            "Thread pools pinned to NUMA nodes {:?}: api {:?}, execution {:?}, state sync {:?}, network {:?}",
+            numa_nodes,
+            config.api.numa_node,
+            config.execution.numa_node,
+            config.state_sync.numa_node,
+            config.network.numa_node
+        );
+    }
+}
+
+/// Returns the cores the threads of a pool should be pinned to, or an empty list if they
+/// shouldn't be pinned.
+pub fn pinned_cores(config: &ThreadPoolConfig) -> Vec<usize> {
+    if !config.core_ids.is_empty() {
+        return config.core_ids.clone();
+    }
+    match config.numa_node {
+        Some(numa_node) => {
+            let cores = numa_node_cores(numa_node);
+            if cores.is_empty() {
+                warn!(
+                    "Unable to find the cores of NUMA node {}, threads won't be pinned",
+                    numa_node
+                );
+            }
+            cores
+        }
+        None => vec![],
+    }
+}
+
+/// Returns the number of threads of a pool: as configured, or one per core it's pinned to, or
+/// one per core of the machine.
+pub fn num_threads(config: &ThreadPoolConfig) -> usize {
+    config.num_threads.unwrap_or_else(|| {
+        let cores = pinned_cores(config);
+        if cores.is_empty() {
+            num_cpus::get()
+        } else {
+            cores.len()
+        }
+    })
+}
+
+/// Builds a multi-threaded tokio runtime whose threads are named `{thread_name}-{id}`.
+pub fn spawn_named_runtime(
+    thread_name: &str,
+    config: &ThreadPoolConfig,
+) -> std::io::Result<Runtime> {
+    let num_threads = num_threads(config);
+    let cores = pinned_cores(config);
+    let thread_name = thread_name.to_string();
+    let runtime = Builder::new_multi_thread()
+        .thread_name_fn({
+            let thread_name = thread_name.clone();
+            let next_id = AtomicUsize::new(0);
+            move || format!("{}-{}", thread_name, next_id.fetch_add(1, Ordering::SeqCst))
+        })
+        .on_thread_start(move || pin_current_thread(&cores))
+        .worker_threads(num_threads)
+        .disable_lifo_slot()
+        .enable_all()
+        .build()?;
+
+    THREAD_POOL_NUM_THREADS
+        .with_label_values(&[thread_name.as_str()])
+        .set(num_threads as i64);
+    let queue_latency = THREAD_POOL_QUEUE_LATENCY.with_label_values(&[thread_name.as_str()]);
+    runtime.spawn(async move {
+        loop {
+            tokio::time::sleep(QUEUE_LATENCY_PROBE_INTERVAL).await;
+            let queued_at = Instant::now();
+            let queue_latency = queue_latency.clone();
+            let _ = tokio::spawn(async move {
+                queue_latency.observe(queued_at.elapsed().as_secs_f64());
+            })
+            .await;
+        }
+    });
+
+    Ok(runtime)
+}
+
+/// Builds a rayon thread pool whose threads are named `{thread_name}_{index}`.
+pub fn spawn_named_rayon_pool(
+    thread_name: &str,
+    config: &ThreadPoolConfig,
+) -> Result<Arc<rayon::ThreadPool>, rayon::ThreadPoolBuildError> {
+    let num_threads = num_threads(config);
+    let cores = pinned_cores(config);
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name({
+                let thread_name = thread_name.to_string();
+                move |index| format!("{}_{}", thread_name, index)
+            })
+            .start_handler(move |_| pin_current_thread(&cores))
+            .build()?,
+    );
+
+    THREAD_POOL_NUM_THREADS
+        .with_label_values(&[thread_name])
+        .set(num_threads as i64);
+    // Rayon pools don't run async tasks, so the probes are queued from a separate thread,
+    // which exits once the pool is dropped.
+    let weak_pool = Arc::downgrade(&pool);
+    let queue_latency = THREAD_POOL_QUEUE_LATENCY.with_label_values(&[thread_name]);
+    thread::Builder::new()
+        .name(format!("{}_probe", thread_name))
+        .spawn(move || loop {
+            thread::sleep(QUEUE_LATENCY_PROBE_INTERVAL);
+            let pool = match weak_pool.upgrade() {
+                Some(pool) => pool,
+                None => return,
+            };
+            let queued_at = Instant::now();
+            let queue_latency = queue_latency.clone();
+            pool.spawn(move || queue_latency.observe(queued_at.elapsed().as_secs_f64()));
+        })
+        .expect("Failed to spawn the thread pool probe thread");
+
+    Ok(pool)
+}
+
+#[cfg(target_os = "linux")]
+fn numa_node_cores(numa_node: usize) -> Vec<usize> {
+    let path = format!("/sys/devices/system/node/node{}/cpulist", numa_node);
+    std::fs::read_to_string(path)
+        .map(|cpu_list| parse_cpu_list(&cpu_list))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn numa_node_cores(_numa_node: usize) -> Vec<usize> {
+    vec![]
+}
+
+/// Node ids are listed in the same format as cpus, e.g., `0-1`
+#[cfg(target_os = "linux")]
+fn online_numa_nodes() -> Vec<usize> {
+    std::fs::read_to_string("/sys/devices/system/node/online")
+        .map(|node_list| parse_cpu_list(&node_list))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn online_numa_nodes() -> Vec<usize> {
+    vec![]
+}
+
+/// Parses a kernel cpu list, e.g., `0-3,8,10-11`.
+fn parse_cpu_list(cpu_list: &str) -> Vec<usize> {
+    let mut cores = vec![];
+    for range in cpu_list.trim().split(',').filter(|range| !range.is_empty()) {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        match (start.parse::<usize>(), end.parse::<usize>()) {
+            (Ok(start), Ok(end)) => cores.extend(start..=end),
+            _ => return vec![],
+        }
+    }
+    cores
+}
+
+#[cfg(target_os = "linux")]
+fn pin_current_thread(cores: &[usize]) {
+    if cores.is_empty() {
+        return;
+    }
+    // A cpu set only fits the cores below CPU_SETSIZE
+    let (cores, out_of_range): (Vec<usize>, Vec<usize>) = cores
+        .iter()
+        .partition(|core| **core < libc::CPU_SETSIZE as usize);
+    if !out_of_range.is_empty() {
+        warn!(
+            "Cores {:?} are out of range, thread {:?} won't be pinned to them",
+            out_of_range,
+            thread::current().name()
+        );
+        if cores.is_empty() {
+            return;
+        }
+    }
+    // Safe because the cpu set is zero initialized, only modified through the libc macros, and
+    // all the cores fit in it.
+    let result = unsafe {
+        let mut cpu_set: libc::cpu_set_t = std::mem::zeroed();
+        for core in &cores {
+            libc::CPU_SET(*core, &mut cpu_set);
+        }
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &cpu_set)
+    };
+    if result != 0 {
+        warn!(
+            "Failed to pin thread {:?} to cores {:?}: {}",
+            thread::current().name(),
+            cores,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(cores: &[usize]) {
+    if !cores.is_empty() {
+        warn!("Pinning threads to cores is only supported on linux");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert_eq!(parse_cpu_list(""), Vec::<usize>::new());
+        assert_eq!(parse_cpu_list("0-a"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_num_threads() {
+        let config = ThreadPoolConfig {
+            core_ids: vec![2, 3],
+            ..Default::default()
+        };
+        assert_eq!(num_threads(&config), 2);
+
+        let config = ThreadPoolConfig {
+            num_threads: Some(4),
+            core_ids: vec![2, 3],
+            ..Default::default()
+        };
+        assert_eq!(num_threads(&config), 4);
+
+        assert_eq!(num_threads(&ThreadPoolConfig::default()), num_cpus::get());
+    }
+
+    #[test]
+    fn test_pin_to_out_of_range_cores() {
+        // Cores that don't fit in a cpu set are skipped rather than overflowing it
+        thread::spawn(|| pin_current_thread(&[usize::MAX, 1 << 20]))
+            .join()
+            .unwrap();
+    }
+}
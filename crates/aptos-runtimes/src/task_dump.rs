@@ -0,0 +1,183 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Keeps track of the long running tokio tasks of the node so that a dump of them (names, poll
+//! counts and whether they're pending or being polled) can be captured when the node hangs.
+//! Only tasks spawned through `spawn_tracked` (or wrapped with `track`) are reported.
+
+use aptos_infallible::Mutex;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::task::JoinHandle;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(0);
+
+static TRACKED_TASKS: Lazy<Mutex<BTreeMap<u64, Arc<TaskState>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// The state of a tracked task, updated every time the task is polled.
+struct TaskState {
+    name: String,
+    spawned_at: Instant,
+    poll_count: AtomicU64,
+    polling: AtomicBool,
+    /// Microseconds since `spawned_at` at which the last poll started
+    last_poll_started_micros: AtomicU64,
+    /// Microseconds since `spawned_at` at which the last poll ended
+    last_poll_ended_micros: AtomicU64,
+}
+
+impl TaskState {
+    fn elapsed_micros(&self) -> u64 {
+        self.spawned_at.elapsed().as_micros() as u64
+    }
+}
+
+/// Whether a task is currently being polled or is waiting to be woken up.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Polling,
+    Pending,
+}
+
+/// A snapshot of a tracked task.
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskDump {
+    pub id: u64,
+    pub name: String,
+    pub status: TaskStatus,
+    pub poll_count: u64,
+    pub age_secs: f64,
+    /// How long the task has been in its current status
+    pub status_duration_secs: f64,
+}
+
+/// A future that records its polls in the task dump, and removes itself from it once dropped.
+pub struct Tracked<F> {
+    id: u64,
+    state: Arc<TaskState>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Tracked<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let state = self.state.clone();
+        state
+            .last_poll_started_micros
+            .store(state.elapsed_micros(), Ordering::Relaxed);
+        state.polling.store(true, Ordering::Relaxed);
+        state.poll_count.fetch_add(1, Ordering::Relaxed);
+
+        let result = self.future.as_mut().poll(cx);
+
+        state
+            .last_poll_ended_micros
+            .store(state.elapsed_micros(), Ordering::Relaxed);
+        state.polling.store(false, Ordering::Relaxed);
+        result
+    }
+}
+
+impl<F> Drop for Tracked<F> {
+    fn drop(&mut self) {
+        TRACKED_TASKS.lock().remove(&self.id);
+    }
+}
+
+/// Wraps the future so that it's reported in the task dump under the given name.
+pub fn track<F: Future>(name: impl Into<String>, future: F) -> Tracked<F> {
+    let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+    let state = Arc::new(TaskState {
+        name: name.into(),
+        spawned_at: Instant::now(),
+        poll_count: AtomicU64::new(0),
+        polling: AtomicBool::new(false),
+        last_poll_started_micros: AtomicU64::new(0),
+        last_poll_ended_micros: AtomicU64::new(0),
+    });
+    TRACKED_TASKS.lock().insert(id, state.clone());
+    Tracked {
+        id,
+        state,
+        future: Box::pin(future),
+    }
+}
+
+/// Spawns the future on the current tokio runtime and reports it in the task dump.
+pub fn spawn_tracked<F>(name: impl Into<String>, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(track(name, future))
+}
+
+/// Returns a snapshot of all the tracked tasks that are still alive, ordered by spawn time.
+pub fn task_dump() -> Vec<TaskDump> {
+    let tasks = TRACKED_TASKS.lock();
+    tasks
+        .iter()
+        .map(|(id, state)| {
+            let now_micros = state.elapsed_micros();
+            let (status, status_since_micros) = if state.polling.load(Ordering::Relaxed) {
+                (
+                    TaskStatus::Polling,
+                    state.last_poll_started_micros.load(Ordering::Relaxed),
+                )
+            } else {
+                (
+                    TaskStatus::Pending,
+                    state.last_poll_ended_micros.load(Ordering::Relaxed),
+                )
+            };
+            TaskDump {
+                id: *id,
+                name: state.name.clone(),
+                status,
+                poll_count: state.poll_count.load(Ordering::Relaxed),
+                age_secs: now_micros as f64 / 1_000_000.0,
+                status_duration_secs: now_micros.saturating_sub(status_since_micros) as f64
+                    / 1_000_000.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_task_dump() {
+        let (sender, receiver) = tokio::sync::oneshot::channel::<()>();
+        let handle = spawn_tracked("test-task", async move {
+            let _ = receiver.await;
+        });
+        tokio::task::yield_now().await;
+
+        let task = task_dump()
+            .into_iter()
+            .find(|task| task.name == "test-task")
+            .unwrap();
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert!(task.poll_count >= 1);
+
+        sender.send(()).unwrap();
+        handle.await.unwrap();
+        assert!(task_dump().iter().all(|task| task.name != "test-task"));
+    }
+}
@@ -0,0 +1,429 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Captures the backtraces of all the threads of the node, to diagnose hangs without having to
+//! attach a debugger. Every thread is interrupted in turn with a signal. The handler only
+//! records the registers of the thread in atomics and waits, while the dumping thread walks the
+//! frame pointers of the paused stack. The node is built with frame pointers (see
+//! `.cargo/config.toml`), and the walk stays within the memory mapping of the stack, so a
+//! corrupt frame can't make the dump fault.
+
+use serde::Serialize;
+use std::fmt;
+
+/// A snapshot of a thread of the node.
+#[derive(Clone, Debug, Serialize)]
+pub struct ThreadDump {
+    pub tid: u32,
+    pub name: String,
+    /// The state reported by the kernel, e.g., `R` (running), `S` (sleeping) or `D` (disk sleep)
+    pub state: String,
+    /// The symbolized frames of the thread, if its stack could be captured
+    pub backtrace: Option<Vec<String>>,
+}
+
+impl fmt::Display for ThreadDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Thread {} \"{}\" ({})", self.tid, self.name, self.state)?;
+        match &self.backtrace {
+            Some(frames) => {
+                for (index, frame) in frames.iter().enumerate() {
+                    writeln!(f, "  #{:<3} {}", index, frame)?;
+                }
+            }
+            None => writeln!(f, "  <backtrace unavailable>")?,
+        }
+        Ok(())
+    }
+}
+
+/// Returns a dump of all the threads of the node, formatted as text.
+pub fn formatted_thread_dump() -> String {
+    thread_dump()
+        .iter()
+        .map(|thread| thread.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::thread_dump;
+
+/// Thread dumps are only supported on linux.
+#[cfg(not(target_os = "linux"))]
+pub fn thread_dump() -> Vec<ThreadDump> {
+    vec![]
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::ThreadDump;
+    use aptos_infallible::Mutex;
+    use once_cell::sync::Lazy;
+    use std::{
+        sync::{
+            atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering},
+            Once,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    /// The maximum number of frames captured per thread
+    const MAX_FRAMES: usize = 128;
+    /// How long to wait for a thread to record its registers before giving up on it
+    const CAPTURE_TIMEOUT: Duration = Duration::from_millis(100);
+    /// How long a thread waits in the signal handler for its stack to be walked
+    const PAUSE_TIMEOUT_NANOS: i64 = 1_000_000_000;
+
+    /// Only one dump can be captured at a time, as the signal handler writes to shared slots.
+    static DUMP_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+    static INSTALL_HANDLER: Once = Once::new();
+
+    /// The thread that's expected to record its registers, or zero if none is
+    static TARGET_TID: AtomicI32 = AtomicI32::new(0);
+    static CAPTURED: AtomicBool = AtomicBool::new(false);
+    /// Set once the stack of the paused thread was walked, so it can resume
+    static RESUME: AtomicBool = AtomicBool::new(false);
+    /// The program counter, stack pointer and frame pointer of the interrupted thread
+    static PC: AtomicUsize = AtomicUsize::new(0);
+    static SP: AtomicUsize = AtomicUsize::new(0);
+    static FP: AtomicUsize = AtomicUsize::new(0);
+
+    fn dump_signal() -> libc::c_int {
+        libc::SIGRTMIN() + 4
+    }
+
+    fn gettid() -> i32 {
+        // Safe because gettid has no side effects and can't fail.
+        unsafe { libc::syscall(libc::SYS_gettid) as i32 }
+    }
+
+    /// Returns the monotonic clock in nanoseconds. clock_gettime is async signal safe.
+    fn monotonic_nanos() -> i64 {
+        let mut time = libc::timespec {
+            tv_sec: 0,
+            tv_nsec: 0,
+        };
+        // Safe because the timespec is valid for writes.
+        unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+        time.tv_sec as i64 * 1_000_000_000 + time.tv_nsec as i64
+    }
+
+    /// Returns the program counter, stack pointer and frame pointer of the interrupted context.
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn registers(context: *const libc::ucontext_t) -> Option<(usize, usize, usize)> {
+        let gregs = &(*context).uc_mcontext.gregs;
+        Some((
+            gregs[libc::REG_RIP as usize] as usize,
+            gregs[libc::REG_RSP as usize] as usize,
+            gregs[libc::REG_RBP as usize] as usize,
+        ))
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn registers(context: *const libc::ucontext_t) -> Option<(usize, usize, usize)> {
+        let mcontext = &(*context).uc_mcontext;
+        Some((
+            mcontext.pc as usize,
+            mcontext.sp as usize,
+            mcontext.regs[29] as usize,
+        ))
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    unsafe fn registers(_context: *const libc::ucontext_t) -> Option<(usize, usize, usize)> {
+        None
+    }
+
+    /// Records the registers of the interrupted thread, and keeps it paused until its stack is
+    /// walked. This only touches atomics and calls clock_gettime, so it's async signal safe: it
+    /// doesn't allocate or take locks.
+    extern "C" fn capture_stack(
+        _signal: libc::c_int,
+        _info: *mut libc::siginfo_t,
+        context: *mut libc::c_void,
+    ) {
+        if TARGET_TID.load(Ordering::Acquire) != gettid() {
+            return;
+        }
+        // Safe because the kernel passes the context of the interrupted thread.
+        let (pc, sp, fp) = match unsafe { registers(context as *const libc::ucontext_t) } {
+            Some(registers) => registers,
+            None => return,
+        };
+        PC.store(pc, Ordering::Relaxed);
+        SP.store(sp, Ordering::Relaxed);
+        FP.store(fp, Ordering::Relaxed);
+        CAPTURED.store(true, Ordering::Release);
+
+        let started = monotonic_nanos();
+        while !RESUME.load(Ordering::Acquire) && monotonic_nanos() - started < PAUSE_TIMEOUT_NANOS {
+            std::hint::spin_loop();
+        }
+    }
+
+    fn install_handler() {
+        INSTALL_HANDLER.call_once(|| {
+            // Safe because the action is zero initialized and only set through libc.
+            let result = unsafe {
+                let mut action: libc::sigaction = std::mem::zeroed();
+                action.sa_sigaction = capture_stack as usize;
+                action.sa_flags = libc::SA_RESTART | libc::SA_SIGINFO;
+                libc::sigemptyset(&mut action.sa_mask);
+                libc::sigaction(dump_signal(), &action, std::ptr::null_mut())
+            };
+            if result != 0 {
+                aptos_logger::warn!(
+                    "Failed to install the thread dump signal handler: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+        });
+    }
+
+    /// Symbolizes the given instruction pointer, e.g., `0x55d0c0ffee foo::bar at bar.rs:12`.
+    fn symbolize(ip: usize) -> String {
+        let mut frame = format!("{:#x}", ip);
+        backtrace::resolve(ip as *mut std::ffi::c_void, |symbol| {
+            if let Some(name) = symbol.name() {
+                frame.push_str(&format!(" {:#}", name));
+            }
+            if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
+                frame.push_str(&format!(" at {}:{}", file.display(), line));
+            }
+        });
+        frame
+    }
+
+    /// Returns the address ranges of the readable memory mappings of the node.
+    fn readable_mappings() -> Vec<(usize, usize)> {
+        std::fs::read_to_string("/proc/self/maps")
+            .map(|maps| {
+                maps.lines()
+                    .filter_map(|line| {
+                        let mut fields = line.split_whitespace();
+                        let (start, end) = fields.next()?.split_once('-')?;
+                        if !fields.next()?.starts_with('r') {
+                            return None;
+                        }
+                        Some((
+                            usize::from_str_radix(start, 16).ok()?,
+                            usize::from_str_radix(end, 16).ok()?,
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Walks the frame pointers of a paused stack, collecting the return addresses in `frames`,
+    /// which must have room for them so that the walk doesn't allocate. Each frame holds the
+    /// previous frame pointer followed by the return address. The frames must be within the
+    /// mapping of the stack and grow towards its base, so the walk only reads mapped memory, and
+    /// ends.
+    fn walk_frames(
+        frames: &mut Vec<usize>,
+        pc: usize,
+        sp: usize,
+        mut fp: usize,
+        mappings: &[(usize, usize)],
+    ) {
+        frames.push(pc);
+        let stack_end = match mappings
+            .iter()
+            .find(|(start, end)| *start <= sp && sp < *end)
+        {
+            Some((_, end)) => *end,
+            None => return,
+        };
+        let word = std::mem::size_of::<usize>();
+        while frames.len() < MAX_FRAMES
+            && fp >= sp
+            && fp % word == 0
+            && fp.saturating_add(2 * word) <= stack_end
+        {
+            // Safe because the frame is within the mapping of the stack, and the thread it
+            // belongs to is paused.
+            let (next_fp, return_address) = unsafe {
+                (
+                    std::ptr::read_volatile(fp as *const usize),
+                    std::ptr::read_volatile((fp + word) as *const usize),
+                )
+            };
+            if return_address == 0 {
+                break;
+            }
+            frames.push(return_address);
+            if next_fp <= fp {
+                break;
+            }
+            fp = next_fp;
+        }
+    }
+
+    /// Interrupts the given thread, and walks its stack while it's paused.
+    fn capture_thread(tid: i32) -> Option<Vec<String>> {
+        // Allocate before pausing the thread, as it may hold a lock of the allocator
+        let mappings = readable_mappings();
+        let mut frames = Vec::with_capacity(MAX_FRAMES);
+        CAPTURED.store(false, Ordering::Release);
+        RESUME.store(false, Ordering::Release);
+        TARGET_TID.store(tid, Ordering::Release);
+        // Safe because tgkill only delivers a signal, for which a handler is installed.
+        let result = unsafe {
+            libc::syscall(
+                libc::SYS_tgkill,
+                libc::getpid(),
+                tid,
+                dump_signal() as libc::c_long,
+            )
+        };
+        let captured = result == 0 && {
+            let started = Instant::now();
+            while !CAPTURED.load(Ordering::Acquire) && started.elapsed() < CAPTURE_TIMEOUT {
+                thread::yield_now();
+            }
+            CAPTURED.load(Ordering::Acquire)
+        };
+        TARGET_TID.store(0, Ordering::Release);
+        if captured {
+            walk_frames(
+                &mut frames,
+                PC.load(Ordering::Relaxed),
+                SP.load(Ordering::Relaxed),
+                FP.load(Ordering::Relaxed),
+                &mappings,
+            );
+        }
+        RESUME.store(true, Ordering::Release);
+        if !captured {
+            return None;
+        }
+
+        // Symbolizing allocates, so it's done once the thread resumed
+        Some(frames.into_iter().map(symbolize).collect())
+    }
+
+    /// Reads the name and state of the thread from procfs.
+    fn thread_status(tid: i32) -> (String, String) {
+        let task = format!("/proc/self/task/{}", tid);
+        let name = std::fs::read_to_string(format!("{}/comm", task))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_default();
+        // The state follows the name, which is in parentheses and may contain spaces.
+        let state = std::fs::read_to_string(format!("{}/stat", task))
+            .ok()
+            .and_then(|stat| {
+                let (_, fields) = stat.rsplit_once(')')?;
+                fields.split_whitespace().next().map(str::to_string)
+            })
+            .unwrap_or_default();
+        (name, state)
+    }
+
+    /// Returns a dump of all the threads of the node, ordered by thread id.
+    pub fn thread_dump() -> Vec<ThreadDump> {
+        let _lock = DUMP_LOCK.lock();
+        install_handler();
+
+        let mut tids: Vec<i32> = match std::fs::read_dir("/proc/self/task") {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+                .collect(),
+            Err(error) => {
+                aptos_logger::warn!("Failed to list the threads of the node: {}", error);
+                return vec![];
+            }
+        };
+        tids.sort_unstable();
+
+        let current_tid = gettid();
+        tids.into_iter()
+            .map(|tid| {
+                let (name, state) = thread_status(tid);
+                let backtrace = if tid == current_tid {
+                    let mut frames = vec![];
+                    backtrace::trace(|frame| {
+                        frames.push(symbolize(frame.ip() as usize));
+                        frames.len() < MAX_FRAMES
+                    });
+                    Some(frames)
+                } else {
+                    capture_thread(tid)
+                };
+                ThreadDump {
+                    tid: tid as u32,
+                    name,
+                    state,
+                    backtrace,
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::sync::mpsc;
+
+        #[test]
+        fn test_walk_frames() {
+            // A stack of three frames, each holding the previous frame pointer and the return
+            // address. The outermost frame has no previous frame.
+            let word = std::mem::size_of::<usize>();
+            let mut stack = [0usize; 8];
+            let base = stack.as_ptr() as usize;
+            stack[2] = base + 4 * word;
+            stack[3] = 0x1000;
+            stack[4] = base + 6 * word;
+            stack[5] = 0x2000;
+            stack[7] = 0x3000;
+
+            let mut frames = Vec::with_capacity(MAX_FRAMES);
+            let mappings = [(base, base + stack.len() * word)];
+            walk_frames(&mut frames, 0x500, base, base + 2 * word, &mappings);
+            assert_eq!(frames, vec![0x500, 0x1000, 0x2000, 0x3000]);
+
+            // Frames outside of the mapping of the stack aren't read
+            let mut frames = Vec::with_capacity(MAX_FRAMES);
+            let mappings = [(base, base + 4 * word)];
+            walk_frames(&mut frames, 0x500, base, base + 2 * word, &mappings);
+            assert_eq!(frames, vec![0x500, 0x1000]);
+
+            // Neither is a stack that isn't mapped
+            let mut frames = Vec::with_capacity(MAX_FRAMES);
+            walk_frames(&mut frames, 0x500, base, base + 2 * word, &[]);
+            assert_eq!(frames, vec![0x500]);
+        }
+
+        #[test]
+        fn test_thread_dump() {
+            let (sender, receiver) = mpsc::channel::<()>();
+            let handle = thread::Builder::new()
+                .name("dump-target".into())
+                .spawn(move || receiver.recv())
+                .unwrap();
+
+            // Wait for the thread to show up before dumping it
+            let started = Instant::now();
+            let dump = loop {
+                let dump = thread_dump();
+                if dump.iter().any(|thread| thread.name == "dump-target")
+                    || started.elapsed() > Duration::from_secs(5)
+                {
+                    break dump;
+                }
+                thread::sleep(Duration::from_millis(10));
+            };
+            let target = dump
+                .iter()
+                .find(|thread| thread.name == "dump-target")
+                .unwrap();
+            assert!(!target.backtrace.as_ref().unwrap().is_empty());
+
+            sender.send(()).unwrap();
+            handle.join().unwrap().unwrap();
+        }
+    }
+}
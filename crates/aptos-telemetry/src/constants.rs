@@ -14,6 +14,7 @@ pub(crate) const ENV_APTOS_DISABLE_TELEMETRY_PUSH_EVENTS: &str =
 pub(crate) const ENV_APTOS_DISABLE_PROMETHEUS_NODE_METRICS: &str =
     "APTOS_DISABLE_PROMETHEUS_NODE_METRICS";
 pub(crate) const ENV_APTOS_DISABLE_LOG_ENV_POLLING: &str = "APTOS_DISABLE_LOG_ENV_POLLING";
+pub(crate) const ENV_APTOS_TELEMETRY_LOCAL_DIR: &str = "APTOS_TELEMETRY_LOCAL_DIR";
 
 pub(crate) const ENV_GA_MEASUREMENT_ID: &str = "GA_MEASUREMENT_ID";
 pub(crate) const ENV_GA_API_SECRET: &str = "GA_API_SECRET";
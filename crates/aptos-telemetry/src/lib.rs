@@ -5,6 +5,7 @@
 
 mod constants;
 mod core_metrics;
+mod local_snapshot;
 mod metrics;
 mod network_metrics;
 mod sender;
@@ -0,0 +1,135 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Local-only telemetry, for deployments that can't reach the telemetry service (e.g.
+//! air-gapped networks). Instead of being pushed, snapshots are periodically written
+//! to the directory set by `APTOS_TELEMETRY_LOCAL_DIR`:
+//! * `metrics.prom`: all the Prometheus metrics of the node, in the Prometheus text
+//!   exposition format. The file can be collected by (e.g.) the textfile collector
+//!   of the Prometheus node exporter.
+//! * `build_info.json`: a JSON object holding the build information of the node, along
+//!   with the `peer_id` and `chain_id` of the node and the `timestamp_micros` of the snapshot.
+//!
+//! Each snapshot replaces the previous one atomically, so readers never see a partial file.
+
+use anyhow::Context;
+use prometheus::Registry;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The file holding the metrics snapshot
+pub(crate) const METRICS_SNAPSHOT_FILE: &str = "metrics.prom";
+/// The file holding the build information snapshot
+pub(crate) const BUILD_INFO_SNAPSHOT_FILE: &str = "build_info.json";
+
+// Keys added to the build information snapshot
+const CHAIN_ID_KEY: &str = "chain_id";
+const PEER_ID_KEY: &str = "peer_id";
+const TIMESTAMP_MICROS_KEY: &str = "timestamp_micros";
+
+/// Writes telemetry snapshots to a local directory
+#[derive(Clone, Debug)]
+pub(crate) struct LocalSnapshotWriter {
+    directory: PathBuf,
+}
+
+impl LocalSnapshotWriter {
+    /// Creates a writer for the given directory, creating the directory if it doesn't exist
+    pub(crate) fn new(directory: PathBuf) -> Result<Self, anyhow::Error> {
+        fs::create_dir_all(&directory).with_context(|| {
+            format!(
+                "Failed to create the telemetry directory {}",
+                directory.display()
+            )
+        })?;
+        Ok(Self { directory })
+    }
+
+    /// Writes a snapshot of all the metrics of the given registry
+    pub(crate) fn write_metrics(&self, registry: &Registry) -> Result<(), anyhow::Error> {
+        let scraped_metrics =
+            prometheus::TextEncoder::new().encode_to_string(&registry.gather())?;
+        self.write_atomically(METRICS_SNAPSHOT_FILE, scraped_metrics.as_bytes())
+    }
+
+    /// Writes a snapshot of the build information of the node
+    pub(crate) fn write_build_info(
+        &self,
+        peer_id: String,
+        chain_id: String,
+        mut build_info: BTreeMap<String, String>,
+    ) -> Result<(), anyhow::Error> {
+        let timestamp_micros = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros();
+        build_info.insert(PEER_ID_KEY.into(), peer_id);
+        build_info.insert(CHAIN_ID_KEY.into(), chain_id);
+        build_info.insert(TIMESTAMP_MICROS_KEY.into(), timestamp_micros.to_string());
+        self.write_atomically(
+            BUILD_INFO_SNAPSHOT_FILE,
+            &serde_json::to_vec_pretty(&build_info)?,
+        )
+    }
+
+    /// Writes the file through a temporary file that's renamed into place
+    fn write_atomically(&self, file_name: &str, contents: &[u8]) -> Result<(), anyhow::Error> {
+        let path = self.directory.join(file_name);
+        let temp_path = self.directory.join(format!(".{}.tmp", file_name));
+        fs::write(&temp_path, contents)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    pub(crate) fn directory(&self) -> &Path {
+        &self.directory
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+    use prometheus::{register_int_counter_vec_with_registry, Registry};
+
+    #[test]
+    fn test_write_snapshots() {
+        let temp_dir = TempPath::new();
+        let writer = LocalSnapshotWriter::new(temp_dir.path().join("telemetry")).unwrap();
+
+        // Write a metrics snapshot
+        let test_registry = Registry::default();
+        register_int_counter_vec_with_registry!(
+            "test_metric",
+            "Test metric",
+            &["type"],
+            test_registry
+        )
+        .unwrap()
+        .with_label_values(&["test"])
+        .inc();
+        writer.write_metrics(&test_registry).unwrap();
+        let metrics = fs::read_to_string(writer.directory().join(METRICS_SNAPSHOT_FILE)).unwrap();
+        assert!(metrics.contains("test_metric{type=\"test\"} 1"));
+
+        // Write a build information snapshot
+        let mut build_info = BTreeMap::new();
+        build_info.insert("build_commit_hash".to_string(), "abc".to_string());
+        writer
+            .write_build_info("peer".into(), "testing".into(), build_info)
+            .unwrap();
+        let build_info: BTreeMap<String, String> = serde_json::from_slice(
+            &fs::read(writer.directory().join(BUILD_INFO_SNAPSHOT_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(build_info.get("build_commit_hash").unwrap(), "abc");
+        assert_eq!(build_info.get(PEER_ID_KEY).unwrap(), "peer");
+        assert_eq!(build_info.get(CHAIN_ID_KEY).unwrap(), "testing");
+        assert!(build_info.contains_key(TIMESTAMP_MICROS_KEY));
+    }
+}
@@ -16,6 +16,8 @@ const NETWORK_INBOUND_TRAFFIC_SUM: &str = "network_inbound_traffic_sum";
 const NETWORK_OUTBOUND_CONNECTIONS: &str = "network_outbound_connections";
 const NETWORK_OUTBOUND_MESSAGE_SUM: &str = "network_outbound_message_sum";
 const NETWORK_OUTBOUND_TRAFFIC_SUM: &str = "network_outbound_traffic_sum";
+const PEER_BUILD_VERSION_PREFIX: &str = "peer_build_version.";
+const PEER_GAS_FEATURE_VERSION_PREFIX: &str = "peer_gas_feature_version.";
 
 /// Collects and sends the build information via telemetry
 pub(crate) async fn create_network_metric_telemetry_event() -> TelemetryEvent {
@@ -40,6 +42,7 @@ pub fn get_network_metrics() -> BTreeMap<String, String> {
 fn collect_network_metrics(network_metrics: &mut BTreeMap<String, String>) {
     collect_connection_metrics(network_metrics);
     collect_message_and_traffic_metrics(network_metrics);
+    collect_peer_version_metrics(network_metrics);
 }
 
 /// Collects the connection metrics and appends them to the given map
@@ -105,3 +108,24 @@ fn collect_message_and_traffic_metrics(network_metrics: &mut BTreeMap<String, St
         network_outbound_traffic_sum.to_string(),
     );
 }
+
+/// Collects the number of connected peers per build and per supported gas feature version, as
+/// gossiped by the peer monitoring service, and appends them to the given map
+fn collect_peer_version_metrics(network_metrics: &mut BTreeMap<String, String>) {
+    for (build_version, peer_count) in
+        aptos_peer_monitoring_service_client::node_information::peer_build_versions()
+    {
+        network_metrics.insert(
+            format!("{}{}", PEER_BUILD_VERSION_PREFIX, build_version),
+            peer_count.to_string(),
+        );
+    }
+    for (gas_feature_version, peer_count) in
+        aptos_peer_monitoring_service_client::node_information::peer_gas_feature_versions()
+    {
+        network_metrics.insert(
+            format!("{}{}", PEER_GAS_FEATURE_VERSION_PREFIX, gas_feature_version),
+            peer_count.to_string(),
+        );
+    }
+}
@@ -12,6 +12,7 @@ use aptos_telemetry_service::types::telemetry::{TelemetryDump, TelemetryEvent};
 use aptos_types::chain_id::{ChainId, NamedChain};
 use futures::channel::mpsc::{self, Receiver};
 use once_cell::sync::Lazy;
+use prometheus::default_registry;
 use rand::Rng;
 use rand_core::OsRng;
 use serde::Deserialize;
@@ -19,6 +20,7 @@ use std::{
     collections::BTreeMap,
     env,
     future::Future,
+    path::PathBuf,
     sync::atomic::{AtomicUsize, Ordering},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -30,7 +32,8 @@ use tokio::{
 use uuid::Uuid;
 
 use crate::{
-    constants::*, core_metrics::create_core_metric_telemetry_event, metrics,
+    constants::*, core_metrics::create_core_metric_telemetry_event,
+    local_snapshot::LocalSnapshotWriter, metrics,
     network_metrics::create_network_metric_telemetry_event, sender::TelemetrySender,
     system_information::create_system_info_telemetry_event,
     telemetry_log_sender::TelemetryLogSender, utils::create_build_info_telemetry_event,
@@ -94,6 +97,15 @@ fn enable_push_custom_events() -> bool {
         || !(telemetry_is_disabled() || env::var(ENV_APTOS_DISABLE_TELEMETRY_PUSH_EVENTS).is_ok())
 }
 
+/// Returns the directory to write telemetry snapshots to, iff telemetry
+/// is in local-only mode
+#[inline]
+fn local_telemetry_directory() -> Option<PathBuf> {
+    env::var(ENV_APTOS_TELEMETRY_LOCAL_DIR)
+        .ok()
+        .map(PathBuf::from)
+}
+
 #[inline]
 fn enable_log_env_polling() -> bool {
     force_enable_telemetry()
@@ -113,8 +125,10 @@ pub fn start_telemetry_service(
         aptos_node_resource_metrics::register_node_metrics_collector();
     }
 
-    // Don't start the service if telemetry has been disabled
-    if telemetry_is_disabled() {
+    // Don't start the service if telemetry has been disabled (local-only
+    // telemetry is opted into explicitly, so it's still started).
+    let local_telemetry_directory = local_telemetry_directory();
+    if telemetry_is_disabled() && local_telemetry_directory.is_none() {
         warn!("Aptos telemetry is disabled!");
         return None;
     }
@@ -131,17 +145,76 @@ pub fn start_telemetry_service(
         .build()
         .expect("Failed to create the Aptos Telemetry runtime!");
 
-    telemetry_runtime.handle().spawn(spawn_telemetry_service(
-        node_config,
-        chain_id,
-        build_info,
-        remote_log_rx,
-        logger_filter_update_job,
-    ));
+    if let Some(local_telemetry_directory) = local_telemetry_directory {
+        telemetry_runtime
+            .handle()
+            .spawn(spawn_local_telemetry_service(
+                local_telemetry_directory,
+                node_config,
+                chain_id,
+                build_info,
+                logger_filter_update_job,
+            ));
+    } else {
+        telemetry_runtime.handle().spawn(spawn_telemetry_service(
+            node_config,
+            chain_id,
+            build_info,
+            remote_log_rx,
+            logger_filter_update_job,
+        ));
+    }
 
     Some(telemetry_runtime)
 }
 
+/// Spawns the local-only telemetry service, which writes snapshots of the
+/// metrics and build information to the given directory instead of sending
+/// them. Logs and custom events are not collected in this mode.
+async fn spawn_local_telemetry_service(
+    directory: PathBuf,
+    node_config: NodeConfig,
+    chain_id: ChainId,
+    build_info: BTreeMap<String, String>,
+    logger_filter_update_job: Option<LoggerFilterUpdater>,
+) {
+    let snapshot_writer = match LocalSnapshotWriter::new(directory) {
+        Ok(snapshot_writer) => snapshot_writer,
+        Err(error) => {
+            error!("Failed to start the local telemetry service: {:?}", error);
+            return;
+        }
+    };
+
+    // Run the logger filter update job within the telemetry runtime.
+    if let Some(job) = logger_filter_update_job {
+        tokio::spawn(job.run());
+    }
+
+    info!(
+        "Local telemetry service started! Writing snapshots to {}",
+        snapshot_writer.directory().display()
+    );
+
+    let peer_id = fetch_peer_id(&node_config);
+    futures::future::join(
+        // Periodically write all prometheus metrics
+        run_function_periodically(PROMETHEUS_PUSH_METRICS_FREQ_SECS, || {
+            write_metrics_snapshot(&snapshot_writer)
+        }),
+        // Periodically write the build information
+        run_function_periodically(NODE_BUILD_INFO_FREQ_SECS, || {
+            write_build_info_snapshot(
+                &snapshot_writer,
+                peer_id.clone(),
+                chain_id.to_string(),
+                build_info.clone(),
+            )
+        }),
+    )
+    .await;
+}
+
 async fn spawn_telemetry_service(
     node_config: NodeConfig,
     chain_id: ChainId,
@@ -201,6 +274,28 @@ async fn spawn_telemetry_service(
     info!("Telemetry service started!");
 }
 
+/// Writes a snapshot of all prometheus metrics to the local telemetry directory
+async fn write_metrics_snapshot(snapshot_writer: &LocalSnapshotWriter) {
+    if let Err(error) = snapshot_writer.write_metrics(default_registry()) {
+        warn!("Failed to write the metrics snapshot: {:?}", error);
+    }
+}
+
+/// Writes a snapshot of the build information to the local telemetry directory
+async fn write_build_info_snapshot(
+    snapshot_writer: &LocalSnapshotWriter,
+    peer_id: String,
+    chain_id: String,
+    build_info: BTreeMap<String, String>,
+) {
+    if let Err(error) = snapshot_writer.write_build_info(peer_id, chain_id, build_info) {
+        warn!(
+            "Failed to write the build information snapshot: {:?}",
+            error
+        );
+    }
+}
+
 fn try_spawn_log_env_poll_task(sender: TelemetrySender) {
     if enable_log_env_polling() {
         tokio::spawn(async move {
@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliError, CliTypedResult, TransactionOptions, TransactionSummary,
+};
+use crate::common::utils::read_from_file;
+use crate::genesis::git::from_yaml;
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_types::transaction::TransactionPayload;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, str::FromStr};
+
+/// Submit a batch of transactions calling entry functions
+///
+/// The transactions are listed in a YAML file, and are signed with consecutive sequence numbers
+/// then submitted concurrently.  e.g.
+///
+/// - function_id: 0x1::aptos_account::transfer
+///   args: ["address:0x42", "u64:100"]
+#[derive(Debug, Parser)]
+pub struct BatchSubmit {
+    /// YAML file listing the transactions to submit
+    ///
+    /// Each one has a `function_id`, `args` and `type_args` in the same format as for
+    /// `aptos move run`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) file: PathBuf,
+
+    /// Maximum number of transactions pending at any time
+    #[clap(long, default_value_t = 16)]
+    pub(crate) max_in_flight: usize,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+/// An entry function call of a batch
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BatchTransaction {
+    pub function_id: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub type_args: Vec<String>,
+}
+
+impl BatchTransaction {
+    fn payload(&self) -> CliTypedResult<TransactionPayload> {
+        let function_id = MemberId::from_str(&self.function_id)?;
+        let args = self
+            .args
+            .iter()
+            .map(|arg| ArgWithType::from_str(arg))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        let type_args = self
+            .type_args
+            .iter()
+            .map(|type_arg| {
+                MoveType::from_str(type_arg)
+                    .map_err(|err| CliError::UnableToParse("type_args", err.to_string()))
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        entry_function_payload(function_id, args, type_args)
+    }
+}
+
+/// The outcome of a transaction of a batch
+#[derive(Clone, Debug, Serialize)]
+pub struct BatchTransactionResult {
+    pub function_id: String,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<BatchTransactionResult>> for BatchSubmit {
+    fn command_name(&self) -> &'static str {
+        "BatchSubmit"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<BatchTransactionResult>> {
+        if self.max_in_flight == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--max-in-flight must be at least 1".to_string(),
+            ));
+        }
+        let batch: Vec<BatchTransaction> =
+            from_yaml(&String::from_utf8(read_from_file(&self.file)?)?)?;
+        let payloads = batch
+            .iter()
+            .enumerate()
+            .map(|(index, txn)| {
+                txn.payload().map_err(|err| {
+                    CliError::CommandArgumentError(format!("Transaction {}: {}", index, err))
+                })
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+
+        let results = self
+            .txn_options
+            .submit_transactions(payloads, self.max_in_flight)
+            .await?;
+        Ok(batch
+            .into_iter()
+            .zip(results)
+            .map(|(txn, result)| match result {
+                Ok(transaction) => BatchTransactionResult {
+                    function_id: txn.function_id,
+                    transaction: Some(TransactionSummary::from(transaction)),
+                    error: None,
+                },
+                Err(err) => BatchTransactionResult {
+                    function_id: txn.function_id,
+                    transaction: None,
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect())
+    }
+}
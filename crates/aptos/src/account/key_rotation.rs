@@ -6,10 +6,13 @@ use crate::common::{
     types::{
         CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, EncodingOptions,
         EncodingType, ExtractPublicKey, ParsePrivateKey, ProfileConfig, ProfileOptions,
-        PublicKeyInputOptions, RestOptions, RotationProofChallenge, TransactionOptions,
+        PublicKeyInputOptions, RestOptions, RngArgs, RotationProofChallenge, TransactionOptions,
         TransactionSummary,
     },
-    utils::{prompt_yes_with_override, read_line},
+    utils::{
+        check_if_file_exists, create_dir_if_not_exist, prompt_yes_with_override, read_line,
+        write_to_user_only_file,
+    },
 };
 use aptos_cached_packages::aptos_stdlib;
 use aptos_crypto::{
@@ -31,10 +34,11 @@ use std::{collections::BTreeMap, path::PathBuf};
 /// Rotate an account's authentication key
 ///
 /// Rotating the account's authentication key allows you to use a new
-/// private key.  You must provide a new private key.  Once it is
+/// private key.  The new private key is either provided, or generated.  Once it is
 /// rotated you will need to use the original account address, with the
 /// new private key.  There is an interactive prompt to help you add it
-/// to a new profile.
+/// to a profile, and the on-chain lookup of the original address by the new key is
+/// verified.
 #[derive(Debug, Parser)]
 pub struct RotateKey {
     #[clap(flatten)]
@@ -45,13 +49,20 @@ pub struct RotateKey {
     pub(crate) new_private_key_file: Option<PathBuf>,
 
     /// New private key encoded in the type from `--encoding`
+    ///
+    /// If neither this nor `--new-private-key-file` is given, a new key is generated, and it
+    /// has to be saved to a profile
     #[clap(long, group = "new_private_key")]
     pub(crate) new_private_key: Option<String>,
 
+    #[clap(flatten)]
+    pub(crate) rng_args: RngArgs,
+
     /// Name of the profile to save the new private key
     ///
     /// If not provided, it will interactively have you save a profile,
-    /// unless `--skip_saving_profile` is provided
+    /// unless `--skip_saving_profile` is provided.  An existing profile is updated with the
+    /// new key once confirmed.
     #[clap(long)]
     pub(crate) save_to_profile: Option<String>,
 
@@ -91,16 +102,35 @@ impl CliCommand<RotateSummary> for RotateKey {
     }
 
     async fn execute(self) -> CliTypedResult<RotateSummary> {
-        let new_private_key = self
-            .extract_private_key(self.txn_options.encoding_options.encoding)?
-            .ok_or_else(|| {
-                CliError::CommandArgumentError(
-                    "One of ['--new-private-key', '--new-private-key-file'] must be used"
-                        .to_string(),
-                )
-            })?;
+        let provided_private_key =
+            self.extract_private_key(self.txn_options.encoding_options.encoding)?;
+        // A generated key only exists in the profile it's saved to
+        let generated = provided_private_key.is_none();
+        if generated && self.skip_saving_profile {
+            return Err(CliError::CommandArgumentError(
+                "One of ['--new-private-key', '--new-private-key-file'] must be used with '--skip-saving-profile'"
+                    .to_string(),
+            ));
+        }
+
+        // The profile is chosen before rotating, so that nothing can fail between the rotation
+        // and saving the new key
+        let new_profile = self.new_profile(generated)?;
+        let new_private_key = match provided_private_key {
+            Some(new_private_key) => new_private_key,
+            None => self
+                .rng_args
+                .key_generator()?
+                .generate_ed25519_private_key(),
+        };
+        let generated_key_file = match (generated, &new_profile) {
+            (true, Some(new_profile)) => {
+                Some(self.save_generated_key(&new_profile.name, &new_private_key)?)
+            }
+            _ => None,
+        };
 
-        let (current_private_key, sender_address) = self.txn_options.get_key_and_address()?;
+        let (current_signer, sender_address) = self.txn_options.get_signer_and_address()?;
 
         // Get sequence number for account
         let sequence_number = self.txn_options.sequence_number(sender_address).await?;
@@ -120,9 +150,9 @@ impl CliCommand<RotateSummary> for RotateKey {
         let rotation_msg =
             bcs::to_bytes(&rotation_proof).map_err(|err| CliError::BCS("rotation_proof", err))?;
 
-        // Signs the struct using both the current private key and the next private key
+        // Signs the struct using both the current signer and the next private key
         let rotation_proof_signed_by_current_private_key =
-            current_private_key.sign_arbitrary_message(&rotation_msg.clone());
+            current_signer.sign_message(&rotation_msg)?;
         let rotation_proof_signed_by_new_private_key =
             new_private_key.sign_arbitrary_message(&rotation_msg);
 
@@ -131,7 +161,7 @@ impl CliCommand<RotateSummary> for RotateKey {
             .submit_transaction(aptos_stdlib::account_rotate_authentication_key(
                 0,
                 // Existing public key
-                current_private_key.public_key().to_bytes().to_vec(),
+                current_signer.public_key().to_bytes().to_vec(),
                 0,
                 // New public key
                 new_private_key.public_key().to_bytes().to_vec(),
@@ -160,88 +190,167 @@ impl CliCommand<RotateSummary> for RotateKey {
             ));
         }
 
-        let mut profile_name: String;
-
-        if self.save_to_profile.is_none() {
-            if self.skip_saving_profile
-                || !prompt_yes("Do you want to create a profile for the new key?")
-            {
-                return Ok(RotateSummary {
-                    transaction: txn_summary,
-                    message: None,
-                });
+        let message = match new_profile {
+            Some(new_profile) => {
+                let profile_name = new_profile.name.clone();
+                new_profile.save(
+                    &new_private_key,
+                    sender_address,
+                    self.txn_options.rest_options.url.clone(),
+                )?;
+                eprintln!("Profile {} is saved.", profile_name);
+                // The profile holds the generated key now
+                if let Some(generated_key_file) = generated_key_file {
+                    let _ = std::fs::remove_file(generated_key_file);
+                }
+                Some(format!("Profile {} is saved.", profile_name))
             }
+            None => None,
+        };
 
-            eprintln!("Enter the name for the profile");
-            profile_name = read_line("Profile name")?.trim().to_string();
-        } else {
-            // We can safely unwrap here
-            profile_name = self.save_to_profile.unwrap();
+        // The new key must lead back to the account through the originating address table
+        let rest_client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let looked_up_address = lookup_address(&rest_client, &new_private_key.public_key()).await?;
+        if looked_up_address != sender_address {
+            return Err(CliError::UnexpectedError(format!(
+                "The new key is mapped to {} instead of {} in the originating address table",
+                looked_up_address, sender_address
+            )));
         }
 
-        // Check if profile name exists
-        let mut config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        Ok(RotateSummary {
+            transaction: txn_summary,
+            message,
+        })
+    }
+}
+
+impl RotateKey {
+    /// Picks the profile to save the new key to, if any, prompting for its name unless it's
+    /// given.  An existing profile is only updated once confirmed.
+    fn new_profile(&self, generated: bool) -> CliTypedResult<Option<NewProfile>> {
+        let mut profile_name = match self.save_to_profile {
+            Some(ref profile_name) => profile_name.clone(),
+            None => {
+                if self.skip_saving_profile
+                    || (!generated
+                        && !prompt_yes("Do you want to create a profile for the new key?"))
+                {
+                    return Ok(None);
+                }
+                eprintln!("Enter the name for the profile");
+                read_line("Profile name")?.trim().to_string()
+            }
+        };
+        if profile_name.is_empty() {
+            return Err(CliError::AbortedError);
+        }
+
+        // Check if profile name exists, in which case it can be updated with the new key
+        let config = CliConfig::load(ConfigSearchMode::CurrentDirAndParents)?;
+        let mut existing_profile = None;
 
         if let Some(ref profiles) = config.profiles {
-            if profiles.contains_key(&profile_name) {
-                if let Err(cli_err) = prompt_yes_with_override(
+            if let Some(profile) = profiles.get(&profile_name) {
+                if prompt_yes_with_override(
                     format!(
-                        "Profile {} exits. Do you want to provide a new profile name?",
+                        "Profile {} exists. Do you want to update it with the new key?",
                         profile_name
                     )
                     .as_str(),
                     self.txn_options.prompt_options,
-                ) {
-                    match cli_err {
-                        CliError::AbortedError => {
-                            return Ok(RotateSummary {
-                                transaction: txn_summary,
-                                message: None,
-                            });
-                        }
-                        _ => {
-                            return Err(cli_err);
-                        }
+                )
+                .is_ok()
+                {
+                    existing_profile = Some(profile.clone());
+                } else {
+                    eprintln!("Enter the name for the new profile");
+                    profile_name = read_line("Profile name")?.trim().to_string();
+                    if profile_name.is_empty() {
+                        return Err(CliError::AbortedError);
+                    }
+                    if profiles.contains_key(&profile_name) {
+                        return Err(CliError::CommandArgumentError(format!(
+                            "Profile {} already exists",
+                            profile_name
+                        )));
                     }
                 }
-
-                eprintln!("Enter the name for the profile");
-                profile_name = read_line("Profile name")?.trim().to_string();
             }
         }
 
-        if profile_name.is_empty() {
-            return Err(CliError::AbortedError);
-        }
+        let base_profile = match existing_profile {
+            Some(profile) => profile,
+            None => self.txn_options.profile_options.profile()?,
+        };
+        Ok(Some(NewProfile {
+            name: profile_name,
+            base_profile,
+            config,
+        }))
+    }
 
+    /// Saves a generated key to a file before rotating to it, so that it isn't lost if the
+    /// profile can't be saved once the key is rotated
+    fn save_generated_key(
+        &self,
+        profile_name: &str,
+        new_private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<PathBuf> {
+        let aptos_folder = CliConfig::aptos_folder(ConfigSearchMode::CurrentDir)?;
+        create_dir_if_not_exist(aptos_folder.as_path())?;
+        let key_file = aptos_folder.join(format!("{}.key", profile_name));
+        check_if_file_exists(key_file.as_path(), self.txn_options.prompt_options)?;
+        let encoded_key = self
+            .txn_options
+            .encoding_options
+            .encoding
+            .encode_key("new private key", new_private_key)?;
+        write_to_user_only_file(key_file.as_path(), "new private key", &encoded_key)?;
+        eprintln!(
+            "The new private key is saved to {} until it's saved to profile {}",
+            key_file.display(),
+            profile_name
+        );
+        Ok(key_file)
+    }
+}
+
+/// A profile the new key is saved to once it's rotated
+struct NewProfile {
+    name: String,
+    /// The profile updated with the new key, or the one of the command for a new profile
+    base_profile: ProfileConfig,
+    config: CliConfig,
+}
+
+impl NewProfile {
+    fn save(
+        self,
+        new_private_key: &Ed25519PrivateKey,
+        account: AccountAddress,
+        rest_url: Option<reqwest::Url>,
+    ) -> CliTypedResult<()> {
         let mut profile_config = ProfileConfig {
             private_key: Some(new_private_key.clone()),
             public_key: Some(new_private_key.public_key()),
-            account: Some(sender_address),
-            ..self.txn_options.profile_options.profile()?
+            account: Some(account),
+            ..self.base_profile
         };
 
-        if let Some(url) = self.txn_options.rest_options.url {
+        if let Some(url) = rest_url {
             profile_config.rest_url = Some(url.into());
         }
 
-        if config.profiles.is_none() {
-            config.profiles = Some(BTreeMap::new());
-        }
-
+        let mut config = self.config;
         config
             .profiles
-            .as_mut()
-            .unwrap()
-            .insert(profile_name.clone(), profile_config);
-        config.save()?;
-
-        eprintln!("Profile {} is saved.", profile_name);
-
-        Ok(RotateSummary {
-            transaction: txn_summary,
-            message: Some(format!("Profile {} is saved.", profile_name)),
-        })
+            .get_or_insert_with(BTreeMap::new)
+            .insert(self.name, profile_config);
+        config.save()
     }
 }
 
@@ -283,43 +392,49 @@ impl CliCommand<AccountAddress> for LookupAddress {
     }
 
     async fn execute(self) -> CliTypedResult<AccountAddress> {
-        let rest_client = self.rest_client()?;
-
-        let originating_resource: OriginatingResource = rest_client
-            .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::account::OriginatingAddress")
-            .await?
-            .into_inner();
-
-        let table_handle = originating_resource.address_map.handle;
-
-        // The derived address that can be used to look up the original address
-        // TODO: This command needs to support multi-ed25519
-        let address_key = AuthenticationKey::ed25519(&self.public_key()?).derived_address();
-        match rest_client
-            .get_table_item_bcs(
-                table_handle,
-                "address",
-                "address",
-                address_key.to_hex_literal(),
-            )
-            .await
-        {
-            Ok(inner) => Ok(inner.into_inner()),
-            Err(RestError::Api(AptosErrorResponse {
-                error:
-                    AptosError {
-                        error_code: AptosErrorCode::TableItemNotFound,
-                        ..
-                    },
-                ..
-            })) => {
-                // If the table item wasn't found, let's at least check if the account exists
-                // It won't be in the table if it wasn't rotated, then return the derived account address
-                rest_client.get_account_bcs(address_key).await?;
-                Ok(address_key)
-            }
-            Err(err) => Err(err)?,
+        lookup_address(&self.rest_client()?, &self.public_key()?).await
+    }
+}
+
+/// Looks up the account address of a public key through the on-chain originating address table
+pub(crate) async fn lookup_address(
+    rest_client: &Client,
+    public_key: &Ed25519PublicKey,
+) -> CliTypedResult<AccountAddress> {
+    let originating_resource: OriginatingResource = rest_client
+        .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::account::OriginatingAddress")
+        .await?
+        .into_inner();
+
+    let table_handle = originating_resource.address_map.handle;
+
+    // The derived address that can be used to look up the original address
+    // TODO: This command needs to support multi-ed25519
+    let address_key = AuthenticationKey::ed25519(public_key).derived_address();
+    match rest_client
+        .get_table_item_bcs(
+            table_handle,
+            "address",
+            "address",
+            address_key.to_hex_literal(),
+        )
+        .await
+    {
+        Ok(inner) => Ok(inner.into_inner()),
+        Err(RestError::Api(AptosErrorResponse {
+            error:
+                AptosError {
+                    error_code: AptosErrorCode::TableItemNotFound,
+                    ..
+                },
+            ..
+        })) => {
+            // If the table item wasn't found, let's at least check if the account exists
+            // It won't be in the table if it wasn't rotated, then return the derived account address
+            rest_client.get_account_bcs(address_key).await?;
+            Ok(address_key)
         }
+        Err(err) => Err(err)?,
     }
 }
 
@@ -332,3 +447,33 @@ pub struct OriginatingResource {
 pub struct Table {
     pub handle: AccountAddress,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_generated_key_is_saved() {
+        // The generated key would only exist in memory
+        let rotate_key =
+            RotateKey::try_parse_from(["rotate-key", "--skip-saving-profile"]).unwrap();
+        assert!(matches!(
+            rotate_key.execute().await,
+            Err(CliError::CommandArgumentError(_))
+        ));
+
+        // The profile is checked before anything is submitted, so there's no need for a node
+        let rotate_key = RotateKey::try_parse_from([
+            "rotate-key",
+            "--save-to-profile",
+            "",
+            "--url",
+            "http://127.0.0.1:1",
+        ])
+        .unwrap();
+        assert!(matches!(
+            rotate_key.execute().await,
+            Err(CliError::AbortedError)
+        ));
+    }
+}
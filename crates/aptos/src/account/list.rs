@@ -1,31 +1,52 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{
-    CliCommand, CliConfig, CliError, CliTypedResult, ConfigSearchMode, ProfileOptions, RestOptions,
+use crate::common::{
+    types::{
+        CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode,
+        ProfileOptions, RestOptions,
+    },
+    utils::start_logger,
 };
+use aptos_rest_client::{aptos_api_types::MoveModuleBytecode, Client, Resource};
 use aptos_types::account_address::AccountAddress;
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
-use serde_json::json;
+use futures::{Stream, StreamExt, TryStreamExt};
+use move_core_types::language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS};
+use serde::Serialize;
+use serde_json::{json, Value};
 use std::{
+    collections::{BTreeSet, HashMap},
     fmt::{Display, Formatter},
     str::FromStr,
 };
 
+const COIN_STORE: &str = "CoinStore";
+const TOKEN_STORE: &str = "0x3::token::TokenStore";
+const TOKEN_ID: &str = "0x3::token::TokenId";
+const TOKEN: &str = "0x3::token::Token";
+const COLLECTIONS: &str = "0x3::token::Collections";
+const TOKEN_DATA_ID: &str = "0x3::token::TokenDataId";
+const TOKEN_DATA: &str = "0x3::token::TokenData";
+
 #[derive(ArgEnum, Clone, Copy, Debug)]
 pub enum ListQuery {
     Balance,
+    Coins,
     Modules,
     Resources,
+    Tokens,
 }
 
 impl Display for ListQuery {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let str = match self {
             ListQuery::Balance => "balance",
+            ListQuery::Coins => "coins",
             ListQuery::Modules => "modules",
             ListQuery::Resources => "resources",
+            ListQuery::Tokens => "tokens",
         };
         write!(f, "{}", str)
     }
@@ -37,55 +58,281 @@ impl FromStr for ListQuery {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "balance" => Ok(ListQuery::Balance),
+            "coins" => Ok(ListQuery::Coins),
             "modules" => Ok(ListQuery::Modules),
             "resources" => Ok(ListQuery::Resources),
-            _ => Err("Invalid query. Valid values are balance, modules, resources"),
+            "tokens" => Ok(ListQuery::Tokens),
+            _ => Err("Invalid query. Valid values are balance, coins, modules, resources, tokens"),
         }
     }
 }
 
-/// List resources, modules, or balance owned by an address
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    Json,
+    Csv,
+}
+
+impl Display for ListFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ListFormat::Json => "json",
+            ListFormat::Csv => "csv",
+        })
+    }
+}
+
+/// List resources, modules, coins, tokens or balance owned by an address
 ///
 /// This allows you to list the current resources at the time of query.  This can change due to
-/// any transactions that have occurred after the request.
+/// any transactions that have occurred after the request.  Resources and modules are fetched a
+/// page at a time, so that accounts with thousands of holdings can be listed.
 #[derive(Debug, Parser)]
 pub struct ListAccount {
     /// Address of the account you want to list resources/modules/balance for
     #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
     pub(crate) account: Option<AccountAddress>,
 
-    /// Type of items to list: [balance, resources, modules]
+    /// Type of items to list: [balance, coins, modules, resources, tokens]
     #[clap(long, default_value_t = ListQuery::Resources)]
     pub(crate) query: ListQuery,
 
+    /// Only list resources whose type starts with this, e.g. `0x1::coin::CoinStore`
+    ///
+    /// For coins, this filters on the coin type instead, e.g. `0x1::aptos_coin::AptosCoin`
+    #[clap(long)]
+    pub(crate) resource_type: Option<String>,
+
+    /// Maximum number of items to list, all of them if not set
+    #[clap(long)]
+    pub(crate) limit: Option<usize>,
+
+    /// Number of resources or modules fetched per request
+    #[clap(long)]
+    pub(crate) page_size: Option<u16>,
+
+    /// Output format: [json, csv]
+    #[clap(long, arg_enum, default_value_t = ListFormat::Json)]
+    pub(crate) format: ListFormat,
+
     #[clap(flatten)]
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) profile_options: ProfileOptions,
 }
 
-#[async_trait]
-impl CliCommand<Vec<serde_json::Value>> for ListAccount {
-    fn command_name(&self) -> &'static str {
-        "ListAccount"
+/// A coin held by the account, with its metadata from `0x1::coin::CoinInfo`
+#[derive(Debug, Serialize)]
+pub struct CoinSummary {
+    pub coin_type: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// The balance in the smallest unit of the coin
+    pub balance: u64,
+    /// The balance in whole coins, if the decimals are known
+    pub amount: Option<String>,
+    pub frozen: bool,
+}
+
+/// A token held by the account, with its metadata from `0x3::token::TokenData`
+#[derive(Debug, Serialize)]
+pub struct TokenSummary {
+    pub creator: String,
+    pub collection: String,
+    pub name: String,
+    pub property_version: String,
+    pub amount: String,
+    pub description: Option<String>,
+    pub uri: Option<String>,
+}
+
+impl ListAccount {
+    /// Prints the items as CSV instead of JSON
+    pub async fn execute_csv(self) -> CliResult {
+        start_logger();
+        let items = self.execute().await.map_err(|err| err.to_string())?;
+        Ok(format_csv(&items))
     }
 
-    async fn execute(self) -> CliTypedResult<Vec<serde_json::Value>> {
-        let account = if let Some(account) = self.account {
-            account
+    fn account(&self) -> CliTypedResult<AccountAddress> {
+        if let Some(account) = self.account {
+            Ok(account)
         } else if let Some(Some(account)) = CliConfig::load_profile(
             self.profile_options.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
         )?
         .map(|p| p.account)
         {
-            account
+            Ok(account)
         } else {
-            return Err(CliError::CommandArgumentError(
+            Err(CliError::CommandArgumentError(
                 "Please provide an account using --account or run aptos init".to_string(),
-            ));
+            ))
+        }
+    }
+
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(usize::MAX)
+    }
+
+    /// Streams the resources of the account matching the resource type filter
+    fn resources<'a>(
+        &'a self,
+        client: &'a Client,
+        account: AccountAddress,
+    ) -> impl Stream<Item = CliTypedResult<Resource>> + 'a {
+        client
+            .account_resources_stream(account, self.page_size)
+            .map_err(CliError::from)
+            .try_filter(move |resource| {
+                let matches = match &self.resource_type {
+                    Some(resource_type) => resource
+                        .resource_type
+                        .to_string()
+                        .starts_with(resource_type.as_str()),
+                    None => true,
+                };
+                futures::future::ready(matches)
+            })
+    }
+
+    async fn list_modules(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+    ) -> CliTypedResult<Vec<Value>> {
+        client
+            .account_modules_stream(account, self.page_size)
+            .take(self.limit())
+            .map_err(CliError::from)
+            .and_then(|module: MoveModuleBytecode| async move {
+                let abi = module
+                    .try_parse_abi()
+                    .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+                Ok(json!(abi))
+            })
+            .try_collect()
+            .await
+    }
+
+    async fn list_resources(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+    ) -> CliTypedResult<Vec<Value>> {
+        self.resources(client, account)
+            .take(self.limit())
+            .map_ok(|resource| {
+                let mut map = serde_json::Map::new();
+                map.insert(resource.resource_type.to_string(), resource.data);
+                Value::Object(map)
+            })
+            .try_collect()
+            .await
+    }
+
+    async fn list_coins(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+    ) -> CliTypedResult<Vec<Value>> {
+        let coin_stores = Box::pin(
+            client
+                .account_resources_stream(account, self.page_size)
+                .map_err(CliError::from)
+                .try_filter_map(|resource| async move {
+                    Ok(coin_type(&resource).map(|coin_type| (coin_type, resource)))
+                }),
+        );
+
+        // Coin metadata is looked up once per coin type
+        let mut coin_infos: HashMap<String, Option<Value>> = HashMap::new();
+        let mut coins = vec![];
+        let mut coin_stores = coin_stores.try_filter(|(coin_type, _)| {
+            let matches = match &self.resource_type {
+                Some(resource_type) => coin_type.to_string().starts_with(resource_type.as_str()),
+                None => true,
+            };
+            futures::future::ready(matches)
+        });
+        while coins.len() < self.limit() {
+            let (coin_type, coin_store) = match coin_stores.next().await {
+                Some(coin_store) => coin_store?,
+                None => break,
+            };
+            let coin_type_name = coin_type.to_string();
+            if !coin_infos.contains_key(&coin_type_name) {
+                let coin_info = get_coin_info(client, &coin_type).await?;
+                coin_infos.insert(coin_type_name.clone(), coin_info);
+            }
+            let coin_info = coin_infos[&coin_type_name].as_ref();
+            coins.push(json!(coin_summary(
+                coin_type_name,
+                &coin_store.data,
+                coin_info
+            )));
+        }
+        Ok(coins)
+    }
+
+    async fn list_tokens(
+        &self,
+        client: &Client,
+        account: AccountAddress,
+    ) -> CliTypedResult<Vec<Value>> {
+        let token_store = match client
+            .get_account_resource(account, TOKEN_STORE)
+            .await?
+            .into_inner()
+        {
+            Some(token_store) => token_store,
+            None => return Ok(vec![]),
         };
+        let tokens_handle = parse_table_handle(&token_store.data["tokens"])?;
+
+        // Tables can't be enumerated, so the tokens that were ever deposited are found through
+        // the deposit events, and their current amount read from the token store
+        let mut token_ids = BTreeSet::new();
+        let mut deposit_events = Box::pin(client.events_stream(
+            account,
+            TOKEN_STORE,
+            "deposit_events",
+            None,
+            self.page_size,
+        ));
+        while let Some(event) = deposit_events.next().await {
+            token_ids.insert(event?.data["id"].to_string());
+        }
+
+        let mut tokens = vec![];
+        for token_id in token_ids {
+            if tokens.len() >= self.limit() {
+                break;
+            }
+            let token_id: Value = serde_json::from_str(&token_id)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            let token =
+                match get_table_item(client, tokens_handle, TOKEN_ID, TOKEN, &token_id).await? {
+                    Some(token) => token,
+                    // The token was withdrawn since
+                    None => continue,
+                };
+            let token_data = get_token_data(client, &token_id["token_data_id"]).await?;
+            tokens.push(json!(token_summary(&token_id, &token, token_data.as_ref())));
+        }
+        Ok(tokens)
+    }
+}
 
+#[async_trait]
+impl CliCommand<Vec<Value>> for ListAccount {
+    fn command_name(&self) -> &'static str {
+        "ListAccount"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<Value>> {
+        let account = self.account()?;
         let client = self.rest_options.client(&self.profile_options)?;
         let response = match self.query {
             ListQuery::Balance => vec![
@@ -99,26 +346,216 @@ impl CliCommand<Vec<serde_json::Value>> for ListAccount {
                     .unwrap()
                     .data,
             ],
-            ListQuery::Modules => client
-                .get_account_modules(account)
-                .await?
-                .into_inner()
-                .into_iter()
-                .map(|module| json!(module.try_parse_abi().unwrap()))
-                .collect::<Vec<serde_json::Value>>(),
-            ListQuery::Resources => client
-                .get_account_resources(account)
-                .await?
-                .into_inner()
-                .into_iter()
-                .map(|resource| {
-                    let mut map = serde_json::Map::new();
-                    map.insert(resource.resource_type.to_string(), resource.data);
-                    serde_json::Value::Object(map)
-                })
-                .collect::<Vec<serde_json::Value>>(),
+            ListQuery::Coins => self.list_coins(&client, account).await?,
+            ListQuery::Modules => self.list_modules(&client, account).await?,
+            ListQuery::Resources => self.list_resources(&client, account).await?,
+            ListQuery::Tokens => self.list_tokens(&client, account).await?,
         };
 
         Ok(response)
     }
 }
+
+/// Returns the coin type of a `0x1::coin::CoinStore` resource
+fn coin_type(resource: &Resource) -> Option<TypeTag> {
+    let StructTag {
+        address,
+        module,
+        name,
+        type_params,
+    } = &resource.resource_type;
+    if *address == CORE_CODE_ADDRESS && module.as_str() == "coin" && name.as_str() == COIN_STORE {
+        type_params.first().cloned()
+    } else {
+        None
+    }
+}
+
+/// Reads the `0x1::coin::CoinInfo` of a coin type, if it's published
+async fn get_coin_info(client: &Client, coin_type: &TypeTag) -> CliTypedResult<Option<Value>> {
+    let coin_address = match coin_type {
+        TypeTag::Struct(struct_tag) => struct_tag.address,
+        _ => return Ok(None),
+    };
+    Ok(client
+        .get_account_resource(coin_address, &format!("0x1::coin::CoinInfo<{}>", coin_type))
+        .await?
+        .into_inner()
+        .map(|resource| resource.data))
+}
+
+fn coin_summary(coin_type: String, coin_store: &Value, coin_info: Option<&Value>) -> CoinSummary {
+    let balance = coin_store["coin"]["value"]
+        .as_str()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default();
+    let decimals = coin_info
+        .and_then(|info| info["decimals"].as_u64())
+        .map(|decimals| decimals as u8);
+    CoinSummary {
+        coin_type,
+        name: coin_info.and_then(|info| info["name"].as_str().map(str::to_string)),
+        symbol: coin_info.and_then(|info| info["symbol"].as_str().map(str::to_string)),
+        decimals,
+        balance,
+        amount: decimals.map(|decimals| format_amount(balance, decimals)),
+        frozen: coin_store["frozen"].as_bool().unwrap_or_default(),
+    }
+}
+
+/// Reads the `0x3::token::TokenData` of a token from the collections of its creator
+async fn get_token_data(client: &Client, token_data_id: &Value) -> CliTypedResult<Option<Value>> {
+    let creator = token_data_id["creator"]
+        .as_str()
+        .and_then(|creator| AccountAddress::from_hex_literal(creator).ok())
+        .ok_or_else(|| {
+            CliError::UnexpectedError(format!("Invalid token data id {}", token_data_id))
+        })?;
+    let collections = match client
+        .get_account_resource(creator, COLLECTIONS)
+        .await?
+        .into_inner()
+    {
+        Some(collections) => collections,
+        None => return Ok(None),
+    };
+    let token_data_handle = parse_table_handle(&collections.data["token_data"])?;
+    get_table_item(
+        client,
+        token_data_handle,
+        TOKEN_DATA_ID,
+        TOKEN_DATA,
+        token_data_id,
+    )
+    .await
+}
+
+fn token_summary(token_id: &Value, token: &Value, token_data: Option<&Value>) -> TokenSummary {
+    let token_data_id = &token_id["token_data_id"];
+    let field = |value: &Value, name: &str| value[name].as_str().unwrap_or_default().to_string();
+    TokenSummary {
+        creator: field(token_data_id, "creator"),
+        collection: field(token_data_id, "collection"),
+        name: field(token_data_id, "name"),
+        property_version: field(token_id, "property_version"),
+        amount: field(token, "amount"),
+        description: token_data.map(|token_data| field(token_data, "description")),
+        uri: token_data.map(|token_data| field(token_data, "uri")),
+    }
+}
+
+fn parse_table_handle(table: &Value) -> CliTypedResult<AccountAddress> {
+    table["handle"]
+        .as_str()
+        .and_then(|handle| AccountAddress::from_hex_literal(handle).ok())
+        .ok_or_else(|| CliError::UnexpectedError(format!("Invalid table {}", table)))
+}
+
+/// Reads an item of a table, returning None if it isn't in the table
+async fn get_table_item(
+    client: &Client,
+    table_handle: AccountAddress,
+    key_type: &str,
+    value_type: &str,
+    key: &Value,
+) -> CliTypedResult<Option<Value>> {
+    match client
+        .get_table_item(table_handle, key_type, value_type, key)
+        .await
+    {
+        Ok(response) => Ok(Some(response.into_inner())),
+        Err(aptos_rest_client::error::RestError::Api(error))
+            if error.status_code == reqwest::StatusCode::NOT_FOUND =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Formats an amount in the smallest unit of a coin as whole coins, e.g. 150000000 with 8
+/// decimals as 1.5
+fn format_amount(amount: u64, decimals: u8) -> String {
+    // The decimal point is placed in the digits, as coins can have more decimals than fit in any
+    // integer power of ten
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", amount, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// Formats the items as CSV, with a column per top level field. Nested values are written as
+/// JSON.
+fn format_csv(items: &[Value]) -> String {
+    let mut columns: Vec<String> = vec![];
+    for item in items {
+        if let Value::Object(fields) = item {
+            for name in fields.keys() {
+                if !columns.contains(name) {
+                    columns.push(name.clone());
+                }
+            }
+        }
+    }
+
+    let mut csv = columns
+        .iter()
+        .map(|column| escape_csv_field(column))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push('\n');
+    for item in items {
+        let row = columns
+            .iter()
+            .map(|column| match &item[column] {
+                Value::Null => String::new(),
+                Value::String(value) => escape_csv_field(value),
+                value => escape_csv_field(&value.to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains(|c| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount(150_000_000, 8), "1.5");
+        assert_eq!(format_amount(1, 8), "0.00000001");
+        assert_eq!(format_amount(200, 0), "200");
+        assert_eq!(format_amount(u64::MAX, 8), "184467440737.09551615");
+        assert_eq!(format_amount(12, 40), format!("0.{}12", "0".repeat(38)));
+        assert_eq!(format_amount(0, u8::MAX), "0");
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let items = vec![
+            json!({"name": "Aptos Coin", "balance": 10}),
+            json!({"name": "a \"quoted\", name", "frozen": true}),
+        ];
+        assert_eq!(
+            format_csv(&items),
+            "balance,name,frozen\n10,Aptos Coin,\n,\"a \"\"quoted\"\", name\",true\n"
+        );
+    }
+}
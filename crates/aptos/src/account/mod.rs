@@ -1,22 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliResult};
+use crate::common::types::{CliCommand, CliResult, OutputFormat};
 use clap::Subcommand;
 
+pub mod batch_submit;
 pub mod create;
 pub mod create_resource_account;
 pub mod fund;
 pub mod key_rotation;
 pub mod list;
 pub mod transfer;
+pub mod watch;
 
 /// Tool for interacting with accounts
 ///
 /// This tool is used to create accounts, get information about the
-/// account's resources, and transfer resources between accounts.
+/// account's resources, watch them, and transfer resources between accounts.
 #[derive(Debug, Subcommand)]
 pub enum AccountTool {
+    BatchSubmit(batch_submit::BatchSubmit),
     Create(create::CreateAccount),
     CreateResourceAccount(create_resource_account::CreateResourceAccount),
     FundWithFaucet(fund::FundWithFaucet),
@@ -24,18 +27,26 @@ pub enum AccountTool {
     LookupAddress(key_rotation::LookupAddress),
     RotateKey(key_rotation::RotateKey),
     Transfer(transfer::TransferCoins),
+    Watch(watch::WatchAccount),
 }
 
 impl AccountTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         match self {
-            AccountTool::Create(tool) => tool.execute_serialized().await,
-            AccountTool::CreateResourceAccount(tool) => tool.execute_serialized().await,
-            AccountTool::FundWithFaucet(tool) => tool.execute_serialized().await,
-            AccountTool::List(tool) => tool.execute_serialized().await,
-            AccountTool::LookupAddress(tool) => tool.execute_serialized().await,
-            AccountTool::RotateKey(tool) => tool.execute_serialized().await,
-            AccountTool::Transfer(tool) => tool.execute_serialized().await,
+            AccountTool::BatchSubmit(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::Create(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::CreateResourceAccount(tool) => {
+                tool.execute_serialized(output_format).await
+            }
+            AccountTool::FundWithFaucet(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::List(tool) => match tool.format {
+                list::ListFormat::Json => tool.execute_serialized(output_format).await,
+                list::ListFormat::Csv => tool.execute_csv().await,
+            },
+            AccountTool::LookupAddress(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::RotateKey(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::Transfer(tool) => tool.execute_serialized(output_format).await,
+            AccountTool::Watch(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
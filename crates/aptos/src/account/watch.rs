@@ -0,0 +1,215 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliTypedResult, OutputFormat, ProfileOptions, RestOptions},
+    utils::print_text,
+};
+use aptos_rest_client::{aptos_api_types::VersionedEvent, Client};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{Duration, Instant},
+};
+
+/// Maximum number of events fetched per request
+const EVENTS_PER_REQUEST: u16 = 100;
+
+/// Watch the resources of an account, printing their changes and the events they emit
+///
+/// The resources are polled until interrupted, or until `--max-changes` or `--timeout-secs` is
+/// reached.  Events are those of the event handles at the top level of the watched resources,
+/// e.g. the `deposit_events` and `withdraw_events` of a `0x1::coin::CoinStore`.
+#[derive(Debug, Parser)]
+pub struct WatchAccount {
+    /// Address of the account to watch, the account of the profile if not set
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) address: Option<AccountAddress>,
+
+    /// Only watch resources whose type starts with this, e.g. `0x1::coin::CoinStore`
+    ///
+    /// All resources of the account are watched if not set
+    #[clap(long)]
+    pub(crate) resource: Option<String>,
+
+    /// Milliseconds between polls of the resources
+    #[clap(long, default_value_t = 1000)]
+    pub(crate) poll_interval_ms: u64,
+
+    /// Stop after this many changes
+    #[clap(long)]
+    pub(crate) max_changes: Option<usize>,
+
+    /// Stop after this many seconds
+    #[clap(long)]
+    pub(crate) timeout_secs: Option<u64>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
+}
+
+/// A change of a resource, with the events it emitted since it was last seen
+#[derive(Debug, Serialize)]
+pub struct ResourceChange {
+    /// Ledger version the change was seen at
+    pub version: u64,
+    pub resource_type: String,
+    /// The new value of the resource, None if it was removed
+    pub resource: Option<Value>,
+    pub events: Vec<VersionedEvent>,
+}
+
+impl WatchAccount {
+    fn address(&self) -> CliTypedResult<AccountAddress> {
+        match self.address {
+            Some(address) => Ok(address),
+            None => self.profile_options.account_address(),
+        }
+    }
+
+    /// Reads the watched resources, along with the ledger version they were read at
+    async fn resources(
+        &self,
+        client: &Client,
+        address: AccountAddress,
+    ) -> CliTypedResult<(u64, BTreeMap<String, Value>)> {
+        let (resources, state) = client.get_account_resources(address).await?.into_parts();
+        let resources = resources
+            .into_iter()
+            .map(|resource| (resource.resource_type.to_string(), resource.data))
+            .filter(|(resource_type, _)| match &self.resource {
+                Some(prefix) => resource_type.starts_with(prefix.as_str()),
+                None => true,
+            })
+            .collect();
+        Ok((state.version, resources))
+    }
+}
+
+#[async_trait]
+impl CliCommand<Vec<ResourceChange>> for WatchAccount {
+    fn command_name(&self) -> &'static str {
+        "WatchAccount"
+    }
+
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<ResourceChange>> {
+        let address = self.address()?;
+        let client = self.rest_options.client(&self.profile_options)?;
+        let deadline = self
+            .timeout_secs
+            .map(|timeout_secs| Instant::now() + Duration::from_secs(timeout_secs));
+        let max_changes = self.max_changes.unwrap_or(usize::MAX);
+
+        let (version, mut resources) = self.resources(&client, address).await?;
+        print_text(
+            self.output_format,
+            &format!(
+                "Watching {} resources of {} from version {}",
+                resources.len(),
+                address,
+                version
+            ),
+        );
+
+        let mut changes = vec![];
+        while changes.len() < max_changes
+            && deadline.map_or(true, |deadline| Instant::now() < deadline)
+        {
+            tokio::time::sleep(Duration::from_millis(self.poll_interval_ms)).await;
+            let (version, new_resources) = self.resources(&client, address).await?;
+
+            let resource_types: BTreeSet<_> =
+                resources.keys().chain(new_resources.keys()).collect();
+            for resource_type in resource_types {
+                if changes.len() == max_changes {
+                    break;
+                }
+                let old = resources.get(resource_type);
+                let new = new_resources.get(resource_type);
+                if old == new {
+                    continue;
+                }
+                let events = match new {
+                    Some(new) => get_new_events(&client, address, resource_type, old, new).await?,
+                    None => vec![],
+                };
+                let change = ResourceChange {
+                    version,
+                    resource_type: resource_type.clone(),
+                    resource: new.cloned(),
+                    events,
+                };
+                print_text(
+                    self.output_format,
+                    &serde_json::to_string_pretty(&change)
+                        .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+                );
+                changes.push(change);
+            }
+            resources = new_resources;
+        }
+        Ok(changes)
+    }
+}
+
+/// Returns the number of events emitted through each event handle of a resource
+fn event_counters(resource: &Value) -> BTreeMap<String, u64> {
+    resource
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, field)| field.get("guid").is_some())
+        .filter_map(|(name, field)| {
+            let counter = field.get("counter")?.as_str()?.parse().ok()?;
+            Some((name.clone(), counter))
+        })
+        .collect()
+}
+
+/// Fetches the events emitted by a resource between its old and new values
+async fn get_new_events(
+    client: &Client,
+    address: AccountAddress,
+    resource_type: &str,
+    old: Option<&Value>,
+    new: &Value,
+) -> CliTypedResult<Vec<VersionedEvent>> {
+    let old_counters = old.map(event_counters).unwrap_or_default();
+    let mut events = vec![];
+    for (field_name, counter) in event_counters(new) {
+        let mut start = old_counters.get(&field_name).copied().unwrap_or_default();
+        while start < counter {
+            let limit = (counter - start).min(EVENTS_PER_REQUEST as u64) as u16;
+            let page = client
+                .get_account_events(
+                    address,
+                    resource_type,
+                    &field_name,
+                    Some(start),
+                    Some(limit),
+                )
+                .await?
+                .into_inner();
+            if page.is_empty() {
+                break;
+            }
+            start += page.len() as u64;
+            events.extend(page);
+        }
+    }
+    events.sort_by_key(|event| (event.version.0, event.sequence_number.0));
+    Ok(events)
+}
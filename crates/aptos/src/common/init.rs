@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{ConfigSearchMode, DEFAULT_PROFILE};
+use crate::common::types::{ConfigSearchMode, NetworkConfig, DEFAULT_PROFILE};
 use crate::common::{
     types::{
         account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
@@ -26,14 +26,11 @@ const NUM_DEFAULT_OCTAS: u64 = 100000000;
 /// Tool to initialize current directory for the aptos tool
 ///
 /// Configuration will be pushed into .aptos/config.yaml
+///
+/// The network is chosen with `--network`, one of [devnet, testnet, mainnet, local, custom] or a
+/// named network of the config.  If custom `rest_url` and `faucet_url` are wanted, use `custom`.
 #[derive(Debug, Parser)]
 pub struct InitTool {
-    /// Network to use for default settings
-    ///
-    /// If custom `rest_url` and `faucet_url` are wanted, use `custom`
-    #[clap(long)]
-    pub network: Option<Network>,
-
     /// URL to a fullnode on the network
     #[clap(long)]
     pub rest_url: Option<Url>,
@@ -86,10 +83,11 @@ impl CliCommand<()> for InitTool {
 
         eprintln!("Configuring for profile {}", profile_name);
 
-        // Choose a network
-        let network = if let Some(network) = self.network {
-            eprintln!("Configuring for network {:?}", network);
-            network
+        // Choose a network, any other name is one of the named networks of the config
+        let network = if let Some(network) = self.profile_options.network.as_deref() {
+            eprintln!("Configuring for network {}", network);
+            Network::from_str(network)
+                .unwrap_or_else(|_| Network::Named(network.trim().to_string()))
         } else {
             eprintln!(
                 "Choose network from [devnet, testnet, mainnet, local, custom | defaults to devnet]"
@@ -104,27 +102,20 @@ impl CliCommand<()> for InitTool {
             }
         };
 
-        match network {
-            Network::Mainnet => {
-                profile_config.rest_url =
-                    Some("https://fullnode.mainnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = None;
-            }
-            Network::Testnet => {
-                profile_config.rest_url =
-                    Some("https://fullnode.testnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = None;
-            }
-            Network::Devnet => {
-                profile_config.rest_url = Some("https://fullnode.devnet.aptoslabs.com".to_string());
-                profile_config.faucet_url = Some("https://faucet.devnet.aptoslabs.com".to_string());
-            }
-            Network::Local => {
-                profile_config.rest_url = Some("http://localhost:8080".to_string());
-                profile_config.faucet_url = Some("http://localhost:8081".to_string());
-            }
-            Network::Custom => self.custom_network(&mut profile_config)?,
+        let network_config = match network {
+            Network::Named(ref name) => Some(CliConfig::load_network(
+                name,
+                ConfigSearchMode::CurrentDirAndParents,
+            )?),
+            _ => network.network_config(),
+        };
+        if let Some(network_config) = network_config {
+            profile_config.rest_url = Some(network_config.rest_url);
+            profile_config.faucet_url = network_config.faucet_url;
+        } else {
+            self.custom_network(&mut profile_config)?
         }
+        profile_config.network = Some(network.clone());
 
         // Private key
         let private_key = if let Some(private_key) = self
@@ -314,13 +305,16 @@ impl InitTool {
 /// A simplified list of all networks supported by the CLI
 ///
 /// Any command using this, will be simpler to setup as profiles
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(from = "String", into = "String")]
 pub enum Network {
     Mainnet,
     Testnet,
     Devnet,
     Local,
     Custom,
+    /// A named network of the config, added with `aptos config set-network`
+    Named(String),
 }
 
 impl FromStr for Network {
@@ -343,8 +337,54 @@ impl FromStr for Network {
     }
 }
 
+impl From<String> for Network {
+    fn from(network: String) -> Self {
+        Network::from_str(&network).unwrap_or(Network::Named(network))
+    }
+}
+
+impl From<Network> for String {
+    fn from(network: Network) -> Self {
+        match network {
+            Network::Mainnet => "Mainnet".to_string(),
+            Network::Testnet => "Testnet".to_string(),
+            Network::Devnet => "Devnet".to_string(),
+            Network::Local => "Local".to_string(),
+            Network::Custom => "Custom".to_string(),
+            Network::Named(name) => name,
+        }
+    }
+}
+
 impl Default for Network {
     fn default() -> Self {
         Self::Devnet
     }
 }
+
+impl Network {
+    /// Returns the endpoints of the network, None for a custom or named network
+    pub fn network_config(&self) -> Option<NetworkConfig> {
+        let (rest_url, faucet_url, chain_id) = match self {
+            Network::Mainnet => ("https://fullnode.mainnet.aptoslabs.com", None, Some(1)),
+            Network::Testnet => ("https://fullnode.testnet.aptoslabs.com", None, Some(2)),
+            // Devnet is reset regularly, with a new chain id
+            Network::Devnet => (
+                "https://fullnode.devnet.aptoslabs.com",
+                Some("https://faucet.devnet.aptoslabs.com"),
+                None,
+            ),
+            Network::Local => (
+                "http://localhost:8080",
+                Some("http://localhost:8081"),
+                Some(4),
+            ),
+            Network::Custom | Network::Named(_) => return None,
+        };
+        Some(NetworkConfig {
+            rest_url: rest_url.to_string(),
+            faucet_url: faucet_url.map(|url| url.to_string()),
+            chain_id,
+        })
+    }
+}
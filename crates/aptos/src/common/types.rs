@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::init::Network;
+#[cfg(feature = "ledger")]
+use crate::common::utils::print_prompt;
 use crate::common::utils::prompt_yes_with_override;
 use crate::{
     common::utils::{
@@ -15,19 +17,36 @@ use crate::{
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-    x25519, PrivateKey, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
+    signing_message, x25519, PrivateKey, SigningKey, ValidCryptoMaterial,
+    ValidCryptoMaterialStringExt,
 };
 use aptos_global_constants::adjust_gas_headroom;
 use aptos_keygen::KeyGen;
+#[cfg(feature = "ledger")]
+use aptos_ledger::{AptosLedger, AptosLedgerError, DEFAULT_DERIVATION_PATH};
 use aptos_rest_client::aptos_api_types::HashValue;
 use aptos_rest_client::error::RestError;
 use aptos_rest_client::{Client, Transaction};
-use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    transaction_submitter::{ResubmissionPolicy, TransactionSubmitter},
+    types::LocalAccount,
+};
+use aptos_types::chain_id::ChainId;
 use aptos_types::transaction::{
-    authenticator::AuthenticationKey, SignedTransaction, TransactionPayload,
+    authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
+};
+#[cfg(feature = "gas-profiling")]
+use aptos_types::transaction::{ExecutionStatus, TransactionStatus};
+#[cfg(feature = "gas-profiling")]
+use aptos_validator_interface::{
+    AptosValidatorInterface, DebuggerStateView, RestDebuggerInterface,
 };
+#[cfg(feature = "gas-profiling")]
+use aptos_vm::AptosVM;
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
+use futures::{stream, StreamExt, TryStreamExt};
 use hex::FromHexError;
 use move_core_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
@@ -41,6 +60,7 @@ use std::{
     fs::OpenOptions,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::Arc,
     time::Instant,
 };
 use thiserror::Error;
@@ -167,12 +187,22 @@ impl From<bcs::Error> for CliError {
     }
 }
 
+#[cfg(feature = "ledger")]
+impl From<AptosLedgerError> for CliError {
+    fn from(e: AptosLedgerError) -> Self {
+        CliError::UnexpectedError(e.to_string())
+    }
+}
+
 /// Config saved to `.aptos/config.yaml`
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CliConfig {
     /// Map of profile configs
     #[serde(skip_serializing_if = "Option::is_none")]
     pub profiles: Option<BTreeMap<String, ProfileConfig>>,
+    /// Map of named networks, selected with `--network`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networks: Option<BTreeMap<String, NetworkConfig>>,
 }
 
 const CONFIG_FILE: &str = "config.yaml";
@@ -201,6 +231,19 @@ pub struct ProfileConfig {
     pub faucet_url: Option<String>,
 }
 
+/// A named network, to select its endpoints with `--network` instead of `--url` and `--faucet-url`
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// URL for the Aptos rest endpoint
+    pub rest_url: String,
+    /// URL for the Faucet endpoint (if applicable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub faucet_url: Option<String>,
+    /// Chain id of the network, transactions are only sent if it matches the one of the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u8>,
+}
+
 /// ProfileConfig but without the private parts
 #[derive(Debug, Serialize)]
 pub struct ProfileSummary {
@@ -231,6 +274,7 @@ impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
             profiles: Some(BTreeMap::new()),
+            networks: None,
         }
     }
 }
@@ -298,6 +342,29 @@ impl CliConfig {
         }
     }
 
+    /// Loads a named network from the config, falling back to the networks known by the CLI
+    pub fn load_network(network: &str, mode: ConfigSearchMode) -> CliTypedResult<NetworkConfig> {
+        let network = network.trim();
+        if Self::config_exists(mode) {
+            if let Some(network_config) = Self::load(mode)?
+                .networks
+                .and_then(|mut networks| networks.remove(network))
+            {
+                return Ok(network_config);
+            }
+        }
+
+        Network::from_str(network)
+            .ok()
+            .and_then(|network| network.network_config())
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Network {} not found.  Please add it with `aptos config set-network`",
+                    network
+                ))
+            })
+    }
+
     pub fn remove_profile(&mut self, profile: &str) -> Option<ProfileConfig> {
         if let Some(ref mut profiles) = self.profiles {
             profiles.remove(&profile.to_string())
@@ -330,7 +397,7 @@ impl CliConfig {
     }
 
     /// Finds the current directory's .aptos folder
-    fn aptos_folder(mode: ConfigSearchMode) -> CliTypedResult<PathBuf> {
+    pub(crate) fn aptos_folder(mode: ConfigSearchMode) -> CliTypedResult<PathBuf> {
         let global_config = GlobalConfig::load()?;
         global_config.get_config_location(mode)
     }
@@ -381,6 +448,14 @@ pub struct ProfileOptions {
     /// Defaults to "default"
     #[clap(long)]
     pub profile: Option<String>,
+
+    /// Named network to use from the CLI config, one of the built in
+    /// [devnet, testnet, mainnet, local] if it isn't defined there
+    ///
+    /// This overrides the REST URL and the Faucet URL of the profile,
+    /// and is overridden by `--url` and `--faucet-url`.
+    #[clap(long)]
+    pub network: Option<String>,
 }
 
 impl ProfileOptions {
@@ -414,6 +489,14 @@ impl ProfileOptions {
         self.profile.as_ref().map(|inner| inner.trim())
     }
 
+    /// Retrieve the named network, if one was selected
+    pub fn network(&self) -> CliTypedResult<Option<NetworkConfig>> {
+        self.network
+            .as_ref()
+            .map(|network| CliConfig::load_network(network, ConfigSearchMode::CurrentDirAndParents))
+            .transpose()
+    }
+
     pub fn profile(&self) -> CliTypedResult<ProfileConfig> {
         if let Some(profile) =
             CliConfig::load_profile(self.profile_name(), ConfigSearchMode::CurrentDirAndParents)?
@@ -488,7 +571,7 @@ impl EncodingType {
     }
 }
 
-#[derive(Clone, Debug, Parser)]
+#[derive(Clone, Debug, Default, Parser)]
 pub struct RngArgs {
     /// The seed used for key generation, should be a 64 character hex string and only used for testing
     ///
@@ -564,6 +647,43 @@ impl FromStr for EncodingType {
     }
 }
 
+/// Format of the output of the CLI
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The JSON result of the command, along with human readable text e.g. tables
+    Text,
+    /// Only the versioned JSON result of the command on stdout, any other text goes to stderr
+    Json,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err("Invalid output format"),
+        }
+    }
+}
+
 /// An insertable option for use with prompts.
 #[derive(Clone, Copy, Debug, Default, Parser, PartialEq, Eq)]
 pub struct PromptOptions {
@@ -683,6 +803,18 @@ pub struct PrivateKeyInputOptions {
     /// Mutually exclusive with `--private-key-file`
     #[clap(long, group = "private_key_input")]
     private_key: Option<String>,
+    /// Sign with the Aptos app of a Ledger device instead of a private key
+    ///
+    /// Every signature has to be approved on the device
+    #[cfg(feature = "ledger")]
+    #[clap(long, group = "private_key_input")]
+    ledger: bool,
+    /// BIP32 derivation path of the Ledger account to sign with
+    ///
+    /// Defaults to the first account, `m/44'/637'/0'/0'/0'`
+    #[cfg(feature = "ledger")]
+    #[clap(long, requires = "ledger")]
+    derivation_path: Option<String>,
 }
 
 impl ParsePrivateKey for PrivateKeyInputOptions {}
@@ -696,6 +828,10 @@ impl PrivateKeyInputOptions {
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             ),
             private_key_file: None,
+            #[cfg(feature = "ledger")]
+            ledger: false,
+            #[cfg(feature = "ledger")]
+            derivation_path: None,
         })
     }
 
@@ -707,6 +843,10 @@ impl PrivateKeyInputOptions {
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             ),
             private_key_file: None,
+            #[cfg(feature = "ledger")]
+            ledger: false,
+            #[cfg(feature = "ledger")]
+            derivation_path: None,
         })
     }
 
@@ -714,6 +854,10 @@ impl PrivateKeyInputOptions {
         PrivateKeyInputOptions {
             private_key: None,
             private_key_file: Some(file),
+            #[cfg(feature = "ledger")]
+            ledger: false,
+            #[cfg(feature = "ledger")]
+            derivation_path: None,
         }
     }
 
@@ -779,6 +923,54 @@ impl PrivateKeyInputOptions {
         }
     }
 
+    /// Extract the signer from CLI args with fallback to config, the Ledger device if `--ledger`
+    /// is used, or the private key otherwise
+    pub fn extract_signer_and_address(
+        &self,
+        encoding: EncodingType,
+        profile: &ProfileOptions,
+        maybe_address: Option<AccountAddress>,
+    ) -> CliTypedResult<(TransactionSigner, AccountAddress)> {
+        #[cfg(feature = "ledger")]
+        if self.ledger {
+            let signer = self.ledger_signer()?;
+            let address = maybe_address
+                .unwrap_or_else(|| account_address_from_public_key(&signer.public_key()));
+            return Ok((signer, address));
+        }
+        let (key, address) =
+            self.extract_private_key_and_address(encoding, profile, maybe_address)?;
+        Ok((TransactionSigner::PrivateKey(key), address))
+    }
+
+    /// Extract the signer from CLI args with fallback to config
+    pub fn extract_signer(
+        &self,
+        encoding: EncodingType,
+        profile: &ProfileOptions,
+    ) -> CliTypedResult<TransactionSigner> {
+        #[cfg(feature = "ledger")]
+        if self.ledger {
+            return self.ledger_signer();
+        }
+        Ok(TransactionSigner::PrivateKey(
+            self.extract_private_key(encoding, profile)?,
+        ))
+    }
+
+    #[cfg(feature = "ledger")]
+    fn ledger_signer(&self) -> CliTypedResult<TransactionSigner> {
+        let derivation_path = self
+            .derivation_path
+            .clone()
+            .unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string());
+        let public_key = AptosLedger::connect()?.get_public_key(&derivation_path)?;
+        Ok(TransactionSigner::Ledger {
+            derivation_path,
+            public_key,
+        })
+    }
+
     /// Extract private key from CLI args
     pub fn extract_private_key_cli(
         &self,
@@ -798,8 +990,73 @@ impl ExtractPublicKey for PrivateKeyInputOptions {
         encoding: EncodingType,
         profile: &ProfileOptions,
     ) -> CliTypedResult<Ed25519PublicKey> {
-        self.extract_private_key(encoding, profile)
-            .map(|private_key| private_key.public_key())
+        self.extract_signer(encoding, profile)
+            .map(|signer| signer.public_key())
+    }
+}
+
+/// Signs on behalf of an account, either with a local private key or with a Ledger device
+pub enum TransactionSigner {
+    PrivateKey(Ed25519PrivateKey),
+    #[cfg(feature = "ledger")]
+    Ledger {
+        derivation_path: String,
+        public_key: Ed25519PublicKey,
+    },
+}
+
+impl TransactionSigner {
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        match self {
+            TransactionSigner::PrivateKey(private_key) => private_key.public_key(),
+            #[cfg(feature = "ledger")]
+            TransactionSigner::Ledger { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    pub fn is_ledger(&self) -> bool {
+        match self {
+            TransactionSigner::PrivateKey(_) => false,
+            #[cfg(feature = "ledger")]
+            TransactionSigner::Ledger { .. } => true,
+        }
+    }
+
+    /// Signs arbitrary bytes e.g. a BCS encoded proof
+    pub fn sign_message(&self, message: &[u8]) -> CliTypedResult<Ed25519Signature> {
+        match self {
+            TransactionSigner::PrivateKey(private_key) => {
+                Ok(private_key.sign_arbitrary_message(message))
+            }
+            #[cfg(feature = "ledger")]
+            TransactionSigner::Ledger {
+                derivation_path, ..
+            } => {
+                print_prompt("Approve the signature on your Ledger device");
+                Ok(AptosLedger::connect()?.sign_message(derivation_path, message)?)
+            }
+        }
+    }
+
+    /// Signs a raw transaction, as it is when submitted
+    pub fn sign_raw_transaction(
+        &self,
+        raw_transaction: &RawTransaction,
+    ) -> CliTypedResult<Ed25519Signature> {
+        self.sign_message(&signing_message(raw_transaction)?)
+    }
+
+    /// Signs a transaction, without checking the signature
+    pub fn sign_transaction(
+        &self,
+        raw_transaction: RawTransaction,
+    ) -> CliTypedResult<SignedTransaction> {
+        let signature = self.sign_raw_transaction(&raw_transaction)?;
+        Ok(SignedTransaction::new(
+            raw_transaction,
+            self.public_key(),
+            signature,
+        ))
     }
 }
 
@@ -872,6 +1129,9 @@ impl RestOptions {
     pub fn url(&self, profile: &ProfileOptions) -> CliTypedResult<reqwest::Url> {
         if let Some(ref url) = self.url {
             Ok(url.clone())
+        } else if let Some(network) = profile.network()? {
+            reqwest::Url::parse(&network.rest_url)
+                .map_err(|err| CliError::UnableToParse("network rest_url", err.to_string()))
         } else if let Some(Some(url)) = CliConfig::load_profile(
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
@@ -1046,27 +1306,36 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
     /// Executes the command, returning a command specific type
     async fn execute(self) -> CliTypedResult<T>;
 
+    /// Sets the output format, for the commands printing human readable text along with their
+    /// result
+    fn with_output_format(self, _output_format: OutputFormat) -> Self {
+        self
+    }
+
     /// Executes the command, and serializes it to the common JSON output type
-    async fn execute_serialized(self) -> CliResult {
+    async fn execute_serialized(self, output_format: OutputFormat) -> CliResult {
         let command_name = self.command_name();
         start_logger();
         let start_time = Instant::now();
-        to_common_result(command_name, start_time, self.execute().await).await
+        let result = self.with_output_format(output_format).execute().await;
+        to_common_result(command_name, start_time, result, output_format).await
     }
 
     /// Same as execute serialized without setting up logging
-    async fn execute_serialized_without_logger(self) -> CliResult {
+    async fn execute_serialized_without_logger(self, output_format: OutputFormat) -> CliResult {
         let command_name = self.command_name();
         let start_time = Instant::now();
-        to_common_result(command_name, start_time, self.execute().await).await
+        let result = self.with_output_format(output_format).execute().await;
+        to_common_result(command_name, start_time, result, output_format).await
     }
 
     /// Executes the command, and throws away Ok(result) for the string Success
-    async fn execute_serialized_success(self) -> CliResult {
+    async fn execute_serialized_success(self, output_format: OutputFormat) -> CliResult {
         start_logger();
         let command_name = self.command_name();
         let start_time = Instant::now();
-        to_common_success_result(command_name, start_time, self.execute().await).await
+        let result = self.with_output_format(output_format).execute().await;
+        to_common_success_result(command_name, start_time, result, output_format).await
     }
 }
 
@@ -1201,6 +1470,15 @@ impl FaucetOptions {
     pub fn faucet_url(&self, profile: &ProfileOptions) -> CliTypedResult<reqwest::Url> {
         if let Some(ref faucet_url) = self.faucet_url {
             Ok(faucet_url.clone())
+        } else if let Some(network) = profile.network()? {
+            let url = network.faucet_url.ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Network {} has no faucet.  Please add --faucet-url",
+                    profile.network.as_deref().unwrap_or_default()
+                ))
+            })?;
+            reqwest::Url::parse(&url)
+                .map_err(|err| CliError::UnableToParse("network faucet_url", err.to_string()))
         } else if let Some(Some(url)) = CliConfig::load_profile(
             profile.profile_name(),
             ConfigSearchMode::CurrentDirAndParents,
@@ -1244,6 +1522,48 @@ pub struct GasOptions {
     pub max_gas: Option<u64>,
 }
 
+/// Options to profile the gas of a transaction instead of submitting it
+#[derive(Debug, Default, Parser)]
+pub struct GasProfilingOptions {
+    /// Simulate the transaction locally instead of submitting it, and write where its gas is
+    /// charged to `--gas-profile-dir`
+    ///
+    /// Only available if the CLI is built with the `gas-profiling` feature.
+    ///
+    /// The gas is broken down per call stack in `gas.folded`, which can be rendered by flamegraph
+    /// tools, and per instruction and native function in `gas.json`.
+    #[clap(long)]
+    pub(crate) profile_gas: bool,
+    /// Directory to write the gas profile to
+    #[clap(long, parse(from_os_str), default_value = "gas-profile")]
+    pub(crate) gas_profile_dir: PathBuf,
+}
+
+/// Simulates a transaction, which must not be validly signed, and returns the gas it used along
+/// with the max gas to submit it with
+pub(crate) async fn estimate_max_gas(
+    client: &Client,
+    signed_transaction: &SignedTransaction,
+) -> CliTypedResult<(u64, u64)> {
+    let txns = client
+        .simulate_with_gas_estimation(signed_transaction, true, false)
+        .await?
+        .into_inner();
+    let simulated_txn = txns.first().unwrap();
+
+    // Check if the transaction will pass, if it doesn't then fail
+    if !simulated_txn.info.success {
+        return Err(CliError::SimulationError(
+            simulated_txn.info.vm_status.clone(),
+        ));
+    }
+
+    // Take the gas used and use a headroom factor on it
+    let gas_used = simulated_txn.info.gas_used.0;
+    let adjusted_max_gas = adjust_gas_headroom(gas_used, simulated_txn.request.max_gas_amount.0);
+    Ok((gas_used, adjusted_max_gas))
+}
+
 /// Common options for interacting with an account for a validator
 #[derive(Debug, Default, Parser)]
 pub struct TransactionOptions {
@@ -1266,6 +1586,17 @@ pub struct TransactionOptions {
     pub(crate) gas_options: GasOptions,
     #[clap(flatten)]
     pub(crate) prompt_options: PromptOptions,
+
+    /// Number of times to rebuild and resubmit the transaction if it expires before being committed
+    ///
+    /// The transaction is signed again with a fresh expiration timestamp.  It is only resubmitted
+    /// with the same sequence number, unless `--allow-sequence-number-change` is set.
+    #[clap(long, default_value_t = 0)]
+    pub(crate) max_resubmissions: u32,
+    /// Allow an expired transaction to be resubmitted with a later sequence number, if its
+    /// sequence number has been used by another transaction in the meantime
+    #[clap(long)]
+    pub(crate) allow_sequence_number_change: bool,
 }
 
 impl TransactionOptions {
@@ -1274,10 +1605,10 @@ impl TransactionOptions {
         self.rest_options.client(&self.profile_options)
     }
 
-    /// Retrieves the public key and the associated address
+    /// Retrieves the signer and the associated address
     /// TODO: Cache this information
-    pub fn get_key_and_address(&self) -> CliTypedResult<(Ed25519PrivateKey, AccountAddress)> {
-        self.private_key_options.extract_private_key_and_address(
+    pub fn get_signer_and_address(&self) -> CliTypedResult<(TransactionSigner, AccountAddress)> {
+        self.private_key_options.extract_signer_and_address(
             self.encoding_options.encoding,
             &self.profile_options,
             self.sender_account,
@@ -1285,7 +1616,7 @@ impl TransactionOptions {
     }
 
     pub fn sender_address(&self) -> CliTypedResult<AccountAddress> {
-        Ok(self.get_key_and_address()?.1)
+        Ok(self.get_signer_and_address()?.1)
     }
 
     /// Gets the auth key by account address. We need to fetch the auth key from Rest API rather than creating an
@@ -1303,13 +1634,38 @@ impl TransactionOptions {
         get_sequence_number(&client, sender_address).await
     }
 
+    /// Retrieves the chain id of the endpoint, checking it against the one of the selected network
+    async fn chain_id(&self, client: &Client) -> CliTypedResult<ChainId> {
+        let chain_id = chain_id(client).await?;
+        if let Some(expected) = self
+            .profile_options
+            .network()?
+            .and_then(|network| network.chain_id)
+        {
+            if chain_id.id() != expected {
+                return Err(CliError::CommandArgumentError(format!(
+                    "The endpoint is on chain {}, but network {} is on chain {}",
+                    chain_id,
+                    self.profile_options.network.as_deref().unwrap_or_default(),
+                    expected
+                )));
+            }
+        }
+        Ok(chain_id)
+    }
+
     /// Submit a transaction
     pub async fn submit_transaction(
         &self,
         payload: TransactionPayload,
     ) -> CliTypedResult<Transaction> {
         let client = self.rest_client()?;
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let (signer, sender_address) = self.get_signer_and_address()?;
+        if signer.is_ledger() && self.max_resubmissions > 0 {
+            return Err(CliError::CommandArgumentError(
+                "'--max-resubmissions' can't be used with '--ledger'".to_string(),
+            ));
+        }
 
         // Get sequence number for account
         let sequence_number = self.sequence_number(sender_address).await?;
@@ -1335,7 +1691,7 @@ impl TransactionOptions {
             }
             max_gas
         } else {
-            let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            let transaction_factory = TransactionFactory::new(self.chain_id(&client).await?)
                 .with_gas_unit_price(gas_unit_price);
 
             let unsigned_transaction = transaction_factory
@@ -1346,27 +1702,12 @@ impl TransactionOptions {
 
             let signed_transaction = SignedTransaction::new(
                 unsigned_transaction,
-                sender_key.public_key(),
+                signer.public_key(),
                 Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
             );
 
-            let txns = client
-                .simulate_with_gas_estimation(&signed_transaction, true, false)
-                .await?
-                .into_inner();
-            let simulated_txn = txns.first().unwrap();
-
-            // Check if the transaction will pass, if it doesn't then fail
-            if !simulated_txn.info.success {
-                return Err(CliError::SimulationError(
-                    simulated_txn.info.vm_status.clone(),
-                ));
-            }
-
-            // Take the gas used and use a headroom factor on it
-            let gas_used = simulated_txn.info.gas_used.0;
-            let adjusted_max_gas =
-                adjust_gas_headroom(gas_used, simulated_txn.request.max_gas_amount.0);
+            let (gas_used, adjusted_max_gas) =
+                estimate_max_gas(&client, &signed_transaction).await?;
 
             // Ask if you want to accept the estimate amount
             let upper_cost_bound = adjusted_max_gas * gas_unit_price;
@@ -1381,18 +1722,229 @@ impl TransactionOptions {
         };
 
         // Sign and submit transaction
-        let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+        let transaction_factory = TransactionFactory::new(self.chain_id(&client).await?)
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas);
+        #[cfg_attr(not(feature = "ledger"), allow(clippy::infallible_destructuring_match))]
+        let sender_key = match signer {
+            TransactionSigner::PrivateKey(sender_key) => sender_key,
+            #[cfg(feature = "ledger")]
+            ledger @ TransactionSigner::Ledger { .. } => {
+                // The device has to approve every signature, so the transaction isn't resubmitted
+                let unsigned_transaction = transaction_factory
+                    .payload(payload)
+                    .sender(sender_address)
+                    .sequence_number(sequence_number)
+                    .build();
+                let signed_transaction = ledger.sign_transaction(unsigned_transaction)?;
+                return Ok(client
+                    .submit_and_wait(&signed_transaction)
+                    .await?
+                    .into_inner());
+            }
+        };
         let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
-        let response = client
-            .submit_and_wait(&transaction)
+        let policy = ResubmissionPolicy {
+            max_resubmissions: self.max_resubmissions,
+            allow_sequence_number_change: self.allow_sequence_number_change,
+        };
+        TransactionSubmitter::new(&client, policy)
+            .submit_and_wait(sender_account, &transaction_factory, payload)
+            .await
+            .map_err(|err| CliError::ApiError(format!("{:#}", err)))
+    }
+
+    /// Signs the payloads with consecutive sequence numbers and submits them, with at most
+    /// `max_in_flight` transactions pending at a time
+    ///
+    /// The result of each transaction is returned in the order of the payloads
+    pub async fn submit_transactions(
+        &self,
+        payloads: Vec<TransactionPayload>,
+        max_in_flight: usize,
+    ) -> CliTypedResult<Vec<CliTypedResult<Transaction>>> {
+        let client = self.rest_client()?;
+        let (signer, sender_address) = self.get_signer_and_address()?;
+        let sequence_number = self.sequence_number(sender_address).await?;
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let transaction_factory = TransactionFactory::new(self.chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price);
+
+        // Without a max gas, each transaction is simulated on its own against the current state
+        let max_gas_amounts: Vec<u64> = if let Some(max_gas) = self.gas_options.max_gas {
+            vec![max_gas; payloads.len()]
+        } else {
+            let public_key = signer.public_key();
+            stream::iter(payloads.iter().cloned())
+                .map(|payload| {
+                    let signed_transaction = SignedTransaction::new(
+                        transaction_factory
+                            .payload(payload)
+                            .sender(sender_address)
+                            .sequence_number(sequence_number)
+                            .build(),
+                        public_key.clone(),
+                        Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+                    );
+                    let client = &client;
+                    async move {
+                        estimate_max_gas(client, &signed_transaction)
+                            .await
+                            .map(|(_, adjusted_max_gas)| adjusted_max_gas)
+                    }
+                })
+                .buffered(max_in_flight)
+                .try_collect()
+                .await?
+        };
+        let message = format!(
+            "Do you want to submit {} transactions for a maximum of {} Octas at a gas unit price of {} Octas?",
+            payloads.len(),
+            max_gas_amounts.iter().sum::<u64>() * gas_unit_price,
+            gas_unit_price
+        );
+        prompt_yes_with_override(&message, self.prompt_options)?;
+
+        let signed_transactions = payloads
+            .into_iter()
+            .zip(max_gas_amounts)
+            .enumerate()
+            .map(|(index, (payload, max_gas))| {
+                signer.sign_transaction(
+                    transaction_factory
+                        .payload(payload)
+                        .sender(sender_address)
+                        .sequence_number(sequence_number + index as u64)
+                        .max_gas_amount(max_gas)
+                        .build(),
+                )
+            })
+            .collect::<CliTypedResult<Vec<_>>>()?;
+
+        Ok(stream::iter(signed_transactions)
+            .map(|signed_transaction| {
+                let client = &client;
+                async move {
+                    client
+                        .submit_and_wait(&signed_transaction)
+                        .await
+                        .map(|response| response.into_inner())
+                        .map_err(CliError::from)
+                }
+            })
+            .buffered(max_in_flight)
+            .collect()
+            .await)
+    }
+
+    /// Simulates a transaction locally against the latest state of the chain instead of
+    /// submitting it, and writes where its gas is charged to `output_dir`
+    #[cfg(not(feature = "gas-profiling"))]
+    pub async fn profile_gas(
+        &self,
+        _payload: TransactionPayload,
+        _output_dir: &Path,
+    ) -> CliTypedResult<TransactionSummary> {
+        Err(CliError::CommandArgumentError(
+            "Profiling gas requires building the CLI with the `gas-profiling` feature".to_string(),
+        ))
+    }
+
+    /// Simulates a transaction locally against the latest state of the chain instead of
+    /// submitting it, and writes where its gas is charged to `output_dir`
+    #[cfg(feature = "gas-profiling")]
+    pub async fn profile_gas(
+        &self,
+        payload: TransactionPayload,
+        output_dir: &Path,
+    ) -> CliTypedResult<TransactionSummary> {
+        let client = self.rest_client()?;
+        let (signer, sender_address) = self.get_signer_and_address()?;
+        let sequence_number = self.sequence_number(sender_address).await?;
+
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let mut transaction_factory = TransactionFactory::new(self.chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price);
+        if let Some(max_gas) = self.gas_options.max_gas {
+            transaction_factory = transaction_factory.with_max_gas_amount(max_gas);
+        }
+        let unsigned_transaction = transaction_factory
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number)
+            .build();
+
+        // Same as for the simulation API, the transaction must not be validly signed
+        let signed_transaction = SignedTransaction::new(
+            unsigned_transaction,
+            signer.public_key(),
+            Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+        );
+        let transaction_hash = signed_transaction.clone().committed_hash();
+
+        let interface = Arc::new(RestDebuggerInterface::new(client));
+        let version = interface
+            .get_latest_version()
             .await
             .map_err(|err| CliError::ApiError(err.to_string()))?;
+        let state_view = DebuggerStateView::new(interface, version);
 
-        Ok(response.into_inner())
+        // The state view blocks on the requests to the REST API, so the VM can't run on the runtime
+        let (vm_status, output, profiler) = tokio::task::spawn_blocking(move || {
+            AptosVM::simulate_signed_transaction_with_gas_profiling(
+                &signed_transaction,
+                &state_view,
+            )
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let profiler =
+            profiler.ok_or_else(|| CliError::SimulationError(format!("{:?}", vm_status)))?;
+
+        create_dir_if_not_exist(output_dir)?;
+        let folded_stacks_file = output_dir.join("gas.folded");
+        write_to_file(
+            &folded_stacks_file,
+            "Gas profile",
+            profiler.to_folded_stacks().as_bytes(),
+        )?;
+        let breakdown = serde_json::to_string_pretty(&profiler.profile())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(
+            &output_dir.join("gas.json"),
+            "Gas breakdown",
+            breakdown.as_bytes(),
+        )?;
+        eprintln!(
+            "Gas profile written to {}, render it with e.g. `inferno-flamegraph {}`",
+            output_dir.display(),
+            folded_stacks_file.display()
+        );
+
+        let txn_output = output.txn_output();
+        Ok(TransactionSummary {
+            transaction_hash: transaction_hash.into(),
+            gas_used: Some(txn_output.gas_used()),
+            gas_unit_price: Some(gas_unit_price),
+            pending: None,
+            sender: Some(sender_address),
+            sequence_number: Some(sequence_number),
+            success: Some(matches!(
+                txn_output.status(),
+                TransactionStatus::Keep(ExecutionStatus::Success)
+            )),
+            timestamp_us: None,
+            version: None,
+            vm_status: Some(format!("{:?}", vm_status)),
+        })
     }
 
     pub async fn estimate_gas_price(&self) -> CliTypedResult<u64> {
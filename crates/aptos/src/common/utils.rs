@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::types::{CliError, CliTypedResult, PromptOptions},
+    common::types::{CliError, CliTypedResult, OutputFormat, PromptOptions},
     config::GlobalConfig,
     CliResult,
 };
@@ -15,7 +15,7 @@ use aptos_types::{chain_id::ChainId, transaction::authenticator::AuthenticationK
 use itertools::Itertools;
 use move_core_types::account_address::AccountAddress;
 use reqwest::Url;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
 use std::{
@@ -28,13 +28,31 @@ use std::{
     time::{Duration, Instant},
 };
 
+/// Version of the JSON output of `--output json`, bumped on any breaking change to its format
+pub const JSON_OUTPUT_VERSION: u32 = 1;
+
+/// Prints human readable text, which goes to stderr with `--output json` so that stdout only
+/// holds the JSON output
+pub fn print_text(output_format: OutputFormat, text: &str) {
+    match output_format {
+        OutputFormat::Text => println!("{}", text),
+        OutputFormat::Json => eprintln!("{}", text),
+    }
+}
+
+/// Prints a message the user has to act on, which goes to stderr so that it never mixes with
+/// the output of the command, whatever its format
+pub fn print_prompt(text: &str) {
+    eprintln!("{}", text);
+}
+
 /// Prompts for confirmation until a yes or no is given explicitly
 pub fn prompt_yes(prompt: &str) -> bool {
     let mut result: Result<bool, ()> = Err(());
 
     // Read input until a yes or a no is given
     while result.is_err() {
-        println!("{} [yes/no] >", prompt);
+        print_prompt(&format!("{} [yes/no] >", prompt));
         let mut input = String::new();
         if std::io::stdin().read_line(&mut input).is_err() {
             continue;
@@ -53,8 +71,15 @@ pub async fn to_common_success_result<T>(
     command: &str,
     start_time: Instant,
     result: CliTypedResult<T>,
+    output_format: OutputFormat,
 ) -> CliResult {
-    to_common_result(command, start_time, result.map(|_| "Success")).await
+    to_common_result(
+        command,
+        start_time,
+        result.map(|_| "Success"),
+        output_format,
+    )
+    .await
 }
 
 /// For pretty printing outputs in JSON
@@ -62,6 +87,7 @@ pub async fn to_common_result<T: Serialize>(
     command: &str,
     start_time: Instant,
     result: CliTypedResult<T>,
+    output_format: OutputFormat,
 ) -> CliResult {
     let latency = start_time.elapsed();
     let is_err = result.is_err();
@@ -76,8 +102,31 @@ pub async fn to_common_result<T: Serialize>(
         send_telemetry_event(command, latency, !is_err, error).await;
     }
 
-    let result: ResultWrapper<T> = result.into();
-    let string = serde_json::to_string_pretty(&result).unwrap();
+    let string = match output_format {
+        OutputFormat::Text => {
+            let result: ResultWrapper<T> = result.into();
+            serde_json::to_string_pretty(&result).unwrap()
+        }
+        OutputFormat::Json => {
+            let output = match result {
+                Ok(result) => JsonOutput {
+                    version: JSON_OUTPUT_VERSION,
+                    command: command.to_string(),
+                    result: Some(result),
+                    error: None,
+                    error_type: None,
+                },
+                Err(error) => JsonOutput {
+                    version: JSON_OUTPUT_VERSION,
+                    command: command.to_string(),
+                    result: None,
+                    error: Some(error.to_string()),
+                    error_type: Some(error.to_str().to_string()),
+                },
+            };
+            serde_json::to_string(&output).unwrap()
+        }
+    };
     if is_err {
         Err(string)
     } else {
@@ -130,6 +179,23 @@ enum ResultWrapper<T> {
     Error(String),
 }
 
+/// The output of `--output json`, on a single line e.g.
+///
+/// {"version":1,"command":"TransferCoins","result":{ ... }}
+///
+/// {"version":1,"command":"TransferCoins","error":"...","error_type":"ApiError"}
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JsonOutput<T> {
+    pub version: u32,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+}
+
 impl<T> From<CliTypedResult<T>> for ResultWrapper<T> {
     fn from(result: CliTypedResult<T>) -> Self {
         match result {
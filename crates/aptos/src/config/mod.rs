@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode, ProfileSummary,
-    CONFIG_FOLDER,
+    CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ConfigSearchMode, NetworkConfig,
+    OutputFormat, ProfileSummary, CONFIG_FOLDER,
 };
 use crate::common::utils::{
     create_dir_if_not_exist, current_dir, read_from_file, write_to_user_only_file,
@@ -31,18 +31,24 @@ pub enum ConfigTool {
     Init(crate::common::init::InitTool),
     GenerateShellCompletions(GenerateShellCompletions),
     SetGlobalConfig(SetGlobalConfig),
+    SetNetwork(SetNetwork),
     ShowGlobalConfig(ShowGlobalConfig),
+    ShowNetworks(ShowNetworks),
     ShowProfiles(ShowProfiles),
 }
 
 impl ConfigTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         match self {
-            ConfigTool::Init(tool) => tool.execute_serialized_success().await,
-            ConfigTool::GenerateShellCompletions(tool) => tool.execute_serialized_success().await,
-            ConfigTool::SetGlobalConfig(tool) => tool.execute_serialized().await,
-            ConfigTool::ShowGlobalConfig(tool) => tool.execute_serialized().await,
-            ConfigTool::ShowProfiles(tool) => tool.execute_serialized().await,
+            ConfigTool::Init(tool) => tool.execute_serialized_success(output_format).await,
+            ConfigTool::GenerateShellCompletions(tool) => {
+                tool.execute_serialized_success(output_format).await
+            }
+            ConfigTool::SetGlobalConfig(tool) => tool.execute_serialized(output_format).await,
+            ConfigTool::SetNetwork(tool) => tool.execute_serialized(output_format).await,
+            ConfigTool::ShowGlobalConfig(tool) => tool.execute_serialized(output_format).await,
+            ConfigTool::ShowNetworks(tool) => tool.execute_serialized(output_format).await,
+            ConfigTool::ShowProfiles(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
@@ -158,6 +164,75 @@ impl CliCommand<BTreeMap<String, ProfileSummary>> for ShowProfiles {
     }
 }
 
+/// Add or update a named network
+///
+/// The network can then be selected with `--network <NAME>` instead of passing `--url` and
+/// `--faucet-url` to every command
+#[derive(Parser, Debug)]
+pub struct SetNetwork {
+    /// Name of the network
+    #[clap(long)]
+    pub(crate) name: String,
+    /// URL to a fullnode on the network
+    #[clap(long)]
+    pub(crate) rest_url: reqwest::Url,
+    /// URL for the Faucet endpoint (if applicable)
+    #[clap(long)]
+    pub(crate) faucet_url: Option<reqwest::Url>,
+    /// Chain id of the network, to check before sending transactions
+    #[clap(long)]
+    pub(crate) chain_id: Option<u8>,
+}
+
+#[async_trait]
+impl CliCommand<NetworkConfig> for SetNetwork {
+    fn command_name(&self) -> &'static str {
+        "SetNetwork"
+    }
+
+    async fn execute(self) -> CliTypedResult<NetworkConfig> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(CliError::CommandArgumentError(
+                "--name can't be empty".to_string(),
+            ));
+        }
+
+        let mut config = if CliConfig::config_exists(ConfigSearchMode::CurrentDir) {
+            CliConfig::load(ConfigSearchMode::CurrentDir)?
+        } else {
+            CliConfig::default()
+        };
+        let network_config = NetworkConfig {
+            rest_url: self.rest_url.to_string(),
+            faucet_url: self.faucet_url.map(|url| url.to_string()),
+            chain_id: self.chain_id,
+        };
+        config
+            .networks
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.to_string(), network_config.clone());
+        config.save()?;
+        Ok(network_config)
+    }
+}
+
+/// Shows the named networks of the config
+#[derive(Parser, Debug)]
+pub struct ShowNetworks {}
+
+#[async_trait]
+impl CliCommand<BTreeMap<String, NetworkConfig>> for ShowNetworks {
+    fn command_name(&self) -> &'static str {
+        "ShowNetworks"
+    }
+
+    async fn execute(self) -> CliTypedResult<BTreeMap<String, NetworkConfig>> {
+        let config = CliConfig::load(ConfigSearchMode::CurrentDir)?;
+        Ok(config.networks.unwrap_or_default())
+    }
+}
+
 /// Shows the properties in the global config
 #[derive(Parser, Debug)]
 pub struct ShowGlobalConfig {}
@@ -11,7 +11,7 @@ use crate::common::utils::dir_default_to_current;
 use crate::genesis::git::{OPERATOR_FILE, OWNER_FILE};
 use crate::{
     common::{
-        types::{CliError, CliTypedResult, PromptOptions},
+        types::{CliError, CliTypedResult, OutputFormat, PromptOptions},
         utils::{check_if_file_exists, write_to_file},
     },
     genesis::git::{
@@ -34,7 +34,7 @@ use aptos_genesis::{
 use aptos_logger::info;
 use aptos_types::account_address::{AccountAddress, AccountAddressWithChecks};
 use aptos_types::on_chain_config::OnChainConsensusConfig;
-use aptos_vm_genesis::{default_gas_schedule, AccountBalance, EmployeePool};
+use aptos_vm_genesis::{default_features, default_gas_schedule, AccountBalance, EmployeePool};
 use async_trait::async_trait;
 use clap::Parser;
 use std::cmp::Ordering;
@@ -61,15 +61,21 @@ pub enum GenesisTool {
 }
 
 impl GenesisTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         match self {
-            GenesisTool::GenerateGenesis(tool) => tool.execute_serialized().await,
-            GenesisTool::GenerateKeys(tool) => tool.execute_serialized().await,
-            GenesisTool::GenerateLayoutTemplate(tool) => tool.execute_serialized_success().await,
-            GenesisTool::GenerateAdminWriteSet(tool) => tool.execute_serialized_success().await,
-            GenesisTool::SetupGit(tool) => tool.execute_serialized_success().await,
-            GenesisTool::SetValidatorConfiguration(tool) => tool.execute_serialized_success().await,
-            GenesisTool::GetPoolAddresses(tool) => tool.execute_serialized().await,
+            GenesisTool::GenerateGenesis(tool) => tool.execute_serialized(output_format).await,
+            GenesisTool::GenerateKeys(tool) => tool.execute_serialized(output_format).await,
+            GenesisTool::GenerateLayoutTemplate(tool) => {
+                tool.execute_serialized_success(output_format).await
+            }
+            GenesisTool::GenerateAdminWriteSet(tool) => {
+                tool.execute_serialized_success(output_format).await
+            }
+            GenesisTool::SetupGit(tool) => tool.execute_serialized_success(output_format).await,
+            GenesisTool::SetValidatorConfiguration(tool) => {
+                tool.execute_serialized_success(output_format).await
+            }
+            GenesisTool::GetPoolAddresses(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
@@ -252,6 +258,7 @@ pub fn fetch_mainnet_genesis_info(git_options: GitOptions) -> CliTypedResult<Mai
             employee_vesting_period_duration: layout.employee_vesting_period_duration,
             consensus_config: OnChainConsensusConfig::default(),
             gas_schedule: default_gas_schedule(),
+            initial_features: default_features(),
         },
     )?)
 }
@@ -291,6 +298,7 @@ pub fn fetch_genesis_info(git_options: GitOptions) -> CliTypedResult<GenesisInfo
             employee_vesting_period_duration: layout.employee_vesting_period_duration,
             consensus_config: OnChainConsensusConfig::default(),
             gas_schedule: default_gas_schedule(),
+            initial_features: default_features(),
         },
     )?)
 }
@@ -2,18 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliError, CliTypedResult, MovePackageDir, PoolAddressArgs, ProfileOptions, PromptOptions,
-    RestOptions, TransactionOptions, TransactionSummary,
+    CliError, CliTypedResult, MovePackageDir, OutputFormat, PoolAddressArgs, ProfileOptions,
+    PromptOptions, RestOptions, TransactionOptions, TransactionSummary,
 };
-use crate::common::utils::prompt_yes_with_override;
 #[cfg(feature = "no-upload-proposal")]
 use crate::common::utils::read_from_file;
+use crate::common::utils::{print_text, prompt_yes_with_override, start_logger};
 use crate::move_tool::{FrameworkPackageArgs, IncludedArtifacts};
 use crate::{CliCommand, CliResult};
 use aptos_cached_packages::aptos_stdlib;
 use aptos_crypto::HashValue;
 use aptos_framework::{BuildOptions, BuiltPackage, ReleasePackage};
 use aptos_logger::warn;
+use aptos_release_builder::{
+    propose::proposal_scripts, verification::verify_multi_step_proposal_hashes, ExecutionManifest,
+};
 use aptos_rest_client::aptos_api_types::{Address, HexEncodedBytes, U128, U64};
 use aptos_rest_client::{Client, Transaction};
 use aptos_sdk::move_types::language_storage::CORE_CODE_ADDRESS;
@@ -26,13 +29,19 @@ use aptos_types::{
     transaction::{Script, TransactionPayload},
 };
 use async_trait::async_trait;
-use clap::Parser;
+use chrono::NaiveDateTime;
+use clap::{ArgEnum, Parser};
 use move_core_types::transaction_argument::TransactionArgument;
 use reqwest::Url;
 use serde::Deserialize;
 use serde::Serialize;
 use std::path::Path;
-use std::{collections::BTreeMap, fmt::Formatter, fs, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    fs,
+    path::PathBuf,
+};
 use tempfile::TempDir;
 
 /// Tool for on-chain governance
@@ -48,20 +57,27 @@ pub enum GovernanceTool {
     ListProposals(ListProposals),
     VerifyProposal(VerifyProposal),
     ExecuteProposal(ExecuteProposal),
+    ExecuteRelease(ExecuteRelease),
+    VerifyExecution(VerifyExecution),
     GenerateUpgradeProposal(GenerateUpgradeProposal),
 }
 
 impl GovernanceTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         use GovernanceTool::*;
         match self {
-            Propose(tool) => tool.execute_serialized().await,
-            Vote(tool) => tool.execute_serialized().await,
-            ExecuteProposal(tool) => tool.execute_serialized().await,
-            GenerateUpgradeProposal(tool) => tool.execute_serialized_success().await,
-            ShowProposal(tool) => tool.execute_serialized().await,
-            ListProposals(tool) => tool.execute_serialized().await,
-            VerifyProposal(tool) => tool.execute_serialized().await,
+            Propose(tool) => tool.execute_serialized(output_format).await,
+            Vote(tool) => tool.execute_serialized(output_format).await,
+            ExecuteProposal(tool) => tool.execute_serialized(output_format).await,
+            ExecuteRelease(tool) => tool.execute_serialized(output_format).await,
+            GenerateUpgradeProposal(tool) => tool.execute_serialized_success(output_format).await,
+            ShowProposal(tool) => tool.execute_serialized(output_format).await,
+            ListProposals(tool) => match tool.format {
+                ListFormat::Json => tool.execute_serialized(output_format).await,
+                ListFormat::Table => tool.execute_table().await,
+            },
+            VerifyProposal(tool) => tool.execute_serialized(output_format).await,
+            VerifyExecution(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
@@ -131,18 +147,44 @@ impl CliCommand<VerifiedProposal> for ViewProposal {
     }
 }
 
-/// List the last 100 visible onchain proposals
+/// List onchain governance proposals
 ///
-/// Note, if the full node you are talking to is pruning data, it may not have some of the
-/// proposals show here
+/// Pages through the proposals from the newest one backwards, showing their votes, voting
+/// deadline, execution hash and status.  Proposals can be filtered by status, e.g. to find the
+/// ones that passed but still have to be executed.
 #[derive(Parser)]
 pub struct ListProposals {
+    /// Only list proposals with this status
+    #[clap(long, arg_enum)]
+    status: Option<ProposalStatus>,
+
+    /// The identifier of the proposal to start listing backwards from, defaults to the latest one
+    #[clap(long)]
+    start_id: Option<u64>,
+
+    /// Maximum number of proposals to list
+    #[clap(long, default_value_t = 100)]
+    limit: u64,
+
+    /// Output format: [json, table]
+    #[clap(long, arg_enum, default_value_t = ListFormat::Json)]
+    format: ListFormat,
+
     #[clap(flatten)]
     rest_options: RestOptions,
     #[clap(flatten)]
     profile: ProfileOptions,
 }
 
+impl ListProposals {
+    /// Prints the proposals as a table instead of JSON
+    async fn execute_table(self) -> CliResult {
+        start_logger();
+        let proposals = self.execute().await.map_err(|err| err.to_string())?;
+        Ok(format_proposal_table(&proposals))
+    }
+}
+
 #[async_trait]
 impl CliCommand<Vec<ProposalSummary>> for ListProposals {
     fn command_name(&self) -> &'static str {
@@ -150,31 +192,109 @@ impl CliCommand<Vec<ProposalSummary>> for ListProposals {
     }
 
     async fn execute(mut self) -> CliTypedResult<Vec<ProposalSummary>> {
-        // List out known proposals based on events
         let client = self.rest_options.client(&self.profile)?;
-
-        let events = client
-            .get_account_events_bcs(
+        let forum = client
+            .get_account_resource_bcs::<VotingForum>(
                 AccountAddress::ONE,
-                "0x1::aptos_governance::GovernanceEvents",
-                "create_proposal_events",
-                None,
-                Some(100),
+                "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
             )
             .await?
             .into_inner();
-        let mut proposals = vec![];
+        let voting_table = forum.table_handle.0;
+
+        // Use the chain's time rather than the local one to tell whether voting is over
+        let now_secs = client
+            .get_ledger_information()
+            .await?
+            .into_inner()
+            .timestamp_usecs
+            / 1_000_000;
 
-        for event in events {
-            let event = bcs::from_bytes::<CreateProposalFullEvent>(event.event.event_data())?;
-            proposals.push(event.into());
+        let mut proposals = vec![];
+        let latest_id = match forum.next_proposal_id.checked_sub(1) {
+            Some(latest_id) => latest_id,
+            None => return Ok(proposals),
+        };
+        let start_id = self.start_id.unwrap_or(latest_id).min(latest_id);
+        for proposal_id in (0..=start_id).rev() {
+            if proposals.len() as u64 >= self.limit {
+                break;
+            }
+            let proposal: Proposal = get_proposal(&client, voting_table, proposal_id)
+                .await?
+                .into();
+            let status = proposal.status(now_secs);
+            if self.status.map_or(true, |wanted| wanted == status) {
+                proposals.push(ProposalSummary::new(proposal_id, status, proposal));
+            }
         }
 
-        // TODO: Show more information about proposal?
         Ok(proposals)
     }
 }
 
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListFormat {
+    Json,
+    Table,
+}
+
+impl Display for ListFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ListFormat::Json => "json",
+            ListFormat::Table => "table",
+        })
+    }
+}
+
+/// Status of a proposal, following `0x1::voting::get_proposal_state`
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Voting is still open
+    Active,
+    /// Voting is over and the proposal passed, but it hasn't been executed yet
+    PendingExecution,
+    /// Voting is over and the proposal didn't pass
+    Failed,
+    /// The proposal has been executed
+    Executed,
+}
+
+impl Display for ProposalStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ProposalStatus::Active => "active",
+            ProposalStatus::PendingExecution => "pending-execution",
+            ProposalStatus::Failed => "failed",
+            ProposalStatus::Executed => "executed",
+        })
+    }
+}
+
+fn format_proposal_table(proposals: &[ProposalSummary]) -> String {
+    let mut table = format!(
+        "{:<6} {:<18} {:>24} {:>24} {:<20} {:<64}",
+        "ID", "STATUS", "YES VOTES", "NO VOTES", "VOTING ENDS (UTC)", "EXECUTION HASH"
+    );
+    for proposal in proposals {
+        let voting_ends = NaiveDateTime::from_timestamp_opt(proposal.expiration_secs as i64, 0)
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| proposal.expiration_secs.to_string());
+        table.push_str(&format!(
+            "\n{:<6} {:<18} {:>24} {:>24} {:<20} {:<64}",
+            proposal.proposal_id,
+            proposal.status.to_string(),
+            proposal.yes_votes,
+            proposal.no_votes,
+            voting_ends,
+            proposal.execution_hash
+        ));
+    }
+    table
+}
+
 /// Verify a proposal given the source code of the script
 ///
 /// The script's bytecode or source can be provided and it will
@@ -270,12 +390,21 @@ pub struct SubmitProposal {
     #[clap(long)]
     pub(crate) metadata_path: Option<PathBuf>,
 
+    /// Whether the proposal is the first step of a multi-step proposal
+    ///
+    /// The script of each step resolves the proposal with the hash of the script of the next
+    /// step, so that all the steps are executed with the votes on this proposal.
+    #[clap(long)]
+    pub(crate) is_multi_step: bool,
+
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
     pub(crate) pool_address_args: PoolAddressArgs,
     #[clap(flatten)]
     pub(crate) compile_proposal_args: CompileScriptFunction,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[async_trait]
@@ -284,6 +413,11 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
         "SubmitProposal"
     }
 
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     async fn execute(mut self) -> CliTypedResult<ProposalSubmissionSummary> {
         let (_bytecode, script_hash) = self
             .compile_proposal_args
@@ -292,53 +426,83 @@ impl CliCommand<ProposalSubmissionSummary> for SubmitProposal {
         // Validate the proposal metadata
         let (metadata, metadata_hash) = self.get_metadata().await?;
 
-        println!(
-            "{}\n\tMetadata Hash: {}\n\tScript Hash: {}",
-            metadata, metadata_hash, script_hash
+        print_text(
+            self.output_format,
+            &format!(
+                "{}\n\tMetadata Hash: {}\n\tScript Hash: {}",
+                metadata, metadata_hash, script_hash
+            ),
         );
         prompt_yes_with_override(
             "Do you want to submit this proposal?",
             self.txn_options.prompt_options,
         )?;
 
-        let txn = self
-            .txn_options
-            .submit_transaction(aptos_stdlib::aptos_governance_create_proposal(
-                self.pool_address_args.pool_address,
-                script_hash.to_vec(),
-                self.metadata_url.to_string().as_bytes().to_vec(),
-                metadata_hash.to_hex().as_bytes().to_vec(),
-            ))
-            .await?;
-        let txn_summary = TransactionSummary::from(&txn);
-        if let Transaction::UserTransaction(inner) = txn {
-            // Find event with proposal id
-            let proposal_id = if let Some(event) = inner.events.into_iter().find(|event| {
-                event.typ.to_string().as_str() == "0x1::aptos_governance::CreateProposalEvent"
-            }) {
-                let data: CreateProposalEvent =
-                    serde_json::from_value(event.data).map_err(|_| {
-                        CliError::UnexpectedError(
-                            "Failed to parse Proposal event to get ProposalId".to_string(),
-                        )
-                    })?;
-                Some(data.proposal_id.0)
-            } else {
-                warn!("No proposal event found to find proposal id");
-                None
-            };
-
-            return Ok(ProposalSubmissionSummary {
-                proposal_id,
-                transaction: txn_summary,
-            });
-        }
-        Err(CliError::UnexpectedError(
-            "Unable to find parse proposal transaction output".to_string(),
-        ))
+        create_proposal(
+            &self.txn_options,
+            self.pool_address_args.pool_address,
+            script_hash,
+            &self.metadata_url,
+            metadata_hash,
+            self.is_multi_step,
+        )
+        .await
     }
 }
 
+/// Creates a proposal executed by the script of the given hash
+async fn create_proposal(
+    txn_options: &TransactionOptions,
+    pool_address: AccountAddress,
+    script_hash: HashValue,
+    metadata_url: &Url,
+    metadata_hash: HashValue,
+    is_multi_step: bool,
+) -> CliTypedResult<ProposalSubmissionSummary> {
+    let payload = if is_multi_step {
+        aptos_stdlib::aptos_governance_create_proposal_v2(
+            pool_address,
+            script_hash.to_vec(),
+            metadata_url.to_string().as_bytes().to_vec(),
+            metadata_hash.to_hex().as_bytes().to_vec(),
+            true,
+        )
+    } else {
+        aptos_stdlib::aptos_governance_create_proposal(
+            pool_address,
+            script_hash.to_vec(),
+            metadata_url.to_string().as_bytes().to_vec(),
+            metadata_hash.to_hex().as_bytes().to_vec(),
+        )
+    };
+    let txn = txn_options.submit_transaction(payload).await?;
+    let txn_summary = TransactionSummary::from(&txn);
+    if let Transaction::UserTransaction(inner) = txn {
+        // Find event with proposal id
+        let proposal_id = if let Some(event) = inner.events.into_iter().find(|event| {
+            event.typ.to_string().as_str() == "0x1::aptos_governance::CreateProposalEvent"
+        }) {
+            let data: CreateProposalEvent = serde_json::from_value(event.data).map_err(|_| {
+                CliError::UnexpectedError(
+                    "Failed to parse Proposal event to get ProposalId".to_string(),
+                )
+            })?;
+            Some(data.proposal_id.0)
+        } else {
+            warn!("No proposal event found to find proposal id");
+            None
+        };
+
+        return Ok(ProposalSubmissionSummary {
+            proposal_id,
+            transaction: txn_summary,
+        });
+    }
+    Err(CliError::UnexpectedError(
+        "Unable to find parse proposal transaction output".to_string(),
+    ))
+}
+
 impl SubmitProposal {
     /// Retrieve metadata and validate it
     async fn get_metadata(&self) -> CliTypedResult<(ProposalMetadata, HashValue)> {
@@ -351,29 +515,31 @@ impl SubmitProposal {
         #[cfg(not(feature = "no-upload-proposal"))]
         let bytes = get_metadata_from_url(&self.metadata_url).await?;
 
-        let metadata: ProposalMetadata = serde_json::from_slice(&bytes).map_err(|err| {
-            CliError::CommandArgumentError(format!(
-                "Metadata is not in a proper JSON format: {}",
-                err
-            ))
-        })?;
-        Url::parse(&metadata.source_code_url).map_err(|err| {
-            CliError::CommandArgumentError(format!(
-                "Source code URL {} is invalid {}",
-                metadata.source_code_url, err
-            ))
-        })?;
-        Url::parse(&metadata.discussion_url).map_err(|err| {
-            CliError::CommandArgumentError(format!(
-                "Discussion URL {} is invalid {}",
-                metadata.discussion_url, err
-            ))
-        })?;
-        let metadata_hash = HashValue::sha3_256_of(&bytes);
-        Ok((metadata, metadata_hash))
+        validate_metadata(&bytes)
     }
 }
 
+/// Parses the metadata and checks its URLs, returning it along with its hash
+fn validate_metadata(bytes: &[u8]) -> CliTypedResult<(ProposalMetadata, HashValue)> {
+    let metadata: ProposalMetadata = serde_json::from_slice(bytes).map_err(|err| {
+        CliError::CommandArgumentError(format!("Metadata is not in a proper JSON format: {}", err))
+    })?;
+    Url::parse(&metadata.source_code_url).map_err(|err| {
+        CliError::CommandArgumentError(format!(
+            "Source code URL {} is invalid {}",
+            metadata.source_code_url, err
+        ))
+    })?;
+    Url::parse(&metadata.discussion_url).map_err(|err| {
+        CliError::CommandArgumentError(format!(
+            "Discussion URL {} is invalid {}",
+            metadata.discussion_url, err
+        ))
+    })?;
+    let metadata_hash = HashValue::sha3_256_of(bytes);
+    Ok((metadata, metadata_hash))
+}
+
 /// Retrieve the Metadata from the given URL
 async fn get_metadata_from_url(metadata_url: &Url) -> CliTypedResult<Vec<u8>> {
     let client = reqwest::ClientBuilder::default()
@@ -437,6 +603,8 @@ pub struct SubmitVote {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[async_trait]
@@ -445,6 +613,11 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
         "SubmitVote"
     }
 
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     async fn execute(mut self) -> CliTypedResult<Vec<TransactionSummary>> {
         let (vote_str, vote) = match (self.yes, self.no) {
             (true, false) => ("Yes", true),
@@ -456,71 +629,97 @@ impl CliCommand<Vec<TransactionSummary>> for SubmitVote {
             }
         };
 
-        let client: &Client = &self
-            .txn_options
-            .rest_options
-            .client(&self.txn_options.profile_options)?;
-        let proposal_id = self.proposal_id;
-        let voting_records = client
-            .get_account_resource_bcs::<VotingRecords>(
-                CORE_CODE_ADDRESS,
-                "0x1::aptos_governance::VotingRecords",
-            )
-            .await
-            .unwrap()
-            .into_inner()
-            .votes;
-
-        let mut summaries: Vec<TransactionSummary> = vec![];
-        for pool_address in self.pool_addresses {
-            let voting_record = client
-                .get_table_item(
-                    voting_records,
-                    "0x1::aptos_governance::RecordKey",
-                    "bool",
-                    VotingRecord {
-                        proposal_id: proposal_id.to_string(),
-                        stake_pool: pool_address,
-                    },
-                )
-                .await;
-            let voted = if let Ok(voting_record) = voting_record {
-                voting_record.into_inner().as_bool().unwrap()
-            } else {
-                false
-            };
-            if voted {
-                println!("Stake pool {} already voted", pool_address);
-                continue;
-            }
+        vote_with_pools(
+            &self.txn_options,
+            self.proposal_id,
+            &self.pool_addresses,
+            vote_str,
+            vote,
+            self.output_format,
+        )
+        .await
+    }
+}
 
-            let stake_pool = client
-                .get_account_resource_bcs::<StakePool>(pool_address, "0x1::stake::StakePool")
-                .await?
-                .into_inner();
-            let voting_power = stake_pool.get_governance_voting_power();
-
-            prompt_yes_with_override(
-                &format!(
-                    "Vote {} with voting power = {} from stake pool {}?",
-                    vote_str, voting_power, pool_address
-                ),
-                self.txn_options.prompt_options,
-            )?;
-
-            summaries.push(
-                self.txn_options
-                    .submit_transaction(aptos_stdlib::aptos_governance_vote(
-                        pool_address,
-                        proposal_id,
-                        vote,
-                    ))
-                    .await
-                    .map(TransactionSummary::from)?,
+/// Votes on the proposal with each of the stake pools, skipping the ones that already voted
+async fn vote_with_pools(
+    txn_options: &TransactionOptions,
+    proposal_id: u64,
+    pool_addresses: &[AccountAddress],
+    vote_str: &str,
+    vote: bool,
+    output_format: OutputFormat,
+) -> CliTypedResult<Vec<TransactionSummary>> {
+    let client: &Client = &txn_options
+        .rest_options
+        .client(&txn_options.profile_options)?;
+    let voting_records = client
+        .get_account_resource_bcs::<VotingRecords>(
+            CORE_CODE_ADDRESS,
+            "0x1::aptos_governance::VotingRecords",
+        )
+        .await?
+        .into_inner()
+        .votes;
+
+    let mut summaries: Vec<TransactionSummary> = vec![];
+    for pool_address in pool_addresses.iter().copied() {
+        let voting_record = client
+            .get_table_item(
+                voting_records,
+                "0x1::aptos_governance::RecordKey",
+                "bool",
+                VotingRecord {
+                    proposal_id: proposal_id.to_string(),
+                    stake_pool: pool_address,
+                },
+            )
+            .await;
+        // Stake pools that didn't vote have no record
+        let voted = if let Ok(voting_record) = voting_record {
+            voting_record.into_inner().as_bool().ok_or_else(|| {
+                CliError::UnexpectedError(format!(
+                    "Failed to parse the voting record of stake pool {}",
+                    pool_address
+                ))
+            })?
+        } else {
+            false
+        };
+        if voted {
+            print_text(
+                output_format,
+                &format!("Stake pool {} already voted", pool_address),
             );
+            continue;
         }
-        Ok(summaries)
+
+        let stake_pool = client
+            .get_account_resource_bcs::<StakePool>(pool_address, "0x1::stake::StakePool")
+            .await?
+            .into_inner();
+        let voting_power = stake_pool.get_governance_voting_power();
+
+        prompt_yes_with_override(
+            &format!(
+                "Vote {} with voting power = {} from stake pool {}?",
+                vote_str, voting_power, pool_address
+            ),
+            txn_options.prompt_options,
+        )?;
+
+        summaries.push(
+            txn_options
+                .submit_transaction(aptos_stdlib::aptos_governance_vote(
+                    pool_address,
+                    proposal_id,
+                    vote,
+                ))
+                .await
+                .map(TransactionSummary::from)?,
+        );
     }
+    Ok(summaries)
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -662,6 +861,298 @@ impl CliCommand<TransactionSummary> for ExecuteProposal {
     }
 }
 
+/// Propose, vote on and execute the proposal scripts of a release
+///
+/// The scripts are the ones generated by the release builder in the given folder, executed in
+/// the order of their index.  A multi-step release is a single proposal whose steps are executed
+/// in turn, otherwise each script is a proposal of its own.  Each proposal is voted on with the
+/// given stake pools, then executed once voting is over and it passed.
+#[derive(Parser)]
+pub struct ExecuteRelease {
+    /// Folder of the proposal scripts generated by the release builder
+    #[clap(long, parse(from_os_str))]
+    pub(crate) release_dir: PathBuf,
+
+    /// Location of the JSON metadata of the proposals
+    #[clap(long)]
+    pub(crate) metadata_url: Url,
+
+    /// Whether the scripts are the steps of a multi-step proposal
+    #[clap(long)]
+    pub(crate) is_multi_step: bool,
+
+    /// Space separated list of the pool addresses voting yes on the proposals
+    ///
+    /// If empty, the proposals are only created and executed once others voted on them
+    #[clap(long, multiple_values = true, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) pool_addresses: Vec<AccountAddress>,
+
+    /// Seconds between two checks of the voting state of a proposal
+    #[clap(long, default_value_t = 10)]
+    pub(crate) poll_interval_secs: u64,
+
+    /// Seconds to wait for a proposal to be executable before giving up
+    #[clap(long, default_value_t = 7 * 24 * 3600)]
+    pub(crate) voting_timeout_secs: u64,
+
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) pool_address_args: PoolAddressArgs,
+    #[clap(flatten)]
+    pub(crate) framework_package_args: FrameworkPackageArgs,
+
+    #[clap(long)]
+    pub(crate) bytecode_version: Option<u32>,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
+}
+
+#[async_trait]
+impl CliCommand<Vec<ReleaseProposalSummary>> for ExecuteRelease {
+    fn command_name(&self) -> &'static str {
+        "ExecuteRelease"
+    }
+
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    async fn execute(mut self) -> CliTypedResult<Vec<ReleaseProposalSummary>> {
+        let scripts = proposal_scripts(self.release_dir.as_path())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        if scripts.is_empty() {
+            return Err(CliError::CommandArgumentError(format!(
+                "No proposal script found in {}",
+                self.release_dir.display()
+            )));
+        }
+        let bytes = get_metadata_from_url(&self.metadata_url).await?;
+        let (metadata, metadata_hash) = validate_metadata(&bytes)?;
+        print_text(
+            self.output_format,
+            &format!("{}\n\tMetadata Hash: {}", metadata, metadata_hash),
+        );
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to propose and execute the {} scripts of {}?",
+                scripts.len(),
+                self.release_dir.display()
+            ),
+            self.txn_options.prompt_options,
+        )?;
+
+        let proposals = if self.is_multi_step {
+            vec![scripts]
+        } else {
+            scripts.into_iter().map(|script| vec![script]).collect()
+        };
+
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let mut summaries = vec![];
+        for scripts in proposals {
+            let compiled = scripts
+                .iter()
+                .map(|script| {
+                    compile_in_temp_dir(
+                        "ExecuteRelease",
+                        script,
+                        &self.framework_package_args,
+                        self.txn_options.prompt_options,
+                        self.bytecode_version.unwrap_or(5),
+                    )
+                })
+                .collect::<CliTypedResult<Vec<_>>>()?;
+            if self.is_multi_step {
+                verify_release_steps(&scripts, &compiled)?;
+            }
+
+            let proposal = create_proposal(
+                &self.txn_options,
+                self.pool_address_args.pool_address,
+                compiled[0].1,
+                &self.metadata_url,
+                metadata_hash,
+                self.is_multi_step,
+            )
+            .await?;
+            let proposal_id = proposal.proposal_id.ok_or_else(|| {
+                CliError::UnexpectedError(format!(
+                    "Failed to find the id of the proposal of {}",
+                    scripts[0].display()
+                ))
+            })?;
+
+            let votes = vote_with_pools(
+                &self.txn_options,
+                proposal_id,
+                &self.pool_addresses,
+                "Yes",
+                true,
+                self.output_format,
+            )
+            .await?;
+
+            self.wait_for_resolution(&client, proposal_id).await?;
+
+            // Each transaction is committed before the next one is built, so that it's signed
+            // with the next sequence number of the proposer
+            let mut executions = vec![];
+            for (bytecode, _script_hash) in compiled {
+                let args = vec![TransactionArgument::U64(proposal_id)];
+                let txn = TransactionPayload::Script(Script::new(bytecode, vec![], args));
+                executions.push(
+                    self.txn_options
+                        .submit_transaction(txn)
+                        .await
+                        .map(TransactionSummary::from)?,
+                );
+            }
+
+            summaries.push(ReleaseProposalSummary {
+                proposal_id,
+                scripts: scripts
+                    .iter()
+                    .map(|script| script.display().to_string())
+                    .collect(),
+                creation: proposal.transaction,
+                votes,
+                executions,
+            });
+        }
+        Ok(summaries)
+    }
+}
+
+/// Checks that the steps of a multi-step release are chained by the hashes of their bytecode, as
+/// compiled to be executed. They're the hashes the chain checks each step against.
+fn verify_release_steps(
+    scripts: &[PathBuf],
+    compiled: &[(Vec<u8>, HashValue)],
+) -> CliTypedResult<()> {
+    let hashes: Vec<_> = compiled.iter().map(|(_, hash)| *hash).collect();
+    verify_multi_step_proposal_hashes(scripts, &hashes)
+        .map_err(|err| CliError::CommandArgumentError(err.to_string()))
+}
+
+impl ExecuteRelease {
+    /// Waits until voting on the proposal is over and the proposal passed
+    async fn wait_for_resolution(&self, client: &Client, proposal_id: u64) -> CliTypedResult<()> {
+        let forum = client
+            .get_account_resource_bcs::<VotingForum>(
+                AccountAddress::ONE,
+                "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
+            )
+            .await?
+            .into_inner();
+        let deadline =
+            std::time::Instant::now() + std::time::Duration::from_secs(self.voting_timeout_secs);
+        loop {
+            // Use the chain's time rather than the local one to tell whether voting is over
+            let now_secs = client
+                .get_ledger_information()
+                .await?
+                .into_inner()
+                .timestamp_usecs
+                / 1_000_000;
+            let proposal: Proposal = get_proposal(client, forum.table_handle.0, proposal_id)
+                .await?
+                .into();
+            match proposal.status(now_secs) {
+                // A proposal can't be resolved in the same second as its last vote
+                ProposalStatus::PendingExecution
+                    if proposal
+                        .resolvable_time_secs()
+                        .map_or(true, |time| now_secs > time) =>
+                {
+                    return Ok(())
+                }
+                ProposalStatus::Failed => {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Proposal {} failed with {} yes votes and {} no votes",
+                        proposal_id, proposal.yes_votes, proposal.no_votes
+                    )))
+                }
+                ProposalStatus::Executed => {
+                    return Err(CliError::UnexpectedError(format!(
+                        "Proposal {} is already resolved",
+                        proposal_id
+                    )))
+                }
+                status => {
+                    if std::time::Instant::now() >= deadline {
+                        return Err(CliError::UnexpectedError(format!(
+                            "Proposal {} is still {} after {} seconds",
+                            proposal_id, status, self.voting_timeout_secs
+                        )));
+                    }
+                    print_text(
+                        self.output_format,
+                        &format!(
+                            "Proposal {} is {}, with {} yes votes and {} no votes",
+                            proposal_id, status, proposal.yes_votes, proposal.no_votes
+                        ),
+                    );
+                    tokio::time::sleep(std::time::Duration::from_secs(self.poll_interval_secs))
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+/// Verify that an executed proposal had the intended effect
+///
+/// The manifest is generated by the release builder next to each proposal
+/// script, and lists the on-chain state expected once the script has been
+/// executed, e.g. the gas schedule or the feature flags. The command fails if
+/// any of them doesn't hold.
+#[derive(Parser)]
+pub struct VerifyExecution {
+    /// Path to the manifest of the executed proposal script
+    #[clap(long, parse(from_os_str))]
+    manifest: PathBuf,
+
+    #[clap(flatten)]
+    rest_options: RestOptions,
+    #[clap(flatten)]
+    profile: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<VerifyExecutionResponse> for VerifyExecution {
+    fn command_name(&self) -> &'static str {
+        "VerifyExecution"
+    }
+
+    async fn execute(mut self) -> CliTypedResult<VerifyExecutionResponse> {
+        let manifest = ExecutionManifest::load(self.manifest.as_path())?;
+        let client = self.rest_options.client(&self.profile)?;
+        let mismatches = manifest.verify(&client).await?;
+
+        // A mismatch fails the command, so that scripts checking a release stop on it
+        if !mismatches.is_empty() {
+            return Err(CliError::UnexpectedError(format!(
+                "Execution of {} can't be verified: {}",
+                manifest.proposal,
+                mismatches
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
+        Ok(VerifyExecutionResponse {
+            verified: true,
+            proposal: manifest.proposal,
+        })
+    }
+}
+
 /// Execute a proposal that has passed voting requirements
 #[derive(Parser)]
 pub struct CompileScriptFunction {
@@ -752,6 +1243,8 @@ pub struct GenerateUpgradeProposal {
     #[clap(long)]
     pub(crate) testnet: bool,
 
+    /// Generate a step of a multi-step proposal, resolving the proposal with this hex encoded hash
+    /// of the bytecode of the next step's script, or with `vector::empty<u8>()` for the last step.
     #[clap(long, default_value = "")]
     pub(crate) next_execution_hash: String,
 
@@ -797,6 +1290,17 @@ impl CliCommand<()> for GenerateUpgradeProposal {
     }
 }
 
+/// Summary of a proposal of `execute-release`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReleaseProposalSummary {
+    proposal_id: u64,
+    /// The scripts executed by the proposal, in order
+    scripts: Vec<String>,
+    creation: TransactionSummary,
+    votes: Vec<TransactionSummary>,
+    executions: Vec<TransactionSummary>,
+}
+
 /// Response for `verify proposal`
 #[derive(Serialize, Deserialize, Debug)]
 pub struct VerifyProposalResponse {
@@ -805,6 +1309,13 @@ pub struct VerifyProposalResponse {
     onchain_hash: String,
 }
 
+/// Response for `verify execution`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyExecutionResponse {
+    pub verified: bool,
+    pub proposal: String,
+}
+
 /// Voting forum onchain type
 ///
 /// TODO: Move to a shared location
@@ -823,42 +1334,44 @@ pub struct VotingEvents {
     vote_events: EventHandle,
 }
 
-/// Summary of proposal from the listing events for `ListProposals`
+/// Summary of a proposal for `ListProposals`
 #[derive(Serialize, Deserialize, Debug)]
-struct ProposalSummary {
-    proposer: AccountAddress,
-    stake_pool: AccountAddress,
+pub struct ProposalSummary {
     proposal_id: u64,
+    status: ProposalStatus,
+    proposer: AccountAddress,
+    yes_votes: u128,
+    no_votes: u128,
+    min_vote_threshold: u128,
+    early_resolution_vote_threshold: Option<u128>,
+    creation_time_secs: u64,
+    /// When voting ends
+    expiration_secs: u64,
     execution_hash: String,
-    proposal_metadata: BTreeMap<String, String>,
+    /// When the proposal was executed, 0 if it hasn't been
+    resolution_time_secs: u64,
+    metadata: BTreeMap<String, String>,
 }
 
-impl From<CreateProposalFullEvent> for ProposalSummary {
-    fn from(event: CreateProposalFullEvent) -> Self {
-        let proposal_metadata = event
-            .proposal_metadata
-            .into_iter()
-            .map(|(key, value)| (key, String::from_utf8(value).unwrap()))
-            .collect();
+impl ProposalSummary {
+    fn new(proposal_id: u64, status: ProposalStatus, proposal: Proposal) -> Self {
         ProposalSummary {
-            proposer: event.proposer,
-            stake_pool: event.stake_pool,
-            proposal_id: event.proposal_id,
-            execution_hash: hex::encode(event.execution_hash),
-            proposal_metadata,
+            proposal_id,
+            status,
+            proposer: proposal.proposer,
+            yes_votes: proposal.yes_votes,
+            no_votes: proposal.no_votes,
+            min_vote_threshold: proposal.min_vote_threshold,
+            early_resolution_vote_threshold: proposal.early_resolution_vote_threshold,
+            creation_time_secs: proposal.creation_time_secs,
+            expiration_secs: proposal.expiration_secs,
+            execution_hash: proposal.execution_hash,
+            resolution_time_secs: proposal.resolution_time_secs,
+            metadata: proposal.metadata,
         }
     }
 }
 
-#[derive(Deserialize)]
-struct CreateProposalFullEvent {
-    proposer: AccountAddress,
-    stake_pool: AccountAddress,
-    proposal_id: u64,
-    execution_hash: Vec<u8>,
-    proposal_metadata: BTreeMap<String, Vec<u8>>,
-}
-
 /// A proposal and the verified information about it
 #[derive(Serialize, Deserialize, Debug)]
 pub struct VerifiedProposal {
@@ -884,6 +1397,36 @@ pub struct Proposal {
     resolution_time_secs: u64,
 }
 
+impl Proposal {
+    /// Mirrors `0x1::voting::get_proposal_state`, given the current onchain time
+    fn status(&self, now_secs: u64) -> ProposalStatus {
+        if self.is_resolved {
+            return ProposalStatus::Executed;
+        }
+        let resolved_early = self
+            .early_resolution_vote_threshold
+            .map_or(false, |threshold| {
+                self.yes_votes >= threshold || self.no_votes >= threshold
+            });
+        if !resolved_early && now_secs <= self.expiration_secs {
+            ProposalStatus::Active
+        } else if self.yes_votes > self.no_votes
+            && self.yes_votes + self.no_votes >= self.min_vote_threshold
+        {
+            ProposalStatus::PendingExecution
+        } else {
+            ProposalStatus::Failed
+        }
+    }
+
+    /// The time after which the proposal can be resolved, set on each vote
+    fn resolvable_time_secs(&self) -> Option<u64> {
+        self.metadata
+            .get("RESOLVABLE_TIME_METADATA_KEY")
+            .and_then(|time| time.parse().ok())
+    }
+}
+
 impl From<JsonProposal> for Proposal {
     fn from(proposal: JsonProposal) -> Self {
         let metadata = proposal
@@ -956,3 +1499,62 @@ struct JsonMetadataPair {
     key: String,
     value: HexEncodedBytes,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_release_builder::ReleaseConfig;
+    use aptos_types::on_chain_config::Version;
+
+    #[test]
+    fn test_verify_release_steps() {
+        let release_dir = TempDir::new().unwrap();
+        ReleaseConfig {
+            framework_release: false,
+            is_multi_step: true,
+            version: Some(Version { major: 10 }),
+            ..Default::default()
+        }
+        .generate_release_proposal_scripts(release_dir.path())
+        .unwrap();
+        let scripts = proposal_scripts(release_dir.path()).unwrap();
+        assert_eq!(scripts.len(), 3);
+
+        // The steps are compiled as they're executed by `execute-release`
+        let framework_package_args = FrameworkPackageArgs {
+            framework_git_rev: None,
+            framework_local_dir: Some(
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("../../aptos-move/framework/aptos-framework"),
+            ),
+            skip_fetch_latest_git_deps: true,
+        };
+        let compiled = scripts
+            .iter()
+            .map(|script| {
+                compile_in_temp_dir(
+                    "ExecuteRelease",
+                    script,
+                    &framework_package_args,
+                    PromptOptions::yes(),
+                    5,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        verify_release_steps(&scripts, &compiled).unwrap();
+
+        // Steps can't be skipped nor reordered
+        verify_release_steps(&scripts[1..], &compiled[1..]).unwrap();
+        verify_release_steps(
+            &[scripts[0].clone(), scripts[2].clone()],
+            &[compiled[0].clone(), compiled[2].clone()],
+        )
+        .unwrap_err();
+        verify_release_steps(
+            &[scripts[1].clone(), scripts[0].clone()],
+            &[compiled[1].clone(), compiled[0].clone()],
+        )
+        .unwrap_err();
+    }
+}
@@ -9,22 +9,43 @@ pub mod config;
 pub mod genesis;
 pub mod governance;
 pub mod move_tool;
+pub mod multisig;
 pub mod node;
 pub mod op;
 pub mod stake;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod test;
+pub mod transaction;
 
-use crate::common::types::{CliCommand, CliResult, CliTypedResult};
+use crate::common::types::{CliCommand, CliResult, CliTypedResult, OutputFormat};
 use crate::common::utils::cli_build_information;
 use async_trait::async_trait;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::collections::BTreeMap;
 
 /// Command Line Interface (CLI) for developing and interacting with the Aptos blockchain
 #[derive(Parser)]
 #[clap(name = "aptos", author, version, propagate_version = true)]
-pub enum Tool {
+pub struct Tool {
+    /// Format of the output: [text, json]
+    ///
+    /// `json` only prints the versioned JSON output of the command to stdout, to be parsed by
+    /// scripts.  Commands with their own `--output` argument keep it, e.g. `aptos governance
+    /// generate-upgrade-proposal`.
+    #[clap(long, global = true, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+    #[clap(subcommand)]
+    command: ToolCommand,
+}
+
+impl Tool {
+    pub async fn execute(self) -> CliResult {
+        self.command.execute(self.output).await
+    }
+}
+
+#[derive(Subcommand)]
+pub enum ToolCommand {
     #[clap(subcommand)]
     Account(account::AccountTool),
     #[clap(subcommand)]
@@ -40,26 +61,32 @@ pub enum Tool {
     #[clap(subcommand)]
     Move(move_tool::MoveTool),
     #[clap(subcommand)]
+    Multisig(multisig::MultisigTool),
+    #[clap(subcommand)]
     Node(node::NodeTool),
     #[clap(subcommand)]
     Stake(stake::StakeTool),
+    #[clap(subcommand)]
+    Transaction(transaction::TransactionTool),
 }
 
-impl Tool {
-    pub async fn execute(self) -> CliResult {
-        use Tool::*;
+impl ToolCommand {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
+        use ToolCommand::*;
         match self {
-            Account(tool) => tool.execute().await,
-            Config(tool) => tool.execute().await,
-            Genesis(tool) => tool.execute().await,
-            Governance(tool) => tool.execute().await,
-            Info(tool) => tool.execute_serialized().await,
+            Account(tool) => tool.execute(output_format).await,
+            Config(tool) => tool.execute(output_format).await,
+            Genesis(tool) => tool.execute(output_format).await,
+            Governance(tool) => tool.execute(output_format).await,
+            Info(tool) => tool.execute_serialized(output_format).await,
             // TODO: Replace entirely with config init
-            Init(tool) => tool.execute_serialized_success().await,
-            Key(tool) => tool.execute().await,
-            Move(tool) => tool.execute().await,
-            Node(tool) => tool.execute().await,
-            Stake(tool) => tool.execute().await,
+            Init(tool) => tool.execute_serialized_success(output_format).await,
+            Key(tool) => tool.execute(output_format).await,
+            Move(tool) => tool.execute(output_format).await,
+            Multisig(tool) => tool.execute(output_format).await,
+            Node(tool) => tool.execute(output_format).await,
+            Stake(tool) => tool.execute(output_format).await,
+            Transaction(tool) => tool.execute(output_format).await,
         }
     }
 }
@@ -11,9 +11,12 @@ mod transactional_tests_runner;
 pub use stored_package::*;
 
 use crate::common::types::MoveManifestAccountWrapper;
-use crate::common::types::{CliConfig, ConfigSearchMode, ProfileOptions, RestOptions};
+use crate::common::types::{
+    CliConfig, ConfigSearchMode, OutputFormat, ProfileOptions, RestOptions,
+};
 use crate::common::utils::{
-    create_dir_if_not_exist, dir_default_to_current, prompt_yes_with_override, write_to_file,
+    create_dir_if_not_exist, dir_default_to_current, print_text, prompt_yes_with_override,
+    write_to_file,
 };
 use crate::governance::CompileScriptFunction;
 use crate::move_tool::manifest::{
@@ -22,8 +25,8 @@ use crate::move_tool::manifest::{
 use crate::{
     common::{
         types::{
-            load_account_arg, CliError, CliTypedResult, MovePackageDir, PromptOptions,
-            TransactionOptions, TransactionSummary,
+            load_account_arg, CliError, CliTypedResult, GasProfilingOptions, MovePackageDir,
+            PromptOptions, TransactionOptions, TransactionSummary,
         },
         utils::check_if_file_exists,
     },
@@ -31,7 +34,7 @@ use crate::{
 };
 use aptos_framework::docgen::DocgenOptions;
 use aptos_framework::natives::code::UpgradePolicy;
-use aptos_framework::prover::ProverOptions;
+use aptos_framework::prover::{ProverDiagnostic, ProverOptions, PROVER_OPTIONS_FILE};
 use aptos_framework::{BuildOptions, BuiltPackage};
 use aptos_gas::{AbstractValueSizeGasParameters, NativeGasParameters};
 use aptos_rest_client::aptos_api_types::MoveType;
@@ -40,7 +43,6 @@ use aptos_types::account_address::{create_resource_address, AccountAddress};
 use aptos_types::transaction::{EntryFunction, Script, TransactionArgument, TransactionPayload};
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser, Subcommand};
-use itertools::Itertools;
 use move_cli::base::test::UnitTestResult;
 use move_command_line_common::env::MOVE_HOME;
 use serde::Serialize;
@@ -60,8 +62,12 @@ use {
     move_core_types::{
         identifier::Identifier,
         language_storage::{ModuleId, TypeTag},
+        value::MoveValue,
+    },
+    move_package::{
+        compilation::package_layout::CompiledPackageLayout,
+        source_package::layout::SourcePackageLayout, BuildConfig,
     },
-    move_package::{source_package::layout::SourcePackageLayout, BuildConfig},
     move_unit_test::UnitTestingConfig,
 };
 
@@ -89,23 +95,25 @@ pub enum MoveTool {
 }
 
 impl MoveTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         match self {
-            MoveTool::Compile(tool) => tool.execute_serialized().await,
-            MoveTool::Init(tool) => tool.execute_serialized_success().await,
-            MoveTool::Publish(tool) => tool.execute_serialized().await,
-            MoveTool::Download(tool) => tool.execute_serialized().await,
-            MoveTool::List(tool) => tool.execute_serialized().await,
-            MoveTool::Clean(tool) => tool.execute_serialized().await,
-            MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
-            MoveTool::Run(tool) => tool.execute_serialized().await,
-            MoveTool::RunScript(tool) => tool.execute_serialized().await,
-            MoveTool::Test(tool) => tool.execute_serialized().await,
-            MoveTool::Prove(tool) => tool.execute_serialized().await,
-            MoveTool::Document(tool) => tool.execute_serialized().await,
-            MoveTool::TransactionalTest(tool) => tool.execute_serialized_success().await,
+            MoveTool::Compile(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Init(tool) => tool.execute_serialized_success(output_format).await,
+            MoveTool::Publish(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Download(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::List(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Clean(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::VerifyPackage(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Run(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::RunScript(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Test(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Prove(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::Document(tool) => tool.execute_serialized(output_format).await,
+            MoveTool::TransactionalTest(tool) => {
+                tool.execute_serialized_success(output_format).await
+            }
             MoveTool::CreateResourceAccountAndPublishPackage(tool) => {
-                tool.execute_serialized_success().await
+                tool.execute_serialized_success(output_format).await
             }
         }
     }
@@ -410,40 +418,59 @@ impl CliCommand<()> for TransactionalTestOpts {
 /// Proves a Move package
 ///
 /// This is a tool for formal verification of a Move package using
-/// the Move prover
+/// the Move prover.  Options can also be set in the `Prover.toml` file next to the `Move.toml`
+/// of the package, in which case the ones given on the command line take precedence.
 #[derive(Parser)]
 pub struct ProvePackage {
     #[clap(flatten)]
-    move_options: MovePackageDir,
+    pub(crate) move_options: MovePackageDir,
 
     #[clap(flatten)]
-    prover_options: ProverOptions,
+    pub(crate) prover_options: ProverOptions,
 }
 
 #[async_trait]
-impl CliCommand<&'static str> for ProvePackage {
+impl CliCommand<Vec<ProverDiagnostic>> for ProvePackage {
     fn command_name(&self) -> &'static str {
         "ProvePackage"
     }
 
-    async fn execute(self) -> CliTypedResult<&'static str> {
+    async fn execute(self) -> CliTypedResult<Vec<ProverDiagnostic>> {
         set_bytecode_version(self.move_options.bytecode_version);
         let ProvePackage {
             move_options,
             prover_options,
         } = self;
+        let package_path = move_options.get_package_path()?;
+        let prover_options = match ProverOptions::from_package(&package_path)
+            .map_err(|err| CliError::UnableToParse(PROVER_OPTIONS_FILE, format!("{:#}", err)))?
+        {
+            Some(manifest_options) => manifest_options.merge(prover_options),
+            None => prover_options,
+        };
 
         let result = task::spawn_blocking(move || {
-            prover_options.prove(
-                move_options.get_package_path()?.as_path(),
-                move_options.named_addresses(),
-            )
+            prover_options
+                .prove_with_diagnostics(package_path.as_path(), move_options.named_addresses())
         })
         .await
         .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
-        match result {
-            Ok(_) => Ok("Success"),
-            Err(e) => Err(CliError::MoveProverError(format!("{:#}", e))),
+        let diagnostics = result.map_err(|e| CliError::MoveProverError(format!("{:#}", e)))?;
+
+        // Verification errors fail the command, while warnings are part of its result
+        let errors: Vec<_> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(|diagnostic| match (&diagnostic.file, diagnostic.line) {
+                (Some(file), Some(line)) => format!("{}:{}: {}", file, line, diagnostic.message),
+                (Some(file), None) => format!("{}: {}", file, diagnostic.message),
+                _ => diagnostic.message.clone(),
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(diagnostics)
+        } else {
+            Err(CliError::MoveProverError(errors.join("\n")))
         }
     }
 }
@@ -517,6 +544,8 @@ pub struct PublishPackage {
     pub(crate) move_options: MovePackageDir,
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[derive(ArgEnum, Clone, Copy, Debug)]
@@ -603,6 +632,11 @@ impl CliCommand<TransactionSummary> for PublishPackage {
         "PublishPackage"
     }
 
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
         set_bytecode_version(self.move_options.bytecode_version);
         let PublishPackage {
@@ -610,6 +644,7 @@ impl CliCommand<TransactionSummary> for PublishPackage {
             txn_options,
             override_size_check,
             included_artifacts_args,
+            output_format,
         } = self;
         let package_path = move_options.get_package_path()?;
         let options = included_artifacts_args.included_artifacts.build_options(
@@ -627,7 +662,7 @@ impl CliCommand<TransactionSummary> for PublishPackage {
             compiled_units,
         );
         let size = bcs::serialized_size(&payload)?;
-        println!("package size {} bytes", size);
+        print_text(output_format, &format!("package size {} bytes", size));
         if !override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
             return Err(CliError::UnexpectedError(format!(
                 "The package is larger than {} bytes ({} bytes)! To lower the size \
@@ -662,6 +697,8 @@ pub struct CreateResourceAccountAndPublishPackage {
     pub(crate) move_options: MovePackageDir,
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[async_trait]
@@ -670,6 +707,11 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
         "ResourceAccountPublishPackage"
     }
 
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
         set_bytecode_version(self.move_options.bytecode_version);
         let CreateResourceAccountAndPublishPackage {
@@ -679,6 +721,7 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
             txn_options,
             override_size_check,
             included_artifacts_args,
+            output_format,
         } = self;
 
         let account = if let Some(Some(account)) = CliConfig::load_profile(
@@ -722,7 +765,7 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
             compiled_units,
         );
         let size = bcs::serialized_size(&payload)?;
-        println!("package size {} bytes", size);
+        print_text(output_format, &format!("package size {} bytes", size));
         if !override_size_check && size > MAX_PUBLISH_PACKAGE_SIZE {
             return Err(CliError::UnexpectedError(format!(
                 "The package is larger than {} bytes ({} bytes)! To lower the size \
@@ -741,7 +784,9 @@ impl CliCommand<TransactionSummary> for CreateResourceAccountAndPublishPackage {
 /// Downloads a package and stores it in a directory named after the package
 ///
 /// This lets you retrieve packages directly from the blockchain for inspection
-/// and use as a local dependency in testing.
+/// and use as a local dependency in testing. The package directory is reconstructed
+/// from the published `Move.toml` and sources, and the bytecode of its modules is
+/// stored in `bytecode_modules`.
 #[derive(Parser)]
 pub struct DownloadPackage {
     /// Address of the account containing the package
@@ -756,10 +801,19 @@ pub struct DownloadPackage {
     #[clap(long, parse(from_os_str))]
     pub output_dir: Option<PathBuf>,
 
+    /// Check the downloaded sources against the source digest of the package
+    ///
+    /// This only succeeds if every source file of the package holds a module, as files
+    /// without modules, e.g. spec files, aren't published.
+    #[clap(long)]
+    pub verify_source_digest: bool,
+
     #[clap(flatten)]
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) profile_options: ProfileOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[async_trait]
@@ -768,6 +822,11 @@ impl CliCommand<&'static str> for DownloadPackage {
         "DownloadPackage"
     }
 
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
     async fn execute(self) -> CliTypedResult<&'static str> {
         let url = self.rest_options.url(&self.profile_options)?;
         let registry = CachedPackageRegistry::create(url, self.account).await?;
@@ -788,16 +847,57 @@ impl CliCommand<&'static str> for DownloadPackage {
         package
             .save_package_to_disk(package_path.as_path())
             .map_err(|e| CliError::UnexpectedError(format!("Failed to save package: {}", e)))?;
-        println!(
-            "Saved package with {} module(s) to `{}`",
-            package.module_names().len(),
-            package_path.display()
+
+        let bytecode_dir = package_path.join(CompiledPackageLayout::CompiledModules.path());
+        create_dir_if_not_exist(bytecode_dir.as_path())?;
+        for module_name in package.module_names() {
+            let bytecode = registry.get_bytecode(module_name).await.map_err(|e| {
+                CliError::ApiError(format!(
+                    "Failed to fetch bytecode of module {}: {}",
+                    module_name, e
+                ))
+            })?;
+            let bytecode_path = bytecode_dir.join(format!("{}.mv", module_name));
+            write_to_file(
+                bytecode_path.as_path(),
+                &bytecode_path.display().to_string(),
+                &bytecode,
+            )?;
+        }
+        print_text(
+            self.output_format,
+            &format!(
+                "Saved package with {} module(s) to `{}`",
+                package.module_names().len(),
+                package_path.display()
+            ),
+        );
+
+        if !self.verify_source_digest {
+            return Ok("Download succeeded");
+        }
+        if !package.has_source() {
+            print_text(
+                self.output_format,
+                "The package was published without sources, its source digest can't be verified",
+            );
+            return Ok("Download succeeded");
+        }
+        package
+            .verify_source_digest()
+            .map_err(|e| CliError::UnexpectedError(e.to_string()))?;
+        print_text(
+            self.output_format,
+            &format!("Verified source digest {}", package.source_digest()),
         );
         Ok("Download succeeded")
     }
 }
 
 /// Downloads a package and verifies that the bytecode matches a local compilation of the Move code
+///
+/// The modules are compared byte for byte, except for the metadata attached to them, then the
+/// package metadata e.g. the source digest is compared unless `--bytecode-only` is set.
 #[derive(Parser)]
 pub struct VerifyPackage {
     /// Address of the account containing the package
@@ -808,6 +908,13 @@ pub struct VerifyPackage {
     #[clap(long, default_value_t = IncludedArtifacts::Sparse)]
     pub(crate) included_artifacts: IncludedArtifacts,
 
+    /// Only compare the bytecode of the modules
+    ///
+    /// The package metadata, e.g. the upgrade number, changes with every upgrade, so it doesn't
+    /// match a local build of a package upgraded by governance.
+    #[clap(long)]
+    pub(crate) bytecode_only: bool,
+
     #[clap(flatten)]
     pub(crate) move_options: MovePackageDir,
     #[clap(flatten)]
@@ -816,13 +923,20 @@ pub struct VerifyPackage {
     pub(crate) profile_options: ProfileOptions,
 }
 
+/// The modules of a package matching the code published on chain
+#[derive(Debug, Serialize)]
+pub struct VerifyPackageSummary {
+    pub package: String,
+    pub verified_modules: Vec<String>,
+}
+
 #[async_trait]
-impl CliCommand<&'static str> for VerifyPackage {
+impl CliCommand<VerifyPackageSummary> for VerifyPackage {
     fn command_name(&self) -> &'static str {
-        "DownloadPackage"
+        "VerifyPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<&'static str> {
+    async fn execute(self) -> CliTypedResult<VerifyPackageSummary> {
         set_bytecode_version(self.move_options.bytecode_version);
         // First build the package locally to get the package metadata
         let build_options = BuildOptions {
@@ -836,7 +950,6 @@ impl CliCommand<&'static str> for VerifyPackage {
         };
         let pack = BuiltPackage::build(self.move_options.get_package_path()?, build_options)
             .map_err(|e| CliError::MoveCompilationError(format!("{:#}", e)))?;
-        let compiled_metadata = pack.extract_metadata()?;
 
         // Now pull the compiled package
         let url = self.rest_options.url(&self.profile_options)?;
@@ -855,10 +968,30 @@ impl CliCommand<&'static str> for VerifyPackage {
             ));
         }
 
+        // Compare the bytecode before the metadata, so that the differing modules are reported
+        let modules: Vec<_> = pack.modules().cloned().collect();
+        let differing_modules = registry.diff_bytecode(pack.name(), &modules).await?;
+        if !differing_modules.is_empty() {
+            return Err(CliError::UnexpectedError(format!(
+                "Modules [{}] of package {} don't match the code published at {}",
+                differing_modules.join(", "),
+                pack.name(),
+                self.account
+            )));
+        }
+
         // Verify that the source digest matches
-        package.verify(&compiled_metadata)?;
+        if !self.bytecode_only {
+            package.verify(&pack.extract_metadata()?)?;
+        }
 
-        Ok("Successfully verified source of package")
+        Ok(VerifyPackageSummary {
+            package: pack.name().to_string(),
+            verified_modules: modules
+                .iter()
+                .map(|module| module.self_id().name().to_string())
+                .collect(),
+        })
     }
 }
 
@@ -905,28 +1038,45 @@ impl FromStr for MoveListQuery {
     }
 }
 
+/// A package published on chain
+#[derive(Debug, Serialize)]
+pub struct PackageSummary {
+    pub name: String,
+    pub upgrade_policy: String,
+    pub upgrade_number: u64,
+    pub source_digest: String,
+    pub modules: Vec<String>,
+}
+
 #[async_trait]
-impl CliCommand<&'static str> for ListPackage {
+impl CliCommand<Vec<PackageSummary>> for ListPackage {
     fn command_name(&self) -> &'static str {
         "ListPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<&'static str> {
+    async fn execute(self) -> CliTypedResult<Vec<PackageSummary>> {
         let url = self.rest_options.url(&self.profile_options)?;
         let registry = CachedPackageRegistry::create(url, self.account).await?;
+        let mut packages = vec![];
         match self.query {
             MoveListQuery::Packages => {
                 for name in registry.package_names() {
                     let data = registry.get_package(name).await?;
-                    println!("package {}", data.name());
-                    println!("  upgrade_policy: {}", data.upgrade_policy());
-                    println!("  upgrade_number: {}", data.upgrade_number());
-                    println!("  source_digest: {}", data.source_digest());
-                    println!("  modules: {}", data.module_names().into_iter().join(", "));
+                    packages.push(PackageSummary {
+                        name: data.name().to_string(),
+                        upgrade_policy: data.upgrade_policy().to_string(),
+                        upgrade_number: data.upgrade_number(),
+                        source_digest: data.source_digest().to_string(),
+                        modules: data
+                            .module_names()
+                            .into_iter()
+                            .map(str::to_string)
+                            .collect(),
+                    });
                 }
             }
         }
-        Ok("list succeeded")
+        Ok(packages)
     }
 }
 
@@ -984,7 +1134,11 @@ pub struct RunFunction {
 
     /// Arguments combined with their type separated by spaces.
     ///
-    /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
+    /// Supported types [u8, u64, u128, bool, hex, string, address, raw, vector<inner_type>]
+    ///
+    /// Vectors are either separated by commas or JSON-encoded, the latter being required for
+    /// nested vectors e.g. `vector<vector<u8>>:[[1,2],[3]]`. Arguments of any other type are
+    /// passed as BCS hex blobs along with their type e.g. `bcs:0x1::string::String:0568656c6c6f`
     ///
     /// Example: `address:0x1 bool:true u8:0`
     #[clap(long, multiple_values = true)]
@@ -998,6 +1152,8 @@ pub struct RunFunction {
 
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
+    #[clap(flatten)]
+    pub(crate) gas_profiling_options: GasProfilingOptions,
 }
 
 #[async_trait]
@@ -1007,32 +1163,47 @@ impl CliCommand<TransactionSummary> for RunFunction {
     }
 
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        let args: Vec<Vec<u8>> = self
-            .args
-            .into_iter()
-            .map(|arg_with_type| arg_with_type.arg)
-            .collect();
-        let mut type_args: Vec<TypeTag> = Vec::new();
-
-        // These TypeArgs are used for generics
-        for type_arg in self.type_args.into_iter() {
-            let type_tag = TypeTag::try_from(type_arg)
-                .map_err(|err| CliError::UnableToParse("--type-args", err.to_string()))?;
-            type_args.push(type_tag)
+        let payload = entry_function_payload(self.function_id, self.args, self.type_args)?;
+        if self.gas_profiling_options.profile_gas {
+            return self
+                .txn_options
+                .profile_gas(payload, &self.gas_profiling_options.gas_profile_dir)
+                .await;
         }
-
         self.txn_options
-            .submit_transaction(TransactionPayload::EntryFunction(EntryFunction::new(
-                self.function_id.module_id,
-                self.function_id.member_id,
-                type_args,
-                args,
-            )))
+            .submit_transaction(payload)
             .await
             .map(TransactionSummary::from)
     }
 }
 
+/// Builds the payload calling an entry function with the arguments given on the command line
+pub(crate) fn entry_function_payload(
+    function_id: MemberId,
+    args: Vec<ArgWithType>,
+    type_args: Vec<MoveType>,
+) -> CliTypedResult<TransactionPayload> {
+    let args: Vec<Vec<u8>> = args
+        .into_iter()
+        .map(|arg_with_type| arg_with_type.arg)
+        .collect();
+    let mut type_tags: Vec<TypeTag> = Vec::new();
+
+    // These TypeArgs are used for generics
+    for type_arg in type_args.into_iter() {
+        let type_tag = TypeTag::try_from(type_arg)
+            .map_err(|err| CliError::UnableToParse("--type-args", err.to_string()))?;
+        type_tags.push(type_tag)
+    }
+
+    Ok(TransactionPayload::EntryFunction(EntryFunction::new(
+        function_id.module_id,
+        function_id.member_id,
+        type_tags,
+        args,
+    )))
+}
+
 /// Run a Move script
 #[derive(Parser)]
 pub struct RunScript {
@@ -1042,7 +1213,11 @@ pub struct RunScript {
     pub(crate) compile_proposal_args: CompileScriptFunction,
     /// Arguments combined with their type separated by spaces.
     ///
-    /// Supported types [u8, u64, u128, bool, hex, string, address, raw]
+    /// Supported types [u8, u64, u128, bool, hex, string, address, raw, vector<u8>]
+    ///
+    /// Scripts only take arguments of these types, which can also be passed as BCS hex blobs
+    /// along with their type e.g. `bcs:u64:0100000000000000`. Nested vectors and structs can
+    /// only be passed to entry functions, see `aptos move run`.
     ///
     /// Example: `address:0x1 bool:true u8:0`
     #[clap(long, multiple_values = true)]
@@ -1052,6 +1227,8 @@ pub struct RunScript {
     /// Example: `u8 u64 u128 bool address vector signer`
     #[clap(long, multiple_values = true)]
     pub(crate) type_args: Vec<MoveType>,
+    #[clap(flatten)]
+    pub(crate) gas_profiling_options: GasProfilingOptions,
 }
 
 #[async_trait]
@@ -1079,12 +1256,14 @@ impl CliCommand<TransactionSummary> for RunScript {
             type_args.push(type_tag)
         }
 
-        let txn = self
-            .txn_options
-            .submit_transaction(TransactionPayload::Script(Script::new(
-                bytecode, type_args, args,
-            )))
-            .await?;
+        let payload = TransactionPayload::Script(Script::new(bytecode, type_args, args));
+        if self.gas_profiling_options.profile_gas {
+            return self
+                .txn_options
+                .profile_gas(payload, &self.gas_profiling_options.gas_profile_dir)
+                .await;
+        }
+        let txn = self.txn_options.submit_transaction(payload).await?;
         Ok(TransactionSummary::from(&txn))
     }
 }
@@ -1101,6 +1280,8 @@ pub(crate) enum FunctionArgType {
     U128,
     Raw,
     Vector(Box<FunctionArgType>),
+    /// A BCS encoded value of the given type
+    Bcs(TypeTag),
 }
 
 impl Display for FunctionArgType {
@@ -1116,6 +1297,7 @@ impl Display for FunctionArgType {
             FunctionArgType::U128 => write!(f, "u128"),
             FunctionArgType::Raw => write!(f, "raw"),
             FunctionArgType::Vector(inner) => write!(f, "vector<{}>", inner),
+            FunctionArgType::Bcs(type_tag) => write!(f, "bcs:{}", type_tag),
         }
     }
 }
@@ -1164,6 +1346,10 @@ impl FunctionArgType {
                 Ok(raw)
             }
             FunctionArgType::Vector(inner) => {
+                // Nested vectors and vectors of strings can only be JSON-encoded
+                if arg.trim_start().starts_with('[') {
+                    return parse_json_arg(self, arg);
+                }
                 let parsed = match inner.deref() {
                     FunctionArgType::Address => parse_vector_arg(arg, |arg| {
                         load_account_arg(arg).map_err(|err| {
@@ -1190,17 +1376,85 @@ impl FunctionArgType {
                         u64::from_str(arg)
                             .map_err(|err| CliError::UnableToParse("vector<128>", err.to_string()))
                     }),
-                    vector_type => {
-                        panic!("Unsupported vector type vector<{}>", vector_type)
-                    }
+                    vector_type => Err(CliError::CommandArgumentError(format!(
+                        "vector<{}> must be JSON-encoded e.g. `[...]`",
+                        vector_type
+                    ))),
                 }?;
                 Ok(parsed)
             }
+            FunctionArgType::Bcs(_) => {
+                let blob = hex::decode(arg)
+                    .map_err(|err| CliError::UnableToParse("bcs", err.to_string()))?;
+                Ok(blob)
+            }
         }
         .map_err(|err| CliError::BCS("arg", err))
     }
 }
 
+fn parse_json_arg(ty: &FunctionArgType, arg: &str) -> CliTypedResult<Vec<u8>> {
+    let value = serde_json::from_str(arg)
+        .map_err(|err| CliError::UnableToParse("json", err.to_string()))?;
+    json_to_move_value(ty, &value)?
+        .simple_serialize()
+        .ok_or_else(|| CliError::UnexpectedError(format!("Failed to serialize {} arg", ty)))
+}
+
+/// Converts a JSON-encoded arg of the given type, e.g. `[["0x1"], []]` for a
+/// `vector<vector<address>>`, into the corresponding Move value
+fn json_to_move_value(
+    ty: &FunctionArgType,
+    value: &serde_json::Value,
+) -> CliTypedResult<MoveValue> {
+    let invalid = || CliError::UnableToParse("json", format!("Expected {} but got {}", ty, value));
+    match ty {
+        FunctionArgType::Address => Ok(MoveValue::Address(load_account_arg(
+            value.as_str().ok_or_else(invalid)?,
+        )?)),
+        FunctionArgType::Bool => value.as_bool().map(MoveValue::Bool).ok_or_else(invalid),
+        FunctionArgType::Hex => Ok(MoveValue::vector_u8(
+            hex::decode(value.as_str().ok_or_else(invalid)?)
+                .map_err(|err| CliError::UnableToParse("hex", err.to_string()))?,
+        )),
+        // Strings are serialized as their UTF-8 bytes
+        FunctionArgType::String => Ok(MoveValue::vector_u8(
+            value.as_str().ok_or_else(invalid)?.as_bytes().to_vec(),
+        )),
+        FunctionArgType::U8 => json_to_integer(value)
+            .and_then(|n| u8::try_from(n).ok())
+            .map(MoveValue::U8)
+            .ok_or_else(invalid),
+        FunctionArgType::U64 => json_to_integer(value)
+            .and_then(|n| u64::try_from(n).ok())
+            .map(MoveValue::U64)
+            .ok_or_else(invalid),
+        FunctionArgType::U128 => json_to_integer(value)
+            .map(MoveValue::U128)
+            .ok_or_else(invalid),
+        FunctionArgType::Vector(inner) => Ok(MoveValue::Vector(
+            value
+                .as_array()
+                .ok_or_else(invalid)?
+                .iter()
+                .map(|value| json_to_move_value(inner, value))
+                .collect::<CliTypedResult<_>>()?,
+        )),
+        FunctionArgType::HexArray | FunctionArgType::Raw | FunctionArgType::Bcs(_) => Err(
+            CliError::CommandArgumentError(format!("{} can't be JSON-encoded", ty)),
+        ),
+    }
+}
+
+fn json_to_integer(value: &serde_json::Value) -> Option<u128> {
+    match value {
+        serde_json::Value::Number(number) => number.as_u64().map(u128::from),
+        // u128s don't fit in JSON numbers, so they can be passed as strings
+        serde_json::Value::String(number) => u128::from_str(number).ok(),
+        _ => None,
+    }
+}
+
 fn parse_vector_arg<T: Serialize, F: Fn(&str) -> CliTypedResult<T>>(
     args: &str,
     parse: F,
@@ -1232,19 +1486,12 @@ impl FromStr for FunctionArgType {
                 if str.starts_with("vector<") && str.ends_with('>') {
                     let arg = FunctionArgType::from_str(&str[7..str.len() - 1])?;
 
-                    // String gets confusing on parsing by commas
-                    if arg == FunctionArgType::String {
-                        return Err(CliError::CommandArgumentError(
-                            "vector<string> is not supported".to_string(),
-                        ));
-                    } else if arg == FunctionArgType::Raw {
+                    // Strings and nested vectors are JSON-encoded, as they'd be confusing to
+                    // parse by commas
+                    if arg == FunctionArgType::Raw {
                         return Err(CliError::CommandArgumentError(
                             "vector<raw> is not supported".to_string(),
                         ));
-                    } else if matches!(arg, FunctionArgType::Vector(_)) {
-                        return Err(CliError::CommandArgumentError(
-                            "nested vector<vector<_>> is not supported".to_string(),
-                        ));
                     } else if arg == FunctionArgType::HexArray {
                         return Err(CliError::CommandArgumentError(
                             "nested vector<hex_array> is not supported".to_string(),
@@ -1253,7 +1500,7 @@ impl FromStr for FunctionArgType {
 
                     Ok(FunctionArgType::Vector(Box::new(arg)))
                 } else {
-                    Err(CliError::CommandArgumentError(format!("Invalid arg type '{}'.  Must be one of: ['address','bool','hex','hex_array','string','u8','u64','u128','raw', 'vector<inner_type>']", str)))
+                    Err(CliError::CommandArgumentError(format!("Invalid arg type '{}'.  Must be one of: ['address','bool','hex','hex_array','string','u8','u64','u128','raw', 'vector<inner_type>', 'bcs:<type_tag>']", str)))
                 }
             }
         }
@@ -1308,8 +1555,27 @@ impl FromStr for ArgWithType {
             ));
         }
 
-        let ty = FunctionArgType::from_str(parts.first().unwrap())?;
-        let arg = parts.last().unwrap();
+        let (ty, arg) = if parts.first().unwrap().eq_ignore_ascii_case("bcs") {
+            // The type tag may itself contain colons, but the hex blob doesn't
+            let (type_tag, arg) = parts.last().unwrap().rsplit_once(':').ok_or_else(|| {
+                CliError::CommandArgumentError(
+                    "BCS arguments must be of the form bcs:<type_tag>:<hex> e.g. bcs:u8:01"
+                        .to_string(),
+                )
+            })?;
+            let type_tag = MoveType::from_str(type_tag)
+                .map_err(|err| CliError::UnableToParse("bcs", err.to_string()))
+                .and_then(|type_tag| {
+                    TypeTag::try_from(type_tag)
+                        .map_err(|err| CliError::UnableToParse("bcs", err.to_string()))
+                })?;
+            (FunctionArgType::Bcs(type_tag), arg)
+        } else {
+            (
+                FunctionArgType::from_str(parts.first().unwrap())?,
+                *parts.last().unwrap(),
+            )
+        };
         let arg = ty.parse_arg(arg)?;
 
         Ok(ArgWithType { _ty: ty, arg })
@@ -1345,8 +1611,27 @@ impl TryInto<TransactionArgument> for ArgWithType {
             FunctionArgType::Raw => Ok(TransactionArgument::U8Vector(txn_arg_parser(
                 &self.arg, "raw",
             )?)),
+            FunctionArgType::Vector(inner) if *inner == FunctionArgType::U8 => Ok(
+                TransactionArgument::U8Vector(txn_arg_parser(&self.arg, "vector<u8>")?),
+            ),
+            FunctionArgType::Bcs(type_tag) => match type_tag {
+                TypeTag::Address => Ok(TransactionArgument::Address(txn_arg_parser(
+                    &self.arg, "bcs",
+                )?)),
+                TypeTag::Bool => Ok(TransactionArgument::Bool(txn_arg_parser(&self.arg, "bcs")?)),
+                TypeTag::U8 => Ok(TransactionArgument::U8(txn_arg_parser(&self.arg, "bcs")?)),
+                TypeTag::U64 => Ok(TransactionArgument::U64(txn_arg_parser(&self.arg, "bcs")?)),
+                TypeTag::U128 => Ok(TransactionArgument::U128(txn_arg_parser(&self.arg, "bcs")?)),
+                TypeTag::Vector(inner) if *inner == TypeTag::U8 => Ok(
+                    TransactionArgument::U8Vector(txn_arg_parser(&self.arg, "bcs")?),
+                ),
+                type_tag => Err(CliError::CommandArgumentError(format!(
+                    "Scripts don't take {} arguments, use an entry function instead",
+                    type_tag
+                ))),
+            },
             arg_type => Err(CliError::CommandArgumentError(format!(
-                "Input type {} not supported",
+                "Scripts don't take {} arguments, use an entry function instead",
                 arg_type
             ))),
         }
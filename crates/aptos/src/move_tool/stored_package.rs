@@ -1,15 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::bail;
+use anyhow::{anyhow, bail};
 use aptos_framework::natives::code::{
     ModuleMetadata, PackageMetadata, PackageRegistry, UpgradePolicy,
 };
 use aptos_framework::unzip_metadata_str;
 use aptos_rest_client::Client;
 use aptos_types::account_address::AccountAddress;
+use move_binary_format::CompiledModule;
 use move_package::compilation::package_layout::CompiledPackageLayout;
+use move_package::resolution::digest::compute_digest;
+use move_package::source_package::layout::SourcePackageLayout;
 use reqwest::Url;
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 
@@ -19,6 +23,8 @@ use std::path::Path;
 /// Represents the package registry at a given account.
 pub struct CachedPackageRegistry {
     inner: PackageRegistry,
+    client: Client,
+    address: AccountAddress,
 }
 
 /// Represents the package metadata found in an registry.
@@ -40,7 +46,11 @@ impl CachedPackageRegistry {
             .get_account_resource_bcs::<PackageRegistry>(addr, "0x1::code::PackageRegistry")
             .await?
             .into_inner();
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            client,
+            address: addr,
+        })
     }
 
     /// Returns the list of packages in this registry by name.
@@ -81,6 +91,63 @@ impl CachedPackageRegistry {
         }
         bail!("package `{}` not found", name)
     }
+
+    /// Fetches the bytecode of the given module from the account of this registry.
+    pub async fn get_bytecode(&self, module_name: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
+        Ok(self
+            .client
+            .get_account_module_bcs(self.address, module_name.as_ref())
+            .await?
+            .into_inner()
+            .to_vec())
+    }
+
+    /// Compares the bytecode of the given package with the compiled modules, ignoring the
+    /// metadata attached to the modules, and returns the names of the modules which differ or
+    /// are only found on one side.
+    pub async fn diff_bytecode(
+        &self,
+        package_name: impl AsRef<str>,
+        modules: &[CompiledModule],
+    ) -> anyhow::Result<Vec<String>> {
+        let package = self.get_package(package_name).await?;
+        let published: BTreeSet<&str> = package.module_names().into_iter().collect();
+        let mut differing = BTreeSet::new();
+        for module in modules {
+            let name = module.self_id().name().to_string();
+            if !published.contains(name.as_str()) {
+                differing.insert(name);
+                continue;
+            }
+            let published_module = CompiledModule::deserialize(&self.get_bytecode(&name).await?)
+                .map_err(|err| anyhow!("Failed to deserialize module {}: {:?}", name, err))?;
+            if bytecode_without_metadata(published_module)?
+                != bytecode_without_metadata(module.clone())?
+            {
+                differing.insert(name);
+            }
+        }
+
+        let compiled: BTreeSet<String> = modules
+            .iter()
+            .map(|module| module.self_id().name().to_string())
+            .collect();
+        differing.extend(
+            published
+                .into_iter()
+                .filter(|name| !compiled.contains(*name))
+                .map(str::to_string),
+        );
+        Ok(differing.into_iter().collect())
+    }
+}
+
+/// Serializes the module without the metadata attached to it, e.g. its error map
+fn bytecode_without_metadata(mut module: CompiledModule) -> anyhow::Result<Vec<u8>> {
+    module.metadata.clear();
+    let mut bytecode = vec![];
+    module.serialize(&mut bytecode)?;
+    Ok(bytecode)
 }
 
 impl<'a> CachedPackageMetadata<'a> {
@@ -122,6 +189,14 @@ impl<'a> CachedPackageMetadata<'a> {
         bail!("module `{}` not found", name)
     }
 
+    /// Returns true if the source of every module was published with the package.
+    pub fn has_source(&self) -> bool {
+        self.metadata
+            .modules
+            .iter()
+            .all(|module| !module.source.is_empty())
+    }
+
     pub fn save_package_to_disk(&self, path: &Path) -> anyhow::Result<()> {
         fs::create_dir_all(path)?;
         fs::write(
@@ -133,7 +208,7 @@ impl<'a> CachedPackageMetadata<'a> {
         for module in &self.metadata.modules {
             let source = match module.source.is_empty() {
                 true => {
-                    println!("module without code: {}", module.name);
+                    eprintln!("module without code: {}", module.name);
                     "".into()
                 }
                 false => unzip_metadata_str(&module.source)?,
@@ -143,6 +218,41 @@ impl<'a> CachedPackageMetadata<'a> {
         Ok(())
     }
 
+    /// Checks that the published manifest and sources hash to the published source digest, i.e.
+    /// that they're the source the package was built from.
+    ///
+    /// At publish time, the digest is computed over the files of the package, while the source
+    /// of every module is published on its own. A file holding several modules is published
+    /// once per module, so the sources are deduplicated to get the files back. Files without
+    /// modules, e.g. spec files or scripts, aren't published at all, so the digest of packages
+    /// having some can't be verified.
+    pub fn verify_source_digest(&self) -> anyhow::Result<()> {
+        // Lay out the files the way they were at publish time, and hash them the same way
+        let package_dir = tempfile::tempdir()?;
+        let manifest_path = package_dir
+            .path()
+            .join(SourcePackageLayout::Manifest.path());
+        fs::write(&manifest_path, unzip_metadata_str(&self.metadata.manifest)?)?;
+        let sources_dir = package_dir.path().join(SourcePackageLayout::Sources.path());
+        fs::create_dir_all(&sources_dir)?;
+        let mut sources = BTreeSet::new();
+        for module in &self.metadata.modules {
+            let source = unzip_metadata_str(&module.source)?;
+            if sources.insert(source.clone()) {
+                fs::write(sources_dir.join(format!("{}.move", module.name)), source)?;
+            }
+        }
+        let digest = compute_digest(&[manifest_path, sources_dir])?.to_string();
+        if digest != self.source_digest() {
+            bail!(
+                "Source digest of the downloaded package doesn't match the published one {} : {}",
+                digest,
+                self.source_digest()
+            )
+        }
+        Ok(())
+    }
+
     pub fn verify(&self, package_metadata: &PackageMetadata) -> anyhow::Result<()> {
         let self_metadata = self.metadata;
 
@@ -213,3 +323,72 @@ impl<'a> CachedModuleMetadata<'a> {
         &self.metadata.source_map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_framework::{natives::code::MoveOption, zip_metadata_str};
+
+    const MANIFEST: &str = "[package]\nname = \"hello\"\nversion = \"0.0.0\"\n";
+    const PAIR_SOURCE: &str = "module 0xcafe::first {}\nmodule 0xcafe::second {}\n";
+    const THIRD_SOURCE: &str = "module 0xcafe::third {}\n";
+
+    /// The source digest of the package, as computed when it's built for publishing
+    fn published_source_digest() -> String {
+        let package_dir = tempfile::tempdir().unwrap();
+        let sources_dir = package_dir.path().join(SourcePackageLayout::Sources.path());
+        fs::create_dir_all(&sources_dir).unwrap();
+        let manifest_path = package_dir
+            .path()
+            .join(SourcePackageLayout::Manifest.path());
+        fs::write(&manifest_path, MANIFEST).unwrap();
+        fs::write(sources_dir.join("pair.move"), PAIR_SOURCE).unwrap();
+        fs::write(sources_dir.join("third.move"), THIRD_SOURCE).unwrap();
+        compute_digest(&[manifest_path, sources_dir])
+            .unwrap()
+            .to_string()
+    }
+
+    fn package_metadata(third_source: &str) -> PackageMetadata {
+        let module = |name: &str, source: &str| ModuleMetadata {
+            name: name.to_string(),
+            source: zip_metadata_str(source).unwrap(),
+            source_map: vec![],
+            extension: MoveOption::none(),
+        };
+        PackageMetadata {
+            name: "hello".to_string(),
+            upgrade_policy: UpgradePolicy::compat(),
+            upgrade_number: 0,
+            source_digest: published_source_digest(),
+            manifest: zip_metadata_str(MANIFEST).unwrap(),
+            modules: vec![
+                module("first", PAIR_SOURCE),
+                module("second", PAIR_SOURCE),
+                module("third", third_source),
+            ],
+            deps: vec![],
+            extension: MoveOption::none(),
+        }
+    }
+
+    #[test]
+    fn test_verify_source_digest() {
+        let metadata = package_metadata(THIRD_SOURCE);
+        CachedPackageMetadata {
+            metadata: &metadata,
+        }
+        .verify_source_digest()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_verify_source_digest_mismatch() {
+        let metadata = package_metadata("module 0xcafe::third { fun f() {} }\n");
+        assert!(CachedPackageMetadata {
+            metadata: &metadata,
+        }
+        .verify_source_digest()
+        .is_err());
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliError, CliTypedResult, EncodingOptions, GasOptions, OutputFormat, PrivateKeyInputOptions,
+    ProfileOptions, PromptOptions, RestOptions, TransactionOptions, TransactionSummary,
+};
+use crate::common::utils::{
+    chain_id, check_if_file_exists, get_sequence_number, prompt_yes_with_override, read_from_file,
+    write_to_file,
+};
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use crate::{CliCommand, CliResult};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    Signature, ValidCryptoMaterialStringExt,
+};
+use aptos_rest_client::aptos_api_types::{HexEncodedBytes, MoveType};
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
+    },
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path, path::PathBuf};
+
+/// Tool for k-of-n multisig accounts
+///
+/// A multisig account is authenticated by the public keys of its owners, a threshold of which
+/// have to approve each transaction.  Proposals and approvals are exchanged as files, so that
+/// owners can approve transactions offline, on different machines.
+#[derive(Parser)]
+pub enum MultisigTool {
+    Create(CreateMultisig),
+    Propose(ProposeMultisig),
+    Approve(ApproveMultisig),
+    Execute(ExecuteMultisig),
+}
+
+impl MultisigTool {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
+        match self {
+            MultisigTool::Create(tool) => tool.execute_serialized(output_format).await,
+            MultisigTool::Propose(tool) => tool.execute_serialized(output_format).await,
+            MultisigTool::Approve(tool) => tool.execute_serialized(output_format).await,
+            MultisigTool::Execute(tool) => tool.execute_serialized(output_format).await,
+        }
+    }
+}
+
+/// A multisig account, as shared with its owners
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultisigAccount {
+    pub address: AccountAddress,
+    pub threshold: u8,
+    pub public_keys: Vec<Ed25519PublicKey>,
+}
+
+impl MultisigAccount {
+    pub fn new(public_keys: Vec<Ed25519PublicKey>, threshold: u8) -> CliTypedResult<Self> {
+        let public_key =
+            MultiEd25519PublicKey::new(public_keys.clone(), threshold).map_err(|err| {
+                CliError::CommandArgumentError(format!(
+                    "Invalid multisig of {} owners with a threshold of {}: {}",
+                    public_keys.len(),
+                    threshold,
+                    err
+                ))
+            })?;
+        Ok(MultisigAccount {
+            address: AuthenticationKey::multi_ed25519(&public_key).derived_address(),
+            threshold,
+            public_keys,
+        })
+    }
+
+    pub fn public_key(&self) -> CliTypedResult<MultiEd25519PublicKey> {
+        Ok(MultiEd25519PublicKey::new(
+            self.public_keys.clone(),
+            self.threshold,
+        )?)
+    }
+
+    /// Index of the owner in the multisig, which is the index of its signature in the bitmap
+    fn owner_index(&self, public_key: &Ed25519PublicKey) -> CliTypedResult<u8> {
+        self.public_keys
+            .iter()
+            .position(|owner| owner == public_key)
+            .map(|index| index as u8)
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "{} isn't an owner of the multisig account {}",
+                    public_key, self.address
+                ))
+            })
+    }
+}
+
+/// A transaction of a multisig account, awaiting the approval of its owners
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultisigProposal {
+    pub multisig_account: MultisigAccount,
+    /// The BCS encoded `RawTransaction` the owners sign
+    pub raw_transaction: HexEncodedBytes,
+}
+
+impl MultisigProposal {
+    pub fn raw_transaction(&self) -> CliTypedResult<RawTransaction> {
+        bcs::from_bytes(&self.raw_transaction.0)
+            .map_err(|err| CliError::UnableToParse("raw_transaction", err.to_string()))
+    }
+}
+
+/// The approval of a multisig transaction by one of the owners
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultisigApproval {
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+fn parse_public_key(str: &str) -> CliTypedResult<Ed25519PublicKey> {
+    Ed25519PublicKey::from_encoded_string(str)
+        .map_err(|err| CliError::UnableToParse("--public-keys", err.to_string()))
+}
+
+fn read_json_file<T: DeserializeOwned>(path: &Path, name: &'static str) -> CliTypedResult<T> {
+    serde_json::from_slice(&read_from_file(path)?)
+        .map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+fn write_json_file<T: Serialize>(path: &Path, name: &str, value: &T) -> CliTypedResult<()> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+    write_to_file(path, name, json.as_bytes())
+}
+
+pub(crate) fn describe_payload(payload: &TransactionPayload) -> String {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => format!(
+            "{}::{}::{}",
+            entry_function.module().address(),
+            entry_function.module().name(),
+            entry_function.function()
+        ),
+        TransactionPayload::Script(_) => "a script".to_string(),
+        TransactionPayload::ModuleBundle(_) => "a module bundle".to_string(),
+    }
+}
+
+/// Create a k-of-n multisig account
+///
+/// The account is created on chain by the sender, and its description is written to
+/// `--output-file` to be shared with the owners.
+#[derive(Parser)]
+pub struct CreateMultisig {
+    /// Hex encoded Ed25519 public keys of the owners, separated by spaces
+    #[clap(long, multiple_values = true, parse(try_from_str = parse_public_key))]
+    pub(crate) public_keys: Vec<Ed25519PublicKey>,
+    /// Number of owners that have to approve each transaction
+    #[clap(long)]
+    pub(crate) threshold: u8,
+    /// Amount of Octas to transfer to the multisig account, to pay for its transactions
+    #[clap(long, default_value_t = 0)]
+    pub(crate) initial_balance: u64,
+    /// File to write the multisig account to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) txn_options: TransactionOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateMultisigSummary {
+    pub multisig_address: AccountAddress,
+    #[serde(flatten)]
+    pub transaction: TransactionSummary,
+}
+
+#[async_trait]
+impl CliCommand<CreateMultisigSummary> for CreateMultisig {
+    fn command_name(&self) -> &'static str {
+        "CreateMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<CreateMultisigSummary> {
+        let multisig_account = MultisigAccount::new(self.public_keys, self.threshold)?;
+        check_if_file_exists(&self.output_file, self.txn_options.prompt_options)?;
+
+        let payload = if self.initial_balance > 0 {
+            aptos_stdlib::aptos_account_transfer(multisig_account.address, self.initial_balance)
+        } else {
+            aptos_stdlib::aptos_account_create_account(multisig_account.address)
+        };
+        let transaction = self.txn_options.submit_transaction(payload).await?;
+        write_json_file(&self.output_file, "Multisig account", &multisig_account)?;
+
+        Ok(CreateMultisigSummary {
+            multisig_address: multisig_account.address,
+            transaction: TransactionSummary::from(&transaction),
+        })
+    }
+}
+
+/// Propose a transaction of a multisig account, calling an entry function
+///
+/// The proposal is written to `--output-file`, to be approved by the owners with
+/// `aptos multisig approve`.  It expires after `--expiration-secs`, and is invalidated by any
+/// other transaction of the multisig account executed before it.
+#[derive(Parser)]
+pub struct ProposeMultisig {
+    /// Multisig account file written by `aptos multisig create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_file: PathBuf,
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+    /// Arguments combined with their type separated by spaces, same as for `aptos move run`
+    ///
+    /// Example: `address:0x1 bool:true u8:0`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<ArgWithType>,
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `u8 u64 u128 bool address vector signer`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+    /// Number of seconds the owners have to approve and execute the transaction
+    #[clap(long, default_value_t = 3600)]
+    pub(crate) expiration_secs: u64,
+    /// File to write the proposal to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) gas_options: GasOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<MultisigProposal> for ProposeMultisig {
+    fn command_name(&self) -> &'static str {
+        "ProposeMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigProposal> {
+        let multisig_account: MultisigAccount =
+            read_json_file(&self.multisig_file, "--multisig-file")?;
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+        let payload = entry_function_payload(self.function_id, self.args, self.type_args)?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let sequence_number = get_sequence_number(&client, multisig_account.address).await?;
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let mut transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_transaction_expiration_time(self.expiration_secs);
+        if let Some(max_gas) = self.gas_options.max_gas {
+            transaction_factory = transaction_factory.with_max_gas_amount(max_gas);
+        }
+        let raw_transaction = transaction_factory
+            .payload(payload)
+            .sender(multisig_account.address)
+            .sequence_number(sequence_number)
+            .build();
+
+        let proposal = MultisigProposal {
+            multisig_account,
+            raw_transaction: HexEncodedBytes::from(bcs::to_bytes(&raw_transaction)?),
+        };
+        write_json_file(&self.output_file, "Multisig proposal", &proposal)?;
+        Ok(proposal)
+    }
+}
+
+/// Approve a transaction proposed for a multisig account
+///
+/// The transaction is signed with the owner's key, without connecting to the network, and the
+/// approval is written to `--output-file`, to be handed to whoever executes the transaction.
+#[derive(Parser)]
+pub struct ApproveMultisig {
+    /// Proposal file written by `aptos multisig propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) proposal_file: PathBuf,
+    /// File to write the approval to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<MultisigApproval> for ApproveMultisig {
+    fn command_name(&self) -> &'static str {
+        "ApproveMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigApproval> {
+        let proposal: MultisigProposal = read_json_file(&self.proposal_file, "--proposal-file")?;
+        let raw_transaction = proposal.raw_transaction()?;
+        let signer = self
+            .private_key_options
+            .extract_signer(self.encoding_options.encoding, &self.profile_options)?;
+        let public_key = signer.public_key();
+        proposal.multisig_account.owner_index(&public_key)?;
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to approve calling {} from the multisig account {} with sequence number {}?",
+                describe_payload(raw_transaction.payload()),
+                proposal.multisig_account.address,
+                raw_transaction.sequence_number()
+            ),
+            self.prompt_options,
+        )?;
+        let approval = MultisigApproval {
+            public_key,
+            signature: signer.sign_raw_transaction(&raw_transaction)?,
+        };
+        write_json_file(&self.output_file, "Multisig approval", &approval)?;
+        Ok(approval)
+    }
+}
+
+/// Execute a transaction of a multisig account approved by enough owners
+#[derive(Parser)]
+pub struct ExecuteMultisig {
+    /// Proposal file written by `aptos multisig propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) proposal_file: PathBuf,
+    /// Approval files written by `aptos multisig approve`, separated by spaces
+    #[clap(long, multiple_values = true, parse(from_os_str))]
+    pub(crate) approval_files: Vec<PathBuf>,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for ExecuteMultisig {
+    fn command_name(&self) -> &'static str {
+        "ExecuteMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let proposal: MultisigProposal = read_json_file(&self.proposal_file, "--proposal-file")?;
+        let multisig_account = &proposal.multisig_account;
+        let raw_transaction = proposal.raw_transaction()?;
+
+        // Approvals are deduplicated by owner, as each owner has a single bit in the bitmap
+        let mut signatures = BTreeMap::new();
+        for approval_file in &self.approval_files {
+            let approval: MultisigApproval = read_json_file(approval_file, "--approval-files")?;
+            let index = multisig_account.owner_index(&approval.public_key)?;
+            approval
+                .signature
+                .verify(&raw_transaction, &approval.public_key)
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "The approval {} isn't a signature of the proposed transaction: {}",
+                        approval_file.display(),
+                        err
+                    ))
+                })?;
+            signatures.insert(index, approval.signature);
+        }
+        if signatures.len() < multisig_account.threshold as usize {
+            return Err(CliError::CommandArgumentError(format!(
+                "The transaction is approved by {} owners, but the multisig account {} requires {}",
+                signatures.len(),
+                multisig_account.address,
+                multisig_account.threshold
+            )));
+        }
+
+        let signature = MultiEd25519Signature::new(
+            signatures
+                .into_iter()
+                .map(|(index, signature)| (signature, index))
+                .collect(),
+        )?;
+        let signed_transaction = SignedTransaction::new_multisig(
+            raw_transaction,
+            multisig_account.public_key()?,
+            signature,
+        );
+        let client = self.rest_options.client(&self.profile_options)?;
+        let transaction = client
+            .submit_and_wait(&signed_transaction)
+            .await?
+            .into_inner();
+        Ok(TransactionSummary::from(&transaction))
+    }
+}
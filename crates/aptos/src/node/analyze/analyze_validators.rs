@@ -10,6 +10,7 @@ use aptos_storage_interface::{DbReader, Order};
 use aptos_types::account_address::AccountAddress;
 use aptos_types::account_config::{new_block_event_key, NewBlockEvent};
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::convert::TryFrom;
 use std::ops::Add;
@@ -17,7 +18,7 @@ use std::ops::Add;
 use super::fetch_metadata::ValidatorInfo;
 
 /// Single validator stats
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ValidatorStats {
     /// Number of successful proposals
     pub proposal_successes: u32,
@@ -104,7 +105,7 @@ impl Add for ValidatorStats {
 }
 
 /// Statistics for all validators
-#[derive(Clone)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct EpochStats {
     /// Statistics for each of the validators
     pub validator_stats: HashMap<AccountAddress, ValidatorStats>,
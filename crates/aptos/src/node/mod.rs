@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod analyze;
+pub mod monitor;
 
 use crate::common::types::{
-    ConfigSearchMode, OptionalPoolAddressArgs, PoolAddressArgs, PromptOptions, TransactionSummary,
+    ConfigSearchMode, OptionalPoolAddressArgs, OutputFormat, PoolAddressArgs, PromptOptions,
+    TransactionSummary,
 };
-use crate::common::utils::prompt_yes_with_override;
+use crate::common::utils::{print_text, prompt_yes_with_override, start_logger, to_common_result};
 use crate::config::GlobalConfig;
-use crate::node::analyze::analyze_validators::{AnalyzeValidators, ValidatorStats};
+use crate::node::analyze::analyze_validators::{AnalyzeValidators, EpochStats, ValidatorStats};
 use crate::node::analyze::fetch_metadata::FetchMetadata;
+use crate::node::monitor::MonitorNode;
 use crate::{
     common::{
         types::{
@@ -31,8 +34,12 @@ use aptos_config::config::NodeConfig;
 use aptos_crypto::bls12381::PublicKey;
 use aptos_crypto::{bls12381, x25519, ValidCryptoMaterialStringExt};
 use aptos_faucet::FaucetArgs;
-use aptos_genesis::config::{HostAndPort, OperatorConfiguration};
-use aptos_rest_client::aptos_api_types::VersionedEvent;
+use aptos_gas::GasScheduleOverrides;
+use aptos_genesis::{
+    config::{HostAndPort, OperatorConfiguration},
+    presets::GenesisPreset,
+};
+use aptos_rest_client::aptos_api_types::{StateSyncStatus, VersionedEvent};
 use aptos_rest_client::{Client, State};
 use aptos_types::account_config::BlockResource;
 use aptos_types::chain_id::ChainId;
@@ -53,7 +60,7 @@ use rand::rngs::StdRng;
 use rand::SeedableRng;
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::{TryFrom, TryInto};
 use std::pin::Pin;
 use std::sync::Arc;
@@ -73,10 +80,12 @@ pub enum NodeTool {
     InitializeValidator(InitializeValidator),
     JoinValidatorSet(JoinValidatorSet),
     LeaveValidatorSet(LeaveValidatorSet),
+    Monitor(MonitorNode),
     ShowEpochInfo(ShowEpochInfo),
     ShowValidatorConfig(ShowValidatorConfig),
     ShowValidatorSet(ShowValidatorSet),
     ShowValidatorStake(ShowValidatorStake),
+    SyncStatus(SyncStatus),
     RunLocalTestnet(RunLocalTestnet),
     UpdateConsensusKey(UpdateConsensusKey),
     UpdateValidatorNetworkAddresses(UpdateValidatorNetworkAddresses),
@@ -85,23 +94,31 @@ pub enum NodeTool {
 }
 
 impl NodeTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         use NodeTool::*;
         match self {
-            GetPerformance(tool) => tool.execute_serialized().await,
-            GetStakePool(tool) => tool.execute_serialized().await,
-            InitializeValidator(tool) => tool.execute_serialized().await,
-            JoinValidatorSet(tool) => tool.execute_serialized().await,
-            LeaveValidatorSet(tool) => tool.execute_serialized().await,
-            ShowEpochInfo(tool) => tool.execute_serialized().await,
-            ShowValidatorSet(tool) => tool.execute_serialized().await,
-            ShowValidatorStake(tool) => tool.execute_serialized().await,
-            ShowValidatorConfig(tool) => tool.execute_serialized().await,
-            RunLocalTestnet(tool) => tool.execute_serialized_without_logger().await,
-            UpdateConsensusKey(tool) => tool.execute_serialized().await,
-            UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized().await,
-            AnalyzeValidatorPerformance(tool) => tool.execute_serialized().await,
-            BootstrapDbFromBackup(tool) => tool.execute_serialized().await,
+            GetPerformance(tool) => tool.execute_serialized(output_format).await,
+            GetStakePool(tool) => tool.execute_serialized(output_format).await,
+            InitializeValidator(tool) => tool.execute_serialized(output_format).await,
+            JoinValidatorSet(tool) => {
+                if tool.auto_join_args.auto {
+                    tool.execute_auto_serialized(output_format).await
+                } else {
+                    tool.execute_serialized(output_format).await
+                }
+            }
+            LeaveValidatorSet(tool) => tool.execute_serialized(output_format).await,
+            Monitor(tool) => tool.execute_serialized_without_logger(output_format).await,
+            ShowEpochInfo(tool) => tool.execute_serialized(output_format).await,
+            ShowValidatorSet(tool) => tool.execute_serialized(output_format).await,
+            ShowValidatorStake(tool) => tool.execute_serialized(output_format).await,
+            ShowValidatorConfig(tool) => tool.execute_serialized(output_format).await,
+            SyncStatus(tool) => tool.execute_serialized(output_format).await,
+            RunLocalTestnet(tool) => tool.execute_serialized_without_logger(output_format).await,
+            UpdateConsensusKey(tool) => tool.execute_serialized(output_format).await,
+            UpdateValidatorNetworkAddresses(tool) => tool.execute_serialized(output_format).await,
+            AnalyzeValidatorPerformance(tool) => tool.execute_serialized(output_format).await,
+            BootstrapDbFromBackup(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
@@ -250,6 +267,33 @@ impl ValidatorNetworkAddressesArgs {
             full_node_host,
         ))
     }
+
+    /// Returns the validator and fullnode network addresses to publish on-chain
+    fn get_network_addresses(
+        &self,
+        operator_config: &Option<OperatorConfiguration>,
+    ) -> CliTypedResult<(Vec<NetworkAddress>, Vec<NetworkAddress>)> {
+        let (
+            validator_network_public_key,
+            full_node_network_public_key,
+            validator_host,
+            full_node_host,
+        ) = self.get_network_configs(operator_config)?;
+        let validator_network_addresses =
+            vec![validator_host.as_network_address(validator_network_public_key)?];
+        let full_node_network_addresses =
+            match (full_node_host.as_ref(), full_node_network_public_key) {
+                (Some(host), Some(public_key)) => vec![host.as_network_address(public_key)?],
+                (None, None) => vec![],
+                _ => {
+                    return Err(CliError::CommandArgumentError(
+                        "If specifying fullnode addresses, both host and public key are required."
+                            .to_string(),
+                    ))
+                }
+            };
+        Ok((validator_network_addresses, full_node_network_addresses))
+    }
 }
 
 #[derive(Copy, Clone, Debug, Serialize)]
@@ -605,27 +649,9 @@ impl CliCommand<TransactionSummary> for InitializeValidator {
         let consensus_proof_of_possession = self
             .validator_consensus_key_args
             .get_consensus_proof_of_possession(&operator_config)?;
-        let (
-            validator_network_public_key,
-            full_node_network_public_key,
-            validator_host,
-            full_node_host,
-        ) = self
+        let (validator_network_addresses, full_node_network_addresses) = self
             .validator_network_addresses_args
-            .get_network_configs(&operator_config)?;
-        let validator_network_addresses =
-            vec![validator_host.as_network_address(validator_network_public_key)?];
-        let full_node_network_addresses =
-            match (full_node_host.as_ref(), full_node_network_public_key) {
-                (Some(host), Some(public_key)) => vec![host.as_network_address(public_key)?],
-                (None, None) => vec![],
-                _ => {
-                    return Err(CliError::CommandArgumentError(
-                        "If specifying fullnode addresses, both host and public key are required."
-                            .to_string(),
-                    ))
-                }
-            };
+            .get_network_addresses(&operator_config)?;
 
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_initialize_validator(
@@ -675,14 +701,81 @@ impl OperatorArgs {
 ///
 /// Joining the validator set requires sufficient stake.  Once the transaction
 /// succeeds, you will join the validator set in the next epoch.
+///
+/// With `--auto`, the whole flow is run from the owner account: the stake pool is
+/// initialized with the validator configuration, the stake is added, the validator
+/// joins the validator set, and the operator and voter are handed over.  The on-chain
+/// requirements are checked before any transaction is submitted, and the command waits
+/// for the validator to become active.
 #[derive(Parser)]
 pub struct JoinValidatorSet {
     #[clap(flatten)]
     pub(crate) txn_options: TransactionOptions,
     #[clap(flatten)]
     pub(crate) operator_args: OperatorArgs,
+    #[clap(flatten)]
+    pub(crate) auto_join_args: AutoJoinArgs,
 }
 
+/// Arguments of the `--auto` join flow
+#[derive(Parser)]
+pub struct AutoJoinArgs {
+    /// Run the full join flow from the owner account, see the command description
+    #[clap(long)]
+    pub(crate) auto: bool,
+
+    /// Amount of Octas (10^-8 APT) the stake pool must hold before joining
+    ///
+    /// Only the missing stake is added if the stake pool already exists.  Defaults to the
+    /// on-chain minimum stake.
+    #[clap(long)]
+    pub(crate) stake_amount: Option<u64>,
+
+    /// Account address of the operator to hand the stake pool over to once it joined
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) operator_address: Option<AccountAddress>,
+
+    /// Account address of the delegated voter to set once the stake pool joined
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) voter_address: Option<AccountAddress>,
+
+    /// Maximum time in seconds to wait for the validator to become active
+    ///
+    /// Validators become active at the start of the next epoch.  Set to 0 to not wait.
+    #[clap(long, default_value_t = 7200)]
+    pub(crate) activation_timeout_secs: u64,
+
+    #[clap(flatten)]
+    pub(crate) operator_config_file_args: OperatorConfigFileArgs,
+    #[clap(flatten)]
+    pub(crate) validator_consensus_key_args: ValidatorConsensusKeyArgs,
+    #[clap(flatten)]
+    pub(crate) validator_network_addresses_args: ValidatorNetworkAddressesArgs,
+}
+
+/// The result of the `--auto` join flow
+#[derive(Debug, Serialize)]
+pub struct JoinValidatorSetSummary {
+    pub pool_address: AccountAddress,
+    pub state: StakePoolState,
+    pub transactions: Vec<TransactionSummary>,
+    pub epoch_info: EpochInfo,
+}
+
+/// The on-chain staking configuration, `0x1::staking_config::StakingConfig`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StakingConfig {
+    pub minimum_stake: u64,
+    pub maximum_stake: u64,
+    pub recurring_lockup_duration_secs: u64,
+    pub allow_validator_set_change: bool,
+    pub rewards_rate: u64,
+    pub rewards_rate_denominator: u64,
+    pub voting_power_increase_limit: u64,
+}
+
+const ACTIVATION_POLL_INTERVAL_SECS: u64 = 10;
+
 #[async_trait]
 impl CliCommand<TransactionSummary> for JoinValidatorSet {
     fn command_name(&self) -> &'static str {
@@ -701,6 +794,217 @@ impl CliCommand<TransactionSummary> for JoinValidatorSet {
     }
 }
 
+impl JoinValidatorSet {
+    pub async fn execute_auto_serialized(self, output_format: OutputFormat) -> CliResult {
+        start_logger();
+        let start_time = std::time::Instant::now();
+        to_common_result(
+            "JoinValidatorSetAuto",
+            start_time,
+            self.execute_auto().await,
+            output_format,
+        )
+        .await
+    }
+
+    /// Runs the full join flow from the owner account.  The transactions are submitted in
+    /// the only order that works with a single signer: the validator configuration and the
+    /// join must be submitted by the operator, which is the owner until it's handed over.
+    pub async fn execute_auto(self) -> CliTypedResult<JoinValidatorSetSummary> {
+        let client = self
+            .txn_options
+            .rest_options
+            .client(&self.txn_options.profile_options)?;
+        let owner_address = self.txn_options.sender_address()?;
+        if let Some(pool_address) = self.operator_args.pool_address_args.pool_address {
+            if pool_address != owner_address {
+                return Err(CliError::CommandArgumentError(
+                    "--auto must be run by the owner of the stake pool, don't set --pool-address"
+                        .to_string(),
+                ));
+            }
+        }
+        let args = &self.auto_join_args;
+
+        // Check the on-chain preconditions before submitting anything
+        let staking_config: StakingConfig = client
+            .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::staking_config::StakingConfig")
+            .await?
+            .into_inner();
+        if !staking_config.allow_validator_set_change {
+            return Err(CliError::UnexpectedError(
+                "Validators are not allowed to join the validator set on this network".to_string(),
+            ));
+        }
+        let stake_amount = args.stake_amount.unwrap_or(staking_config.minimum_stake);
+        if stake_amount < staking_config.minimum_stake
+            || stake_amount > staking_config.maximum_stake
+        {
+            return Err(CliError::CommandArgumentError(format!(
+                "Stake amount {} must be between the minimum stake {} and the maximum stake {}",
+                stake_amount, staking_config.minimum_stake, staking_config.maximum_stake
+            )));
+        }
+
+        let validator_set: ValidatorSet = client
+            .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::stake::ValidatorSet")
+            .await?
+            .into_inner();
+        let state = get_stake_pool_state(&validator_set, &owner_address);
+        if state != StakePoolState::Inactive {
+            return Err(CliError::UnexpectedError(format!(
+                "Stake pool {} is already in the validator set, state {:?}",
+                owner_address, state
+            )));
+        }
+
+        let stake_pool = client
+            .get_account_resource_bcs::<StakePool>(owner_address, "0x1::stake::StakePool")
+            .await
+            .ok()
+            .map(|response| response.into_inner());
+        if let Some(ref stake_pool) = stake_pool {
+            if stake_pool.operator_address != owner_address {
+                return Err(CliError::UnexpectedError(format!(
+                    "The operator of stake pool {} was already handed over to {}, join with the \
+                    operator account instead",
+                    owner_address, stake_pool.operator_address
+                )));
+            }
+        }
+        let current_stake = stake_pool
+            .as_ref()
+            .map(|stake_pool| stake_pool.active + stake_pool.pending_active)
+            .unwrap_or(0);
+        let stake_to_add = stake_amount.saturating_sub(current_stake);
+        let balance = client
+            .get_account_balance(owner_address)
+            .await?
+            .into_inner()
+            .get();
+        if balance < stake_to_add {
+            return Err(CliError::UnexpectedError(format!(
+                "Account {} holds {} Octas, but {} Octas must be staked",
+                owner_address, balance, stake_to_add
+            )));
+        }
+
+        let operator_config = args.operator_config_file_args.load()?;
+        let consensus_public_key = args
+            .validator_consensus_key_args
+            .get_consensus_public_key(&operator_config)?;
+        let consensus_proof_of_possession = args
+            .validator_consensus_key_args
+            .get_consensus_proof_of_possession(&operator_config)?;
+        let (validator_network_addresses, full_node_network_addresses) = args
+            .validator_network_addresses_args
+            .get_network_addresses(&operator_config)?;
+
+        let mut transactions: Vec<TransactionSummary> = vec![];
+        if stake_pool.is_none() {
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_initialize_validator(
+                        consensus_public_key.to_bytes().to_vec(),
+                        consensus_proof_of_possession.to_bytes().to_vec(),
+                        // BCS encode, so that we can hide the original type
+                        bcs::to_bytes(&validator_network_addresses)?,
+                        bcs::to_bytes(&full_node_network_addresses)?,
+                    ))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+        } else {
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_rotate_consensus_key(
+                        owner_address,
+                        consensus_public_key.to_bytes().to_vec(),
+                        consensus_proof_of_possession.to_bytes().to_vec(),
+                    ))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_update_network_and_fullnode_addresses(
+                        owner_address,
+                        bcs::to_bytes(&validator_network_addresses)?,
+                        bcs::to_bytes(&full_node_network_addresses)?,
+                    ))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+        }
+        if stake_to_add > 0 {
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_add_stake(stake_to_add))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+        }
+        transactions.push(
+            self.txn_options
+                .submit_transaction(aptos_stdlib::stake_join_validator_set(owner_address))
+                .await
+                .map(|inner| inner.into())?,
+        );
+
+        // The owner can't operate the stake pool anymore once the operator is handed over
+        if let Some(voter_address) = args.voter_address {
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_set_delegated_voter(voter_address))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+        }
+        if let Some(operator_address) = args.operator_address {
+            transactions.push(
+                self.txn_options
+                    .submit_transaction(aptos_stdlib::stake_set_operator(operator_address))
+                    .await
+                    .map(|inner| inner.into())?,
+            );
+        }
+
+        let state = wait_for_activation(
+            &client,
+            owner_address,
+            Duration::from_secs(args.activation_timeout_secs),
+        )
+        .await?;
+        Ok(JoinValidatorSetSummary {
+            pool_address: owner_address,
+            state,
+            transactions,
+            epoch_info: get_epoch_info(&client).await?,
+        })
+    }
+}
+
+/// Waits until the stake pool is in the active validator set, or until the timeout expires.
+/// Returns the last state of the stake pool.
+async fn wait_for_activation(
+    client: &Client,
+    pool_address: AccountAddress,
+    timeout: Duration,
+) -> CliTypedResult<StakePoolState> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let validator_set: ValidatorSet = client
+            .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::stake::ValidatorSet")
+            .await?
+            .into_inner();
+        let state = get_stake_pool_state(&validator_set, &pool_address);
+        if state == StakePoolState::Active || Instant::now() >= deadline {
+            return Ok(state);
+        }
+        tokio::time::sleep(Duration::from_secs(ACTIVATION_POLL_INTERVAL_SECS)).await;
+    }
+}
+
 /// Leave the validator set
 ///
 /// Leaving the validator set will require you to have unlocked and withdrawn all stake.  After this
@@ -1072,6 +1376,22 @@ pub struct RunLocalTestnet {
     #[clap(long)]
     do_not_delegate: bool,
 
+    /// Start the local testnet with the on-chain configs of a public network
+    ///
+    /// One of `mainnet-parity` or `testnet-parity`. The feature flags, gas schedule and
+    /// consensus config at genesis are read from the network when the chain is created, so this
+    /// only applies when starting a new chain, and needs access to the network.
+    #[clap(long)]
+    genesis_preset: Option<GenesisPreset>,
+
+    /// Path to a YAML file overriding gas parameters of the local testnet
+    ///
+    /// Maps the names of on-chain gas parameters (e.g. `txn.write_data.per_byte_in_val`) to
+    /// their values. This only applies when starting a new chain, use the
+    /// `/v1/dev/gas_schedule` endpoint of the node to change them afterwards.
+    #[clap(long, parse(from_os_str))]
+    gas_overrides: Option<PathBuf>,
+
     #[clap(flatten)]
     prompt_options: PromptOptions,
 }
@@ -1104,15 +1424,34 @@ impl CliCommand<()> for RunLocalTestnet {
             })?;
         }
 
+        // The configs of the network are only needed to create a new chain
+        let network_configs = match self.genesis_preset {
+            Some(genesis_preset) if !test_dir.join("0").join("node.yaml").exists() => Some(
+                genesis_preset
+                    .fetch_network_configs()
+                    .await
+                    .map_err(|err| CliError::ApiError(err.to_string()))?,
+            ),
+            _ => None,
+        };
+
         // Spawn the node in a separate thread
         let config_path = self.config_path.clone();
         let test_dir_copy = test_dir.clone();
+        let gas_overrides = self
+            .gas_overrides
+            .as_ref()
+            .map(GasScheduleOverrides::load)
+            .transpose()
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
         let node_thread_handle = thread::spawn(move || {
             let result = aptos_node::load_test_environment(
                 config_path,
                 Some(test_dir_copy),
                 false,
                 false,
+                network_configs,
+                gas_overrides,
                 aptos_cached_packages::head_release_bundle(),
                 rng,
             );
@@ -1282,27 +1621,9 @@ impl CliCommand<TransactionSummary> for UpdateValidatorNetworkAddresses {
             .address_fallback_to_txn(&self.txn_options)?;
 
         let validator_config = self.operator_config_file_args.load()?;
-        let (
-            validator_network_public_key,
-            full_node_network_public_key,
-            validator_host,
-            full_node_host,
-        ) = self
+        let (validator_network_addresses, full_node_network_addresses) = self
             .validator_network_addresses_args
-            .get_network_configs(&validator_config)?;
-        let validator_network_addresses =
-            vec![validator_host.as_network_address(validator_network_public_key)?];
-        let full_node_network_addresses =
-            match (full_node_host.as_ref(), full_node_network_public_key) {
-                (Some(host), Some(public_key)) => vec![host.as_network_address(public_key)?],
-                (None, None) => vec![],
-                _ => {
-                    return Err(CliError::CommandArgumentError(
-                        "If specifying fullnode addresses, both host and public key are required."
-                            .to_string(),
-                    ))
-                }
-            };
+            .get_network_addresses(&validator_config)?;
 
         self.txn_options
             .submit_transaction(aptos_stdlib::stake_update_network_and_fullnode_addresses(
@@ -1345,6 +1666,8 @@ pub struct AnalyzeValidatorPerformance {
     pub(crate) rest_options: RestOptions,
     #[clap(flatten)]
     pub(crate) profile_options: ProfileOptions,
+    #[clap(skip)]
+    pub(crate) output_format: OutputFormat,
 }
 
 #[derive(PartialEq, Eq, clap::ArgEnum, Clone)]
@@ -1363,13 +1686,27 @@ pub enum AnalyzeMode {
     NetworkHealthOverTime,
 }
 
+/// Performance of the validators in the analyzed epochs
+#[derive(Deserialize, Serialize)]
+pub struct ValidatorPerformanceReport {
+    /// Statistics of each full epoch
+    pub epochs: BTreeMap<u64, EpochStats>,
+    /// Statistics of all the full epochs together, without voting power
+    pub total: Option<EpochStats>,
+}
+
 #[async_trait]
-impl CliCommand<()> for AnalyzeValidatorPerformance {
+impl CliCommand<ValidatorPerformanceReport> for AnalyzeValidatorPerformance {
     fn command_name(&self) -> &'static str {
         "AnalyzeValidatorPerformance"
     }
 
-    async fn execute(mut self) -> CliTypedResult<()> {
+    fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    async fn execute(mut self) -> CliTypedResult<ValidatorPerformanceReport> {
         let client = self.rest_options.client(&self.profile_options)?;
 
         let epochs =
@@ -1377,8 +1714,11 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
                 .await?;
         let mut stats = HashMap::new();
 
-        let print_detailed = self.analyze_mode == AnalyzeMode::DetailedEpochTable
-            || self.analyze_mode == AnalyzeMode::All;
+        // The tables are only printed for humans, scripts get the statistics in the result
+        let print_tables = self.output_format == OutputFormat::Text;
+        let print_detailed = print_tables
+            && (self.analyze_mode == AnalyzeMode::DetailedEpochTable
+                || self.analyze_mode == AnalyzeMode::All);
         for epoch_info in epochs {
             let mut epoch_stats =
                 AnalyzeValidators::analyze(&epoch_info.blocks, &epoch_info.validators);
@@ -1417,8 +1757,11 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
         }
 
         if stats.is_empty() {
-            println!("No data found for given input");
-            return Ok(());
+            print_text(self.output_format, "No data found for given input");
+            return Ok(ValidatorPerformanceReport {
+                epochs: BTreeMap::new(),
+                total: None,
+            });
         }
         let total_stats = stats
             .iter()
@@ -1434,8 +1777,9 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
             AnalyzeValidators::print_detailed_epoch_table(&total_stats, None, true);
         }
         let all_validators: Vec<_> = total_stats.validator_stats.keys().cloned().collect();
-        if self.analyze_mode == AnalyzeMode::ValidatorHealthOverTime
-            || self.analyze_mode == AnalyzeMode::All
+        if print_tables
+            && (self.analyze_mode == AnalyzeMode::ValidatorHealthOverTime
+                || self.analyze_mode == AnalyzeMode::All)
         {
             println!(
                 "Validator health over epochs [{}, {}]:",
@@ -1444,8 +1788,9 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
             );
             AnalyzeValidators::print_validator_health_over_time(&stats, &all_validators, None);
         }
-        if self.analyze_mode == AnalyzeMode::NetworkHealthOverTime
-            || self.analyze_mode == AnalyzeMode::All
+        if print_tables
+            && (self.analyze_mode == AnalyzeMode::NetworkHealthOverTime
+                || self.analyze_mode == AnalyzeMode::All)
         {
             println!(
                 "Network health over epochs [{}, {}]:",
@@ -1454,7 +1799,10 @@ impl CliCommand<()> for AnalyzeValidatorPerformance {
             );
             AnalyzeValidators::print_network_health_over_time(&stats, &all_validators);
         }
-        Ok(())
+        Ok(ValidatorPerformanceReport {
+            epochs: stats.into_iter().collect(),
+            total: Some(total_stats),
+        })
     }
 }
 
@@ -1551,6 +1899,31 @@ impl CliCommand<EpochInfo> for ShowEpochInfo {
     }
 }
 
+/// Show the state sync status of a node
+///
+/// Displays the bootstrapping progress of the node: the bootstrapping mode and
+/// phase, the synced and target versions, the number of state values downloaded,
+/// the sync throughput and the estimated time until the target version is reached
+#[derive(Parser)]
+pub struct SyncStatus {
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+}
+
+#[async_trait]
+impl CliCommand<StateSyncStatus> for SyncStatus {
+    fn command_name(&self) -> &'static str {
+        "SyncStatus"
+    }
+
+    async fn execute(self) -> CliTypedResult<StateSyncStatus> {
+        let client = &self.rest_options.client(&self.profile_options)?;
+        Ok(client.get_state_sync_status().await?.into_inner())
+    }
+}
+
 async fn get_epoch_info(client: &Client) -> CliTypedResult<EpochInfo> {
     let (block_resource, state): (BlockResource, State) = client
         .get_account_resource_bcs(CORE_CODE_ADDRESS, "0x1::block::BlockResource")
@@ -0,0 +1,382 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use async_trait::async_trait;
+use clap::Parser;
+use reqwest::Url;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io::Write,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+const CONSENSUS_ROUND_METRIC: &str = "aptos_consensus_current_round";
+const CONNECTIONS_METRIC: &str = "aptos_connections";
+const MEMPOOL_SIZE_METRIC: &str = "aptos_core_mempool_index_size";
+const SYNCED_VERSION_METRIC: &str = "aptos_state_sync_version";
+
+/// Monitor the health of the chain and of a node
+///
+/// Refreshes a dashboard in the terminal with the ledger version, TPS and
+/// ledger age reported by the REST API.  If the inspection service of a node
+/// is given, also shows its consensus round, sync lag, peer count and mempool size.
+/// The dashboard is written to stderr, and the last snapshot is returned as the
+/// result of the command.
+#[derive(Parser)]
+pub struct MonitorNode {
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+
+    /// URL of the inspection service of the node to monitor, e.g. http://localhost:9101
+    #[clap(long)]
+    pub(crate) inspection_url: Option<Url>,
+
+    /// Seconds between refreshes of the dashboard
+    #[clap(long, default_value_t = 5)]
+    pub(crate) interval_secs: u64,
+
+    /// Number of refreshes before exiting, runs until interrupted if not set
+    #[clap(long)]
+    pub(crate) iterations: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<NodeHealth> for MonitorNode {
+    fn command_name(&self) -> &'static str {
+        "MonitorNode"
+    }
+
+    async fn execute(self) -> CliTypedResult<NodeHealth> {
+        if self.interval_secs == 0 {
+            return Err(CliError::CommandArgumentError(
+                "--interval-secs must be greater than 0".to_string(),
+            ));
+        }
+        let client = self.rest_options.client(&self.profile_options)?;
+        let metrics_url = self
+            .inspection_url
+            .as_ref()
+            .map(|url| url.join("metrics"))
+            .transpose()
+            .map_err(|err| CliError::UnableToParse("inspection-url", err.to_string()))?;
+        let http_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(
+                self.rest_options.connection_timeout_secs,
+            ))
+            .build()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        let mut previous: Option<(Instant, u64)> = None;
+        let mut iteration = 0;
+        loop {
+            let index = client.get_index().await?.into_inner();
+            let now = Instant::now();
+            let ledger_version = index.ledger_version.0;
+            let tps = previous.map(|(previous_time, previous_version)| {
+                ledger_version.saturating_sub(previous_version) as f64
+                    / now.duration_since(previous_time).as_secs_f64()
+            });
+            previous = Some((now, ledger_version));
+
+            let mut health = NodeHealth {
+                chain_id: index.chain_id,
+                epoch: index.epoch.0,
+                ledger_version,
+                block_height: index.block_height.0,
+                tps,
+                ledger_age_secs: ledger_age_secs(index.ledger_timestamp.0),
+                node: None,
+            };
+            if let Some(metrics_url) = &metrics_url {
+                let metrics = fetch_metrics(&http_client, metrics_url).await?;
+                health.node = Some(LocalNodeHealth::from_metrics(&metrics, ledger_version));
+            }
+
+            // The dashboard goes to stderr, so that stdout only has the JSON result of the command.
+            // Clear the terminal and move the cursor to the top left before redrawing
+            let mut stderr = std::io::stderr();
+            write!(stderr, "\x1B[2J\x1B[H{}", health.render())
+                .and_then(|_| stderr.flush())
+                .map_err(|err| CliError::IO("stderr".to_string(), err))?;
+
+            iteration += 1;
+            if self
+                .iterations
+                .map_or(false, |iterations| iteration >= iterations)
+            {
+                return Ok(health);
+            }
+            tokio::time::sleep(Duration::from_secs(self.interval_secs)).await;
+        }
+    }
+}
+
+/// A snapshot of the health of the chain and of a node
+#[derive(Debug, Serialize)]
+pub struct NodeHealth {
+    pub chain_id: u8,
+    pub epoch: u64,
+    pub ledger_version: u64,
+    pub block_height: u64,
+    /// Transactions per second since the previous refresh
+    pub tps: Option<f64>,
+    /// Seconds since the timestamp of the latest ledger info
+    pub ledger_age_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<LocalNodeHealth>,
+}
+
+/// Health of a node, as reported by its inspection service
+#[derive(Debug, Serialize)]
+pub struct LocalNodeHealth {
+    pub consensus_round: Option<u64>,
+    pub synced_version: Option<u64>,
+    /// Versions the node is behind the ledger version of the REST API
+    pub sync_lag: Option<u64>,
+    pub peer_count: Option<u64>,
+    pub mempool_size: Option<u64>,
+}
+
+impl LocalNodeHealth {
+    fn from_metrics(metrics: &[Metric], ledger_version: u64) -> Self {
+        let synced_version = metric_value(metrics, SYNCED_VERSION_METRIC, &[("type", "synced")]);
+        LocalNodeHealth {
+            consensus_round: metric_value(metrics, CONSENSUS_ROUND_METRIC, &[]),
+            synced_version,
+            sync_lag: synced_version.map(|version| ledger_version.saturating_sub(version)),
+            peer_count: metric_sum(metrics, CONNECTIONS_METRIC),
+            mempool_size: metric_value(metrics, MEMPOOL_SIZE_METRIC, &[("index", "system_ttl")]),
+        }
+    }
+}
+
+impl NodeHealth {
+    fn render(&self) -> String {
+        let mut lines = vec![
+            "Chain".to_string(),
+            format!("  Chain ID:          {}", self.chain_id),
+            format!("  Epoch:             {}", self.epoch),
+            format!("  Ledger version:    {}", self.ledger_version),
+            format!("  Block height:      {}", self.block_height),
+            format!("  TPS:               {}", display_tps(self.tps)),
+            format!("  Ledger age:        {:.1}s", self.ledger_age_secs),
+        ];
+        if let Some(node) = &self.node {
+            lines.push("Node".to_string());
+            lines.push(format!(
+                "  Consensus round:   {}",
+                display_value(node.consensus_round)
+            ));
+            lines.push(format!(
+                "  Synced version:    {}",
+                display_value(node.synced_version)
+            ));
+            lines.push(format!(
+                "  Sync lag:          {}",
+                display_value(node.sync_lag)
+            ));
+            lines.push(format!(
+                "  Peers:             {}",
+                display_value(node.peer_count)
+            ));
+            lines.push(format!(
+                "  Mempool size:      {}",
+                display_value(node.mempool_size)
+            ));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+fn display_value(value: Option<u64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+fn display_tps(tps: Option<f64>) -> String {
+    tps.map_or_else(|| "-".to_string(), |tps| format!("{:.1}", tps))
+}
+
+fn ledger_age_secs(ledger_timestamp_usecs: u64) -> f64 {
+    let now_usecs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64;
+    now_usecs.saturating_sub(ledger_timestamp_usecs) as f64 / 1_000_000.0
+}
+
+/// A sample of the Prometheus text format
+#[derive(Debug, PartialEq)]
+struct Metric {
+    name: String,
+    labels: HashMap<String, String>,
+    value: f64,
+}
+
+async fn fetch_metrics(client: &reqwest::Client, url: &Url) -> CliTypedResult<Vec<Metric>> {
+    let body = client
+        .get(url.clone())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| CliError::ApiError(format!("Failed to fetch metrics: {}", err)))?
+        .text()
+        .await
+        .map_err(|err| CliError::ApiError(format!("Failed to read metrics: {}", err)))?;
+    Ok(parse_metrics(&body))
+}
+
+/// Parses the samples of the Prometheus text format, skipping comments and malformed lines
+fn parse_metrics(body: &str) -> Vec<Metric> {
+    body.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(parse_metric)
+        .collect()
+}
+
+/// Parses a sample line, `name[{labels}] value [timestamp]`
+fn parse_metric(line: &str) -> Option<Metric> {
+    let name_end = line.find(|c: char| c == '{' || c.is_whitespace())?;
+    let (name, rest) = line.split_at(name_end);
+    if name.is_empty() {
+        return None;
+    }
+    let (labels, rest) = match rest.strip_prefix('{') {
+        Some(rest) => parse_labels(rest)?,
+        None => (HashMap::new(), rest),
+    };
+    // The timestamp, if any, is ignored
+    let value = rest.split_whitespace().next()?.parse().ok()?;
+    Some(Metric {
+        name: name.to_string(),
+        labels,
+        value,
+    })
+}
+
+/// Parses the labels following the opening brace, `key="value",...}`, and returns them with the
+/// rest of the line. Label values can contain any character, with `\\`, `\"` and `\n` escaped.
+fn parse_labels(mut rest: &str) -> Option<(HashMap<String, String>, &str)> {
+    let mut labels = HashMap::new();
+    loop {
+        rest = rest.trim_start();
+        if let Some(rest) = rest.strip_prefix('}') {
+            return Some((labels, rest));
+        }
+        let (key, value_and_rest) = rest.split_once('=')?;
+        let value_and_rest = value_and_rest.trim_start().strip_prefix('"')?;
+        let mut value = String::new();
+        let mut chars = value_and_rest.char_indices();
+        let value_end = loop {
+            match chars.next()? {
+                (index, '"') => break index,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        labels.insert(key.trim().to_string(), value);
+        rest = value_and_rest[value_end + 1..].trim_start();
+        // Labels are separated by commas, and the last one may be followed by one too
+        if let Some(next_labels) = rest.strip_prefix(',') {
+            rest = next_labels;
+        } else if !rest.starts_with('}') {
+            return None;
+        }
+    }
+}
+
+/// Returns the value of the first sample of `name` matching all of `labels`
+fn metric_value(metrics: &[Metric], name: &str, labels: &[(&str, &str)]) -> Option<u64> {
+    metrics
+        .iter()
+        .find(|metric| {
+            metric.name == name
+                && labels
+                    .iter()
+                    .all(|(key, value)| metric.labels.get(*key).map(String::as_str) == Some(*value))
+        })
+        .map(|metric| metric.value as u64)
+}
+
+/// Returns the sum of all samples of `name`
+fn metric_sum(metrics: &[Metric], name: &str) -> Option<u64> {
+    let samples: Vec<_> = metrics
+        .iter()
+        .filter(|metric| metric.name == name)
+        .collect();
+    if samples.is_empty() {
+        None
+    } else {
+        Some(samples.iter().map(|metric| metric.value).sum::<f64>() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = r#"
+# HELP aptos_connections Number of current connections and their direction
+# TYPE aptos_connections gauge
+aptos_connections{direction="inbound",network_id="Validator",peer_id="c4e3",role_type="validator"} 3
+aptos_connections{direction="outbound",network_id="Validator",peer_id="c4e3",role_type="validator"} 2
+aptos_consensus_current_round 1234
+aptos_core_mempool_index_size{index="parking_lot"} 1
+aptos_core_mempool_index_size{index="system_ttl"} 42
+aptos_state_sync_version{type="synced"} 990
+aptos_state_sync_version{type="synced_epoch"} 7
+"#;
+
+    #[test]
+    fn test_local_node_health_from_metrics() {
+        let metrics = parse_metrics(METRICS);
+        assert_eq!(metrics.len(), 7);
+
+        let health = LocalNodeHealth::from_metrics(&metrics, 1000);
+        assert_eq!(health.consensus_round, Some(1234));
+        assert_eq!(health.synced_version, Some(990));
+        assert_eq!(health.sync_lag, Some(10));
+        assert_eq!(health.peer_count, Some(5));
+        assert_eq!(health.mempool_size, Some(42));
+
+        let health = LocalNodeHealth::from_metrics(&[], 1000);
+        assert_eq!(health.consensus_round, None);
+        assert_eq!(health.sync_lag, None);
+        assert_eq!(health.peer_count, None);
+    }
+
+    #[test]
+    fn test_parse_malformed_metrics() {
+        assert_eq!(parse_metric("aptos_metric"), None);
+        assert_eq!(parse_metric("aptos_metric{type=\"a\" 1"), None);
+        assert_eq!(parse_metric("aptos_metric not_a_number"), None);
+        assert_eq!(parse_metric("aptos_metric{type=\"a\" kind=\"b\"} 1"), None);
+        assert_eq!(parse_metric("aptos_metric{type=\"a} 1"), None);
+        assert_eq!(parse_metric("{type=\"a\"} 1"), None);
+    }
+
+    #[test]
+    fn test_parse_quoted_labels() {
+        let metric =
+            parse_metric(r#"aptos_metric{path="/a,b",msg="say \"hi\"\nbye\\",empty="",} 2.5 1668"#)
+                .unwrap();
+        assert_eq!(metric.name, "aptos_metric");
+        assert_eq!(metric.value, 2.5);
+        assert_eq!(metric.labels.len(), 3);
+        assert_eq!(metric.labels["path"], "/a,b");
+        assert_eq!(metric.labels["msg"], "say \"hi\"\nbye\\");
+        assert_eq!(metric.labels["empty"], "");
+
+        let metric = parse_metric(r#"aptos_metric{type="a b} c"} 3"#).unwrap();
+        assert_eq!(metric.labels["type"], "a b} c");
+        assert_eq!(metric.value, 3.0);
+    }
+}
@@ -4,7 +4,8 @@
 use crate::{
     common::{
         types::{
-            CliError, CliTypedResult, EncodingOptions, EncodingType, KeyType, RngArgs, SaveFile,
+            CliError, CliTypedResult, EncodingOptions, EncodingType, KeyType, OutputFormat,
+            RngArgs, SaveFile,
         },
         utils::{append_file_extension, check_if_file_exists, write_to_file},
     },
@@ -34,10 +35,10 @@ pub enum KeyTool {
 }
 
 impl KeyTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         match self {
-            KeyTool::Generate(tool) => tool.execute_serialized().await,
-            KeyTool::ExtractPeer(tool) => tool.execute_serialized().await,
+            KeyTool::Generate(tool) => tool.execute_serialized(output_format).await,
+            KeyTool::ExtractPeer(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
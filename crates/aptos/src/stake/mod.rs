@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::common::types::{
-    CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
+    CliCommand, CliError, CliResult, CliTypedResult, OutputFormat, TransactionOptions,
+    TransactionSummary,
 };
 use crate::common::utils::prompt_yes_with_override;
 use crate::node::{get_stake_pools, StakePoolType};
@@ -32,20 +33,20 @@ pub enum StakeTool {
 }
 
 impl StakeTool {
-    pub async fn execute(self) -> CliResult {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
         use StakeTool::*;
         match self {
-            AddStake(tool) => tool.execute_serialized().await,
-            CreateStakingContract(tool) => tool.execute_serialized().await,
-            UnlockStake(tool) => tool.execute_serialized().await,
-            WithdrawStake(tool) => tool.execute_serialized().await,
-            IncreaseLockup(tool) => tool.execute_serialized().await,
-            InitializeStakeOwner(tool) => tool.execute_serialized().await,
-            RequestCommission(tool) => tool.execute_serialized().await,
-            SetOperator(tool) => tool.execute_serialized().await,
-            SetDelegatedVoter(tool) => tool.execute_serialized().await,
-            UnlockVestedCoins(tool) => tool.execute_serialized().await,
-            DistributeVestedCoins(tool) => tool.execute_serialized().await,
+            AddStake(tool) => tool.execute_serialized(output_format).await,
+            CreateStakingContract(tool) => tool.execute_serialized(output_format).await,
+            UnlockStake(tool) => tool.execute_serialized(output_format).await,
+            WithdrawStake(tool) => tool.execute_serialized(output_format).await,
+            IncreaseLockup(tool) => tool.execute_serialized(output_format).await,
+            InitializeStakeOwner(tool) => tool.execute_serialized(output_format).await,
+            RequestCommission(tool) => tool.execute_serialized(output_format).await,
+            SetOperator(tool) => tool.execute_serialized(output_format).await,
+            SetDelegatedVoter(tool) => tool.execute_serialized(output_format).await,
+            UnlockVestedCoins(tool) => tool.execute_serialized(output_format).await,
+            DistributeVestedCoins(tool) => tool.execute_serialized(output_format).await,
         }
     }
 }
@@ -3,43 +3,54 @@
 
 use crate::account::key_rotation::LookupAddress;
 use crate::account::{
+    batch_submit::{BatchSubmit, BatchTransactionResult},
     create::{CreateAccount, DEFAULT_FUNDED_COINS},
     fund::FundWithFaucet,
     key_rotation::{RotateKey, RotateSummary},
-    list::{ListAccount, ListQuery},
+    list::{ListAccount, ListFormat, ListQuery},
     transfer::{TransferCoins, TransferSummary},
+    watch::{ResourceChange, WatchAccount},
 };
-use crate::common::init::{InitTool, Network};
+use crate::common::init::InitTool;
 use crate::common::types::{
     account_address_from_public_key, AccountAddressWrapper, CliError, CliTypedResult,
-    EncodingOptions, FaucetOptions, GasOptions, KeyType, MoveManifestAccountWrapper,
-    MovePackageDir, OptionalPoolAddressArgs, PrivateKeyInputOptions, PromptOptions,
-    PublicKeyInputOptions, RestOptions, RngArgs, SaveFile, TransactionOptions, TransactionSummary,
+    EncodingOptions, FaucetOptions, GasOptions, GasProfilingOptions, KeyType,
+    MoveManifestAccountWrapper, MovePackageDir, OptionalPoolAddressArgs, PrivateKeyInputOptions,
+    ProfileOptions, PromptOptions, PublicKeyInputOptions, RestOptions, RngArgs, SaveFile,
+    TransactionOptions, TransactionSummary,
 };
 
-use crate::common::utils::write_to_file;
+use crate::common::utils::{write_to_file, JsonOutput};
 
-use crate::governance::CompileScriptFunction;
+use crate::governance::{CompileScriptFunction, ExecuteProposal, SubmitVote};
 use crate::move_tool::{
     ArgWithType, CompilePackage, DownloadPackage, FrameworkPackageArgs, IncludedArtifacts,
     IncludedArtifactsArgs, InitPackage, MemberId, PublishPackage, RunFunction, RunScript,
-    TestPackage,
+    TestPackage, VerifyPackage, VerifyPackageSummary,
+};
+use crate::multisig::{
+    ApproveMultisig, CreateMultisig, CreateMultisigSummary, ExecuteMultisig, MultisigApproval,
+    MultisigProposal, ProposeMultisig,
 };
 use crate::node::{
-    AnalyzeMode, AnalyzeValidatorPerformance, GetStakePool, InitializeValidator, JoinValidatorSet,
+    AutoJoinArgs, GetStakePool, InitializeValidator, JoinValidatorSet, JoinValidatorSetSummary,
     LeaveValidatorSet, OperatorArgs, OperatorConfigFileArgs, ShowValidatorConfig, ShowValidatorSet,
     ShowValidatorStake, StakePoolResult, UpdateConsensusKey, UpdateValidatorNetworkAddresses,
     ValidatorConfig, ValidatorConsensusKeyArgs, ValidatorNetworkAddressesArgs,
+    ValidatorPerformanceReport,
 };
 use crate::op::key::{ExtractPeer, GenerateKey, NetworkKeyInputOptions, SaveKey};
 use crate::stake::{
-    AddStake, IncreaseLockup, InitializeStakeOwner, SetDelegatedVoter, SetOperator, UnlockStake,
-    WithdrawStake,
+    AddStake, CreateStakingContract, IncreaseLockup, InitializeStakeOwner, RequestCommission,
+    SetDelegatedVoter, SetOperator, UnlockStake, WithdrawStake,
+};
+use crate::transaction::{
+    BuildTransaction, RawTransactionSummary, SignTransaction, SubmitTransaction,
 };
-use crate::CliCommand;
+use crate::{CliCommand, Tool};
 use aptos_config::config::Peer;
 use aptos_crypto::ed25519::Ed25519PublicKey;
-use aptos_crypto::{bls12381, ed25519::Ed25519PrivateKey, x25519, PrivateKey};
+use aptos_crypto::{bls12381, ed25519::Ed25519PrivateKey, x25519, HashValue, PrivateKey};
 use aptos_genesis::config::HostAndPort;
 use aptos_keygen::KeyGen;
 use aptos_logger::warn;
@@ -50,11 +61,18 @@ use aptos_sdk::move_types::identifier::Identifier;
 use aptos_sdk::move_types::language_storage::ModuleId;
 use aptos_temppath::TempPath;
 use aptos_types::on_chain_config::ValidatorSet;
+use clap::Parser;
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::{collections::BTreeMap, mem, path::PathBuf, str::FromStr, time::Duration};
+use std::{
+    collections::BTreeMap,
+    mem,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 use tempfile::TempDir;
 use thiserror::__private::PathAsDisplay;
 
@@ -243,6 +261,7 @@ impl CliTestFramework {
                 ..Default::default()
             },
             new_private_key: Some(new_private_key),
+            rng_args: RngArgs::default(),
             save_to_profile: None,
             new_private_key_file: None,
             skip_saving_profile: true,
@@ -259,6 +278,10 @@ impl CliTestFramework {
             profile_options: Default::default(),
             account: Some(self.account_id(index)),
             query,
+            resource_type: None,
+            limit: None,
+            page_size: None,
+            format: ListFormat::Json,
         }
         .execute()
         .await
@@ -280,6 +303,43 @@ impl CliTestFramework {
         .await
     }
 
+    pub async fn watch_account(
+        &self,
+        index: usize,
+        resource: Option<&str>,
+        max_changes: usize,
+        timeout_secs: u64,
+    ) -> CliTypedResult<Vec<ResourceChange>> {
+        WatchAccount {
+            address: Some(self.account_id(index)),
+            resource: resource.map(str::to_string),
+            poll_interval_ms: 100,
+            max_changes: Some(max_changes),
+            timeout_secs: Some(timeout_secs),
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+            output_format: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn batch_submit(
+        &self,
+        sender_index: usize,
+        file: PathBuf,
+        max_in_flight: usize,
+        gas_options: Option<GasOptions>,
+    ) -> CliTypedResult<Vec<BatchTransactionResult>> {
+        BatchSubmit {
+            file,
+            max_in_flight,
+            txn_options: self.transaction_options(sender_index, gas_options),
+        }
+        .execute()
+        .await
+    }
+
     pub async fn transfer_invalid_addr(
         &self,
         sender_index: usize,
@@ -305,6 +365,153 @@ impl CliTestFramework {
                 vec![],
             ))],
             txn_options: self.transaction_options(sender_index, gas_options),
+            gas_profiling_options: GasProfilingOptions::default(),
+        }
+        .execute()
+        .await
+    }
+
+    /// Creates a multisig account owned by the given accounts, funded by the first owner
+    pub async fn create_multisig(
+        &self,
+        owner_indices: &[usize],
+        threshold: u8,
+        initial_balance: u64,
+        output_file: PathBuf,
+    ) -> CliTypedResult<CreateMultisigSummary> {
+        CreateMultisig {
+            public_keys: owner_indices
+                .iter()
+                .map(|index| self.private_key(*index).public_key())
+                .collect(),
+            threshold,
+            initial_balance,
+            output_file,
+            txn_options: self.transaction_options(owner_indices[0], None),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn propose_multisig(
+        &self,
+        multisig_file: PathBuf,
+        function_id: MemberId,
+        args: Vec<&str>,
+        gas_options: Option<GasOptions>,
+        output_file: PathBuf,
+    ) -> CliTypedResult<MultisigProposal> {
+        let (args, type_args) = Self::parse_args(args, vec![])?;
+
+        ProposeMultisig {
+            multisig_file,
+            function_id,
+            args,
+            type_args,
+            expiration_secs: 60,
+            output_file,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+            gas_options: gas_options.unwrap_or_default(),
+            prompt_options: PromptOptions::yes(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn approve_multisig(
+        &self,
+        owner_index: usize,
+        proposal_file: PathBuf,
+        output_file: PathBuf,
+    ) -> CliTypedResult<MultisigApproval> {
+        ApproveMultisig {
+            proposal_file,
+            output_file,
+            private_key_options: PrivateKeyInputOptions::from_private_key(
+                self.private_key(owner_index),
+            )?,
+            encoding_options: Default::default(),
+            profile_options: Default::default(),
+            prompt_options: PromptOptions::yes(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn execute_multisig(
+        &self,
+        proposal_file: PathBuf,
+        approval_files: Vec<PathBuf>,
+    ) -> CliTypedResult<TransactionSummary> {
+        ExecuteMultisig {
+            proposal_file,
+            approval_files,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn build_transaction(
+        &self,
+        sender_index: usize,
+        function_id: MemberId,
+        args: Vec<&str>,
+        output_file: PathBuf,
+    ) -> CliTypedResult<RawTransactionSummary> {
+        let (args, type_args) = Self::parse_args(args, vec![])?;
+
+        BuildTransaction {
+            function_id,
+            args,
+            type_args,
+            sender_account: None,
+            sequence_number: None,
+            expiration_secs: 60,
+            output_file,
+            public_key_options: PublicKeyInputOptions::from_key(
+                &self.private_key(sender_index).public_key(),
+            ),
+            encoding_options: Default::default(),
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+            gas_options: Default::default(),
+            prompt_options: PromptOptions::yes(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        signer_index: usize,
+        payload: PathBuf,
+        output_file: PathBuf,
+    ) -> CliTypedResult<RawTransactionSummary> {
+        SignTransaction {
+            payload,
+            output_file,
+            private_key_options: PrivateKeyInputOptions::from_private_key(
+                self.private_key(signer_index),
+            )?,
+            encoding_options: Default::default(),
+            profile_options: Default::default(),
+            prompt_options: PromptOptions::yes(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_signed_transaction(
+        &self,
+        signed: PathBuf,
+    ) -> CliTypedResult<TransactionSummary> {
+        SubmitTransaction {
+            signed,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
         }
         .execute()
         .await
@@ -434,11 +641,69 @@ impl CliTestFramework {
         JoinValidatorSet {
             txn_options: self.transaction_options(operator_index, None),
             operator_args: self.operator_args(pool_index),
+            auto_join_args: AutoJoinArgs {
+                auto: false,
+                stake_amount: None,
+                operator_address: None,
+                voter_address: None,
+                activation_timeout_secs: 0,
+                operator_config_file_args: OperatorConfigFileArgs {
+                    operator_config_file: None,
+                },
+                validator_consensus_key_args: ValidatorConsensusKeyArgs {
+                    consensus_public_key: None,
+                    proof_of_possession: None,
+                },
+                validator_network_addresses_args: ValidatorNetworkAddressesArgs {
+                    validator_host: None,
+                    validator_network_public_key: None,
+                    full_node_host: None,
+                    full_node_network_public_key: None,
+                },
+            },
         }
         .execute()
         .await
     }
 
+    pub async fn join_validator_set_auto(
+        &self,
+        owner_index: usize,
+        stake_amount: u64,
+        consensus_public_key: bls12381::PublicKey,
+        proof_of_possession: bls12381::ProofOfPossession,
+        validator_host: HostAndPort,
+        validator_network_public_key: x25519::PublicKey,
+        activation_timeout_secs: u64,
+    ) -> CliTypedResult<JoinValidatorSetSummary> {
+        JoinValidatorSet {
+            txn_options: self.transaction_options(owner_index, None),
+            operator_args: self.operator_args(None),
+            auto_join_args: AutoJoinArgs {
+                auto: true,
+                stake_amount: Some(stake_amount),
+                operator_address: None,
+                voter_address: None,
+                activation_timeout_secs,
+                operator_config_file_args: OperatorConfigFileArgs {
+                    operator_config_file: None,
+                },
+                validator_consensus_key_args: ValidatorConsensusKeyArgs {
+                    consensus_public_key: Some(consensus_public_key),
+                    proof_of_possession: Some(proof_of_possession),
+                },
+                validator_network_addresses_args: ValidatorNetworkAddressesArgs {
+                    validator_host: Some(validator_host),
+                    validator_network_public_key: Some(validator_network_public_key),
+                    full_node_host: None,
+                    full_node_network_public_key: None,
+                },
+            },
+        }
+        .execute_auto()
+        .await
+    }
+
     pub async fn leave_validator_set(
         &self,
         operator_index: usize,
@@ -480,17 +745,40 @@ impl CliTestFramework {
         &self,
         start_epoch: Option<i64>,
         end_epoch: Option<i64>,
-    ) -> CliTypedResult<()> {
-        AnalyzeValidatorPerformance {
-            start_epoch: start_epoch.unwrap_or(-2),
-            end_epoch,
-            rest_options: self.rest_options(),
-            profile_options: Default::default(),
-            analyze_mode: AnalyzeMode::All,
-            pool_addresses: vec![],
+    ) -> CliTypedResult<ValidatorPerformanceReport> {
+        let start_epoch = start_epoch.unwrap_or(-2).to_string();
+        let end_epoch = end_epoch.map(|epoch| epoch.to_string());
+        let mut args = vec![
+            "node",
+            "analyze-validator-performance",
+            "--analyze-mode",
+            "all",
+            "--start-epoch",
+            start_epoch.as_str(),
+            "--url",
+            self.endpoint.as_str(),
+        ];
+        if let Some(ref end_epoch) = end_epoch {
+            args.extend(["--end-epoch", end_epoch.as_str()]);
         }
-        .execute()
-        .await
+        Self::run_with_json_output(&args).await
+    }
+
+    /// Runs the CLI with `--output json`, as a script would, and parses the result of the command
+    pub async fn run_with_json_output<T: DeserializeOwned>(args: &[&str]) -> CliTypedResult<T> {
+        let tool = Tool::try_parse_from(["aptos"].iter().chain(args).chain(&["--output", "json"]))
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let output = match tool.execute().await {
+            Ok(output) | Err(output) => output,
+        };
+        let output: JsonOutput<T> = serde_json::from_str(&output)
+            .map_err(|err| CliError::UnableToParse("JSON output", err.to_string()))?;
+        if let Some(error) = output.error {
+            return Err(CliError::UnexpectedError(error));
+        }
+        output.result.ok_or_else(|| {
+            CliError::UnexpectedError(format!("No result in the output of {}", output.command))
+        })
     }
 
     pub async fn update_consensus_key(
@@ -517,12 +805,14 @@ impl CliTestFramework {
 
     pub async fn init(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
         InitTool {
-            network: Some(Network::Custom),
             rest_url: Some(self.endpoint.clone()),
             faucet_url: Some(self.faucet_endpoint.clone()),
             rng_args: RngArgs::from_seed([0; 32]),
             private_key_options: PrivateKeyInputOptions::from_private_key(private_key)?,
-            profile_options: Default::default(),
+            profile_options: ProfileOptions {
+                profile: None,
+                network: Some("custom".to_string()),
+            },
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
             skip_faucet: false,
@@ -576,23 +866,32 @@ impl CliTestFramework {
         amount: u64,
         commission_percentage: u64,
     ) -> CliTypedResult<TransactionSummary> {
-        RunFunction {
-            function_id: MemberId::from_str("0x1::staking_contract::create_staking_contract")
-                .unwrap(),
-            args: vec![
-                ArgWithType::address(self.account_id(operator_index)),
-                ArgWithType::address(self.account_id(voter_index)),
-                ArgWithType::u64(amount),
-                ArgWithType::u64(commission_percentage),
-                ArgWithType::bytes(vec![]),
-            ],
-            type_args: vec![],
+        CreateStakingContract {
+            operator: self.account_id(operator_index),
+            voter: self.account_id(voter_index),
+            amount,
+            commission_percentage,
             txn_options: self.transaction_options(owner_index, None),
         }
         .execute()
         .await
     }
 
+    pub async fn request_commission(
+        &self,
+        sender_index: usize,
+        owner_index: usize,
+        operator_index: usize,
+    ) -> CliTypedResult<TransactionSummary> {
+        RequestCommission {
+            owner_address: self.account_id(owner_index),
+            operator_address: self.account_id(operator_index),
+            txn_options: self.transaction_options(sender_index, None),
+        }
+        .execute()
+        .await
+    }
+
     pub async fn set_operator(
         &self,
         owner_index: usize,
@@ -844,6 +1143,7 @@ impl CliTestFramework {
             included_artifacts_args: IncludedArtifactsArgs {
                 included_artifacts: included_artifacts.unwrap_or(IncludedArtifacts::Sparse),
             },
+            output_format: Default::default(),
         }
         .execute()
         .await
@@ -861,6 +1161,26 @@ impl CliTestFramework {
             account: self.account_id(index),
             package,
             output_dir: Some(output_dir),
+            verify_source_digest: false,
+            output_format: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn verify_package(
+        &self,
+        account: AccountAddress,
+        package_dir: PathBuf,
+        bytecode_only: bool,
+    ) -> CliTypedResult<VerifyPackageSummary> {
+        VerifyPackage {
+            account,
+            included_artifacts: IncludedArtifacts::Sparse,
+            bytecode_only,
+            move_options: MovePackageDir::new(package_dir),
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
         }
         .execute()
         .await
@@ -874,6 +1194,23 @@ impl CliTestFramework {
         args: Vec<&str>,
         type_args: Vec<&str>,
     ) -> CliTypedResult<TransactionSummary> {
+        let (args, type_args) = Self::parse_args(args, type_args)?;
+
+        RunFunction {
+            txn_options: self.transaction_options(index, gas_options),
+            function_id,
+            args,
+            type_args,
+            gas_profiling_options: GasProfilingOptions::default(),
+        }
+        .execute()
+        .await
+    }
+
+    fn parse_args(
+        args: Vec<&str>,
+        type_args: Vec<&str>,
+    ) -> CliTypedResult<(Vec<ArgWithType>, Vec<MoveType>)> {
         let mut parsed_args = Vec::new();
         for arg in args {
             parsed_args.push(
@@ -889,22 +1226,96 @@ impl CliTestFramework {
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             )
         }
+        Ok((parsed_args, parsed_type_args))
+    }
 
-        RunFunction {
-            txn_options: self.transaction_options(index, gas_options),
-            function_id,
-            args: parsed_args,
-            type_args: parsed_type_args,
+    pub async fn run_script(
+        &self,
+        index: usize,
+        script_contents: &str,
+    ) -> CliTypedResult<TransactionSummary> {
+        let (_temp_dir, compile_proposal_args) = Self::script_compile_args(script_contents)?;
+
+        RunScript {
+            txn_options: self.transaction_options(index, None),
+            compile_proposal_args,
+            args: Vec::new(),
+            type_args: Vec::new(),
+            gas_profiling_options: GasProfilingOptions::default(),
         }
         .execute()
         .await
     }
 
-    pub async fn run_script(
+    /// Runs the script at the given path with the given args, in the same format as the CLI e.g.
+    /// `vector<vector<u8>>:[[1,2],[3]]`
+    pub async fn run_script_with_script_path(
+        &self,
+        index: usize,
+        script_path: &Path,
+        args: Vec<&str>,
+        type_args: Vec<&str>,
+    ) -> CliTypedResult<TransactionSummary> {
+        let (args, type_args) = Self::parse_args(args, type_args)?;
+
+        RunScript {
+            txn_options: self.transaction_options(index, None),
+            compile_proposal_args: Self::script_path_compile_args(script_path.to_path_buf()),
+            args,
+            type_args,
+            gas_profiling_options: GasProfilingOptions::default(),
+        }
+        .execute()
+        .await
+    }
+
+    /// Compiles the given script, returning its bytecode and hash, e.g. to create a governance
+    /// proposal executed by the script
+    pub fn compile_script(&self, script_contents: &str) -> CliTypedResult<(Vec<u8>, HashValue)> {
+        let (_temp_dir, compile_proposal_args) = Self::script_compile_args(script_contents)?;
+        compile_proposal_args.compile("CompileScript", PromptOptions::yes())
+    }
+
+    pub async fn vote(
+        &self,
+        index: usize,
+        proposal_id: u64,
+        pool_addresses: Vec<AccountAddress>,
+        yes: bool,
+    ) -> CliTypedResult<Vec<TransactionSummary>> {
+        SubmitVote {
+            proposal_id,
+            yes,
+            no: !yes,
+            pool_addresses,
+            txn_options: self.transaction_options(index, None),
+            output_format: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn execute_proposal(
         &self,
         index: usize,
+        proposal_id: u64,
         script_contents: &str,
     ) -> CliTypedResult<TransactionSummary> {
+        let (_temp_dir, compile_proposal_args) = Self::script_compile_args(script_contents)?;
+
+        ExecuteProposal {
+            proposal_id,
+            txn_options: self.transaction_options(index, None),
+            compile_proposal_args,
+        }
+        .execute()
+        .await
+    }
+
+    /// Writes the script to a temporary directory, which has to be kept until the script is compiled
+    fn script_compile_args(
+        script_contents: &str,
+    ) -> CliTypedResult<(TempDir, CompileScriptFunction)> {
         // Make a temporary directory for compilation
         let temp_dir = TempDir::new().map_err(|err| {
             CliError::UnexpectedError(format!("Failed to create temporary directory {}", err))
@@ -918,23 +1329,20 @@ impl CliTestFramework {
         )
         .unwrap();
 
-        RunScript {
-            txn_options: self.transaction_options(index, None),
-            compile_proposal_args: CompileScriptFunction {
-                script_path: Some(source_path),
-                compiled_script_path: None,
-                framework_package_args: FrameworkPackageArgs {
-                    framework_git_rev: None,
-                    framework_local_dir: Some(Self::aptos_framework_dir()),
-                    skip_fetch_latest_git_deps: false,
-                },
-                bytecode_version: None,
+        Ok((temp_dir, Self::script_path_compile_args(source_path)))
+    }
+
+    fn script_path_compile_args(script_path: PathBuf) -> CompileScriptFunction {
+        CompileScriptFunction {
+            script_path: Some(script_path),
+            compiled_script_path: None,
+            framework_package_args: FrameworkPackageArgs {
+                framework_git_rev: None,
+                framework_local_dir: Some(Self::aptos_framework_dir()),
+                skip_fetch_latest_git_deps: false,
             },
-            args: Vec::new(),
-            type_args: Vec::new(),
+            bytecode_version: None,
         }
-        .execute()
-        .await
     }
 
     fn aptos_framework_dir() -> PathBuf {
@@ -984,6 +1392,14 @@ impl CliTestFramework {
             .collect()
     }
 
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    pub fn faucet_endpoint(&self) -> &Url {
+        &self.faucet_endpoint
+    }
+
     pub fn rest_options(&self) -> RestOptions {
         RestOptions::new(Some(self.endpoint.clone()), None)
     }
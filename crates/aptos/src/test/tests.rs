@@ -2,11 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    common::{
+        init::Network,
+        types::{CliConfig, CliTypedResult},
+        utils::{JsonOutput, JSON_OUTPUT_VERSION},
+    },
+    genesis::git::from_yaml,
     move_tool::{ArgWithType, FunctionArgType},
     CliResult, Tool,
 };
+use aptos_types::transaction::TransactionArgument;
 use clap::Parser;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 /// In order to ensure that there aren't duplicate input arguments for untested CLI commands,
 /// we call help on every command to ensure it at least runs
@@ -15,6 +22,7 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos"]).await;
 
     assert_cmd_not_panic(&["aptos", "account"]).await;
+    assert_cmd_not_panic(&["aptos", "account", "batch-submit", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "account", "create", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "account", "create-resource-account", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "account", "fund-with-faucet", "--help"]).await;
@@ -22,12 +30,15 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "account", "lookup-address", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "account", "rotate-key", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "account", "transfer", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "account", "watch", "--help"]).await;
 
     assert_cmd_not_panic(&["aptos", "config"]).await;
     assert_cmd_not_panic(&["aptos", "config", "generate-shell-completions", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "config", "init", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "config", "set-global-config", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "config", "set-network", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "config", "show-global-config"]).await;
+    assert_cmd_not_panic(&["aptos", "config", "show-networks", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "config", "show-profiles"]).await;
 
     assert_cmd_not_panic(&["aptos", "genesis"]).await;
@@ -40,6 +51,7 @@ async fn ensure_every_command_args_work() {
 
     assert_cmd_not_panic(&["aptos", "governance"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "execute-proposal", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "governance", "execute-release", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "generate-upgrade-proposal", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "propose", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "governance", "vote", "--help"]).await;
@@ -64,6 +76,13 @@ async fn ensure_every_command_args_work() {
     assert_cmd_not_panic(&["aptos", "move", "run-script", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "test", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "move", "transactional-test", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "move", "verify-package", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "multisig"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "approve", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "create", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "execute", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "multisig", "propose", "--help"]).await;
 
     assert_cmd_not_panic(&["aptos", "node"]).await;
     assert_cmd_not_panic(&["aptos", "node", "get-stake-pool", "--help"]).await;
@@ -87,12 +106,83 @@ async fn ensure_every_command_args_work() {
 
     assert_cmd_not_panic(&["aptos", "stake"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "add-stake", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "create-staking-contract", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "distribute-vested-coins", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "increase-lockup", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "initialize-stake-owner", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "request-commission", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "set-delegated-voter", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "set-operator", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "unlock-stake", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "stake", "unlock-vested-coins", "--help"]).await;
     assert_cmd_not_panic(&["aptos", "stake", "withdraw-stake", "--help"]).await;
+
+    assert_cmd_not_panic(&["aptos", "transaction"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "build", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "sign", "--help"]).await;
+    assert_cmd_not_panic(&["aptos", "transaction", "submit", "--help"]).await;
+}
+
+/// Ensure `--output` is accepted by every command, without clashing with the ones having their own
+#[tokio::test]
+async fn ensure_can_parse_output_format() {
+    assert!(Tool::try_parse_from(["aptos", "info", "--output", "json"]).is_ok());
+    assert!(Tool::try_parse_from(["aptos", "--output", "text", "info"]).is_ok());
+    assert!(Tool::try_parse_from(["aptos", "info", "--output", "yaml"]).is_err());
+    assert!(Tool::try_parse_from([
+        "aptos",
+        "governance",
+        "generate-upgrade-proposal",
+        "--output",
+        "proposal.move",
+    ])
+    .is_ok());
+}
+
+/// Ensure named networks can be read from the config, and the built in ones have endpoints
+#[tokio::test]
+async fn ensure_can_parse_networks() {
+    let config: CliConfig = from_yaml(
+        "---\nnetworks:\n  swarm:\n    rest_url: \"http://localhost:8080/\"\n    chain_id: 4\n",
+    )
+    .unwrap();
+    assert!(config.profiles.is_none());
+    let network = config.networks.unwrap().remove("swarm").unwrap();
+    assert_eq!(network.rest_url, "http://localhost:8080/");
+    assert_eq!(network.faucet_url, None);
+    assert_eq!(network.chain_id, Some(4));
+
+    assert_eq!(Network::Testnet.network_config().unwrap().chain_id, Some(2));
+    assert!(Network::Custom.network_config().is_none());
+    assert!(Tool::try_parse_from(["aptos", "account", "list", "--network", "testnet"]).is_ok());
+
+    // Profiles keep the name of their network, and the built in ones are still read as such
+    let config: CliConfig = from_yaml(
+        "---\nprofiles:\n  default:\n    network: Testnet\n  swarm:\n    network: swarm\n",
+    )
+    .unwrap();
+    let profiles = config.profiles.unwrap();
+    assert_eq!(profiles["default"].network, Some(Network::Testnet));
+    assert_eq!(
+        profiles["swarm"].network,
+        Some(Network::Named("swarm".to_string()))
+    );
+    assert_eq!(String::from(Network::Named("swarm".to_string())), "swarm");
+}
+
+/// Ensure the output format only applies to the command it's given to
+#[tokio::test]
+async fn ensure_output_format_is_per_command() {
+    let output = run_cmd(&["aptos", "info", "--output", "json"])
+        .await
+        .unwrap();
+    let output: JsonOutput<BTreeMap<String, String>> = serde_json::from_str(&output).unwrap();
+    assert_eq!(output.version, JSON_OUTPUT_VERSION);
+    assert_eq!(output.command, "GetCLIInfo");
+    assert!(output.result.is_some());
+
+    let output = run_cmd(&["aptos", "info"]).await.unwrap();
+    assert!(output.starts_with("{\n  \"Result\": {"));
 }
 
 /// Ensure we can parse URLs for args
@@ -106,6 +196,57 @@ async fn ensure_can_parse_args_with_urls() {
     );
 }
 
+/// Ensure we can parse JSON-encoded nested vectors and BCS blobs
+#[tokio::test]
+async fn ensure_can_parse_complex_args() {
+    let result = ArgWithType::from_str("vector<vector<u8>>:[[1,2],[]]").unwrap();
+    assert_eq!(
+        result.arg,
+        bcs::to_bytes(&vec![vec![1u8, 2], vec![]]).unwrap()
+    );
+
+    let result = ArgWithType::from_str(r#"vector<string>:["a,b","c"]"#).unwrap();
+    assert_eq!(
+        result.arg,
+        bcs::to_bytes(&vec!["a,b".to_string(), "c".to_string()]).unwrap()
+    );
+
+    let result = ArgWithType::from_str("bcs:0x1::string::String:0568656c6c6f").unwrap();
+    assert_eq!(result.arg, bcs::to_bytes(&"hello".to_string()).unwrap());
+
+    assert!(ArgWithType::from_str("vector<vector<u8>>:1,2").is_err());
+    assert!(ArgWithType::from_str("vector<u8>:[256]").is_err());
+}
+
+/// Ensure only the arguments scripts take are converted to script arguments
+#[tokio::test]
+async fn ensure_can_convert_script_args() {
+    let to_script_arg = |arg: &str| -> CliTypedResult<TransactionArgument> {
+        ArgWithType::from_str(arg).unwrap().try_into()
+    };
+    assert_eq!(
+        to_script_arg("vector<u8>:1,2").unwrap(),
+        TransactionArgument::U8Vector(vec![1, 2])
+    );
+    assert_eq!(
+        to_script_arg("vector<u8>:[1,2]").unwrap(),
+        TransactionArgument::U8Vector(vec![1, 2])
+    );
+    assert_eq!(
+        to_script_arg("bcs:u64:0100000000000000").unwrap(),
+        TransactionArgument::U64(1)
+    );
+    assert_eq!(
+        to_script_arg("bcs:vector<u8>:020102").unwrap(),
+        TransactionArgument::U8Vector(vec![1, 2])
+    );
+
+    // Nested vectors and structs can only be passed to entry functions
+    assert!(to_script_arg("vector<vector<u8>>:[[1,2],[]]").is_err());
+    assert!(to_script_arg("vector<u64>:1,2").is_err());
+    assert!(to_script_arg("bcs:0x1::string::String:0568656c6c6f").is_err());
+}
+
 async fn assert_cmd_not_panic(args: &[&str]) {
     // When a command fails, it will have a panic in it due to an improperly setup command
     // thread 'main' panicked at 'Command propose: Argument names must be unique, but 'assume-yes' is
@@ -0,0 +1,303 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    account_address_from_public_key, estimate_max_gas, CliError, CliTypedResult, EncodingOptions,
+    ExtractPublicKey, GasOptions, OutputFormat, PrivateKeyInputOptions, ProfileOptions,
+    PromptOptions, PublicKeyInputOptions, RestOptions, TransactionSummary,
+};
+use crate::common::utils::{
+    chain_id, check_if_file_exists, get_sequence_number, prompt_yes_with_override, read_from_file,
+    write_to_file,
+};
+use crate::move_tool::{entry_function_payload, ArgWithType, MemberId};
+use crate::multisig::describe_payload;
+use crate::{CliCommand, CliResult};
+use aptos_crypto::ed25519::Ed25519Signature;
+use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{RawTransaction, SignedTransaction},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    convert::TryFrom,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Tool for building, signing and submitting transactions separately
+///
+/// Transactions are built and submitted online, while they are signed without connecting to the
+/// network, so that the key signing them can stay on an air-gapped machine.  They are exchanged
+/// as BCS encoded files.
+#[derive(Parser)]
+pub enum TransactionTool {
+    Build(BuildTransaction),
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self, output_format: OutputFormat) -> CliResult {
+        match self {
+            TransactionTool::Build(tool) => tool.execute_serialized(output_format).await,
+            TransactionTool::Sign(tool) => tool.execute_serialized(output_format).await,
+            TransactionTool::Submit(tool) => tool.execute_serialized(output_format).await,
+        }
+    }
+}
+
+/// The content of a raw transaction, to be reviewed before signing it
+#[derive(Debug, Serialize)]
+pub struct RawTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub function: String,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+    pub chain_id: u8,
+}
+
+impl From<&RawTransaction> for RawTransactionSummary {
+    fn from(raw_transaction: &RawTransaction) -> Self {
+        RawTransactionSummary {
+            sender: raw_transaction.sender(),
+            sequence_number: raw_transaction.sequence_number(),
+            function: describe_payload(raw_transaction.payload()),
+            max_gas_amount: raw_transaction.max_gas_amount(),
+            gas_unit_price: raw_transaction.gas_unit_price(),
+            expiration_timestamp_secs: raw_transaction.expiration_timestamp_secs(),
+            chain_id: raw_transaction.chain_id().id(),
+        }
+    }
+}
+
+fn read_bcs_file<T: DeserializeOwned>(path: &Path, name: &'static str) -> CliTypedResult<T> {
+    bcs::from_bytes(&read_from_file(path)?)
+        .map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+/// Build a transaction calling an entry function, to be signed offline
+///
+/// The BCS encoded raw transaction is written to `--output-file`, along with its sequence
+/// number, chain id and expiration, to be signed with `aptos transaction sign`.  Only the public
+/// key of the sender is needed, to derive its address and to simulate the transaction when
+/// `--max-gas` isn't set.
+#[derive(Parser)]
+pub struct BuildTransaction {
+    /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
+    #[clap(long)]
+    pub(crate) function_id: MemberId,
+    /// Arguments combined with their type separated by spaces, same as for `aptos move run`
+    ///
+    /// Example: `address:0x1 bool:true u8:0`
+    #[clap(long, multiple_values = true)]
+    pub(crate) args: Vec<ArgWithType>,
+    /// TypeTag arguments separated by spaces.
+    ///
+    /// Example: `u8 u64 u128 bool address vector signer`
+    #[clap(long, multiple_values = true)]
+    pub(crate) type_args: Vec<MoveType>,
+    /// Sender account address
+    ///
+    /// Defaults to the address derived from the public key, to be set if the authentication key
+    /// was rotated
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) sender_account: Option<AccountAddress>,
+    /// Sequence number of the transaction, the next one of the sender if not set
+    #[clap(long)]
+    pub(crate) sequence_number: Option<u64>,
+    /// Number of seconds the transaction can be signed and submitted in
+    #[clap(long, default_value_t = 3600)]
+    pub(crate) expiration_secs: u64,
+    /// File to write the raw transaction to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) public_key_options: PublicKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) gas_options: GasOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<RawTransactionSummary> for BuildTransaction {
+    fn command_name(&self) -> &'static str {
+        "BuildTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransactionSummary> {
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+        let payload = entry_function_payload(self.function_id, self.args, self.type_args)?;
+        let public_key = || {
+            self.public_key_options
+                .extract_public_key(self.encoding_options.encoding, &self.profile_options)
+        };
+        let sender = match self.sender_account {
+            Some(sender) => sender,
+            None => account_address_from_public_key(&public_key()?),
+        };
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let sequence_number = match self.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => get_sequence_number(&client, sender).await?,
+        };
+        let gas_unit_price = if let Some(gas_unit_price) = self.gas_options.gas_unit_price {
+            gas_unit_price
+        } else {
+            client.estimate_gas_price().await?.into_inner().gas_estimate
+        };
+        let transaction_factory = TransactionFactory::new(chain_id(&client).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_transaction_expiration_time(self.expiration_secs);
+        let max_gas = if let Some(max_gas) = self.gas_options.max_gas {
+            max_gas
+        } else {
+            let signed_transaction = SignedTransaction::new(
+                transaction_factory
+                    .payload(payload.clone())
+                    .sender(sender)
+                    .sequence_number(sequence_number)
+                    .build(),
+                public_key()?,
+                Ed25519Signature::try_from([0u8; 64].as_ref()).unwrap(),
+            );
+            estimate_max_gas(&client, &signed_transaction).await?.1
+        };
+
+        let raw_transaction = transaction_factory
+            .payload(payload)
+            .sender(sender)
+            .sequence_number(sequence_number)
+            .max_gas_amount(max_gas)
+            .build();
+        write_to_file(
+            &self.output_file,
+            "Raw transaction",
+            &bcs::to_bytes(&raw_transaction)?,
+        )?;
+        Ok(RawTransactionSummary::from(&raw_transaction))
+    }
+}
+
+/// Sign a transaction built by `aptos transaction build`
+///
+/// This doesn't connect to the network, so that it can run on an air-gapped machine.  The
+/// signed transaction is written to `--output-file`, to be submitted with
+/// `aptos transaction submit`.
+#[derive(Parser)]
+pub struct SignTransaction {
+    /// Raw transaction file written by `aptos transaction build`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) payload: PathBuf,
+    /// File to write the signed transaction to
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<RawTransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransactionSummary> {
+        let raw_transaction: RawTransaction = read_bcs_file(&self.payload, "--payload")?;
+        let summary = RawTransactionSummary::from(&raw_transaction);
+        let now_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if summary.expiration_timestamp_secs <= now_secs {
+            return Err(CliError::CommandArgumentError(format!(
+                "The transaction expired at {}, it has to be built again",
+                summary.expiration_timestamp_secs
+            )));
+        }
+        let signer = self
+            .private_key_options
+            .extract_signer(self.encoding_options.encoding, &self.profile_options)?;
+        check_if_file_exists(&self.output_file, self.prompt_options)?;
+
+        prompt_yes_with_override(
+            &format!(
+                "Do you want to sign calling {} from {} with sequence number {} on chain {}, for a maximum of {} Octas?",
+                summary.function,
+                summary.sender,
+                summary.sequence_number,
+                summary.chain_id,
+                // The fee of an arbitrary payload file may not fit in a u64
+                summary.max_gas_amount as u128 * summary.gas_unit_price as u128
+            ),
+            self.prompt_options,
+        )?;
+        let signed_transaction = signer.sign_transaction(raw_transaction)?;
+        write_to_file(
+            &self.output_file,
+            "Signed transaction",
+            &bcs::to_bytes(&signed_transaction)?,
+        )?;
+        Ok(summary)
+    }
+}
+
+/// Submit a transaction signed by `aptos transaction sign`
+#[derive(Parser)]
+pub struct SubmitTransaction {
+    /// Signed transaction file written by `aptos transaction sign`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) signed: PathBuf,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let signed_transaction: SignedTransaction = read_bcs_file(&self.signed, "--signed")?;
+        signed_transaction
+            .clone()
+            .check_signature()
+            .map_err(|err| {
+                CliError::CommandArgumentError(format!(
+                    "The signature of {} is invalid: {}",
+                    self.signed.display(),
+                    err
+                ))
+            })?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let transaction = client
+            .submit_and_wait(&signed_transaction)
+            .await?
+            .into_inner();
+        Ok(TransactionSummary::from(&transaction))
+    }
+}
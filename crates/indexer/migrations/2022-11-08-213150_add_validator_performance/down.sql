@@ -0,0 +1,10 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS ev_pa_index;
+DROP INDEX IF EXISTS ev_tv_index;
+DROP TABLE IF EXISTS epoch_validators;
+DROP INDEX IF EXISTS bva_epoch_index;
+DROP TABLE IF EXISTS block_validator_activities;
+DROP INDEX IF EXISTS vr_pa_index;
+DROP TABLE IF EXISTS validator_rewards;
+DROP INDEX IF EXISTS vp_pa_index;
+DROP TABLE IF EXISTS validator_performances;
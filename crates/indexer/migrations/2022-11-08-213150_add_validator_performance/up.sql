@@ -0,0 +1,56 @@
+-- Your SQL goes here
+-- Active validators of every epoch, in the order of their index in the validator set. Block
+-- metadata refers to failed proposers and voters by these indices.
+CREATE TABLE epoch_validators (
+  epoch BIGINT NOT NULL,
+  validator_index BIGINT NOT NULL,
+  pool_address VARCHAR(66) NOT NULL,
+  voting_power NUMERIC NOT NULL,
+  -- version of the reconfiguration that started the epoch
+  transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  PRIMARY KEY (epoch, validator_index)
+);
+CREATE INDEX ev_pa_index ON epoch_validators (pool_address);
+CREATE INDEX ev_tv_index ON epoch_validators (transaction_version);
+-- Proposer, failed proposers and voters of every block
+CREATE TABLE block_validator_activities (
+  transaction_version BIGINT UNIQUE PRIMARY KEY NOT NULL,
+  epoch BIGINT NOT NULL,
+  round BIGINT NOT NULL,
+  proposer VARCHAR(66) NOT NULL,
+  failed_proposer_indices jsonb NOT NULL,
+  -- indices of the validators that voted for the previous block
+  voter_indices jsonb NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX bva_epoch_index ON block_validator_activities (epoch);
+-- Rewards distributed to each stake pool at the end of an epoch
+CREATE TABLE validator_rewards (
+  epoch BIGINT NOT NULL,
+  pool_address VARCHAR(66) NOT NULL,
+  rewards_amount NUMERIC NOT NULL,
+  transaction_version BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  PRIMARY KEY (epoch, pool_address)
+);
+CREATE INDEX vr_pa_index ON validator_rewards (pool_address);
+-- Performance of each validator over an epoch, aggregated once the epoch is over
+CREATE TABLE validator_performances (
+  epoch BIGINT NOT NULL,
+  pool_address VARCHAR(66) NOT NULL,
+  validator_index BIGINT NOT NULL,
+  voting_power NUMERIC NOT NULL,
+  successful_proposals BIGINT NOT NULL,
+  failed_proposals BIGINT NOT NULL,
+  votes BIGINT NOT NULL,
+  -- number of blocks in the epoch, i.e. the number of votes the validator could have cast
+  blocks BIGINT NOT NULL,
+  rewards_amount NUMERIC NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  -- Constraints
+  PRIMARY KEY (epoch, pool_address)
+);
+CREATE INDEX vp_pa_index ON validator_performances (pool_address);
@@ -82,3 +82,53 @@ pub static LATEST_PROCESSED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Latest version of the chain, as seen by a processor
+pub static HEAD_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_processor_head_version",
+        "Latest version of the chain, as seen by a processor",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of versions a processor is behind the head of the chain
+pub static PROCESSOR_LAG_VERSIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_processor_lag_versions",
+        "Number of versions a processor is behind the head of the chain",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of versions a processor has consumed per second since the last lag check
+pub static PROCESSING_RATE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_processor_versions_per_second",
+        "Number of versions a processor has consumed per second since the last lag check",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Whether the lag alert of a processor is firing (1) or not (0)
+pub static LAG_ALERT_FIRING: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "indexer_processor_lag_alert_firing",
+        "Whether the lag alert of a processor is firing (1) or not (0)",
+        &["processor_name"]
+    )
+    .unwrap()
+});
+
+/// Number of lag alerts posted to the webhook, by result
+pub static LAG_ALERT_WEBHOOK_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "indexer_processor_lag_alert_webhook_count",
+        "Number of lag alerts posted to the webhook, by result",
+        &["processor_name", "result"]
+    )
+    .unwrap()
+});
@@ -0,0 +1,276 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves the processed tables over GraphQL, so that consumers don't need direct access to
+//! postgres. The GraphQL schema is generated from the tables created by the processors'
+//! migrations: every exposed table gets a root query field that accepts hasura style `where`,
+//! `order_by`, `limit` and `offset` arguments.
+
+pub mod sql;
+
+use crate::{database::PgDbPool, validator_performance::epoch_performance_poem};
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use aptos_logger::info;
+use async_graphql::{
+    dynamic::{
+        Enum, EnumItem, Field, FieldFuture, FieldValue, InputObject, InputValue, Object,
+        ResolverContext, Scalar, Schema, TypeRef,
+    },
+    http::GraphiQLSource,
+    Value,
+};
+use async_graphql_poem::GraphQL;
+use poem::{get, listener::TcpListener, web::Html, EndpointExt, Route, Server};
+use sql::{load_page, load_table_schemas, ColumnType, Comparison, Filter, OrderBy, TableQuery};
+use std::sync::Arc;
+
+pub use sql::TableSchema;
+
+/// The tables exposed over GraphQL, as long as their processor created them
+pub const EXPOSED_TABLES: &[&str] = &[
+    "transactions",
+    "user_transactions",
+    "block_metadata_transactions",
+    "events",
+    "token_ownerships",
+    "current_token_ownerships",
+    "current_token_datas",
+    "current_collection_datas",
+    "token_activities",
+    "coin_balances",
+    "current_coin_balances",
+    "coin_activities",
+    "coin_infos",
+    "validator_performances",
+];
+
+/// The number of rows returned when a query doesn't set a limit
+pub const DEFAULT_PAGE_SIZE: u64 = 25;
+
+/// Settings shared by all resolvers
+struct QuerySettings {
+    max_page_size: u64,
+}
+
+fn comparison_exp_name(column_type: ColumnType) -> String {
+    format!("{}_comparison_exp", column_type.graphql_name())
+}
+
+fn bool_exp_name(table: &TableSchema) -> String {
+    format!("{}_bool_exp", table.name)
+}
+
+fn order_by_name(table: &TableSchema) -> String {
+    format!("{}_order_by", table.name)
+}
+
+/// Generates the GraphQL schema of the given tables.
+pub fn build_schema(
+    tables: Vec<TableSchema>,
+    pool: PgDbPool,
+    max_page_size: u64,
+) -> Result<Schema> {
+    let mut query = Object::new("query_root");
+    let mut builder_types = vec![];
+
+    for table in tables {
+        let table = Arc::new(table);
+
+        let mut row = Object::new(table.name.as_str());
+        let mut bool_exp = InputObject::new(bool_exp_name(&table));
+        let mut order_by = InputObject::new(order_by_name(&table));
+        for column in &table.columns {
+            let type_name = column.column_type.graphql_name();
+            let type_ref = if column.nullable {
+                TypeRef::named(type_name)
+            } else {
+                TypeRef::named_nn(type_name)
+            };
+            let column_name = column.name.clone();
+            row = row.field(Field::new(column.name.as_str(), type_ref, move |ctx| {
+                let column_name = column_name.clone();
+                FieldFuture::new(async move {
+                    let row = ctx.parent_value.try_downcast_ref::<serde_json::Value>()?;
+                    match row.get(&column_name) {
+                        None | Some(serde_json::Value::Null) => Ok(None),
+                        Some(value) => {
+                            Ok(Some(FieldValue::value(Value::from_json(value.clone())?)))
+                        }
+                    }
+                })
+            }));
+            bool_exp = bool_exp.field(InputValue::new(
+                column.name.as_str(),
+                TypeRef::named(comparison_exp_name(column.column_type)),
+            ));
+            order_by = order_by.field(InputValue::new(
+                column.name.as_str(),
+                TypeRef::named("order_by"),
+            ));
+        }
+
+        let resolver_table = table.clone();
+        query = query.field(
+            Field::new(
+                table.name.as_str(),
+                TypeRef::named_nn_list_nn(table.name.as_str()),
+                move |ctx| {
+                    let table = resolver_table.clone();
+                    FieldFuture::new(async move {
+                        let rows = resolve_table(&ctx, table).await?;
+                        Ok(Some(FieldValue::list(
+                            rows.into_iter().map(FieldValue::owned_any),
+                        )))
+                    })
+                },
+            )
+            .argument(InputValue::new(
+                "where",
+                TypeRef::named(bool_exp_name(&table)),
+            ))
+            .argument(InputValue::new(
+                "order_by",
+                TypeRef::named_nn_list(order_by_name(&table)),
+            ))
+            .argument(InputValue::new("limit", TypeRef::named(TypeRef::INT)))
+            .argument(InputValue::new("offset", TypeRef::named(TypeRef::INT))),
+        );
+        builder_types.push((row, bool_exp, order_by));
+    }
+
+    let mut builder = Schema::build(query.type_name(), None, None);
+    builder = builder.register(query);
+    for (row, bool_exp, order_by) in builder_types {
+        builder = builder.register(row).register(bool_exp).register(order_by);
+    }
+    for column_type in ColumnType::all() {
+        if !matches!(
+            column_type,
+            ColumnType::Boolean | ColumnType::Int | ColumnType::String
+        ) {
+            builder = builder.register(Scalar::new(column_type.graphql_name()));
+        }
+        let mut comparison_exp = InputObject::new(comparison_exp_name(column_type));
+        for (operator, comparison) in Comparison::all() {
+            let type_ref = match comparison {
+                Comparison::In => TypeRef::named_nn_list(column_type.graphql_name()),
+                Comparison::IsNull => TypeRef::named(TypeRef::BOOLEAN),
+                _ => TypeRef::named(column_type.graphql_name()),
+            };
+            comparison_exp = comparison_exp.field(InputValue::new(operator, type_ref));
+        }
+        builder = builder.register(comparison_exp);
+    }
+    builder = builder.register(
+        Enum::new("order_by")
+            .item(EnumItem::new("asc"))
+            .item(EnumItem::new("desc")),
+    );
+
+    builder
+        .data(pool)
+        .data(QuerySettings { max_page_size })
+        .finish()
+        .map_err(|e| anyhow!("Failed to build the GraphQL schema: {}", e))
+}
+
+/// Turns the arguments of a root query field into a `TableQuery` and runs it
+async fn resolve_table(
+    ctx: &ResolverContext<'_>,
+    table: Arc<TableSchema>,
+) -> async_graphql::Result<Vec<serde_json::Value>> {
+    let settings = ctx.data::<QuerySettings>()?;
+    let pool = ctx.data::<PgDbPool>()?.clone();
+
+    let mut filters = vec![];
+    if let Some(bool_exp) = ctx.args.get("where") {
+        for (column, comparison_exp) in bool_exp.object()?.iter() {
+            for (operator, value) in comparison_exp.object()?.iter() {
+                let comparison = Comparison::from_operator(operator.as_str())
+                    .ok_or_else(|| format!("Unknown operator {}", operator))?;
+                filters.push(Filter {
+                    column: column.to_string(),
+                    comparison,
+                    value: value.as_value().clone().into_json()?,
+                });
+            }
+        }
+    }
+    let mut order_by = vec![];
+    if let Some(orders) = ctx.args.get("order_by") {
+        for order in orders.list()?.iter() {
+            for (column, direction) in order.object()?.iter() {
+                order_by.push(OrderBy {
+                    column: column.to_string(),
+                    descending: direction.enum_name()? == "desc",
+                });
+            }
+        }
+    }
+    let limit = match ctx.args.get("limit") {
+        Some(limit) => limit.u64()?.min(settings.max_page_size),
+        None => DEFAULT_PAGE_SIZE.min(settings.max_page_size),
+    };
+    let offset = match ctx.args.get("offset") {
+        Some(offset) => offset.u64()?,
+        None => 0,
+    };
+    let query = TableQuery {
+        filters,
+        order_by,
+        limit,
+        offset,
+    };
+
+    // Diesel is blocking, so the query runs off the async runtime
+    let rows = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        load_page(&mut conn, &table, &query)
+    })
+    .await??;
+    Ok(rows)
+}
+
+/// Generates the GraphQL schema from the processed tables, and serves it at `/graphql` on the
+/// given address. The generated SDL is served at `/graphql/schema`, and a GraphiQL playground
+/// at `/`. The aggregated validator performance of an epoch is also served as JSON at
+/// `/validator_performance/:epoch`.
+pub async fn run_graphql_server(pool: PgDbPool, address: String, max_page_size: u64) -> Result<()> {
+    let tables = {
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = pool.get()?;
+            load_table_schemas(&mut conn, EXPOSED_TABLES)
+        })
+        .await??
+    };
+    let table_names: Vec<_> = tables.iter().map(|table| table.name.clone()).collect();
+    info!(
+        tables = table_names.join(","),
+        "Generated the GraphQL schema of the processed tables"
+    );
+
+    let schema = build_schema(tables, pool.clone(), max_page_size)?;
+    let sdl = schema.sdl();
+    let playground = GraphiQLSource::build().endpoint("/graphql").finish();
+    let app = Route::new()
+        .at(
+            "/",
+            get(poem::endpoint::make_sync(move |_| Html(playground.clone()))),
+        )
+        .at("/graphql", GraphQL::new(schema))
+        .at(
+            "/graphql/schema",
+            get(poem::endpoint::make_sync(move |_| sdl.clone())),
+        )
+        .at(
+            "/validator_performance/:epoch",
+            get(epoch_performance_poem).data(pool),
+        );
+
+    info!(address = address, "Starting the GraphQL server");
+    Server::new(TcpListener::bind(address.clone()))
+        .run(app)
+        .await
+        .with_context(|| format!("GraphQL server at {} failed", address))
+}
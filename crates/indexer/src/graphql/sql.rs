@@ -0,0 +1,478 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Describes the processed tables as found in postgres, and builds the parameterized SQL that
+//! answers a GraphQL query against one of them.
+
+#![allow(clippy::extra_unused_lifetimes)]
+
+use crate::database::PgPoolConnection;
+use anyhow::{bail, Result};
+use diesel::{
+    sql_types::{Array, Text},
+    QueryableByName, RunQueryDsl,
+};
+use std::collections::BTreeMap;
+
+/// The GraphQL scalar a postgres column is exposed as. Values are always bound as text and cast
+/// back to the postgres type in SQL, so filters don't depend on how GraphQL parsed them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColumnType {
+    Boolean,
+    Int,
+    BigInt,
+    Numeric,
+    String,
+    Timestamp,
+    Jsonb,
+}
+
+impl ColumnType {
+    /// Maps the `data_type` reported by `information_schema.columns`
+    pub fn from_pg_data_type(data_type: &str) -> Self {
+        match data_type {
+            "boolean" => ColumnType::Boolean,
+            "smallint" | "integer" => ColumnType::Int,
+            "bigint" => ColumnType::BigInt,
+            "numeric" => ColumnType::Numeric,
+            "timestamp without time zone" | "timestamp with time zone" => ColumnType::Timestamp,
+            "json" | "jsonb" => ColumnType::Jsonb,
+            _ => ColumnType::String,
+        }
+    }
+
+    /// The name of the GraphQL scalar, following the names hasura uses for the same types
+    pub fn graphql_name(&self) -> &'static str {
+        match self {
+            ColumnType::Boolean => "Boolean",
+            ColumnType::Int => "Int",
+            ColumnType::BigInt => "bigint",
+            ColumnType::Numeric => "numeric",
+            ColumnType::String => "String",
+            ColumnType::Timestamp => "timestamp",
+            ColumnType::Jsonb => "jsonb",
+        }
+    }
+
+    fn pg_cast(&self) -> &'static str {
+        match self {
+            ColumnType::Boolean => "boolean",
+            ColumnType::Int => "integer",
+            ColumnType::BigInt => "bigint",
+            ColumnType::Numeric => "numeric",
+            ColumnType::String => "text",
+            ColumnType::Timestamp => "timestamp",
+            ColumnType::Jsonb => "jsonb",
+        }
+    }
+
+    pub fn all() -> [ColumnType; 7] {
+        [
+            ColumnType::Boolean,
+            ColumnType::Int,
+            ColumnType::BigInt,
+            ColumnType::Numeric,
+            ColumnType::String,
+            ColumnType::Timestamp,
+            ColumnType::Jsonb,
+        ]
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ColumnSchema {
+    pub name: String,
+    pub column_type: ColumnType,
+    pub nullable: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableSchema {
+    pub name: String,
+    pub columns: Vec<ColumnSchema>,
+    /// Used as the default ordering, so that pages are stable
+    pub primary_key: Vec<String>,
+}
+
+impl TableSchema {
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+}
+
+#[derive(QueryableByName)]
+struct ColumnRow {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    #[diesel(sql_type = Text)]
+    column_name: String,
+    #[diesel(sql_type = Text)]
+    data_type: String,
+    #[diesel(sql_type = Text)]
+    is_nullable: String,
+}
+
+#[derive(QueryableByName)]
+struct PrimaryKeyRow {
+    #[diesel(sql_type = Text)]
+    table_name: String,
+    #[diesel(sql_type = Text)]
+    column_name: String,
+}
+
+/// Reads the columns and primary keys of the given tables from the database. Tables that don't
+/// exist (e.g., because their processor never ran its migrations) are left out.
+pub fn load_table_schemas(
+    conn: &mut PgPoolConnection,
+    table_names: &[&str],
+) -> Result<Vec<TableSchema>> {
+    let table_names: Vec<String> = table_names.iter().map(|name| name.to_string()).collect();
+    let column_rows: Vec<ColumnRow> = diesel::sql_query(
+        "SELECT table_name::text, column_name::text, data_type::text, is_nullable::text \
+         FROM information_schema.columns \
+         WHERE table_schema = current_schema() AND table_name = ANY($1) \
+         ORDER BY table_name, ordinal_position",
+    )
+    .bind::<Array<Text>, _>(&table_names)
+    .load(conn)?;
+    let primary_key_rows: Vec<PrimaryKeyRow> = diesel::sql_query(
+        "SELECT kcu.table_name::text, kcu.column_name::text \
+         FROM information_schema.table_constraints tc \
+         JOIN information_schema.key_column_usage kcu \
+           ON tc.constraint_name = kcu.constraint_name AND tc.table_schema = kcu.table_schema \
+         WHERE tc.constraint_type = 'PRIMARY KEY' AND tc.table_schema = current_schema() \
+           AND tc.table_name = ANY($1) \
+         ORDER BY kcu.table_name, kcu.ordinal_position",
+    )
+    .bind::<Array<Text>, _>(&table_names)
+    .load(conn)?;
+
+    let mut tables: BTreeMap<String, TableSchema> = BTreeMap::new();
+    for row in column_rows {
+        tables
+            .entry(row.table_name.clone())
+            .or_insert_with(|| TableSchema {
+                name: row.table_name.clone(),
+                columns: vec![],
+                primary_key: vec![],
+            })
+            .columns
+            .push(ColumnSchema {
+                name: row.column_name,
+                column_type: ColumnType::from_pg_data_type(&row.data_type),
+                nullable: row.is_nullable == "YES",
+            });
+    }
+    for row in primary_key_rows {
+        if let Some(table) = tables.get_mut(&row.table_name) {
+            table.primary_key.push(row.column_name);
+        }
+    }
+    Ok(tables.into_values().collect())
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    IsNull,
+}
+
+impl Comparison {
+    /// The operators of a column's comparison expression, named like hasura's
+    pub fn all() -> [(&'static str, Comparison); 8] {
+        [
+            ("_eq", Comparison::Eq),
+            ("_neq", Comparison::Neq),
+            ("_gt", Comparison::Gt),
+            ("_gte", Comparison::Gte),
+            ("_lt", Comparison::Lt),
+            ("_lte", Comparison::Lte),
+            ("_in", Comparison::In),
+            ("_is_null", Comparison::IsNull),
+        ]
+    }
+
+    pub fn from_operator(operator: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|(name, _)| *name == operator)
+            .map(|(_, comparison)| comparison)
+    }
+
+    fn sql_operator(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "=",
+            Comparison::Neq => "<>",
+            Comparison::Gt => ">",
+            Comparison::Gte => ">=",
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+            Comparison::In | Comparison::IsNull => unreachable!("Not a binary operator"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    pub column: String,
+    pub comparison: Comparison,
+    pub value: serde_json::Value,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OrderBy {
+    pub column: String,
+    pub descending: bool,
+}
+
+/// A page of rows of a table, matching all the filters
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableQuery {
+    pub filters: Vec<Filter>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: u64,
+    pub offset: u64,
+}
+
+/// Returns the text postgres should cast back to the column type
+fn bind_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+impl TableQuery {
+    /// Builds the SQL that returns the page as a JSON array, along with the values to bind as
+    /// text to its parameters. Every column is checked against the table schema.
+    pub fn to_sql(&self, table: &TableSchema) -> Result<(String, Vec<String>)> {
+        let mut binds = vec![];
+        let mut conditions = vec![];
+        for filter in &self.filters {
+            let column = match table.column(&filter.column) {
+                Some(column) => column,
+                None => bail!("Unknown column {} of {}", filter.column, table.name),
+            };
+            let name = quote_identifier(&column.name);
+            let cast = column.column_type.pg_cast();
+            let condition = match filter.comparison {
+                Comparison::IsNull => match filter.value.as_bool() {
+                    Some(true) => format!("{} IS NULL", name),
+                    Some(false) => format!("{} IS NOT NULL", name),
+                    None => bail!("_is_null of {} expects a boolean", column.name),
+                },
+                Comparison::In => {
+                    let values = match filter.value.as_array() {
+                        Some(values) => values,
+                        None => bail!("_in of {} expects a list", column.name),
+                    };
+                    if values.is_empty() {
+                        "FALSE".to_string()
+                    } else {
+                        let params: Vec<String> = values
+                            .iter()
+                            .map(|value| {
+                                binds.push(bind_value(value));
+                                format!("CAST(${} AS {})", binds.len(), cast)
+                            })
+                            .collect();
+                        format!("{} IN ({})", name, params.join(", "))
+                    }
+                }
+                comparison => {
+                    binds.push(bind_value(&filter.value));
+                    format!(
+                        "{} {} CAST(${} AS {})",
+                        name,
+                        comparison.sql_operator(),
+                        binds.len(),
+                        cast
+                    )
+                }
+            };
+            conditions.push(condition);
+        }
+
+        let order_by = if self.order_by.is_empty() {
+            table
+                .primary_key
+                .iter()
+                .map(|column| quote_identifier(column))
+                .collect::<Vec<_>>()
+        } else {
+            let mut order_by = vec![];
+            for order in &self.order_by {
+                if table.column(&order.column).is_none() {
+                    bail!("Unknown column {} of {}", order.column, table.name);
+                }
+                order_by.push(format!(
+                    "{} {}",
+                    quote_identifier(&order.column),
+                    if order.descending { "DESC" } else { "ASC" }
+                ));
+            }
+            order_by
+        };
+
+        // Numeric columns are returned as text, as JSON numbers would lose their precision
+        let columns: Vec<String> = table
+            .columns
+            .iter()
+            .map(|column| {
+                let name = quote_identifier(&column.name);
+                if column.column_type == ColumnType::Numeric {
+                    format!("{}::text AS {}", name, name)
+                } else {
+                    name
+                }
+            })
+            .collect();
+
+        let mut sql = format!(
+            "SELECT {} FROM {}",
+            columns.join(", "),
+            quote_identifier(&table.name)
+        );
+        if !conditions.is_empty() {
+            sql.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        if !order_by.is_empty() {
+            sql.push_str(&format!(" ORDER BY {}", order_by.join(", ")));
+        }
+        sql.push_str(&format!(" LIMIT {} OFFSET {}", self.limit, self.offset));
+        Ok((
+            format!(
+                "SELECT COALESCE(json_agg(page), '[]'::json)::text AS rows FROM ({}) page",
+                sql
+            ),
+            binds,
+        ))
+    }
+}
+
+#[derive(QueryableByName)]
+struct PageRow {
+    #[diesel(sql_type = Text)]
+    rows: String,
+}
+
+/// Runs the query and returns the matching rows as JSON objects keyed by column name
+pub fn load_page(
+    conn: &mut PgPoolConnection,
+    table: &TableSchema,
+    query: &TableQuery,
+) -> Result<Vec<serde_json::Value>> {
+    let (sql, binds) = query.to_sql(table)?;
+    let mut statement = diesel::sql_query(sql).into_boxed();
+    for bind in binds {
+        statement = statement.bind::<Text, _>(bind);
+    }
+    let page: PageRow = statement.get_result(conn)?;
+    Ok(serde_json::from_str(&page.rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn coin_balances() -> TableSchema {
+        TableSchema {
+            name: "current_coin_balances".to_string(),
+            columns: vec![
+                ColumnSchema {
+                    name: "owner_address".to_string(),
+                    column_type: ColumnType::String,
+                    nullable: false,
+                },
+                ColumnSchema {
+                    name: "amount".to_string(),
+                    column_type: ColumnType::Numeric,
+                    nullable: false,
+                },
+                ColumnSchema {
+                    name: "last_transaction_version".to_string(),
+                    column_type: ColumnType::BigInt,
+                    nullable: false,
+                },
+            ],
+            primary_key: vec!["owner_address".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_to_sql() {
+        let query = TableQuery {
+            filters: vec![
+                Filter {
+                    column: "owner_address".to_string(),
+                    comparison: Comparison::In,
+                    value: json!(["0x1", "0x2"]),
+                },
+                Filter {
+                    column: "last_transaction_version".to_string(),
+                    comparison: Comparison::Gte,
+                    value: json!(100),
+                },
+            ],
+            order_by: vec![OrderBy {
+                column: "amount".to_string(),
+                descending: true,
+            }],
+            limit: 10,
+            offset: 20,
+        };
+        let (sql, binds) = query.to_sql(&coin_balances()).unwrap();
+        assert_eq!(
+            sql,
+            "SELECT COALESCE(json_agg(page), '[]'::json)::text AS rows FROM (\
+             SELECT \"owner_address\", \"amount\"::text AS \"amount\", \"last_transaction_version\" \
+             FROM \"current_coin_balances\" \
+             WHERE \"owner_address\" IN (CAST($1 AS text), CAST($2 AS text)) \
+             AND \"last_transaction_version\" >= CAST($3 AS bigint) \
+             ORDER BY \"amount\" DESC LIMIT 10 OFFSET 20) page"
+        );
+        assert_eq!(binds, vec!["0x1", "0x2", "100"]);
+    }
+
+    #[test]
+    fn test_to_sql_defaults_to_primary_key_order() {
+        let query = TableQuery {
+            filters: vec![Filter {
+                column: "amount".to_string(),
+                comparison: Comparison::IsNull,
+                value: json!(false),
+            }],
+            order_by: vec![],
+            limit: 5,
+            offset: 0,
+        };
+        let (sql, binds) = query.to_sql(&coin_balances()).unwrap();
+        assert!(sql.contains("WHERE \"amount\" IS NOT NULL ORDER BY \"owner_address\" LIMIT 5"));
+        assert!(binds.is_empty());
+    }
+
+    #[test]
+    fn test_to_sql_rejects_unknown_columns() {
+        let query = TableQuery {
+            filters: vec![],
+            order_by: vec![OrderBy {
+                column: "amount; DROP TABLE events".to_string(),
+                descending: false,
+            }],
+            limit: 5,
+            offset: 0,
+        };
+        assert!(query.to_sql(&coin_balances()).is_err());
+    }
+}
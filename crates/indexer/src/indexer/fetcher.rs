@@ -3,7 +3,9 @@
 
 use crate::counters::{FETCHED_TRANSACTION, UNABLE_TO_FETCH_TRANSACTION};
 use aptos_api::Context;
-use aptos_api_types::{AsConverter, LedgerInfo, Transaction, TransactionOnChainData};
+use aptos_api_types::{
+    AsConverter, LedgerInfo, Transaction, TransactionBlockContext, TransactionOnChainData,
+};
 use aptos_logger::prelude::*;
 use aptos_storage_interface::state_view::DbStateView;
 use aptos_vm::data_cache::StorageAdapterOwned;
@@ -242,7 +244,7 @@ async fn fetch_nexts(
                 starting_version,
             )
         });
-    let mut timestamp = block_event.proposed_time();
+    let mut block = TransactionBlockContext::from(&block_event);
     let mut epoch = block_event.epoch();
     let mut epoch_bcs = aptos_api_types::U64::from(epoch);
     let mut block_height = block_event.height();
@@ -260,7 +262,7 @@ async fn fetch_nexts(
             if let aptos_types::transaction::Transaction::BlockMetadata(ref txn) =
                 raw_txn.transaction
             {
-                timestamp = txn.timestamp_usecs();
+                block = TransactionBlockContext::from(txn);
                 epoch = txn.epoch();
                 epoch_bcs = aptos_api_types::U64::from(epoch);
                 block_height += 1;
@@ -268,7 +270,7 @@ async fn fetch_nexts(
             }
         }
         match converter
-            .try_into_onchain_transaction(timestamp, raw_txn)
+            .try_into_onchain_transaction(block, raw_txn)
             .map(|mut txn| {
                 match txn {
                     Transaction::PendingTransaction(_) => {
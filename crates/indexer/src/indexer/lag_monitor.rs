@@ -0,0 +1,236 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters::{
+    HEAD_VERSION, LAG_ALERT_FIRING, LAG_ALERT_WEBHOOK_COUNT, PROCESSING_RATE,
+    PROCESSOR_LAG_VERSIONS,
+};
+use aptos_api::context::Context;
+use aptos_logger::{error, info, warn};
+use serde::Serialize;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+const LAG_ALERT_WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LagAlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// Posted to the lag alert webhook when a processor starts or stops lagging behind the chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LagAlert {
+    pub processor_name: String,
+    pub status: LagAlertStatus,
+    pub head_version: u64,
+    pub processed_version: u64,
+    pub lag_versions: u64,
+    pub threshold_versions: u64,
+    /// Versions processed per second since the previous lag check
+    pub versions_per_second: u64,
+}
+
+/// Tracks how far a processor is behind the head of the chain, and decides when its lag alert
+/// starts and stops firing.
+pub struct LagMonitor {
+    processor_name: String,
+    threshold_versions: Option<u64>,
+    firing: bool,
+    // (when, processed version) at the previous check
+    last_check: Option<(Instant, u64)>,
+}
+
+impl LagMonitor {
+    pub fn new(processor_name: String, threshold_versions: Option<u64>) -> Self {
+        Self {
+            processor_name,
+            threshold_versions,
+            firing: false,
+            last_check: None,
+        }
+    }
+
+    /// Records the lag at `now` and returns an alert if the alert started or stopped firing.
+    pub fn check(
+        &mut self,
+        now: Instant,
+        head_version: u64,
+        processed_version: u64,
+    ) -> Option<LagAlert> {
+        let lag_versions = head_version.saturating_sub(processed_version);
+        let versions_per_second = match self.last_check {
+            Some((last_time, last_version)) => {
+                let elapsed_millis = now.duration_since(last_time).as_millis() as u64;
+                if elapsed_millis == 0 {
+                    0
+                } else {
+                    processed_version.saturating_sub(last_version) * 1000 / elapsed_millis
+                }
+            }
+            None => 0,
+        };
+        self.last_check = Some((now, processed_version));
+
+        HEAD_VERSION
+            .with_label_values(&[&self.processor_name])
+            .set(head_version as i64);
+        PROCESSOR_LAG_VERSIONS
+            .with_label_values(&[&self.processor_name])
+            .set(lag_versions as i64);
+        PROCESSING_RATE
+            .with_label_values(&[&self.processor_name])
+            .set(versions_per_second as i64);
+
+        let threshold_versions = self.threshold_versions?;
+        let firing = lag_versions > threshold_versions;
+        if firing == self.firing {
+            return None;
+        }
+        self.firing = firing;
+        LAG_ALERT_FIRING
+            .with_label_values(&[&self.processor_name])
+            .set(firing as i64);
+
+        Some(LagAlert {
+            processor_name: self.processor_name.clone(),
+            status: if firing {
+                LagAlertStatus::Firing
+            } else {
+                LagAlertStatus::Resolved
+            },
+            head_version,
+            processed_version,
+            lag_versions,
+            threshold_versions,
+            versions_per_second,
+        })
+    }
+}
+
+/// Periodically compares the processed version against the head of the chain. Runs in its own
+/// task so that a processor which is stuck still gets reported.
+pub async fn run_lag_monitor(
+    context: Arc<Context>,
+    mut monitor: LagMonitor,
+    processed_version: Arc<AtomicU64>,
+    check_interval: Duration,
+    webhook_url: Option<String>,
+) {
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(check_interval);
+    loop {
+        interval.tick().await;
+        let head_version = match context.get_latest_ledger_info_wrapped() {
+            Ok(ledger_info) => ledger_info.version(),
+            Err(err) => {
+                warn!(
+                    processor_name = monitor.processor_name,
+                    error = format!("{:?}", err),
+                    "Failed to get the head of the chain for lag check"
+                );
+                continue;
+            }
+        };
+        let alert = match monitor.check(
+            Instant::now(),
+            head_version,
+            processed_version.load(Ordering::Relaxed),
+        ) {
+            Some(alert) => alert,
+            None => continue,
+        };
+
+        match alert.status {
+            LagAlertStatus::Firing => error!(
+                processor_name = alert.processor_name,
+                head_version = alert.head_version,
+                processed_version = alert.processed_version,
+                lag_versions = alert.lag_versions,
+                threshold_versions = alert.threshold_versions,
+                "Processor is lagging behind the chain!"
+            ),
+            LagAlertStatus::Resolved => info!(
+                processor_name = alert.processor_name,
+                head_version = alert.head_version,
+                processed_version = alert.processed_version,
+                lag_versions = alert.lag_versions,
+                "Processor caught up with the chain"
+            ),
+        }
+
+        if let Some(url) = &webhook_url {
+            let result = client
+                .post(url)
+                .timeout(Duration::from_secs(LAG_ALERT_WEBHOOK_TIMEOUT_SECS))
+                .json(&alert)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            let label = match result {
+                Ok(_) => "sent",
+                Err(err) => {
+                    warn!(
+                        processor_name = alert.processor_name,
+                        error = format!("{:?}", err),
+                        "Failed to post lag alert to webhook"
+                    );
+                    "failed"
+                }
+            };
+            LAG_ALERT_WEBHOOK_COUNT
+                .with_label_values(&[&alert.processor_name, label])
+                .inc();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lag_alert_transitions() {
+        let start = Instant::now();
+        let mut monitor = LagMonitor::new("test_processor".to_string(), Some(100));
+
+        // Within the threshold, nothing to report
+        assert_eq!(monitor.check(start, 150, 100), None);
+
+        // Crossing the threshold fires once
+        let alert = monitor
+            .check(start + Duration::from_secs(1), 400, 200)
+            .unwrap();
+        assert_eq!(alert.status, LagAlertStatus::Firing);
+        assert_eq!(alert.lag_versions, 200);
+        assert_eq!(alert.versions_per_second, 100);
+        assert_eq!(
+            monitor.check(start + Duration::from_secs(2), 500, 200),
+            None
+        );
+
+        // Catching up resolves it
+        let alert = monitor
+            .check(start + Duration::from_secs(3), 500, 450)
+            .unwrap();
+        assert_eq!(alert.status, LagAlertStatus::Resolved);
+        assert_eq!(alert.lag_versions, 50);
+        assert_eq!(
+            monitor.check(start + Duration::from_secs(4), 500, 500),
+            None
+        );
+    }
+
+    #[test]
+    fn test_no_threshold() {
+        let mut monitor = LagMonitor::new("test_processor".to_string(), None);
+        assert_eq!(monitor.check(Instant::now(), 1_000_000, 0), None);
+    }
+}
@@ -3,6 +3,7 @@
 
 pub mod errors;
 pub mod fetcher;
+pub mod lag_monitor;
 pub mod processing_result;
 pub mod tailer;
 pub mod transaction_processor;
@@ -772,6 +772,8 @@ mod test {
                 }
               ],
               "timestamp": "1649713141723410",
+              "block_round": "0",
+              "block_proposer": "0x0",
               "changes": [
                 {
                   "type": "write_resource",
@@ -896,7 +898,9 @@ mod test {
                 "signature": "0x9c1fef826ead87392f945bce527169b6627205a8d3bae77c5d8293c00b6e6a7657b4464b1fe2b36b89f5a2e64468ce7a04191d5fba431f1dc084f90292c9eb04"
               },
               "events": [],
-              "timestamp": "1651789018411640"
+              "timestamp": "1651789018411640",
+              "block_round": "0",
+              "block_proposer": "0x0"
             }
         )).unwrap();
 
@@ -13,12 +13,14 @@ extern crate diesel;
 
 pub mod counters;
 pub mod database;
+pub mod graphql;
 pub mod indexer;
 pub mod models;
 pub mod processors;
 pub mod runtime;
 pub mod schema;
 mod util;
+pub mod validator_performance;
 
 /// By default, skips test unless `INDEXER_DATABASE_URL` is set.
 /// In CI, will explode if `INDEXER_DATABASE_URL` is NOT set.
@@ -4,3 +4,4 @@
 pub mod proposal_votes;
 pub mod stake_utils;
 pub mod staking_pool_voter;
+pub mod validator_performance;
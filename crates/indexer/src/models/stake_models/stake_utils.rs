@@ -12,6 +12,32 @@ pub struct StakePoolResource {
     pub delegated_voter: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidatorConfig {
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub validator_index: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidatorInfo {
+    pub addr: String,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub voting_power: BigDecimal,
+    pub config: ValidatorConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidatorSetResource {
+    pub active_validators: Vec<ValidatorInfo>,
+}
+
+/// 0x1::reconfiguration::Configuration, which is only written when a new epoch starts
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigurationResource {
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub epoch: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GovernanceVoteEvent {
     #[serde(deserialize_with = "deserialize_from_string")]
@@ -23,20 +49,38 @@ pub struct GovernanceVoteEvent {
     pub should_pass: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DistributeRewardsEvent {
+    pub pool_address: String,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub rewards_amount: BigDecimal,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StakeResource {
     StakePool(StakePoolResource),
+    ValidatorSet(ValidatorSetResource),
+    Configuration(ConfigurationResource),
 }
 
 impl StakeResource {
     fn is_resource_supported(data_type: &str) -> bool {
-        matches!(data_type, "0x1::stake::StakePool")
+        matches!(
+            data_type,
+            "0x1::stake::StakePool"
+                | "0x1::stake::ValidatorSet"
+                | "0x1::reconfiguration::Configuration"
+        )
     }
 
     fn from_resource(data_type: &str, data: &serde_json::Value, txn_version: i64) -> Result<Self> {
         match data_type {
             "0x1::stake::StakePool" => serde_json::from_value(data.clone())
                 .map(|inner| Some(StakeResource::StakePool(inner))),
+            "0x1::stake::ValidatorSet" => serde_json::from_value(data.clone())
+                .map(|inner| Some(StakeResource::ValidatorSet(inner))),
+            "0x1::reconfiguration::Configuration" => serde_json::from_value(data.clone())
+                .map(|inner| Some(StakeResource::Configuration(inner))),
             _ => Ok(None),
         }
         .context(format!(
@@ -79,6 +123,7 @@ impl StakeResource {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StakeEvent {
     GovernanceVoteEvent(GovernanceVoteEvent),
+    DistributeRewardsEvent(DistributeRewardsEvent),
 }
 
 impl StakeEvent {
@@ -90,6 +135,8 @@ impl StakeEvent {
         match data_type {
             "0x1::aptos_governance::VoteEvent" => serde_json::from_value(data.clone())
                 .map(|inner| Some(StakeEvent::GovernanceVoteEvent(inner))),
+            "0x1::stake::DistributeRewardsEvent" => serde_json::from_value(data.clone())
+                .map(|inner| Some(StakeEvent::DistributeRewardsEvent(inner))),
             _ => Ok(None),
         }
         .context(format!(
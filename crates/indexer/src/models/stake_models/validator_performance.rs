@@ -0,0 +1,166 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+
+use super::stake_utils::{StakeEvent, StakeResource};
+use crate::{
+    schema::{block_validator_activities, epoch_validators, validator_rewards},
+    util::standardize_address,
+};
+use aptos_api_types::{
+    Event as APIEvent, Transaction as APITransaction, WriteSetChange as APIWriteSetChange,
+};
+use aptos_bitvec::BitVec;
+use bigdecimal::BigDecimal;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(epoch, validator_index))]
+#[diesel(table_name = epoch_validators)]
+pub struct EpochValidator {
+    pub epoch: i64,
+    pub validator_index: i64,
+    pub pool_address: String,
+    pub voting_power: BigDecimal,
+    pub transaction_version: i64,
+}
+
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version))]
+#[diesel(table_name = block_validator_activities)]
+pub struct BlockValidatorActivity {
+    pub transaction_version: i64,
+    pub epoch: i64,
+    pub round: i64,
+    pub proposer: String,
+    pub failed_proposer_indices: serde_json::Value,
+    pub voter_indices: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(epoch, pool_address))]
+#[diesel(table_name = validator_rewards)]
+pub struct ValidatorReward {
+    pub epoch: i64,
+    pub pool_address: String,
+    pub rewards_amount: BigDecimal,
+    pub transaction_version: i64,
+}
+
+/// Returns the version, changes and events of the transactions that can start a new epoch
+fn reconfiguration_candidate(
+    transaction: &APITransaction,
+) -> Option<(i64, &Vec<APIWriteSetChange>, &Vec<APIEvent>)> {
+    match transaction {
+        APITransaction::UserTransaction(txn) => {
+            Some((txn.info.version.0 as i64, &txn.info.changes, &txn.events))
+        }
+        APITransaction::GenesisTransaction(txn) => {
+            Some((txn.info.version.0 as i64, &txn.info.changes, &txn.events))
+        }
+        APITransaction::BlockMetadataTransaction(txn) => {
+            Some((txn.info.version.0 as i64, &txn.info.changes, &txn.events))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the epoch started by the transaction, if it's a reconfiguration
+fn new_epoch(changes: &[APIWriteSetChange], txn_version: i64) -> anyhow::Result<Option<u64>> {
+    for wsc in changes {
+        if let APIWriteSetChange::WriteResource(write_resource) = wsc {
+            if let Some(StakeResource::Configuration(inner)) =
+                StakeResource::from_write_resource(write_resource, txn_version)?
+            {
+                return Ok(Some(inner.epoch));
+            }
+        }
+    }
+    Ok(None)
+}
+
+impl EpochValidator {
+    /// The active validators of the epoch started by the transaction, if any.
+    pub fn from_transaction(transaction: &APITransaction) -> anyhow::Result<Vec<Self>> {
+        let (txn_version, changes, _) = match reconfiguration_candidate(transaction) {
+            Some(candidate) => candidate,
+            None => return Ok(vec![]),
+        };
+        let epoch = match new_epoch(changes, txn_version)? {
+            Some(epoch) => epoch,
+            None => return Ok(vec![]),
+        };
+        let mut epoch_validators = vec![];
+        for wsc in changes {
+            if let APIWriteSetChange::WriteResource(write_resource) = wsc {
+                if let Some(StakeResource::ValidatorSet(inner)) =
+                    StakeResource::from_write_resource(write_resource, txn_version)?
+                {
+                    for validator in inner.active_validators {
+                        epoch_validators.push(Self {
+                            epoch: epoch as i64,
+                            validator_index: validator.config.validator_index as i64,
+                            pool_address: standardize_address(&validator.addr),
+                            voting_power: validator.voting_power,
+                            transaction_version: txn_version,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(epoch_validators)
+    }
+}
+
+impl BlockValidatorActivity {
+    pub fn from_transaction(transaction: &APITransaction) -> Option<Self> {
+        if let APITransaction::BlockMetadataTransaction(txn) = transaction {
+            let votes = BitVec::from(txn.previous_block_votes_bitvec.clone());
+            let voter_indices: Vec<usize> = votes.iter_ones().collect();
+            Some(Self {
+                transaction_version: txn.info.version.0 as i64,
+                epoch: txn.epoch.0 as i64,
+                round: txn.round.0 as i64,
+                proposer: standardize_address(&txn.proposer.inner().to_hex_literal()),
+                failed_proposer_indices: serde_json::to_value(&txn.failed_proposer_indices)
+                    .unwrap(),
+                voter_indices: serde_json::to_value(&voter_indices).unwrap(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl ValidatorReward {
+    /// Rewards are distributed by the reconfiguration that ends the epoch they were earned in.
+    pub fn from_transaction(transaction: &APITransaction) -> anyhow::Result<Vec<Self>> {
+        let (txn_version, changes, events) = match reconfiguration_candidate(transaction) {
+            Some(candidate) => candidate,
+            None => return Ok(vec![]),
+        };
+        let epoch = match new_epoch(changes, txn_version)? {
+            // Genesis starts the first epoch without distributing anything
+            Some(epoch) if epoch > 0 => epoch - 1,
+            _ => return Ok(vec![]),
+        };
+        let mut rewards = vec![];
+        for event in events {
+            let event_type = event.typ.to_string();
+            if let Some(StakeEvent::DistributeRewardsEvent(ev)) =
+                StakeEvent::from_event(event_type.as_str(), &event.data, txn_version)?
+            {
+                rewards.push(Self {
+                    epoch: epoch as i64,
+                    pool_address: standardize_address(&ev.pool_address),
+                    rewards_amount: ev.rewards_amount,
+                    transaction_version: txn_version,
+                });
+            }
+        }
+        Ok(rewards)
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
     models::stake_models::{
         proposal_votes::ProposalVote,
         staking_pool_voter::{CurrentStakingPoolVoter, StakingPoolVoterMap},
+        validator_performance::{BlockValidatorActivity, EpochValidator, ValidatorReward},
     },
     schema,
 };
@@ -47,9 +48,15 @@ fn insert_to_db_impl(
     conn: &mut PgConnection,
     current_stake_pool_voters: &[CurrentStakingPoolVoter],
     proposal_votes: &[ProposalVote],
+    epoch_validators: &[EpochValidator],
+    block_validator_activities: &[BlockValidatorActivity],
+    validator_rewards: &[ValidatorReward],
 ) -> Result<(), diesel::result::Error> {
     insert_current_stake_pool_voter(conn, current_stake_pool_voters)?;
     insert_proposal_votes(conn, proposal_votes)?;
+    insert_epoch_validators(conn, epoch_validators)?;
+    insert_block_validator_activities(conn, block_validator_activities)?;
+    insert_validator_rewards(conn, validator_rewards)?;
     Ok(())
 }
 
@@ -60,6 +67,9 @@ fn insert_to_db(
     end_version: u64,
     current_stake_pool_voters: Vec<CurrentStakingPoolVoter>,
     proposal_votes: Vec<ProposalVote>,
+    epoch_validators: Vec<EpochValidator>,
+    block_validator_activities: Vec<BlockValidatorActivity>,
+    validator_rewards: Vec<ValidatorReward>,
 ) -> Result<(), diesel::result::Error> {
     aptos_logger::trace!(
         name = name,
@@ -71,7 +81,14 @@ fn insert_to_db(
         .build_transaction()
         .read_write()
         .run::<_, Error, _>(|pg_conn| {
-            insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
+            insert_to_db_impl(
+                pg_conn,
+                &current_stake_pool_voters,
+                &proposal_votes,
+                &epoch_validators,
+                &block_validator_activities,
+                &validator_rewards,
+            )
         }) {
         Ok(_) => Ok(()),
         Err(_) => conn
@@ -80,8 +97,19 @@ fn insert_to_db(
             .run::<_, Error, _>(|pg_conn| {
                 let current_stake_pool_voters = clean_data_for_db(current_stake_pool_voters, true);
                 let proposal_votes = clean_data_for_db(proposal_votes, true);
+                let epoch_validators = clean_data_for_db(epoch_validators, true);
+                let block_validator_activities =
+                    clean_data_for_db(block_validator_activities, true);
+                let validator_rewards = clean_data_for_db(validator_rewards, true);
 
-                insert_to_db_impl(pg_conn, &current_stake_pool_voters, &proposal_votes)
+                insert_to_db_impl(
+                    pg_conn,
+                    &current_stake_pool_voters,
+                    &proposal_votes,
+                    &epoch_validators,
+                    &block_validator_activities,
+                    &validator_rewards,
+                )
             }),
     }
 }
@@ -134,6 +162,66 @@ fn insert_proposal_votes(
     Ok(())
 }
 
+fn insert_epoch_validators(
+    conn: &mut PgConnection,
+    item_to_insert: &[EpochValidator],
+) -> Result<(), diesel::result::Error> {
+    use schema::epoch_validators::dsl::*;
+
+    let chunks = get_chunks(item_to_insert.len(), EpochValidator::field_count());
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::epoch_validators::table)
+                .values(&item_to_insert[start_ind..end_ind])
+                .on_conflict((epoch, validator_index))
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_block_validator_activities(
+    conn: &mut PgConnection,
+    item_to_insert: &[BlockValidatorActivity],
+) -> Result<(), diesel::result::Error> {
+    use schema::block_validator_activities::dsl::*;
+
+    let chunks = get_chunks(item_to_insert.len(), BlockValidatorActivity::field_count());
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::block_validator_activities::table)
+                .values(&item_to_insert[start_ind..end_ind])
+                .on_conflict(transaction_version)
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_validator_rewards(
+    conn: &mut PgConnection,
+    item_to_insert: &[ValidatorReward],
+) -> Result<(), diesel::result::Error> {
+    use schema::validator_rewards::dsl::*;
+
+    let chunks = get_chunks(item_to_insert.len(), ValidatorReward::field_count());
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::validator_rewards::table)
+                .values(&item_to_insert[start_ind..end_ind])
+                .on_conflict((epoch, pool_address))
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl TransactionProcessor for StakeTransactionProcessor {
     fn name(&self) -> &'static str {
@@ -148,12 +236,22 @@ impl TransactionProcessor for StakeTransactionProcessor {
     ) -> Result<ProcessingResult, TransactionProcessingError> {
         let mut all_current_stake_pool_voters: StakingPoolVoterMap = HashMap::new();
         let mut all_proposal_votes = vec![];
+        let mut all_epoch_validators = vec![];
+        let mut all_block_validator_activities = vec![];
+        let mut all_validator_rewards = vec![];
 
         for txn in &transactions {
             let current_stake_pool_voter = CurrentStakingPoolVoter::from_transaction(txn).unwrap();
             all_current_stake_pool_voters.extend(current_stake_pool_voter);
             let mut proposal_votes = ProposalVote::from_transaction(txn).unwrap();
             all_proposal_votes.append(&mut proposal_votes);
+            let mut epoch_validators = EpochValidator::from_transaction(txn).unwrap();
+            all_epoch_validators.append(&mut epoch_validators);
+            if let Some(activity) = BlockValidatorActivity::from_transaction(txn) {
+                all_block_validator_activities.push(activity);
+            }
+            let mut validator_rewards = ValidatorReward::from_transaction(txn).unwrap();
+            all_validator_rewards.append(&mut validator_rewards);
         }
         let mut all_current_stake_pool_voters = all_current_stake_pool_voters
             .into_values()
@@ -171,6 +269,9 @@ impl TransactionProcessor for StakeTransactionProcessor {
             end_version,
             all_current_stake_pool_voters,
             all_proposal_votes,
+            all_epoch_validators,
+            all_block_validator_activities,
+            all_validator_rewards,
         );
         match tx_result {
             Ok(_) => Ok(ProcessingResult::new(
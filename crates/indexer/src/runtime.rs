@@ -3,8 +3,12 @@
 
 use crate::{
     database::new_db_pool,
+    graphql::run_graphql_server,
     indexer::{
-        fetcher::TransactionFetcherOptions, processing_result::ProcessingResult, tailer::Tailer,
+        fetcher::TransactionFetcherOptions,
+        lag_monitor::{run_lag_monitor, LagMonitor},
+        processing_result::ProcessingResult,
+        tailer::Tailer,
         transaction_processor::TransactionProcessor,
     },
     processors::{
@@ -12,6 +16,7 @@ use crate::{
         stake_processor::StakeTransactionProcessor, token_processor::TokenTransactionProcessor,
         Processor,
     },
+    validator_performance::{run_aggregation_job, AGGREGATION_INTERVAL},
 };
 
 use aptos_api::context::Context;
@@ -21,7 +26,9 @@ use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
 use aptos_types::chain_id::ChainId;
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::runtime::{Builder, Runtime};
 
 pub struct MovingAverage {
@@ -114,6 +121,7 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
     let emit_every = config.emit_every.unwrap();
     let batch_size = config.batch_size.unwrap();
     let lookback_versions = config.gap_lookback_versions.unwrap() as i64;
+    let lag_check_interval_secs = config.lag_check_interval_secs.unwrap();
 
     info!(processor_name = processor_name, "Starting indexer...");
 
@@ -146,7 +154,7 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
     let options =
         TransactionFetcherOptions::new(None, None, Some(batch_size), None, fetch_tasks as usize);
 
-    let tailer = Tailer::new(context, conn_pool.clone(), processor, options)
+    let tailer = Tailer::new(context.clone(), conn_pool.clone(), processor, options)
         .expect("Failed to instantiate tailer");
 
     if !skip_migrations {
@@ -154,6 +162,22 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
         tailer.run_migrations();
     }
 
+    // The GraphQL schema is generated from the tables, so this has to start after the migrations
+    if let Some(graphql_address) = config.graphql_address.clone() {
+        let max_page_size = config.graphql_max_page_size.unwrap();
+        let pool = conn_pool.clone();
+        tokio::spawn(async move {
+            if let Err(err) = run_graphql_server(pool, graphql_address, max_page_size).await {
+                error!(error = ?err, "GraphQL server stopped");
+            }
+        });
+    }
+
+    // Validator performance is aggregated from the tables of the stake processor
+    if matches!(processor_enum, Processor::StakeProcessor) {
+        tokio::spawn(run_aggregation_job(conn_pool.clone(), AGGREGATION_INTERVAL));
+    }
+
     info!(
         processor_name = processor_name,
         lookback_versions = lookback_versions,
@@ -193,6 +217,15 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
         "Indexing loop started!"
     );
 
+    let processed_version = Arc::new(AtomicU64::new(start_version));
+    tokio::spawn(run_lag_monitor(
+        context,
+        LagMonitor::new(processor_name.clone(), config.lag_alert_threshold_versions),
+        processed_version.clone(),
+        Duration::from_secs(lag_check_interval_secs),
+        config.lag_alert_webhook_url.clone(),
+    ));
+
     let mut versions_processed: u64 = 0;
     let mut base: u64 = 0;
 
@@ -260,6 +293,7 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
                 panic!("Failed to update last processed version: {:?}", e);
             });
 
+        processed_version.fetch_max(batch_end_version, Ordering::Relaxed);
         ma.tick_now(num_res);
 
         versions_processed += num_res;
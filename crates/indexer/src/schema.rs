@@ -18,6 +18,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    block_validator_activities (transaction_version) {
+        transaction_version -> Int8,
+        epoch -> Int8,
+        round -> Int8,
+        proposer -> Varchar,
+        failed_proposer_indices -> Jsonb,
+        voter_indices -> Jsonb,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     coin_activities (transaction_version, event_account_address, event_creation_number, event_sequence_number) {
         transaction_version -> Int8,
@@ -211,6 +223,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    epoch_validators (epoch, validator_index) {
+        epoch -> Int8,
+        validator_index -> Int8,
+        pool_address -> Varchar,
+        voting_power -> Numeric,
+        transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     events (account_address, creation_number, sequence_number) {
         sequence_number -> Int8,
@@ -466,6 +489,31 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    validator_performances (epoch, pool_address) {
+        epoch -> Int8,
+        pool_address -> Varchar,
+        validator_index -> Int8,
+        voting_power -> Numeric,
+        successful_proposals -> Int8,
+        failed_proposals -> Int8,
+        votes -> Int8,
+        blocks -> Int8,
+        rewards_amount -> Numeric,
+        inserted_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    validator_rewards (epoch, pool_address) {
+        epoch -> Int8,
+        pool_address -> Varchar,
+        rewards_amount -> Numeric,
+        transaction_version -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     write_set_changes (transaction_version, index) {
         transaction_version -> Int8,
@@ -481,6 +529,7 @@ diesel::table! {
 
 diesel::allow_tables_to_appear_in_same_query!(
     block_metadata_transactions,
+    block_validator_activities,
     coin_activities,
     coin_balances,
     coin_infos,
@@ -493,6 +542,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     current_token_datas,
     current_token_ownerships,
     current_token_pending_claims,
+    epoch_validators,
     events,
     indexer_status,
     ledger_infos,
@@ -510,5 +560,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     tokens,
     transactions,
     user_transactions,
+    validator_performances,
+    validator_rewards,
     write_set_changes,
 );
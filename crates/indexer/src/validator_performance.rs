@@ -0,0 +1,286 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Aggregates the performance of every validator over each epoch, from the tables written by the
+//! stake processor: proposals that succeeded or failed, votes cast for the blocks of the epoch,
+//! and rewards distributed at its end. Epochs are aggregated into `validator_performances` once
+//! the stake processor has processed them entirely, and are served over REST for validator
+//! performance dashboards. The epoch in progress is aggregated on demand.
+
+use crate::{
+    database::{PgDbPool, PgPoolConnection},
+    models::processor_status::ProcessorStatusV2Query,
+    processors::stake_processor::NAME as STAKE_PROCESSOR_NAME,
+};
+use anyhow::Result;
+use aptos_logger::{error, info};
+use bigdecimal::BigDecimal;
+use diesel::{
+    sql_types::{BigInt, Numeric, Text},
+    QueryableByName, RunQueryDsl,
+};
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Data, Json, Path},
+};
+use serde::Serialize;
+use std::time::Duration;
+
+/// How often finished epochs are aggregated
+pub const AGGREGATION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Computes the performance of the validators that were active during epoch $1. Votes are those
+/// carried by the blocks of the epoch, i.e., the votes for their parent blocks.
+const PERFORMANCE_QUERY: &str = "
+    SELECT
+        v.pool_address,
+        v.validator_index,
+        v.voting_power,
+        COALESCE(p.successful_proposals, 0) AS successful_proposals,
+        COALESCE(f.failed_proposals, 0) AS failed_proposals,
+        COALESCE(vo.votes, 0) AS votes,
+        b.blocks,
+        COALESCE(r.rewards_amount, 0) AS rewards_amount
+    FROM epoch_validators v
+    CROSS JOIN (
+        SELECT COUNT(*) AS blocks FROM block_validator_activities WHERE epoch = $1
+    ) b
+    LEFT JOIN (
+        SELECT proposer, COUNT(*) AS successful_proposals
+        FROM block_validator_activities
+        WHERE epoch = $1
+        GROUP BY proposer
+    ) p ON p.proposer = v.pool_address
+    LEFT JOIN (
+        SELECT failed::BIGINT AS validator_index, COUNT(*) AS failed_proposals
+        FROM block_validator_activities,
+            jsonb_array_elements_text(failed_proposer_indices) AS failed
+        WHERE epoch = $1
+        GROUP BY 1
+    ) f ON f.validator_index = v.validator_index
+    LEFT JOIN (
+        SELECT voter::BIGINT AS validator_index, COUNT(*) AS votes
+        FROM block_validator_activities,
+            jsonb_array_elements_text(voter_indices) AS voter
+        WHERE epoch = $1
+        GROUP BY 1
+    ) vo ON vo.validator_index = v.validator_index
+    LEFT JOIN validator_rewards r ON r.epoch = v.epoch AND r.pool_address = v.pool_address
+    WHERE v.epoch = $1
+    ORDER BY v.validator_index";
+
+/// An epoch is over once the next one started. It can be aggregated once the stake processor
+/// processed the reconfiguration that started the next epoch ($1 is its last processed version).
+const FINISHED_EPOCHS_QUERY: &str = "
+    SELECT DISTINCT ended.epoch
+    FROM epoch_validators ended
+    JOIN epoch_validators started ON started.epoch = ended.epoch + 1
+    WHERE started.transaction_version <= $1
+        AND NOT EXISTS (SELECT 1 FROM validator_performances p WHERE p.epoch = ended.epoch)
+    ORDER BY ended.epoch";
+
+#[derive(Debug, QueryableByName)]
+struct EpochRow {
+    #[diesel(sql_type = BigInt)]
+    epoch: i64,
+}
+
+/// The performance of a validator over an epoch
+#[derive(Clone, Debug, QueryableByName, Serialize)]
+pub struct ValidatorPerformance {
+    #[diesel(sql_type = Text)]
+    pub pool_address: String,
+    #[diesel(sql_type = BigInt)]
+    pub validator_index: i64,
+    #[diesel(sql_type = Numeric)]
+    pub voting_power: BigDecimal,
+    #[diesel(sql_type = BigInt)]
+    pub successful_proposals: i64,
+    #[diesel(sql_type = BigInt)]
+    pub failed_proposals: i64,
+    #[diesel(sql_type = BigInt)]
+    pub votes: i64,
+    /// The number of blocks of the epoch, i.e., the number of votes the validator could cast
+    #[diesel(sql_type = BigInt)]
+    pub blocks: i64,
+    #[diesel(sql_type = Numeric)]
+    pub rewards_amount: BigDecimal,
+}
+
+impl ValidatorPerformance {
+    /// The share of the proposals of the validator that succeeded
+    pub fn proposal_success_rate(&self) -> Option<f64> {
+        let proposals = self.successful_proposals + self.failed_proposals;
+        if proposals == 0 {
+            return None;
+        }
+        Some(self.successful_proposals as f64 / proposals as f64)
+    }
+
+    /// The share of the blocks of the epoch the validator voted for
+    pub fn vote_participation(&self) -> Option<f64> {
+        if self.blocks == 0 {
+            return None;
+        }
+        Some(self.votes as f64 / self.blocks as f64)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ValidatorPerformanceView {
+    #[serde(flatten)]
+    pub performance: ValidatorPerformance,
+    pub proposal_success_rate: Option<f64>,
+    pub vote_participation: Option<f64>,
+}
+
+impl From<ValidatorPerformance> for ValidatorPerformanceView {
+    fn from(performance: ValidatorPerformance) -> Self {
+        Self {
+            proposal_success_rate: performance.proposal_success_rate(),
+            vote_participation: performance.vote_participation(),
+            performance,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct EpochPerformance {
+    pub epoch: u64,
+    /// Whether the epoch is over. The performance of the epoch in progress keeps changing.
+    pub finalized: bool,
+    pub validators: Vec<ValidatorPerformanceView>,
+}
+
+/// Aggregates the epochs that are over and haven't been aggregated yet, and returns them.
+pub fn aggregate_finished_epochs(conn: &mut PgPoolConnection) -> Result<Vec<i64>> {
+    let processed_version =
+        match ProcessorStatusV2Query::get_by_processor(&STAKE_PROCESSOR_NAME.to_string(), conn)? {
+            Some(status) => status.last_success_version,
+            None => return Ok(vec![]),
+        };
+    let epochs: Vec<EpochRow> = diesel::sql_query(FINISHED_EPOCHS_QUERY)
+        .bind::<BigInt, _>(processed_version)
+        .load(conn)?;
+
+    let mut aggregated = vec![];
+    for EpochRow { epoch } in epochs {
+        diesel::sql_query(format!(
+            "INSERT INTO validator_performances (
+                pool_address, validator_index, voting_power, successful_proposals,
+                failed_proposals, votes, blocks, rewards_amount, epoch
+            )
+            SELECT performance.*, $1 FROM ({}) performance
+            ON CONFLICT (epoch, pool_address) DO NOTHING",
+            PERFORMANCE_QUERY
+        ))
+        .bind::<BigInt, _>(epoch)
+        .execute(conn)?;
+        aggregated.push(epoch);
+    }
+    Ok(aggregated)
+}
+
+/// Aggregates finished epochs every `interval`.
+pub async fn run_aggregation_job(pool: PgDbPool, interval: Duration) {
+    loop {
+        let job_pool = pool.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut conn = job_pool.get()?;
+            aggregate_finished_epochs(&mut conn)
+        })
+        .await;
+        match result {
+            Ok(Ok(epochs)) if !epochs.is_empty() => {
+                info!(epochs = ?epochs, "Aggregated the validator performance of epochs");
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => error!(error = ?err, "Failed to aggregate validator performance"),
+            Err(err) => error!(error = ?err, "Validator performance aggregation panicked"),
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Returns the performance of the validators over the epoch, or None if the epoch is unknown.
+pub fn load_epoch_performance(
+    conn: &mut PgPoolConnection,
+    epoch: u64,
+) -> Result<Option<EpochPerformance>> {
+    let finalized: Vec<ValidatorPerformance> = diesel::sql_query(
+        "SELECT pool_address, validator_index, voting_power, successful_proposals,
+            failed_proposals, votes, blocks, rewards_amount
+        FROM validator_performances
+        WHERE epoch = $1
+        ORDER BY validator_index",
+    )
+    .bind::<BigInt, _>(epoch as i64)
+    .load(conn)?;
+    let (finalized, validators) = if finalized.is_empty() {
+        let in_progress: Vec<ValidatorPerformance> = diesel::sql_query(PERFORMANCE_QUERY)
+            .bind::<BigInt, _>(epoch as i64)
+            .load(conn)?;
+        (false, in_progress)
+    } else {
+        (true, finalized)
+    };
+    if validators.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(EpochPerformance {
+        epoch,
+        finalized,
+        validators: validators.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[handler]
+pub async fn epoch_performance_poem(
+    pool: Data<&PgDbPool>,
+    Path(epoch): Path<u64>,
+) -> poem::Result<Json<EpochPerformance>> {
+    let pool = pool.0.clone();
+    let performance = tokio::task::spawn_blocking(move || {
+        let mut conn = pool.get()?;
+        load_epoch_performance(&mut conn, epoch)
+    })
+    .await
+    .map_err(|err| poem::Error::from_string(err.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?
+    .map_err(|err| poem::Error::from_string(err.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+    performance.map(Json).ok_or_else(|| {
+        poem::Error::from_string(
+            format!("No validator performance for epoch {}", epoch),
+            StatusCode::NOT_FOUND,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn performance(successful_proposals: i64, failed_proposals: i64) -> ValidatorPerformance {
+        ValidatorPerformance {
+            pool_address: "0x1".to_string(),
+            validator_index: 0,
+            voting_power: BigDecimal::from(100),
+            successful_proposals,
+            failed_proposals,
+            votes: 90,
+            blocks: 120,
+            rewards_amount: BigDecimal::from(0),
+        }
+    }
+
+    #[test]
+    fn test_rates() {
+        let view = ValidatorPerformanceView::from(performance(3, 1));
+        assert_eq!(view.proposal_success_rate, Some(0.75));
+        assert_eq!(view.vote_participation, Some(0.75));
+
+        // A validator that was never elected as proposer has no success rate
+        let view = ValidatorPerformanceView::from(performance(0, 0));
+        assert_eq!(view.proposal_success_rate, None);
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{gather_metrics, json_encoder::JsonEncoder, NUM_METRICS};
 use aptos_build_info::build_information;
 use aptos_config::config::NodeConfig;
+use aptos_peer_monitoring_service_client::node_information::upgrade_readiness_report;
 use hyper::{
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, Server, StatusCode,
@@ -77,6 +78,15 @@ fn get_metrics(fams: Vec<MetricFamily>) -> HashMap<String, String> {
     all_metrics
 }
 
+/// Parses the gas feature version from the query of an upgrade readiness request
+fn get_gas_feature_version(req: &Request<Body>) -> Option<u64> {
+    req.uri().query()?.split('&').find_map(|parameter| {
+        parameter
+            .strip_prefix("gas_feature_version=")
+            .and_then(|version| version.parse().ok())
+    })
+}
+
 pub fn get_all_metrics() -> HashMap<String, String> {
     let all_metric_families = gather_metrics();
     get_metrics(all_metric_families)
@@ -131,6 +141,62 @@ async fn serve_requests(
                 *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
             }
         }
+        // Expose the postmortem records of recent consensus rounds
+        (&Method::GET, "/consensus_round_postmortems") => {
+            if node_config
+                .inspection_service
+                .expose_consensus_round_postmortems
+            {
+                let postmortems = aptos_consensus::round_postmortem::recent_round_postmortems();
+                let encoded_postmortems = serde_json::to_string(&postmortems).unwrap();
+                *resp.body_mut() = Body::from(encoded_postmortems);
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
+        // Expose the backtraces of all the threads of the node
+        (&Method::GET, "/thread_dump") => {
+            if node_config.inspection_service.expose_thread_dump {
+                // Capturing the dump blocks until every thread has been interrupted
+                let thread_dump =
+                    tokio::task::spawn_blocking(aptos_runtimes::thread_dump::formatted_thread_dump)
+                        .await
+                        .unwrap();
+                *resp.body_mut() = Body::from(thread_dump);
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
+        // Expose the names, poll counts and states of the tracked async tasks
+        (&Method::GET, "/task_dump") => {
+            if node_config.inspection_service.expose_task_dump {
+                let task_dump = aptos_runtimes::task_dump::task_dump();
+                let encoded_task_dump = serde_json::to_string(&task_dump).unwrap();
+                *resp.body_mut() = Body::from(encoded_task_dump);
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
+        // Expose the fraction of stake on builds supporting the given gas feature version
+        (&Method::GET, "/upgrade_readiness") => {
+            if node_config.inspection_service.expose_upgrade_readiness {
+                match get_gas_feature_version(&req) {
+                    Some(gas_feature_version) => {
+                        let report = upgrade_readiness_report(gas_feature_version);
+                        let encoded_report = serde_json::to_string(&report).unwrap();
+                        *resp.body_mut() = Body::from(encoded_report);
+                    }
+                    None => {
+                        *resp.status_mut() = StatusCode::BAD_REQUEST;
+                        *resp.body_mut() = Body::from(
+                            "A gas feature version is required, e.g. ?gas_feature_version=6",
+                        );
+                    }
+                }
+            } else {
+                *resp.body_mut() = Body::from(DISABLED_ENDPOINT_MESSAGE);
+            }
+        }
         _ => {
             *resp.status_mut() = StatusCode::NOT_FOUND;
         }
@@ -75,11 +75,15 @@ pub struct ClusterArgs {
     pub coin_source_args: CoinSourceArgs,
 }
 
-#[derive(Debug, Clone, Copy, ArgEnum, Deserialize, Parser, Serialize)]
+#[derive(Debug, Clone, Copy, ArgEnum, Deserialize, Eq, Hash, Parser, PartialEq, Serialize)]
 pub enum TransactionType {
     P2P,
     AccountGeneration,
     NftMintAndTransfer,
+    /// Publishes, then upgrades, a package at the address of the sender
+    PublishPackage,
+    /// Runs a script with a large argument
+    LargeScript,
 }
 
 impl Default for TransactionType {
@@ -88,6 +92,40 @@ impl Default for TransactionType {
     }
 }
 
+/// Named mixes of transactions, to emit a known workload without listing its transaction types
+#[derive(Debug, Clone, Copy, ArgEnum, Deserialize, Eq, PartialEq, Serialize)]
+pub enum WorkloadProfile {
+    CoinTransfer,
+    AccountCreation,
+    NftMintAndTransfer,
+    ModulePublish,
+    LargeScript,
+    /// All of the above, weighted like the traffic of a live network
+    Mixed,
+}
+
+impl WorkloadProfile {
+    /// The transaction types of the profile, along with their percentage of the transactions
+    pub fn transaction_mix(self) -> Vec<(TransactionType, usize)> {
+        match self {
+            WorkloadProfile::CoinTransfer => vec![(TransactionType::P2P, 100)],
+            WorkloadProfile::AccountCreation => vec![(TransactionType::AccountGeneration, 100)],
+            WorkloadProfile::NftMintAndTransfer => {
+                vec![(TransactionType::NftMintAndTransfer, 100)]
+            }
+            WorkloadProfile::ModulePublish => vec![(TransactionType::PublishPackage, 100)],
+            WorkloadProfile::LargeScript => vec![(TransactionType::LargeScript, 100)],
+            WorkloadProfile::Mixed => vec![
+                (TransactionType::P2P, 60),
+                (TransactionType::AccountGeneration, 15),
+                (TransactionType::NftMintAndTransfer, 15),
+                (TransactionType::PublishPackage, 5),
+                (TransactionType::LargeScript, 5),
+            ],
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Parser, Serialize)]
 #[clap(group(
     ArgGroup::new("mode")
@@ -128,6 +166,10 @@ pub struct EmitArgs {
     #[clap(long, min_values = 0)]
     pub transaction_type_weights: Vec<usize>,
 
+    /// Named mix of transactions to emit, instead of --transaction-type
+    #[clap(long, arg_enum, ignore_case = true)]
+    pub workload_profile: Option<WorkloadProfile>,
+
     #[clap(long)]
     pub expected_max_txns: Option<u64>,
 
@@ -154,3 +196,20 @@ fn parse_target(target: &str) -> Result<Url> {
     }
     Ok(url)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workload_profiles_are_percentages() {
+        for profile in WorkloadProfile::value_variants() {
+            let total: usize = profile
+                .transaction_mix()
+                .iter()
+                .map(|(_, percentage)| percentage)
+                .sum();
+            assert_eq!(total, 100, "{:?}", profile);
+        }
+    }
+}
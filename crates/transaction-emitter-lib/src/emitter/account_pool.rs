@@ -0,0 +1,154 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::emitter::query_sequence_numbers;
+use anyhow::{Context, Result};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_logger::info;
+use aptos_rest_client::Client as RestClient;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{AccountKey, LocalAccount},
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, path::Path};
+
+/// Number of accounts whose sequence number is fetched in a single batch when loading a pool
+const LOAD_BATCH_SIZE: usize = 100;
+const LOAD_PARALLELISM: usize = 10;
+
+/// Accounts that were created and funded by the emitter, kept to be reused by the following
+/// jobs instead of creating new accounts for each of them.
+///
+/// Accounts are handed out in rotation: the accounts returned by a job go to the back of the
+/// pool, so that consecutive jobs use different accounts when the pool is larger than a job.
+/// The keys can be saved to a file and loaded back, to reuse the accounts across the phases of
+/// a test that each create their own emitter.
+#[derive(Debug, Default)]
+pub struct AccountPool {
+    accounts: VecDeque<LocalAccount>,
+}
+
+/// The persisted form of an account in the pool
+#[derive(Deserialize, Serialize)]
+struct PooledAccount {
+    address: AccountAddress,
+    private_key: String,
+}
+
+impl AccountPool {
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Adds accounts at the back of the pool
+    pub fn add(&mut self, accounts: Vec<LocalAccount>) {
+        self.accounts.extend(accounts);
+    }
+
+    /// Takes up to `count` accounts from the front of the pool
+    pub fn take(&mut self, count: usize) -> Vec<LocalAccount> {
+        let count = count.min(self.accounts.len());
+        self.accounts.drain(..count).collect()
+    }
+
+    pub fn take_one(&mut self) -> Option<LocalAccount> {
+        self.accounts.pop_front()
+    }
+
+    pub fn clear(&mut self) {
+        self.accounts.clear();
+    }
+
+    /// Saves the keys of the accounts in the pool. Sequence numbers aren't saved, they're
+    /// fetched from the chain when the pool is loaded.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                Ok(PooledAccount {
+                    address: account.address(),
+                    private_key: account.private_key().to_encoded_string()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        std::fs::write(path, serde_json::to_vec(&accounts)?)
+            .with_context(|| format!("Failed to save account pool to {}", path.display()))?;
+        info!("Saved {} accounts to {}", accounts.len(), path.display());
+        Ok(())
+    }
+
+    /// Loads the accounts saved to a file, with their current sequence numbers. Returns an empty
+    /// pool if the file doesn't exist.
+    pub async fn load(path: &Path, client: &RestClient) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read account pool from {}", path.display()))?;
+        let pooled_accounts: Vec<PooledAccount> = serde_json::from_slice(&bytes)?;
+
+        let batches = pooled_accounts
+            .chunks(LOAD_BATCH_SIZE)
+            .map(|batch| async move {
+                let addresses: Vec<_> = batch.iter().map(|account| account.address).collect();
+                let (sequence_numbers, _) =
+                    query_sequence_numbers(client, addresses.iter()).await?;
+                batch
+                    .iter()
+                    .zip(sequence_numbers)
+                    .map(|(account, sequence_number)| {
+                        let private_key =
+                            Ed25519PrivateKey::from_encoded_string(&account.private_key)?;
+                        Ok(LocalAccount::new(
+                            account.address,
+                            AccountKey::from_private_key(private_key),
+                            sequence_number,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            });
+        let accounts: Vec<Vec<LocalAccount>> = stream::iter(batches)
+            .buffered(LOAD_PARALLELISM)
+            .try_collect()
+            .await?;
+
+        let accounts: VecDeque<_> = accounts.into_iter().flatten().collect();
+        info!("Loaded {} accounts from {}", accounts.len(), path.display());
+        Ok(Self { accounts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_accounts_rotate() {
+        let mut rng = StdRng::from_seed([0; 32]);
+        let accounts: Vec<_> = (0..4).map(|_| LocalAccount::generate(&mut rng)).collect();
+        let addresses: Vec<_> = accounts.iter().map(|account| account.address()).collect();
+
+        let mut pool = AccountPool::default();
+        pool.add(accounts);
+        let first_job = pool.take(2);
+        pool.add(first_job);
+        let second_job = pool.take(2);
+        assert_eq!(
+            second_job
+                .iter()
+                .map(|account| account.address())
+                .collect::<Vec<_>>(),
+            addresses[2..].to_vec()
+        );
+        assert_eq!(pool.take(10).len(), 2);
+        assert!(pool.is_empty());
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod account_minter;
+pub mod account_pool;
 pub mod stats;
 pub mod submission_worker;
 
@@ -17,13 +18,14 @@ use aptos_sdk::{
     types::{transaction::SignedTransaction, LocalAccount},
 };
 use futures::future::{try_join_all, FutureExt};
-use itertools::zip;
+use itertools::{zip, Itertools};
 use once_cell::sync::Lazy;
 use rand::seq::IteratorRandom;
 use rand_core::SeedableRng;
 use std::{
     cmp::{max, min},
     collections::{HashMap, HashSet},
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -33,16 +35,18 @@ use std::{
 use tokio::{runtime::Handle, task::JoinHandle, time};
 
 use crate::{
-    args::TransactionType,
+    args::{TransactionType, WorkloadProfile},
     emitter::{
         account_minter::AccountMinter,
+        account_pool::AccountPool,
         stats::{DynamicStatsTracking, TxnStats},
         submission_worker::SubmissionWorker,
     },
     transaction_generator::{
-        account_generator::AccountGeneratorCreator,
+        account_generator::AccountGeneratorCreator, large_script::LargeScriptGeneratorCreator,
         nft_mint_and_transfer::NFTMintAndTransferGeneratorCreator,
         p2p_transaction_generator::P2PTransactionGeneratorCreator,
+        publish_package::PublishPackageGeneratorCreator,
         transaction_mix_generator::TxnMixGeneratorCreator, TransactionGeneratorCreator,
     },
 };
@@ -115,9 +119,12 @@ pub struct EmitJobRequest {
     gas_price: u64,
     invalid_transaction_ratio: usize,
     reuse_accounts: bool,
+    account_pool_file: Option<PathBuf>,
     mint_to_root: bool,
 
     transaction_mix: Vec<(TransactionType, usize)>,
+    /// Overrides `transaction_mix` with a mix for each phase of the jobs, if not empty
+    phase_transaction_mixes: Vec<Vec<(TransactionType, usize)>>,
 
     add_created_accounts_to_pool: bool,
     max_account_working_set: usize,
@@ -138,8 +145,10 @@ impl Default for EmitJobRequest {
             gas_price: aptos_global_constants::GAS_UNIT_PRICE,
             invalid_transaction_ratio: 0,
             reuse_accounts: false,
+            account_pool_file: None,
             mint_to_root: false,
             transaction_mix: vec![(TransactionType::P2P, 1)],
+            phase_transaction_mixes: Vec::new(),
             add_created_accounts_to_pool: true,
             max_account_working_set: 1_000_000,
             txn_expiration_time_secs: 60,
@@ -195,6 +204,29 @@ impl EmitJobRequest {
         self
     }
 
+    pub fn workload_profile(self, workload_profile: WorkloadProfile) -> Self {
+        self.transaction_mix(workload_profile.transaction_mix())
+    }
+
+    /// Emits a different mix of transactions in each phase of the jobs, e.g., to change the
+    /// workload as the network is upgraded. The last mix is kept for the following phases.
+    pub fn phase_transaction_mixes(
+        mut self,
+        phase_transaction_mixes: Vec<Vec<(TransactionType, usize)>>,
+    ) -> Self {
+        self.phase_transaction_mixes = phase_transaction_mixes;
+        self
+    }
+
+    pub fn phase_workload_profiles(self, workload_profiles: Vec<WorkloadProfile>) -> Self {
+        self.phase_transaction_mixes(
+            workload_profiles
+                .into_iter()
+                .map(WorkloadProfile::transaction_mix)
+                .collect(),
+        )
+    }
+
     pub fn mode(mut self, mode: EmitJobMode) -> Self {
         self.mode = mode;
         self
@@ -205,6 +237,17 @@ impl EmitJobRequest {
         self
     }
 
+    /// Keeps the accounts of the emitter in a file, so that they're reused by the following
+    /// emitters (e.g. of the next test phase) instead of creating new ones
+    pub fn account_pool_file(mut self, account_pool_file: PathBuf) -> Self {
+        self.account_pool_file = Some(account_pool_file);
+        self
+    }
+
+    pub fn get_account_pool_file(&self) -> Option<&PathBuf> {
+        self.account_pool_file.as_ref()
+    }
+
     pub fn add_created_accounts_to_pool(mut self, add_created_accounts_to_pool: bool) -> Self {
         self.add_created_accounts_to_pool = add_created_accounts_to_pool;
         self
@@ -220,6 +263,12 @@ impl EmitJobRequest {
         self
     }
 
+    /// The number of accounts a job of this request uses
+    pub fn num_accounts(&self) -> usize {
+        let mode_params = self.calculate_mode_params();
+        self.rest_clients.len() * mode_params.workers_per_endpoint * mode_params.accounts_per_worker
+    }
+
     pub fn calculate_mode_params(&self) -> EmitModeParams {
         let clients_count = self.rest_clients.len();
 
@@ -348,6 +397,7 @@ pub struct EmitJob {
     workers: Vec<Worker>,
     stop: Arc<AtomicBool>,
     stats: Arc<DynamicStatsTracking>,
+    account_pool_file: Option<PathBuf>,
 }
 
 impl EmitJob {
@@ -362,7 +412,7 @@ impl EmitJob {
 
 #[derive(Debug)]
 pub struct TxnEmitter {
-    accounts: Vec<LocalAccount>,
+    account_pool: AccountPool,
     txn_factory: TransactionFactory,
     rng: StdRng,
 }
@@ -370,18 +420,67 @@ pub struct TxnEmitter {
 impl TxnEmitter {
     pub fn new(transaction_factory: TransactionFactory, rng: StdRng) -> Self {
         Self {
-            accounts: vec![],
+            account_pool: AccountPool::default(),
             txn_factory: transaction_factory,
             rng,
         }
     }
 
     pub fn take_account(&mut self) -> LocalAccount {
-        self.accounts.remove(0)
+        self.account_pool
+            .take_one()
+            .expect("No account left in the pool")
     }
 
     pub fn clear(&mut self) {
-        self.accounts.clear();
+        self.account_pool.clear();
+    }
+
+    /// Creates and funds accounts ahead of the jobs, so that the accounts creation isn't
+    /// measured as part of a job. The accounts are funded for the expected transactions of the
+    /// request, and saved to its account pool file if set.
+    pub async fn pregenerate_accounts(
+        &mut self,
+        root_account: &mut LocalAccount,
+        req: &EmitJobRequest,
+        num_accounts: usize,
+    ) -> Result<()> {
+        self.load_account_pool(req).await?;
+        let num_new_accounts = num_accounts.saturating_sub(self.account_pool.len());
+        if num_new_accounts > 0 {
+            let mode_params = req.calculate_mode_params();
+            let txn_factory = self
+                .txn_factory
+                .clone()
+                .with_transaction_expiration_time(mode_params.txn_expiration_time_secs)
+                .with_gas_unit_price(req.gas_price);
+            let mut account_minter =
+                AccountMinter::new(root_account, txn_factory, self.rng.clone());
+            let new_accounts = account_minter
+                .create_accounts(req, &mode_params, num_new_accounts)
+                .await?;
+            self.account_pool.add(new_accounts);
+        }
+        info!("Account pool has {} accounts", self.account_pool.len());
+        self.save_account_pool(req.account_pool_file.as_ref())
+    }
+
+    /// Loads the accounts persisted by a previous emitter, unless accounts were already loaded
+    async fn load_account_pool(&mut self, req: &EmitJobRequest) -> Result<()> {
+        if let Some(account_pool_file) = &req.account_pool_file {
+            if self.account_pool.is_empty() {
+                self.account_pool =
+                    AccountPool::load(account_pool_file, &req.rest_clients[0]).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn save_account_pool(&self, account_pool_file: Option<&PathBuf>) -> Result<()> {
+        match account_pool_file {
+            Some(account_pool_file) => self.account_pool.save(account_pool_file),
+            None => Ok(()),
+        }
     }
 
     pub fn rng(&mut self) -> &mut StdRng {
@@ -415,13 +514,19 @@ impl TxnEmitter {
             .with_transaction_expiration_time(mode_params.txn_expiration_time_secs)
             .with_gas_unit_price(req.gas_price);
 
-        let mut account_minter =
-            AccountMinter::new(root_account, txn_factory.clone(), self.rng.clone());
-        let mut new_accounts = account_minter
-            .create_accounts(&req, &mode_params, num_accounts)
-            .await?;
-        self.accounts.append(&mut new_accounts);
-        let all_accounts = self.accounts.split_off(self.accounts.len() - num_accounts);
+        // Reuse the accounts of the previous jobs, and only create the missing ones
+        self.load_account_pool(&req).await?;
+        let mut all_accounts = self.account_pool.take(num_accounts);
+        if all_accounts.len() < num_accounts {
+            let mut account_minter =
+                AccountMinter::new(root_account, txn_factory.clone(), self.rng.clone());
+            let mut new_accounts = account_minter
+                .create_accounts(&req, &mode_params, num_accounts - all_accounts.len())
+                .await?;
+            all_accounts.append(&mut new_accounts);
+        } else {
+            info!("Reusing {} accounts from the account pool", num_accounts);
+        }
         let all_addresses: Vec<_> = all_accounts.iter().map(|d| d.address()).collect();
         let all_addresses = Arc::new(RwLock::new(all_addresses));
         let mut all_accounts = all_accounts.into_iter();
@@ -429,43 +534,87 @@ impl TxnEmitter {
         let stats = Arc::new(DynamicStatsTracking::new(stats_tracking_phases));
         let tokio_handle = Handle::current();
 
-        let mut txn_generator_creator_mix: Vec<(Box<dyn TransactionGeneratorCreator>, usize)> =
-            Vec::new();
-        for (transaction_type, weight) in req.transaction_mix {
-            let txn_generator_creator: Box<dyn TransactionGeneratorCreator> = match transaction_type
-            {
-                TransactionType::P2P => Box::new(P2PTransactionGeneratorCreator::new(
-                    self.from_rng(),
-                    txn_factory.clone(),
-                    SEND_AMOUNT,
-                    all_addresses.clone(),
-                    req.invalid_transaction_ratio,
-                    req.gas_price,
-                )),
-                TransactionType::AccountGeneration => Box::new(AccountGeneratorCreator::new(
-                    txn_factory.clone(),
-                    all_addresses.clone(),
-                    req.add_created_accounts_to_pool,
-                    req.max_account_working_set,
-                    req.gas_price,
-                )),
-                TransactionType::NftMintAndTransfer => Box::new(
-                    NFTMintAndTransferGeneratorCreator::new(
+        let phase_transaction_mixes = if req.phase_transaction_mixes.is_empty() {
+            vec![req.transaction_mix]
+        } else {
+            req.phase_transaction_mixes
+        };
+        let transaction_types: Vec<TransactionType> = phase_transaction_mixes
+            .iter()
+            .flatten()
+            .map(|(transaction_type, _)| *transaction_type)
+            .unique()
+            .collect();
+        // The weight of each transaction type in each phase
+        let phase_weights: Vec<Vec<usize>> = phase_transaction_mixes
+            .iter()
+            .map(|transaction_mix| {
+                transaction_types
+                    .iter()
+                    .map(|transaction_type| {
+                        transaction_mix
+                            .iter()
+                            .filter(|(t, _)| t == transaction_type)
+                            .map(|(_, weight)| weight)
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+        ensure!(
+            phase_weights
+                .iter()
+                .all(|weights| weights.iter().sum::<usize>() > 0),
+            "The transaction mix of each phase needs a positive weight"
+        );
+
+        let mut txn_generator_creators: Vec<Box<dyn TransactionGeneratorCreator>> = Vec::new();
+        for transaction_type in transaction_types {
+            let txn_generator_creator: Box<dyn TransactionGeneratorCreator> =
+                match transaction_type {
+                    TransactionType::P2P => Box::new(P2PTransactionGeneratorCreator::new(
                         self.from_rng(),
                         txn_factory.clone(),
-                        root_account,
-                        req.rest_clients[0].clone(),
-                    )
-                    .await,
-                ),
-            };
-            txn_generator_creator_mix.push((txn_generator_creator, weight));
+                        SEND_AMOUNT,
+                        all_addresses.clone(),
+                        req.invalid_transaction_ratio,
+                        req.gas_price,
+                    )),
+                    TransactionType::AccountGeneration => Box::new(AccountGeneratorCreator::new(
+                        txn_factory.clone(),
+                        all_addresses.clone(),
+                        req.add_created_accounts_to_pool,
+                        req.max_account_working_set,
+                        req.gas_price,
+                    )),
+                    TransactionType::NftMintAndTransfer => Box::new(
+                        NFTMintAndTransferGeneratorCreator::new(
+                            self.from_rng(),
+                            txn_factory.clone(),
+                            root_account,
+                            req.rest_clients[0].clone(),
+                        )
+                        .await,
+                    ),
+                    TransactionType::PublishPackage => Box::new(
+                        PublishPackageGeneratorCreator::new(txn_factory.clone(), req.gas_price),
+                    ),
+                    TransactionType::LargeScript => Box::new(LargeScriptGeneratorCreator::new(
+                        txn_factory.clone(),
+                        req.gas_price,
+                    )),
+                };
+            txn_generator_creators.push(txn_generator_creator);
         }
         let txn_generator_creator: Box<dyn TransactionGeneratorCreator> =
-            if txn_generator_creator_mix.len() > 1 {
-                Box::new(TxnMixGeneratorCreator::new(txn_generator_creator_mix))
+            if txn_generator_creators.len() > 1 {
+                Box::new(TxnMixGeneratorCreator::new(
+                    txn_generator_creators,
+                    phase_weights,
+                    stats.clone(),
+                ))
             } else {
-                txn_generator_creator_mix.into_iter().next().unwrap().0
+                txn_generator_creators.into_iter().next().unwrap()
             };
 
         let total_workers = req.rest_clients.len() * workers_per_endpoint;
@@ -515,17 +664,21 @@ impl TxnEmitter {
             workers,
             stop,
             stats,
+            account_pool_file: req.account_pool_file,
         })
     }
 
     pub async fn stop_job(&mut self, job: EmitJob) -> Vec<TxnStats> {
         job.stop.store(true, Ordering::Relaxed);
         for worker in job.workers {
-            let mut accounts = worker
+            let accounts = worker
                 .join_handle
                 .await
                 .expect("TxnEmitter worker thread failed");
-            self.accounts.append(&mut accounts);
+            self.account_pool.add(accounts);
+        }
+        if let Err(e) = self.save_account_pool(job.account_pool_file.as_ref()) {
+            warn!("Failed to save the account pool: {:?}", e);
         }
 
         job.stats.accumulate()
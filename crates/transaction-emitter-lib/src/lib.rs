@@ -11,7 +11,7 @@ mod transaction_generator;
 mod wrappers;
 
 // These are the top level things you should need to run the emitter.
-pub use args::{ClusterArgs, CoinSourceArgs, EmitArgs, TransactionType};
+pub use args::{ClusterArgs, CoinSourceArgs, EmitArgs, TransactionType, WorkloadProfile};
 pub use wrappers::emit_transactions;
 
 // We export these if you want finer grained control.
@@ -0,0 +1,106 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Script, SignedTransaction, TransactionArgument, TransactionPayload},
+        LocalAccount,
+    },
+};
+use async_trait::async_trait;
+use move_binary_format::file_format::{empty_script, Signature, SignatureIndex, SignatureToken};
+
+/// Size of the argument of the scripts, which keeps the transactions well under the maximum
+/// transaction size
+const LARGE_SCRIPT_ARGUMENT_SIZE: usize = 32 * 1024;
+
+/// Runs a script doing nothing with a large `vector<u8>` argument, to load the network with big
+/// transactions that are cheap to execute
+pub struct LargeScriptGenerator {
+    txn_factory: TransactionFactory,
+    code: Vec<u8>,
+    gas_price: u64,
+}
+
+impl LargeScriptGenerator {
+    pub fn new(txn_factory: TransactionFactory, code: Vec<u8>, gas_price: u64) -> Self {
+        Self {
+            txn_factory,
+            code,
+            gas_price,
+        }
+    }
+
+    fn gen_single_txn(&self, account: &mut LocalAccount) -> SignedTransaction {
+        account.sign_with_transaction_builder(
+            self.txn_factory
+                .payload(TransactionPayload::Script(Script::new(
+                    self.code.clone(),
+                    vec![],
+                    vec![TransactionArgument::U8Vector(vec![
+                        0u8;
+                        LARGE_SCRIPT_ARGUMENT_SIZE
+                    ])],
+                )))
+                .gas_unit_price(self.gas_price),
+        )
+    }
+}
+
+/// Returns the code of a script whose `main` takes a `vector<u8>` and returns
+fn script_code() -> Vec<u8> {
+    let mut script = empty_script();
+    script
+        .signatures
+        .push(Signature(vec![SignatureToken::Vector(Box::new(
+            SignatureToken::U8,
+        ))]));
+    script.parameters = SignatureIndex((script.signatures.len() - 1) as u16);
+    let mut code = vec![];
+    script.serialize(&mut code).expect("Script must serialize");
+    code
+}
+
+impl TransactionGenerator for LargeScriptGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            for _ in 0..transactions_per_account {
+                requests.push(self.gen_single_txn(account));
+            }
+        }
+        requests
+    }
+}
+
+pub struct LargeScriptGeneratorCreator {
+    txn_factory: TransactionFactory,
+    code: Vec<u8>,
+    gas_price: u64,
+}
+
+impl LargeScriptGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, gas_price: u64) -> Self {
+        Self {
+            txn_factory,
+            code: script_code(),
+            gas_price,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for LargeScriptGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(LargeScriptGenerator::new(
+            self.txn_factory.clone(),
+            self.code.clone(),
+            self.gas_price,
+        ))
+    }
+}
@@ -5,8 +5,10 @@ use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
 use async_trait::async_trait;
 
 pub mod account_generator;
+pub mod large_script;
 pub mod nft_mint_and_transfer;
 pub mod p2p_transaction_generator;
+pub mod publish_package;
 pub mod transaction_mix_generator;
 
 pub trait TransactionGenerator: Sync + Send {
@@ -0,0 +1,106 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_framework::natives::code::{ModuleMetadata, MoveOption, PackageMetadata, UpgradePolicy};
+use aptos_sdk::{
+    move_types::{account_address::AccountAddress, identifier::Identifier},
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use async_trait::async_trait;
+use move_binary_format::file_format::empty_module;
+
+const PACKAGE_NAME: &str = "EmitterWorkload";
+const MODULE_NAME: &str = "emitter_workload";
+
+/// Publishes an empty package at the address of each account. The first transaction of an
+/// account publishes the package, and the following ones upgrade it.
+pub struct PublishPackageGenerator {
+    txn_factory: TransactionFactory,
+    gas_price: u64,
+}
+
+impl PublishPackageGenerator {
+    pub fn new(txn_factory: TransactionFactory, gas_price: u64) -> Self {
+        Self {
+            txn_factory,
+            gas_price,
+        }
+    }
+
+    fn gen_single_txn(&self, account: &mut LocalAccount) -> SignedTransaction {
+        let metadata = PackageMetadata {
+            name: PACKAGE_NAME.to_string(),
+            upgrade_policy: UpgradePolicy::compat(),
+            upgrade_number: 0,
+            source_digest: String::new(),
+            manifest: vec![],
+            modules: vec![ModuleMetadata {
+                name: MODULE_NAME.to_string(),
+                source: vec![],
+                source_map: vec![],
+                extension: MoveOption::none(),
+            }],
+            deps: vec![],
+            extension: MoveOption::none(),
+        };
+        account.sign_with_transaction_builder(
+            self.txn_factory
+                .payload(aptos_stdlib::code_publish_package_txn(
+                    bcs::to_bytes(&metadata).expect("PackageMetadata must serialize"),
+                    vec![module_code(account.address())],
+                ))
+                .gas_unit_price(self.gas_price),
+        )
+    }
+}
+
+/// Returns the code of an empty module published at `address`
+fn module_code(address: AccountAddress) -> Vec<u8> {
+    let mut module = empty_module();
+    module.address_identifiers[0] = address;
+    module.identifiers[0] = Identifier::new(MODULE_NAME).unwrap();
+    let mut code = vec![];
+    module.serialize(&mut code).expect("Module must serialize");
+    code
+}
+
+impl TransactionGenerator for PublishPackageGenerator {
+    fn generate_transactions(
+        &mut self,
+        accounts: Vec<&mut LocalAccount>,
+        transactions_per_account: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(accounts.len() * transactions_per_account);
+        for account in accounts {
+            for _ in 0..transactions_per_account {
+                requests.push(self.gen_single_txn(account));
+            }
+        }
+        requests
+    }
+}
+
+pub struct PublishPackageGeneratorCreator {
+    txn_factory: TransactionFactory,
+    gas_price: u64,
+}
+
+impl PublishPackageGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, gas_price: u64) -> Self {
+        Self {
+            txn_factory,
+            gas_price,
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionGeneratorCreator for PublishPackageGeneratorCreator {
+    async fn create_transaction_generator(&self) -> Box<dyn TransactionGenerator> {
+        Box::new(PublishPackageGenerator::new(
+            self.txn_factory.clone(),
+            self.gas_price,
+        ))
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
+use crate::emitter::stats::DynamicStatsTracking;
 use crate::transaction_generator::{TransactionGenerator, TransactionGeneratorCreator};
 use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
 use async_trait::async_trait;
@@ -7,20 +8,29 @@ use futures::future::join_all;
 use rand::prelude::StdRng;
 use rand::Rng;
 use rand_core::{OsRng, SeedableRng};
+use std::{cmp::min, sync::Arc};
 
+/// Picks the generator of each batch with the weights of the current phase of the job. The last
+/// weights are kept for the phases following them.
 pub struct TxnMixGenerator {
     rng: StdRng,
-    txn_mix: Vec<(Box<dyn TransactionGenerator>, usize)>,
-    total_weight: usize,
+    txn_mix: Vec<Box<dyn TransactionGenerator>>,
+    phase_weights: Vec<Vec<usize>>,
+    stats: Arc<DynamicStatsTracking>,
 }
 
 impl TxnMixGenerator {
-    pub fn new(rng: StdRng, txn_mix: Vec<(Box<dyn TransactionGenerator>, usize)>) -> Self {
-        let total_weight = txn_mix.iter().map(|(_, weight)| weight).sum();
+    pub fn new(
+        rng: StdRng,
+        txn_mix: Vec<Box<dyn TransactionGenerator>>,
+        phase_weights: Vec<Vec<usize>>,
+        stats: Arc<DynamicStatsTracking>,
+    ) -> Self {
         Self {
             rng,
             txn_mix,
-            total_weight,
+            phase_weights,
+            stats,
         }
     }
 }
@@ -31,8 +41,11 @@ impl TransactionGenerator for TxnMixGenerator {
         accounts: Vec<&mut LocalAccount>,
         transactions_per_account: usize,
     ) -> Vec<SignedTransaction> {
-        let mut picked = self.rng.gen_range(0, self.total_weight);
-        for (gen, weight) in &mut self.txn_mix {
+        let phase = min(self.stats.get_cur_phase(), self.phase_weights.len() - 1);
+        let weights = &self.phase_weights[phase];
+        let total_weight: usize = weights.iter().sum();
+        let mut picked = self.rng.gen_range(0, total_weight);
+        for (gen, weight) in self.txn_mix.iter_mut().zip(weights) {
             if picked < *weight {
                 return gen.generate_transactions(accounts, transactions_per_account);
             }
@@ -40,18 +53,36 @@ impl TransactionGenerator for TxnMixGenerator {
         }
         panic!(
             "Picked {} out of {}, couldn't find correct generator",
-            picked, self.total_weight
+            picked, total_weight
         );
     }
 }
 
 pub struct TxnMixGeneratorCreator {
-    txn_mix_creators: Vec<(Box<dyn TransactionGeneratorCreator>, usize)>,
+    txn_mix_creators: Vec<Box<dyn TransactionGeneratorCreator>>,
+    phase_weights: Vec<Vec<usize>>,
+    stats: Arc<DynamicStatsTracking>,
 }
 
 impl TxnMixGeneratorCreator {
-    pub fn new(txn_mix_creators: Vec<(Box<dyn TransactionGeneratorCreator>, usize)>) -> Self {
-        Self { txn_mix_creators }
+    /// `phase_weights` has the weight of each creator, for each phase
+    pub fn new(
+        txn_mix_creators: Vec<Box<dyn TransactionGeneratorCreator>>,
+        phase_weights: Vec<Vec<usize>>,
+        stats: Arc<DynamicStatsTracking>,
+    ) -> Self {
+        assert!(
+            !phase_weights.is_empty()
+                && phase_weights.iter().all(|weights| {
+                    weights.len() == txn_mix_creators.len() && weights.iter().sum::<usize>() > 0
+                }),
+            "Each phase needs a positive weight for some of the transaction generators"
+        );
+        Self {
+            txn_mix_creators,
+            phase_weights,
+            stats,
+        }
     }
 }
 
@@ -63,15 +94,12 @@ impl TransactionGeneratorCreator for TxnMixGeneratorCreator {
             join_all(
                 self.txn_mix_creators
                     .iter()
-                    .map(|(generator_creator, weight)| async {
-                        (
-                            generator_creator.create_transaction_generator().await,
-                            *weight,
-                        )
-                    })
+                    .map(|generator_creator| generator_creator.create_transaction_generator())
                     .collect::<Vec<_>>(),
             )
             .await,
+            self.phase_weights.clone(),
+            self.stats.clone(),
         ))
     }
 }
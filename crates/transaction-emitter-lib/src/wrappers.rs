@@ -43,7 +43,9 @@ pub async fn emit_transactions_with_cluster(
         StdRng::from_seed(OsRng.gen()),
     );
 
-    let transaction_mix = if args.transaction_type_weights.is_empty() {
+    let transaction_mix = if let Some(workload_profile) = args.workload_profile {
+        workload_profile.transaction_mix()
+    } else if args.transaction_type_weights.is_empty() {
         args.transaction_type.iter().map(|t| (*t, 1)).collect()
     } else {
         assert_eq!(
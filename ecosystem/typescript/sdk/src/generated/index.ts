@@ -30,6 +30,9 @@ export type { EntryFunctionId } from './models/EntryFunctionId';
 export type { EntryFunctionPayload } from './models/EntryFunctionPayload';
 export type { Event } from './models/Event';
 export type { EventGuid } from './models/EventGuid';
+export { ErrorCategory } from './models/ErrorCategory';
+export type { ErrorCodeInfo } from './models/ErrorCodeInfo';
+export { EvictionReason } from './models/EvictionReason';
 export type { GasEstimation } from './models/GasEstimation';
 export type { GenesisPayload } from './models/GenesisPayload';
 export type { GenesisPayload_WriteSetPayload } from './models/GenesisPayload_WriteSetPayload';
@@ -60,11 +63,13 @@ export type { MultiAgentSignature } from './models/MultiAgentSignature';
 export type { MultiEd25519Signature } from './models/MultiEd25519Signature';
 export type { PendingTransaction } from './models/PendingTransaction';
 export type { RawTableItemRequest } from './models/RawTableItemRequest';
+export { RetryHint } from './models/RetryHint';
 export { RoleType } from './models/RoleType';
 export type { ScriptPayload } from './models/ScriptPayload';
 export type { ScriptWriteSet } from './models/ScriptWriteSet';
 export type { StateCheckpointTransaction } from './models/StateCheckpointTransaction';
 export type { StateKeyWrapper } from './models/StateKeyWrapper';
+export type { SubmissionReceipt } from './models/SubmissionReceipt';
 export type { SubmitTransactionRequest } from './models/SubmitTransactionRequest';
 export type { TableItemRequest } from './models/TableItemRequest';
 export type { Transaction } from './models/Transaction';
@@ -73,6 +78,7 @@ export type { Transaction_GenesisTransaction } from './models/Transaction_Genesi
 export type { Transaction_PendingTransaction } from './models/Transaction_PendingTransaction';
 export type { Transaction_StateCheckpointTransaction } from './models/Transaction_StateCheckpointTransaction';
 export type { Transaction_UserTransaction } from './models/Transaction_UserTransaction';
+export type { TransactionEviction } from './models/TransactionEviction';
 export type { TransactionPayload } from './models/TransactionPayload';
 export type { TransactionPayload_EntryFunctionPayload } from './models/TransactionPayload_EntryFunctionPayload';
 export type { TransactionPayload_ModuleBundlePayload } from './models/TransactionPayload_ModuleBundlePayload';
@@ -125,6 +131,9 @@ export { $EntryFunctionId } from './schemas/$EntryFunctionId';
 export { $EntryFunctionPayload } from './schemas/$EntryFunctionPayload';
 export { $Event } from './schemas/$Event';
 export { $EventGuid } from './schemas/$EventGuid';
+export { $ErrorCategory } from './schemas/$ErrorCategory';
+export { $ErrorCodeInfo } from './schemas/$ErrorCodeInfo';
+export { $EvictionReason } from './schemas/$EvictionReason';
 export { $GasEstimation } from './schemas/$GasEstimation';
 export { $GenesisPayload } from './schemas/$GenesisPayload';
 export { $GenesisPayload_WriteSetPayload } from './schemas/$GenesisPayload_WriteSetPayload';
@@ -155,11 +164,13 @@ export { $MultiAgentSignature } from './schemas/$MultiAgentSignature';
 export { $MultiEd25519Signature } from './schemas/$MultiEd25519Signature';
 export { $PendingTransaction } from './schemas/$PendingTransaction';
 export { $RawTableItemRequest } from './schemas/$RawTableItemRequest';
+export { $RetryHint } from './schemas/$RetryHint';
 export { $RoleType } from './schemas/$RoleType';
 export { $ScriptPayload } from './schemas/$ScriptPayload';
 export { $ScriptWriteSet } from './schemas/$ScriptWriteSet';
 export { $StateCheckpointTransaction } from './schemas/$StateCheckpointTransaction';
 export { $StateKeyWrapper } from './schemas/$StateKeyWrapper';
+export { $SubmissionReceipt } from './schemas/$SubmissionReceipt';
 export { $SubmitTransactionRequest } from './schemas/$SubmitTransactionRequest';
 export { $TableItemRequest } from './schemas/$TableItemRequest';
 export { $Transaction } from './schemas/$Transaction';
@@ -168,6 +179,7 @@ export { $Transaction_GenesisTransaction } from './schemas/$Transaction_GenesisT
 export { $Transaction_PendingTransaction } from './schemas/$Transaction_PendingTransaction';
 export { $Transaction_StateCheckpointTransaction } from './schemas/$Transaction_StateCheckpointTransaction';
 export { $Transaction_UserTransaction } from './schemas/$Transaction_UserTransaction';
+export { $TransactionEviction } from './schemas/$TransactionEviction';
 export { $TransactionPayload } from './schemas/$TransactionPayload';
 export { $TransactionPayload_EntryFunctionPayload } from './schemas/$TransactionPayload_EntryFunctionPayload';
 export { $TransactionPayload_ModuleBundlePayload } from './schemas/$TransactionPayload_ModuleBundlePayload';
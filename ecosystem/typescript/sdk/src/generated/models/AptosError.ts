@@ -3,6 +3,7 @@
 /* eslint-disable */
 
 import type { AptosErrorCode } from './AptosErrorCode';
+import type { ErrorCodeInfo } from './ErrorCodeInfo';
 
 /**
  * This is the generic struct we use for all API errors, it contains a string
@@ -18,5 +19,6 @@ export type AptosError = {
      * A code providing VM error details when submitting transactions to the VM
      */
     vm_error_code?: number;
+    error_info?: ErrorCodeInfo;
 };
 
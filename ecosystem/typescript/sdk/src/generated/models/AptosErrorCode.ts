@@ -21,6 +21,9 @@ export enum AptosErrorCode {
     INVALID_TRANSACTION_UPDATE = 'invalid_transaction_update',
     SEQUENCE_NUMBER_TOO_OLD = 'sequence_number_too_old',
     VM_ERROR = 'vm_error',
+    SEQUENCE_NUMBER_TOO_NEW = 'sequence_number_too_new',
+    TRANSACTION_DENIED = 'transaction_denied',
+    TRANSACTION_NOT_ALLOWLISTED = 'transaction_not_allowlisted',
     HEALTH_CHECK_FAILED = 'health_check_failed',
     MEMPOOL_IS_FULL = 'mempool_is_full',
     INTERNAL_ERROR = 'internal_error',
@@ -0,0 +1,15 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * The broad category of an error
+ */
+export enum ErrorCategory {
+    NOT_FOUND = 'not_found',
+    PRUNED = 'pruned',
+    INVALID_INPUT = 'invalid_input',
+    TRANSACTION_REJECTED = 'transaction_rejected',
+    UNAVAILABLE = 'unavailable',
+    INTERNAL = 'internal',
+}
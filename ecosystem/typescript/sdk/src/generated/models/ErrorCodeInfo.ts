@@ -0,0 +1,23 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ErrorCategory } from './ErrorCategory';
+import type { RetryHint } from './RetryHint';
+
+/**
+ * The catalog entry of an error
+ */
+export type ErrorCodeInfo = {
+    /**
+     * The stable numeric code of the error
+     */
+    code: number;
+    /**
+     * The name of the error, e.g. `sequence_number_too_old`
+     */
+    name: string;
+    category: ErrorCategory;
+    retry_hint: RetryHint;
+};
+
@@ -0,0 +1,13 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * The reason a transaction was evicted from mempool
+ */
+export enum EvictionReason {
+    MEMPOOL_FULL = 'mempool_full',
+    REPLACED = 'replaced',
+    SYSTEM_TTL_EXPIRED = 'system_ttl_expired',
+    EXPIRED = 'expired',
+}
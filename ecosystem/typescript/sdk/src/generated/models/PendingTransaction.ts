@@ -4,6 +4,8 @@
 
 import type { Address } from './Address';
 import type { HashValue } from './HashValue';
+import type { SubmissionReceipt } from './SubmissionReceipt';
+import type { TransactionEviction } from './TransactionEviction';
 import type { TransactionPayload } from './TransactionPayload';
 import type { TransactionSignature } from './TransactionSignature';
 import type { U64 } from './U64';
@@ -20,5 +22,7 @@ export type PendingTransaction = {
     expiration_timestamp_secs: U64;
     payload: TransactionPayload;
     signature?: TransactionSignature;
+    eviction?: TransactionEviction;
+    receipt?: SubmissionReceipt;
 };
 
@@ -0,0 +1,12 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * How a client should react to an error
+ */
+export enum RetryHint {
+    DO_NOT_RETRY = 'do_not_retry',
+    RETRY_LATER = 'retry_later',
+    REBUILD_TRANSACTION = 'rebuild_transaction',
+}
@@ -0,0 +1,25 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { Address } from './Address';
+import type { HashValue } from './HashValue';
+import type { HexEncodedBytes } from './HexEncodedBytes';
+import type { U64 } from './U64';
+
+/**
+ * A receipt signed by the node, acknowledging that it accepted a transaction into its mempool
+ *
+ * The signature is over the BCS encoded `SubmissionReceiptMessage`. It's made with the network
+ * identity key of the node, so it can be checked by anyone who knows the node's identity, e.g.
+ * from its network address.
+ */
+export type SubmissionReceipt = {
+    chain_id: number;
+    transaction_hash: HashValue;
+    accepted_at: U64;
+    node: Address;
+    public_key: HexEncodedBytes;
+    signature: HexEncodedBytes;
+};
+
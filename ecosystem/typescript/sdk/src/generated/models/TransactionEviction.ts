@@ -0,0 +1,18 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { EvictionReason } from './EvictionReason';
+import type { U64 } from './U64';
+
+/**
+ * The eviction of a transaction from mempool before it was committed
+ *
+ * An evicted transaction won't be committed, unless it's submitted again or it's still in the
+ * mempool of other nodes.
+ */
+export type TransactionEviction = {
+    reason: EvictionReason;
+    timestamp: U64;
+};
+
@@ -45,5 +45,7 @@ export type UserTransaction = {
      */
     events: Array<Event>;
     timestamp: U64;
+    block_round: U64;
+    block_proposer: Address;
 };
 
@@ -19,5 +19,8 @@ export const $AptosError = {
             description: `A code providing VM error details when submitting transactions to the VM`,
             format: 'uint64',
         },
+        error_info: {
+            type: 'ErrorCodeInfo',
+        },
     },
 } as const;
@@ -0,0 +1,6 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export const $ErrorCategory = {
+    type: 'Enum',
+} as const;
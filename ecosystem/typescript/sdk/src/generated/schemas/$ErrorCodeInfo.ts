@@ -0,0 +1,27 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export const $ErrorCodeInfo = {
+    description: `The catalog entry of an error`,
+    properties: {
+        code: {
+            type: 'number',
+            description: `The stable numeric code of the error`,
+            isRequired: true,
+            format: 'uint64',
+        },
+        name: {
+            type: 'string',
+            description: `The name of the error, e.g. \`sequence_number_too_old\``,
+            isRequired: true,
+        },
+        category: {
+            type: 'ErrorCategory',
+            isRequired: true,
+        },
+        retry_hint: {
+            type: 'RetryHint',
+            isRequired: true,
+        },
+    },
+} as const;
@@ -0,0 +1,6 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export const $EvictionReason = {
+    type: 'Enum',
+} as const;
@@ -35,5 +35,11 @@ export const $PendingTransaction = {
         signature: {
             type: 'TransactionSignature',
         },
+        eviction: {
+            type: 'TransactionEviction',
+        },
+        receipt: {
+            type: 'SubmissionReceipt',
+        },
     },
 } as const;
@@ -0,0 +1,37 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export const $SubmissionReceipt = {
+    description: `A receipt signed by the node, acknowledging that it accepted a transaction into its mempool
+
+    The signature is over the BCS encoded \`SubmissionReceiptMessage\`. It's made with the network
+    identity key of the node, so it can be checked by anyone who knows the node's identity, e.g.
+    from its network address.`,
+    properties: {
+        chain_id: {
+            type: 'number',
+            isRequired: true,
+            format: 'uint8',
+        },
+        transaction_hash: {
+            type: 'HashValue',
+            isRequired: true,
+        },
+        accepted_at: {
+            type: 'U64',
+            isRequired: true,
+        },
+        node: {
+            type: 'Address',
+            isRequired: true,
+        },
+        public_key: {
+            type: 'HexEncodedBytes',
+            isRequired: true,
+        },
+        signature: {
+            type: 'HexEncodedBytes',
+            isRequired: true,
+        },
+    },
+} as const;
@@ -0,0 +1,19 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+export const $TransactionEviction = {
+    description: `The eviction of a transaction from mempool before it was committed
+
+    An evicted transaction won't be committed, unless it's submitted again or it's still in the
+    mempool of other nodes.`,
+    properties: {
+        reason: {
+            type: 'EvictionReason',
+            isRequired: true,
+        },
+        timestamp: {
+            type: 'U64',
+            isRequired: true,
+        },
+    },
+} as const;
@@ -8,13 +8,15 @@ use crate::shared_mempool::types::MultiBucketTimelineIndexIds;
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{
+            EvictedTransaction, MempoolTransaction, ParkedTransactionEvent, TimelineState,
+        },
         transaction_store::TransactionStore,
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{MempoolConfig, NodeConfig};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_types::{
@@ -23,6 +25,7 @@ use aptos_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
     transaction::SignedTransaction,
 };
+use futures::channel::mpsc;
 use std::{
     collections::HashSet,
     time::{Duration, SystemTime},
@@ -33,6 +36,9 @@ pub struct Mempool {
     transactions: TransactionStore,
 
     pub system_transaction_timeout: Duration,
+
+    // Max number of sequence numbers a transaction can be ahead of its account's sequence number
+    max_sequence_number_horizon: u64,
 }
 
 impl Mempool {
@@ -42,9 +48,24 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            max_sequence_number_horizon: config.mempool.max_sequence_number_horizon,
         }
     }
 
+    /// Applies the capacity limits of `config`, e.g. after the node config was reloaded.
+    pub(crate) fn update_capacity(&mut self, config: &MempoolConfig) {
+        self.transactions.update_capacity(config);
+    }
+
+    /// Sets the channel parked transactions are reported on once they become ready or are
+    /// evicted.
+    pub(crate) fn set_parked_transaction_sender(
+        &mut self,
+        sender: mpsc::Sender<ParkedTransactionEvent>,
+    ) {
+        self.transactions.set_parked_transaction_sender(sender);
+    }
+
     /// This function will be called once the transaction has been stored.
     pub(crate) fn commit_transaction(&mut self, sender: &AccountAddress, sequence_number: u64) {
         trace!(
@@ -109,6 +130,14 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    pub(crate) fn get_evicted_by_hash(&self, hash: HashValue) -> Option<EvictedTransaction> {
+        self.transactions.get_evicted_by_hash(hash)
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.transactions.is_full()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -134,6 +163,21 @@ impl Mempool {
             ));
         }
 
+        // don't accept transactions too far ahead of the account's sequence number, they'd
+        // take up space in the parking lot for a long time
+        if txn.sequence_number()
+            > db_sequence_number.saturating_add(self.max_sequence_number_horizon)
+        {
+            return MempoolStatus::new(MempoolStatusCode::SequenceNumberTooNew).with_message(
+                format!(
+                    "transaction sequence number is {}, current sequence number is {}, max sequence number horizon is {}",
+                    txn.sequence_number(),
+                    db_sequence_number,
+                    self.max_sequence_number_horizon,
+                ),
+            );
+        }
+
         let now = SystemTime::now();
         let expiration_time =
             aptos_infallible::duration_since_epoch_at(&now) + self.system_transaction_timeout;
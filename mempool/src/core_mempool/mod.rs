@@ -7,6 +7,12 @@ mod transaction;
 mod transaction_store;
 
 pub use self::{
-    index::TxnPointer, mempool::Mempool as CoreMempool, transaction::MempoolTransaction,
-    transaction::TimelineState, transaction_store::TXN_INDEX_ESTIMATED_BYTES,
+    index::TxnPointer,
+    mempool::Mempool as CoreMempool,
+    transaction::TimelineState,
+    transaction::{
+        EvictedTransaction, EvictionReason, MempoolTransaction, ParkedTransactionEvent,
+        ParkedTransactionStatus,
+    },
+    transaction_store::TXN_INDEX_ESTIMATED_BYTES,
 };
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::core_mempool::TXN_INDEX_ESTIMATED_BYTES;
+use crate::{core_mempool::TXN_INDEX_ESTIMATED_BYTES, counters};
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress, account_config::AccountSequenceInfo,
@@ -72,6 +72,70 @@ pub enum TimelineState {
     NonQualified,
 }
 
+/// Reported when a parked transaction, i.e., one waiting for a transaction with a lower sequence
+/// number of the same account, leaves the parking lot.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ParkedTransactionEvent {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub hash: HashValue,
+    pub status: ParkedTransactionStatus,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParkedTransactionStatus {
+    // The transaction can be included in the next block
+    Ready,
+    // The transaction was removed from mempool to make space or because it expired
+    Evicted,
+}
+
+impl ParkedTransactionEvent {
+    pub(crate) fn new(txn: &MempoolTransaction, status: ParkedTransactionStatus) -> Self {
+        Self {
+            sender: txn.get_sender(),
+            sequence_number: txn.sequence_info.transaction_sequence_number,
+            hash: txn.get_committed_hash(),
+            status,
+        }
+    }
+}
+
+/// Why a transaction was removed from mempool before it was committed
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionReason {
+    // Mempool was full, and the parked transaction made space for a ready one
+    MempoolFull,
+    // The same transaction was resubmitted with a higher gas unit price
+    Replaced,
+    // The transaction stayed in mempool for longer than the system TTL
+    SystemTtlExpired,
+    // The expiration timestamp of the transaction passed
+    Expired,
+}
+
+impl EvictionReason {
+    pub(crate) fn as_label(&self) -> &'static str {
+        match self {
+            EvictionReason::MempoolFull => counters::EVICTION_MEMPOOL_FULL_LABEL,
+            EvictionReason::Replaced => counters::EVICTION_REPLACED_LABEL,
+            EvictionReason::SystemTtlExpired => counters::EVICTION_SYSTEM_TTL_LABEL,
+            EvictionReason::Expired => counters::EVICTION_EXPIRED_LABEL,
+        }
+    }
+}
+
+/// A transaction evicted from mempool, remembered for a while so that clients looking it up
+/// learn why it disappeared
+#[derive(Clone, Debug)]
+pub struct EvictedTransaction {
+    pub txn: SignedTransaction,
+    pub reason: EvictionReason,
+    pub eviction_time: SystemTime,
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct SequenceInfo {
     pub transaction_sequence_number: u64,
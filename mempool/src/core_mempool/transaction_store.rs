@@ -11,7 +11,10 @@ use crate::{
             AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
             PriorityQueueIter, TTLIndex,
         },
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{
+            EvictedTransaction, EvictionReason, MempoolTransaction, ParkedTransactionEvent,
+            ParkedTransactionStatus, TimelineState,
+        },
     },
     counters,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
@@ -25,10 +28,11 @@ use aptos_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
     transaction::SignedTransaction,
 };
+use futures::channel::mpsc;
 use std::cmp::max;
 use std::mem::size_of;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     ops::Bound,
     time::{Duration, SystemTime},
 };
@@ -39,6 +43,16 @@ pub const TXN_INDEX_ESTIMATED_BYTES: usize = size_of::<crate::core_mempool::inde
     + (size_of::<u64>() * 3 + size_of::<AccountAddress>()) // timeline_index
     + (size_of::<HashValue>() + size_of::<u64>() + size_of::<AccountAddress>()); // hash_index
 
+/// The estimated bytes of an entry of the eviction order, excluding the transaction
+const EVICTION_ORDER_ESTIMATED_BYTES: usize = size_of::<(SystemTime, HashValue)>();
+/// The estimated bytes of an evicted transaction, excluding the raw transaction
+const EVICTED_TXN_FIXED_ESTIMATED_BYTES: usize =
+    size_of::<HashValue>() + size_of::<EvictedTransaction>();
+
+fn evicted_txn_estimated_bytes(evicted: &EvictedTransaction) -> usize {
+    evicted.txn.raw_txn_bytes_len() + EVICTED_TXN_FIXED_ESTIMATED_BYTES
+}
+
 /// TransactionStore is in-memory storage for all transactions in mempool.
 pub struct TransactionStore {
     // main DS
@@ -66,6 +80,17 @@ pub struct TransactionStore {
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
 
+    // Recently evicted transactions by committed hash, and the order they were evicted in.
+    // A transaction can be evicted again after being resubmitted, so the order may contain
+    // stale entries, which are skipped when pruning.
+    evicted_transactions: HashMap<HashValue, EvictedTransaction>,
+    eviction_order: VecDeque<(SystemTime, HashValue)>,
+    evicted_transaction_retention: Duration,
+    // estimated size in bytes of the evicted transactions and their order, bounded by the
+    // capacity
+    evicted_transactions_size_bytes: usize,
+    evicted_transactions_capacity_bytes: usize,
+
     // estimated size in bytes
     size_bytes: usize,
 
@@ -74,6 +99,9 @@ pub struct TransactionStore {
     capacity_bytes: usize,
     capacity_per_user: usize,
     max_batch_bytes: u64,
+
+    // reports parked transactions that become ready or are evicted
+    parked_transaction_sender: Option<mpsc::Sender<ParkedTransactionEvent>>,
 }
 
 impl TransactionStore {
@@ -94,6 +122,14 @@ impl TransactionStore {
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
 
+            evicted_transactions: HashMap::new(),
+            eviction_order: VecDeque::new(),
+            evicted_transaction_retention: Duration::from_secs(
+                config.evicted_transaction_retention_secs,
+            ),
+            evicted_transactions_size_bytes: 0,
+            evicted_transactions_capacity_bytes: config.evicted_transactions_capacity_bytes,
+
             // estimated size in bytes
             size_bytes: 0,
 
@@ -102,6 +138,43 @@ impl TransactionStore {
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+
+            parked_transaction_sender: None,
+        }
+    }
+
+    /// Applies new capacity limits. Lowering them doesn't evict anything, the mempool only
+    /// rejects new transactions until it has drained below the new limits.
+    pub(crate) fn update_capacity(&mut self, config: &MempoolConfig) {
+        self.capacity = config.capacity;
+        self.capacity_bytes = config.capacity_bytes;
+        self.capacity_per_user = config.capacity_per_user;
+    }
+
+    pub(crate) fn set_parked_transaction_sender(
+        &mut self,
+        sender: mpsc::Sender<ParkedTransactionEvent>,
+    ) {
+        self.parked_transaction_sender = Some(sender);
+    }
+
+    /// Reports a transaction leaving the parking lot. Takes the sender instead of `self`, so it
+    /// can be called while the account's transactions are borrowed. Events are dropped if the
+    /// receiver can't keep up, mempool never waits on it.
+    fn notify_parked_transaction(
+        sender: &mut Option<mpsc::Sender<ParkedTransactionEvent>>,
+        txn: &MempoolTransaction,
+        status: ParkedTransactionStatus,
+    ) {
+        if let Some(sender) = sender {
+            if sender
+                .try_send(ParkedTransactionEvent::new(txn, status))
+                .is_err()
+            {
+                counters::parked_txn_notification_inc(
+                    counters::PARKED_TXN_NOTIFICATION_DROPPED_LABEL,
+                );
+            }
         }
     }
 
@@ -147,6 +220,70 @@ impl TransactionStore {
         }
     }
 
+    /// Returns the transaction with the given hash if it was evicted within the retention period
+    pub(crate) fn get_evicted_by_hash(&self, hash: HashValue) -> Option<EvictedTransaction> {
+        self.evicted_transactions
+            .get(&hash)
+            .filter(|evicted| !self.is_eviction_expired(evicted.eviction_time))
+            .cloned()
+    }
+
+    fn is_eviction_expired(&self, eviction_time: SystemTime) -> bool {
+        SystemTime::now()
+            .duration_since(eviction_time)
+            .map_or(false, |age| age >= self.evicted_transaction_retention)
+    }
+
+    /// Remembers why `txn` was removed from mempool, for clients looking it up later
+    fn record_eviction(&mut self, txn: &MempoolTransaction, reason: EvictionReason) {
+        if self.evicted_transaction_retention.is_zero() {
+            return;
+        }
+        let hash = txn.get_committed_hash();
+        let eviction_time = SystemTime::now();
+        self.forget_eviction(&hash);
+        let evicted = EvictedTransaction {
+            txn: txn.txn.clone(),
+            reason,
+            eviction_time,
+        };
+        self.evicted_transactions_size_bytes +=
+            evicted_txn_estimated_bytes(&evicted) + EVICTION_ORDER_ESTIMATED_BYTES;
+        self.evicted_transactions.insert(hash, evicted);
+        self.eviction_order.push_back((eviction_time, hash));
+        counters::CORE_MEMPOOL_EVICTED_TXNS
+            .with_label_values(&[reason.as_label()])
+            .inc();
+        self.prune_evicted_transactions();
+    }
+
+    /// Forgets the eviction of the transaction with the given hash, if it was evicted
+    fn forget_eviction(&mut self, hash: &HashValue) {
+        if let Some(evicted) = self.evicted_transactions.remove(hash) {
+            self.evicted_transactions_size_bytes -= evicted_txn_estimated_bytes(&evicted);
+        }
+    }
+
+    /// Forgets the evicted transactions older than the retention period, or above the capacity
+    fn prune_evicted_transactions(&mut self) {
+        while let Some((eviction_time, hash)) = self.eviction_order.front().copied() {
+            if !self.is_eviction_expired(eviction_time)
+                && self.evicted_transactions_size_bytes <= self.evicted_transactions_capacity_bytes
+            {
+                break;
+            }
+            self.eviction_order.pop_front();
+            self.evicted_transactions_size_bytes -= EVICTION_ORDER_ESTIMATED_BYTES;
+            if self
+                .evicted_transactions
+                .get(&hash)
+                .map_or(false, |evicted| evicted.eviction_time == eviction_time)
+            {
+                self.forget_eviction(&hash);
+            }
+        }
+    }
+
     /// Return (SystemTime, is the timestamp for end-to-end)
     pub(crate) fn get_insertion_time_and_bucket(
         &self,
@@ -216,6 +353,7 @@ impl TransactionStore {
                 } else if current_version.txn.gas_unit_price() < txn.get_gas_price() {
                     // Update txn if gas unit price is a larger value than before
                     if let Some(txn) = txns.remove(&sequence_number.transaction_sequence_number) {
+                        self.record_eviction(&txn, EvictionReason::Replaced);
                         self.index_remove(&txn);
                     };
                 } else if current_version.get_gas_price() > txn.get_gas_price() {
@@ -271,6 +409,8 @@ impl TransactionStore {
                 txn.get_committed_hash(),
                 (sender, sequence_number.transaction_sequence_number),
             );
+            // A resubmitted transaction is no longer evicted
+            self.forget_eviction(&txn.get_committed_hash());
             let txn_size_bytes = txn.get_estimated_bytes();
             txns.insert(sequence_number.transaction_sequence_number, txn);
             self.sequence_numbers.insert(
@@ -310,6 +450,10 @@ impl TransactionStore {
             counters::TRANSACTION_HASH_INDEX_LABEL,
             self.hash_index.len(),
         );
+        counters::core_mempool_index_size(
+            counters::EVICTED_TRANSACTIONS_INDEX_LABEL,
+            self.evicted_transactions.len(),
+        );
         counters::core_mempool_index_size(counters::SIZE_BYTES_LABEL, self.size_bytes);
     }
 
@@ -335,6 +479,12 @@ impl TransactionStore {
                             txn.sequence_info.transaction_sequence_number
                         ))
                     );
+                    Self::notify_parked_transaction(
+                        &mut self.parked_transaction_sender,
+                        &txn,
+                        ParkedTransactionStatus::Evicted,
+                    );
+                    self.record_eviction(&txn, EvictionReason::MempoolFull);
                     self.index_remove(&txn);
                 }
             }
@@ -342,7 +492,7 @@ impl TransactionStore {
         self.is_full()
     }
 
-    fn is_full(&self) -> bool {
+    pub(crate) fn is_full(&self) -> bool {
         self.system_ttl_index.size() >= self.capacity || self.size_bytes >= self.capacity_bytes
     }
 
@@ -388,6 +538,13 @@ impl TransactionStore {
             match sequence_info {
                 AccountSequenceInfo::Sequential(_) => {
                     while let Some(txn) = txns.get_mut(&min_seq) {
+                        if self.parking_lot_index.contains(address, &min_seq) {
+                            Self::notify_parked_transaction(
+                                &mut self.parked_transaction_sender,
+                                txn,
+                                ParkedTransactionStatus::Ready,
+                            );
+                        }
                         self.priority_index.insert(txn);
 
                         let mut broadcast_ready = false;
@@ -639,17 +796,19 @@ impl TransactionStore {
     }
 
     fn gc(&mut self, now: Duration, by_system_ttl: bool) {
-        let (metric_label, index, log_event) = if by_system_ttl {
+        let (metric_label, index, log_event, eviction_reason) = if by_system_ttl {
             (
                 counters::GC_SYSTEM_TTL_LABEL,
                 &mut self.system_ttl_index,
                 LogEvent::SystemTTLExpiration,
+                EvictionReason::SystemTtlExpired,
             )
         } else {
             (
                 counters::GC_CLIENT_EXP_LABEL,
                 &mut self.expiration_time_index,
                 LogEvent::ClientExpiration,
+                EvictionReason::Expired,
             )
         };
         counters::CORE_MEMPOOL_GC_EVENT_COUNT
@@ -690,6 +849,16 @@ impl TransactionStore {
                     } else {
                         counters::GC_PARKED_TXN_LABEL
                     };
+                    if self
+                        .parking_lot_index
+                        .contains(&key.address, &key.sequence_number)
+                    {
+                        Self::notify_parked_transaction(
+                            &mut self.parked_transaction_sender,
+                            &txn,
+                            ParkedTransactionStatus::Evicted,
+                        );
+                    }
                     let account = txn.get_sender();
                     let txn_sequence_number = txn.sequence_info.transaction_sequence_number;
                     gc_txns_log.add_with_status(account, txn_sequence_number, status);
@@ -700,6 +869,7 @@ impl TransactionStore {
                     }
 
                     // remove txn
+                    self.record_eviction(&txn, eviction_reason);
                     self.index_remove(&txn);
                 }
             }
@@ -710,6 +880,7 @@ impl TransactionStore {
         } else {
             trace!(LogSchema::event_log(LogEntry::GCRemoveTxns, log_event).txns(gc_txns_log));
         }
+        self.prune_evicted_transactions();
         self.track_indices();
     }
 
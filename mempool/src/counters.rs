@@ -18,6 +18,7 @@ pub const SYSTEM_TTL_INDEX_LABEL: &str = "system_ttl";
 pub const TIMELINE_INDEX_LABEL: &str = "timeline";
 pub const PARKING_LOT_INDEX_LABEL: &str = "parking_lot";
 pub const TRANSACTION_HASH_INDEX_LABEL: &str = "transaction_hash";
+pub const EVICTED_TRANSACTIONS_INDEX_LABEL: &str = "evicted_transactions";
 pub const SIZE_BYTES_LABEL: &str = "size_bytes";
 
 // Core mempool stages labels
@@ -32,6 +33,12 @@ pub const BROADCAST_BATCHED_LABEL: &str = "broadcast_batched";
 pub const GC_SYSTEM_TTL_LABEL: &str = "system_ttl";
 pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 
+// Core mempool eviction reason labels
+pub const EVICTION_MEMPOOL_FULL_LABEL: &str = "mempool_full";
+pub const EVICTION_REPLACED_LABEL: &str = "replaced";
+pub const EVICTION_SYSTEM_TTL_LABEL: &str = "system_ttl";
+pub const EVICTION_EXPIRED_LABEL: &str = "expired";
+
 // Core mempool GC txn status label
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
@@ -158,6 +165,16 @@ pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns evicted from core mempool before being committed, by reason
+pub static CORE_MEMPOOL_EVICTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_evicted_txns_count",
+        "Number of txns evicted from core mempool before being committed",
+        &["reason"]
+    )
+    .unwrap()
+});
+
 /// Counter tracking number of txns received that are idempotent duplicates
 pub static CORE_MEMPOOL_IDEMPOTENT_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -500,6 +517,34 @@ pub static VM_RECONFIG_UPDATE_FAIL_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static TRANSACTION_DENY_LIST_REJECTED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_mempool_transaction_deny_list_rejected_count",
+        "Number of transactions rejected by the on-chain transaction deny list"
+    )
+    .unwrap()
+});
+
+pub const PARKED_TXN_NOTIFICATION_SENT_LABEL: &str = "sent";
+pub const PARKED_TXN_NOTIFICATION_FAILED_LABEL: &str = "failed";
+pub const PARKED_TXN_NOTIFICATION_DROPPED_LABEL: &str = "dropped";
+
+/// Counter for notifications about parked transactions becoming ready or being evicted
+static PARKED_TXN_NOTIFICATION_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_mempool_parked_txn_notification_count",
+        "Number of notifications about parked transactions becoming ready or being evicted",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub fn parked_txn_notification_inc(label: &'static str) {
+    PARKED_TXN_NOTIFICATION_COUNT
+        .with_label_values(&[label])
+        .inc();
+}
+
 /// Counter for failed network sends
 static NETWORK_SEND_FAIL: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
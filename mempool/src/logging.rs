@@ -172,6 +172,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    ParkedTxnWebhook,
 }
 
 #[derive(Clone, Copy, Serialize)]
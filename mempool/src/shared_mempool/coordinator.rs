@@ -3,7 +3,7 @@
 
 //! Processes that are directly spawned by shared mempool runtime initialization
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{CoreMempool, ParkedTransactionEvent, TimelineState},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{MempoolNetworkEvents, MempoolSyncMsg},
@@ -38,6 +38,8 @@ use tokio_stream::wrappers::IntervalStream;
 
 use super::types::MempoolClientRequest;
 
+const PARKED_TXN_WEBHOOK_TIMEOUT_SECS: u64 = 5;
+
 /// Coordinator that handles inbound network events and outbound txn broadcasts.
 pub(crate) async fn coordinator<V>(
     mut smp: SharedMempool<V>,
@@ -159,6 +161,27 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetEvictedTransactionByHash(hash, callback) => {
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_TXN_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_evicted_transaction(
+                    smp.clone(),
+                    hash,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetHealth(callback) => {
+            tasks::process_client_get_health(smp, callback);
+        }
     }
 }
 
@@ -228,6 +251,7 @@ async fn handle_mempool_reconfig_event<V>(
             config_update,
             smp.validator.clone(),
             smp.broadcast_within_validator_network.clone(),
+            smp.transaction_deny_list.clone(),
         ))
         .await;
 }
@@ -371,3 +395,38 @@ pub(crate) async fn snapshot_job(mempool: Arc<Mutex<CoreMempool>>, snapshot_inte
         trace!(LogSchema::new(LogEntry::MempoolSnapshot).txns(snapshot));
     }
 }
+
+/// Posts the parked transactions that become ready or are evicted to a webhook, so that clients
+/// submitting bursts of transactions don't have to poll for them. Events are posted one at a
+/// time; if the webhook can't keep up, mempool drops new events instead of waiting.
+pub(crate) async fn parked_transaction_webhook(
+    mut events: mpsc::Receiver<ParkedTransactionEvent>,
+    url: String,
+) {
+    let client = reqwest::Client::new();
+    while let Some(event) = events.next().await {
+        let result = client
+            .post(&url)
+            .timeout(Duration::from_secs(PARKED_TXN_WEBHOOK_TIMEOUT_SECS))
+            .json(&event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        match result {
+            Ok(_) => {
+                counters::parked_txn_notification_inc(counters::PARKED_TXN_NOTIFICATION_SENT_LABEL)
+            }
+            Err(err) => {
+                counters::parked_txn_notification_inc(
+                    counters::PARKED_TXN_NOTIFICATION_FAILED_LABEL,
+                );
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(60)),
+                    warn!(LogSchema::new(LogEntry::ParkedTxnWebhook)
+                        .account(event.sender)
+                        .error(&err.into()))
+                );
+            }
+        }
+    }
+}
@@ -244,6 +244,10 @@ impl MempoolNetworkInterface {
         let _ = std::mem::replace(&mut *prioritized_peers, peers);
     }
 
+    pub fn num_upstream_peers(&self) -> usize {
+        self.sync_states.read_all().len()
+    }
+
     pub fn is_validator(&self) -> bool {
         self.role.is_validator()
     }
@@ -5,18 +5,22 @@ use crate::{
     core_mempool::CoreMempool,
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        coordinator::{coordinator, gc_coordinator, parked_transaction_webhook, snapshot_job},
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
 };
-use aptos_config::{config::NodeConfig, network_id::NetworkId};
+use aptos_config::{
+    config::{MempoolConfig, NodeConfig},
+    network_id::NetworkId,
+};
 use aptos_infallible::{Mutex, RwLock};
 
 use aptos_event_notifications::ReconfigNotificationListener;
 use aptos_logger::Level;
 use aptos_mempool_notifications::MempoolNotificationListener;
 use aptos_network::application::storage::PeerMetadataStorage;
+use aptos_runtimes::task_dump::track;
 use aptos_storage_interface::DbReader;
 use aptos_vm_validator::vm_validator::{TransactionValidation, VMValidator};
 use futures::channel::mpsc::{self, Receiver, UnboundedSender};
@@ -29,11 +33,15 @@ use std::{
 };
 use tokio::runtime::{Builder, Handle, Runtime};
 
+// Max number of parked transaction events waiting to be posted to the webhook
+const PARKED_TXN_WEBHOOK_CHANNEL_SIZE: usize = 10_000;
+
 /// Bootstrap of SharedMempool.
 /// Creates a separate Tokio Runtime that runs the following routines:
 ///   - outbound_sync_task (task that periodically broadcasts transactions to peers).
 ///   - inbound_network_task (task that handles inbound mempool messages and network events).
 ///   - gc_task (task that performs GC of all expired transactions by SystemTTL).
+///   - parked_transaction_webhook (task that posts parked transaction events, if configured).
 pub(crate) fn start_shared_mempool<V>(
     executor: &Handle,
     config: &NodeConfig,
@@ -59,6 +67,12 @@ pub(crate) fn start_shared_mempool<V>(
         network_senders.insert(network_id, network_sender);
     }
 
+    if let Some(url) = &config.mempool.parked_transaction_webhook_url {
+        let (sender, receiver) = mpsc::channel(PARKED_TXN_WEBHOOK_CHANNEL_SIZE);
+        mempool.lock().set_parked_transaction_sender(sender);
+        executor.spawn(parked_transaction_webhook(receiver, url.clone()));
+    }
+
     let smp = SharedMempool::new(
         mempool.clone(),
         config.mempool.clone(),
@@ -70,19 +84,25 @@ pub(crate) fn start_shared_mempool<V>(
         peer_metadata_storage,
     );
 
-    executor.spawn(coordinator(
-        smp,
-        executor.clone(),
-        all_network_events,
-        client_events,
-        quorum_store_requests,
-        mempool_listener,
-        mempool_reconfig_events,
+    executor.spawn(track(
+        "mempool-coordinator",
+        coordinator(
+            smp,
+            executor.clone(),
+            all_network_events,
+            client_events,
+            quorum_store_requests,
+            mempool_listener,
+            mempool_reconfig_events,
+        ),
     ));
 
-    executor.spawn(gc_coordinator(
-        mempool.clone(),
-        config.mempool.system_transaction_gc_interval_ms,
+    executor.spawn(track(
+        "mempool-gc-coordinator",
+        gc_coordinator(
+            mempool.clone(),
+            config.mempool.system_transaction_gc_interval_ms,
+        ),
     ));
 
     if aptos_logger::enabled!(Level::Trace) {
@@ -104,7 +124,7 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
-) -> Runtime {
+) -> (Runtime, MempoolCapacityUpdater) {
     let runtime = Builder::new_multi_thread()
         .thread_name_fn(|| {
             static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
@@ -120,7 +140,7 @@ pub fn bootstrap(
     start_shared_mempool(
         runtime.handle(),
         config,
-        mempool,
+        mempool.clone(),
         mempool_network_handles,
         client_events,
         quorum_store_requests,
@@ -131,5 +151,19 @@ pub fn bootstrap(
         vec![],
         peer_metadata_storage,
     );
-    runtime
+    (runtime, MempoolCapacityUpdater { mempool })
+}
+
+/// Changes the capacity limits of a running mempool, without restarting it.
+#[derive(Clone)]
+pub struct MempoolCapacityUpdater {
+    mempool: Arc<Mutex<CoreMempool>>,
+}
+
+impl MempoolCapacityUpdater {
+    /// Applies `capacity`, `capacity_bytes` and `capacity_per_user` from `config`, the other
+    /// mempool settings are only read on startup.
+    pub fn update(&self, config: &MempoolConfig) {
+        self.mempool.lock().update_capacity(config);
+    }
 }
@@ -3,12 +3,12 @@
 
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{CoreMempool, EvictedTransaction, TimelineState, TxnPointer},
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, MempoolSyncMsg},
     shared_mempool::types::{
-        notify_subscribers, MultiBatchId, ScheduledBroadcast, SharedMempool,
+        notify_subscribers, MempoolHealth, MultiBatchId, ScheduledBroadcast, SharedMempool,
         SharedMempoolNotification, SubmissionStatusBundle,
     },
     thread_pool::IO_POOL,
@@ -23,13 +23,16 @@ use aptos_logger::prelude::*;
 use aptos_metrics_core::HistogramTimer;
 use aptos_network::application::interface::NetworkInterface;
 use aptos_storage_interface::state_view::LatestDbStateCheckpointView;
-use aptos_types::on_chain_config::OnChainConsensusConfig;
+use aptos_types::on_chain_config::{
+    FeatureFlag, Features, OnChainConsensusConfig, TransactionDenyList,
+};
 use aptos_types::{
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
     vm_status::DiscardedVMStatus,
 };
+use aptos_vm::AptosVM;
 use aptos_vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
 use futures::{channel::oneshot, stream::FuturesUnordered};
 use rayon::prelude::*;
@@ -152,6 +155,44 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get evicted transaction by hash request by client.
+pub(crate) async fn process_client_get_evicted_transaction<V>(
+    smp: SharedMempool<V>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<EvictedTransaction>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_get_txn_latency_timer_client();
+    let txn = smp.mempool.lock().get_evicted_by_hash(hash);
+
+    if callback.send(txn).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::GetTransaction,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Reports the health of mempool to the client
+pub(crate) fn process_client_get_health<V>(
+    smp: &SharedMempool<V>,
+    callback: oneshot::Sender<MempoolHealth>,
+) where
+    V: TransactionValidation,
+{
+    let health = MempoolHealth {
+        is_full: smp.mempool.lock().is_full(),
+        num_upstream_peers: smp.network_interface.num_upstream_peers(),
+    };
+    if callback.send(health).is_err() {
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -247,6 +288,54 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject transactions on the on-chain deny list before doing any storage reads
+    let transactions = {
+        let deny_list = smp.transaction_deny_list.read();
+        if deny_list.is_empty() {
+            transactions
+        } else {
+            transactions
+                .into_iter()
+                .filter(|t| {
+                    if deny_list.denies(t) {
+                        counters::TRANSACTION_DENY_LIST_REJECTED_COUNT.inc();
+                        statuses.push((
+                            t.clone(),
+                            (
+                                MempoolStatus::new(MempoolStatusCode::TransactionDenied)
+                                    .with_message("Rejected by the transaction deny list".into()),
+                                None,
+                            ),
+                        ));
+                        false
+                    } else {
+                        true
+                    }
+                })
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Reject transactions outside the address allowlist of a permissioned network
+    let transactions: Vec<_> = transactions
+        .into_iter()
+        .filter(|t| {
+            if AptosVM::is_allowlisted(t) {
+                true
+            } else {
+                statuses.push((
+                    t.clone(),
+                    (
+                        MempoolStatus::new(MempoolStatusCode::NotAllowlisted)
+                            .with_message("Rejected by the address allowlist".into()),
+                        None,
+                    ),
+                ));
+                false
+            }
+        })
+        .collect();
+
     let start_storage_read = Instant::now();
     let state_view = smp
         .db
@@ -503,6 +592,7 @@ pub(crate) async fn process_config_update<V>(
     config_update: OnChainConfigPayload,
     validator: Arc<RwLock<V>>,
     broadcast_within_validator_network: Arc<RwLock<bool>>,
+    transaction_deny_list: Arc<RwLock<TransactionDenyList>>,
 ) where
     V: TransactionValidation,
 {
@@ -529,4 +619,13 @@ pub(crate) async fn process_config_update<V>(
             );
         }
     }
+
+    // The deny list is not published until governance first sets it, so treat it as empty. It is
+    // also ignored until its feature is enabled.
+    let features: Features = config_update.get().unwrap_or_default();
+    *transaction_deny_list.write() = if features.is_enabled(FeatureFlag::TRANSACTION_DENY_LIST) {
+        config_update.get().unwrap_or_default()
+    } else {
+        TransactionDenyList::default()
+    };
 }
@@ -3,7 +3,8 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
+    core_mempool::{CoreMempool, EvictedTransaction},
+    network::MempoolNetworkInterface,
     shared_mempool::network::MempoolNetworkSender,
 };
 use anyhow::Result;
@@ -17,7 +18,8 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_network::{application::storage::PeerMetadataStorage, transport::ConnectionMetadata};
 use aptos_storage_interface::DbReader;
 use aptos_types::{
-    mempool_status::MempoolStatus, transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    mempool_status::MempoolStatus, on_chain_config::TransactionDenyList,
+    transaction::SignedTransaction, vm_status::DiscardedVMStatus,
 };
 use aptos_vm_validator::vm_validator::TransactionValidation;
 use futures::{
@@ -50,6 +52,7 @@ where
     pub validator: Arc<RwLock<V>>,
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     pub broadcast_within_validator_network: Arc<RwLock<bool>>,
+    pub transaction_deny_list: Arc<RwLock<TransactionDenyList>>,
 }
 
 impl<V: TransactionValidation + 'static> SharedMempool<V> {
@@ -77,6 +80,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             validator,
             subscribers,
             broadcast_within_validator_network: Arc::new(RwLock::new(true)),
+            transaction_deny_list: Arc::new(RwLock::new(TransactionDenyList::default())),
         }
     }
 
@@ -216,6 +220,19 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Looks up a recently evicted transaction, to tell clients why it disappeared
+    GetEvictedTransactionByHash(HashValue, oneshot::Sender<Option<EvictedTransaction>>),
+    /// Reports whether mempool can accept transactions, for health checks
+    GetHealth(oneshot::Sender<MempoolHealth>),
+}
+
+/// State of mempool relevant to whether the node can serve transaction submissions
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MempoolHealth {
+    /// Whether mempool is at capacity, i.e., new transactions are only accepted by evicting others
+    pub is_full: bool,
+    /// Number of connected peers mempool broadcasts transactions to
+    pub num_upstream_peers: usize,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
@@ -3,7 +3,10 @@
 
 use crate::tests::common::setup_mempool_with_broadcast_buckets;
 use crate::{
-    core_mempool::{CoreMempool, MempoolTransaction, TimelineState},
+    core_mempool::{
+        CoreMempool, EvictedTransaction, EvictionReason, MempoolTransaction,
+        ParkedTransactionStatus, TimelineState,
+    },
     tests::common::{add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool, TestTransaction},
 };
 use aptos_config::config::NodeConfig;
@@ -12,7 +15,7 @@ use aptos_types::mempool_status::MempoolStatusCode;
 use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
 use itertools::Itertools;
 use std::time::SystemTime;
-use std::{collections::HashSet, time::Duration};
+use std::{collections::HashSet, mem::size_of, time::Duration};
 
 #[test]
 fn test_transaction_ordering_only_seqnos() {
@@ -485,6 +488,27 @@ fn test_capacity() {
     assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_ok());
 }
 
+#[test]
+fn test_update_capacity() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_err());
+
+    // Raising the capacity makes room right away
+    config.mempool.capacity = 2;
+    pool.update_capacity(&config.mempool);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_ok());
+
+    // Lowering it keeps what's already there, but rejects new transactions
+    config.mempool.capacity = 1;
+    pool.update_capacity(&config.mempool);
+    assert_eq!(pool.get_transaction_store().get_transactions().len(), 1);
+    assert!(add_txn(&mut pool, TestTransaction::new(2, 0, 1)).is_err());
+}
+
 #[test]
 fn test_capacity_bytes() {
     let capacity_bytes = 2_048;
@@ -785,3 +809,160 @@ fn test_sequence_number_behavior_at_capacity() {
     let batch = pool.get_batch(10, 10240, HashSet::new());
     assert_eq!(batch.len(), 1);
 }
+
+#[test]
+fn test_max_sequence_number_horizon() {
+    let mut config = NodeConfig::random();
+    config.mempool.max_sequence_number_horizon = 5;
+    let mut pool = CoreMempool::new(&config);
+
+    add_txn(&mut pool, TestTransaction::new(1, 5, 1)).unwrap();
+    let txn = TestTransaction::new(1, 6, 1).make_signed_transaction();
+    let status = pool.add_txn(
+        txn,
+        1,
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    );
+    assert_eq!(status.code, MempoolStatusCode::SequenceNumberTooNew);
+
+    // The horizon moves with the account's sequence number
+    let txn = TestTransaction::new(1, 6, 1).make_signed_transaction();
+    let status = pool.add_txn(
+        txn,
+        1,
+        AccountSequenceInfo::Sequential(1),
+        TimelineState::NotReady,
+    );
+    assert_eq!(status.code, MempoolStatusCode::Accepted);
+}
+
+#[test]
+fn test_parked_transaction_notifications() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+    let (sender, mut receiver) = futures::channel::mpsc::channel(10);
+    pool.set_parked_transaction_sender(sender);
+
+    // Sequence numbers 2 and 5 are parked
+    for seq in &[0, 2, 5] {
+        add_txn(&mut pool, TestTransaction::new(1, *seq, 1)).unwrap();
+    }
+    assert!(receiver.try_next().is_err());
+
+    // Mempool is full, so 5 is evicted to make space for 1, which makes 2 ready
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+
+    let events: Vec<_> = std::iter::from_fn(|| receiver.try_next().ok().flatten())
+        .map(|event| (event.sequence_number, event.status))
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            (5, ParkedTransactionStatus::Evicted),
+            (2, ParkedTransactionStatus::Ready),
+        ]
+    );
+}
+
+#[test]
+fn test_evicted_transactions() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    let mut pool = CoreMempool::new(&config);
+
+    // Mempool is full, so the parked 5 is evicted to make space for 1
+    for seq in &[0, 2, 5] {
+        add_txn(&mut pool, TestTransaction::new(1, *seq, 1)).unwrap();
+    }
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+    let evicted_hash = TestTransaction::new(1, 5, 1)
+        .make_signed_transaction()
+        .committed_hash();
+    let evicted = pool.get_evicted_by_hash(evicted_hash).unwrap();
+    assert_eq!(evicted.reason, EvictionReason::MempoolFull);
+    assert_eq!(evicted.txn.sequence_number(), 5);
+
+    // Bumping the gas unit price replaces the transaction
+    let replaced_hash = TestTransaction::new(1, 2, 1)
+        .make_signed_transaction()
+        .committed_hash();
+    add_txn(&mut pool, TestTransaction::new(1, 2, 2)).unwrap();
+    let evicted = pool.get_evicted_by_hash(replaced_hash).unwrap();
+    assert_eq!(evicted.reason, EvictionReason::Replaced);
+
+    // Transactions in mempool aren't evicted
+    let hash = TestTransaction::new(1, 0, 1)
+        .make_signed_transaction()
+        .committed_hash();
+    assert!(pool.get_evicted_by_hash(hash).is_none());
+
+    // Expired transactions are evicted
+    let mut pool = setup_mempool().0;
+    let txn = TestTransaction::new(1, 0, 1).make_signed_transaction_with_expiration_time(0);
+    pool.add_txn(
+        txn.clone(),
+        1,
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    );
+    pool.gc_by_expiration_time(Duration::from_secs(1));
+    let hash = txn.clone().committed_hash();
+    let evicted = pool.get_evicted_by_hash(hash).unwrap();
+    assert_eq!(evicted.reason, EvictionReason::Expired);
+
+    // Resubmitting a transaction clears its eviction
+    pool.add_txn(
+        txn,
+        1,
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    );
+    assert!(pool.get_evicted_by_hash(hash).is_none());
+}
+
+#[test]
+fn test_evicted_transactions_disabled() {
+    let mut config = NodeConfig::random();
+    config.mempool.evicted_transaction_retention_secs = 0;
+    let mut pool = CoreMempool::new(&config);
+
+    let txn = TestTransaction::new(1, 0, 1).make_signed_transaction_with_expiration_time(0);
+    pool.add_txn(
+        txn.clone(),
+        1,
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    );
+    pool.gc_by_expiration_time(Duration::from_secs(1));
+    assert!(pool.get_evicted_by_hash(txn.committed_hash()).is_none());
+}
+
+#[test]
+fn test_evicted_transactions_capacity() {
+    let txns: Vec<_> = (0..3)
+        .map(|seq| TestTransaction::new(1, seq, 1).make_signed_transaction_with_expiration_time(0))
+        .collect();
+
+    // The capacity fits two of the evicted transactions, so the oldest one is forgotten
+    let evicted_txn_bytes = txns[0].raw_txn_bytes_len()
+        + size_of::<HashValue>()
+        + size_of::<EvictedTransaction>()
+        + size_of::<(SystemTime, HashValue)>();
+    let mut config = NodeConfig::random();
+    config.mempool.evicted_transactions_capacity_bytes = 2 * evicted_txn_bytes;
+    let mut pool = CoreMempool::new(&config);
+    for txn in &txns {
+        pool.add_txn(
+            txn.clone(),
+            1,
+            AccountSequenceInfo::Sequential(0),
+            TimelineState::NotReady,
+        );
+        pool.gc_by_expiration_time(Duration::from_secs(1));
+    }
+    assert!(pool.get_evicted_by_hash(txns[0].committed_hash()).is_none());
+    assert!(pool.get_evicted_by_hash(txns[1].committed_hash()).is_some());
+    assert!(pool.get_evicted_by_hash(txns[2].committed_hash()).is_some());
+}
@@ -24,6 +24,8 @@ use async_trait::async_trait;
 use std::{sync::Arc, time::Duration};
 use thiserror::Error;
 
+pub mod node_information;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Aptos network rpc error: {0}")]
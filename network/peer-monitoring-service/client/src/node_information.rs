@@ -0,0 +1,294 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracks the build and supported versions of the peers of this node, as gossiped through the
+//! peer monitoring service, and reports how much of the stake runs builds that are ready for an
+//! upgrade (e.g. a new gas feature version) before the upgrade is enabled on chain.
+//!
+//! Validators are matched with peers by their account address, which is their peer id on the
+//! validator network. The readiness report is therefore only complete when taken on a validator.
+
+use crate::PeerMonitoringServiceClient;
+use aptos_config::{config::PeerMonitoringServiceConfig, network_id::PeerNetworkId};
+use aptos_event_notifications::ReconfigNotificationListener;
+use aptos_infallible::RwLock;
+use aptos_logger::prelude::*;
+use aptos_peer_monitoring_service_types::{NodeInformationResponse, PeerMonitoringServiceRequest};
+use aptos_types::{on_chain_config::ValidatorSet, PeerId};
+use futures::{FutureExt, StreamExt};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    time::Duration,
+};
+
+/// The label used for builds without a known commit hash
+const UNKNOWN_BUILD: &str = "unknown";
+
+static NODE_INFORMATION: Lazy<RwLock<NodeInformationStore>> =
+    Lazy::new(|| RwLock::new(NodeInformationStore::default()));
+
+/// The node information of this node and its peers, along with the stake of the validators
+#[derive(Default)]
+struct NodeInformationStore {
+    local: Option<(PeerId, NodeInformationResponse)>,
+    peers: HashMap<PeerNetworkId, NodeInformationResponse>,
+    validator_voting_powers: Vec<(PeerId, u64)>,
+}
+
+impl NodeInformationStore {
+    /// Returns the node information of the given peer, on any network
+    fn get(&self, peer_id: &PeerId) -> Option<&NodeInformationResponse> {
+        match &self.local {
+            Some((local_peer_id, node_information)) if local_peer_id == peer_id => {
+                Some(node_information)
+            }
+            _ => self
+                .peers
+                .iter()
+                .find(|(peer_network_id, _)| peer_network_id.peer_id() == *peer_id)
+                .map(|(_, node_information)| node_information),
+        }
+    }
+}
+
+/// Sets the node information of this node, so that its own stake counts in readiness reports
+pub fn set_local_node_information(peer_id: PeerId, node_information: NodeInformationResponse) {
+    NODE_INFORMATION.write().local = Some((peer_id, node_information));
+}
+
+/// Returns the number of connected peers running each build, by commit hash
+pub fn peer_build_versions() -> BTreeMap<String, u64> {
+    let mut build_versions = BTreeMap::new();
+    for node_information in NODE_INFORMATION.read().peers.values() {
+        let build = node_information
+            .build_commit_hash()
+            .cloned()
+            .unwrap_or_else(|| UNKNOWN_BUILD.to_string());
+        *build_versions.entry(build).or_insert(0) += 1;
+    }
+    build_versions
+}
+
+/// Returns the number of connected peers supporting each gas feature version
+pub fn peer_gas_feature_versions() -> BTreeMap<u64, u64> {
+    let mut gas_feature_versions = BTreeMap::new();
+    for node_information in NODE_INFORMATION.read().peers.values() {
+        *gas_feature_versions
+            .entry(node_information.gas_feature_version)
+            .or_insert(0) += 1;
+    }
+    gas_feature_versions
+}
+
+/// Returns the readiness of the current validators for the given gas feature version
+pub fn upgrade_readiness_report(required_gas_feature_version: u64) -> UpgradeReadinessReport {
+    let store = NODE_INFORMATION.read();
+    UpgradeReadinessReport::new(
+        required_gas_feature_version,
+        &store.validator_voting_powers,
+        |peer_id| store.get(peer_id),
+    )
+}
+
+/// The readiness of the validators for an upgrade requiring a gas feature version
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct UpgradeReadinessReport {
+    pub required_gas_feature_version: u64,
+    pub total_voting_power: u128,
+    pub ready_voting_power: u128, // The stake on builds supporting the required version
+    pub unknown_voting_power: u128, // The stake of validators whose build isn't known
+    pub ready_stake_fraction: f64,
+    pub validators: Vec<ValidatorReadiness>,
+}
+
+/// The readiness of a single validator
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ValidatorReadiness {
+    pub peer_id: PeerId,
+    pub voting_power: u64,
+    pub build_commit_hash: Option<String>,
+    pub gas_feature_version: Option<u64>,
+    pub ready: bool,
+}
+
+impl UpgradeReadinessReport {
+    pub fn new<'a>(
+        required_gas_feature_version: u64,
+        validator_voting_powers: &[(PeerId, u64)],
+        node_information: impl Fn(&PeerId) -> Option<&'a NodeInformationResponse>,
+    ) -> Self {
+        let mut total_voting_power = 0;
+        let mut ready_voting_power = 0;
+        let mut unknown_voting_power = 0;
+        let mut validators = vec![];
+        for (peer_id, voting_power) in validator_voting_powers {
+            let node_information = node_information(peer_id);
+            let gas_feature_version =
+                node_information.map(|node_information| node_information.gas_feature_version);
+            let ready = gas_feature_version
+                .map(|version| version >= required_gas_feature_version)
+                .unwrap_or(false);
+
+            total_voting_power += *voting_power as u128;
+            if ready {
+                ready_voting_power += *voting_power as u128;
+            }
+            if node_information.is_none() {
+                unknown_voting_power += *voting_power as u128;
+            }
+            validators.push(ValidatorReadiness {
+                peer_id: *peer_id,
+                voting_power: *voting_power,
+                build_commit_hash: node_information
+                    .and_then(|node_information| node_information.build_commit_hash().cloned()),
+                gas_feature_version,
+                ready,
+            });
+        }
+
+        let ready_stake_fraction = if total_voting_power == 0 {
+            0.0
+        } else {
+            ready_voting_power as f64 / total_voting_power as f64
+        };
+        Self {
+            required_gas_feature_version,
+            total_voting_power,
+            ready_voting_power,
+            unknown_voting_power,
+            ready_stake_fraction,
+            validators,
+        }
+    }
+}
+
+/// Periodically fetches the node information of all connected peers, and tracks the stake of
+/// the validators through reconfigurations.
+pub async fn start_node_information_poller(
+    config: PeerMonitoringServiceConfig,
+    peer_monitoring_client: PeerMonitoringServiceClient,
+    mut reconfig_events: ReconfigNotificationListener,
+) {
+    let mut poll_interval = tokio::time::interval(Duration::from_millis(
+        config.node_information_poll_interval_ms,
+    ));
+    let request_timeout = Duration::from_millis(config.node_information_request_timeout_ms);
+    loop {
+        futures::select! {
+            _ = poll_interval.tick().fuse() => {
+                poll_peers(&peer_monitoring_client, request_timeout).await;
+            }
+            notification = reconfig_events.select_next_some() => {
+                match notification.on_chain_configs.get::<ValidatorSet>() {
+                    Ok(validator_set) => {
+                        NODE_INFORMATION.write().validator_voting_powers = validator_set
+                            .payload()
+                            .map(|validator| {
+                                (*validator.account_address(), validator.consensus_voting_power())
+                            })
+                            .collect();
+                    }
+                    Err(error) => {
+                        warn!("Failed to read the validator set on reconfiguration: {}", error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fetches the node information of the connected peers, replacing that of the previous poll
+async fn poll_peers(peer_monitoring_client: &PeerMonitoringServiceClient, timeout: Duration) {
+    let peer_metadata = &peer_monitoring_client.peer_metadata;
+    let connected_peers: Vec<PeerNetworkId> = peer_metadata
+        .networks()
+        .flat_map(|network| peer_metadata.read_all(network))
+        .filter(|(_, peer_info)| peer_info.is_connected())
+        .map(|(peer_network_id, _)| peer_network_id)
+        .collect();
+
+    let requests = connected_peers
+        .into_iter()
+        .map(|peer_network_id| async move {
+            let response = peer_monitoring_client
+                .send_request(
+                    peer_network_id,
+                    PeerMonitoringServiceRequest::GetNodeInformation,
+                    timeout,
+                )
+                .await;
+            let node_information = match response {
+                Ok(response) => {
+                    NodeInformationResponse::try_from(response).map_err(|error| error.to_string())
+                }
+                Err(error) => Err(error.to_string()),
+            };
+            (peer_network_id, node_information)
+        });
+
+    let mut peers = HashMap::new();
+    for (peer_network_id, node_information) in futures::future::join_all(requests).await {
+        match node_information {
+            Ok(node_information) => {
+                peers.insert(peer_network_id, node_information);
+            }
+            Err(error) => {
+                debug!(
+                    "Failed to fetch the node information of peer {}: {}",
+                    peer_network_id, error
+                );
+            }
+        }
+    }
+    NODE_INFORMATION.write().peers = peers;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_build_info::BUILD_COMMIT_HASH;
+
+    fn node_information(commit_hash: &str, gas_feature_version: u64) -> NodeInformationResponse {
+        let mut build_information = BTreeMap::new();
+        build_information.insert(BUILD_COMMIT_HASH.to_string(), commit_hash.to_string());
+        NodeInformationResponse {
+            build_information,
+            gas_feature_version,
+            binary_format_version: 6,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_readiness_report() {
+        let (ready, not_ready, unknown) = (PeerId::random(), PeerId::random(), PeerId::random());
+        let mut peers = HashMap::new();
+        peers.insert(ready, node_information("new", 6));
+        peers.insert(not_ready, node_information("old", 5));
+
+        let report = UpgradeReadinessReport::new(
+            6,
+            &[(ready, 60), (not_ready, 30), (unknown, 10)],
+            |peer_id| peers.get(peer_id),
+        );
+        assert_eq!(report.total_voting_power, 100);
+        assert_eq!(report.ready_voting_power, 60);
+        assert_eq!(report.unknown_voting_power, 10);
+        assert!((report.ready_stake_fraction - 0.6).abs() < f64::EPSILON);
+        assert_eq!(
+            report.validators[0].build_commit_hash,
+            Some("new".to_string())
+        );
+        assert!(!report.validators[1].ready);
+        assert_eq!(report.validators[2].gas_feature_version, None);
+    }
+
+    #[test]
+    fn test_empty_validator_set() {
+        let report = UpgradeReadinessReport::new(6, &[], |_| None);
+        assert_eq!(report.ready_stake_fraction, 0.0);
+        assert!(report.validators.is_empty());
+    }
+}
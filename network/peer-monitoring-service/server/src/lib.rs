@@ -13,8 +13,9 @@ use aptos_config::config::PeerMonitoringServiceConfig;
 use aptos_logger::prelude::*;
 use aptos_network::{application::storage::PeerMetadataStorage, ProtocolId};
 use aptos_peer_monitoring_service_types::{
-    ConnectedPeersResponse, PeerMonitoringServiceError, PeerMonitoringServiceRequest,
-    PeerMonitoringServiceResponse, Result, ServerProtocolVersionResponse,
+    ConnectedPeersResponse, NodeInformationResponse, PeerMonitoringServiceError,
+    PeerMonitoringServiceRequest, PeerMonitoringServiceResponse, Result,
+    ServerProtocolVersionResponse,
 };
 use futures::stream::StreamExt;
 use serde::{Deserialize, Serialize};
@@ -54,6 +55,7 @@ impl Error {
 pub struct PeerMonitoringServiceServer {
     bounded_executor: BoundedExecutor,
     network_requests: PeerMonitoringServiceNetworkEvents,
+    node_information: NodeInformationResponse,
     peer_metadata: Arc<PeerMetadataStorage>,
 }
 
@@ -62,6 +64,7 @@ impl PeerMonitoringServiceServer {
         config: PeerMonitoringServiceConfig,
         executor: Handle,
         network_requests: PeerMonitoringServiceNetworkEvents,
+        node_information: NodeInformationResponse,
         peer_metadata: Arc<PeerMetadataStorage>,
     ) -> Self {
         let bounded_executor =
@@ -70,6 +73,7 @@ impl PeerMonitoringServiceServer {
         Self {
             bounded_executor,
             network_requests,
+            node_information,
             peer_metadata,
         }
     }
@@ -89,10 +93,12 @@ impl PeerMonitoringServiceServer {
 
             // All handler methods are currently CPU-bound so we want
             // to spawn on the blocking thread pool.
+            let node_information = self.node_information.clone();
             let peer_metadata = self.peer_metadata.clone();
             self.bounded_executor
                 .spawn_blocking(move || {
-                    let response = Handler::new(peer_metadata).call(protocol, request);
+                    let response =
+                        Handler::new(node_information, peer_metadata).call(protocol, request);
                     log_monitoring_service_response(&response);
                     response_sender.send(response);
                 })
@@ -106,12 +112,19 @@ impl PeerMonitoringServiceServer {
 /// request. We usually clone/create a new handler for every request.
 #[derive(Clone)]
 pub struct Handler {
+    node_information: NodeInformationResponse,
     peer_metadata: Arc<PeerMetadataStorage>,
 }
 
 impl Handler {
-    pub fn new(peer_metadata: Arc<PeerMetadataStorage>) -> Self {
-        Self { peer_metadata }
+    pub fn new(
+        node_information: NodeInformationResponse,
+        peer_metadata: Arc<PeerMetadataStorage>,
+    ) -> Self {
+        Self {
+            node_information,
+            peer_metadata,
+        }
     }
 
     pub fn call(
@@ -140,6 +153,7 @@ impl Handler {
                 self.get_depth_from_validators()
             }
             PeerMonitoringServiceRequest::GetKnownPeers => self.get_known_peers(),
+            PeerMonitoringServiceRequest::GetNodeInformation => self.get_node_information(),
             PeerMonitoringServiceRequest::GetServerProtocolVersion => {
                 self.get_server_protocol_version()
             }
@@ -198,11 +212,21 @@ impl Handler {
     }
 
     fn get_depth_from_validators(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        Err(Error::InvalidRequest(
+            "Requests for the depth from the validators aren't supported yet".into(),
+        ))
     }
 
     fn get_known_peers(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        Err(Error::InvalidRequest(
+            "Requests for the known peers aren't supported yet".into(),
+        ))
+    }
+
+    fn get_node_information(&self) -> Result<PeerMonitoringServiceResponse, Error> {
+        Ok(PeerMonitoringServiceResponse::NodeInformation(
+            self.node_information.clone(),
+        ))
     }
 
     fn get_server_protocol_version(&self) -> Result<PeerMonitoringServiceResponse, Error> {
@@ -214,11 +238,13 @@ impl Handler {
     }
 
     fn get_validators_and_vfns(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        Err(Error::InvalidRequest(
+            "Requests for the validators and VFNs aren't supported yet".into(),
+        ))
     }
 
     fn handle_ping(&self) -> Result<PeerMonitoringServiceResponse, Error> {
-        unimplemented!();
+        Err(Error::InvalidRequest("Pings aren't supported yet".into()))
     }
 }
 
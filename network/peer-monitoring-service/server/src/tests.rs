@@ -27,13 +27,14 @@ use aptos_network::{
     transport::{ConnectionId, ConnectionMetadata},
 };
 use aptos_peer_monitoring_service_types::{
-    ConnectedPeersResponse, PeerMonitoringServiceError, PeerMonitoringServiceMessage,
-    PeerMonitoringServiceRequest, PeerMonitoringServiceResponse, ServerProtocolVersionResponse,
+    ConnectedPeersResponse, NodeInformationResponse, PeerMonitoringServiceError,
+    PeerMonitoringServiceMessage, PeerMonitoringServiceRequest, PeerMonitoringServiceResponse,
+    ServerProtocolVersionResponse,
 };
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use futures::channel::oneshot;
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, BTreeMap, HashMap},
     str::FromStr,
     sync::Arc,
 };
@@ -56,6 +57,21 @@ async fn test_get_server_protocol_version() {
     assert_eq!(response, expected_response);
 }
 
+#[tokio::test]
+async fn test_get_node_information() {
+    // Create the peer monitoring client and server
+    let (mut mock_client, service, _) = MockClient::new();
+    tokio::spawn(service.start());
+
+    // Process a request to fetch the node information
+    let request = PeerMonitoringServiceRequest::GetNodeInformation;
+    let response = mock_client.send_request(request).await.unwrap();
+
+    // Verify the response is correct
+    let expected_response = PeerMonitoringServiceResponse::NodeInformation(node_information());
+    assert_eq!(response, expected_response);
+}
+
 #[tokio::test]
 async fn test_get_connected_peers() {
     // Create the peer monitoring client and server
@@ -123,6 +139,27 @@ async fn test_get_connected_peers() {
     assert_eq!(response, expected_response);
 }
 
+#[tokio::test]
+async fn test_unsupported_requests() {
+    // Create the peer monitoring client and server
+    let (mut mock_client, service, _) = MockClient::new();
+    tokio::spawn(service.start());
+
+    // Verify the requests that aren't supported yet are rejected
+    for request in [
+        PeerMonitoringServiceRequest::GetDepthFromValidators,
+        PeerMonitoringServiceRequest::GetKnownPeers,
+        PeerMonitoringServiceRequest::GetValidatorsAndVFNs,
+        PeerMonitoringServiceRequest::Ping,
+    ] {
+        let error = mock_client.send_request(request).await.unwrap_err();
+        assert!(matches!(
+            error,
+            PeerMonitoringServiceError::InvalidRequest(_)
+        ));
+    }
+}
+
 /// A wrapper around the inbound network interface/channel for easily sending
 /// mock client requests to a [`PeerMonitoringServiceServer`].
 struct MockClient {
@@ -155,6 +192,7 @@ impl MockClient {
             peer_monitoring_service_config,
             executor,
             network_request_stream,
+            node_information(),
             peer_metadata_storage.clone(),
         );
 
@@ -203,6 +241,20 @@ impl MockClient {
     }
 }
 
+/// Returns the node information served in tests
+fn node_information() -> NodeInformationResponse {
+    let mut build_information = BTreeMap::new();
+    build_information.insert(
+        aptos_build_info::BUILD_COMMIT_HASH.to_string(),
+        "abcdef".to_string(),
+    );
+    NodeInformationResponse {
+        build_information,
+        gas_feature_version: 6,
+        binary_format_version: 6,
+    }
+}
+
 /// Initializes the Aptos logger for tests
 pub fn initialize_logger() {
     aptos_logger::Logger::builder()
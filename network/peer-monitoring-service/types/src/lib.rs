@@ -3,10 +3,14 @@
 
 #![forbid(unsafe_code)]
 
+use aptos_build_info::BUILD_COMMIT_HASH;
 use aptos_config::network_id::PeerNetworkId;
 use aptos_network::application::types::PeerInfo;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, convert::TryFrom};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+};
 use thiserror::Error;
 
 pub type Result<T, E = PeerMonitoringServiceError> = ::std::result::Result<T, E>;
@@ -36,6 +40,7 @@ pub enum PeerMonitoringServiceRequest {
     GetConnectedPeers,        // Returns all connected peers
     GetDepthFromValidators,   // Returns the depth of the node from the validators
     GetKnownPeers,            // Returns all of the known peers in the network
+    GetNodeInformation,       // Returns the build and supported versions of the node
     GetServerProtocolVersion, // Fetches the protocol version run by the server
     GetValidatorsAndVFNs,     // Returns the current validators and VFNs
     Ping, // A simple message used by the client to ensure liveness and measure latency
@@ -48,6 +53,7 @@ impl PeerMonitoringServiceRequest {
             Self::GetConnectedPeers => "get_connected_peers",
             Self::GetDepthFromValidators => "get_depth_from_validators",
             Self::GetKnownPeers => "get_known_peers",
+            Self::GetNodeInformation => "get_node_information",
             Self::GetServerProtocolVersion => "get_server_protocol_version",
             Self::GetValidatorsAndVFNs => "get_validators_and_vfns",
             Self::Ping => "ping",
@@ -62,6 +68,7 @@ pub enum PeerMonitoringServiceResponse {
     ConnectedPeers(ConnectedPeersResponse), // Holds all currently connected peers
     DepthFromValidators(DepthFromValidatorsResponse), // Holds the min depth from the validators
     KnownPeers(KnownPeersResponse),         // Holds all currently known peers
+    NodeInformation(NodeInformationResponse), // Holds the build and supported versions of the node
     Ping(PingResponse), // A simple message to respond to liveness checks (i.e., pings)
     ServerProtocolVersion(ServerProtocolVersionResponse), // Returns the current server protocol version
     ValidatorsAndVFNs(ValidatorsAndVFNsResponse), // Holds the current validator set and VFNs
@@ -74,6 +81,7 @@ impl PeerMonitoringServiceResponse {
             Self::ConnectedPeers(_) => "connected_peers",
             Self::DepthFromValidators(_) => "depth_from_validators",
             Self::KnownPeers(_) => "known_peers",
+            Self::NodeInformation(_) => "node_information",
             Self::Ping(_) => "ping",
             Self::ServerProtocolVersion(_) => "server_protocol_version",
             Self::ValidatorsAndVFNs(_) => "validators_and_vfns",
@@ -99,6 +107,21 @@ pub struct KnownPeersResponse {
     pub todo: bool,
 }
 
+/// A response for the node information request
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NodeInformationResponse {
+    pub build_information: BTreeMap<String, String>, // The build information of the node
+    pub gas_feature_version: u64, // The latest gas feature version supported by the node
+    pub binary_format_version: u32, // The latest Move binary format version supported by the node
+}
+
+impl NodeInformationResponse {
+    /// Returns the commit hash the node was built from, if known
+    pub fn build_commit_hash(&self) -> Option<&String> {
+        self.build_information.get(BUILD_COMMIT_HASH)
+    }
+}
+
 /// A response for the ping request
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct PingResponse {
@@ -160,6 +183,19 @@ impl TryFrom<PeerMonitoringServiceResponse> for KnownPeersResponse {
     }
 }
 
+impl TryFrom<PeerMonitoringServiceResponse> for NodeInformationResponse {
+    type Error = UnexpectedResponseError;
+    fn try_from(response: PeerMonitoringServiceResponse) -> Result<Self, Self::Error> {
+        match response {
+            PeerMonitoringServiceResponse::NodeInformation(inner) => Ok(inner),
+            _ => Err(UnexpectedResponseError(format!(
+                "expected node_information_response, found {}",
+                response.get_label()
+            ))),
+        }
+    }
+}
+
 impl TryFrom<PeerMonitoringServiceResponse> for PingResponse {
     type Error = UnexpectedResponseError;
     fn try_from(response: PeerMonitoringServiceResponse) -> Result<Self, Self::Error> {
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    bcs,
+    bcs, clock,
     move_types::{
         identifier::Identifier,
         language_storage::{ModuleId, TypeTag},
@@ -15,10 +15,7 @@ use crate::{
     },
 };
 use anyhow::{Context, Result};
-use std::{
-    str::FromStr,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::str::FromStr;
 
 use crate::rest_client::Client as ApiClient;
 
@@ -62,11 +59,7 @@ impl<'a> CoinClient<'a> {
                     bcs::to_bytes(&amount).unwrap(),
                 ],
             )),
-            SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs()
-                + options.timeout_secs,
+            clock::now_secs() + options.timeout_secs,
             ChainId::new(chain_id),
         )
         .sender(from_account.address())
@@ -0,0 +1,676 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bcs, clock,
+    move_types::{identifier::Identifier, language_storage::ModuleId},
+    rest_client::{Client as ApiClient, PendingTransaction},
+    transaction_builder::TransactionBuilder,
+    types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{EntryFunction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use anyhow::{bail, ensure, format_err, Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const STAKING_CONTRACT_MODULE: &str = "staking_contract";
+const STAKING_CONTRACT_STORE: &str = "0x1::staking_contract::Store";
+const STAKE_POOL_RESOURCE: &str = "0x1::stake::StakePool";
+
+/// Client for delegating stake to an operator through `0x1::staking_contract`. The staker owns
+/// the stake pool of each of their staking contracts, and the operator runs it in exchange for
+/// a commission on the rewards.
+#[derive(Clone, Debug)]
+pub struct DelegationClient<'a> {
+    api_client: &'a ApiClient,
+}
+
+impl<'a> DelegationClient<'a> {
+    pub fn new(api_client: &'a ApiClient) -> Self {
+        Self { api_client }
+    }
+
+    /// Adds `amount` coins from the staker to the stake pool of their staking contract with
+    /// `operator`.
+    pub async fn add_stake(
+        &self,
+        staker: &mut LocalAccount,
+        operator: AccountAddress,
+        amount: u64,
+        options: Option<DelegationOptions>,
+    ) -> Result<PendingTransaction> {
+        self.submit(
+            staker,
+            "add_stake",
+            vec![
+                bcs::to_bytes(&operator).unwrap(),
+                bcs::to_bytes(&amount).unwrap(),
+            ],
+            options,
+        )
+        .await
+    }
+
+    /// Unlocks `amount` coins of the staker's active stake, after paying the operator's
+    /// commission. They can be withdrawn once the stake pool's lockup expires.
+    pub async fn unlock(
+        &self,
+        staker: &mut LocalAccount,
+        operator: AccountAddress,
+        amount: u64,
+        options: Option<DelegationOptions>,
+    ) -> Result<PendingTransaction> {
+        self.submit(
+            staker,
+            "unlock_stake",
+            vec![
+                bcs::to_bytes(&operator).unwrap(),
+                bcs::to_bytes(&amount).unwrap(),
+            ],
+            options,
+        )
+        .await
+    }
+
+    /// Withdraws all the unlocked stake of the staking contract, paying out the staker and the
+    /// operator their pending distributions.
+    pub async fn withdraw(
+        &self,
+        staker: &mut LocalAccount,
+        operator: AccountAddress,
+        options: Option<DelegationOptions>,
+    ) -> Result<PendingTransaction> {
+        self.submit(
+            staker,
+            "withdraw",
+            vec![bcs::to_bytes(&operator).unwrap()],
+            options,
+        )
+        .await
+    }
+
+    /// Returns the staking contract between `staker` and `operator`.
+    pub async fn get_staking_contract(
+        &self,
+        staker: AccountAddress,
+        operator: AccountAddress,
+    ) -> Result<StakingContract> {
+        let resource = self
+            .api_client
+            .get_account_resource(staker, STAKING_CONTRACT_STORE)
+            .await
+            .context("Failed to get staking contracts")?
+            .into_inner()
+            .ok_or_else(|| format_err!("No staking contracts for {}", staker))?;
+        let entries = resource.data["staking_contracts"]["data"]
+            .as_array()
+            .ok_or_else(|| format_err!("Invalid staking contracts: {}", resource.data))?;
+        match entries.iter().find(|entry| {
+            entry["key"]
+                .as_str()
+                .and_then(|key| AccountAddress::from_hex_literal(key).ok())
+                == Some(operator)
+        }) {
+            Some(entry) => StakingContract::from_json(&entry["value"]),
+            None => bail!("No staking contract between {} and {}", staker, operator),
+        }
+    }
+
+    /// Returns the active stake of the staking contract between `staker` and `operator`, along
+    /// with the rewards accumulated since the last commission payment.
+    pub async fn get_rewards(
+        &self,
+        staker: AccountAddress,
+        operator: AccountAddress,
+    ) -> Result<StakingContractAmounts> {
+        let staking_contract = self.get_staking_contract(staker, operator).await?;
+        let resource = self
+            .api_client
+            .get_account_resource(staking_contract.pool_address, STAKE_POOL_RESOURCE)
+            .await
+            .context("Failed to get stake pool")?
+            .into_inner()
+            .ok_or_else(|| format_err!("No stake pool at {}", staking_contract.pool_address))?;
+        staking_contract.amounts(
+            parse_u64(&resource.data["active"]["value"])?,
+            parse_u64(&resource.data["pending_active"]["value"])?,
+        )
+    }
+
+    async fn submit(
+        &self,
+        staker: &mut LocalAccount,
+        function: &str,
+        args: Vec<Vec<u8>>,
+        options: Option<DelegationOptions>,
+    ) -> Result<PendingTransaction> {
+        let options = options.unwrap_or_default();
+
+        let chain_id = self
+            .api_client
+            .get_index()
+            .await
+            .context("Failed to get chain ID")?
+            .inner()
+            .chain_id;
+        let transaction_builder = TransactionBuilder::new(
+            TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(
+                    AccountAddress::ONE,
+                    Identifier::new(STAKING_CONTRACT_MODULE).unwrap(),
+                ),
+                Identifier::new(function).unwrap(),
+                vec![],
+                args,
+            )),
+            clock::now_secs() + options.timeout_secs,
+            ChainId::new(chain_id),
+        )
+        .sender(staker.address())
+        .sequence_number(staker.sequence_number())
+        .max_gas_amount(options.max_gas_amount)
+        .gas_unit_price(options.gas_unit_price);
+        let signed_txn = staker.sign_with_transaction_builder(transaction_builder);
+        Ok(self
+            .api_client
+            .submit(&signed_txn)
+            .await
+            .with_context(|| format!("Failed to submit {} transaction", function))?
+            .into_inner())
+    }
+}
+
+pub struct DelegationOptions {
+    pub max_gas_amount: u64,
+
+    pub gas_unit_price: u64,
+
+    /// This is the number of seconds from now you're willing to wait for the
+    /// transaction to be committed.
+    pub timeout_secs: u64,
+}
+
+impl Default for DelegationOptions {
+    fn default() -> Self {
+        Self {
+            max_gas_amount: 5_000,
+            gas_unit_price: 100,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// Mirror of `0x1::staking_contract::StakingContract`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakingContract {
+    /// Principal recorded after the last commission payment
+    pub principal: u64,
+    pub pool_address: AccountAddress,
+    pub commission_percentage: u64,
+    /// Unlocked stake owed to the staker and the operator, paid out by `withdraw`
+    pub distribution_pool: SharePool,
+}
+
+impl StakingContract {
+    /// Parses the JSON representation of a `staking_contract::StakingContract` returned by the
+    /// REST API.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let pool_address = value["pool_address"]
+            .as_str()
+            .ok_or_else(|| format_err!("Missing pool address in staking contract: {}", value))?;
+        Ok(Self {
+            principal: parse_u64(&value["principal"])?,
+            pool_address: AccountAddress::from_hex_literal(pool_address)?,
+            commission_percentage: parse_u64(&value["commission_percentage"])?,
+            distribution_pool: SharePool::from_json(&value["distribution_pool"])?,
+        })
+    }
+
+    /// Computes the amounts of the staking contract from the `active` and `pending_active`
+    /// stake of its stake pool, as `staking_contract::staking_contract_amounts` does.
+    pub fn amounts(&self, active: u64, pending_active: u64) -> Result<StakingContractAmounts> {
+        let total_active_stake = active
+            .checked_add(pending_active)
+            .ok_or_else(|| format_err!("Total active stake cannot exceed u64.max"))?;
+        let accumulated_rewards = total_active_stake
+            .checked_sub(self.principal)
+            .ok_or_else(|| format_err!("Active stake is below the recorded principal"))?;
+        let commission_amount =
+            multiply_then_divide(accumulated_rewards, self.commission_percentage, 100)?;
+        Ok(StakingContractAmounts {
+            total_active_stake,
+            accumulated_rewards,
+            commission_amount,
+        })
+    }
+}
+
+/// Amounts of a staking contract, as returned by `staking_contract::staking_contract_amounts`
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StakingContractAmounts {
+    pub total_active_stake: u64,
+    /// Rewards accumulated since the last commission payment, including the commission
+    pub accumulated_rewards: u64,
+    /// The operator's share of the accumulated rewards
+    pub commission_amount: u64,
+}
+
+/// Mirror of `aptos_std::pool_u64::Pool`, which staking contracts use to track the
+/// distributions owed to the staker and the operator. The conversions between shares and coins
+/// round exactly as the Move module does, and fail where it aborts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SharePool {
+    pub total_coins: u64,
+    pub total_shares: u64,
+    pub scaling_factor: u64,
+    pub shares: BTreeMap<AccountAddress, u64>,
+}
+
+impl Default for SharePool {
+    fn default() -> Self {
+        Self::with_scaling_factor(1)
+    }
+}
+
+impl SharePool {
+    pub fn with_scaling_factor(scaling_factor: u64) -> Self {
+        Self {
+            total_coins: 0,
+            total_shares: 0,
+            scaling_factor,
+            shares: BTreeMap::new(),
+        }
+    }
+
+    /// Parses the JSON representation of a `pool_u64::Pool` returned by the REST API.
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let mut shares = BTreeMap::new();
+        let entries = value["shares"]["data"]
+            .as_array()
+            .ok_or_else(|| format_err!("Missing shares in pool: {}", value))?;
+        for entry in entries {
+            let shareholder = entry["key"]
+                .as_str()
+                .ok_or_else(|| format_err!("Invalid shareholder: {}", entry))?;
+            shares.insert(
+                AccountAddress::from_hex_literal(shareholder)?,
+                parse_u64(&entry["value"])?,
+            );
+        }
+        Ok(Self {
+            total_coins: parse_u64(&value["total_coins"])?,
+            total_shares: parse_u64(&value["total_shares"])?,
+            scaling_factor: parse_u64(&value["scaling_factor"])?,
+            shares,
+        })
+    }
+
+    pub fn shares(&self, shareholder: &AccountAddress) -> u64 {
+        self.shares.get(shareholder).copied().unwrap_or(0)
+    }
+
+    /// Returns the number of coins the shares of `shareholder` are worth.
+    pub fn balance(&self, shareholder: &AccountAddress) -> Result<u64> {
+        self.shares_to_amount(self.shares(shareholder))
+    }
+
+    pub fn amount_to_shares(&self, coins_amount: u64) -> Result<u64> {
+        self.amount_to_shares_with_total_coins(coins_amount, self.total_coins)
+    }
+
+    pub fn amount_to_shares_with_total_coins(
+        &self,
+        coins_amount: u64,
+        total_coins: u64,
+    ) -> Result<u64> {
+        if self.total_coins == 0 || self.total_shares == 0 {
+            coins_amount
+                .checked_mul(self.scaling_factor)
+                .ok_or_else(|| format_err!("Scaled coins amount cannot exceed u64.max"))
+        } else {
+            multiply_then_divide(coins_amount, self.total_shares, total_coins)
+        }
+    }
+
+    pub fn shares_to_amount(&self, shares: u64) -> Result<u64> {
+        self.shares_to_amount_with_total_coins(shares, self.total_coins)
+    }
+
+    pub fn shares_to_amount_with_total_coins(&self, shares: u64, total_coins: u64) -> Result<u64> {
+        if self.total_coins == 0 || self.total_shares == 0 {
+            Ok(0)
+        } else {
+            multiply_then_divide(shares, total_coins, self.total_shares)
+        }
+    }
+
+    /// Adds `coins_amount` coins for `shareholder` in exchange for new shares, returning the
+    /// number of new shares.
+    pub fn buy_in(&mut self, shareholder: AccountAddress, coins_amount: u64) -> Result<u64> {
+        if coins_amount == 0 {
+            return Ok(0);
+        }
+
+        let new_shares = self.amount_to_shares(coins_amount)?;
+        ensure!(
+            u64::MAX - self.total_coins >= coins_amount,
+            "Pool's total coins cannot exceed u64.max"
+        );
+        ensure!(
+            u64::MAX - self.total_shares >= new_shares,
+            "Pool's total shares cannot exceed u64.max"
+        );
+
+        self.total_coins += coins_amount;
+        self.total_shares += new_shares;
+        if new_shares > 0 {
+            *self.shares.entry(shareholder).or_insert(0) += new_shares;
+        }
+        Ok(new_shares)
+    }
+
+    /// Redeems `shares_to_redeem` shares of `shareholder`, returning the coins they were worth.
+    pub fn redeem_shares(
+        &mut self,
+        shareholder: AccountAddress,
+        shares_to_redeem: u64,
+    ) -> Result<u64> {
+        let shares = match self.shares.get(&shareholder) {
+            Some(shares) => *shares,
+            None => bail!("Shareholder {} not found", shareholder),
+        };
+        ensure!(
+            shares >= shares_to_redeem,
+            "Cannot redeem more shares than the shareholder has"
+        );
+        if shares_to_redeem == 0 {
+            return Ok(0);
+        }
+
+        let redeemed_coins = self.shares_to_amount(shares_to_redeem)?;
+        let total_coins = self
+            .total_coins
+            .checked_sub(redeemed_coins)
+            .ok_or_else(|| format_err!("Cannot redeem more coins than the pool has"))?;
+        let total_shares = self
+            .total_shares
+            .checked_sub(shares_to_redeem)
+            .ok_or_else(|| format_err!("Cannot redeem more shares than the pool has"))?;
+        self.total_coins = total_coins;
+        self.total_shares = total_shares;
+        if shares == shares_to_redeem {
+            self.shares.remove(&shareholder);
+        } else {
+            self.shares.insert(shareholder, shares - shares_to_redeem);
+        }
+        Ok(redeemed_coins)
+    }
+}
+
+/// Computes `x * y / z` in u128, failing if the result doesn't fit in a u64.
+fn multiply_then_divide(x: u64, y: u64, z: u64) -> Result<u64> {
+    ensure!(z != 0, "Cannot divide by zero");
+    u64::try_from(x as u128 * y as u128 / z as u128)
+        .map_err(|_| format_err!("Result cannot exceed u64.max"))
+}
+
+/// u64s are serialized as strings in the JSON API.
+fn parse_u64(value: &Value) -> Result<u64> {
+    value
+        .as_str()
+        .ok_or_else(|| format_err!("Expected u64 string, found: {}", value))?
+        .parse()
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn address(i: u8) -> AccountAddress {
+        let mut bytes = [0; AccountAddress::LENGTH];
+        bytes[AccountAddress::LENGTH - 1] = i;
+        AccountAddress::new(bytes)
+    }
+
+    // Mirrors `pool_u64::test_buy_in_and_redeem`.
+    #[test]
+    fn test_buy_in_and_redeem() {
+        let mut pool = SharePool::default();
+
+        pool.buy_in(address(1), 1000).unwrap();
+        pool.buy_in(address(2), 2000).unwrap();
+        assert_eq!(pool.total_coins, 3000);
+        assert_eq!(pool.total_shares, 3000);
+        assert_eq!(pool.balance(&address(1)).unwrap(), 1000);
+        assert_eq!(pool.balance(&address(2)).unwrap(), 2000);
+
+        pool.total_coins = 5000;
+        assert_eq!(pool.balance(&address(1)).unwrap(), 1000 * 5000 / 3000);
+        assert_eq!(pool.balance(&address(2)).unwrap(), 2000 * 5000 / 3000);
+
+        let expected_shares = 1000 * 3000 / 5000;
+        pool.buy_in(address(3), 1000).unwrap();
+        assert_eq!(pool.shares(&address(3)), expected_shares);
+        assert_eq!(pool.balance(&address(3)).unwrap(), 1000);
+
+        pool.total_coins = 8000;
+        let all_shares = 3000 + expected_shares;
+        let expected_value_per_500_shares = 500 * 8000 / all_shares;
+        assert_eq!(
+            pool.redeem_shares(address(1), 500).unwrap(),
+            expected_value_per_500_shares
+        );
+        assert_eq!(
+            pool.redeem_shares(address(1), 500).unwrap(),
+            expected_value_per_500_shares
+        );
+        assert_eq!(
+            pool.redeem_shares(address(2), 2000).unwrap(),
+            expected_value_per_500_shares * 4
+        );
+
+        let shareholder_3_balance = expected_value_per_500_shares * 6 / 5 + 1;
+        assert_eq!(pool.balance(&address(3)).unwrap(), shareholder_3_balance);
+        assert_eq!(pool.total_coins, shareholder_3_balance);
+        assert_eq!(pool.shares.len(), 1);
+        let num_shares_3 = pool.shares(&address(3));
+        assert_eq!(
+            pool.redeem_shares(address(3), num_shares_3).unwrap(),
+            shareholder_3_balance
+        );
+        assert!(pool.shares.is_empty());
+        assert_eq!(pool.total_coins, 0);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let value = serde_json::json!({
+            "shareholders_limit": "10000",
+            "total_coins": "3000",
+            "total_shares": "300000",
+            "shares": {
+                "data": [
+                    { "key": "0x1", "value": "100000" },
+                    { "key": "0x2", "value": "200000" },
+                ]
+            },
+            "shareholders": ["0x1", "0x2"],
+            "scaling_factor": "100",
+        });
+        let pool = SharePool::from_json(&value).unwrap();
+        assert_eq!(pool.scaling_factor, 100);
+        assert_eq!(pool.balance(&address(1)).unwrap(), 1000);
+        assert_eq!(pool.balance(&address(2)).unwrap(), 2000);
+        assert!(SharePool::from_json(&serde_json::json!({})).is_err());
+    }
+
+    // Mirrors `pool_u64::test_buy_in_and_redeem_large_numbers`.
+    #[test]
+    fn test_buy_in_and_redeem_large_numbers() {
+        let mut pool = SharePool::default();
+        let half_max_u64 = u64::MAX / 2;
+        let shares_1 = pool.buy_in(address(1), half_max_u64).unwrap();
+        assert_eq!(shares_1, half_max_u64);
+        let shares_2 = pool.buy_in(address(2), half_max_u64 + 1).unwrap();
+        assert_eq!(shares_2, half_max_u64 + 1);
+        assert_eq!(pool.total_shares, u64::MAX);
+        assert_eq!(pool.total_coins, u64::MAX);
+        assert_eq!(
+            pool.redeem_shares(address(1), shares_1).unwrap(),
+            half_max_u64
+        );
+        assert_eq!(
+            pool.redeem_shares(address(2), shares_2).unwrap(),
+            half_max_u64 + 1
+        );
+        assert!(pool.buy_in(address(1), 1).is_ok());
+        assert!(pool.buy_in(address(2), u64::MAX).is_err());
+    }
+
+    // Mirrors `pool_u64::test_buy_in_and_redeem_large_numbers_with_scaling_factor`.
+    #[test]
+    fn test_buy_in_and_redeem_large_numbers_with_scaling_factor() {
+        let mut pool = SharePool::with_scaling_factor(100);
+        let coins_amount = u64::MAX / 100;
+        let shares = pool.buy_in(address(1), coins_amount).unwrap();
+        assert_eq!(pool.total_shares, coins_amount * 100);
+        assert_eq!(pool.total_coins, coins_amount);
+        assert_eq!(
+            pool.redeem_shares(address(1), shares).unwrap(),
+            coins_amount
+        );
+
+        // The scaled amount overflows where the Move module aborts.
+        assert!(pool.buy_in(address(1), coins_amount + 1).is_err());
+        assert_eq!(pool.total_coins, 0);
+    }
+
+    // Mirrors `pool_u64::test_buy_in_with_small_coins_amount`.
+    #[test]
+    fn test_buy_in_with_small_coins_amount() {
+        let mut pool = SharePool::default();
+        pool.buy_in(address(1), 100_000_000_000_000_000).unwrap();
+        assert_eq!(pool.buy_in(address(2), 1).unwrap(), 1);
+        pool.total_coins = pool.total_coins * 6 / 5;
+        assert_eq!(
+            pool.balance(&address(1)).unwrap(),
+            100_000_000_000_000_000 * 6 / 5 - 1
+        );
+        assert_eq!(pool.balance(&address(2)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_conversions_overflow() {
+        let pool = SharePool {
+            total_coins: 1,
+            total_shares: u64::MAX,
+            ..SharePool::default()
+        };
+        assert_eq!(pool.amount_to_shares(1).unwrap(), u64::MAX);
+        assert!(pool.amount_to_shares(2).is_err());
+        assert_eq!(pool.shares_to_amount(u64::MAX).unwrap(), 1);
+        assert_eq!(pool.shares_to_amount(u64::MAX - 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_redeem_shares_underflow() {
+        // The shareholder holds more shares than the pool, which the Move module never allows.
+        let mut pool = SharePool {
+            total_coins: 100,
+            total_shares: 100,
+            shares: [(address(1), 200)].into_iter().collect(),
+            ..SharePool::default()
+        };
+        assert!(pool.redeem_shares(address(1), 200).is_err());
+        assert_eq!(pool.total_coins, 100);
+        assert_eq!(pool.total_shares, 100);
+        assert_eq!(pool.shares(&address(1)), 200);
+    }
+
+    proptest! {
+        // The conversions round the same way as the Move module: multiply in u128, then
+        // divide and truncate, failing where the result doesn't fit in a u64.
+        #[test]
+        fn conversions_match_move(
+            total_coins in 1..u64::MAX,
+            total_shares in 1..u64::MAX,
+            amount in any::<u64>(),
+        ) {
+            let pool = SharePool {
+                total_coins,
+                total_shares,
+                ..SharePool::default()
+            };
+            let expected = amount as u128 * total_shares as u128 / total_coins as u128;
+            prop_assert_eq!(
+                pool.amount_to_shares(amount).ok(),
+                u64::try_from(expected).ok()
+            );
+            let expected = amount as u128 * total_coins as u128 / total_shares as u128;
+            prop_assert_eq!(
+                pool.shares_to_amount(amount).ok(),
+                u64::try_from(expected).ok()
+            );
+        }
+
+        // While the value of the pool doesn't change, shareholders redeem exactly the coins they
+        // bought in with, and redeeming all the shares empties the pool.
+        #[test]
+        fn buy_in_and_redeem_round_trip(
+            // Small enough that the scaled shares of all the amounts fit in a u64.
+            amounts in proptest::collection::vec(1..u64::MAX / 512, 1..8),
+            scaling_factor in 1..64u64,
+        ) {
+            let mut pool = SharePool::with_scaling_factor(scaling_factor);
+            for (i, amount) in amounts.iter().enumerate() {
+                pool.buy_in(address(i as u8), *amount).unwrap();
+            }
+            prop_assert_eq!(pool.total_coins, amounts.iter().sum::<u64>());
+            for (i, amount) in amounts.iter().enumerate() {
+                let shares = pool.shares(&address(i as u8));
+                prop_assert_eq!(pool.redeem_shares(address(i as u8), shares).unwrap(), *amount);
+            }
+            prop_assert_eq!(pool.total_coins, 0);
+            prop_assert_eq!(pool.total_shares, 0);
+            prop_assert!(pool.shares.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_staking_contract_amounts() {
+        let value = serde_json::json!({
+            "principal": "10000",
+            "pool_address": "0x1234",
+            "commission_percentage": "10",
+            "distribution_pool": {
+                "shareholders_limit": "20",
+                "total_coins": "0",
+                "total_shares": "0",
+                "shares": { "data": [] },
+                "shareholders": [],
+                "scaling_factor": "1",
+            },
+        });
+        let staking_contract = StakingContract::from_json(&value).unwrap();
+        assert_eq!(
+            staking_contract.pool_address,
+            AccountAddress::from_hex_literal("0x1234").unwrap()
+        );
+        assert_eq!(
+            staking_contract.amounts(10950, 100).unwrap(),
+            StakingContractAmounts {
+                total_active_stake: 11050,
+                accumulated_rewards: 1050,
+                commission_amount: 105,
+            }
+        );
+        assert!(staking_contract.amounts(9000, 0).is_err());
+        assert!(staking_contract.amounts(u64::MAX, 1).is_err());
+    }
+}
@@ -8,9 +8,12 @@
 //! This SDK provides all the necessary components for building on top of the Aptos Blockchain. Some of the important modules are:
 //!
 //! * `crypto` - Types used for signing and verifying
+//! * `delegation_client` - Helpers for delegating stake through staking contracts
+//! * `framework_errors` - The error constants declared by the framework modules
 //! * `move_types` - Includes types used when interacting with the Move VM
 //! * `rest_client` - The Aptos API Client, used for sending requests to the Aptos Blockchain.
 //! * `transaction_builder` - Includes helpers for constructing transactions
+//! * `transaction_submitter` - Submits transactions, resubmitting them when they expire
 //! * `types` - Includes types for Aptos on-chain data structures
 //!
 //! ## Example
@@ -21,20 +24,34 @@
 
 pub use bcs;
 
+mod clock;
+
+#[cfg(not(target_arch = "wasm32"))]
 pub mod coin_client;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod delegation_client;
+
 pub mod crypto {
     pub use aptos_crypto::*;
 }
 
+pub mod framework_errors {
+    pub use aptos_error_registry::*;
+}
+
 pub mod move_types {
     pub use move_core_types::*;
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod rest_client {
     pub use aptos_rest_client::*;
 }
 
 pub mod transaction_builder;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transaction_submitter;
+
 pub mod types;
@@ -5,15 +5,17 @@ use crate::{
     move_types::account_address::AccountAddress,
     types::{
         chain_id::ChainId,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, TransactionPayload},
+        transaction::{RawTransaction, TransactionPayload},
     },
 };
+#[cfg(not(target_arch = "wasm32"))]
 pub use aptos_cached_packages::aptos_stdlib;
+#[cfg(not(target_arch = "wasm32"))]
 use aptos_crypto::ed25519::Ed25519PublicKey;
 use aptos_global_constants::{GAS_UNIT_PRICE, MAX_GAS_AMOUNT};
-use aptos_types::transaction::{
-    authenticator::AuthenticationKeyPreimage, EntryFunction, ModuleBundle, Script,
-};
+#[cfg(not(target_arch = "wasm32"))]
+use aptos_types::transaction::authenticator::{AuthenticationKey, AuthenticationKeyPreimage};
+use aptos_types::transaction::{EntryFunction, ModuleBundle, Script};
 
 pub struct TransactionBuilder {
     sender: Option<AccountAddress>,
@@ -140,6 +142,35 @@ impl TransactionFactory {
         self.payload(TransactionPayload::EntryFunction(func))
     }
 
+    //
+    // Internal Helpers
+    //
+
+    pub fn script(&self, script: Script) -> TransactionBuilder {
+        self.payload(TransactionPayload::Script(script))
+    }
+
+    fn transaction_builder(&self, payload: TransactionPayload) -> TransactionBuilder {
+        TransactionBuilder {
+            sender: None,
+            sequence_number: None,
+            payload,
+            max_gas_amount: self.max_gas_amount,
+            gas_unit_price: self.gas_unit_price,
+            expiration_timestamp_secs: self.expiration_timestamp(),
+            chain_id: self.chain_id,
+        }
+    }
+
+    fn expiration_timestamp(&self) -> u64 {
+        crate::clock::now_secs() + self.transaction_expiration_time
+    }
+}
+
+// The builders of the framework entry functions come from the cached framework packages, which
+// aren't available on wasm32
+#[cfg(not(target_arch = "wasm32"))]
+impl TransactionFactory {
     pub fn create_user_account(&self, public_key: &Ed25519PublicKey) -> TransactionBuilder {
         let preimage = AuthenticationKeyPreimage::ed25519(public_key);
         self.payload(aptos_stdlib::aptos_account_create_account(
@@ -170,34 +201,6 @@ impl TransactionFactory {
     pub fn mint(&self, to: AccountAddress, amount: u64) -> TransactionBuilder {
         self.payload(aptos_stdlib::aptos_coin_mint(to, amount))
     }
-
-    //
-    // Internal Helpers
-    //
-
-    pub fn script(&self, script: Script) -> TransactionBuilder {
-        self.payload(TransactionPayload::Script(script))
-    }
-
-    fn transaction_builder(&self, payload: TransactionPayload) -> TransactionBuilder {
-        TransactionBuilder {
-            sender: None,
-            sequence_number: None,
-            payload,
-            max_gas_amount: self.max_gas_amount,
-            gas_unit_price: self.gas_unit_price,
-            expiration_timestamp_secs: self.expiration_timestamp(),
-            chain_id: self.chain_id,
-        }
-    }
-
-    fn expiration_timestamp(&self) -> u64 {
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs()
-            + self.transaction_expiration_time
-    }
 }
 
 pub struct DualAttestationMessage {
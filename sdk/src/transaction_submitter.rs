@@ -0,0 +1,138 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    rest_client::{error::RestError, Client as ApiClient, Transaction},
+    transaction_builder::TransactionFactory,
+    types::{transaction::TransactionPayload, LocalAccount},
+};
+use anyhow::{bail, Context, Result};
+
+/// Controls when a transaction that expired before being committed is rebuilt and submitted again
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ResubmissionPolicy {
+    /// How many times the transaction may be rebuilt and resubmitted after it expired.
+    /// With 0, an expired transaction is returned as an error, as with a plain submission.
+    pub max_resubmissions: u32,
+
+    /// Whether the transaction may be rebuilt with a later sequence number, when the one it
+    /// was signed with has been used by another transaction of the sender in the meantime.
+    /// This is off by default, as the other transaction may have been a copy of this one
+    /// submitted through a different route.
+    pub allow_sequence_number_change: bool,
+}
+
+impl Default for ResubmissionPolicy {
+    fn default() -> Self {
+        Self {
+            max_resubmissions: 2,
+            allow_sequence_number_change: false,
+        }
+    }
+}
+
+impl ResubmissionPolicy {
+    /// Returns the sequence number to rebuild an expired transaction with, or an error if it
+    /// is not safe to resubmit it.
+    pub fn sequence_number_for_resubmission(
+        &self,
+        expired_sequence_number: u64,
+        on_chain_sequence_number: u64,
+    ) -> Result<u64> {
+        if on_chain_sequence_number <= expired_sequence_number {
+            // Nothing has used the sequence number yet, so it can be signed again
+            Ok(expired_sequence_number)
+        } else if self.allow_sequence_number_change {
+            Ok(on_chain_sequence_number)
+        } else {
+            bail!(
+                "Transaction with sequence number {} expired, and the account has since moved on to sequence number {}",
+                expired_sequence_number,
+                on_chain_sequence_number
+            )
+        }
+    }
+}
+
+/// Submits transactions and waits for them, rebuilding them with a fresh expiration timestamp
+/// and sequence number when they expire before being committed.
+#[derive(Clone, Debug)]
+pub struct TransactionSubmitter<'a> {
+    api_client: &'a ApiClient,
+    policy: ResubmissionPolicy,
+}
+
+impl<'a> TransactionSubmitter<'a> {
+    pub fn new(api_client: &'a ApiClient, policy: ResubmissionPolicy) -> Self {
+        Self { api_client, policy }
+    }
+
+    /// Signs the payload with `account`, submits it and waits for it to be committed. The
+    /// expiration timestamp is taken from `transaction_factory` on every attempt, and the
+    /// sequence number of `account` is kept in sync with the transaction that was committed.
+    pub async fn submit_and_wait(
+        &self,
+        account: &mut LocalAccount,
+        transaction_factory: &TransactionFactory,
+        payload: TransactionPayload,
+    ) -> Result<Transaction> {
+        let mut resubmissions = 0;
+        loop {
+            let sequence_number = account.sequence_number();
+            let transaction =
+                account.sign_with_transaction_builder(transaction_factory.payload(payload.clone()));
+            let err = match self.api_client.submit_and_wait(&transaction).await {
+                Ok(response) => return Ok(response.into_inner()),
+                Err(err) => err,
+            };
+            if !err.is_transaction_expired() || resubmissions >= self.policy.max_resubmissions {
+                return Err(err_with_attempts(err, resubmissions));
+            }
+
+            let on_chain_sequence_number = self
+                .api_client
+                .get_account(account.address())
+                .await
+                .context("Failed to get sequence number for resubmission")?
+                .into_inner()
+                .sequence_number;
+            *account.sequence_number_mut() = self
+                .policy
+                .sequence_number_for_resubmission(sequence_number, on_chain_sequence_number)?;
+            resubmissions += 1;
+        }
+    }
+}
+
+fn err_with_attempts(err: RestError, resubmissions: u32) -> anyhow::Error {
+    if resubmissions == 0 {
+        err.into()
+    } else {
+        anyhow::Error::new(err).context(format!(
+            "Transaction failed after {} resubmissions",
+            resubmissions
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_number_for_resubmission() {
+        let policy = ResubmissionPolicy::default();
+        // The sequence number is still free, or earlier transactions are still pending
+        assert_eq!(policy.sequence_number_for_resubmission(5, 5).unwrap(), 5);
+        assert_eq!(policy.sequence_number_for_resubmission(5, 3).unwrap(), 5);
+        // Another transaction used it
+        assert!(policy.sequence_number_for_resubmission(5, 6).is_err());
+
+        let policy = ResubmissionPolicy {
+            allow_sequence_number_change: true,
+            ..Default::default()
+        };
+        assert_eq!(policy.sequence_number_for_resubmission(5, 5).unwrap(), 5);
+        assert_eq!(policy.sequence_number_for_resubmission(5, 7).unwrap(), 7);
+    }
+}
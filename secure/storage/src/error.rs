@@ -9,6 +9,8 @@ use thiserror::Error;
 pub enum Error {
     #[error("Entropy error: {0}")]
     EntropyError(String),
+    #[error("Compare and set failed, the value of the key was updated: {0}")]
+    CompareAndSetFailed(String),
     #[error("Internal error: {0}")]
     InternalError(String),
     #[error("Key already exists: {0}")]
@@ -52,6 +52,17 @@ impl KVStorage for GitHubStorage {
         Ok(())
     }
 
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        _key: &str,
+        _expected: Option<T>,
+        _value: T,
+    ) -> Result<(), Error> {
+        Err(Error::InternalError(
+            "Compare and set is not supported by GitHub storage".into(),
+        ))
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         self.client.delete_directory("/").map_err(|e| e.into())
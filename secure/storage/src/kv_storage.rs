@@ -22,6 +22,16 @@ pub trait KVStorage {
     /// invalid permissions.
     fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), Error>;
 
+    /// Sets a value in storage only if the value currently stored at the key is `expected` (or
+    /// the key isn't set, if `expected` is None), so that concurrent updaters of the key can't
+    /// overwrite each other. Fails with `Error::CompareAndSetFailed` if the stored value differs.
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<T>,
+        value: T,
+    ) -> Result<(), Error>;
+
     /// Resets and clears all data held in the storage engine.
     /// Note: this should only be exposed and used for testing. Resetting the storage engine is not
     /// something that should be supported in production.
@@ -45,12 +55,33 @@ where
         S::set(self, key, value)
     }
 
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<T>,
+        value: T,
+    ) -> Result<(), Error> {
+        S::compare_and_set(self, key, expected, value)
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         S::reset_and_clear(self)
     }
 }
 
+/// Returns the value currently stored at `key`, or None if the key isn't set
+pub(crate) fn current_value<S: KVStorage, T: DeserializeOwned>(
+    storage: &S,
+    key: &str,
+) -> Result<Option<T>, Error> {
+    match storage.get(key) {
+        Ok(response) => Ok(Some(response.value)),
+        Err(Error::KeyNotSet(_)) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
 /// A container for a get response that contains relevant metadata and the value stored at the
 /// given key.
 #[derive(Debug, Deserialize, PartialEq, Eq, Serialize)]
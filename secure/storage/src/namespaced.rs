@@ -71,6 +71,16 @@ impl<S: KVStorage> KVStorage for Namespaced<S> {
         self.inner.set(&self.namespaced(key), value)
     }
 
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<T>,
+        value: T,
+    ) -> Result<(), Error> {
+        self.inner
+            .compare_and_set(&self.namespaced(key), expected, value)
+    }
+
     /// Note: This is not a namespace function
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{CryptoKVStorage, Error, GetResponse, KVStorage};
+use crate::{kv_storage::current_value, CryptoKVStorage, Error, GetResponse, KVStorage};
 use aptos_temppath::TempPath;
 use aptos_time_service::{TimeService, TimeServiceTrait};
 use serde::{de::DeserializeOwned, Serialize};
@@ -92,6 +92,19 @@ impl KVStorage for OnDiskStorage {
         self.write(&data)
     }
 
+    fn compare_and_set<V: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<V>,
+        value: V,
+    ) -> Result<(), Error> {
+        // Updates take a mutable reference, so nothing can update the key in between
+        if current_value::<_, V>(self, key)? != expected {
+            return Err(Error::CompareAndSetFailed(key.to_string()));
+        }
+        self.set(key, value)
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         self.write(&HashMap::new())
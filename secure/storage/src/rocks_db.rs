@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::rocks_db::database_schema::{SecureStorageKey, SecureStorageSchema, SecureStorageValue};
-use crate::{CryptoKVStorage, Error, GetResponse, KVStorage};
+use crate::{kv_storage::current_value, CryptoKVStorage, Error, GetResponse, KVStorage};
 use anyhow::{anyhow, Result};
 use aptos_crypto::_once_cell::sync::Lazy;
 use aptos_infallible::Mutex;
@@ -153,6 +153,19 @@ impl KVStorage for RocksDbStorage {
         self.set_key_value(secure_storage_key, secure_storage_value)
     }
 
+    fn compare_and_set<V: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<V>,
+        value: V,
+    ) -> Result<(), Error> {
+        // Updates take a mutable reference, so nothing can update the key in between
+        if current_value::<_, V>(self, key)? != expected {
+            return Err(Error::CompareAndSetFailed(key.to_string()));
+        }
+        self.set(key, value)
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         // Drop all references to the database
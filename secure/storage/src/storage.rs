@@ -38,6 +38,15 @@ impl KVStorage for Box<Storage> {
         Storage::set(self, key, value)
     }
 
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<T>,
+        value: T,
+    ) -> Result<(), Error> {
+        Storage::compare_and_set(self, key, expected, value)
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         Storage::reset_and_clear(self)
@@ -7,4 +7,5 @@ use crate::{tests::suite, InMemoryStorage, Storage};
 fn in_memory() {
     let mut storage = Storage::from(InMemoryStorage::new());
     suite::execute_all_storage_tests(&mut storage);
+    suite::test_compare_and_set(&mut storage);
 }
@@ -9,4 +9,5 @@ fn on_disk() {
     let path_buf = TempPath::new().path().to_path_buf();
     let mut storage = Storage::from(OnDiskStorage::new(path_buf));
     suite::execute_all_storage_tests(&mut storage);
+    suite::test_compare_and_set(&mut storage);
 }
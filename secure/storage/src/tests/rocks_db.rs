@@ -14,6 +14,7 @@ fn rocks_db() {
     let path_buf = TempPath::new().path().to_path_buf();
     let mut storage = Storage::from(RocksDbStorage::new(path_buf));
     suite::execute_all_storage_tests(&mut storage);
+    suite::test_compare_and_set(&mut storage);
 
     // Test concurrent storage creation
     test_concurrent_storage_creations();
@@ -48,6 +48,38 @@ pub fn execute_all_storage_tests(storage: &mut Storage) {
     }
 }
 
+/// This test checks that compare and set only updates a key if it holds the expected value. It
+/// isn't part of the canonical list, as not every backend supports compare and set.
+pub fn test_compare_and_set(storage: &mut Storage) {
+    storage.reset_and_clear().unwrap();
+
+    let u64_1 = 10;
+    let u64_2 = 647;
+    assert_eq!(
+        storage.compare_and_set(U64_KEY, Some(u64_1), u64_2),
+        Err(Error::CompareAndSetFailed(U64_KEY.to_string()))
+    );
+    storage.compare_and_set(U64_KEY, None, u64_1).unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, u64_1);
+
+    assert_eq!(
+        storage.compare_and_set(U64_KEY, None, u64_2),
+        Err(Error::CompareAndSetFailed(U64_KEY.to_string()))
+    );
+    assert_eq!(
+        storage.compare_and_set(U64_KEY, Some(u64_2), u64_2),
+        Err(Error::CompareAndSetFailed(U64_KEY.to_string()))
+    );
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, u64_1);
+
+    storage
+        .compare_and_set(U64_KEY, Some(u64_1), u64_2)
+        .unwrap();
+    assert_eq!(storage.get::<u64>(U64_KEY).unwrap().value, u64_2);
+
+    storage.reset_and_clear().unwrap();
+}
+
 /// This test tries to set a key, reset the storage and then retrieve its
 // value, checking that the reset is indeed performed in testing mode. It
 /// should be performed first, as other tests will depend on it.
@@ -69,6 +69,7 @@ fn execute_storage_tests_vault() {
 fn test_suite_no_namespaces() {
     let mut storage = Storage::from(create_vault());
     suite::execute_all_storage_tests(&mut storage);
+    suite::test_compare_and_set(&mut storage);
 }
 
 /// Runs the test suite on a VaultStorage instance that supports multiple distinct namespaces.
@@ -81,6 +82,7 @@ fn test_suite_multiple_namespaces() {
     suite::execute_all_storage_tests(&mut storage_1);
     suite::execute_all_storage_tests(&mut storage_2);
     suite::execute_all_storage_tests(&mut storage_3);
+    suite::test_compare_and_set(&mut storage_1);
 }
 
 /// Creates and initializes a VaultStorage instance for testing. If a namespace is specified, the
@@ -182,6 +182,42 @@ impl KVStorage for VaultStorage {
         Ok(())
     }
 
+    fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+        &mut self,
+        key: &str,
+        expected: Option<T>,
+        value: T,
+    ) -> Result<(), Error> {
+        let secret = key;
+        let key = self.unnamespaced(key);
+        // The write is conditioned on the version of the secret that was compared, Vault rejects
+        // it if the secret was written in between. Version 0 means the secret doesn't exist.
+        let (current, version) = match self.client().read_secret(secret, key) {
+            Ok(resp) => (Some(serde_json::from_value::<T>(resp.value)?), resp.version),
+            Err(aptos_vault_client::Error::NotFound(_, _)) => (None, 0),
+            Err(error) => return Err(error.into()),
+        };
+        if current != expected {
+            return Err(Error::CompareAndSetFailed(key.to_string()));
+        }
+        let new_version = match self.client().write_secret(
+            secret,
+            key,
+            &serde_json::to_value(&value)?,
+            Some(version),
+        ) {
+            Ok(new_version) => new_version,
+            Err(aptos_vault_client::Error::HttpError(400, _, _)) => {
+                return Err(Error::CompareAndSetFailed(key.to_string()))
+            }
+            Err(error) => return Err(error.into()),
+        };
+        self.secret_versions
+            .write()
+            .insert(key.to_string(), new_version);
+        Ok(())
+    }
+
     #[cfg(any(test, feature = "testing"))]
     fn reset_and_clear(&mut self) -> Result<(), Error> {
         self.secret_versions.write().clear();
@@ -460,6 +496,16 @@ pub mod policy {
             self.vault.set(&secret, value)
         }
 
+        fn compare_and_set<T: DeserializeOwned + PartialEq + Serialize>(
+            &mut self,
+            key: &str,
+            expected: Option<T>,
+            value: T,
+        ) -> Result<(), Error> {
+            let secret = self.secret_name(key);
+            self.vault.compare_and_set(&secret, expected, value)
+        }
+
         fn reset_and_clear(&mut self) -> Result<(), Error> {
             self.vault.reset_and_clear()?;
             self.reset_policies()
@@ -4,6 +4,7 @@
 use crate::metrics::ExecutingComponent;
 use crate::utils::OutputFallbackHandler;
 use crate::{
+    bootstrapping_progress::{update_bootstrapping_progress, BootstrappingPhase},
     driver::DriverConfiguration,
     error::Error,
     logging::{LogEntry, LogSchema},
@@ -355,6 +356,14 @@ impl<
         self.driver_configuration.config.bootstrapping_mode
     }
 
+    /// Updates the bootstrapping phase reported by the node
+    fn update_bootstrapping_phase(&self, phase: BootstrappingPhase) {
+        let bootstrapping_mode = self.get_bootstrapping_mode();
+        update_bootstrapping_progress(|progress, now| {
+            progress.set_phase(bootstrapping_mode, phase, now)
+        });
+    }
+
     /// Returns true iff the node has already completed bootstrapping
     pub fn is_bootstrapped(&self) -> bool {
         self.bootstrapped
@@ -365,6 +374,7 @@ impl<
         info!(LogSchema::new(LogEntry::Bootstrapper)
             .message("The node has successfully bootstrapped!"));
         self.bootstrapped = true;
+        self.update_bootstrapping_phase(BootstrappingPhase::Bootstrapped);
         self.notify_listeners_if_bootstrapped().await
     }
 
@@ -448,6 +458,7 @@ impl<
 
         // Always fetch the new epoch ending ledger infos first
         if self.should_fetch_epoch_ending_ledger_infos() {
+            self.update_bootstrapping_phase(BootstrappingPhase::FetchingEpochEndingLedgerInfos);
             return self
                 .fetch_epoch_ending_ledger_infos(global_data_summary)
                 .await;
@@ -639,6 +650,10 @@ impl<
 
         // Fetch the data that we're missing
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
+        self.update_bootstrapping_phase(BootstrappingPhase::DownloadingStates);
+        update_bootstrapping_progress(|progress, _| {
+            progress.set_target_version(target_ledger_info_version)
+        });
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
             // Fetch the transaction info first, before the states
             self.streaming_client
@@ -671,6 +686,9 @@ impl<
             // Fetch the missing state values
             self.state_value_syncer
                 .update_next_state_index_to_process(next_state_index_to_process);
+            update_bootstrapping_progress(|progress, now| {
+                progress.update_synced_states(next_state_index_to_process, now)
+            });
             self.streaming_client
                 .get_all_state_values(
                     target_ledger_info_version,
@@ -697,6 +715,11 @@ impl<
             .verified_epoch_states
             .next_epoch_ending_version(highest_synced_version)
             .expect("No higher epoch ending version known!");
+        self.update_bootstrapping_phase(BootstrappingPhase::SyncingTransactions);
+        update_bootstrapping_progress(|progress, now| {
+            progress.set_target_version(highest_known_ledger_version);
+            progress.update_synced_version(highest_synced_version, now);
+        });
         let data_stream = match self.get_bootstrapping_mode() {
             BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
                 self.streaming_client
@@ -1007,6 +1030,10 @@ impl<
                     "The next state value index to process has overflown!".into(),
                 )
             })?;
+        let synced_states = self.state_value_syncer.next_state_index_to_process;
+        update_bootstrapping_progress(|progress, now| {
+            progress.update_synced_states(synced_states, now)
+        });
 
         Ok(())
     }
@@ -1208,6 +1235,9 @@ impl<
             .ok_or_else(|| Error::IntegerOverflow("The synced version has overflown!".into()))?;
         self.get_speculative_stream_state()
             .update_synced_version(synced_version);
+        update_bootstrapping_progress(|progress, now| {
+            progress.update_synced_version(synced_version, now)
+        });
 
         Ok(())
     }
@@ -0,0 +1,198 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::BootstrappingMode;
+use aptos_infallible::Mutex;
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The window over which the bootstrapping throughput is measured
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(60);
+
+/// The bootstrapping progress of this node, as reported by the bootstrapper.
+/// There is a single state sync driver per node, so the progress is global.
+static BOOTSTRAPPING_PROGRESS: Lazy<Mutex<BootstrappingProgressTracker>> =
+    Lazy::new(|| Mutex::new(BootstrappingProgressTracker::new()));
+
+/// Returns the current bootstrapping progress of the node
+pub fn bootstrapping_progress() -> BootstrappingProgress {
+    BOOTSTRAPPING_PROGRESS.lock().progress(Instant::now())
+}
+
+/// Updates the global bootstrapping progress using the given function
+pub(crate) fn update_bootstrapping_progress(
+    update: impl FnOnce(&mut BootstrappingProgressTracker, Instant),
+) {
+    update(&mut BOOTSTRAPPING_PROGRESS.lock(), Instant::now())
+}
+
+/// The phase the bootstrapper is currently in
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrappingPhase {
+    NotStarted,                     // The bootstrapper hasn't started yet
+    FetchingEpochEndingLedgerInfos, // Fetching and verifying the epoch changes up to the waypoint
+    DownloadingStates,              // Downloading the state snapshot at the target version
+    SyncingTransactions,            // Executing or applying transactions up to the target version
+    Bootstrapped,                   // Bootstrapping has completed
+}
+
+impl BootstrappingPhase {
+    pub fn to_label(&self) -> &'static str {
+        match self {
+            BootstrappingPhase::NotStarted => "not_started",
+            BootstrappingPhase::FetchingEpochEndingLedgerInfos => {
+                "fetching_epoch_ending_ledger_infos"
+            }
+            BootstrappingPhase::DownloadingStates => "downloading_states",
+            BootstrappingPhase::SyncingTransactions => "syncing_transactions",
+            BootstrappingPhase::Bootstrapped => "bootstrapped",
+        }
+    }
+}
+
+/// A snapshot of the bootstrapping progress of the node
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BootstrappingProgress {
+    /// The configured bootstrapping mode (once the bootstrapper has started)
+    pub bootstrapping_mode: Option<BootstrappingMode>,
+    pub phase: BootstrappingPhase,
+    /// The highest version synced by the bootstrapper
+    pub synced_version: Option<Version>,
+    /// The version the bootstrapper is syncing to
+    pub target_version: Option<Version>,
+    /// The number of state values downloaded for the state snapshot. The total
+    /// number of state values isn't known to the bootstrapper.
+    pub synced_states: u64,
+    /// The number of versions (or state values, when downloading states)
+    /// synced per second, measured over the last minute
+    pub throughput_per_sec: Option<f64>,
+    /// The estimated time until the target version is reached. Only known
+    /// when syncing transactions.
+    pub eta_secs: Option<u64>,
+}
+
+/// Tracks the bootstrapping progress and the throughput of the current phase
+pub(crate) struct BootstrappingProgressTracker {
+    bootstrapping_mode: Option<BootstrappingMode>,
+    phase: BootstrappingPhase,
+    synced_version: Option<Version>,
+    target_version: Option<Version>,
+    synced_states: u64,
+
+    // Samples of the progress (versions or states synced) in the current phase
+    progress_samples: VecDeque<(Instant, u64)>,
+}
+
+impl BootstrappingProgressTracker {
+    pub fn new() -> Self {
+        Self {
+            bootstrapping_mode: None,
+            phase: BootstrappingPhase::NotStarted,
+            synced_version: None,
+            target_version: None,
+            synced_states: 0,
+            progress_samples: VecDeque::new(),
+        }
+    }
+
+    /// Sets the bootstrapping mode and the current phase. The throughput
+    /// samples are reset if the phase changes.
+    pub fn set_phase(
+        &mut self,
+        bootstrapping_mode: BootstrappingMode,
+        phase: BootstrappingPhase,
+        now: Instant,
+    ) {
+        self.bootstrapping_mode = Some(bootstrapping_mode);
+        if self.phase != phase {
+            self.phase = phase;
+            self.progress_samples.clear();
+            self.record_sample(now);
+        }
+    }
+
+    /// Sets the version the bootstrapper is syncing to
+    pub fn set_target_version(&mut self, target_version: Version) {
+        self.target_version = Some(target_version);
+    }
+
+    /// Updates the highest version synced by the bootstrapper
+    pub fn update_synced_version(&mut self, synced_version: Version, now: Instant) {
+        self.synced_version = Some(synced_version);
+        self.record_sample(now);
+    }
+
+    /// Updates the number of state values downloaded
+    pub fn update_synced_states(&mut self, synced_states: u64, now: Instant) {
+        self.synced_states = synced_states;
+        self.record_sample(now);
+    }
+
+    /// Returns the progress measured by the current phase (if any)
+    fn current_progress(&self) -> Option<u64> {
+        match self.phase {
+            BootstrappingPhase::DownloadingStates => Some(self.synced_states),
+            BootstrappingPhase::SyncingTransactions => self.synced_version,
+            _ => None,
+        }
+    }
+
+    fn record_sample(&mut self, now: Instant) {
+        if let Some(progress) = self.current_progress() {
+            self.progress_samples.push_back((now, progress));
+            // Drop the samples that fell out of the window, but keep enough to measure
+            while self.progress_samples.len() > 2
+                && self.progress_samples.front().map_or(false, |(time, _)| {
+                    now.duration_since(*time) > THROUGHPUT_WINDOW
+                })
+            {
+                self.progress_samples.pop_front();
+            }
+        }
+    }
+
+    /// Returns the throughput of the current phase, per second. The throughput
+    /// is measured up to `now`, so that it drops if syncing stalls.
+    fn throughput_per_sec(&self, now: Instant) -> Option<f64> {
+        let (first_time, first_progress) = self.progress_samples.front()?;
+        let (last_time, last_progress) = self.progress_samples.back()?;
+        let elapsed_secs = now
+            .max(*last_time)
+            .duration_since(*first_time)
+            .as_secs_f64();
+        if elapsed_secs > 0.0 {
+            Some(last_progress.saturating_sub(*first_progress) as f64 / elapsed_secs)
+        } else {
+            None
+        }
+    }
+
+    pub fn progress(&self, now: Instant) -> BootstrappingProgress {
+        let throughput_per_sec = self.throughput_per_sec(now);
+        let eta_secs = match (self.phase, self.synced_version, self.target_version) {
+            (BootstrappingPhase::SyncingTransactions, Some(synced), Some(target)) => {
+                let remaining_versions = target.saturating_sub(synced);
+                throughput_per_sec
+                    .filter(|throughput| *throughput > 0.0)
+                    .map(|throughput| (remaining_versions as f64 / throughput).ceil() as u64)
+            }
+            _ => None,
+        };
+
+        BootstrappingProgress {
+            bootstrapping_mode: self.bootstrapping_mode,
+            phase: self.phase,
+            synced_version: self.synced_version,
+            target_version: self.target_version,
+            synced_states: self.synced_states,
+            throughput_per_sec,
+            eta_secs,
+        }
+    }
+}
@@ -19,15 +19,13 @@ use aptos_event_notifications::{EventNotificationSender, EventSubscriptionServic
 use aptos_executor_types::ChunkExecutorTrait;
 use aptos_infallible::Mutex;
 use aptos_mempool_notifications::MempoolNotificationSender;
+use aptos_runtimes::task_dump::track;
 use aptos_storage_interface::DbReaderWriter;
 use aptos_time_service::TimeService;
 use aptos_types::{move_resource::MoveStorage, waypoint::Waypoint};
 use futures::{channel::mpsc, executor::block_on};
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
-};
-use tokio::runtime::{Builder, Runtime};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
 
 /// Creates a new state sync driver and client
 pub struct DriverFactory {
@@ -85,16 +83,11 @@ impl DriverFactory {
         // Create a new runtime (if required)
         let driver_runtime = if create_runtime {
             Some(
-                Builder::new_multi_thread()
-                    .thread_name_fn(|| {
-                        static ATOMIC_ID: AtomicUsize = AtomicUsize::new(0);
-                        let id = ATOMIC_ID.fetch_add(1, Ordering::SeqCst);
-                        format!("sync-driver-{}", id)
-                    })
-                    .disable_lifo_slot()
-                    .enable_all()
-                    .build()
-                    .expect("Failed to create state sync v2 driver runtime!"),
+                aptos_runtimes::spawn_named_runtime(
+                    "sync-driver",
+                    &node_config.thread_pools.state_sync,
+                )
+                .expect("Failed to create state sync v2 driver runtime!"),
             )
         } else {
             None
@@ -139,10 +132,11 @@ impl DriverFactory {
         );
 
         // Spawn the driver
+        let driver = track("state-sync-driver", state_sync_driver.start_driver());
         if let Some(driver_runtime) = &driver_runtime {
-            driver_runtime.spawn(state_sync_driver.start_driver());
+            driver_runtime.spawn(driver);
         } else {
-            tokio::spawn(state_sync_driver.start_driver());
+            tokio::spawn(driver);
         }
 
         Self {
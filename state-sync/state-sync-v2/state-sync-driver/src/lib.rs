@@ -4,6 +4,7 @@
 #![forbid(unsafe_code)]
 
 mod bootstrapper;
+pub mod bootstrapping_progress;
 mod continuous_syncer;
 mod driver;
 mod driver_client;
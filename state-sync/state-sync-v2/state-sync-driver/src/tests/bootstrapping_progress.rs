@@ -0,0 +1,76 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::bootstrapping_progress::{BootstrappingPhase, BootstrappingProgressTracker};
+use aptos_config::config::BootstrappingMode;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_transaction_throughput_and_eta() {
+    // Create a tracker and start syncing transactions
+    let start_time = Instant::now();
+    let mut tracker = BootstrappingProgressTracker::new();
+    tracker.set_phase(
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        BootstrappingPhase::SyncingTransactions,
+        start_time,
+    );
+    tracker.set_target_version(1000);
+    tracker.update_synced_version(100, start_time);
+
+    // Verify nothing can be estimated yet
+    let progress = tracker.progress(start_time);
+    assert_eq!(progress.phase, BootstrappingPhase::SyncingTransactions);
+    assert_eq!(progress.synced_version, Some(100));
+    assert_eq!(progress.target_version, Some(1000));
+    assert_eq!(progress.throughput_per_sec, None);
+    assert_eq!(progress.eta_secs, None);
+
+    // Sync 200 versions in 10 seconds and verify the throughput and ETA
+    let time = start_time + Duration::from_secs(10);
+    tracker.update_synced_version(300, time);
+    let progress = tracker.progress(time);
+    assert_eq!(progress.throughput_per_sec, Some(20.0));
+    assert_eq!(progress.eta_secs, Some(35));
+
+    // Verify the throughput drops if syncing stalls
+    let progress = tracker.progress(start_time + Duration::from_secs(20));
+    assert_eq!(progress.throughput_per_sec, Some(10.0));
+    assert_eq!(progress.eta_secs, Some(70));
+}
+
+#[test]
+fn test_phase_change_resets_throughput() {
+    // Create a tracker and download some states
+    let start_time = Instant::now();
+    let mut tracker = BootstrappingProgressTracker::new();
+    tracker.set_phase(
+        BootstrappingMode::DownloadLatestStates,
+        BootstrappingPhase::DownloadingStates,
+        start_time,
+    );
+    tracker.set_target_version(5000);
+    let time = start_time + Duration::from_secs(10);
+    tracker.update_synced_states(500, time);
+
+    // Verify the state throughput (the ETA is unknown as the number of states is unknown)
+    let progress = tracker.progress(time);
+    assert_eq!(
+        progress.bootstrapping_mode,
+        Some(BootstrappingMode::DownloadLatestStates)
+    );
+    assert_eq!(progress.synced_states, 500);
+    assert_eq!(progress.throughput_per_sec, Some(50.0));
+    assert_eq!(progress.eta_secs, None);
+
+    // Complete bootstrapping and verify the throughput is reset
+    tracker.set_phase(
+        BootstrappingMode::DownloadLatestStates,
+        BootstrappingPhase::Bootstrapped,
+        time,
+    );
+    let progress = tracker.progress(time);
+    assert_eq!(progress.phase, BootstrappingPhase::Bootstrapped);
+    assert_eq!(progress.synced_states, 500);
+    assert_eq!(progress.throughput_per_sec, None);
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bootstrapper;
+mod bootstrapping_progress;
 mod continuous_syncer;
 mod driver;
 mod driver_factory;
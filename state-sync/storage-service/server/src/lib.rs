@@ -6,7 +6,10 @@
 use crate::metrics::increment_network_frame_overflow;
 use crate::{
     logging::{LogEntry, LogSchema},
-    metrics::{increment_counter, start_timer, LRU_CACHE_HIT, LRU_CACHE_PROBE},
+    metrics::{
+        increment_counter, increment_lru_cache_request_event, start_timer, LRU_CACHE_HIT,
+        LRU_CACHE_MISS, LRU_CACHE_PROBE,
+    },
     network::{ResponseSender, StorageServiceNetworkEvents},
 };
 use aptos_bounded_executor::BoundedExecutor;
@@ -827,11 +830,16 @@ impl<T: StorageReaderInterface> Handler<T> {
     ) -> Result<StorageServiceResponse, Error> {
         increment_counter(&metrics::LRU_CACHE_EVENT, protocol, LRU_CACHE_PROBE.into());
 
-        // Check if the response is already in the cache
+        // Check if the response is already in the cache. The hits and misses
+        // are also tracked per request type, as the hit rate differs widely
+        // between (e.g.) transaction and state value chunks.
+        let request_label = request.get_label();
         if let Some(response) = self.lru_storage_cache.lock().get(request) {
             increment_counter(&metrics::LRU_CACHE_EVENT, protocol, LRU_CACHE_HIT.into());
+            increment_lru_cache_request_event(protocol, &request_label, LRU_CACHE_HIT);
             return Ok(response.clone());
         }
+        increment_lru_cache_request_event(protocol, &request_label, LRU_CACHE_MISS);
 
         // Fetch the data response from storage
         let data_response = match &request.data_request {
@@ -9,6 +9,7 @@ use once_cell::sync::Lazy;
 
 /// Useful metric constants for the storage service
 pub const LRU_CACHE_HIT: &str = "lru_cache_hit";
+pub const LRU_CACHE_MISS: &str = "lru_cache_miss";
 pub const LRU_CACHE_PROBE: &str = "lru_cache_probe";
 
 /// Counter for lru cache events in the storage service (server-side)
@@ -21,6 +22,17 @@ pub static LRU_CACHE_EVENT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for lru cache hits and misses per request type in the storage
+/// service (server-side)
+pub static LRU_CACHE_REQUEST_EVENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_storage_service_server_lru_cache_requests",
+        "Counters for lru cache hits and misses per request type in the storage server",
+        &["protocol", "request_type", "event"]
+    )
+    .unwrap()
+});
+
 /// Counter for the number of times a storage response overflowed the network
 /// frame limit size and had to be retried.
 pub static NETWORK_FRAME_OVERFLOW: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -89,6 +101,13 @@ pub fn increment_network_frame_overflow(response_type: &str) {
         .inc()
 }
 
+/// Increments the lru cache hit or miss counter for the given request type
+pub fn increment_lru_cache_request_event(protocol: ProtocolId, request_type: &str, event: &str) {
+    LRU_CACHE_REQUEST_EVENT
+        .with_label_values(&[protocol.as_str(), request_type, event])
+        .inc();
+}
+
 /// Increments the given counter with the provided label values.
 pub fn increment_counter(counter: &Lazy<IntCounterVec>, protocol: ProtocolId, label: String) {
     counter
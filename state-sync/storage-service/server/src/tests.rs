@@ -3,7 +3,7 @@
 
 #![forbid(unsafe_code)]
 
-use crate::{network::StorageServiceNetworkEvents, StorageReader, StorageServiceServer};
+use crate::{metrics, network::StorageServiceNetworkEvents, StorageReader, StorageServiceServer};
 use anyhow::{format_err, Result};
 use aptos_bitvec::BitVec;
 use aptos_channels::aptos_channel;
@@ -202,6 +202,93 @@ async fn test_cachable_requests_eviction() {
         get_state_values_with_proof(&mut mock_client, version, start_index, end_index, true).await;
 }
 
+#[tokio::test]
+async fn test_cachable_requests_metrics() {
+    // Create test data
+    let versions = [10, 20, 30];
+    let number_of_states = 165;
+    let start_version = 0;
+    let end_version = 99;
+    let proof_version = end_version;
+    let include_events = false;
+    let transaction_list_with_proof = create_transaction_list_with_proof(
+        start_version,
+        end_version,
+        proof_version,
+        include_events,
+    );
+
+    // Create the mock db reader
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_state_leaf_count()
+        .times(versions.len())
+        .with(always())
+        .returning(move |_| Ok(number_of_states));
+    expect_get_transactions(
+        &mut db_reader,
+        start_version,
+        end_version - start_version + 1,
+        proof_version,
+        include_events,
+        transaction_list_with_proof,
+    );
+
+    // Create the storage client and server
+    let (mut mock_client, service, _) = MockClient::new(Some(db_reader), None);
+    tokio::spawn(service.start());
+
+    // Get the initial cache hits and misses for each request type
+    let states_request =
+        StorageServiceRequest::new(DataRequest::GetNumberOfStatesAtVersion(versions[0]), false);
+    let transactions_request = StorageServiceRequest::new(
+        DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version,
+            start_version,
+            end_version,
+            include_events,
+        }),
+        false,
+    );
+    let (states_hits, states_misses) = get_lru_cache_hits_and_misses(&states_request);
+    let (transactions_hits, transactions_misses) =
+        get_lru_cache_hits_and_misses(&transactions_request);
+
+    // Fetch the number of states at each version several times. Only the
+    // first request for each version should miss the cache.
+    for version in versions {
+        for _ in 0..4 {
+            get_number_of_states(&mut mock_client, version, false)
+                .await
+                .unwrap();
+        }
+    }
+
+    // Fetch the transactions twice, missing the cache once
+    for _ in 0..2 {
+        get_transactions_with_proof(
+            &mut mock_client,
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+            false,
+        )
+        .await
+        .unwrap();
+    }
+
+    // Verify the cache hits and misses were counted per request type
+    assert_eq!(
+        get_lru_cache_hits_and_misses(&states_request),
+        (states_hits + 9, states_misses + 3)
+    );
+    assert_eq!(
+        get_lru_cache_hits_and_misses(&transactions_request),
+        (transactions_hits + 1, transactions_misses + 1)
+    );
+}
+
 #[tokio::test]
 async fn test_cachable_requests_data_versions() {
     // Create test data
@@ -2475,6 +2562,24 @@ async fn send_storage_request(
     mock_client.process_request(storage_request).await
 }
 
+/// Returns the lru cache hits and misses counted for the type of the given request
+fn get_lru_cache_hits_and_misses(request: &StorageServiceRequest) -> (u64, u64) {
+    let request_label = request.get_label();
+    let get_count = |event| {
+        metrics::LRU_CACHE_REQUEST_EVENT
+            .with_label_values(&[
+                ProtocolId::StorageServiceRpc.as_str(),
+                &request_label,
+                event,
+            ])
+            .get()
+    };
+    (
+        get_count(metrics::LRU_CACHE_HIT),
+        get_count(metrics::LRU_CACHE_MISS),
+    )
+}
+
 /// Creates a mock db with the basic expectations required to handle subscription requests
 fn create_mock_db_for_subscription(
     highest_ledger_info_clone: LedgerInfoWithSignatures,
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    get_first_seq_num_and_limit,
+    get_first_seq_num_and_limit, parse_rocksdb_tickers,
     pruner::{
         ledger_pruner_manager::LedgerPrunerManager, state_pruner_manager::StatePrunerManager,
     },
@@ -87,6 +87,24 @@ fn test_too_many_requested() {
     assert!(db.get_transaction_outputs(0, 1001 /* limit */, 0).is_err());
 }
 
+#[test]
+fn test_parse_rocksdb_tickers() {
+    let statistics = "rocksdb.block.cache.miss COUNT : 12
+rocksdb.block.cache.hit COUNT : 30
+rocksdb.bloom.filter.useful COUNT : 7
+rocksdb.number.keys.written COUNT : 100
+rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 P99 : 3.000000 P100 : 4.000000 COUNT : 5 SUM : 6
+";
+    assert_eq!(
+        parse_rocksdb_tickers(statistics),
+        vec![
+            ("rocksdb.block.cache.miss", 12),
+            ("rocksdb.block.cache.hit", 30),
+            ("rocksdb.bloom.filter.useful", 7),
+        ]
+    );
+}
+
 #[test]
 fn test_pruner_config() {
     let tmp_dir = TempPath::new();
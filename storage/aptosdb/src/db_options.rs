@@ -17,6 +17,7 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         EPOCH_BY_VERSION_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
+        EVENT_BY_TYPE_CF_NAME,
         EVENT_BY_VERSION_CF_NAME,
         EVENT_CF_NAME,
         LEDGER_INFO_CF_NAME,
@@ -46,22 +47,22 @@ pub(super) fn state_merkle_db_column_families() -> Vec<ColumnFamilyName> {
 pub(super) fn gen_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = ledger_db_column_families();
     let mut cfds = Vec::with_capacity(cfs.len());
-    let mut table_options = BlockBasedOptions::default();
-    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
-    table_options.set_block_size(rocksdb_config.block_size as usize);
     let cache = Cache::new_lru_cache(rocksdb_config.block_cache_size as usize)
         .expect("Create Rocksdb block cache failed.");
-    table_options.set_block_cache(&cache);
+    let table_options = gen_table_options(rocksdb_config, &cache, false);
     for cf_name in cfs {
         let mut cf_opts = Options::default();
         cf_opts.set_compression_type(DBCompressionType::Lz4);
-        cf_opts.set_block_based_table_factory(&table_options);
         // set cf options separately
         if cf_name == STATE_VALUE_CF_NAME {
-            // TODO(lightmark): Use the defaults for bloom filter for now, will tune later.
+            // State values are looked up by seeking to the latest version of a state key, so the
+            // bloom filter is checked against the state key prefix.
             let prefix_extractor =
                 SliceTransform::create("state_key_extractor", state_key_extractor, None);
             cf_opts.set_prefix_extractor(prefix_extractor);
+            cf_opts.set_block_based_table_factory(&gen_table_options(rocksdb_config, &cache, true));
+        } else {
+            cf_opts.set_block_based_table_factory(&table_options);
         }
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
     }
@@ -70,22 +71,38 @@ pub(super) fn gen_ledger_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamil
 
 pub(super) fn gen_state_merkle_cfds(rocksdb_config: &RocksdbConfig) -> Vec<ColumnFamilyDescriptor> {
     let cfs = state_merkle_db_column_families();
-    let mut table_options = BlockBasedOptions::default();
-    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
-    table_options.set_block_size(rocksdb_config.block_size as usize);
     let cache = Cache::new_lru_cache(rocksdb_config.block_cache_size as usize)
         .expect("Create Rocksdb block cache failed.");
-    table_options.set_block_cache(&cache);
+    let table_options = gen_table_options(rocksdb_config, &cache, false);
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
         let mut cf_opts = Options::default();
         cf_opts.set_compression_type(DBCompressionType::Lz4);
-        cf_opts.set_block_based_table_factory(&table_options);
+        if cf_name == JELLYFISH_MERKLE_NODE_CF_NAME {
+            cf_opts.set_block_based_table_factory(&gen_table_options(rocksdb_config, &cache, true));
+        } else {
+            cf_opts.set_block_based_table_factory(&table_options);
+        }
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
     }
     cfds
 }
 
+fn gen_table_options(
+    rocksdb_config: &RocksdbConfig,
+    cache: &Cache,
+    with_bloom_filter: bool,
+) -> BlockBasedOptions {
+    let mut table_options = BlockBasedOptions::default();
+    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
+    table_options.set_block_size(rocksdb_config.block_size as usize);
+    table_options.set_block_cache(cache);
+    if with_bloom_filter && rocksdb_config.bloom_filter_bits_per_key > 0 {
+        table_options.set_bloom_filter(rocksdb_config.bloom_filter_bits_per_key as f64, false);
+    }
+    table_options
+}
+
 fn state_key_extractor(state_value_raw_key: &[u8]) -> &[u8] {
     &state_value_raw_key[..(state_value_raw_key.len() - VERSION_SIZE)]
 }
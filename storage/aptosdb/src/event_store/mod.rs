@@ -10,7 +10,8 @@ use crate::{
     errors::AptosDbError,
     schema::{
         event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema,
     },
     utils::iterators::EventsByVersionIter,
 };
@@ -29,6 +30,7 @@ use aptos_types::{
     proof::position::Position,
     transaction::Version,
 };
+use move_core_types::language_storage::TypeTag;
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::{TryFrom, TryInto},
@@ -187,6 +189,34 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given a type tag, return `(Version, index)` of up to `limit` events of that type emitted
+    /// at or after position `(start_version, start_index)`, where index is the event's index
+    /// among all events emitted by the same transaction. Result won't contain records with a
+    /// transaction version > `ledger_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64)>> {
+        let type_tag_hash = hash_type_tag(type_tag)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(type_tag_hash, start_version, start_index))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((hash, ver, idx), ()) = res?;
+            if hash != type_tag_hash || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -321,6 +351,10 @@ impl EventStore {
                 batch.put::<EventByVersionSchema>(
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
+                )?;
+                batch.put::<EventByTypeSchema>(
+                    &(hash_type_tag(event.type_tag())?, version, idx as u64),
+                    &(),
                 )
             })?;
 
@@ -468,6 +502,11 @@ impl EventStore {
                     event.sequence_number(),
                 ))?;
                 db_batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+                db_batch.delete::<EventByTypeSchema>(&(
+                    hash_type_tag(event.type_tag())?,
+                    current_version as u64,
+                    current_index as u64,
+                ))?;
                 db_batch.delete::<EventSchema>(&(current_version as u64, current_index as u64))?;
             }
             current_version += 1;
@@ -477,6 +516,11 @@ impl EventStore {
     }
 }
 
+/// Type tags have an unbounded size, so the index is keyed by their hash.
+fn hash_type_tag(type_tag: &TypeTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(type_tag)?))
+}
+
 struct EventHashReader<'a> {
     store: &'a EventStore,
     version: Version,
@@ -361,3 +361,62 @@ proptest! {
         test_get_last_version_before_timestamp_impl(new_block_events)
     }
 }
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_lookup_events_by_type(
+        events1 in vec(any::<ContractEvent>().no_shrink(), 1..20),
+        events2 in vec(any::<ContractEvent>().no_shrink(), 1..20),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.event_store;
+        save(store, 99 /*version*/, &events1);
+        save(store, 100 /*version*/, &events2);
+
+        let type_tag = events1[0].type_tag();
+        let expected: Vec<_> = [(99, &events1), (100, &events2)]
+            .iter()
+            .flat_map(|(version, events)| {
+                events
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, event)| event.type_tag() == type_tag)
+                    .map(move |(idx, _)| (*version, idx as u64))
+            })
+            .collect();
+        prop_assert_eq!(
+            store.lookup_events_by_type(type_tag, 0, 0, 100, 100).unwrap(),
+            expected.clone()
+        );
+
+        // respects the start version, ledger version and limit
+        let from_100: Vec<_> = expected.iter().copied().filter(|(v, _)| *v == 100).collect();
+        prop_assert_eq!(
+            store.lookup_events_by_type(type_tag, 100, 0, 100, 100).unwrap(),
+            from_100
+        );
+        let until_99: Vec<_> = expected.iter().copied().filter(|(v, _)| *v == 99).collect();
+        prop_assert_eq!(
+            store.lookup_events_by_type(type_tag, 0, 0, 100, 99).unwrap(),
+            until_99
+        );
+        prop_assert_eq!(
+            store.lookup_events_by_type(type_tag, 0, 0, 1, 100).unwrap(),
+            expected[..1].to_vec()
+        );
+
+        // resumes from the position of an event
+        let (version, index) = expected[expected.len() - 1];
+        prop_assert_eq!(
+            store.lookup_events_by_type(type_tag, version, index, 100, 100).unwrap(),
+            expected[expected.len() - 1..].to_vec()
+        );
+        prop_assert!(store
+            .lookup_events_by_type(type_tag, version, index + 1, 100, 100)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -46,7 +46,7 @@ use crate::{
     ledger_store::LedgerStore,
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
-        OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES,
+        OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES, ROCKSDB_TICKERS,
     },
     pruner::{pruner_manager::PrunerManager, pruner_utils},
     schema::*,
@@ -61,7 +61,7 @@ use aptos_config::config::{
     NO_OP_STORAGE_PRUNER_CONFIG,
 };
 
-use aptos_crypto::hash::HashValue;
+use aptos_crypto::hash::{CryptoHash, HashValue, TransactionAccumulatorHasher};
 use aptos_db_indexer::Indexer;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -95,6 +95,7 @@ use aptos_types::{
 };
 use aptos_vm::data_cache::AsMoveResolver;
 use itertools::zip_eq;
+use move_core_types::language_storage::TypeTag;
 use move_resource_viewer::MoveValueAnnotator;
 use once_cell::sync::Lazy;
 use std::{
@@ -117,8 +118,8 @@ use crate::{
     stale_node_index_cross_epoch::StaleNodeIndexCrossEpochSchema,
 };
 use aptos_storage_interface::{
-    state_delta::StateDelta, state_view::DbStateView, DbReader, DbWriter, ExecutedTrees, Order,
-    StateSnapshotReceiver, MAX_REQUEST_LIMIT,
+    state_delta::StateDelta, state_view::DbStateView, BlockTransaction, BlockWithTransactions,
+    DbReader, DbWriter, ExecutedTrees, Order, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
 };
 
 pub const LEDGER_DB_NAME: &str = "ledger_db";
@@ -167,6 +168,43 @@ static ROCKSDB_PROPERTY_MAP: Lazy<HashMap<&str, String>> = Lazy::new(|| {
     .collect()
 });
 
+// The statistics tickers reported as metrics, which show how well point lookups are served by the
+// caches and bloom filters.
+const ROCKSDB_TICKERS_TO_REPORT: &[&str] = &[
+    "rocksdb.block.cache.hit",
+    "rocksdb.block.cache.miss",
+    "rocksdb.block.cache.data.hit",
+    "rocksdb.block.cache.data.miss",
+    "rocksdb.block.cache.filter.hit",
+    "rocksdb.block.cache.filter.miss",
+    "rocksdb.block.cache.index.hit",
+    "rocksdb.block.cache.index.miss",
+    "rocksdb.row.cache.hit",
+    "rocksdb.row.cache.miss",
+    "rocksdb.bloom.filter.useful",
+    "rocksdb.bloom.filter.full.positive",
+    "rocksdb.bloom.filter.full.true.positive",
+    "rocksdb.bloom.filter.prefix.checked",
+    "rocksdb.bloom.filter.prefix.useful",
+    "rocksdb.memtable.hit",
+    "rocksdb.memtable.miss",
+];
+
+/// Parses the tickers to report out of the rocksdb statistics, which have a line per ticker of the
+/// form `rocksdb.block.cache.hit COUNT : 42`.
+fn parse_rocksdb_tickers(statistics: &str) -> Vec<(&str, u64)> {
+    statistics
+        .lines()
+        .filter_map(|line| {
+            let (name, count) = line.split_once(" COUNT : ")?;
+            let name = ROCKSDB_TICKERS_TO_REPORT
+                .iter()
+                .find(|ticker| **ticker == name)?;
+            Some((*name, count.trim().parse().ok()?))
+        })
+        .collect()
+}
+
 fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<()> {
     if num_requested > max_allowed {
         Err(AptosDbError::TooManyRequested(num_requested, max_allowed).into())
@@ -193,6 +231,18 @@ fn update_rocksdb_properties(ledger_rocksdb: &DB, state_merkle_rocksdb: &DB) ->
                 .set(state_merkle_rocksdb.get_property(cf_name, rockdb_property_name)? as i64);
         }
     }
+    for (db_name, db) in [
+        (LEDGER_DB_NAME, ledger_rocksdb),
+        (STATE_MERKLE_DB_NAME, state_merkle_rocksdb),
+    ] {
+        if let Some(statistics) = db.get_statistics()? {
+            for (ticker_name, count) in parse_rocksdb_tickers(&statistics) {
+                ROCKSDB_TICKERS
+                    .with_label_values(&[db_name, ticker_name])
+                    .set(count as i64);
+            }
+        }
+    }
     Ok(())
 }
 
@@ -1161,6 +1211,33 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Returns up to `limit` events of the given type emitted at or after the event of index
+    /// `start_index` of transaction `start_version`, in order, each with its index among the
+    /// events of its transaction. Only events committed since the node started indexing them are
+    /// returned.
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+            self.error_if_ledger_pruned("Event", start_version)?;
+
+            self.event_store
+                .lookup_events_by_type(type_tag, start_version, start_index, limit, ledger_version)?
+                .into_iter()
+                .map(|(ver, idx)| {
+                    let event = self.event_store.get_event_by_version_and_index(ver, idx)?;
+                    Ok((idx, EventWithVersion::new(ver, event)))
+                })
+                .collect()
+        })
+    }
+
     /// Gets ledger info at specified version and ensures it's an epoch ending.
     fn get_epoch_ending_ledger_info(&self, version: u64) -> Result<LedgerInfoWithSignatures> {
         gauged_api("get_epoch_ending_ledger_info", || {
@@ -1349,6 +1426,73 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_block_by_height_with_transactions(
+        &self,
+        height: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<BlockWithTransactions> {
+        gauged_api("get_block_by_height_with_transactions", || {
+            error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+            let (first_version, last_version, new_block_event) =
+                self.get_block_info_by_height(height)?;
+            ensure!(
+                first_version <= ledger_version,
+                "Block {} starts at version {} > ledger version {}",
+                height,
+                first_version,
+                ledger_version
+            );
+            self.error_if_ledger_pruned("Transaction", first_version)?;
+
+            let end_version = std::cmp::min(last_version, ledger_version) + 1;
+            let num_txns = std::cmp::min(limit, end_version - first_version) as usize;
+
+            // Each column family is read with a single iterator, and the accumulator root
+            // hashes are computed in memory from the frozen subtrees before the block.
+            let txns = self
+                .transaction_store
+                .get_transaction_iter(first_version, num_txns)?;
+            let txn_infos = self
+                .ledger_store
+                .get_transaction_info_iter(first_version, num_txns)?;
+            let events = self
+                .event_store
+                .get_events_by_version_iter(first_version, num_txns)?;
+            let write_sets = self
+                .transaction_store
+                .get_write_set_iter(first_version, num_txns)?;
+            let mut accumulator = InMemoryAccumulator::<TransactionAccumulatorHasher>::new(
+                self.ledger_store.get_frozen_subtree_hashes(first_version)?,
+                first_version,
+            )?;
+
+            let mut transactions = Vec::with_capacity(num_txns);
+            for (idx, (((txn, info), events), write_set)) in
+                zip_eq(zip_eq(zip_eq(txns, txn_infos), events), write_sets).enumerate()
+            {
+                let info = info?;
+                accumulator = accumulator.append(&[info.hash()]);
+                transactions.push(BlockTransaction {
+                    version: first_version + idx as u64,
+                    transaction: txn?,
+                    info,
+                    events: events?,
+                    write_set: write_set?,
+                    accumulator_root_hash: accumulator.root_hash(),
+                });
+            }
+
+            Ok(BlockWithTransactions {
+                first_version,
+                last_version,
+                new_block_event,
+                transactions,
+            })
+        })
+    }
+
     fn get_last_version_before_timestamp(
         &self,
         timestamp: u64,
@@ -0,0 +1,76 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which a ContractEvent (
+//! represented by a <txn_version, event_idx> tuple so that it can be fetched from `EventSchema`)
+//! can be found by the hash of its type tag, in version order.
+//!
+//! ```text
+//! |<-------------key------------->|<-value->|
+//! | type_tag_hash | txn_ver | idx |  empty  |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref type_tag_hash, version, index) = *self;
+
+        let mut encoded = type_tag_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+
+        const HASH_LEN: usize = HashValue::LENGTH;
+        const HASH_AND_VER_LEN: usize = size_of::<(HashValue, Version)>();
+        let type_tag_hash = HashValue::from_slice(&data[..HASH_LEN])?;
+        let version = (&data[HASH_LEN..]).read_u64::<BigEndian>()?;
+        let index = (&data[HASH_AND_VER_LEN..]).read_u64::<BigEndian>()?;
+
+        Ok((type_tag_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+impl SeekKeyCodec<EventByTypeSchema> for (HashValue, Version) {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        let (ref type_tag_hash, version) = *self;
+
+        let mut encoded = type_tag_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+}
+
+#[cfg(test)]
+mod test;
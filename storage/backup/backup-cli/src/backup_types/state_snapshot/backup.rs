@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_types::state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
+    backup_types::state_snapshot::{
+        chunk_store::ChunkStore,
+        manifest::{StateSnapshotBackup, StateSnapshotChunk},
+    },
     metadata::Metadata,
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
@@ -10,7 +13,7 @@ use crate::{
         should_cut_chunk, storage_ext::BackupStorageExt, GlobalBackupOpt,
     },
 };
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_logger::prelude::*;
 use aptos_types::{
@@ -33,12 +36,30 @@ pub struct StateSnapshotBackupOpt {
         help = "Epoch at the end of which a state snapshot is to be taken."
     )]
     pub epoch: u64,
+
+    #[clap(
+        long,
+        help = "Store the chunks in the content-addressed chunk store shared by state snapshots, \
+        so that chunks unchanged since the previous snapshot aren't stored again. Reuse is more \
+        likely with a smaller --max-chunk-size. Unreferenced chunks are deleted by garbage \
+        collecting the chunk store."
+    )]
+    pub dedup_chunks: bool,
+
+    #[clap(
+        long,
+        help = "Manifest of the previous state snapshot, whose chunks are reused if unchanged. \
+        Requires --dedup-chunks."
+    )]
+    pub previous_manifest: Option<FileHandle>,
 }
 
 pub struct StateSnapshotBackupController {
     epoch: u64,
     version: Option<Version>, // initialize before using
     max_chunk_size: usize,
+    dedup_chunks: bool,
+    previous_manifest: Option<FileHandle>,
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
 }
@@ -54,6 +75,8 @@ impl StateSnapshotBackupController {
             epoch: opt.epoch,
             version: None,
             max_chunk_size: global_opt.max_chunk_size,
+            dedup_chunks: opt.dedup_chunks,
+            previous_manifest: opt.previous_manifest,
             client,
             storage,
         }
@@ -70,11 +93,23 @@ impl StateSnapshotBackupController {
     }
 
     async fn run_impl(mut self) -> Result<FileHandle> {
+        ensure!(
+            self.dedup_chunks || self.previous_manifest.is_none(),
+            "A previous manifest can only be reused with --dedup-chunks."
+        );
         self.version = Some(self.get_version_for_epoch_ending(self.epoch).await?);
         let backup_handle = self
             .storage
             .create_backup_with_random_suffix(&self.backup_name())
             .await?;
+        let mut chunk_store = if self.dedup_chunks {
+            Some(
+                ChunkStore::new(Arc::clone(&self.storage), self.previous_manifest.as_deref())
+                    .await?,
+            )
+        } else {
+            None
+        };
 
         let mut chunks = vec![];
 
@@ -91,9 +126,10 @@ impl StateSnapshotBackupController {
 
         let start = Instant::now();
         while let Some(record_bytes) = state_snapshot_file.read_record_bytes().await? {
-            if should_cut_chunk(&chunk_bytes, &record_bytes, self.max_chunk_size) {
+            if self.should_cut_chunk(&chunk_bytes, &prev_record_bytes, &record_bytes)? {
                 let chunk = self
                     .write_chunk(
+                        &mut chunk_store,
                         &backup_handle,
                         &chunk_bytes,
                         chunk_first_idx,
@@ -124,6 +160,7 @@ impl StateSnapshotBackupController {
         assert!(!chunk_bytes.is_empty());
         let chunk = self
             .write_chunk(
+                &mut chunk_store,
                 &backup_handle,
                 &chunk_bytes,
                 chunk_first_idx,
@@ -134,6 +171,11 @@ impl StateSnapshotBackupController {
             .await?;
         chunks.push(chunk);
 
+        if let Some(chunk_store) = &chunk_store {
+            chunk_store
+                .save_index(&backup_handle, self.version())
+                .await?;
+        }
         self.write_manifest(&backup_handle, chunks).await
     }
 }
@@ -169,6 +211,22 @@ impl StateSnapshotBackupController {
             .unwrap()
     }
 
+    /// Whether the chunk should end before `record_bytes`. With the chunk store, chunks end at
+    /// stable boundaries once they're half full, so that unchanged chunks can be reused.
+    fn should_cut_chunk(
+        &self,
+        chunk_bytes: &[u8],
+        prev_record_bytes: &Bytes,
+        record_bytes: &[u8],
+    ) -> Result<bool> {
+        if should_cut_chunk(chunk_bytes, record_bytes, self.max_chunk_size) {
+            return Ok(true);
+        }
+        Ok(self.dedup_chunks
+            && chunk_bytes.len() >= self.max_chunk_size / 2
+            && ChunkStore::is_chunk_boundary(&Self::parse_key(prev_record_bytes)?))
+    }
+
     fn parse_key(record: &Bytes) -> Result<HashValue> {
         let (key, _): (StateKey, StateValue) = bcs::from_bytes(record)?;
         Ok(key.hash())
@@ -191,6 +249,7 @@ impl StateSnapshotBackupController {
 
     async fn write_chunk(
         &self,
+        chunk_store: &mut Option<ChunkStore>,
         backup_handle: &BackupHandleRef,
         chunk_bytes: &[u8],
         first_idx: usize,
@@ -198,12 +257,21 @@ impl StateSnapshotBackupController {
         first_key: HashValue,
         last_key: HashValue,
     ) -> Result<StateSnapshotChunk> {
-        let (chunk_handle, mut chunk_file) = self
-            .storage
-            .create_for_write(backup_handle, &Self::chunk_name(first_idx))
-            .await?;
-        chunk_file.write_all(chunk_bytes).await?;
-        chunk_file.shutdown().await?;
+        let (chunk_handle, blobs_hash) = match chunk_store {
+            Some(chunk_store) => {
+                let (hash, chunk_handle) = chunk_store.put(backup_handle, chunk_bytes).await?;
+                (chunk_handle, Some(hash))
+            }
+            None => {
+                let (chunk_handle, mut chunk_file) = self
+                    .storage
+                    .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+                    .await?;
+                chunk_file.write_all(chunk_bytes).await?;
+                chunk_file.shutdown().await?;
+                (chunk_handle, None)
+            }
+        };
         let (proof_handle, mut proof_file) = self
             .storage
             .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
@@ -224,6 +292,7 @@ impl StateSnapshotBackupController {
             first_key,
             last_key,
             blobs: chunk_handle,
+            blobs_hash,
             proof: proof_handle,
         })
     }
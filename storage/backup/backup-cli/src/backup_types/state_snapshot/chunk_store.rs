@@ -0,0 +1,216 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A content-addressed store for the chunks of state snapshots. Adjacent snapshots share most of
+//! the state, so a snapshot refers to the chunks of the previous one whose content is unchanged
+//! instead of storing them again. Chunks are identified by the hash of their content, recorded in
+//! the manifests, and every chunk stays in the backup that first stored it.
+//!
+//! Chunks that are no longer referred to by any state snapshot in the metadata, e.g., once old
+//! snapshots are retired by removing their metadata files, are deleted by `collect_garbage`.
+
+use crate::{
+    backup_types::state_snapshot::manifest::StateSnapshotBackup,
+    metadata::{view::MetadataView, Metadata},
+    storage::{BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName},
+    utils::storage_ext::BackupStorageExt,
+};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use futures::{stream, StreamExt, TryStreamExt};
+use once_cell::sync::Lazy;
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::AsyncWriteExt;
+
+/// Roughly one in 2^BOUNDARY_BITS keys ends a chunk, see `ChunkStore::is_chunk_boundary`.
+const BOUNDARY_BITS: u32 = 12;
+
+pub struct ChunkStore {
+    storage: Arc<dyn BackupStorage>,
+    /// Chunks that can be referred to instead of being stored again, by hash of their content.
+    known_chunks: HashMap<HashValue, FileHandle>,
+    /// Chunks stored by the current backup.
+    new_chunks: Vec<FileHandle>,
+    /// Number of chunks of the current backup that were stored already.
+    num_reused_chunks: usize,
+}
+
+impl ChunkStore {
+    /// Opens the chunk store for a new state snapshot backup, reusing the chunks of the state
+    /// snapshot with the given manifest if any.
+    pub async fn new(
+        storage: Arc<dyn BackupStorage>,
+        previous_manifest: Option<&FileHandleRef>,
+    ) -> Result<Self> {
+        let mut known_chunks = HashMap::new();
+        if let Some(manifest_handle) = previous_manifest {
+            let manifest: StateSnapshotBackup = storage.load_json_file(manifest_handle).await?;
+            known_chunks.extend(
+                manifest
+                    .chunks
+                    .into_iter()
+                    .filter_map(|chunk| chunk.blobs_hash.map(|hash| (hash, chunk.blobs))),
+            );
+            info!(
+                manifest = manifest_handle,
+                chunks = known_chunks.len(),
+                "Reusing the chunks of the previous state snapshot."
+            );
+        }
+
+        Ok(Self {
+            storage,
+            known_chunks,
+            new_chunks: vec![],
+            num_reused_chunks: 0,
+        })
+    }
+
+    /// Whether a chunk can end with the record with the given key. Cutting chunks at keys rather
+    /// than at fixed sizes keeps the boundaries of unchanged chunks stable when records are
+    /// inserted or deleted elsewhere in the state. Keys are hashes, so boundaries are uniformly
+    /// spread.
+    pub fn is_chunk_boundary(key: &HashValue) -> bool {
+        let bytes = key.as_ref();
+        u16::from_be_bytes([bytes[0], bytes[1]]) >> (16 - BOUNDARY_BITS) == 0
+    }
+
+    fn chunk_name(hash: &HashValue) -> ShellSafeName {
+        format!("{}.chunk", hash.to_hex()).try_into().unwrap()
+    }
+
+    fn index_name() -> &'static ShellSafeName {
+        static NAME: Lazy<ShellSafeName> =
+            Lazy::new(|| ShellSafeName::from_str("chunk_store.index").unwrap());
+        &NAME
+    }
+
+    /// Stores a chunk under `backup_handle` unless a chunk with the same content is known already.
+    /// Returns the hash of the chunk and the handle to refer to it by.
+    pub async fn put(
+        &mut self,
+        backup_handle: &BackupHandleRef,
+        chunk_bytes: &[u8],
+    ) -> Result<(HashValue, FileHandle)> {
+        let hash = HashValue::sha3_256_of(chunk_bytes);
+        if let Some(chunk_handle) = self.known_chunks.get(&hash) {
+            self.num_reused_chunks += 1;
+            return Ok((hash, chunk_handle.clone()));
+        }
+
+        let (chunk_handle, mut chunk_file) = self
+            .storage
+            .create_for_write(backup_handle, &Self::chunk_name(&hash))
+            .await?;
+        chunk_file.write_all(chunk_bytes).await?;
+        chunk_file.shutdown().await?;
+        self.known_chunks.insert(hash, chunk_handle.clone());
+        self.new_chunks.push(chunk_handle.clone());
+        Ok((hash, chunk_handle))
+    }
+
+    /// Records the chunks stored by the current backup, so that they can be garbage collected.
+    /// This must happen before the manifest of the snapshot is saved.
+    pub async fn save_index(
+        &self,
+        backup_handle: &BackupHandleRef,
+        version: Version,
+    ) -> Result<()> {
+        info!(
+            new_chunks = self.new_chunks.len(),
+            reused_chunks = self.num_reused_chunks,
+            "Chunk store updated."
+        );
+        if self.new_chunks.is_empty() {
+            return Ok(());
+        }
+
+        let (index_handle, mut index_file) = self
+            .storage
+            .create_for_write(backup_handle, Self::index_name())
+            .await?;
+        index_file
+            .write_all(&serde_json::to_vec(&self.new_chunks)?)
+            .await?;
+        index_file.shutdown().await?;
+
+        let metadata = Metadata::new_chunk_store_index(version, index_handle);
+        self.storage
+            .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+            .await
+    }
+}
+
+/// Deletes the chunks of the chunk store that no state snapshot in the metadata refers to, and
+/// returns them. Nothing is deleted on a dry run.
+///
+/// Retiring a snapshot while a backup reusing its chunks is in progress makes that backup refer to
+/// deleted chunks, so garbage collection shouldn't run concurrently with state snapshot backups.
+pub async fn collect_garbage(
+    storage: Arc<dyn BackupStorage>,
+    metadata_view: &MetadataView,
+    concurrent_downloads: usize,
+    dry_run: bool,
+) -> Result<Vec<FileHandle>> {
+    let referenced: HashSet<FileHandle> = stream::iter(metadata_view.all_state_snapshots())
+        .map(|snapshot| {
+            let storage = storage.clone();
+            async move {
+                let manifest: StateSnapshotBackup =
+                    storage.load_json_file(&snapshot.manifest).await?;
+                Result::<_>::Ok(
+                    manifest
+                        .chunks
+                        .into_iter()
+                        .filter(|chunk| chunk.blobs_hash.is_some())
+                        .map(|chunk| chunk.blobs)
+                        .collect::<Vec<_>>(),
+                )
+            }
+        })
+        .buffer_unordered(concurrent_downloads)
+        .try_concat()
+        .await?
+        .into_iter()
+        .collect();
+
+    let stored: HashSet<FileHandle> = stream::iter(metadata_view.chunk_store_indices())
+        .map(|index| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .load_json_file::<Vec<FileHandle>>(&index.index)
+                    .await
+            }
+        })
+        .buffer_unordered(concurrent_downloads)
+        .try_concat()
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut garbage: Vec<_> = stored.difference(&referenced).cloned().collect();
+    garbage.sort();
+    info!(
+        stored_chunks = stored.len(),
+        referenced_chunks = referenced.len(),
+        unreferenced_chunks = garbage.len(),
+        dry_run = dry_run,
+        "Collecting garbage in the chunk store."
+    );
+    if !dry_run {
+        stream::iter(&garbage)
+            .map(|chunk_handle| storage.delete_file(chunk_handle))
+            .buffer_unordered(concurrent_downloads)
+            .try_collect::<()>()
+            .await?;
+    }
+    Ok(garbage)
+}
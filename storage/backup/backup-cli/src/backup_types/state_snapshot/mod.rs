@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod backup;
+pub mod chunk_store;
 pub mod manifest;
 pub mod restore;
 
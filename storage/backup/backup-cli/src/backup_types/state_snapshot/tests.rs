@@ -5,11 +5,15 @@ use crate::utils::ReplayConcurrencyLevelOpt;
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        chunk_store,
+        manifest::StateSnapshotBackup,
         restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
-    storage::{local_fs::LocalFs, BackupStorage},
+    metadata::cache::{self, MetadataCacheOpt},
+    storage::{local_fs::LocalFs, BackupStorage, FileHandle, FileHandleRef},
     utils::{
         backup_service_client::BackupServiceClient,
+        storage_ext::BackupStorageExt,
         test_utils::{start_local_backup_service, tmp_db_with_random_content},
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, RocksdbOpt, TrustedWaypointOpt,
     },
@@ -17,7 +21,7 @@ use crate::{
 use aptos_db::AptosDB;
 use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
-use std::{convert::TryInto, sync::Arc};
+use std::{convert::TryInto, path::Path, sync::Arc};
 use tokio::time::Duration;
 
 #[test]
@@ -60,7 +64,11 @@ fn end_to_end() {
     let manifest_handle = rt
         .block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { epoch },
+                StateSnapshotBackupOpt {
+                    epoch,
+                    dedup_chunks: false,
+                    previous_manifest: None,
+                },
                 GlobalBackupOpt {
                     max_chunk_size: 500,
                 },
@@ -107,3 +115,142 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+#[test]
+fn dedup_chunks_and_collect_garbage() {
+    let (_src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+    let backup_dir = TempPath::new();
+    backup_dir.create_as_dir().unwrap();
+    let metadata_cache_dir = TempPath::new();
+    metadata_cache_dir.create_as_dir().unwrap();
+    let local_fs = LocalFs::new(backup_dir.path().to_path_buf());
+    let metadata_dir = local_fs.metadata_dir();
+    let store: Arc<dyn BackupStorage> = Arc::new(local_fs);
+    let metadata_cache_opt = MetadataCacheOpt::new(Some(metadata_cache_dir.path().to_path_buf()));
+
+    let epoch = src_db
+        .get_latest_ledger_info()
+        .unwrap()
+        .ledger_info()
+        .next_block_epoch()
+        - 1;
+    let version = src_db
+        .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+        .unwrap()
+        .ledger_info_with_sigs
+        .pop()
+        .unwrap()
+        .ledger_info()
+        .version();
+
+    let (rt, port) = start_local_backup_service(src_db);
+    let client = Arc::new(BackupServiceClient::new(format!(
+        "http://localhost:{}",
+        port
+    )));
+    let backup = |previous_manifest| {
+        rt.block_on(
+            StateSnapshotBackupController::new(
+                StateSnapshotBackupOpt {
+                    epoch,
+                    dedup_chunks: true,
+                    previous_manifest,
+                },
+                GlobalBackupOpt {
+                    max_chunk_size: 500,
+                },
+                Arc::clone(&client),
+                Arc::clone(&store),
+            )
+            .run(),
+        )
+        .unwrap()
+    };
+    let load_manifest = |manifest_handle: &FileHandleRef| {
+        rt.block_on(store.load_json_file::<StateSnapshotBackup>(manifest_handle))
+            .unwrap()
+    };
+    let collect_garbage = |dry_run| {
+        let metadata_view = rt
+            .block_on(cache::sync_and_load(
+                &metadata_cache_opt,
+                Arc::clone(&store),
+                1,
+            ))
+            .unwrap();
+        rt.block_on(chunk_store::collect_garbage(
+            Arc::clone(&store),
+            &metadata_view,
+            1,
+            dry_run,
+        ))
+        .unwrap()
+    };
+    let retire_snapshot = || {
+        std::fs::remove_file(metadata_dir.join(format!("state_snapshot_ver_{}.meta", version)))
+            .unwrap()
+    };
+
+    // The second snapshot of the same state stores no chunks of its own.
+    let first_manifest_handle = backup(None);
+    retire_snapshot();
+    let second_manifest_handle = backup(Some(first_manifest_handle.clone()));
+    let first_manifest = load_manifest(&first_manifest_handle);
+    let second_manifest = load_manifest(&second_manifest_handle);
+    assert!(first_manifest.chunks.len() > 1);
+    assert_eq!(first_manifest.chunks.len(), second_manifest.chunks.len());
+    for (first, second) in first_manifest.chunks.iter().zip(&second_manifest.chunks) {
+        assert!(first.blobs_hash.is_some());
+        assert_eq!(first.blobs_hash, second.blobs_hash);
+        assert_eq!(first.blobs, second.blobs);
+    }
+
+    // The chunks are still referred to by the second snapshot, which restores fine.
+    assert!(collect_garbage(false).is_empty());
+    rt.block_on(
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle: second_manifest_handle,
+                version,
+                validate_modules: false,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+            }
+            .try_into()
+            .unwrap(),
+            Arc::clone(&store),
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+    let tgt_db = AptosDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap()
+            .0,
+        version
+    );
+
+    // Once no snapshot refers to them, all chunks are collected.
+    retire_snapshot();
+    let chunk_exists = |chunk: &FileHandle| backup_dir.path().join(Path::new(chunk)).exists();
+    let garbage = collect_garbage(true);
+    assert_eq!(garbage.len(), first_manifest.chunks.len());
+    assert!(garbage.iter().all(chunk_exists));
+    assert_eq!(collect_garbage(false), garbage);
+    assert!(!garbage.iter().any(chunk_exists));
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+}
@@ -9,7 +9,10 @@ use clap::Parser;
 use aptos_backup_cli::{
     backup_types::{
         epoch_ending::backup::{EpochEndingBackupController, EpochEndingBackupOpt},
-        state_snapshot::backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        state_snapshot::{
+            backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+            chunk_store,
+        },
         transaction::backup::{TransactionBackupController, TransactionBackupOpt},
     },
     coordinators::backup::{BackupCoordinator, BackupCoordinatorOpt},
@@ -44,6 +47,12 @@ enum OneShotCommand {
     Query(OneShotQueryType),
     #[clap(about = "Do a one shot backup of either of the backup types.")]
     Backup(OneShotBackupOpt),
+    #[clap(
+        about = "Delete the state snapshot chunks no longer referred to by any state snapshot \
+        in the metadata, e.g. after old snapshots are retired by removing their metadata files. \
+        Must not run concurrently with state snapshot backups."
+    )]
+    GcChunkStore(OneShotGcChunkStoreOpt),
 }
 
 #[derive(Parser)]
@@ -75,6 +84,18 @@ struct OneShotQueryBackupStorageStateOpt {
     storage: StorageOpt,
 }
 
+#[derive(Parser)]
+struct OneShotGcChunkStoreOpt {
+    #[clap(flatten)]
+    metadata_cache: MetadataCacheOpt,
+    #[clap(flatten)]
+    concurrent_downloads: ConcurrentDownloadsOpt,
+    #[clap(long, help = "Only print the chunks that would be deleted.")]
+    dry_run: bool,
+    #[clap(subcommand)]
+    storage: StorageOpt,
+}
+
 #[derive(Parser)]
 struct OneShotBackupOpt {
     #[clap(flatten)]
@@ -205,6 +226,22 @@ async fn main_impl() -> Result<()> {
                     }
                 }
             }
+            OneShotCommand::GcChunkStore(opt) => {
+                let storage = opt.storage.init_storage().await?;
+                let concurrent_downloads = opt.concurrent_downloads.get();
+                let view = cache::sync_and_load(
+                    &opt.metadata_cache,
+                    Arc::clone(&storage),
+                    concurrent_downloads,
+                )
+                .await?;
+                let garbage =
+                    chunk_store::collect_garbage(storage, &view, concurrent_downloads, opt.dry_run)
+                        .await?;
+                for chunk_handle in garbage {
+                    println!("{}", chunk_handle)
+                }
+            }
         },
         Command::Coordinator(coordinator_cmd) => match coordinator_cmd {
             CoordinatorCommand::Run(opt) => {
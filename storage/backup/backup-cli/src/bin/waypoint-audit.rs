@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_backup_cli::{
+    coordinators::waypoint_audit::{WaypointAuditCoordinator, WaypointAuditOpt},
+    utils::TrustedWaypointOpt,
+};
+use aptos_logger::{prelude::*, Level, Logger};
+use clap::Parser;
+
+/// Cross-checks the epoch ending LedgerInfos served by several independent fullnodes, verifying
+/// the signatures on them against the validator sets, and reports where the fullnodes diverge.
+#[derive(Parser)]
+struct Opt {
+    #[clap(flatten)]
+    audit_opt: WaypointAuditOpt,
+    #[clap(flatten)]
+    trusted_waypoints_opt: TrustedWaypointOpt,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    main_impl().await.map_err(|e| {
+        error!("main_impl() failed: {}", e);
+        e
+    })
+}
+
+async fn main_impl() -> Result<()> {
+    Logger::new().level(Level::Info).init();
+
+    let opt = Opt::from_args();
+    WaypointAuditCoordinator::new(opt.audit_opt, opt.trusted_waypoints_opt)?
+        .run()
+        .await?;
+    Ok(())
+}
@@ -12,7 +12,7 @@ use crate::{
     metrics::backup::{
         EPOCH_ENDING_EPOCH, HEARTBEAT_TS, STATE_SNAPSHOT_EPOCH, TRANSACTION_VERSION,
     },
-    storage::BackupStorage,
+    storage::{BackupStorage, FileHandle},
     utils::{
         backup_service_client::BackupServiceClient, unix_timestamp_sec, ConcurrentDownloadsOpt,
         GlobalBackupOpt,
@@ -20,6 +20,7 @@ use crate::{
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_db::backup::backup_handler::DbState;
+use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
 use clap::Parser;
@@ -65,6 +66,13 @@ pub struct BackupCoordinatorOpt {
     pub transaction_batch_size: usize,
     #[clap(flatten)]
     pub concurrent_downloads: ConcurrentDownloadsOpt,
+    #[clap(
+        long,
+        help = "Store the chunks of state snapshots in the content-addressed chunk store, so that \
+        each snapshot only stores the chunks that changed since the previous one. See \
+        `--dedup-chunks` of one shot state snapshot backups."
+    )]
+    pub dedup_state_snapshot_chunks: bool,
 }
 
 impl BackupCoordinatorOpt {
@@ -85,6 +93,9 @@ pub struct BackupCoordinator {
     state_snapshot_interval_epochs: usize,
     transaction_batch_size: usize,
     concurrent_downloads: usize,
+    dedup_state_snapshot_chunks: bool,
+    /// Manifest of the latest state snapshot, whose chunks the next one reuses.
+    latest_state_snapshot_manifest: Mutex<Option<FileHandle>>,
 }
 
 impl BackupCoordinator {
@@ -103,18 +114,23 @@ impl BackupCoordinator {
             state_snapshot_interval_epochs: opt.state_snapshot_interval_epochs,
             transaction_batch_size: opt.transaction_batch_size,
             concurrent_downloads: opt.concurrent_downloads.get(),
+            dedup_state_snapshot_chunks: opt.dedup_state_snapshot_chunks,
+            latest_state_snapshot_manifest: Mutex::new(None),
         }
     }
 
     pub async fn run(&self) -> Result<()> {
         // Connect to both the local node and the backup storage.
-        let backup_state = metadata::cache::sync_and_load(
+        let metadata_view = metadata::cache::sync_and_load(
             &self.metadata_cache_opt,
             Arc::clone(&self.storage),
             self.concurrent_downloads,
         )
-        .await?
-        .get_storage_state()?;
+        .await?;
+        let backup_state = metadata_view.get_storage_state()?;
+        *self.latest_state_snapshot_manifest.lock() = metadata_view
+            .select_state_snapshot(Version::MAX)?
+            .map(|snapshot| snapshot.manifest);
 
         // On new DbState retrieved:
         // `watch_db_state` informs `backup_epoch_endings` via channel 1,
@@ -249,14 +265,24 @@ impl BackupCoordinator {
             return Ok(last_snapshot_epoch_in_backup);
         }
 
-        StateSnapshotBackupController::new(
-            StateSnapshotBackupOpt { epoch },
+        let previous_manifest = if self.dedup_state_snapshot_chunks {
+            self.latest_state_snapshot_manifest.lock().clone()
+        } else {
+            None
+        };
+        let manifest = StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                epoch,
+                dedup_chunks: self.dedup_state_snapshot_chunks,
+                previous_manifest,
+            },
             self.global_opt.clone(),
             Arc::clone(&self.client),
             Arc::clone(&self.storage),
         )
         .run()
         .await?;
+        *self.latest_state_snapshot_manifest.lock() = Some(manifest);
 
         Ok(Some(epoch))
     }
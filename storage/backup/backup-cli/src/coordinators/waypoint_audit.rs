@@ -0,0 +1,418 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::{
+    backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
+    TrustedWaypointOpt,
+};
+use anyhow::{anyhow, bail, ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{
+    epoch_change::Verifier, ledger_info::LedgerInfoWithSignatures, transaction::Version,
+    waypoint::Waypoint,
+};
+use clap::Parser;
+use futures::future::join_all;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+#[derive(Parser)]
+pub struct WaypointAuditOpt {
+    #[clap(
+        long = "upstream",
+        required = true,
+        help = "(multiple) Backup service address of an independent fullnode to audit, \
+        e.g. http://fullnode-1:6186."
+    )]
+    pub upstreams: Vec<String>,
+
+    #[clap(long, default_value = "0", help = "First epoch to audit.")]
+    pub start_epoch: u64,
+
+    #[clap(
+        long,
+        help = "Epoch before which the audit stops. [Defaults to the open epoch of the upstream \
+        that's the furthest behind]"
+    )]
+    pub end_epoch: Option<u64>,
+
+    #[clap(
+        long = "version",
+        help = "(multiple) Versions to report the waypoints of, i.e., the waypoint of the first \
+        epoch ending at or after each version."
+    )]
+    pub versions: Vec<Version>,
+}
+
+/// Fetches the epoch ending LedgerInfos from several upstreams, verifies the signatures on them
+/// and reports where the upstreams disagree.
+pub struct WaypointAuditCoordinator {
+    upstreams: Vec<String>,
+    trusted_waypoints: HashMap<Version, Waypoint>,
+    start_epoch: u64,
+    end_epoch: Option<u64>,
+    versions: Vec<Version>,
+}
+
+impl WaypointAuditCoordinator {
+    pub fn new(opt: WaypointAuditOpt, trusted_waypoints_opt: TrustedWaypointOpt) -> Result<Self> {
+        ensure!(
+            opt.upstreams.iter().collect::<BTreeSet<_>>().len() >= 2,
+            "At least two distinct upstreams are needed for an audit."
+        );
+        Ok(Self {
+            upstreams: opt.upstreams,
+            trusted_waypoints: trusted_waypoints_opt.verify()?,
+            start_epoch: opt.start_epoch,
+            end_epoch: opt.end_epoch,
+            versions: opt.versions,
+        })
+    }
+
+    pub async fn run(self) -> Result<WaypointAuditReport> {
+        info!(
+            "Waypoint audit started. Upstreams: {:?}, start epoch: {}.",
+            self.upstreams, self.start_epoch,
+        );
+        let ret = self
+            .run_impl()
+            .await
+            .map_err(|e| anyhow!("Waypoint audit failed: {}", e))?;
+        info!("Waypoint audit succeeded.");
+        Ok(ret)
+    }
+
+    async fn run_impl(self) -> Result<WaypointAuditReport> {
+        let clients: Vec<_> = self
+            .upstreams
+            .iter()
+            .map(|address| BackupServiceClient::new(address.clone()))
+            .collect();
+
+        let end_epoch = match self.end_epoch {
+            Some(end_epoch) => end_epoch,
+            None => {
+                let db_states = join_all(clients.iter().map(|client| client.get_db_state())).await;
+                let mut end_epoch = None;
+                for (address, db_state) in self.upstreams.iter().zip(db_states) {
+                    match db_state {
+                        Ok(Some(db_state)) => {
+                            end_epoch = Some(
+                                end_epoch.map_or(db_state.epoch, |e: u64| e.min(db_state.epoch)),
+                            )
+                        }
+                        Ok(None) => warn!("Upstream {} has an empty DB.", address),
+                        Err(e) => warn!("Failed to get the DB state of {}: {}", address, e),
+                    }
+                }
+                end_epoch.ok_or_else(|| anyhow!("None of the upstreams has a DB state."))?
+            }
+        };
+        ensure!(
+            self.start_epoch < end_epoch,
+            "Nothing to audit, start epoch {} isn't before end epoch {}.",
+            self.start_epoch,
+            end_epoch,
+        );
+
+        let ledger_infos = join_all(
+            clients
+                .iter()
+                .map(|client| self.fetch_verified_ledger_infos(client, end_epoch)),
+        )
+        .await;
+        let report = WaypointAuditReport::new(
+            self.upstreams.iter().cloned().zip(ledger_infos).collect(),
+            &self.versions,
+        )?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+
+        if !report.is_consistent() {
+            bail!(
+                "Upstreams diverge or failed verification. Divergent epochs: {:?}, failed upstreams: {:?}",
+                report.divergent_epochs(),
+                report.failed_upstreams(),
+            );
+        }
+        Ok(report)
+    }
+
+    async fn fetch_verified_ledger_infos(
+        &self,
+        client: &BackupServiceClient,
+        end_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        let mut file = client
+            .get_epoch_ending_ledger_infos(self.start_epoch, end_epoch)
+            .await?;
+        let mut ledger_infos = Vec::new();
+        while let Some(record_bytes) = file.read_record_bytes().await? {
+            ledger_infos.push(bcs::from_bytes(&record_bytes)?);
+        }
+        ensure!(
+            ledger_infos.len() as u64 == end_epoch - self.start_epoch,
+            "Expected {} epoch ending LedgerInfos, got {}.",
+            end_epoch - self.start_epoch,
+            ledger_infos.len(),
+        );
+        verify_ledger_infos(&ledger_infos, self.start_epoch, &self.trusted_waypoints)?;
+        Ok(ledger_infos)
+    }
+}
+
+/// Checks that the LedgerInfos are consecutive epoch endings, each of them either matching a
+/// trusted waypoint or signed by the validator set of the previous one.
+fn verify_ledger_infos(
+    ledger_infos: &[LedgerInfoWithSignatures],
+    start_epoch: u64,
+    trusted_waypoints: &HashMap<Version, Waypoint>,
+) -> Result<()> {
+    let mut previous_li: Option<&LedgerInfoWithSignatures> = None;
+    for (li, epoch) in ledger_infos.iter().zip(start_epoch..) {
+        ensure!(
+            li.ledger_info().epoch() == epoch,
+            "LedgerInfo epoch not expected. Expected: {}, actual: {}.",
+            epoch,
+            li.ledger_info().epoch(),
+        );
+        let wp_li = Waypoint::new_epoch_boundary(li.ledger_info())?;
+        if let Some(wp_trusted) = trusted_waypoints.get(&wp_li.version()) {
+            ensure!(
+                *wp_trusted == wp_li,
+                "Waypoints don't match. From upstream: {}, trusted: {}",
+                wp_li,
+                wp_trusted,
+            );
+        } else if let Some(pre_li) = previous_li {
+            pre_li
+                .ledger_info()
+                .next_epoch_state()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Next epoch state not found from LI at epoch {}.",
+                        pre_li.ledger_info().epoch()
+                    )
+                })?
+                .verify(li)?;
+        } else {
+            bail!(
+                "Signatures on the LedgerInfo at epoch {} can't be verified, \
+                pass its waypoint {} as a trusted waypoint if it's the expected one.",
+                epoch,
+                wp_li,
+            );
+        }
+        previous_li = Some(li);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct WaypointAuditReport {
+    /// Upstreams whose LedgerInfos couldn't be fetched or verified, with the reason.
+    pub failed_upstreams: BTreeMap<String, String>,
+    pub epochs: Vec<WaypointAudit>,
+    pub versions: Vec<WaypointAudit>,
+}
+
+/// The waypoints reported by each verified upstream at an epoch or version.
+#[derive(Debug, Serialize)]
+pub struct WaypointAudit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    pub waypoints: BTreeMap<String, String>,
+    pub divergent: bool,
+}
+
+impl WaypointAudit {
+    fn new(
+        epoch: Option<u64>,
+        version: Option<Version>,
+        waypoints: BTreeMap<String, String>,
+    ) -> Self {
+        let divergent = waypoints.values().collect::<BTreeSet<_>>().len() > 1;
+        Self {
+            epoch,
+            version,
+            waypoints,
+            divergent,
+        }
+    }
+}
+
+impl WaypointAuditReport {
+    fn new(
+        upstreams: Vec<(String, Result<Vec<LedgerInfoWithSignatures>>)>,
+        versions: &[Version],
+    ) -> Result<Self> {
+        let mut failed_upstreams = BTreeMap::new();
+        let mut waypoints_by_upstream = BTreeMap::new();
+        for (address, ledger_infos) in upstreams {
+            match ledger_infos {
+                Ok(ledger_infos) => {
+                    let waypoints = ledger_infos
+                        .iter()
+                        .map(|li| {
+                            Ok((
+                                li.ledger_info().epoch(),
+                                Waypoint::new_epoch_boundary(li.ledger_info())?,
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    waypoints_by_upstream.insert(address, waypoints);
+                }
+                Err(e) => {
+                    warn!("Upstream {} failed verification: {}", address, e);
+                    failed_upstreams.insert(address, e.to_string());
+                }
+            }
+        }
+
+        let mut epochs = BTreeMap::new();
+        for (address, waypoints) in &waypoints_by_upstream {
+            for (epoch, waypoint) in waypoints {
+                epochs
+                    .entry(*epoch)
+                    .or_insert_with(BTreeMap::new)
+                    .insert(address.clone(), waypoint.to_string());
+            }
+        }
+        let epochs = epochs
+            .into_iter()
+            .map(|(epoch, waypoints)| WaypointAudit::new(Some(epoch), None, waypoints))
+            .collect();
+
+        let versions = versions
+            .iter()
+            .map(|version| {
+                let waypoints = waypoints_by_upstream
+                    .iter()
+                    .filter_map(|(address, waypoints)| {
+                        waypoints
+                            .iter()
+                            .find(|(_, waypoint)| waypoint.version() >= *version)
+                            .map(|(_, waypoint)| (address.clone(), waypoint.to_string()))
+                    })
+                    .collect();
+                WaypointAudit::new(None, Some(*version), waypoints)
+            })
+            .collect();
+
+        Ok(Self {
+            failed_upstreams,
+            epochs,
+            versions,
+        })
+    }
+
+    pub fn divergent_epochs(&self) -> Vec<u64> {
+        self.epochs
+            .iter()
+            .filter(|audit| audit.divergent)
+            .filter_map(|audit| audit.epoch)
+            .collect()
+    }
+
+    pub fn failed_upstreams(&self) -> Vec<&String> {
+        self.failed_upstreams.keys().collect()
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.failed_upstreams.is_empty()
+            && !self
+                .epochs
+                .iter()
+                .chain(self.versions.iter())
+                .any(|audit| audit.divergent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature, block_info::BlockInfo, epoch_state::EpochState,
+        ledger_info::LedgerInfo,
+    };
+
+    fn epoch_ending_li(
+        epoch: u64,
+        version: Version,
+        root_hash: HashValue,
+    ) -> LedgerInfoWithSignatures {
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    root_hash,
+                    version,
+                    0,
+                    Some(EpochState::empty()),
+                ),
+                HashValue::zero(),
+            ),
+            AggregateSignature::empty(),
+        )
+    }
+
+    #[test]
+    fn test_report_divergence() {
+        let agreed = vec![
+            epoch_ending_li(0, 0, HashValue::zero()),
+            epoch_ending_li(1, 100, HashValue::zero()),
+        ];
+        let forked = vec![
+            epoch_ending_li(0, 0, HashValue::zero()),
+            epoch_ending_li(1, 100, HashValue::random()),
+        ];
+
+        let report = WaypointAuditReport::new(
+            vec![
+                ("a".to_string(), Ok(agreed.clone())),
+                ("b".to_string(), Ok(agreed)),
+                ("c".to_string(), Err(anyhow!("unreachable"))),
+            ],
+            &[50],
+        )
+        .unwrap();
+        assert!(!report.is_consistent());
+        assert!(report.divergent_epochs().is_empty());
+        assert_eq!(report.failed_upstreams(), vec!["c"]);
+        assert_eq!(report.versions[0].waypoints.len(), 2);
+        assert!(!report.versions[0].divergent);
+
+        let report = WaypointAuditReport::new(
+            vec![
+                ("a".to_string(), Ok(forked)),
+                (
+                    "b".to_string(),
+                    Ok(vec![
+                        epoch_ending_li(0, 0, HashValue::zero()),
+                        epoch_ending_li(1, 100, HashValue::zero()),
+                    ]),
+                ),
+            ],
+            &[50],
+        )
+        .unwrap();
+        assert!(!report.is_consistent());
+        assert_eq!(report.divergent_epochs(), vec![1]);
+        assert!(report.versions[0].divergent);
+    }
+
+    #[test]
+    fn test_verify_needs_trust_anchor() {
+        let lis = vec![epoch_ending_li(0, 0, HashValue::zero())];
+        assert!(verify_ledger_infos(&lis, 0, &HashMap::new()).is_err());
+
+        let waypoint = Waypoint::new_epoch_boundary(lis[0].ledger_info()).unwrap();
+        let trusted = [(waypoint.version(), waypoint)].into_iter().collect();
+        verify_ledger_infos(&lis, 0, &trusted).unwrap();
+        assert!(verify_ledger_infos(&lis, 1, &trusted).is_err());
+    }
+}
@@ -18,6 +18,7 @@ pub(crate) enum Metadata {
     StateSnapshotBackup(StateSnapshotBackupMeta),
     TransactionBackup(TransactionBackupMeta),
     Identity(IdentityMeta),
+    ChunkStoreIndex(ChunkStoreIndexMeta),
 }
 
 impl Metadata {
@@ -57,6 +58,10 @@ impl Metadata {
         })
     }
 
+    pub fn new_chunk_store_index(version: Version, index: FileHandle) -> Self {
+        Self::ChunkStoreIndex(ChunkStoreIndexMeta { version, index })
+    }
+
     pub fn new_random_identity() -> Self {
         Self::Identity(IdentityMeta {
             id: HashValue::random(),
@@ -73,6 +78,7 @@ impl Metadata {
                 format!("transaction_{}-{}.meta", t.first_version, t.last_version,)
             }
             Metadata::Identity(_) => "identity.meta".into(),
+            Self::ChunkStoreIndex(c) => format!("chunk_store_index_ver_{}.meta", c.version),
         }
         .try_into()
         .unwrap()
@@ -110,3 +116,13 @@ pub struct TransactionBackupMeta {
 pub struct IdentityMeta {
     pub id: HashValue,
 }
+
+/// Lists the chunks a state snapshot backup added to the chunk store, so that they can be garbage
+/// collected once no state snapshot refers to them anymore.
+#[derive(Clone, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
+pub struct ChunkStoreIndexMeta {
+    /// Version of the state snapshot that added the chunks.
+    pub version: Version,
+    /// JSON serialized list of the `FileHandle`s of the chunks.
+    pub index: FileHandle,
+}
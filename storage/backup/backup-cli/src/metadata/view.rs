@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::metadata::{
-    EpochEndingBackupMeta, IdentityMeta, Metadata, StateSnapshotBackupMeta, TransactionBackupMeta,
+    ChunkStoreIndexMeta, EpochEndingBackupMeta, IdentityMeta, Metadata, StateSnapshotBackupMeta,
+    TransactionBackupMeta,
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_types::transaction::Version;
@@ -14,6 +15,7 @@ pub struct MetadataView {
     state_snapshot_backups: Vec<StateSnapshotBackupMeta>,
     transaction_backups: Vec<TransactionBackupMeta>,
     _identity: Option<IdentityMeta>,
+    chunk_store_indices: Vec<ChunkStoreIndexMeta>,
 }
 
 impl MetadataView {
@@ -53,6 +55,14 @@ impl MetadataView {
             .map(Clone::clone))
     }
 
+    pub fn all_state_snapshots(&self) -> &[StateSnapshotBackupMeta] {
+        &self.state_snapshot_backups
+    }
+
+    pub fn chunk_store_indices(&self) -> &[ChunkStoreIndexMeta] {
+        &self.chunk_store_indices
+    }
+
     pub fn expect_state_snapshot(&self, version: Version) -> Result<StateSnapshotBackupMeta> {
         self.state_snapshot_backups
             .iter()
@@ -134,6 +144,7 @@ impl From<Vec<Metadata>> for MetadataView {
         let mut state_snapshot_backups = Vec::new();
         let mut transaction_backups = Vec::new();
         let mut identity = None;
+        let mut chunk_store_indices = Vec::new();
 
         for meta in metadata_vec {
             match meta {
@@ -141,6 +152,7 @@ impl From<Vec<Metadata>> for MetadataView {
                 Metadata::StateSnapshotBackup(s) => state_snapshot_backups.push(s),
                 Metadata::TransactionBackup(t) => transaction_backups.push(t),
                 Metadata::Identity(i) => identity = Some(i),
+                Metadata::ChunkStoreIndex(c) => chunk_store_indices.push(c),
             }
         }
 
@@ -149,6 +161,7 @@ impl From<Vec<Metadata>> for MetadataView {
             state_snapshot_backups,
             transaction_backups,
             _identity: identity,
+            chunk_store_indices,
         }
     }
 }
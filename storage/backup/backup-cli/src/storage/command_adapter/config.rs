@@ -67,6 +67,12 @@ pub struct Commands {
     /// Command line to list all existing metadata file handles.
     /// expected stdout to stream out lines of file handles.
     pub list_metadata_files: String,
+    /// Command line to delete a file, only needed to garbage collect the chunk store. Deleting a
+    /// file that doesn't exist must succeed.
+    /// input env vars:
+    ///     $FILE_HANDLE
+    #[serde(default)]
+    pub delete_file: Option<String>,
 }
 
 #[derive(Clone, Default, Deserialize)]
@@ -15,7 +15,7 @@ use async_trait::async_trait;
 use clap::Parser;
 use std::path::{Path, PathBuf};
 use tokio::{
-    fs::{create_dir_all, read_dir, OpenOptions},
+    fs::{create_dir_all, read_dir, remove_file, OpenOptions},
     io::{AsyncRead, AsyncWrite, AsyncWriteExt},
 };
 
@@ -123,4 +123,12 @@ impl BackupStorage for LocalFs {
         }
         Ok(res)
     }
+
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let path = self.dir.join(file_handle);
+        match remove_file(&path).await {
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            res => res.err_notes(&path),
+        }
+    }
 }
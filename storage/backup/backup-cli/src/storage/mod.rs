@@ -13,7 +13,7 @@ use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
     local_fs::{LocalFs, LocalFsOpt},
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use async_trait::async_trait;
 use clap::Parser;
 use once_cell::sync::Lazy;
@@ -167,6 +167,15 @@ pub trait BackupStorage: Send + Sync {
     ///   2. But the cache does expect the content stays the same for a file handle, so when
     /// reorganising metadata files, give them new unique names.
     async fn list_metadata_files(&self) -> Result<Vec<FileHandle>>;
+    /// Deletes a file, so that unreferenced chunks of the chunk store can be garbage collected.
+    /// Deleting a file that doesn't exist anymore must succeed, since garbage collection can be
+    /// interrupted and retried.
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        bail!(
+            "This backup storage doesn't support deleting files, can't delete {}.",
+            file_handle
+        )
+    }
 }
 
 #[derive(Parser)]
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_config::config::RocksdbConfig;
-use rocksdb::Options;
+use rocksdb::{Cache, Options};
 
 // TODO: Clean this up. It is currently separated into its own crate
 // to avoid circular dependencies, because it depends on aptos-config (which
@@ -17,6 +17,14 @@ pub fn gen_rocksdb_options(config: &RocksdbConfig, readonly: bool) -> Options {
         db_opts.create_if_missing(true);
         db_opts.create_missing_column_families(true);
     }
+    if config.row_cache_size > 0 {
+        let row_cache = Cache::new_lru_cache(config.row_cache_size as usize)
+            .expect("Create Rocksdb row cache failed.");
+        db_opts.set_row_cache(&row_cache);
+    }
+    if config.enable_statistics {
+        db_opts.enable_statistics();
+    }
 
     db_opts
 }
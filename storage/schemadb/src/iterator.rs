@@ -6,6 +6,7 @@ use crate::{
     APTOS_SCHEMADB_ITER_LATENCY_SECONDS,
 };
 use anyhow::Result;
+use fail::fail_point;
 use std::marker::PhantomData;
 
 pub enum ScanDirection {
@@ -35,11 +36,13 @@ where
 
     /// Seeks to the first key.
     pub fn seek_to_first(&mut self) {
+        fail_point!("schemadb::read");
         self.db_iter.seek_to_first();
     }
 
     /// Seeks to the last key.
     pub fn seek_to_last(&mut self) {
+        fail_point!("schemadb::read");
         self.db_iter.seek_to_last();
     }
 
@@ -50,6 +53,7 @@ where
         SK: SeekKeyCodec<S>,
     {
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
+        fail_point!("schemadb::read");
         self.db_iter.seek(&key);
         Ok(())
     }
@@ -63,6 +67,7 @@ where
         SK: SeekKeyCodec<S>,
     {
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
+        fail_point!("schemadb::read");
         self.db_iter.seek_for_prev(&key);
         Ok(())
     }
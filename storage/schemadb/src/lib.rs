@@ -30,6 +30,7 @@ use crate::{
 use anyhow::{format_err, Result};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
+use fail::fail_point;
 use std::{collections::HashMap, iter::Iterator, path::Path};
 
 use iterator::{ScanDirection, SchemaIterator};
@@ -178,6 +179,8 @@ impl DB {
         let k = <S::Key as KeyCodec<S>>::encode_key(schema_key)?;
         let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
+        // Simulates a slow disk in tests.
+        fail_point!("schemadb::read");
         let result = self.inner.get_cf(cf_handle, &k)?;
         APTOS_SCHEMADB_GET_BYTES
             .with_label_values(&[S::COLUMN_FAMILY_NAME])
@@ -238,6 +241,8 @@ impl DB {
         }
         let serialized_size = db_batch.size_in_bytes();
 
+        // Simulates slow fsyncs in tests, as all writes are synchronous.
+        fail_point!("schemadb::sync_write");
         self.inner.write_opt(db_batch, &default_write_options())?;
 
         // Bump counters only after DB write succeeds.
@@ -277,6 +282,13 @@ impl DB {
         Ok(self.inner.flush_cf(self.get_cf_handle(cf_name)?)?)
     }
 
+    /// Compacts the whole key range of a column family, dropping deleted data from disk.
+    pub fn compact_cf(&self, cf_name: &str) -> Result<()> {
+        self.inner
+            .compact_range_cf::<&[u8], &[u8]>(self.get_cf_handle(cf_name)?, None, None);
+        Ok(())
+    }
+
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> Result<u64> {
         self.inner
             .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)?
@@ -289,6 +301,12 @@ impl DB {
             })
     }
 
+    /// Returns the statistics of the DB as reported by rocksdb, or None if statistics are not
+    /// enabled.
+    pub fn get_statistics(&self) -> Result<Option<String>> {
+        Ok(self.inner.property_value("rocksdb.options-statistics")?)
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
@@ -10,7 +10,7 @@ use aptos_types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::CORE_CODE_ADDRESS,
-    contract_event::EventWithVersion,
+    contract_event::{ContractEvent, EventWithVersion},
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
     event::EventKey,
@@ -28,10 +28,12 @@ use aptos_types::{
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
-        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
+    write_set::WriteSet,
 };
+use move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
@@ -99,6 +101,28 @@ pub enum Order {
     Descending,
 }
 
+/// A block along with (a prefix of) its transactions, see
+/// [DbReader::get_block_by_height_with_transactions].
+#[derive(Clone, Debug)]
+pub struct BlockWithTransactions {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub new_block_event: NewBlockEvent,
+    /// The transactions of the block in version order, starting at `first_version`
+    pub transactions: Vec<BlockTransaction>,
+}
+
+/// A committed transaction along with everything needed to render it
+#[derive(Clone, Debug)]
+pub struct BlockTransaction {
+    pub version: Version,
+    pub transaction: Transaction,
+    pub info: TransactionInfo,
+    pub events: Vec<ContractEvent>,
+    pub write_set: WriteSet,
+    pub accumulator_root_hash: HashValue,
+}
+
 /// Trait that is implemented by a DB that supports certain public (to client) read APIs
 /// expected of an Aptos DB
 #[allow(unused_variables)]
@@ -206,6 +230,21 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// See [AptosDB::get_events_by_type].
+    ///
+    /// [AptosDB::get_events_by_type]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_events_by_type
+    fn get_events_by_type(
+        &self,
+        type_tag: &TypeTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(u64, EventWithVersion)>> {
+        unimplemented!()
+    }
+
     /// See [AptosDB::get_block_timestamp].
     ///
     /// [AptosDB::get_block_timestamp]:
@@ -233,6 +272,18 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the block at the given height along with up to `limit` of its transactions, none
+    /// beyond `ledger_version`. The transactions are read with one iteration over each column
+    /// family rather than one lookup per transaction.
+    fn get_block_by_height_with_transactions(
+        &self,
+        height: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<BlockWithTransactions> {
+        unimplemented!()
+    }
+
     /// Gets the version of the last transaction committed before timestamp,
     /// a committed block at or after the required timestamp must exist (otherwise it's possible
     /// the next block committed as a timestamp smaller than the one in the request).
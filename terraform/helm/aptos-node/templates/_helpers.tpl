@@ -57,6 +57,23 @@ app.kubernetes.io/part-of: {{ include "aptos-validator.name" . }}
 app.kubernetes.io/managed-by: helm
 {{- end -}}
 
+{{/*
+Whether the validator with the given index, and its fullnodes, are deployed by this release. If
+`validator.region` is set, only the validators that `validator.regions` places in that region are.
+Usage: include "aptos-validator.deployed" (list $ $i)
+*/}}
+{{- define "aptos-validator.deployed" -}}
+{{- $root := index . 0 -}}
+{{- $i := index . 1 -}}
+{{- if not $root.Values.validator.region -}}
+true
+{{- else if gt (len $root.Values.validator.regions) $i -}}
+{{- if eq (index $root.Values.validator.regions $i) $root.Values.validator.region -}}
+true
+{{- end -}}
+{{- end -}}
+{{- end -}}
+
 {{/*
 Create the name of the service account to use
 */}}
@@ -4,10 +4,12 @@
 use anyhow::{format_err, Context, Result};
 use aptos_config::config::ConsensusConfig;
 use aptos_forge::{
+    latency_probe::ProbeLatency,
     success_criteria::{LatencyType, StateProgressThreshold, SuccessCriteria},
     system_metrics::{MetricsThreshold, SystemMetricsThreshold},
     ForgeConfig, Options, *,
 };
+use aptos_gas::GasScheduleOverrides;
 use aptos_logger::Level;
 use aptos_rest_client::Client as RestClient;
 use aptos_sdk::{move_types::account_address::AccountAddress, transaction_builder::aptos_stdlib};
@@ -37,6 +39,7 @@ use aptos_testcases::{
 use std::{
     env,
     num::NonZeroUsize,
+    path::PathBuf,
     process,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -87,6 +90,7 @@ enum CliCommand {
 enum TestCommand {
     LocalSwarm(LocalSwarm),
     K8sSwarm(K8sSwarm),
+    ExistingNetwork(ExistingNetwork),
 }
 
 #[derive(StructOpt, Debug)]
@@ -97,7 +101,22 @@ enum OperatorCommand {
 }
 
 #[derive(StructOpt, Debug)]
-struct LocalSwarm {}
+struct LocalSwarm {
+    #[structopt(
+        long,
+        help = "Path to a yaml file overriding gas parameters of the swarm at genesis"
+    )]
+    gas_overrides: Option<PathBuf>,
+}
+
+#[derive(StructOpt, Debug)]
+struct ExistingNetwork {
+    #[structopt(
+        long,
+        help = "Path to a yaml file describing the endpoints and root key of the network"
+    )]
+    network_config: PathBuf,
+}
 
 #[derive(StructOpt, Debug)]
 struct K8sSwarm {
@@ -137,6 +156,31 @@ struct K8sSwarm {
     keep: bool,
     #[structopt(long, help = "If set, enables HAProxy for each of the validators")]
     enable_haproxy: bool,
+    #[structopt(
+        long,
+        help = "Regions to spread the validators across, as name[:weight][@kube_context]. \
+            If set, the kube context selects the cluster running the validators of the region. \
+            Repeat for each region"
+    )]
+    region: Vec<K8sRegion>,
+    #[structopt(
+        long,
+        help = "Latency to emulate between validators in different regions of a single cluster",
+        default_value = "100"
+    )]
+    inter_region_latency_ms: u64,
+    #[structopt(
+        long,
+        help = "How much the emulated latency of a packet depends on that of the previous one, \
+            as a percentage",
+        default_value = "50"
+    )]
+    latency_correlation_percentage: u64,
+    #[structopt(
+        long,
+        help = "Domain of the external DNS names of validators in different clusters"
+    )]
+    domain: Option<String>,
 }
 
 #[derive(StructOpt, Debug)]
@@ -233,7 +277,7 @@ fn main() -> Result<()> {
 
             // Run the test suite
             match test_cmd {
-                TestCommand::LocalSwarm(..) => {
+                TestCommand::LocalSwarm(local) => {
                     // Loosen all criteria for local runs
                     test_suite.get_success_criteria_mut().avg_tps = 400;
                     let previous_emit_job = test_suite.get_emit_job().clone();
@@ -242,10 +286,15 @@ fn main() -> Result<()> {
                             mempool_backlog: 5000,
                         }));
 
+                    let mut factory = LocalFactory::from_workspace()?;
+                    if let Some(gas_overrides) = &local.gas_overrides {
+                        factory =
+                            factory.with_gas_overrides(GasScheduleOverrides::load(gas_overrides)?);
+                    }
                     run_forge(
                         duration,
                         test_suite,
-                        LocalFactory::from_workspace()?,
+                        factory,
                         &args.options,
                         args.changelog.clone(),
                     )
@@ -254,21 +303,34 @@ fn main() -> Result<()> {
                     if let Some(move_modules_dir) = &k8s.move_modules_dir {
                         test_suite = test_suite.with_genesis_modules_path(move_modules_dir.clone());
                     }
+                    let mut factory = K8sFactory::new(
+                        k8s.namespace.clone(),
+                        k8s.image_tag.clone(),
+                        k8s.upgrade_image_tag.clone(),
+                        k8s.port_forward,
+                        k8s.reuse,
+                        k8s.keep,
+                        k8s.enable_haproxy,
+                    )
+                    .unwrap();
+                    if k8s.region.len() > 1 {
+                        factory = factory.with_multi_region(multi_region_config(
+                            &k8s.region,
+                            k8s.inter_region_latency_ms,
+                            k8s.latency_correlation_percentage,
+                            k8s.domain.as_deref(),
+                        )?);
+                    }
+                    run_forge(duration, test_suite, factory, &args.options, args.changelog)?;
+                    Ok(())
+                }
+                TestCommand::ExistingNetwork(existing) => {
                     run_forge(
                         duration,
                         test_suite,
-                        K8sFactory::new(
-                            k8s.namespace.clone(),
-                            k8s.image_tag.clone(),
-                            k8s.upgrade_image_tag.clone(),
-                            k8s.port_forward,
-                            k8s.reuse,
-                            k8s.keep,
-                            k8s.enable_haproxy,
-                        )
-                        .unwrap(),
+                        ExistingNetworkFactory::from_file(&existing.network_config)?,
                         &args.options,
-                        args.changelog,
+                        args.changelog.clone(),
                     )?;
                     Ok(())
                 }
@@ -282,12 +344,13 @@ fn main() -> Result<()> {
                     set_stateful_set_image_tag_config.container_name,
                     set_stateful_set_image_tag_config.image_tag,
                     set_stateful_set_image_tag_config.namespace,
+                    None,
                 ))?;
                 Ok(())
             }
             OperatorCommand::CleanUp(cleanup) => {
                 if let Some(namespace) = cleanup.namespace {
-                    runtime.block_on(uninstall_testnet_resources(namespace))?;
+                    runtime.block_on(uninstall_testnet_resources(namespace, None))?;
                 } else {
                     runtime.block_on(cleanup_cluster_with_management())?;
                 }
@@ -305,6 +368,7 @@ fn main() -> Result<()> {
                     resize.enable_haproxy,
                     None,
                     None,
+                    None,
                 ))?;
                 Ok(())
             }
@@ -312,6 +376,26 @@ fn main() -> Result<()> {
     }
 }
 
+/// Builds a topology where every pair of regions sees the same latency
+fn multi_region_config(
+    regions: &[K8sRegion],
+    latency_ms: u64,
+    correlation_percentage: u64,
+    domain: Option<&str>,
+) -> Result<MultiRegionConfig> {
+    let mut config = MultiRegionConfig::new(regions.to_vec())?
+        .with_correlation_percentage(correlation_percentage)?;
+    for (i, from) in regions.iter().enumerate() {
+        for to in &regions[i + 1..] {
+            config = config.with_latency(&from.name, &to.name, latency_ms)?;
+        }
+    }
+    if let Some(domain) = domain {
+        config = config.with_domain(domain);
+    }
+    Ok(config)
+}
+
 pub fn run_forge<F: Factory>(
     global_duration: Duration,
     tests: ForgeConfig<'_>,
@@ -928,6 +1012,8 @@ fn land_blocking_test_suite(duration: Duration) -> ForgeConfig<'static> {
                 // Check that we don't use more than 10 GB of memory for 30% of the time.
                 MetricsThreshold::new(10 * 1024 * 1024 * 1024, 30),
             ))
+            // Check how long transactions take to become visible on all nodes under load.
+            .add_probe_latency_threshold(20.0, LatencyType::P50, ProbeLatency::ApiVisibility)
             .add_chain_progress(StateProgressThreshold {
                 max_no_progress_secs: 10.0,
                 max_round_gap: 4,
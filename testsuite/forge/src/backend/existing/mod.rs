@@ -0,0 +1,182 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Factory, GenesisConfig, GenesisConfigFn, NodeConfigFn, Result, Swarm, Version};
+use anyhow::{bail, format_err, Context};
+use aptos_logger::{info, warn};
+use aptos_sdk::{
+    crypto::ed25519::Ed25519PrivateKey,
+    move_types::account_address::AccountAddress,
+    types::{chain_id::ChainId, PeerId},
+};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use url::Url;
+
+mod node;
+mod swarm;
+
+pub use node::ExistingNode;
+pub use swarm::ExistingSwarm;
+
+/// Describes an already running network, e.g., a long lived staging network.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExistingNetworkConfig {
+    pub chain_id: u8,
+    /// Hex encoded private key of the account used to fund the test accounts
+    pub root_key: String,
+    /// Address of the root account, defaults to the test root address
+    #[serde(default)]
+    pub root_address: Option<AccountAddress>,
+    /// Version label of the software the network is running, only used for reporting
+    #[serde(default = "default_version")]
+    pub version: String,
+    pub validators: Vec<ExistingNodeConfig>,
+    #[serde(default)]
+    pub full_nodes: Vec<ExistingNodeConfig>,
+    /// Prometheus server scraping the network, used to query metrics
+    #[serde(default)]
+    pub prometheus_url: Option<Url>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExistingNodeConfig {
+    pub name: String,
+    pub peer_id: PeerId,
+    /// REST API endpoint, including the `/v1` path
+    pub rest_api: Url,
+    pub inspection_service: Url,
+    /// Config file of the node, relative to the network config file if the path is relative
+    pub config: PathBuf,
+}
+
+fn default_version() -> String {
+    "existing".to_string()
+}
+
+impl ExistingNetworkConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: Self = serde_yaml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        if let Some(dir) = path.parent() {
+            for node in config.validators.iter_mut().chain(&mut config.full_nodes) {
+                node.config = dir.join(&node.config);
+            }
+        }
+        if config.validators.is_empty() {
+            bail!("An existing network needs at least one validator");
+        }
+        Ok(config)
+    }
+
+    fn root_key(&self) -> Result<Ed25519PrivateKey> {
+        let bytes = hex::decode(self.root_key.trim_start_matches("0x"))?;
+        Ed25519PrivateKey::try_from(bytes.as_slice())
+            .map_err(|e| format_err!("Invalid root key: {}", e))
+    }
+
+    fn version(&self) -> Version {
+        Version::new(0, self.version.clone())
+    }
+}
+
+/// Attaches to an already running network instead of provisioning a new one.
+/// Nodes of the network can't be started, stopped or upgraded by the tests.
+pub struct ExistingNetworkFactory {
+    config: ExistingNetworkConfig,
+}
+
+impl ExistingNetworkFactory {
+    pub fn new(config: ExistingNetworkConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::new(ExistingNetworkConfig::load(path)?))
+    }
+}
+
+#[async_trait::async_trait]
+impl Factory for ExistingNetworkFactory {
+    fn versions<'a>(&'a self) -> Box<dyn Iterator<Item = Version> + 'a> {
+        Box::new(std::iter::once(self.config.version()))
+    }
+
+    async fn launch_swarm(
+        &self,
+        _rng: &mut StdRng,
+        num_validators: NonZeroUsize,
+        num_fullnodes: usize,
+        _version: &Version,
+        _genesis_version: &Version,
+        genesis_config: Option<&GenesisConfig>,
+        _cleanup_duration: Duration,
+        genesis_config_fn: Option<GenesisConfigFn>,
+        node_config_fn: Option<NodeConfigFn>,
+        existing_db_tag: Option<String>,
+    ) -> Result<Box<dyn Swarm>> {
+        if genesis_config.is_some() || genesis_config_fn.is_some() {
+            bail!("Genesis of an existing network can't be changed");
+        }
+        if node_config_fn.is_some() || existing_db_tag.is_some() {
+            bail!("Nodes of an existing network can't be reconfigured");
+        }
+        if num_validators.get() != self.config.validators.len()
+            || num_fullnodes != self.config.full_nodes.len()
+        {
+            warn!(
+                "Test requested {} validators and {} fullnodes, the existing network has {} and {}",
+                num_validators,
+                num_fullnodes,
+                self.config.validators.len(),
+                self.config.full_nodes.len()
+            );
+        }
+
+        let chain_id = ChainId::new(self.config.chain_id);
+        info!("Attaching to existing network with chain id {}", chain_id);
+        let swarm = ExistingSwarm::new(&self.config, self.config.root_key()?, chain_id).await?;
+        Ok(Box::new(swarm))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_existing_network_config() {
+        let config: ExistingNetworkConfig = serde_yaml::from_str(
+            r#"
+chain_id: 40
+root_key: "0x0101010101010101010101010101010101010101010101010101010101010101"
+validators:
+  - name: validator-0
+    peer_id: "0x1"
+    rest_api: "http://validator-0:8080/v1"
+    inspection_service: "http://validator-0:9101"
+    config: validator-0/node.yaml
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.version, "existing");
+        assert_eq!(
+            config.validators[0].config,
+            PathBuf::from("validator-0/node.yaml")
+        );
+        assert!(config.full_nodes.is_empty());
+        assert!(config.root_key().is_ok());
+    }
+}
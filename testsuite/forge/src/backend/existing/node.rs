@@ -0,0 +1,122 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{FullNode, HealthCheckError, Node, NodeExt, Result, Validator, Version};
+use anyhow::{bail, format_err};
+use aptos_config::config::NodeConfig;
+use aptos_sdk::types::PeerId;
+use serde_json::Value;
+use std::fmt::{Debug, Formatter};
+use url::Url;
+
+/// A node of a network that Forge doesn't manage. It can be queried, but not controlled.
+pub struct ExistingNode {
+    pub(crate) name: String,
+    pub(crate) index: usize,
+    pub(crate) peer_id: PeerId,
+    pub(crate) version: Version,
+    pub(crate) rest_api_endpoint: Url,
+    pub(crate) inspection_service_endpoint: Url,
+    /// The config the node was started with, as loaded from its config file
+    pub(crate) config: NodeConfig,
+}
+
+impl ExistingNode {
+    fn unsupported<T>(&self, operation: &str) -> Result<T> {
+        bail!(
+            "Can't {} node {}: it belongs to an existing network",
+            operation,
+            self.name
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl Node for ExistingNode {
+    fn peer_id(&self) -> PeerId {
+        self.peer_id
+    }
+
+    fn index(&self) -> usize {
+        self.index
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> Version {
+        self.version.clone()
+    }
+
+    fn rest_api_endpoint(&self) -> Url {
+        self.rest_api_endpoint.clone()
+    }
+
+    fn inspection_service_endpoint(&self) -> Url {
+        self.inspection_service_endpoint.clone()
+    }
+
+    fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        self.unsupported("start")
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.unsupported("stop")
+    }
+
+    async fn get_identity(&mut self) -> Result<String> {
+        self.unsupported("get the identity of")
+    }
+
+    async fn set_identity(&mut self, _k8s_secret_name: String) -> Result<()> {
+        self.unsupported("set the identity of")
+    }
+
+    async fn clear_storage(&mut self) -> Result<()> {
+        self.unsupported("clear the storage of")
+    }
+
+    async fn health_check(&mut self) -> Result<(), HealthCheckError> {
+        self.rest_client()
+            .get_ledger_information()
+            .await
+            .map(|_| ())
+            .map_err(|e| {
+                HealthCheckError::Failure(format_err!("Existing node health_check failed: {}", e))
+            })
+    }
+
+    fn counter(&self, counter: &str, _port: u64) -> Result<f64> {
+        let url = self.inspection_service_endpoint.join("counters")?;
+        let response: Value = reqwest::blocking::get(url)?.json()?;
+        response[counter].as_f64().ok_or_else(|| {
+            format_err!(
+                "Counter({}) was not a number: {:?}",
+                counter,
+                response[counter]
+            )
+        })
+    }
+
+    fn expose_metric(&self) -> Result<u64> {
+        self.inspection_service_endpoint
+            .port_or_known_default()
+            .map(u64::from)
+            .ok_or_else(|| format_err!("No port in {}", self.inspection_service_endpoint))
+    }
+}
+
+impl Validator for ExistingNode {}
+
+impl FullNode for ExistingNode {}
+
+impl Debug for ExistingNode {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.name, self.rest_api_endpoint)
+    }
+}
@@ -0,0 +1,310 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{ExistingNetworkConfig, ExistingNode, ExistingNodeConfig};
+use crate::{
+    interface::system_metrics::SystemMetricsThreshold, query_sequence_number, ChainInfo, FullNode,
+    LogEntry, Node, NodeExt, Result, Swarm, SwarmChaos, Validator, Version,
+};
+use anyhow::{anyhow, bail, format_err};
+use aptos_config::config::NodeConfig;
+use aptos_logger::{info, warn};
+use aptos_sdk::{
+    crypto::ed25519::Ed25519PrivateKey,
+    types::{
+        account_config::aptos_test_root_address, chain_id::ChainId, AccountKey, LocalAccount,
+        PeerId,
+    },
+};
+use prometheus_http_query::{response::PromqlResult, Client as PrometheusClient};
+use std::{collections::HashMap, path::Path, time::SystemTime};
+
+/// A swarm over the nodes of an already running network.
+pub struct ExistingSwarm {
+    validators: HashMap<PeerId, ExistingNode>,
+    fullnodes: HashMap<PeerId, ExistingNode>,
+    root_account: LocalAccount,
+    chain_id: ChainId,
+    version: Version,
+    prom_client: Option<PrometheusClient>,
+}
+
+impl ExistingSwarm {
+    pub async fn new(
+        config: &ExistingNetworkConfig,
+        root_key: Ed25519PrivateKey,
+        chain_id: ChainId,
+    ) -> Result<Self> {
+        let version = config.version();
+        let to_nodes = |nodes: &[ExistingNodeConfig]| -> Result<HashMap<_, _>> {
+            let mut existing_nodes = HashMap::new();
+            for (index, node) in nodes.iter().enumerate() {
+                let node_config = NodeConfig::load(&node.config).map_err(|e| {
+                    format_err!(
+                        "Failed to load the config of node {} from {}: {}",
+                        node.name,
+                        node.config.display(),
+                        e
+                    )
+                })?;
+                existing_nodes.insert(
+                    node.peer_id,
+                    ExistingNode {
+                        name: node.name.clone(),
+                        index,
+                        peer_id: node.peer_id,
+                        version: version.clone(),
+                        rest_api_endpoint: node.rest_api.clone(),
+                        inspection_service_endpoint: node.inspection_service.clone(),
+                        config: node_config,
+                    },
+                );
+            }
+            Ok(existing_nodes)
+        };
+        let validators = to_nodes(&config.validators)?;
+        let fullnodes = to_nodes(&config.full_nodes)?;
+
+        let mut swarm = Self {
+            validators,
+            fullnodes,
+            // The sequence number is fetched from the network below
+            root_account: LocalAccount::new(
+                config.root_address.unwrap_or_else(aptos_test_root_address),
+                AccountKey::from_private_key(root_key),
+                0,
+            ),
+            chain_id,
+            version,
+            prom_client: config
+                .prometheus_url
+                .as_ref()
+                .map(|url| PrometheusClient::from(reqwest::Client::new(), url.as_str()))
+                .transpose()
+                .map_err(|e| format_err!("Failed to create prometheus client: {}", e))?,
+        };
+
+        let client = swarm.validator_by_index(0)?.rest_client();
+        let actual_chain_id = client.get_index().await?.into_inner().chain_id;
+        if actual_chain_id != chain_id.id() {
+            bail!(
+                "Expected chain id {} but the network has chain id {}",
+                chain_id,
+                actual_chain_id
+            );
+        }
+        let address = swarm.root_account.address();
+        let sequence_number = query_sequence_number(&client, address)
+            .await
+            .map_err(|e| format_err!("Failed to query the root account {}: {}", address, e))?;
+        *swarm.root_account.sequence_number_mut() = sequence_number;
+
+        Ok(swarm)
+    }
+
+    fn validator_by_index(&self, idx: usize) -> Result<&ExistingNode> {
+        self.validators
+            .values()
+            .find(|node| node.index() == idx)
+            .ok_or_else(|| anyhow!("No validator with index {}", idx))
+    }
+
+    fn get_rest_api_url(&self, idx: usize) -> String {
+        self.validator_by_index(idx)
+            .unwrap()
+            .rest_api_endpoint()
+            .to_string()
+    }
+
+    fn unsupported<T>(operation: &str) -> Result<T> {
+        bail!("Can't {} on an existing network", operation)
+    }
+}
+
+#[async_trait::async_trait]
+impl Swarm for ExistingSwarm {
+    async fn health_check(&mut self) -> Result<()> {
+        let mut unhealthy_nodes = vec![];
+        for node in self
+            .validators
+            .values_mut()
+            .chain(self.fullnodes.values_mut())
+        {
+            if let Err(e) = node.health_check().await {
+                info!("Node {} unhealthy: {}", node.name(), e);
+                unhealthy_nodes.push(node.name().to_string());
+            }
+        }
+        if !unhealthy_nodes.is_empty() {
+            bail!("Unhealthy nodes: {:?}", unhealthy_nodes)
+        }
+        Ok(())
+    }
+
+    fn validators<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn Validator> + 'a> {
+        let mut validators: Vec<_> = self
+            .validators
+            .values()
+            .map(|v| v as &'a dyn Validator)
+            .collect();
+        validators.sort_by_key(|v| v.index());
+        Box::new(validators.into_iter())
+    }
+
+    fn validators_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut dyn Validator> + 'a> {
+        let mut validators: Vec<_> = self
+            .validators
+            .values_mut()
+            .map(|v| v as &'a mut dyn Validator)
+            .collect();
+        validators.sort_by_key(|v| v.index());
+        Box::new(validators.into_iter())
+    }
+
+    fn validator(&self, id: PeerId) -> Option<&dyn Validator> {
+        self.validators.get(&id).map(|v| v as &dyn Validator)
+    }
+
+    fn validator_mut(&mut self, id: PeerId) -> Option<&mut dyn Validator> {
+        self.validators
+            .get_mut(&id)
+            .map(|v| v as &mut dyn Validator)
+    }
+
+    async fn upgrade_validator(&mut self, _id: PeerId, _version: &Version) -> Result<()> {
+        Self::unsupported("upgrade validators")
+    }
+
+    async fn restart_validator_with_options(
+        &mut self,
+        _id: PeerId,
+        _wipe_db: bool,
+        _clear_secure_storage: bool,
+    ) -> Result<()> {
+        Self::unsupported("restart validators")
+    }
+
+    async fn snapshot(&mut self, _snapshot_dir: &Path) -> Result<()> {
+        Self::unsupported("snapshot the nodes")
+    }
+
+    fn full_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn FullNode> + 'a> {
+        let mut fullnodes: Vec<_> = self
+            .fullnodes
+            .values()
+            .map(|v| v as &'a dyn FullNode)
+            .collect();
+        fullnodes.sort_by_key(|v| v.index());
+        Box::new(fullnodes.into_iter())
+    }
+
+    fn full_nodes_mut<'a>(&'a mut self) -> Box<dyn Iterator<Item = &'a mut dyn FullNode> + 'a> {
+        let mut fullnodes: Vec<_> = self
+            .fullnodes
+            .values_mut()
+            .map(|v| v as &'a mut dyn FullNode)
+            .collect();
+        fullnodes.sort_by_key(|v| v.index());
+        Box::new(fullnodes.into_iter())
+    }
+
+    fn full_node(&self, id: PeerId) -> Option<&dyn FullNode> {
+        self.fullnodes.get(&id).map(|v| v as &dyn FullNode)
+    }
+
+    fn full_node_mut(&mut self, id: PeerId) -> Option<&mut dyn FullNode> {
+        self.fullnodes.get_mut(&id).map(|v| v as &mut dyn FullNode)
+    }
+
+    fn add_validator(&mut self, _version: &Version, _template: NodeConfig) -> Result<PeerId> {
+        Self::unsupported("add validators")
+    }
+
+    fn remove_validator(&mut self, _id: PeerId) -> Result<()> {
+        Self::unsupported("remove validators")
+    }
+
+    fn add_validator_full_node(
+        &mut self,
+        _version: &Version,
+        _template: NodeConfig,
+        _id: PeerId,
+    ) -> Result<PeerId> {
+        Self::unsupported("add validator fullnodes")
+    }
+
+    fn add_full_node(&mut self, _version: &Version, _template: NodeConfig) -> Result<PeerId> {
+        Self::unsupported("add fullnodes")
+    }
+
+    fn remove_full_node(&mut self, _id: PeerId) -> Result<()> {
+        Self::unsupported("remove fullnodes")
+    }
+
+    fn versions<'a>(&'a self) -> Box<dyn Iterator<Item = Version> + 'a> {
+        Box::new(std::iter::once(self.version.clone()))
+    }
+
+    fn chain_info(&mut self) -> ChainInfo<'_> {
+        let rest_api_url = self.get_rest_api_url(0);
+        ChainInfo::new(&mut self.root_account, rest_api_url, self.chain_id)
+    }
+
+    fn logs_location(&mut self) -> String {
+        "Logs of an existing network aren't collected by Forge.".to_string()
+    }
+
+    fn grep_logs(&self, _pattern: &str, _since: SystemTime) -> Result<Vec<(PeerId, LogEntry)>> {
+        Self::unsupported("search logs")
+    }
+
+    fn inject_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
+        Self::unsupported("inject chaos")
+    }
+
+    fn remove_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
+        Self::unsupported("remove chaos")
+    }
+
+    fn remove_all_chaos(&mut self) -> Result<()> {
+        // Chaos is never injected, so there is nothing to remove
+        Ok(())
+    }
+
+    async fn ensure_no_validator_restart(&self) -> Result<()> {
+        // Forge doesn't run the nodes, so it can't see their restarts
+        warn!("Skipping the validator restart check on an existing network");
+        Ok(())
+    }
+
+    async fn ensure_no_fullnode_restart(&self) -> Result<()> {
+        warn!("Skipping the fullnode restart check on an existing network");
+        Ok(())
+    }
+
+    async fn ensure_healthy_system_metrics(
+        &mut self,
+        _start_time: i64,
+        _end_time: i64,
+        _threshold: SystemMetricsThreshold,
+    ) -> Result<()> {
+        Self::unsupported("check system metrics")
+    }
+
+    async fn query_metrics(
+        &self,
+        query: &str,
+        time: Option<i64>,
+        timeout: Option<i64>,
+    ) -> Result<PromqlResult> {
+        match &self.prom_client {
+            Some(client) => Ok(client.query(query, time, timeout).await?),
+            None => bail!("No prometheus_url configured for the existing network"),
+        }
+    }
+
+    fn chain_info_for_node(&mut self, idx: usize) -> ChainInfo<'_> {
+        let rest_api_url = self.get_rest_api_url(idx);
+        ChainInfo::new(&mut self.root_account, rest_api_url, self.chain_id)
+    }
+}
@@ -3,12 +3,13 @@
 
 use crate::{
     get_fullnodes, get_validators, k8s_wait_genesis_strategy, k8s_wait_nodes_strategy,
-    nodes_healthcheck, wait_stateful_set, Create, GenesisConfigFn, K8sApi, K8sNode, NodeConfigFn,
-    Result, APTOS_NODE_HELM_CHART_PATH, APTOS_NODE_HELM_RELEASE_NAME, DEFAULT_ROOT_KEY,
-    FORGE_KEY_SEED, FULLNODE_HAPROXY_SERVICE_SUFFIX, FULLNODE_SERVICE_SUFFIX,
-    GENESIS_HELM_CHART_PATH, GENESIS_HELM_RELEASE_NAME, HELM_BIN, KUBECTL_BIN,
-    MANAGEMENT_CONFIGMAP_PREFIX, NAMESPACE_CLEANUP_THRESHOLD_SECS, POD_CLEANUP_THRESHOLD_SECS,
-    VALIDATOR_HAPROXY_SERVICE_SUFFIX, VALIDATOR_SERVICE_SUFFIX,
+    nodes_healthcheck, wait_stateful_set, Create, GenesisConfigFn, K8sApi, K8sNode,
+    MultiRegionConfig, NodeConfigFn, Result, APTOS_NODE_HELM_CHART_PATH,
+    APTOS_NODE_HELM_RELEASE_NAME, DEFAULT_ROOT_KEY, FORGE_KEY_SEED,
+    FULLNODE_HAPROXY_SERVICE_SUFFIX, FULLNODE_SERVICE_SUFFIX, GENESIS_HELM_CHART_PATH,
+    GENESIS_HELM_RELEASE_NAME, HELM_BIN, KUBECTL_BIN, MANAGEMENT_CONFIGMAP_PREFIX,
+    NAMESPACE_CLEANUP_THRESHOLD_SECS, POD_CLEANUP_THRESHOLD_SECS, VALIDATOR_HAPROXY_SERVICE_SUFFIX,
+    VALIDATOR_SERVICE_SUFFIX,
 };
 use again::RetryPolicy;
 use anyhow::{anyhow, bail, format_err};
@@ -17,11 +18,12 @@ use aptos_sdk::types::PeerId;
 use k8s_openapi::api::{
     apps::v1::{Deployment, StatefulSet},
     batch::{v1::Job, v1beta1::CronJob},
-    core::v1::{ConfigMap, Namespace, PersistentVolume, PersistentVolumeClaim, Pod},
+    core::v1::{ConfigMap, Namespace, PersistentVolume, PersistentVolumeClaim, Pod, Secret},
 };
 use kube::{
     api::{Api, DeleteParams, ListParams, Meta, ObjectMeta, Patch, PatchParams, PostParams},
     client::Client as K8sClient,
+    config::KubeConfigOptions,
     Config, Error as KubeError,
 };
 use rand::Rng;
@@ -91,16 +93,16 @@ async fn wait_genesis_job(kube_client: &K8sClient, era: &str, kube_namespace: &s
     .await
 }
 
-/// Waits for a given number of HAProxy K8s Deployments to be ready
+/// Waits for the HAProxy K8s Deployments in front of the given validators to be ready
 async fn wait_node_haproxy(
     kube_client: &K8sClient,
     kube_namespace: &str,
-    num_haproxy: usize,
+    validators: &HashMap<PeerId, K8sNode>,
 ) -> Result<()> {
     aptos_retrier::retry_async(k8s_wait_nodes_strategy(), || {
         let deployments_api: Api<Deployment> = Api::namespaced(kube_client.clone(), kube_namespace);
         Box::pin(async move {
-            for i in 0..num_haproxy {
+            for i in validators.values().map(|v| v.index) {
                 let haproxy_deployment_name =
                     format!("{}-{}-haproxy", APTOS_NODE_HELM_RELEASE_NAME, i);
                 match deployments_api.get_status(&haproxy_deployment_name).await {
@@ -183,7 +185,11 @@ async fn delete_k8s_collection<T: Clone + DeserializeOwned + Meta>(
 }
 
 /// Delete existing k8s resources in the namespace. This is essentially helm uninstall but lighter weight
-pub(crate) async fn delete_k8s_resources(client: K8sClient, kube_namespace: &str) -> Result<()> {
+pub(crate) async fn delete_k8s_resources(
+    client: K8sClient,
+    kube_namespace: &str,
+    kube_context: Option<&str>,
+) -> Result<()> {
     // selector for the helm chart
     let aptos_node_helm_selector = "app.kubernetes.io/part-of=aptos-node";
     let testnet_addons_helm_selector = "app.kubernetes.io/part-of=testnet-addons";
@@ -213,14 +219,16 @@ pub(crate) async fn delete_k8s_resources(client: K8sClient, kube_namespace: &str
         // delete_k8s_collection(services.clone(), "Services", selector).await?;
     }
 
-    delete_all_chaos(kube_namespace)?;
+    delete_all_chaos(kube_namespace, kube_context)?;
 
     Ok(())
 }
 
-pub(crate) fn delete_all_chaos(kube_namespace: &str) -> Result<()> {
+pub(crate) fn delete_all_chaos(kube_namespace: &str, kube_context: Option<&str>) -> Result<()> {
     // clear everything manually, in case there are some dangling
-    let delete_networkchaos = ["-n", kube_namespace, "delete", "networkchaos", "--all"];
+    let mut delete_networkchaos = kubectl_context_args(kube_context);
+    delete_networkchaos
+        .extend(["-n", kube_namespace, "delete", "networkchaos", "--all"].map(String::from));
     info!("{:?}", delete_networkchaos);
     let delete_networkchaos_output = Command::new(KUBECTL_BIN)
         .stdout(Stdio::inherit())
@@ -239,8 +247,8 @@ pub(crate) fn delete_all_chaos(kube_namespace: &str) -> Result<()> {
 /// Deletes all Forge resources from the given namespace. If the namespace is "default", delete the management configmap
 /// as well as all compute resources. If the namespace is a Forge namespace (has the "forge-*" prefix), then simply delete
 /// the entire namespace
-async fn delete_k8s_cluster(kube_namespace: String) -> Result<()> {
-    let client: K8sClient = create_k8s_client().await;
+async fn delete_k8s_cluster(kube_namespace: String, kube_context: Option<&str>) -> Result<()> {
+    let client: K8sClient = create_k8s_client_for_context(kube_context).await;
 
     // if operating on the default namespace,
     match kube_namespace.as_str() {
@@ -270,7 +278,7 @@ async fn delete_k8s_cluster(kube_namespace: String) -> Result<()> {
                 }
                 Err(e) => bail!(e),
             };
-            delete_k8s_resources(client, "default").await?;
+            delete_k8s_resources(client, "default", kube_context).await?;
         }
         s if s.starts_with("forge") => {
             let namespaces: Api<Namespace> = Api::all(client);
@@ -382,9 +390,12 @@ fn upgrade_genesis_helm(options: &[String], kube_namespace: String) -> Result<()
     )
 }
 
-pub async fn uninstall_testnet_resources(kube_namespace: String) -> Result<()> {
+pub async fn uninstall_testnet_resources(
+    kube_namespace: String,
+    kube_context: Option<&str>,
+) -> Result<()> {
     // delete kubernetes resources
-    delete_k8s_cluster(kube_namespace.clone()).await?;
+    delete_k8s_cluster(kube_namespace.clone(), kube_context).await?;
     info!(
         "aptos-node resources for Forge removed in namespace: {}",
         kube_namespace
@@ -498,13 +509,15 @@ pub async fn install_testnet_resources(
     enable_haproxy: bool,
     genesis_helm_config_fn: Option<GenesisConfigFn>,
     node_helm_config_fn: Option<NodeConfigFn>,
+    multi_region: Option<&MultiRegionConfig>,
 ) -> Result<(HashMap<PeerId, K8sNode>, HashMap<PeerId, K8sNode>)> {
     let kube_client = create_k8s_client().await;
 
     // get deployment-specific helm values and cache it
     let tmp_dir = TempDir::new().expect("Could not create temp dir");
-    let aptos_node_values_file = dump_helm_values_to_file(APTOS_NODE_HELM_RELEASE_NAME, &tmp_dir)?;
-    let genesis_values_file = dump_helm_values_to_file(GENESIS_HELM_RELEASE_NAME, &tmp_dir)?;
+    let aptos_node_values_file =
+        dump_helm_values_to_file(APTOS_NODE_HELM_RELEASE_NAME, None, &tmp_dir)?;
+    let genesis_values_file = dump_helm_values_to_file(GENESIS_HELM_RELEASE_NAME, None, &tmp_dir)?;
 
     // generate a random era to wipe the network state
     let new_era = generate_new_era();
@@ -548,7 +561,7 @@ pub async fn install_testnet_resources(
         "-f".to_string(),
         aptos_node_values_file,
         "-f".to_string(),
-        aptos_node_forge_values_file,
+        aptos_node_forge_values_file.clone(),
     ];
 
     let mut genesis_upgrade_options = vec![
@@ -573,6 +586,37 @@ pub async fn install_testnet_resources(
     // wait for genesis to run again, and get the updated validators
     wait_genesis_job(&kube_client, &new_era, &kube_namespace).await?;
 
+    if let Some(multi_region) = multi_region.filter(|m| m.is_multi_cluster()) {
+        // the validators of each region run in the cluster of the region, with the genesis
+        // secrets created in the cluster of the test runner
+        for region in multi_region.regions() {
+            let kube_context = region.kube_context.as_deref();
+            let region_kube_client = create_k8s_client_for_context(kube_context).await;
+            copy_genesis_secrets(
+                &kube_client,
+                &region_kube_client,
+                &kube_namespace,
+                &new_era,
+                &multi_region.validator_indices(region, num_validators),
+            )
+            .await?;
+
+            let mut region_upgrade_options = helm_context_args(kube_context);
+            region_upgrade_options.extend([
+                // use the old values of the cluster of the region
+                "-f".to_string(),
+                dump_helm_values_to_file(APTOS_NODE_HELM_RELEASE_NAME, kube_context, &tmp_dir)?,
+                "-f".to_string(),
+                aptos_node_forge_values_file.clone(),
+                "--set".to_string(),
+                format!("validator.region={}", region.name),
+            ]);
+            upgrade_aptos_node_helm(region_upgrade_options.as_slice(), kube_namespace.clone())?;
+        }
+
+        return collect_running_multi_cluster_nodes(multi_region, kube_namespace).await;
+    }
+
     // TODO(rustielin): get the helm releases to be consistent
     upgrade_aptos_node_helm(
         aptos_node_upgrade_options.as_slice(),
@@ -590,6 +634,41 @@ pub async fn install_testnet_resources(
     Ok((validators, fullnodes))
 }
 
+/// Copies the genesis secrets of the given validators from the cluster that ran genesis to the
+/// same namespace in another cluster
+async fn copy_genesis_secrets(
+    from_kube_client: &K8sClient,
+    to_kube_client: &K8sClient,
+    kube_namespace: &str,
+    era: &str,
+    validator_indices: &[usize],
+) -> Result<()> {
+    let from_secrets: Api<Secret> = Api::namespaced(from_kube_client.clone(), kube_namespace);
+    let to_secrets: Api<Secret> = Api::namespaced(to_kube_client.clone(), kube_namespace);
+    for i in validator_indices {
+        let secret_name = format!("{}-{}-genesis-e{}", APTOS_NODE_HELM_RELEASE_NAME, i, era);
+        let secret = from_secrets.get(&secret_name).await?;
+        let secret = Secret {
+            data: secret.data,
+            type_: secret.type_,
+            metadata: ObjectMeta {
+                name: Some(secret_name.clone()),
+                ..ObjectMeta::default()
+            },
+            ..Secret::default()
+        };
+        match to_secrets.create(&PostParams::default(), &secret).await {
+            Ok(_) => info!("Copied genesis secret {}", &secret_name),
+            // the region runs in the cluster that ran genesis
+            Err(KubeError::Api(api_err)) if api_err.code == 409 => {
+                info!("Genesis secret {} already exists", &secret_name)
+            }
+            Err(e) => bail!(e),
+        }
+    }
+    Ok(())
+}
+
 pub fn construct_node_helm_values(
     node_helm_config_fn: Option<NodeConfigFn>,
     base_helm_values: String,
@@ -650,6 +729,46 @@ pub fn construct_genesis_helm_values(
     serde_yaml::to_string(&value).map_err(|e| anyhow::anyhow!("{:?}", e))
 }
 
+/// Collect the running nodes of a network spread across the clusters of the given regions into
+/// K8sNodes. As the test runner is outside of most of these clusters, the nodes are reached through
+/// the external DNS names of their HAProxy load balancers
+pub async fn collect_running_multi_cluster_nodes(
+    multi_region: &MultiRegionConfig,
+    kube_namespace: String,
+) -> Result<(HashMap<PeerId, K8sNode>, HashMap<PeerId, K8sNode>)> {
+    let domain = multi_region.domain()?;
+    let mut validators = HashMap::new();
+    let mut fullnodes = HashMap::new();
+    for region in multi_region.regions() {
+        let kube_context = region.kube_context.as_deref();
+        let kube_client = create_k8s_client_for_context(kube_context).await;
+
+        let mut region_validators =
+            get_validators(kube_client.clone(), &kube_namespace, false, true).await?;
+        wait_nodes_stateful_set(&kube_client, &kube_namespace, &region_validators).await?;
+        wait_node_haproxy(&kube_client, &kube_namespace, &region_validators).await?;
+        let mut region_fullnodes =
+            get_fullnodes(kube_client.clone(), &kube_namespace, false, true).await?;
+        wait_nodes_stateful_set(&kube_client, &kube_namespace, &region_fullnodes).await?;
+
+        for node in region_validators
+            .values_mut()
+            .chain(region_fullnodes.values_mut())
+        {
+            node.set_cluster(kube_context, domain);
+        }
+        validators.extend(region_validators);
+        fullnodes.extend(region_fullnodes);
+    }
+
+    let nodes = validators
+        .values()
+        .chain(fullnodes.values())
+        .collect::<Vec<&K8sNode>>();
+    nodes_healthcheck(nodes).await?;
+    Ok((validators, fullnodes))
+}
+
 /// Collect the running nodes in the network into K8sNodes
 pub async fn collect_running_nodes(
     kube_client: &K8sClient,
@@ -671,7 +790,7 @@ pub async fn collect_running_nodes(
     wait_nodes_stateful_set(kube_client, &kube_namespace, &validators).await?;
 
     if enable_haproxy {
-        wait_node_haproxy(kube_client, &kube_namespace, validators.len()).await?;
+        wait_node_haproxy(kube_client, &kube_namespace, &validators).await?;
     }
 
     // get all fullnodes
@@ -704,21 +823,54 @@ pub async fn collect_running_nodes(
 }
 
 pub async fn create_k8s_client() -> K8sClient {
-    // get the client from the local kube context
-    let config_infer = Config::infer().await.unwrap();
-    K8sClient::try_from(config_infer).unwrap()
+    create_k8s_client_for_context(None).await
+}
+
+/// Creates a client for the cluster of the given kube context, or of the local kube context
+pub async fn create_k8s_client_for_context(kube_context: Option<&str>) -> K8sClient {
+    let config = match kube_context {
+        Some(kube_context) => Config::from_kubeconfig(&KubeConfigOptions {
+            context: Some(kube_context.to_string()),
+            ..KubeConfigOptions::default()
+        })
+        .await
+        .unwrap(),
+        // get the client from the local kube context
+        None => Config::infer().await.unwrap(),
+    };
+    K8sClient::try_from(config).unwrap()
+}
+
+/// The helm flags selecting the given kube context, if any
+fn helm_context_args(kube_context: Option<&str>) -> Vec<String> {
+    match kube_context {
+        Some(kube_context) => vec!["--kube-context".to_string(), kube_context.to_string()],
+        None => vec![],
+    }
+}
+
+/// The kubectl flags selecting the given kube context, if any
+pub(crate) fn kubectl_context_args(kube_context: Option<&str>) -> Vec<String> {
+    match kube_context {
+        Some(kube_context) => vec!["--context".to_string(), kube_context.to_string()],
+        None => vec![],
+    }
 }
 
 /// Gets the result of helm status command as JSON
-fn get_helm_status(helm_release_name: &str) -> Result<Value> {
-    let status_args = [
-        "status",
-        helm_release_name,
-        "--namespace",
-        "default",
-        "-o",
-        "json",
-    ];
+fn get_helm_status(helm_release_name: &str, kube_context: Option<&str>) -> Result<Value> {
+    let mut status_args = helm_context_args(kube_context);
+    status_args.extend(
+        [
+            "status",
+            helm_release_name,
+            "--namespace",
+            "default",
+            "-o",
+            "json",
+        ]
+        .map(String::from),
+    );
     info!("{:?}", status_args);
     let raw_helm_values = Command::new(HELM_BIN)
         .args(&status_args)
@@ -750,12 +902,24 @@ pub fn dump_string_to_file(
     Ok(file_path_str)
 }
 
-fn dump_helm_values_to_file(helm_release_name: &str, tmp_dir: &TempDir) -> Result<String> {
+fn dump_helm_values_to_file(
+    helm_release_name: &str,
+    kube_context: Option<&str>,
+    tmp_dir: &TempDir,
+) -> Result<String> {
     // get aptos-node values
-    let v: Value = get_helm_status(helm_release_name).unwrap();
+    let v: Value = get_helm_status(helm_release_name, kube_context).unwrap();
     let config = &v["config"];
     let content = config.to_string();
-    let file_name = format!("{}_status.json", helm_release_name);
+    let file_name = match kube_context {
+        // kube contexts may contain characters that aren't valid in file names
+        Some(kube_context) => format!(
+            "{}_{}_status.json",
+            helm_release_name,
+            kube_context.replace(|c: char| !c.is_ascii_alphanumeric(), "-")
+        ),
+        None => format!("{}_status.json", helm_release_name),
+    };
 
     dump_string_to_file(file_name, content, tmp_dir)
 }
@@ -809,8 +973,9 @@ pub async fn create_management_configmap(
     kube_namespace: String,
     keep: bool,
     cleanup_duration: Duration,
+    kube_context: Option<&str>,
 ) -> Result<()> {
-    let kube_client = create_k8s_client().await;
+    let kube_client = create_k8s_client_for_context(kube_context).await;
     let namespaces_api = Arc::new(K8sApi::<Namespace>::from_client(kube_client.clone(), None));
     let other_kube_namespace = kube_namespace.clone();
 
@@ -942,7 +1107,7 @@ pub async fn cleanup_cluster_with_management() -> Result<()> {
         .collect::<Vec<ConfigMap>>();
     for configmap in configmaps {
         let namespace = configmap.namespace().unwrap();
-        uninstall_testnet_resources(namespace).await?;
+        uninstall_testnet_resources(namespace, None).await?;
     }
 
     Ok(())
@@ -1,16 +1,17 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Factory, GenesisConfig, GenesisConfigFn, NodeConfigFn, Result, Swarm, Version};
+use crate::{Factory, GenesisConfig, GenesisConfigFn, Node, NodeConfigFn, Result, Swarm, Version};
 use anyhow::bail;
 use aptos_logger::info;
 use rand::rngs::StdRng;
-use std::{convert::TryInto, num::NonZeroUsize, time::Duration};
+use std::{convert::TryInto, num::NonZeroUsize, sync::Arc, time::Duration};
 
 pub mod chaos;
 mod cluster_helper;
 pub mod constants;
 pub mod kube_api;
+mod multi_region;
 pub mod node;
 pub mod prometheus;
 mod stateful_set;
@@ -19,6 +20,7 @@ mod swarm;
 pub use cluster_helper::*;
 pub use constants::*;
 pub use kube_api::*;
+pub use multi_region::*;
 pub use node::K8sNode;
 pub use stateful_set::*;
 pub use swarm::*;
@@ -34,6 +36,7 @@ pub struct K8sFactory {
     reuse: bool,
     keep: bool,
     enable_haproxy: bool,
+    multi_region: Option<MultiRegionConfig>,
 }
 
 impl K8sFactory {
@@ -73,8 +76,17 @@ impl K8sFactory {
             reuse,
             keep,
             enable_haproxy,
+            multi_region: None,
         })
     }
+
+    /// Spreads the validators across the regions of the given config. If the regions run in
+    /// their own clusters, the validators are deployed there, otherwise the configured
+    /// inter-region latencies are emulated once the swarm is up
+    pub fn with_multi_region(mut self, multi_region: MultiRegionConfig) -> Self {
+        self.multi_region = Some(multi_region);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -110,27 +122,104 @@ impl Factory for K8sFactory {
             None => None,
         };
 
+        let multi_cluster = self.multi_region.as_ref().filter(|m| m.is_multi_cluster());
+        if let Some(multi_region) = multi_cluster {
+            if !self.enable_haproxy {
+                bail!("Validators in different clusters are reached through haproxy, enable it");
+            }
+            multi_region.domain()?;
+            if existing_db_tag.is_some() {
+                bail!("Reusing an existing db is not supported across clusters");
+            }
+        }
+        // the clusters to clean up, besides the current one
+        let region_kube_contexts: Vec<&str> = multi_cluster
+            .map(|m| {
+                m.regions()
+                    .iter()
+                    .filter_map(|r| r.kube_context.as_deref())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // let the validators of each region discover each other by their external DNS names
+        let genesis_config_fn = match multi_cluster {
+            Some(multi_region) => {
+                let multi_region = multi_region.clone();
+                let genesis_config_fn: GenesisConfigFn = Arc::new(move |helm_values| {
+                    multi_region.apply_genesis_helm_values(helm_values);
+                    if let Some(config_fn) = &genesis_config_fn {
+                        (config_fn)(helm_values);
+                    }
+                });
+                Some(genesis_config_fn)
+            }
+            None => genesis_config_fn,
+        };
+
+        // attach the region of each validator to its helm values, on top of the test's own overrides
+        let node_config_fn = match &self.multi_region {
+            Some(multi_region) => {
+                let multi_region = multi_region.clone();
+                let num_validators = num_validators.get();
+                let node_config_fn: NodeConfigFn = Arc::new(move |helm_values| {
+                    multi_region.apply_node_helm_values(helm_values, num_validators);
+                    if let Some(config_fn) = &node_config_fn {
+                        (config_fn)(helm_values);
+                    }
+                });
+                Some(node_config_fn)
+            }
+            None => node_config_fn,
+        };
+
         let kube_client = create_k8s_client().await;
         let (validators, fullnodes) = if self.reuse {
-            match collect_running_nodes(
-                &kube_client,
-                self.kube_namespace.clone(),
-                self.use_port_forward,
-                self.enable_haproxy,
-            )
-            .await
-            {
+            let running_nodes = match multi_cluster {
+                Some(multi_region) => {
+                    collect_running_multi_cluster_nodes(multi_region, self.kube_namespace.clone())
+                        .await
+                }
+                None => {
+                    collect_running_nodes(
+                        &kube_client,
+                        self.kube_namespace.clone(),
+                        self.use_port_forward,
+                        self.enable_haproxy,
+                    )
+                    .await
+                }
+            };
+            match running_nodes {
                 Ok(res) => res,
                 Err(e) => {
                     bail!(e);
                 }
             }
         } else {
-            // clear the cluster of resources
-            delete_k8s_resources(kube_client.clone(), &self.kube_namespace).await?;
+            // clear the clusters of resources
+            delete_k8s_resources(kube_client.clone(), &self.kube_namespace, None).await?;
+            for kube_context in &region_kube_contexts {
+                let client = create_k8s_client_for_context(Some(kube_context)).await;
+                delete_k8s_resources(client, &self.kube_namespace, Some(kube_context)).await?;
+            }
             // create the forge-management configmap before installing anything
-            create_management_configmap(self.kube_namespace.clone(), self.keep, cleanup_duration)
+            create_management_configmap(
+                self.kube_namespace.clone(),
+                self.keep,
+                cleanup_duration,
+                None,
+            )
+            .await?;
+            for kube_context in &region_kube_contexts {
+                create_management_configmap(
+                    self.kube_namespace.clone(),
+                    self.keep,
+                    cleanup_duration,
+                    Some(kube_context),
+                )
                 .await?;
+            }
             if let Some(existing_db_tag) = existing_db_tag {
                 // TODO(prod-eng): For now we are managing PVs out of forge, and bind them manually
                 // with the volume. Going forward we should consider automate this process.
@@ -159,18 +248,26 @@ impl Factory for K8sFactory {
                 self.enable_haproxy,
                 genesis_config_fn,
                 node_config_fn,
+                multi_cluster,
             )
             .await
             {
                 Ok(res) => res,
                 Err(e) => {
-                    uninstall_testnet_resources(self.kube_namespace.clone()).await?;
+                    uninstall_testnet_resources(self.kube_namespace.clone(), None).await?;
+                    for kube_context in &region_kube_contexts {
+                        uninstall_testnet_resources(
+                            self.kube_namespace.clone(),
+                            Some(kube_context),
+                        )
+                        .await?;
+                    }
                     bail!(e);
                 }
             }
         };
 
-        let swarm = K8sSwarm::new(
+        let mut swarm = K8sSwarm::new(
             &self.root_key,
             &self.image_tag,
             &self.upgrade_image_tag,
@@ -181,6 +278,17 @@ impl Factory for K8sFactory {
         )
         .await
         .unwrap();
+
+        // validators in different clusters already see the real latency between them
+        if let Some(multi_region) = self.multi_region.as_ref().filter(|m| !m.is_multi_cluster()) {
+            let validators: Vec<_> = swarm.validators().map(|v| v.peer_id()).collect();
+            let chaos = multi_region.to_swarm_chaos(&validators)?;
+            info!(
+                "Emulating multi-region topology: {:?}",
+                multi_region.regions()
+            );
+            swarm.inject_chaos(chaos)?;
+        }
         Ok(Box::new(swarm))
     }
 }
@@ -0,0 +1,472 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GroupNetworkDelay, Result, SwarmChaos, SwarmNetworkDelay};
+use anyhow::{bail, format_err};
+use aptos_sdk::types::PeerId;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+pub const DEFAULT_LATENCY_CORRELATION_PERCENTAGE: u64 = 50;
+
+/// A single region of a multi-region forge deployment
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct K8sRegion {
+    pub name: String,
+    /// Relative share of the validators placed in this region
+    pub weight: usize,
+    /// Kube context of the cluster running the validators of this region. If unset, the
+    /// validators run in the cluster of the test runner
+    pub kube_context: Option<String>,
+}
+
+impl K8sRegion {
+    pub fn new(name: &str, weight: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            weight,
+            kube_context: None,
+        }
+    }
+
+    pub fn with_kube_context(mut self, kube_context: &str) -> Self {
+        self.kube_context = Some(kube_context.to_string());
+        self
+    }
+}
+
+impl FromStr for K8sRegion {
+    type Err = anyhow::Error;
+
+    /// Parses a region from `name[:weight][@kube_context]`, where weight defaults to 1
+    fn from_str(s: &str) -> Result<Self> {
+        let (s, kube_context) = match s.split_once('@') {
+            Some((s, kube_context)) => (s, Some(kube_context)),
+            None => (s, None),
+        };
+        let (name, weight) = match s.split_once(':') {
+            Some((name, weight)) => (name, weight.parse()?),
+            None => (s, 1),
+        };
+        if name.is_empty() {
+            bail!("Region name must not be empty");
+        }
+        let region = Self::new(name, weight);
+        match kube_context {
+            Some("") => bail!("Kube context of region {} must not be empty", name),
+            Some(kube_context) => Ok(region.with_kube_context(kube_context)),
+            None => Ok(region),
+        }
+    }
+}
+
+/// Describes how validators are spread across regions and the latency expected between them.
+/// Validators are assigned to regions in index order, proportionally to each region's weight.
+///
+/// Either every region runs in its own cluster, so that the latency between the regions is real,
+/// or all of them run in the cluster of the test runner, which emulates the expected latency.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MultiRegionConfig {
+    regions: Vec<K8sRegion>,
+    /// Expected one-way latency between two regions, keyed by (from, to) region name
+    inter_region_latency_ms: BTreeMap<(String, String), u64>,
+    jitter_ms: u64,
+    /// How much the emulated delay of a packet depends on the delay of the previous one
+    correlation_percentage: u64,
+    /// Base domain of the external DNS names through which validators in different clusters
+    /// reach each other
+    domain: Option<String>,
+}
+
+impl MultiRegionConfig {
+    pub fn new(regions: Vec<K8sRegion>) -> Result<Self> {
+        if regions.is_empty() {
+            bail!("At least one region must be specified");
+        }
+        if regions.iter().any(|r| r.weight == 0) {
+            bail!("Region weights must be positive");
+        }
+        let names: BTreeSet<_> = regions.iter().map(|r| &r.name).collect();
+        if names.len() != regions.len() {
+            bail!("Region names must be unique");
+        }
+        let kube_contexts: BTreeSet<_> = regions.iter().flat_map(|r| &r.kube_context).collect();
+        if !kube_contexts.is_empty() && kube_contexts.len() != regions.len() {
+            bail!("Either every region or none of them must run in its own cluster");
+        }
+        Ok(Self {
+            regions,
+            inter_region_latency_ms: BTreeMap::new(),
+            jitter_ms: 0,
+            correlation_percentage: DEFAULT_LATENCY_CORRELATION_PERCENTAGE,
+            domain: None,
+        })
+    }
+
+    /// Sets the expected latency between two regions. Latency is symmetric
+    pub fn with_latency(mut self, from: &str, to: &str, latency_ms: u64) -> Result<Self> {
+        for name in [from, to] {
+            if !self.regions.iter().any(|r| r.name == name) {
+                bail!("Unknown region: {}", name);
+            }
+        }
+        self.inter_region_latency_ms
+            .insert((from.to_string(), to.to_string()), latency_ms);
+        self.inter_region_latency_ms
+            .insert((to.to_string(), from.to_string()), latency_ms);
+        Ok(self)
+    }
+
+    pub fn with_jitter(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    pub fn with_correlation_percentage(mut self, correlation_percentage: u64) -> Result<Self> {
+        if correlation_percentage > 100 {
+            bail!(
+                "Latency correlation must be a percentage, got {}",
+                correlation_percentage
+            );
+        }
+        self.correlation_percentage = correlation_percentage;
+        Ok(self)
+    }
+
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    pub fn regions(&self) -> &[K8sRegion] {
+        &self.regions
+    }
+
+    /// Whether every region runs in its own cluster
+    pub fn is_multi_cluster(&self) -> bool {
+        self.regions.iter().any(|r| r.kube_context.is_some())
+    }
+
+    /// The base domain of the external DNS names of the validators, which is required when
+    /// they run in different clusters
+    pub fn domain(&self) -> Result<&str> {
+        self.domain
+            .as_deref()
+            .ok_or_else(|| format_err!("Validators in different clusters require a domain"))
+    }
+
+    /// The expected latency between two regions. Nodes in the same region have no added latency
+    pub fn latency_ms(&self, from: &str, to: &str) -> u64 {
+        if from == to {
+            return 0;
+        }
+        self.inter_region_latency_ms
+            .get(&(from.to_string(), to.to_string()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the region of each validator index, for a network of the given size
+    pub fn assign_validators(&self, num_validators: usize) -> Vec<&K8sRegion> {
+        let total_weight: usize = self.regions.iter().map(|r| r.weight).sum();
+        let mut assignment = Vec::with_capacity(num_validators);
+        let mut assigned_weight = 0;
+        for region in &self.regions {
+            assigned_weight += region.weight;
+            // the last region picks up any remainder from rounding
+            let upper = if assigned_weight == total_weight {
+                num_validators
+            } else {
+                num_validators * assigned_weight / total_weight
+            };
+            while assignment.len() < upper {
+                assignment.push(region);
+            }
+        }
+        assignment
+    }
+
+    /// Returns the indices of the validators assigned to the given region
+    pub fn validator_indices(&self, region: &K8sRegion, num_validators: usize) -> Vec<usize> {
+        self.assign_validators(num_validators)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, r)| *r == region)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Adds the region of every validator to the aptos-node helm values, so that the
+    /// region label is attached to each node and to its metrics. Validators in different
+    /// clusters are exposed through load balancers with external DNS names
+    pub fn apply_node_helm_values(&self, value: &mut serde_yaml::Value, num_validators: usize) {
+        let regions: Vec<serde_yaml::Value> = self
+            .assign_validators(num_validators)
+            .into_iter()
+            .map(|r| r.name.clone().into())
+            .collect();
+        value["validator"]["regions"] = regions.into();
+        value["labels"]["forge-multi-region"] = "true".into();
+        if let Some(domain) = &self.domain {
+            if self.is_multi_cluster() {
+                value["service"]["domain"] = domain.clone().into();
+                value["service"]["validator"]["external"]["type"] = "LoadBalancer".into();
+                value["service"]["fullnode"]["external"]["type"] = "LoadBalancer".into();
+            }
+        }
+    }
+
+    /// Makes genesis address the validators by their external DNS names, when they run in
+    /// different clusters
+    pub fn apply_genesis_helm_values(&self, value: &mut serde_yaml::Value) {
+        if let Some(domain) = &self.domain {
+            if self.is_multi_cluster() {
+                value["genesis"]["domain"] = domain.clone().into();
+                value["genesis"]["validator"]["enable_onchain_discovery"] = true.into();
+                value["genesis"]["fullnode"]["enable_onchain_discovery"] = true.into();
+            }
+        }
+    }
+
+    /// Groups the given validators (ordered by index) by region
+    pub fn group_validators(&self, validators: &[PeerId]) -> BTreeMap<String, Vec<PeerId>> {
+        let mut groups: BTreeMap<String, Vec<PeerId>> = BTreeMap::new();
+        for (region, peer_id) in self
+            .assign_validators(validators.len())
+            .into_iter()
+            .zip(validators)
+        {
+            groups
+                .entry(region.name.clone())
+                .or_default()
+                .push(*peer_id);
+        }
+        groups
+    }
+
+    /// Builds the delay chaos that emulates the configured inter-region latencies, for regions
+    /// running in a single cluster
+    pub fn to_swarm_chaos(&self, validators: &[PeerId]) -> Result<SwarmChaos> {
+        if self.is_multi_cluster() {
+            bail!("The latency between regions in different clusters can't be emulated");
+        }
+        let groups = self.group_validators(validators);
+        let mut group_network_delays = vec![];
+        for (from, source_nodes) in &groups {
+            for (to, target_nodes) in &groups {
+                let latency_ms = self.latency_ms(from, to);
+                if latency_ms == 0 {
+                    continue;
+                }
+                group_network_delays.push(GroupNetworkDelay {
+                    name: format!("{}-to-{}-delay", from, to),
+                    source_nodes: source_nodes.clone(),
+                    target_nodes: target_nodes.clone(),
+                    latency_ms,
+                    jitter_ms: self.jitter_ms,
+                    correlation_percentage: self.correlation_percentage,
+                });
+            }
+        }
+        if group_network_delays.is_empty() {
+            return Err(format_err!("No inter-region latency configured"));
+        }
+        Ok(SwarmChaos::Delay(SwarmNetworkDelay {
+            group_network_delays,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_regions() -> MultiRegionConfig {
+        MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 2),
+            K8sRegion::new("eu-west", 1),
+            K8sRegion::new("ap-northeast", 1),
+        ])
+        .unwrap()
+        .with_latency("us-west", "eu-west", 70)
+        .unwrap()
+        .with_latency("us-west", "ap-northeast", 50)
+        .unwrap()
+        .with_latency("eu-west", "ap-northeast", 110)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_assign_validators() {
+        let config = three_regions();
+        let names: Vec<_> = config
+            .assign_validators(7)
+            .into_iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "us-west",
+                "us-west",
+                "us-west",
+                "eu-west",
+                "eu-west",
+                "ap-northeast",
+                "ap-northeast"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_region() {
+        assert_eq!(
+            "us-west:3".parse::<K8sRegion>().unwrap(),
+            K8sRegion::new("us-west", 3)
+        );
+        assert_eq!(
+            "eu-west".parse::<K8sRegion>().unwrap(),
+            K8sRegion::new("eu-west", 1)
+        );
+        assert!(":3".parse::<K8sRegion>().is_err());
+        assert_eq!(
+            "us-west:3@us-west-cluster".parse::<K8sRegion>().unwrap(),
+            K8sRegion::new("us-west", 3).with_kube_context("us-west-cluster")
+        );
+        assert_eq!(
+            "eu-west@eu-west-cluster".parse::<K8sRegion>().unwrap(),
+            K8sRegion::new("eu-west", 1).with_kube_context("eu-west-cluster")
+        );
+        assert!("eu-west@".parse::<K8sRegion>().is_err());
+    }
+
+    #[test]
+    fn test_kube_contexts() {
+        let config = MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 1).with_kube_context("us-west-cluster"),
+            K8sRegion::new("eu-west", 1).with_kube_context("eu-west-cluster"),
+        ])
+        .unwrap();
+        assert!(config.is_multi_cluster());
+        assert!(config.domain().is_err());
+        assert!(!three_regions().is_multi_cluster());
+
+        // mixing clusters with the current one, or sharing a cluster, is rejected
+        assert!(MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 1).with_kube_context("us-west-cluster"),
+            K8sRegion::new("eu-west", 1),
+        ])
+        .is_err());
+        assert!(MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 1).with_kube_context("cluster"),
+            K8sRegion::new("eu-west", 1).with_kube_context("cluster"),
+        ])
+        .is_err());
+        assert!(MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 1),
+            K8sRegion::new("us-west", 1),
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_validator_indices() {
+        let config = three_regions();
+        let regions = config.regions();
+        assert_eq!(config.validator_indices(&regions[0], 7), vec![0, 1, 2]);
+        assert_eq!(config.validator_indices(&regions[1], 7), vec![3, 4]);
+        assert_eq!(config.validator_indices(&regions[2], 7), vec![5, 6]);
+    }
+
+    #[test]
+    fn test_multi_cluster_helm_values() {
+        let config = MultiRegionConfig::new(vec![
+            K8sRegion::new("us-west", 1).with_kube_context("us-west-cluster"),
+            K8sRegion::new("eu-west", 1).with_kube_context("eu-west-cluster"),
+        ])
+        .unwrap()
+        .with_domain("forge.example.com");
+
+        let mut node_values = serde_yaml::Value::Null;
+        config.apply_node_helm_values(&mut node_values, 2);
+        assert_eq!(node_values["validator"]["regions"][1], "eu-west");
+        assert_eq!(node_values["service"]["domain"], "forge.example.com");
+        assert_eq!(
+            node_values["service"]["validator"]["external"]["type"],
+            "LoadBalancer"
+        );
+
+        let mut genesis_values = serde_yaml::Value::Null;
+        config.apply_genesis_helm_values(&mut genesis_values);
+        assert_eq!(genesis_values["genesis"]["domain"], "forge.example.com");
+        assert_eq!(
+            genesis_values["genesis"]["validator"]["enable_onchain_discovery"],
+            true
+        );
+
+        // the latency between clusters is real, so it is not emulated
+        let validators: Vec<_> = (0..2).map(|_| PeerId::random()).collect();
+        assert!(config.to_swarm_chaos(&validators).is_err());
+
+        // a single cluster keeps its helm values untouched
+        let mut node_values = serde_yaml::Value::Null;
+        three_regions()
+            .with_domain("forge.example.com")
+            .apply_node_helm_values(&mut node_values, 4);
+        assert!(node_values["service"].is_null());
+    }
+
+    #[test]
+    fn test_latency_is_symmetric() {
+        let config = three_regions();
+        assert_eq!(config.latency_ms("eu-west", "us-west"), 70);
+        assert_eq!(config.latency_ms("us-west", "us-west"), 0);
+        assert!(config.clone().with_latency("us-west", "mars", 1).is_err());
+    }
+
+    #[test]
+    fn test_to_swarm_chaos() {
+        let config = three_regions();
+        let validators: Vec<_> = (0..4).map(|_| PeerId::random()).collect();
+        match config.to_swarm_chaos(&validators).unwrap() {
+            SwarmChaos::Delay(delay) => {
+                // every ordered pair of distinct regions gets its own delay
+                assert_eq!(delay.group_network_delays.len(), 6);
+                let us_to_eu = delay
+                    .group_network_delays
+                    .iter()
+                    .find(|d| d.name == "us-west-to-eu-west-delay")
+                    .unwrap();
+                assert_eq!(us_to_eu.source_nodes, validators[0..2].to_vec());
+                assert_eq!(us_to_eu.target_nodes, vec![validators[2]]);
+                assert_eq!(us_to_eu.latency_ms, 70);
+            }
+            _ => panic!("Expected delay chaos"),
+        }
+    }
+
+    #[test]
+    fn test_correlation_percentage() {
+        let validators: Vec<_> = (0..4).map(|_| PeerId::random()).collect();
+        let correlations =
+            |config: MultiRegionConfig| match config.to_swarm_chaos(&validators).unwrap() {
+                SwarmChaos::Delay(delay) => delay
+                    .group_network_delays
+                    .iter()
+                    .map(|d| d.correlation_percentage)
+                    .collect::<BTreeSet<_>>(),
+                _ => panic!("Expected delay chaos"),
+            };
+        assert_eq!(
+            correlations(three_regions()),
+            BTreeSet::from([DEFAULT_LATENCY_CORRELATION_PERCENTAGE])
+        );
+        assert_eq!(
+            correlations(three_regions().with_correlation_percentage(0).unwrap()),
+            BTreeSet::from([0])
+        );
+        assert!(three_regions().with_correlation_percentage(101).is_err());
+    }
+}
@@ -3,9 +3,9 @@
 
 use crate::backend::k8s::stateful_set;
 use crate::{
-    get_free_port, scale_stateful_set_replicas, FullNode, HealthCheckError, Node, NodeExt, Result,
-    Validator, Version, KUBECTL_BIN, LOCALHOST, NODE_METRIC_PORT, REST_API_HAPROXY_SERVICE_PORT,
-    REST_API_SERVICE_PORT,
+    get_free_port, kubectl_context_args, scale_stateful_set_replicas, FullNode, HealthCheckError,
+    Node, NodeExt, Result, Validator, Version, KUBECTL_BIN, LOCALHOST, NODE_METRIC_PORT,
+    REST_API_HAPROXY_SERVICE_PORT, REST_API_SERVICE_PORT,
 };
 use anyhow::{anyhow, format_err};
 use aptos_config::config::NodeConfig;
@@ -40,6 +40,8 @@ pub struct K8sNode {
     pub haproxy_enabled: bool,
     // whether we should try using port-forward on the Service to reach this node
     pub port_forward_enabled: bool,
+    // the kube context of the cluster of this node, if not the cluster of the test runner
+    pub kube_context: Option<String>,
 }
 
 impl K8sNode {
@@ -63,18 +65,38 @@ impl K8sNode {
         &self.namespace
     }
 
+    pub fn kube_context(&self) -> Option<&str> {
+        self.kube_context.as_deref()
+    }
+
+    /// Moves the node to the cluster of the given kube context, where it is reached through the
+    /// external DNS name of its HAProxy load balancer under the given domain
+    pub(crate) fn set_cluster(&mut self, kube_context: Option<&str>, domain: &str) {
+        // matches the hostnames in `terraform/helm/aptos-node/templates/haproxy.yaml`
+        let host_prefix = if self.name.starts_with("validator") {
+            "val"
+        } else {
+            "fullnode"
+        };
+        self.kube_context = kube_context.map(str::to_string);
+        self.service_name = format!("{}{}.{}", host_prefix, self.index, domain);
+        self.rest_api_port = REST_API_HAPROXY_SERVICE_PORT;
+        self.port_forward_enabled = false;
+    }
+
     /// Start a port-forward to the node's Service
     fn port_forward(&self, port: u32, remote_port: u32) -> Result<()> {
-        let port_forward_args = [
-            "port-forward",
-            "-n",
-            self.namespace(),
-            &format!("svc/{}", self.service_name()),
-            &format!("{}:{}", port, remote_port),
-        ];
+        let mut port_forward_args = kubectl_context_args(self.kube_context());
+        port_forward_args.extend([
+            "port-forward".to_string(),
+            "-n".to_string(),
+            self.namespace().to_string(),
+            format!("svc/{}", self.service_name()),
+            format!("{}:{}", port, remote_port),
+        ]);
         // spawn a port-forward child process
         let cmd = Command::new(KUBECTL_BIN)
-            .args(port_forward_args)
+            .args(&port_forward_args)
             .stdout(Stdio::null())
             // .stderr(Stdio::null())
             .spawn();
@@ -118,6 +140,46 @@ impl K8sNode {
         };
         self.port_forward(self.rest_api_port(), remote_rest_api_port)
     }
+
+    /// Paths of the ledger, state and state sync dbs of the node in its pod
+    pub(crate) fn db_paths() -> Vec<String> {
+        vec![
+            format!("{}/db/{}", APTOS_DATA_DIR, LEDGER_DB_NAME),
+            format!("{}/db/{}", APTOS_DATA_DIR, STATE_MERKLE_DB_NAME),
+            format!("{}/db/{}", APTOS_DATA_DIR, STATE_SYNC_DB_NAME),
+        ]
+    }
+
+    /// Path of the secure storage of the node in its pod
+    pub(crate) fn secure_storage_path() -> String {
+        format!("{}/{}", APTOS_DATA_DIR, SECURE_STORAGE_DB_NAME)
+    }
+
+    /// Deletes the given paths in the pod of the node, which has to be running
+    pub(crate) fn delete_paths(&self, paths: &[String]) {
+        let mut delete_storage_paths = kubectl_context_args(self.kube_context());
+        delete_storage_paths.extend([
+            "-n".to_string(),
+            self.namespace().to_string(),
+            "exec".to_string(),
+            format!("sts/{}", self.stateful_set_name()),
+            "--".to_string(),
+            "rm".to_string(),
+            "-rf".to_string(),
+        ]);
+        delete_storage_paths.extend(paths.iter().cloned());
+        info!("{:?}", delete_storage_paths);
+        let cleanup_output = Command::new(KUBECTL_BIN)
+            .stdout(Stdio::inherit())
+            .args(&delete_storage_paths)
+            .output()
+            .expect("failed to clear node storage");
+        assert!(
+            cleanup_output.status.success(),
+            "{}",
+            String::from_utf8(cleanup_output.stderr).unwrap()
+        );
+    }
 }
 
 #[async_trait::async_trait]
@@ -135,7 +197,13 @@ impl Node for K8sNode {
     }
 
     async fn start(&mut self) -> Result<()> {
-        scale_stateful_set_replicas(self.stateful_set_name(), self.namespace(), 1).await?;
+        scale_stateful_set_replicas(
+            self.stateful_set_name(),
+            self.namespace(),
+            self.kube_context(),
+            1,
+        )
+        .await?;
         // need to port-forward again since the node is coming back
         // note that we will get a new port
         if self.port_forward_enabled {
@@ -148,7 +216,13 @@ impl Node for K8sNode {
 
     async fn stop(&mut self) -> Result<()> {
         info!("going to stop node {}", self.stateful_set_name());
-        scale_stateful_set_replicas(self.stateful_set_name(), self.namespace(), 0).await
+        scale_stateful_set_replicas(
+            self.stateful_set_name(),
+            self.namespace(),
+            self.kube_context(),
+            0,
+        )
+        .await
     }
 
     fn version(&self) -> Version {
@@ -167,35 +241,9 @@ impl Node for K8sNode {
 
     async fn clear_storage(&mut self) -> Result<()> {
         // Remove all storage files
-        let ledger_db_path = format!("{}/db/{}", APTOS_DATA_DIR, LEDGER_DB_NAME);
-        let state_db_path = format!("{}/db/{}", APTOS_DATA_DIR, STATE_MERKLE_DB_NAME);
-        let secure_storage_db_path = format!("{}/{}", APTOS_DATA_DIR, SECURE_STORAGE_DB_NAME);
-        let state_sync_db_path = format!("{}/db/{}", APTOS_DATA_DIR, STATE_SYNC_DB_NAME);
-
-        let delete_storage_paths = [
-            "-n",
-            self.namespace(),
-            "exec",
-            &format!("sts/{}", self.stateful_set_name()),
-            "--",
-            "rm",
-            "-rf",
-            &ledger_db_path,
-            &state_db_path,
-            &secure_storage_db_path,
-            &state_sync_db_path,
-        ];
-        info!("{:?}", delete_storage_paths);
-        let cleanup_output = Command::new(KUBECTL_BIN)
-            .stdout(Stdio::inherit())
-            .args(&delete_storage_paths)
-            .output()
-            .expect("failed to clear node storage");
-        assert!(
-            cleanup_output.status.success(),
-            "{}",
-            String::from_utf8(cleanup_output.stderr).unwrap()
-        );
+        let mut paths = Self::db_paths();
+        paths.push(Self::secure_storage_path());
+        self.delete_paths(&paths);
 
         // Stop the node to clear buffers
         // This step must be done after removing the storage files, since clearing storage involves exec into the (running) node
@@ -260,13 +308,19 @@ impl Node for K8sNode {
     }
 
     async fn get_identity(&mut self) -> Result<String> {
-        stateful_set::get_identity(self.stateful_set_name(), self.namespace()).await
+        stateful_set::get_identity(
+            self.stateful_set_name(),
+            self.namespace(),
+            self.kube_context(),
+        )
+        .await
     }
 
     async fn set_identity(&mut self, k8s_secret_name: String) -> Result<()> {
         stateful_set::set_identity(
             self.stateful_set_name(),
             self.namespace(),
+            self.kube_context(),
             k8s_secret_name.as_str(),
         )
         .await
@@ -18,7 +18,7 @@ use kube::{
 use serde_json::{json, Value};
 use thiserror::Error;
 
-use crate::create_k8s_client;
+use crate::{create_k8s_client_for_context, kubectl_context_args};
 
 #[derive(Error, Debug)]
 #[error("{0}")]
@@ -188,8 +188,9 @@ pub async fn set_stateful_set_image_tag(
     container_name: String,
     image_tag: String,
     kube_namespace: String,
+    kube_context: Option<&str>,
 ) -> Result<()> {
-    let kube_client: K8sClient = create_k8s_client().await;
+    let kube_client: K8sClient = create_k8s_client_for_context(kube_context).await;
     let sts_api: Api<StatefulSet> = Api::namespaced(kube_client.clone(), &kube_namespace);
     let sts = sts_api.get(&stateful_set_name).await?;
     let image_repo = get_stateful_set_image(&sts)?.name;
@@ -199,15 +200,17 @@ pub async fn set_stateful_set_image_tag(
 
     // set the image using kubectl
     // patching the node spec may not work
+    let mut set_image_args = kubectl_context_args(kube_context);
+    set_image_args.extend([
+        "-n".to_string(),
+        kube_namespace,
+        "set".to_string(),
+        "image".to_string(),
+        format!("statefulset/{}", &stateful_set_name),
+        format!("{}={}", &container_name, &new_image),
+    ]);
     Command::new(KUBECTL_BIN)
-        .args([
-            "-n",
-            &kube_namespace,
-            "set",
-            "image",
-            &format!("statefulset/{}", &stateful_set_name),
-            &format!("{}={}", &container_name, &new_image),
-        ])
+        .args(&set_image_args)
         .status()
         .expect("Failed to set image for StatefulSet");
 
@@ -218,9 +221,10 @@ pub async fn set_stateful_set_image_tag(
 pub async fn scale_stateful_set_replicas(
     sts_name: &str,
     kube_namespace: &str,
+    kube_context: Option<&str>,
     replica_num: u64,
 ) -> Result<()> {
-    let kube_client = create_k8s_client().await;
+    let kube_client = create_k8s_client_for_context(kube_context).await;
     let stateful_set_api: Api<StatefulSet> = Api::namespaced(kube_client.clone(), kube_namespace);
     let pp = PatchParams::apply("forge").force();
     let patch = serde_json::json!({
@@ -252,9 +256,10 @@ pub async fn scale_stateful_set_replicas(
 pub async fn set_identity(
     sts_name: &str,
     kube_namespace: &str,
+    kube_context: Option<&str>,
     k8s_secret_name: &str,
 ) -> Result<()> {
-    let kube_client = create_k8s_client().await;
+    let kube_client = create_k8s_client_for_context(kube_context).await;
     let stateful_set_api: Api<StatefulSet> = Api::namespaced(kube_client.clone(), kube_namespace);
     let patch_op = PatchOperation::Replace(ReplaceOperation {
         // The json path below should match `terraform/helm/aptos-node/templates/validator.yaml`.
@@ -267,8 +272,12 @@ pub async fn set_identity(
     Ok(())
 }
 
-pub async fn get_identity(sts_name: &str, kube_namespace: &str) -> Result<String> {
-    let kube_client = create_k8s_client().await;
+pub async fn get_identity(
+    sts_name: &str,
+    kube_namespace: &str,
+    kube_context: Option<&str>,
+) -> Result<String> {
+    let kube_client = create_k8s_client_for_context(kube_context).await;
     let stateful_set_api: Api<StatefulSet> = Api::namespaced(kube_client.clone(), kube_namespace);
     let sts = stateful_set_api.get(sts_name).await?;
     // The json path below should match `terraform/helm/aptos-node/templates/validator.yaml`.
@@ -2,14 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    check_for_container_restart, create_k8s_client, delete_all_chaos, get_free_port,
-    get_stateful_set_image,
+    check_for_container_restart, create_k8s_client, create_k8s_client_for_context,
+    delete_all_chaos, get_free_port, get_stateful_set_image,
     interface::system_metrics::{query_prometheus_system_metrics, SystemMetricsThreshold},
     node::K8sNode,
     prometheus::{self, query_with_metadata},
     query_sequence_number, set_stateful_set_image_tag, uninstall_testnet_resources, ChainInfo,
-    FullNode, Node, Result, Swarm, SwarmChaos, Validator, Version, HAPROXY_SERVICE_SUFFIX,
-    REST_API_HAPROXY_SERVICE_PORT, REST_API_SERVICE_PORT,
+    FullNode, LogEntry, Node, Result, Swarm, SwarmChaos, Validator, Version,
+    HAPROXY_SERVICE_SUFFIX, REST_API_HAPROXY_SERVICE_PORT, REST_API_SERVICE_PORT,
 };
 use ::aptos_logger::*;
 use anyhow::{anyhow, bail, format_err};
@@ -28,10 +28,13 @@ use kube::{
 use prometheus_http_query::{response::PromqlResult, Client as PrometheusClient};
 use regex::Regex;
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     convert::TryFrom,
-    env, str,
+    env,
+    path::Path,
+    str,
     sync::Arc,
+    time::SystemTime,
 };
 use tokio::{runtime::Runtime, time::Duration};
 
@@ -124,6 +127,24 @@ impl K8sSwarm {
     fn get_kube_client(&self) -> K8sClient {
         self.kube_client.clone()
     }
+
+    /// Returns a client for the cluster of the given node
+    async fn get_node_kube_client(&self, node: &K8sNode) -> K8sClient {
+        match node.kube_context() {
+            Some(kube_context) => create_k8s_client_for_context(Some(kube_context)).await,
+            None => self.kube_client.clone(),
+        }
+    }
+
+    /// Returns the kube contexts of the clusters the nodes run in, other than the cluster of the
+    /// test runner
+    fn node_kube_contexts(&self) -> BTreeSet<String> {
+        self.validators
+            .values()
+            .chain(self.fullnodes.values())
+            .filter_map(|node| node.kube_context.clone())
+            .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -189,6 +210,7 @@ impl Swarm for K8sSwarm {
             // extract the image tag from the "version"
             version.to_string(),
             self.kube_namespace.clone(),
+            validator.kube_context(),
         )
         .await?;
 
@@ -198,6 +220,35 @@ impl Swarm for K8sSwarm {
         Ok(())
     }
 
+    async fn restart_validator_with_options(
+        &mut self,
+        id: PeerId,
+        wipe_db: bool,
+        clear_secure_storage: bool,
+    ) -> Result<()> {
+        let validator = self
+            .validators
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("Invalid id: {}", id))?;
+        let mut paths = vec![];
+        if wipe_db {
+            paths.extend(K8sNode::db_paths());
+        }
+        if clear_secure_storage {
+            paths.push(K8sNode::secure_storage_path());
+        }
+        // The files are deleted by exec-ing into the running pod, so before stopping it
+        if !paths.is_empty() {
+            validator.delete_paths(&paths);
+        }
+        validator.stop().await?;
+        validator.start().await
+    }
+
+    async fn snapshot(&mut self, _snapshot_dir: &Path) -> Result<()> {
+        bail!("k8s swarms can't be snapshotted, their storage belongs to the cluster")
+    }
+
     fn full_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn FullNode> + 'a> {
         Box::new(self.fullnodes.values().map(|v| v as &'a dyn FullNode))
     }
@@ -258,6 +309,10 @@ impl Swarm for K8sSwarm {
         "See fgi output for more information.".to_string()
     }
 
+    fn grep_logs(&self, _pattern: &str, _since: SystemTime) -> Result<Vec<(PeerId, LogEntry)>> {
+        bail!("Logs of k8s swarms can only be searched in their logging backend")
+    }
+
     fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
         self.inject_swarm_chaos(&chaos)?;
         self.chaoses.insert(chaos);
@@ -279,18 +334,18 @@ impl Swarm for K8sSwarm {
             self.remove_swarm_chaos(&chaos)?;
         }
         // force remove all others
-        delete_all_chaos(&self.kube_namespace)?;
+        delete_all_chaos(&self.kube_namespace, None)?;
 
         self.chaoses.clear();
         Ok(())
     }
 
     async fn ensure_no_validator_restart(&self) -> Result<()> {
-        for validator in &self.validators {
+        for validator in self.validators.values() {
             check_for_container_restart(
-                &self.kube_client,
+                &self.get_node_kube_client(validator).await,
                 &self.kube_namespace.clone(),
-                validator.1.stateful_set_name(),
+                validator.stateful_set_name(),
             )
             .await?;
         }
@@ -299,11 +354,11 @@ impl Swarm for K8sSwarm {
     }
 
     async fn ensure_no_fullnode_restart(&self) -> Result<()> {
-        for fullnode in &self.fullnodes {
+        for fullnode in self.fullnodes.values() {
             check_for_container_restart(
-                &self.kube_client,
+                &self.get_node_kube_client(fullnode).await,
                 &self.kube_namespace.clone(),
-                fullnode.1.stateful_set_name(),
+                fullnode.stateful_set_name(),
             )
             .await?;
         }
@@ -442,6 +497,7 @@ fn get_k8s_node_from_stateful_set(
         namespace: namespace.to_string(),
         haproxy_enabled: enable_haproxy,
         port_forward_enabled: use_port_forward,
+        kube_context: None,
     }
 }
 
@@ -559,8 +615,19 @@ impl Drop for K8sSwarm {
         let runtime = Runtime::new().unwrap();
         if !self.keep {
             runtime
-                .block_on(uninstall_testnet_resources(self.kube_namespace.clone()))
+                .block_on(uninstall_testnet_resources(
+                    self.kube_namespace.clone(),
+                    None,
+                ))
                 .unwrap();
+            for kube_context in self.node_kube_contexts() {
+                runtime
+                    .block_on(uninstall_testnet_resources(
+                        self.kube_namespace.clone(),
+                        Some(&kube_context),
+                    ))
+                    .unwrap();
+            }
         } else {
             println!("Keeping kube_namespace {}", self.kube_namespace);
         }
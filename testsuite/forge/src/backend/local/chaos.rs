@@ -0,0 +1,366 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    GroupNetworkDelay, SwarmChaos, SwarmNetworkBandwidth, SwarmNetworkLoss, SwarmNetworkPartition,
+};
+use anyhow::{anyhow, Result};
+use aptos_infallible::RwLock;
+use aptos_logger::{debug, info};
+use aptos_sdk::types::PeerId;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpListener, TcpStream,
+    },
+    runtime::Handle,
+    sync::mpsc,
+    task::JoinHandle,
+};
+
+/// Delay of a lost chunk, as it would be retransmitted by TCP
+const LOSS_RETRANSMISSION_DELAY: Duration = Duration::from_millis(200);
+
+/// Size of the chunks read from the connections
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Maximum number of chunks in flight in a direction of a connection
+const MAX_PENDING_CHUNKS: usize = 1024;
+
+/// How often partitions are checked on idle connections
+const PARTITION_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The chaos applied to the traffic from a node to another
+#[derive(Debug, Default, PartialEq)]
+struct LinkChaos {
+    partitioned: bool,
+    latency: Duration,
+    jitter: Duration,
+    /// Percentages of loss and of its correlation
+    loss: Option<(u64, u64)>,
+    /// In mbps
+    bandwidth: Option<u64>,
+}
+
+/// The chaos injected in a local swarm
+#[derive(Debug, Default)]
+struct ChaosState {
+    /// The validators, ordered by index
+    validators: Vec<PeerId>,
+    chaoses: HashSet<SwarmChaos>,
+    /// Seeds the jitter and loss of the links, to reproduce a run
+    seed: Option<u64>,
+}
+
+impl ChaosState {
+    /// Returns the chaos applied to the traffic from `source` to `target`
+    fn link(&self, source: PeerId, target: PeerId) -> LinkChaos {
+        let mut link = LinkChaos::default();
+        for chaos in &self.chaoses {
+            match chaos {
+                SwarmChaos::Delay(delay) => {
+                    for GroupNetworkDelay {
+                        source_nodes,
+                        target_nodes,
+                        latency_ms,
+                        jitter_ms,
+                        ..
+                    } in &delay.group_network_delays
+                    {
+                        if source_nodes.contains(&source) && target_nodes.contains(&target) {
+                            link.latency += Duration::from_millis(*latency_ms);
+                            link.jitter += Duration::from_millis(*jitter_ms);
+                        }
+                    }
+                }
+                SwarmChaos::Partition(SwarmNetworkPartition {
+                    partition_percentage,
+                }) => {
+                    // The first validators are partitioned from the others
+                    let partitioned =
+                        (self.validators.len() * *partition_percentage as usize + 99) / 100;
+                    let partition = &self.validators[..partitioned.min(self.validators.len())];
+                    link.partitioned |= partition.contains(&source) != partition.contains(&target);
+                }
+                SwarmChaos::Bandwidth(SwarmNetworkBandwidth { rate, .. }) => {
+                    link.bandwidth = Some(link.bandwidth.map_or(*rate, |bw| bw.min(*rate)));
+                }
+                SwarmChaos::Loss(SwarmNetworkLoss {
+                    loss_percentage,
+                    correlation_percentage,
+                }) => link.loss = Some((*loss_percentage, *correlation_percentage)),
+            }
+        }
+        link
+    }
+
+    /// Returns the randomness of the jitter and loss of the traffic from `source` to `target`
+    fn link_rng(&self, source: PeerId, target: PeerId) -> StdRng {
+        match self.seed {
+            Some(seed) => {
+                let mut hasher = DefaultHasher::new();
+                (seed, source, target).hash(&mut hasher);
+                StdRng::seed_from_u64(hasher.finish())
+            }
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+/// Proxies the validator network of the nodes of a local swarm, to inject chaos between them
+///
+/// Each validator listens on a new port, while its proxy listens on the port in the validator
+/// set.  The proxy identifies the dialing validator by the peer id starting the noise handshake,
+/// and applies the chaos of the link to the traffic in each direction.  Packet loss is emulated by
+/// delaying chunks as if TCP retransmitted them.
+#[derive(Debug)]
+pub struct ChaosProxies {
+    state: Arc<RwLock<ChaosState>>,
+    proxies: Vec<JoinHandle<()>>,
+}
+
+impl ChaosProxies {
+    pub fn new(validators: Vec<PeerId>, seed: Option<u64>) -> Result<Self> {
+        Handle::try_current()
+            .map_err(|_| anyhow!("Chaos can only be injected from within a tokio runtime"))?;
+        Ok(Self {
+            state: Arc::new(RwLock::new(ChaosState {
+                validators,
+                chaoses: HashSet::new(),
+                seed,
+            })),
+            proxies: vec![],
+        })
+    }
+
+    /// Proxies the connections to `peer_id` on `listen_port` to `node_port`
+    pub fn proxy(&mut self, peer_id: PeerId, listen_port: u16, node_port: u16) -> Result<()> {
+        let listener = std::net::TcpListener::bind(("0.0.0.0", listen_port))?;
+        listener.set_nonblocking(true)?;
+        let handle = Handle::current();
+        let listener = {
+            let _guard = handle.enter();
+            TcpListener::from_std(listener)?
+        };
+        let state = self.state.clone();
+        self.proxies.push(handle.spawn(async move {
+            while let Ok((inbound, _)) = listener.accept().await {
+                tokio::spawn(proxy_connection(state.clone(), peer_id, inbound, node_port));
+            }
+        }));
+        info!(
+            "Proxying the validator network of {} from port {} to port {}",
+            peer_id, listen_port, node_port
+        );
+        Ok(())
+    }
+
+    pub fn insert(&self, chaos: SwarmChaos) {
+        self.state.write().chaoses.insert(chaos);
+    }
+
+    pub fn remove(&self, chaos: &SwarmChaos) -> bool {
+        self.state.write().chaoses.remove(chaos)
+    }
+
+    pub fn clear(&self) {
+        self.state.write().chaoses.clear();
+    }
+}
+
+impl Drop for ChaosProxies {
+    fn drop(&mut self) {
+        for proxy in &self.proxies {
+            proxy.abort();
+        }
+    }
+}
+
+async fn proxy_connection(
+    state: Arc<RwLock<ChaosState>>,
+    target: PeerId,
+    mut inbound: TcpStream,
+    node_port: u16,
+) {
+    // The noise handshake starts with the peer id of the dialer
+    let mut peer_id = [0u8; PeerId::LENGTH];
+    if inbound.read_exact(&mut peer_id).await.is_err() {
+        return;
+    }
+    let source = match PeerId::try_from(&peer_id[..]) {
+        Ok(source) => source,
+        Err(_) => return,
+    };
+    if state.read().link(source, target).partitioned {
+        debug!("Refusing connection from {} to {}", source, target);
+        return;
+    }
+    let mut outbound = match TcpStream::connect(("127.0.0.1", node_port)).await {
+        Ok(outbound) => outbound,
+        Err(_) => return,
+    };
+    if outbound.write_all(&peer_id).await.is_err() {
+        return;
+    }
+
+    let (inbound_read, inbound_write) = inbound.into_split();
+    let (outbound_read, outbound_write) = outbound.into_split();
+    // Either direction closes the whole connection when it ends
+    tokio::select! {
+        _ = forward(state.clone(), source, target, inbound_read, outbound_write) => {}
+        _ = forward(state, target, source, outbound_read, inbound_write) => {}
+    }
+}
+
+/// Forwards the traffic from `source` to `target`, applying the chaos of their link
+async fn forward(
+    state: Arc<RwLock<ChaosState>>,
+    source: PeerId,
+    target: PeerId,
+    mut reader: OwnedReadHalf,
+    mut writer: OwnedWriteHalf,
+) {
+    let (sender, mut receiver) = mpsc::channel::<(Instant, Vec<u8>)>(MAX_PENDING_CHUNKS);
+    let mut rng = state.read().link_rng(source, target);
+
+    // Chunks are delivered in order, once their delay expired
+    let writer_state = state.clone();
+    let write = async move {
+        while let Some((deliver_at, chunk)) = receiver.recv().await {
+            tokio::time::sleep_until(deliver_at.into()).await;
+            if writer_state.read().link(source, target).partitioned
+                || writer.write_all(&chunk).await.is_err()
+            {
+                break;
+            }
+        }
+    };
+
+    let read = async move {
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let mut last_deliver_at = Instant::now();
+        let mut lost = false;
+        loop {
+            let read = match tokio::time::timeout(
+                PARTITION_CHECK_INTERVAL,
+                reader.read(&mut buffer),
+            )
+            .await
+            {
+                Ok(Ok(0)) | Ok(Err(_)) => break,
+                Ok(Ok(read)) => read,
+                Err(_) => {
+                    if state.read().link(source, target).partitioned {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            let link = state.read().link(source, target);
+            if link.partitioned {
+                break;
+            }
+            let mut delay = link.latency;
+            if !link.jitter.is_zero() {
+                delay += link.jitter.mul_f64(rng.gen::<f64>());
+            }
+            if let Some((loss_percentage, correlation_percentage)) = link.loss {
+                if rng.gen_range(0, 100) >= correlation_percentage {
+                    lost = rng.gen_range(0, 100) < loss_percentage;
+                }
+                if lost {
+                    delay += LOSS_RETRANSMISSION_DELAY;
+                }
+            }
+            if let Some(bandwidth) = link.bandwidth {
+                // Chunks are sent one after the other at the rate of the link
+                let transmission =
+                    Duration::from_secs_f64(read as f64 * 8.0 / (bandwidth.max(1) as f64 * 1e6));
+                last_deliver_at = last_deliver_at.max(Instant::now()) + transmission;
+            }
+            // Chunks can't overtake each other, whatever their jitter
+            last_deliver_at = last_deliver_at.max(Instant::now() + delay);
+            if sender
+                .send((last_deliver_at, buffer[..read].to_vec()))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = read => {}
+        _ = write => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SwarmNetworkDelay;
+
+    #[test]
+    fn test_link_chaos() {
+        let validators: Vec<_> = (0..4).map(|_| PeerId::random()).collect();
+        let mut state = ChaosState {
+            validators: validators.clone(),
+            chaoses: HashSet::new(),
+            seed: Some(7),
+        };
+        assert_eq!(
+            state.link(validators[0], validators[1]),
+            LinkChaos::default()
+        );
+
+        state.chaoses.insert(SwarmChaos::Delay(SwarmNetworkDelay {
+            group_network_delays: vec![GroupNetworkDelay {
+                name: "delay".to_string(),
+                source_nodes: vec![validators[0]],
+                target_nodes: vec![validators[1]],
+                latency_ms: 100,
+                jitter_ms: 10,
+                correlation_percentage: 0,
+            }],
+        }));
+        state
+            .chaoses
+            .insert(SwarmChaos::Partition(SwarmNetworkPartition {
+                partition_percentage: 25,
+            }));
+
+        let link = state.link(validators[0], validators[1]);
+        assert_eq!(link.latency, Duration::from_millis(100));
+        assert_eq!(link.jitter, Duration::from_millis(10));
+        assert!(link.partitioned);
+        let link = state.link(validators[1], validators[0]);
+        assert_eq!(link.latency, Duration::ZERO);
+        assert!(link.partitioned);
+        assert!(!state.link(validators[1], validators[2]).partitioned);
+
+        let jitter = |source, target| {
+            state
+                .link_rng(source, target)
+                .gen_range(0, u64::max_value())
+        };
+        assert_eq!(
+            jitter(validators[0], validators[1]),
+            jitter(validators[0], validators[1])
+        );
+        assert_ne!(
+            jitter(validators[0], validators[1]),
+            jitter(validators[1], validators[0])
+        );
+    }
+}
@@ -1,11 +1,12 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Factory, GenesisConfig, GenesisConfigFn, NodeConfigFn, Result, Swarm, Version};
-use anyhow::{bail, Context};
+use crate::{Factory, GenesisConfig, GenesisConfigFn, Node, NodeConfigFn, Result, Swarm, Version};
+use anyhow::{bail, ensure, Context};
 use aptos_config::config::NodeConfig;
 use aptos_framework::ReleaseBundle;
-use aptos_genesis::builder::{InitConfigFn, InitGenesisConfigFn};
+use aptos_gas::GasScheduleOverrides;
+use aptos_genesis::builder::{GenesisConfiguration, InitConfigFn, InitGenesisConfigFn};
 use aptos_infallible::Mutex;
 use rand::rngs::StdRng;
 use std::{
@@ -17,14 +18,20 @@ use std::{
 };
 
 mod cargo;
+mod chaos;
 mod node;
+mod snapshot;
 mod swarm;
 pub use cargo::cargo_build_common_args;
 pub use node::LocalNode;
+pub use snapshot::SwarmSnapshot;
 pub use swarm::{LocalSwarm, SwarmDirectory};
 
 pub use self::swarm::ActiveNodesGuard;
 
+/// Revision standing for the build of the workspace in [`LocalVersion::from_revision`]
+pub const WORKSPACE_REVISION: &str = "workspace";
+
 #[derive(Clone, Debug)]
 pub struct LocalVersion {
     bin: PathBuf,
@@ -43,19 +50,38 @@ impl LocalVersion {
     pub fn version(&self) -> Version {
         self.version.clone()
     }
+
+    /// Returns aptos-node built at the given git revision, which is only built once, or the build
+    /// of the workspace for [`WORKSPACE_REVISION`]
+    pub fn from_revision(version: usize, revision: &str) -> Result<Self> {
+        let (revision, bin) = if revision == WORKSPACE_REVISION {
+            cargo::get_aptos_node_binary_from_worktree()?
+        } else {
+            cargo::get_aptos_node_binary_at_revision(revision)?
+        };
+        Ok(Self::new(bin, Version::new(version, revision)))
+    }
 }
 
 pub struct LocalFactory {
     versions: Arc<HashMap<Version, LocalVersion>>,
+    gas_overrides: Option<GasScheduleOverrides>,
 }
 
 impl LocalFactory {
     pub fn new(versions: HashMap<Version, LocalVersion>) -> Self {
         Self {
             versions: Arc::new(versions),
+            gas_overrides: None,
         }
     }
 
+    /// Overrides gas parameters at genesis of the swarms launched by this factory
+    pub fn with_gas_overrides(mut self, gas_overrides: GasScheduleOverrides) -> Self {
+        self.gas_overrides = Some(gas_overrides);
+        self
+    }
+
     pub fn from_workspace() -> Result<Self> {
         let mut versions = HashMap::new();
         let new_version = cargo::get_aptos_node_binary_from_worktree().map(|(revision, bin)| {
@@ -121,23 +147,65 @@ impl LocalFactory {
         init_config: Option<InitConfigFn>,
         vfn_config: Option<NodeConfig>,
         init_genesis_config: Option<InitGenesisConfigFn>,
+        seed: Option<u64>,
         guard: ActiveNodesGuard,
     ) -> Result<LocalSwarm>
     where
         R: ::rand::RngCore + ::rand::CryptoRng,
     {
+        self.new_swarm_with_versions(
+            rng,
+            number_of_validators,
+            number_of_fullnodes,
+            std::slice::from_ref(version),
+            genesis_framework,
+            init_config,
+            vfn_config,
+            init_genesis_config,
+            seed,
+            guard,
+        )
+        .await
+    }
+
+    /// Launches a swarm whose validators are split evenly between the given versions, in order of
+    /// their indices, e.g., to test the compatibility of two releases. Each fullnode runs the
+    /// version of its validator.
+    ///
+    /// The `seed`, if any, draws the ports of the nodes and the chaos injected in the swarm, to
+    /// reproduce a run along with an `rng` seeded the same.
+    pub async fn new_swarm_with_versions<R>(
+        &self,
+        rng: R,
+        number_of_validators: NonZeroUsize,
+        number_of_fullnodes: usize,
+        versions: &[Version],
+        genesis_framework: Option<ReleaseBundle>,
+        init_config: Option<InitConfigFn>,
+        vfn_config: Option<NodeConfig>,
+        init_genesis_config: Option<InitGenesisConfigFn>,
+        seed: Option<u64>,
+        guard: ActiveNodesGuard,
+    ) -> Result<LocalSwarm>
+    where
+        R: ::rand::RngCore + ::rand::CryptoRng,
+    {
+        ensure!(!versions.is_empty(), "No version to launch the swarm with");
+
         // Build the swarm
         let mut swarm = LocalSwarm::build(
             rng,
             number_of_validators,
             self.versions.clone(),
-            Some(version.clone()),
+            Some(versions[0].clone()),
             init_config,
             init_genesis_config,
             None,
             genesis_framework,
+            seed,
             guard,
         )?;
+        swarm.set_validator_versions(versions)?;
 
         // Launch the swarm
         swarm
@@ -148,9 +216,10 @@ impl LocalFactory {
         // Add and launch the fullnodes
         let validator_peer_ids = swarm.validators().map(|v| v.peer_id()).collect::<Vec<_>>();
         for validator_peer_id in validator_peer_ids.iter().take(number_of_fullnodes) {
+            let version = swarm.validator(*validator_peer_id).unwrap().version();
             let _ = swarm
                 .add_validator_fullnode(
-                    version,
+                    &version,
                     vfn_config
                         .clone()
                         .unwrap_or_else(NodeConfig::default_for_validator_full_node),
@@ -162,6 +231,32 @@ impl LocalFactory {
 
         Ok(swarm)
     }
+
+    /// Launches a swarm from the snapshot saved to `snapshot_dir`, see [`Swarm::snapshot`], with
+    /// all its nodes running the given version. The APIs and the other services of the nodes get
+    /// new ports, but their networks listen on the ports of the snapshotted swarm, which are part
+    /// of its validator set, so a snapshot can only be running once at a time.
+    pub async fn restore_swarm(
+        &self,
+        snapshot_dir: &Path,
+        snapshot: SwarmSnapshot,
+        version: &Version,
+        guard: ActiveNodesGuard,
+    ) -> Result<LocalSwarm> {
+        let mut swarm = LocalSwarm::restore(
+            snapshot_dir,
+            snapshot,
+            self.versions.clone(),
+            version,
+            None,
+            guard,
+        )?;
+        swarm
+            .launch()
+            .await
+            .with_context(|| format!("Swarm logs can be found here: {}", swarm.logs_location()))?;
+        Ok(swarm)
+    }
 }
 
 #[async_trait::async_trait]
@@ -196,6 +291,14 @@ impl Factory for LocalFactory {
         // no guarding, as this code path is not used in parallel
         let guard = ActiveNodesGuard::grab(1, Arc::new(Mutex::new(0))).await;
 
+        let init_genesis_config = self.gas_overrides.clone().map(|gas_overrides| {
+            Arc::new(move |genesis_config: &mut GenesisConfiguration| {
+                gas_overrides
+                    .apply(&mut genesis_config.gas_schedule)
+                    .expect("Gas overrides should apply to the genesis gas schedule");
+            }) as InitGenesisConfigFn
+        });
+
         let swarm = self
             .new_swarm_with_version(
                 rng,
@@ -205,6 +308,7 @@ impl Factory for LocalFactory {
                 framework,
                 None,
                 None,
+                init_genesis_config,
                 None,
                 guard,
             )
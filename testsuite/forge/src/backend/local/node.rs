@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{FullNode, HealthCheckError, LocalVersion, Node, NodeExt, Validator, Version};
+use crate::{
+    FullNode, HealthCheckError, LocalVersion, LogEntry, Node, NodeExt, Validator, Version,
+};
 use anyhow::{anyhow, ensure, Context, Result};
 use aptos_config::{config::NodeConfig, keys::ConfigKey};
 use aptos_db::{LEDGER_DB_NAME, STATE_MERKLE_DB_NAME};
@@ -12,14 +14,19 @@ use aptos_sdk::{
 };
 use aptos_state_sync_driver::metadata_storage::STATE_SYNC_DB_NAME;
 use std::{
+    collections::BTreeMap,
     env,
     fs::{self, OpenOptions},
+    io::{BufRead, BufReader},
     path::PathBuf,
     process::{Child, Command},
     str::FromStr,
 };
 use url::Url;
 
+/// What the panic handlers of the node and the Rust runtime both log when a thread panics
+const PANIC_LOG_MARKER: &str = "panicked at";
+
 #[derive(Debug)]
 struct Process(Child);
 
@@ -50,6 +57,8 @@ pub struct LocalNode {
     peer_id: AccountAddress,
     directory: PathBuf,
     config: NodeConfig,
+    /// Exit statuses of the processes of the node that terminated without being stopped, by PID
+    unexpected_exits: BTreeMap<u32, String>,
 }
 
 impl LocalNode {
@@ -76,6 +85,7 @@ impl LocalNode {
             peer_id,
             directory,
             config,
+            unexpected_exits: BTreeMap::new(),
         })
     }
 
@@ -157,9 +167,53 @@ impl LocalNode {
     }
 
     pub fn stop(&mut self) {
+        self.record_unexpected_exit();
         self.process = None;
     }
 
+    /// Records the exit of the node process if it terminated on its own, e.g., after a panic.
+    /// Nodes stopped by the swarm aren't affected.
+    fn record_unexpected_exit(&mut self) {
+        if let Some(p) = &mut self.process {
+            if let Ok(Some(status)) = p.0.try_wait() {
+                self.unexpected_exits.insert(p.0.id(), status.to_string());
+            }
+        }
+    }
+
+    /// Ensures that the node neither crashed nor panicked since it was first started, even if it
+    /// was restarted and recovered since.
+    pub fn check_no_crashes_or_panics(&mut self) -> Result<()> {
+        self.record_unexpected_exit();
+        ensure!(
+            self.unexpected_exits.is_empty(),
+            "Node {} exited unexpectedly: {}",
+            self.name,
+            self.unexpected_exits
+                .iter()
+                .map(|(pid, status)| format!("PID {} with {}", pid, status))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let panics = self
+            .get_log_contents()
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| line.contains(PANIC_LOG_MARKER))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        ensure!(
+            panics.is_empty(),
+            "Node {} panicked {} times, see {:?}. First panic: {}",
+            self.name,
+            panics.len(),
+            self.log_path(),
+            panics[0]
+        );
+        Ok(())
+    }
+
     pub fn port(&self) -> u16 {
         self.config.api.address.port()
     }
@@ -176,12 +230,78 @@ impl LocalNode {
         &mut self.config
     }
 
+    /// Sets the version the node runs once started
+    pub(crate) fn set_version(&mut self, version: LocalVersion) -> Result<()> {
+        ensure!(self.process.is_none(), "node {} already running", self.name);
+        self.version = version;
+        Ok(())
+    }
+
     pub fn upgrade(&mut self, version: LocalVersion) -> Result<()> {
         self.stop();
         self.version = version;
         self.start()
     }
 
+    /// Restarts the node, wiping its databases and/or its secure storage while it is stopped, e.g.,
+    /// to test a validator losing its state and syncing back from its waypoint
+    pub fn restart_with_options(
+        &mut self,
+        wipe_db: bool,
+        clear_secure_storage: bool,
+    ) -> Result<()> {
+        self.stop();
+        if wipe_db {
+            self.remove_dbs()?;
+        }
+        if clear_secure_storage {
+            self.remove_secure_storage()?;
+        }
+        self.start()
+    }
+
+    fn db_paths(&self) -> [PathBuf; 3] {
+        let storage_dir = self.config.storage.dir();
+        [
+            storage_dir.join(LEDGER_DB_NAME),
+            storage_dir.join(STATE_MERKLE_DB_NAME),
+            storage_dir.join(STATE_SYNC_DB_NAME),
+        ]
+    }
+
+    fn secure_storage_path(&self) -> PathBuf {
+        self.config.working_dir().join("secure_storage.json")
+    }
+
+    /// Removes the ledger, state and state sync dbs of the node
+    fn remove_dbs(&self) -> Result<()> {
+        for path in self.db_paths() {
+            if path.exists() {
+                fs::remove_dir_all(&path)
+                    .with_context(|| format!("Failed to delete {:?}", path))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the secure storage of the node, which only validators have
+    fn remove_secure_storage(&self) -> Result<()> {
+        let path = self.secure_storage_path();
+        if self.config.base.role.is_validator() && path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to delete {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the entries of the log of the node, as they are read from it
+    pub fn log_stream(&self) -> Result<impl Iterator<Item = LogEntry>> {
+        let log_file = fs::File::open(self.log_path())?;
+        Ok(BufReader::new(log_file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| LogEntry::parse(&line)))
+    }
+
     pub fn get_log_contents(&self) -> Result<String> {
         fs::read_to_string(self.log_path()).map_err(Into::into)
     }
@@ -193,6 +313,7 @@ impl LocalNode {
             match p.0.try_wait() {
                 // This would mean the child process has crashed
                 Ok(Some(status)) => {
+                    self.unexpected_exits.insert(p.0.id(), status.to_string());
                     let error = format!("Node '{}' crashed with: {}", self.name, status);
                     return Err(HealthCheckError::NotRunning(error));
                 }
@@ -279,11 +400,8 @@ impl Node for LocalNode {
 
     async fn clear_storage(&mut self) -> Result<()> {
         // Remove all storage files (i.e., blockchain data, consensus data and state sync data)
-        let node_config = self.config();
-        let ledger_db_path = node_config.storage.dir().join(LEDGER_DB_NAME);
-        let state_db_path = node_config.storage.dir().join(STATE_MERKLE_DB_NAME);
-        let secure_storage_path = node_config.working_dir().join("secure_storage.json");
-        let state_sync_db_path = node_config.storage.dir().join(STATE_SYNC_DB_NAME);
+        let [ledger_db_path, state_db_path, state_sync_db_path] = self.db_paths();
+        let secure_storage_path = self.secure_storage_path();
 
         debug!(
             "Deleting ledger, state, secure and state sync db paths ({:?}, {:?}, {:?}, {:?}) for node {:?}",
@@ -302,20 +420,8 @@ impl Node for LocalNode {
         }
 
         // Remove the files
-        fs::remove_dir_all(ledger_db_path)
-            .map_err(anyhow::Error::from)
-            .context("Failed to delete ledger_db_path")?;
-        fs::remove_dir_all(state_db_path)
-            .map_err(anyhow::Error::from)
-            .context("Failed to delete state_db_path")?;
-        fs::remove_dir_all(state_sync_db_path)
-            .map_err(anyhow::Error::from)
-            .context("Failed to delete state_sync_db_path")?;
-        if self.config.base.role.is_validator() {
-            fs::remove_file(secure_storage_path)
-                .map_err(anyhow::Error::from)
-                .context("Failed to delete secure_storage_db_path")?;
-        }
+        self.remove_dbs()?;
+        self.remove_secure_storage()?;
 
         // Stop the node to clear buffers
         self.stop();
@@ -0,0 +1,125 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, Context, Result};
+use aptos_config::{
+    config::{NetworkConfig, NodeConfig},
+    keys::ConfigKey,
+};
+use aptos_sdk::{
+    crypto::ed25519::Ed25519PrivateKey,
+    types::{chain_id::ChainId, waypoint::Waypoint, PeerId},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// File describing the swarm in a snapshot, written once the nodes are copied
+const SNAPSHOT_FILE: &str = "snapshot.yaml";
+
+/// Directory of a snapshot the directory of the swarm is copied to
+const NODES_DIR: &str = "nodes";
+
+/// Logs of the nodes, which are left out of the snapshots
+const LOG_FILE: &str = "log";
+
+/// The state of a local swarm that isn't persisted by its nodes, along with the copy of their
+/// directories
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SwarmSnapshot {
+    /// Directory of the snapshotted swarm, which the configs of the nodes refer to
+    pub(crate) dir: PathBuf,
+    pub(crate) node_name_counter: usize,
+    pub(crate) genesis_waypoint: Waypoint,
+    pub(crate) chain_id: ChainId,
+    pub(crate) root_key: ConfigKey<Ed25519PrivateKey>,
+    pub(crate) root_sequence_number: u64,
+    pub(crate) validators: Vec<NodeSnapshot>,
+    pub(crate) fullnodes: Vec<NodeSnapshot>,
+    pub(crate) public_networks: HashMap<PeerId, NetworkConfig>,
+}
+
+/// A node of a swarm snapshot, whose directory is named after it
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct NodeSnapshot {
+    pub name: String,
+    pub index: usize,
+    pub account_private_key: Option<ConfigKey<Ed25519PrivateKey>>,
+}
+
+impl SwarmSnapshot {
+    /// Loads the snapshot saved to the given directory
+    pub fn load(snapshot_dir: &Path) -> Result<Self> {
+        let snapshot_file = snapshot_dir.join(SNAPSHOT_FILE);
+        let snapshot = fs::read_to_string(&snapshot_file)
+            .with_context(|| format!("No snapshot at {:?}", snapshot_file))?;
+        Ok(serde_yaml::from_str(&snapshot)?)
+    }
+
+    pub fn num_validators(&self) -> usize {
+        self.validators.len()
+    }
+
+    /// Copies the directory of the swarm, whose nodes have to be stopped, and saves the snapshot
+    /// to the given directory, replacing any previous one
+    pub(crate) fn save(&self, snapshot_dir: &Path) -> Result<()> {
+        let snapshot_file = snapshot_dir.join(SNAPSHOT_FILE);
+        let nodes_dir = snapshot_dir.join(NODES_DIR);
+        if snapshot_file.exists() {
+            fs::remove_file(&snapshot_file)?;
+        }
+        if nodes_dir.exists() {
+            fs::remove_dir_all(&nodes_dir)?;
+        }
+        copy_dir(&self.dir, &nodes_dir)?;
+
+        // Written last, so that only complete snapshots can be loaded
+        fs::write(snapshot_file, serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Copies the nodes of the snapshot saved to `snapshot_dir` to `dir`, and points their configs
+    /// to their new directories. The services local to each node get ports of their own, while
+    /// the networks keep listening on the ports of the snapshot, which the validator set and the
+    /// seeds of the fullnodes refer to.
+    pub(crate) fn restore(self, snapshot_dir: &Path, dir: &Path) -> Result<Self> {
+        copy_dir(&snapshot_dir.join(NODES_DIR), dir)?;
+
+        let (swarm_dir, dir) = match (self.dir.to_str(), dir.to_str()) {
+            (Some(swarm_dir), Some(dir)) => (swarm_dir, dir),
+            _ => return Err(anyhow!("The swarm directories should be valid unicode")),
+        };
+        for node in self.validators.iter().chain(&self.fullnodes) {
+            let config_path = Path::new(dir).join(&node.name).join("node.yaml");
+            let config = fs::read_to_string(&config_path)?;
+            fs::write(&config_path, config.replace(swarm_dir, dir))?;
+
+            let mut config = NodeConfig::load(&config_path)?;
+            config.api.randomize_ports();
+            config.inspection_service.randomize_ports();
+            config.storage.randomize_ports();
+            config.save(&config_path)?;
+        }
+
+        Ok(Self {
+            dir: PathBuf::from(dir),
+            ..self
+        })
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else if entry.file_name() != LOG_FILE {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
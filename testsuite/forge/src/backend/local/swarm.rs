@@ -1,15 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use super::{
+    chaos::ChaosProxies,
+    snapshot::{NodeSnapshot, SwarmSnapshot},
+};
 use crate::{
     interface::system_metrics::SystemMetricsThreshold, ChainInfo, FullNode, HealthCheckError,
-    LocalNode, LocalVersion, Node, Swarm, SwarmChaos, SwarmExt, Validator, Version,
+    LocalNode, LocalVersion, LogEntry, Node, Swarm, SwarmChaos, SwarmExt, Validator, Version,
 };
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, ensure, Result};
 use aptos_config::{
     config::{NetworkConfig, NodeConfig},
     keys::ConfigKey,
     network_id::NetworkId,
+    utils::{get_available_port, with_port_seed},
 };
 use aptos_framework::ReleaseBundle;
 use aptos_genesis::builder::{FullnodeNodeConfig, InitConfigFn, InitGenesisConfigFn};
@@ -18,11 +23,16 @@ use aptos_logger::{info, warn};
 use aptos_sdk::{
     crypto::ed25519::Ed25519PrivateKey,
     types::{
-        chain_id::ChainId, transaction::Transaction, waypoint::Waypoint, AccountKey, LocalAccount,
-        PeerId,
+        chain_id::ChainId,
+        network_address::{NetworkAddress, Protocol},
+        transaction::Transaction,
+        waypoint::Waypoint,
+        AccountKey, LocalAccount, PeerId,
     },
 };
+use chrono::{DateTime, Utc};
 use prometheus_http_query::response::PromqlResult;
+use regex::Regex;
 use std::{
     collections::HashMap,
     fs, mem,
@@ -30,7 +40,7 @@ use std::{
     ops,
     path::{Path, PathBuf},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 use tempfile::TempDir;
 
@@ -60,6 +70,22 @@ impl SwarmDirectory {
     }
 }
 
+impl SwarmDirectory {
+    /// Returns the given directory, emptied, or a temporary directory
+    fn new(dir: Option<PathBuf>) -> Result<Self> {
+        Ok(match dir {
+            Some(dir) => {
+                if dir.exists() {
+                    fs::remove_dir_all(&dir)?;
+                }
+                fs::create_dir_all(&dir)?;
+                SwarmDirectory::Persistent(dir)
+            }
+            None => SwarmDirectory::Temporary(TempDir::new()?),
+        })
+    }
+}
+
 impl ops::Deref for SwarmDirectory {
     type Target = Path;
 
@@ -93,6 +119,10 @@ pub struct LocalSwarm {
     root_account: LocalAccount,
     chain_id: ChainId,
     root_key: ConfigKey<Ed25519PrivateKey>,
+    /// Proxies of the validator network, started when chaos is first injected
+    chaos_proxies: Option<ChaosProxies>,
+    /// Seeds the ports of the nodes and the chaos, to reproduce a run
+    seed: Option<u64>,
 
     launched: bool,
     #[allow(dead_code)]
@@ -109,52 +139,45 @@ impl LocalSwarm {
         init_genesis_config: Option<InitGenesisConfigFn>,
         dir: Option<PathBuf>,
         genesis_framework: Option<ReleaseBundle>,
+        seed: Option<u64>,
         guard: ActiveNodesGuard,
     ) -> Result<LocalSwarm>
     where
         R: ::rand::RngCore + ::rand::CryptoRng,
     {
         info!("Building a new swarm");
-        let dir_actual = if let Some(dir_) = dir {
-            if dir_.exists() {
-                fs::remove_dir_all(&dir_)?;
-            }
-            fs::create_dir_all(&dir_)?;
-            SwarmDirectory::Persistent(dir_)
-        } else {
-            SwarmDirectory::Temporary(TempDir::new()?)
-        };
+        let dir_actual = SwarmDirectory::new(dir)?;
+
+        let genesis_builder = aptos_genesis::builder::Builder::new(
+            &dir_actual,
+            genesis_framework
+                .unwrap_or_else(|| aptos_cached_packages::head_release_bundle().clone()),
+        )?
+        .with_num_validators(number_of_validators)
+        .with_init_config(Some(Arc::new(
+            move |index, config, genesis_stake_amount| {
+                // for local tests, turn off parallel execution:
+                config.execution.concurrency_level = 1;
+
+                // Single node orders blocks too fast which would trigger backpressure and stall for 1 sec
+                // which cause flakiness in tests.
+                if number_of_validators.get() == 1 {
+                    // this delays empty block by (30-1) * 30ms
+                    config.consensus.quorum_store_poll_count = 30;
+                    config
+                        .state_sync
+                        .state_sync_driver
+                        .max_connection_deadline_secs = 1;
+                }
 
+                if let Some(init_config) = &init_config {
+                    (init_config)(index, config, genesis_stake_amount);
+                }
+            },
+        )))
+        .with_init_genesis_config(init_genesis_config);
         let (root_key, genesis, genesis_waypoint, validators) =
-            aptos_genesis::builder::Builder::new(
-                &dir_actual,
-                genesis_framework
-                    .unwrap_or_else(|| aptos_cached_packages::head_release_bundle().clone()),
-            )?
-            .with_num_validators(number_of_validators)
-            .with_init_config(Some(Arc::new(
-                move |index, config, genesis_stake_amount| {
-                    // for local tests, turn off parallel execution:
-                    config.execution.concurrency_level = 1;
-
-                    // Single node orders blocks too fast which would trigger backpressure and stall for 1 sec
-                    // which cause flakiness in tests.
-                    if number_of_validators.get() == 1 {
-                        // this delays empty block by (30-1) * 30ms
-                        config.consensus.quorum_store_poll_count = 30;
-                        config
-                            .state_sync
-                            .state_sync_driver
-                            .max_connection_deadline_secs = 1;
-                    }
-
-                    if let Some(init_config) = &init_config {
-                        (init_config)(index, config, genesis_stake_amount);
-                    }
-                },
-            )))
-            .with_init_genesis_config(init_genesis_config)
-            .build(rng)?;
+            with_seeded_ports(seed, 0, || genesis_builder.build(rng))?;
 
         // Get the initial version to start the nodes with, either the one provided or fallback to
         // using the latest version
@@ -235,6 +258,80 @@ impl LocalSwarm {
             root_account,
             chain_id: ChainId::test(),
             root_key,
+            chaos_proxies: None,
+            seed,
+            launched: false,
+            guard,
+        })
+    }
+
+    /// Builds a swarm from the snapshot saved to `snapshot_dir`, see [`Swarm::snapshot`], with all
+    /// its nodes running `version`
+    pub fn restore(
+        snapshot_dir: &Path,
+        snapshot: SwarmSnapshot,
+        versions: Arc<HashMap<Version, LocalVersion>>,
+        version: &Version,
+        dir: Option<PathBuf>,
+        guard: ActiveNodesGuard,
+    ) -> Result<LocalSwarm> {
+        info!("Restoring swarm snapshot {:?}", snapshot_dir);
+        let dir_actual = SwarmDirectory::new(dir)?;
+        let snapshot = snapshot.restore(snapshot_dir, &dir_actual.canonicalize()?)?;
+
+        let version = versions
+            .get(version)
+            .ok_or_else(|| anyhow!("Invalid version: {:?}", version))?;
+        let restore_node = |node: NodeSnapshot| -> Result<(PeerId, LocalNode)> {
+            let node = LocalNode::new(
+                version.clone(),
+                node.name.clone(),
+                node.index,
+                snapshot.dir.join(&node.name),
+                node.account_private_key,
+            )?;
+            Ok((node.peer_id(), node))
+        };
+        let validators = snapshot
+            .validators
+            .into_iter()
+            .map(restore_node)
+            .collect::<Result<HashMap<_, _>>>()?;
+        let fullnodes = snapshot
+            .fullnodes
+            .into_iter()
+            .map(restore_node)
+            .collect::<Result<HashMap<_, _>>>()?;
+        let genesis = validators
+            .values()
+            .find_map(|validator| validator.config().execution.genesis.clone())
+            .ok_or_else(|| {
+                anyhow!(
+                    "The validators of snapshot {:?} have no genesis",
+                    snapshot_dir
+                )
+            })?;
+
+        let root_account = LocalAccount::new(
+            aptos_sdk::types::account_config::aptos_test_root_address(),
+            AccountKey::from_private_key(snapshot.root_key.private_key()),
+            snapshot.root_sequence_number,
+        );
+
+        Ok(LocalSwarm {
+            node_name_counter: snapshot.node_name_counter,
+            genesis,
+            genesis_waypoint: snapshot.genesis_waypoint,
+            versions,
+            validators,
+            fullnodes,
+            public_networks: snapshot.public_networks,
+            dir: dir_actual,
+            root_account,
+            chain_id: snapshot.chain_id,
+            root_key: snapshot.root_key,
+            chaos_proxies: None,
+            seed: None,
             launched: false,
             guard,
         })
@@ -250,6 +347,10 @@ impl LocalSwarm {
         for validator in self.validators.values_mut() {
             validator.start()?;
         }
+        // Fullnodes are only there before launching when restored from a snapshot
+        for fullnode in self.fullnodes.values_mut() {
+            fullnode.start()?;
+        }
 
         self.wait_all_alive(Duration::from_secs(60)).await?;
         info!("Swarm launched successfully.");
@@ -333,15 +434,17 @@ impl LocalSwarm {
         let name = self.node_name_counter.to_string();
         let index = self.node_name_counter;
         self.node_name_counter += 1;
-        let fullnode_config = FullnodeNodeConfig::validator_fullnode(
-            name,
-            self.dir.as_ref(),
-            template,
-            validator.config(),
-            &self.genesis_waypoint,
-            &self.genesis,
-            public_network,
-        )?;
+        let fullnode_config = with_seeded_ports(self.seed, index as u64, || {
+            FullnodeNodeConfig::validator_fullnode(
+                name,
+                self.dir.as_ref(),
+                template,
+                validator.config(),
+                &self.genesis_waypoint,
+                &self.genesis,
+                public_network,
+            )
+        })?;
 
         let version = self.versions.get(version).unwrap();
         let mut fullnode = LocalNode::new(
@@ -365,13 +468,15 @@ impl LocalSwarm {
         let name = self.node_name_counter.to_string();
         let index = self.node_name_counter;
         self.node_name_counter += 1;
-        let fullnode_config = FullnodeNodeConfig::public_fullnode(
-            name,
-            self.dir.as_ref(),
-            template,
-            &self.genesis_waypoint,
-            &self.genesis,
-        )?;
+        let fullnode_config = with_seeded_ports(self.seed, index as u64, || {
+            FullnodeNodeConfig::public_fullnode(
+                name,
+                self.dir.as_ref(),
+                template,
+                &self.genesis_waypoint,
+                &self.genesis,
+            )
+        })?;
 
         let version = self.versions.get(version).unwrap();
         let mut fullnode = LocalNode::new(
@@ -420,6 +525,31 @@ impl LocalSwarm {
         validators.into_iter()
     }
 
+    /// Splits the validators evenly between the given versions, in order of their indices. This
+    /// has to be done before the swarm is launched.
+    pub fn set_validator_versions(&mut self, versions: &[Version]) -> Result<()> {
+        ensure!(!self.launched, "Swarm already launched");
+        ensure!(
+            !versions.is_empty(),
+            "No version to run the validators with"
+        );
+        let versions = versions
+            .iter()
+            .map(|version| {
+                self.versions
+                    .get(version)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Invalid version: {:?}", version))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let num_validators = self.validators.len();
+        for validator in self.validators.values_mut() {
+            let version = &versions[validator.index() * versions.len() / num_validators];
+            validator.set_version(version.clone())?;
+        }
+        Ok(())
+    }
+
     pub fn fullnode(&self, peer_id: PeerId) -> Option<&LocalNode> {
         self.fullnodes.get(&peer_id)
     }
@@ -431,6 +561,59 @@ impl LocalSwarm {
     pub fn dir(&self) -> &Path {
         self.dir.as_ref()
     }
+
+    /// Puts a proxy in front of the validator network of every validator, to inject chaos
+    ///
+    /// The validators are restarted to listen on new ports, while the proxies listen on the
+    /// ports of the validator set.
+    fn chaos_proxies(&mut self) -> Result<&ChaosProxies> {
+        if self.chaos_proxies.is_none() {
+            let validators = self.validators().map(|v| v.peer_id()).collect();
+            let mut proxies = ChaosProxies::new(validators, self.seed)?;
+            let seed = self.seed;
+            for validator in self.validators_mut() {
+                // Salted apart from the ports drawn for the nodes themselves
+                let salt = u64::max_value() - validator.index() as u64;
+                let network = validator
+                    .config_mut()
+                    .validator_network
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("Validator has no validator network"))?;
+                let listen_port = network
+                    .listen_address
+                    .find_port()
+                    .ok_or_else(|| anyhow!("Validator network has no port"))?;
+                let node_port = with_seeded_ports(seed, salt, get_available_port);
+                network.listen_address = NetworkAddress::from_protocols(
+                    network
+                        .listen_address
+                        .as_slice()
+                        .iter()
+                        .map(|protocol| match protocol {
+                            Protocol::Tcp(_) => Protocol::Tcp(node_port),
+                            protocol => protocol.clone(),
+                        })
+                        .collect(),
+                )?;
+                validator.config().save(validator.config_path())?;
+
+                validator.stop();
+                proxies.proxy(validator.peer_id(), listen_port, node_port)?;
+                validator.start()?;
+            }
+            self.chaos_proxies = Some(proxies);
+        }
+        Ok(self.chaos_proxies.as_ref().unwrap())
+    }
+}
+
+/// Runs `f` with the ports drawn from the seed of a swarm, if any, mixed with `salt` so that each
+/// node gets its own ports
+fn with_seeded_ports<T>(seed: Option<u64>, salt: u64, f: impl FnOnce() -> T) -> T {
+    match seed {
+        Some(seed) => with_port_seed(seed.wrapping_add(salt), f),
+        None => f(),
+    }
 }
 
 impl Drop for LocalSwarm {
@@ -438,6 +621,9 @@ impl Drop for LocalSwarm {
         // If panicking, persist logs
         if std::thread::panicking() {
             eprintln!("Logs located at {}", self.logs_location());
+            if let Some(seed) = self.seed {
+                eprintln!("Swarm seeded with {}", seed);
+            }
         }
     }
 }
@@ -491,6 +677,64 @@ impl Swarm for LocalSwarm {
         validator.upgrade(version)
     }
 
+    async fn restart_validator_with_options(
+        &mut self,
+        id: PeerId,
+        wipe_db: bool,
+        clear_secure_storage: bool,
+    ) -> Result<()> {
+        let validator = self
+            .validators
+            .get_mut(&id)
+            .ok_or_else(|| anyhow!("Invalid id: {}", id))?;
+        validator.restart_with_options(wipe_db, clear_secure_storage)
+    }
+
+    async fn snapshot(&mut self, snapshot_dir: &Path) -> Result<()> {
+        ensure!(self.launched, "Swarm not launched");
+        ensure!(
+            self.chaos_proxies.is_none(),
+            "Can't snapshot a swarm with chaos injected, its validators don't listen on the ports \
+             of the validator set"
+        );
+        let node_snapshot = |node: &LocalNode| NodeSnapshot {
+            name: node.name().to_string(),
+            index: node.index(),
+            account_private_key: node.account_private_key().clone(),
+        };
+        let snapshot = SwarmSnapshot {
+            dir: self.dir().canonicalize()?,
+            node_name_counter: self.node_name_counter,
+            genesis_waypoint: self.genesis_waypoint,
+            chain_id: self.chain_id,
+            root_key: self.root_key.clone(),
+            root_sequence_number: self.root_account.sequence_number(),
+            validators: self.validators.values().map(node_snapshot).collect(),
+            fullnodes: self.fullnodes.values().map(node_snapshot).collect(),
+            public_networks: self.public_networks.clone(),
+        };
+
+        // The databases can only be copied while the nodes are stopped
+        for node in self
+            .validators
+            .values_mut()
+            .chain(self.fullnodes.values_mut())
+        {
+            node.stop();
+        }
+        let saved = snapshot.save(snapshot_dir);
+        for node in self
+            .validators
+            .values_mut()
+            .chain(self.fullnodes.values_mut())
+        {
+            node.start()?;
+        }
+        self.wait_all_alive(Duration::from_secs(60)).await?;
+        info!("Swarm snapshot saved to {:?}", snapshot_dir);
+        saved
+    }
+
     fn full_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn FullNode> + 'a> {
         Box::new(self.fullnodes.values().map(|v| v as &'a dyn FullNode))
     }
@@ -560,16 +804,40 @@ impl Swarm for LocalSwarm {
         self.dir.display().to_string()
     }
 
-    fn inject_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
-        todo!()
+    fn grep_logs(&self, pattern: &str, since: SystemTime) -> Result<Vec<(PeerId, LogEntry)>> {
+        let pattern = Regex::new(pattern)?;
+        let since = DateTime::<Utc>::from(since);
+        let mut entries = vec![];
+        for node in self.validators.values().chain(self.fullnodes.values()) {
+            entries.extend(
+                node.log_stream()?
+                    .filter(|entry| entry.timestamp >= since && entry.is_match(&pattern))
+                    .map(|entry| (node.peer_id(), entry)),
+            );
+        }
+        entries.sort_by_key(|(_, entry)| entry.timestamp);
+        Ok(entries)
     }
 
-    fn remove_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
-        todo!()
+    fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        info!("Injecting chaos: {:?}", chaos);
+        self.chaos_proxies()?.insert(chaos);
+        Ok(())
+    }
+
+    fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        match &self.chaos_proxies {
+            Some(proxies) if proxies.remove(&chaos) => Ok(()),
+            _ => bail!("Chaos {:?} not found", chaos),
+        }
     }
 
     fn remove_all_chaos(&mut self) -> Result<()> {
-        todo!()
+        // The proxies are kept, without chaos
+        if let Some(proxies) = &self.chaos_proxies {
+            proxies.clear();
+        }
+        Ok(())
     }
 
     async fn ensure_no_validator_restart(&self) -> Result<()> {
@@ -580,6 +848,21 @@ impl Swarm for LocalSwarm {
         todo!()
     }
 
+    async fn assert_no_restarts_or_panics(&mut self) -> Result<()> {
+        let errors: Vec<_> = self
+            .validators
+            .values_mut()
+            .chain(self.fullnodes.values_mut())
+            .filter_map(|node| node.check_no_crashes_or_panics().err())
+            .map(|err| err.to_string())
+            .collect();
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+        info!("Found no node restarts or panics");
+        Ok(())
+    }
+
     async fn query_metrics(
         &self,
         _query: &str,
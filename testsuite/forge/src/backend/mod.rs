@@ -4,5 +4,8 @@
 mod local;
 pub use local::{LocalNode, *};
 
+mod existing;
+pub use existing::*;
+
 mod k8s;
 pub use k8s::{K8sNode, *};
@@ -0,0 +1,109 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::Level;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::Value;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// An entry of the log of a node, parsed from the text format of aptos-logger:
+/// `<timestamp> [<thread name>] <level> <source> <message> <data as JSON>`
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub thread_name: Option<String>,
+    pub level: Level,
+    /// File and line of the code logging the entry
+    pub source: String,
+    pub message: Option<String>,
+    pub data: BTreeMap<String, Value>,
+}
+
+impl LogEntry {
+    /// Parses a line of a log. Returns None for lines that don't start an entry, e.g., the
+    /// following lines of a backtrace.
+    pub fn parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp)
+            .ok()?
+            .with_timezone(&Utc);
+        let (thread_name, rest) = match rest.strip_prefix('[') {
+            Some(rest) => {
+                let (thread_name, rest) = rest.split_once("] ")?;
+                (Some(thread_name.to_string()), rest)
+            }
+            None => (None, rest),
+        };
+        let (level, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        let level = Level::from_str(level).ok()?;
+        let (source, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        // The data is the JSON object ending the line, if any. The message may contain braces.
+        let (message, data) = rest
+            .match_indices('{')
+            .filter(|(index, _)| *index == 0 || rest[..*index].ends_with(' '))
+            .find_map(|(index, _)| {
+                serde_json::from_str(&rest[index..])
+                    .ok()
+                    .map(|data| (rest[..index].trim_end(), data))
+            })
+            .unwrap_or((rest, BTreeMap::new()));
+
+        Some(Self {
+            timestamp,
+            thread_name,
+            level,
+            source: source.to_string(),
+            message: (!message.is_empty()).then(|| message.to_string()),
+            data,
+        })
+    }
+
+    /// Whether the message, or a value of the data, of this entry matches the pattern
+    pub fn is_match(&self, pattern: &Regex) -> bool {
+        self.message
+            .as_ref()
+            .map_or(false, |message| pattern.is_match(message))
+            || self.data.values().any(|value| match value {
+                Value::String(value) => pattern.is_match(value),
+                value => pattern.is_match(&value.to_string()),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_entry() {
+        let entry = LogEntry::parse(
+            r#"2022-11-08T10:01:02.123456Z [consensus] INFO consensus/src/epoch_manager.rs:594 Starting new epoch {"epoch":2,"root_block":"[id: 1a2b]"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.thread_name.as_deref(), Some("consensus"));
+        assert_eq!(entry.level, Level::Info);
+        assert_eq!(entry.source, "consensus/src/epoch_manager.rs:594");
+        assert_eq!(entry.message.as_deref(), Some("Starting new epoch"));
+        assert_eq!(entry.data["epoch"], Value::from(2));
+        assert!(entry.is_match(&Regex::new("^Starting new epoch$").unwrap()));
+        assert!(entry.is_match(&Regex::new("1a2b").unwrap()));
+
+        let entry = LogEntry::parse(
+            r#"2022-11-08T10:01:02.123456Z WARN state-sync/src/driver.rs:10 {not json} {"error":"timeout"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.thread_name, None);
+        assert_eq!(entry.level, Level::Warn);
+        assert_eq!(entry.message.as_deref(), Some("{not json}"));
+        assert_eq!(entry.data["error"], Value::from("timeout"));
+
+        let entry =
+            LogEntry::parse("2022-11-08T10:01:02.123456Z ERROR aptos-node/src/lib.rs:1").unwrap();
+        assert_eq!(entry.message, None);
+        assert!(entry.data.is_empty());
+
+        assert_eq!(LogEntry::parse("   0: backtrace::capture"), None);
+    }
+}
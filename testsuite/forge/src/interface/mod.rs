@@ -15,6 +15,8 @@ mod swarm;
 pub use swarm::*;
 mod chaos;
 pub use chaos::*;
+mod logs;
+pub use logs::*;
 mod node;
 pub use node::*;
 mod chain_info;
@@ -4,6 +4,7 @@
 use std::time::Duration;
 
 use super::Test;
+use crate::latency_probe::LatencyProbeStats;
 use crate::success_criteria::{SuccessCriteria, SuccessCriteriaChecker};
 use crate::{CoreContext, Result, Swarm, TestReport};
 use aptos_transaction_emitter_lib::{EmitJobRequest, TxnStats};
@@ -58,6 +59,7 @@ impl<'t> NetworkContext<'t> {
     pub fn check_for_success(
         &mut self,
         stats: &TxnStats,
+        probe_stats: Option<&LatencyProbeStats>,
         window: Duration,
         start_time: i64,
         end_time: i64,
@@ -69,6 +71,7 @@ impl<'t> NetworkContext<'t> {
                 &self.success_criteria,
                 self.swarm,
                 stats,
+                probe_stats,
                 window,
                 start_time,
                 end_time,
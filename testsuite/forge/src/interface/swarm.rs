@@ -2,19 +2,31 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    interface::system_metrics::SystemMetricsThreshold, AptosPublicInfo, ChainInfo, FullNode,
-    NodeExt, Result, SwarmChaos, Validator, Version,
+    interface::system_metrics::SystemMetricsThreshold,
+    latency_probe::{LatencyProbe, LatencyProbeConfig},
+    AptosPublicInfo, ChainInfo, FullNode, LogEntry, NodeExt, Result, SwarmChaos, Validator,
+    Version,
 };
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, ensure};
 use aptos_config::config::NodeConfig;
+use aptos_inspection_service::inspection_client::{InspectionClient, MetricValue};
 use aptos_logger::info;
 use aptos_rest_client::Client as RestClient;
 use aptos_sdk::types::PeerId;
 use futures::future::{join_all, try_join_all};
 use prometheus_http_query::response::PromqlResult;
-use std::time::{Duration, Instant};
+use std::{
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
 use tokio::runtime::Runtime;
 
+/// Enough to pay for the gas of the latency probe's marker transactions for days.
+const LATENCY_PROBE_ACCOUNT_FUNDS: u64 = 10_000_000_000;
+
+/// How often metrics are polled by the metric assertions
+const METRIC_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Trait used to represent a running network comprised of Validators and FullNodes
 #[async_trait::async_trait]
 pub trait Swarm: Sync {
@@ -37,6 +49,19 @@ pub trait Swarm: Sync {
     /// Upgrade a Validator to run specified `Version`
     async fn upgrade_validator(&mut self, id: PeerId, version: &Version) -> Result<()>;
 
+    /// Restarts the Validator with the provided PeerId, wiping its databases and/or its secure
+    /// storage while it is stopped, so that it has to sync back from its waypoint
+    async fn restart_validator_with_options(
+        &mut self,
+        id: PeerId,
+        wipe_db: bool,
+        clear_secure_storage: bool,
+    ) -> Result<()>;
+
+    /// Persists the configs and databases of all the nodes to the given directory, stopping them
+    /// meanwhile, so that the swarm can be restored in this state instead of being set up again
+    async fn snapshot(&mut self, snapshot_dir: &Path) -> Result<()>;
+
     /// Returns an Iterator of references to all the FullNodes in the Swarm
     fn full_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = &'a dyn FullNode> + 'a>;
 
@@ -76,6 +101,10 @@ pub trait Swarm: Sync {
 
     fn logs_location(&mut self) -> String;
 
+    /// Returns the entries logged by the nodes since the given time whose message or data match
+    /// the regex pattern, with the ids of the nodes, in the order they were logged
+    fn grep_logs(&self, pattern: &str, since: SystemTime) -> Result<Vec<(PeerId, LogEntry)>>;
+
     /// Injects all types of chaos
     fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()>;
     fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()>;
@@ -84,6 +113,13 @@ pub trait Swarm: Sync {
     async fn ensure_no_validator_restart(&self) -> Result<()>;
     async fn ensure_no_fullnode_restart(&self) -> Result<()>;
 
+    /// Ensures that no node restarted or panicked since the swarm was launched, even if it
+    /// recovered since. Nodes restarted on purpose, e.g. to upgrade them, aren't reported.
+    async fn assert_no_restarts_or_panics(&mut self) -> Result<()> {
+        self.ensure_no_validator_restart().await?;
+        self.ensure_no_fullnode_restart().await
+    }
+
     async fn ensure_healthy_system_metrics(
         &mut self,
         start_time: i64,
@@ -99,10 +135,58 @@ pub trait Swarm: Sync {
         timeout: Option<i64>,
     ) -> Result<PromqlResult>;
 
+    /// Queries a metric from the metrics endpoint of the node with the given id, summing its
+    /// series with the given labels. Returns None if the node doesn't report any such series.
+    async fn query_metric(
+        &self,
+        node: PeerId,
+        metric_name: &str,
+        labels: &[(&str, &str)],
+    ) -> Result<Option<f64>> {
+        let inspection_client = self
+            .validator(node)
+            .map(|node| node.inspection_client())
+            .or_else(|| self.full_node(node).map(|node| node.inspection_client()))
+            .ok_or_else(|| anyhow!("No node with id {}", node))?;
+        query_metric_with_labels(&inspection_client, metric_name, labels).await
+    }
+
     fn aptos_public_info(&mut self) -> AptosPublicInfo<'_> {
         self.chain_info().into_aptos_public_info()
     }
 
+    /// Starts a latency probe submitting its marker transactions to the node with the given id,
+    /// from a new account funded by the root account. The probe runs on the current tokio runtime
+    /// until it is stopped.
+    async fn start_latency_probe(
+        &mut self,
+        config: LatencyProbeConfig,
+        submit_to: PeerId,
+    ) -> Result<LatencyProbe> {
+        let mut aptos_public_info = self.aptos_public_info();
+        let transaction_factory = aptos_public_info.transaction_factory();
+        let account = aptos_public_info
+            .create_and_fund_user_account(LATENCY_PROBE_ACCOUNT_FUNDS)
+            .await?;
+        let submit_client = self
+            .validator(submit_to)
+            .map(|node| node.rest_client())
+            .or_else(|| self.full_node(submit_to).map(|node| node.rest_client()))
+            .ok_or_else(|| anyhow!("No node with id {}", submit_to))?;
+        let visibility_clients = self
+            .get_all_nodes_clients_with_names()
+            .into_iter()
+            .map(|(_, client)| client)
+            .collect();
+        Ok(LatencyProbe::start(
+            config,
+            account,
+            transaction_factory,
+            submit_client,
+            visibility_clients,
+        ))
+    }
+
     fn chain_info_for_node(&mut self, idx: usize) -> ChainInfo<'_>;
 
     fn aptos_public_info_for_node(&mut self, idx: usize) -> AptosPublicInfo<'_> {
@@ -324,6 +408,84 @@ pub trait SwarmExt: Swarm {
             .collect()
     }
 
+    /// Waits for a metric of a node to reach at least `threshold`, e.g., for consensus to reach a
+    /// round or state sync to reach a version, and returns its value
+    async fn assert_eventually_ge(
+        &self,
+        node: PeerId,
+        metric_name: &str,
+        labels: &[(&str, &str)],
+        threshold: f64,
+        timeout: Duration,
+    ) -> Result<f64> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // The node may not be up, or not report the metric yet
+            let value = self
+                .query_metric(node, metric_name, labels)
+                .await
+                .ok()
+                .flatten();
+            if let Some(value) = value {
+                if value >= threshold {
+                    return Ok(value);
+                }
+            }
+            if Instant::now() > deadline {
+                bail!(
+                    "Metric {} of node {} didn't reach {} within {:?}, last value: {:?}",
+                    metric_name,
+                    node,
+                    threshold,
+                    timeout,
+                    value
+                );
+            }
+            tokio::time::sleep(METRIC_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Samples a metric of a node for `duration`, ensuring that it never decreases and that it
+    /// increased in the end, e.g., that consensus makes progress
+    async fn assert_monotonic(
+        &self,
+        node: PeerId,
+        metric_name: &str,
+        labels: &[(&str, &str)],
+        duration: Duration,
+    ) -> Result<()> {
+        let query = move || async move {
+            self.query_metric(node, metric_name, labels)
+                .await?
+                .ok_or_else(|| anyhow!("Node {} doesn't report metric {}", node, metric_name))
+        };
+        let deadline = Instant::now() + duration;
+        let first = query().await?;
+        let mut last = first;
+        while Instant::now() < deadline {
+            tokio::time::sleep(METRIC_POLL_INTERVAL).await;
+            let value = query().await?;
+            ensure!(
+                value >= last,
+                "Metric {} of node {} decreased from {} to {}",
+                metric_name,
+                node,
+                last,
+                value
+            );
+            last = value;
+        }
+        ensure!(
+            last > first,
+            "Metric {} of node {} stayed at {} for {:?}",
+            metric_name,
+            node,
+            first,
+            duration
+        );
+        Ok(())
+    }
+
     async fn get_client_with_newest_ledger_version(&self) -> Option<(u64, RestClient)> {
         let clients = self.get_all_nodes_clients_with_names();
         let ledger_infos = join_all(clients.iter().map(|(_name, client)| async {
@@ -350,6 +512,45 @@ pub trait SwarmExt: Swarm {
     }
 }
 
+/// Sums the series of a metric with the given labels, None if there are none
+async fn query_metric_with_labels(
+    inspection_client: &InspectionClient,
+    metric_name: &str,
+    labels: &[(&str, &str)],
+) -> Result<Option<f64>> {
+    let labels: Vec<_> = labels
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect();
+    let values: Vec<_> = inspection_client
+        .get_node_metric_with_name(metric_name)
+        .await?
+        .into_iter()
+        .flatten()
+        .filter(|(series, _)| {
+            // Series are formatted as `name{label=value,...}`
+            let series_labels: Vec<_> = series
+                .trim_start_matches(metric_name)
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .split(',')
+                .collect();
+            labels
+                .iter()
+                .all(|label| series_labels.contains(&label.as_str()))
+        })
+        .map(|(_, value)| match value {
+            MetricValue::I64(value) => value as f64,
+            MetricValue::F64(value) | MetricValue::I64orF64(_, value) => value,
+        })
+        .collect();
+    Ok(if values.is_empty() {
+        None
+    } else {
+        Some(values.into_iter().sum())
+    })
+}
+
 /// Waits for all nodes to have caught up to the specified `target_version`.
 pub async fn wait_for_all_nodes_to_catchup_to_version(
     clients: &[(String, RestClient)],
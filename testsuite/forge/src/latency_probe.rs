@@ -0,0 +1,260 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! An end-to-end latency probe. While a test runs, it submits marker transactions at a fixed
+//! interval and measures how long each of them takes to be committed by the node it was submitted
+//! to, and to become visible through the API of every node.
+
+use crate::success_criteria::LatencyType;
+use anyhow::{bail, ensure, Result};
+use aptos_cached_packages::aptos_stdlib;
+use aptos_logger::{info, warn};
+use aptos_rest_client::Client as RestClient;
+use aptos_sdk::{
+    crypto::HashValue,
+    transaction_builder::TransactionFactory,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use futures::future::try_join_all;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tokio::{sync::oneshot, task::JoinHandle};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Clone, Debug)]
+pub struct LatencyProbeConfig {
+    /// How often a marker transaction is submitted.
+    pub interval: Duration,
+    /// How long a marker transaction has to become visible on all nodes before it counts as lost.
+    pub timeout: Duration,
+}
+
+impl Default for LatencyProbeConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// What a latency measured by the probe ends with.
+#[derive(Clone, Copy, Debug)]
+pub enum ProbeLatency {
+    /// The marker transaction is committed on the node it was submitted to.
+    Commit,
+    /// The marker transaction is visible through the API of every node.
+    ApiVisibility,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LatencyProbeStats {
+    pub submitted: usize,
+    /// Marker transactions that failed, or weren't visible on all nodes within the timeout.
+    pub lost: usize,
+    /// Sorted latencies of the marker transactions that made it.
+    commit_latencies: Vec<Duration>,
+    api_visibility_latencies: Vec<Duration>,
+}
+
+impl LatencyProbeStats {
+    fn latencies(&self, measure: ProbeLatency) -> &[Duration] {
+        match measure {
+            ProbeLatency::Commit => &self.commit_latencies,
+            ProbeLatency::ApiVisibility => &self.api_visibility_latencies,
+        }
+    }
+
+    /// Returns the given percentile of the latencies, or None if no marker transaction made it.
+    pub fn percentile(&self, measure: ProbeLatency, percentile: usize) -> Option<Duration> {
+        let latencies = self.latencies(measure);
+        if latencies.is_empty() {
+            return None;
+        }
+        let idx = ((latencies.len() - 1) * percentile.min(100) + 50) / 100;
+        Some(latencies[idx])
+    }
+
+    pub fn latency(&self, measure: ProbeLatency, latency_type: &LatencyType) -> Option<Duration> {
+        match latency_type {
+            LatencyType::Average => {
+                let latencies = self.latencies(measure);
+                if latencies.is_empty() {
+                    None
+                } else {
+                    Some(latencies.iter().sum::<Duration>() / latencies.len() as u32)
+                }
+            }
+            LatencyType::P50 => self.percentile(measure, 50),
+            LatencyType::P90 => self.percentile(measure, 90),
+            LatencyType::P99 => self.percentile(measure, 99),
+        }
+    }
+}
+
+impl fmt::Display for LatencyProbeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |latency: Option<Duration>| {
+            latency.map_or_else(|| "-".to_string(), |l| l.as_millis().to_string())
+        };
+        write!(
+            f,
+            "submitted: {}, lost: {}, commit p50/p99: {}/{} ms, api visibility p50/p99: {}/{} ms",
+            self.submitted,
+            self.lost,
+            ms(self.percentile(ProbeLatency::Commit, 50)),
+            ms(self.percentile(ProbeLatency::Commit, 99)),
+            ms(self.percentile(ProbeLatency::ApiVisibility, 50)),
+            ms(self.percentile(ProbeLatency::ApiVisibility, 99)),
+        )
+    }
+}
+
+/// A running latency probe, see the module documentation. Created by
+/// `SwarmExt::start_latency_probe`.
+pub struct LatencyProbe {
+    stop_sender: oneshot::Sender<()>,
+    handle: JoinHandle<LatencyProbeStats>,
+}
+
+impl LatencyProbe {
+    /// Starts submitting marker transactions from `account`, which must be funded, on the current
+    /// tokio runtime.
+    pub fn start(
+        config: LatencyProbeConfig,
+        account: LocalAccount,
+        transaction_factory: TransactionFactory,
+        submit_client: RestClient,
+        visibility_clients: Vec<RestClient>,
+    ) -> Self {
+        let (stop_sender, stop_receiver) = oneshot::channel();
+        let handle = tokio::spawn(run_probe(
+            config,
+            account,
+            transaction_factory,
+            submit_client,
+            visibility_clients,
+            stop_receiver,
+        ));
+        Self {
+            stop_sender,
+            handle,
+        }
+    }
+
+    /// Stops submitting marker transactions, and returns the stats once the outstanding ones are
+    /// visible or timed out.
+    pub async fn stop(self) -> Result<LatencyProbeStats> {
+        let _ = self.stop_sender.send(());
+        Ok(self.handle.await?)
+    }
+}
+
+async fn run_probe(
+    config: LatencyProbeConfig,
+    mut account: LocalAccount,
+    transaction_factory: TransactionFactory,
+    submit_client: RestClient,
+    visibility_clients: Vec<RestClient>,
+    mut stop_receiver: oneshot::Receiver<()>,
+) -> LatencyProbeStats {
+    let mut interval = tokio::time::interval(config.interval);
+    let mut markers = vec![];
+    loop {
+        tokio::select! {
+            _ = &mut stop_receiver => break,
+            _ = interval.tick() => {
+                // Transferring nothing to itself leaves the state of the chain as it is
+                let txn = account.sign_with_transaction_builder(
+                    transaction_factory.payload(aptos_stdlib::aptos_coin_transfer(account.address(), 0)),
+                );
+                markers.push(tokio::spawn(measure_marker(
+                    txn,
+                    submit_client.clone(),
+                    visibility_clients.clone(),
+                    config.timeout,
+                )));
+            }
+        }
+    }
+
+    let mut stats = LatencyProbeStats {
+        submitted: markers.len(),
+        ..LatencyProbeStats::default()
+    };
+    for marker in markers {
+        match marker.await {
+            Ok(Ok((commit_latency, api_visibility_latency))) => {
+                stats.commit_latencies.push(commit_latency);
+                stats.api_visibility_latencies.push(api_visibility_latency);
+            }
+            Ok(Err(err)) => {
+                warn!("Latency probe lost a marker transaction: {:#}", err);
+                stats.lost += 1;
+            }
+            Err(err) => {
+                warn!("Latency probe task failed: {}", err);
+                stats.lost += 1;
+            }
+        }
+    }
+    stats.commit_latencies.sort();
+    stats.api_visibility_latencies.sort();
+    info!("Latency probe stats: {}", stats);
+    stats
+}
+
+/// Submits a marker transaction, and returns its commit and API visibility latencies.
+async fn measure_marker(
+    txn: SignedTransaction,
+    submit_client: RestClient,
+    visibility_clients: Vec<RestClient>,
+    timeout: Duration,
+) -> Result<(Duration, Duration)> {
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let hash = txn.clone().committed_hash();
+    submit_client.submit(&txn).await?;
+
+    wait_until_committed(&submit_client, hash, deadline).await?;
+    let commit_latency = start.elapsed();
+
+    try_join_all(
+        visibility_clients
+            .iter()
+            .map(|client| wait_until_committed(client, hash, deadline)),
+    )
+    .await?;
+    let api_visibility_latency = start.elapsed();
+
+    Ok((commit_latency, api_visibility_latency))
+}
+
+async fn wait_until_committed(
+    client: &RestClient,
+    hash: HashValue,
+    deadline: Instant,
+) -> Result<()> {
+    loop {
+        // Until the node has seen the transaction, it responds with a 404
+        if let Ok(response) = client.get_transaction_by_hash(hash).await {
+            let txn = response.into_inner();
+            if !txn.is_pending() {
+                ensure!(
+                    txn.success(),
+                    "Marker transaction {} failed: {}",
+                    hash,
+                    txn.vm_status()
+                );
+                return Ok(());
+            }
+        }
+        if Instant::now() > deadline {
+            bail!("Marker transaction {} wasn't committed in time", hash);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
@@ -25,6 +25,8 @@ pub use github::*;
 mod slack;
 pub use slack::*;
 
+pub mod latency_probe;
+
 pub mod success_criteria;
 
 pub mod test_utils;
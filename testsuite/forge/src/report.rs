@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::latency_probe::{LatencyProbeStats, ProbeLatency};
 use aptos_transaction_emitter_lib::emitter::stats::TxnStats;
 use serde::Serialize;
 use std::{fmt, time::Duration};
@@ -38,6 +39,24 @@ impl TestReport {
         self.text.push_str(&text);
     }
 
+    pub fn report_latency_probe_stats(&mut self, test_name: String, stats: &LatencyProbeStats) {
+        for (measure, name) in [
+            (ProbeLatency::Commit, "probe_commit"),
+            (ProbeLatency::ApiVisibility, "probe_api_visibility"),
+        ] {
+            for percentile in [50, 99] {
+                if let Some(latency) = stats.percentile(measure, percentile) {
+                    self.report_metric(
+                        test_name.clone(),
+                        format!("{}_p{}_latency", name, percentile),
+                        latency.as_millis() as f64,
+                    );
+                }
+            }
+        }
+        self.report_text(format!("{} latency probe : {}", test_name, stats));
+    }
+
     pub fn report_txn_stats(&mut self, test_name: String, stats: &TxnStats, window: Duration) {
         let submitted_txn = stats.submitted;
         let expired_txn = stats.expired;
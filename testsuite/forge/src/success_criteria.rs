@@ -7,6 +7,7 @@ use aptos_sdk::types::PeerId;
 use aptos_transaction_emitter_lib::{TxnStats, TxnStatsRate};
 use std::time::Duration;
 
+use crate::latency_probe::{LatencyProbeConfig, LatencyProbeStats, ProbeLatency};
 use crate::system_metrics::SystemMetricsThreshold;
 use crate::{Swarm, SwarmExt};
 
@@ -33,6 +34,9 @@ pub struct SuccessCriteria {
     // Maximum amount of CPU cores and memory bytes used by the nodes.
     system_metrics_threshold: Option<SystemMetricsThreshold>,
     chain_progress_check: Option<StateProgressThreshold>,
+    // Runs the latency probe during the test, if set.
+    latency_probe: Option<LatencyProbeConfig>,
+    probe_latency_thresholds: Vec<(Duration, LatencyType, ProbeLatency)>,
 }
 
 impl SuccessCriteria {
@@ -44,6 +48,8 @@ impl SuccessCriteria {
             wait_for_all_nodes_to_catchup: None,
             system_metrics_threshold: None,
             chain_progress_check: None,
+            latency_probe: None,
+            probe_latency_thresholds: Vec::new(),
         }
     }
 
@@ -72,6 +78,32 @@ impl SuccessCriteria {
             .push((Duration::from_secs_f32(threshold_s), latency_type));
         self
     }
+
+    pub fn add_latency_probe(mut self, config: LatencyProbeConfig) -> Self {
+        self.latency_probe = Some(config);
+        self
+    }
+
+    /// Adds a threshold on the latency measured by the latency probe, running the probe with the
+    /// default config unless one was added.
+    pub fn add_probe_latency_threshold(
+        mut self,
+        threshold_s: f32,
+        latency_type: LatencyType,
+        measure: ProbeLatency,
+    ) -> Self {
+        self.latency_probe.get_or_insert_with(Default::default);
+        self.probe_latency_thresholds.push((
+            Duration::from_secs_f32(threshold_s),
+            latency_type,
+            measure,
+        ));
+        self
+    }
+
+    pub fn latency_probe(&self) -> Option<&LatencyProbeConfig> {
+        self.latency_probe.as_ref()
+    }
 }
 
 pub struct SuccessCriteriaChecker {}
@@ -81,6 +113,7 @@ impl SuccessCriteriaChecker {
         success_criteria: &SuccessCriteria,
         swarm: &mut dyn Swarm,
         stats: &TxnStats,
+        probe_stats: Option<&LatencyProbeStats>,
         window: Duration,
         start_time: i64,
         end_time: i64,
@@ -100,6 +133,13 @@ impl SuccessCriteriaChecker {
 
         Self::check_latency(&success_criteria.latency_thresholds, &stats_rate)?;
 
+        if !success_criteria.probe_latency_thresholds.is_empty() {
+            Self::check_probe_latency(
+                &success_criteria.probe_latency_thresholds,
+                probe_stats.context("Latency probe didn't run")?,
+            )?;
+        }
+
         if let Some(timeout) = success_criteria.wait_for_all_nodes_to_catchup {
             swarm
                 .wait_for_all_nodes_to_catchup_to_next(timeout)
@@ -271,4 +311,36 @@ impl SuccessCriteriaChecker {
             Ok(())
         }
     }
+
+    pub fn check_probe_latency(
+        probe_latency_thresholds: &[(Duration, LatencyType, ProbeLatency)],
+        probe_stats: &LatencyProbeStats,
+    ) -> anyhow::Result<()> {
+        let mut failures = Vec::new();
+        for (latency_threshold, latency_type, measure) in probe_latency_thresholds {
+            match probe_stats.latency(*measure, latency_type) {
+                Some(latency) if latency <= *latency_threshold => {}
+                Some(latency) => failures.push(format!(
+                    "{:?} {:?} latency is {}s and exceeds limit of {}s",
+                    latency_type,
+                    measure,
+                    latency.as_secs_f32(),
+                    latency_threshold.as_secs_f32()
+                )),
+                None => failures.push(format!(
+                    "{:?} {:?} latency is unknown, no marker transaction made it",
+                    latency_type, measure
+                )),
+            }
+        }
+        if !failures.is_empty() {
+            bail!(
+                "Failed latency probe check ({}), for {:?}",
+                probe_stats,
+                failures
+            );
+        } else {
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,85 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_config::config::NodeConfig;
+use aptos_rest_client::Client as RestClient;
+use std::time::Duration;
+
+/// Failpoint delaying every synchronous write to AptosDB, i.e. every fsync.
+pub const SYNC_WRITE_FAILPOINT: &str = "schemadb::sync_write";
+/// Failpoint delaying every point read and iterator seek on AptosDB.
+pub const READ_FAILPOINT: &str = "schemadb::read";
+
+/// Artificial disk latencies injected into the AptosDB of a node, to reproduce storage-bound
+/// behavior such as commit stalls and backpressure. Requires the node to be built with the
+/// `failpoints` feature, as local swarm nodes are.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IoLatency {
+    pub sync_write: Duration,
+    pub read: Duration,
+}
+
+impl IoLatency {
+    pub fn new(sync_write: Duration, read: Duration) -> Self {
+        Self { sync_write, read }
+    }
+
+    /// Returns the failpoints and their actions. A zero latency turns the failpoint off.
+    pub fn failpoints(&self) -> Vec<(String, String)> {
+        [
+            (SYNC_WRITE_FAILPOINT, self.sync_write),
+            (READ_FAILPOINT, self.read),
+        ]
+        .into_iter()
+        .map(|(name, latency)| (name.to_string(), sleep_action(latency)))
+        .collect()
+    }
+
+    /// Injects the latencies from node startup on.
+    pub fn apply_to_config(&self, config: &mut NodeConfig) {
+        let failpoints = config.failpoints.get_or_insert_with(Default::default);
+        for (name, actions) in self.failpoints() {
+            failpoints.insert(name, actions);
+        }
+    }
+
+    /// Injects the latencies into a running node. The node must have `api.failpoints_enabled`.
+    pub async fn apply_to_node(&self, client: &RestClient) -> Result<()> {
+        for (name, actions) in self.failpoints() {
+            client.set_failpoint(name, actions).await?;
+        }
+        Ok(())
+    }
+}
+
+fn sleep_action(latency: Duration) -> String {
+    if latency.is_zero() {
+        "off".to_string()
+    } else {
+        format!("sleep({})", latency.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failpoints() {
+        let latency = IoLatency::new(Duration::from_millis(50), Duration::ZERO);
+        assert_eq!(
+            latency.failpoints(),
+            vec![
+                (SYNC_WRITE_FAILPOINT.to_string(), "sleep(50)".to_string()),
+                (READ_FAILPOINT.to_string(), "off".to_string()),
+            ]
+        );
+
+        let mut config = NodeConfig::default();
+        latency.apply_to_config(&mut config);
+        let failpoints = config.failpoints.unwrap();
+        assert_eq!(failpoints[SYNC_WRITE_FAILPOINT], "sleep(50)");
+        assert_eq!(failpoints[READ_FAILPOINT], "off");
+    }
+}
@@ -2,10 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::smoke_test_environment::SwarmBuilder;
+use crate::workspace_builder::get_bin;
 use aptos::account::create::DEFAULT_FUNDED_COINS;
 use aptos::common::types::GasOptions;
+use aptos::move_tool::MemberId;
 use aptos_crypto::{PrivateKey, ValidCryptoMaterialStringExt};
+use aptos_forge::Swarm;
 use aptos_keygen::KeyGen;
+use aptos_temppath::TempPath;
+use std::{process::Command, str::FromStr, time::Duration};
 
 #[tokio::test]
 async fn test_account_flow() {
@@ -131,3 +136,284 @@ async fn test_account_key_rotation() {
         .await
         .expect("New key should be able to transfer");
 }
+
+#[tokio::test]
+async fn test_multisig_flow() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(3)
+        .await;
+    let files = TempPath::new();
+    files.create_as_dir().unwrap();
+    let multisig_file = files.path().join("multisig.json");
+    let proposal_file = files.path().join("proposal.json");
+    let approval_files: Vec<_> = (0..3)
+        .map(|index| files.path().join(format!("approval-{}.json", index)))
+        .collect();
+
+    let multisig = cli
+        .create_multisig(&[0, 1, 2], 2, 10_000_000, multisig_file.clone())
+        .await
+        .unwrap();
+    assert!(multisig.transaction.success.unwrap());
+
+    let transfer_amount = 100;
+    cli.propose_multisig(
+        multisig_file,
+        MemberId::from_str("0x1::aptos_account::transfer").unwrap(),
+        vec![
+            &format!("address:{}", cli.account_id(1)),
+            &format!("u64:{}", transfer_amount),
+        ],
+        Some(GasOptions {
+            gas_unit_price: None,
+            max_gas: Some(10_000),
+        }),
+        proposal_file.clone(),
+    )
+    .await
+    .unwrap();
+
+    // A single approval doesn't reach the threshold
+    cli.approve_multisig(1, proposal_file.clone(), approval_files[1].clone())
+        .await
+        .unwrap();
+    cli.execute_multisig(proposal_file.clone(), vec![approval_files[1].clone()])
+        .await
+        .expect_err("One approval shouldn't be enough to execute");
+
+    cli.approve_multisig(2, proposal_file.clone(), approval_files[2].clone())
+        .await
+        .unwrap();
+    let balance_before = cli.account_balance_now(1).await.unwrap();
+    let summary = cli
+        .execute_multisig(proposal_file, approval_files[1..].to_vec())
+        .await
+        .unwrap();
+    assert!(summary.success.unwrap());
+    assert_eq!(
+        summary.sender.unwrap(),
+        multisig.multisig_address,
+        "The transaction should be sent by the multisig account"
+    );
+    cli.assert_account_balance_now(1, balance_before + transfer_amount)
+        .await;
+}
+
+#[tokio::test]
+async fn test_batch_submit() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+
+    let transfer_amount = 10;
+    let num_transfers = 5;
+    let receiver = cli.account_id(1).to_hex_literal();
+    let batch: String = (0..num_transfers)
+        .map(|_| {
+            format!(
+                "- function_id: 0x1::aptos_account::transfer\n  args: [\"address:{}\", \"u64:{}\"]\n",
+                receiver, transfer_amount
+            )
+        })
+        .collect();
+    let batch_file = TempPath::new();
+    batch_file.create_as_file().unwrap();
+    std::fs::write(batch_file.path(), batch).unwrap();
+
+    let results = cli
+        .batch_submit(0, batch_file.path().to_path_buf(), 2, None)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), num_transfers);
+    let mut sequence_number = None;
+    for (index, result) in results.iter().enumerate() {
+        let summary = result
+            .transaction
+            .as_ref()
+            .unwrap_or_else(|| panic!("Transaction {} failed: {:?}", index, result.error));
+        assert!(summary.success.unwrap());
+        let first_sequence_number =
+            *sequence_number.get_or_insert(summary.sequence_number.unwrap());
+        assert_eq!(
+            summary.sequence_number.unwrap(),
+            first_sequence_number + index as u64,
+            "The transactions should have consecutive sequence numbers"
+        );
+    }
+    cli.assert_account_balance_now(
+        1,
+        DEFAULT_FUNDED_COINS + transfer_amount * num_transfers as u64,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_named_network() {
+    let (swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(1)
+        .await;
+
+    // The CLI config is read from the working directory, so the CLI runs in a directory of its
+    // own, instead of sharing the config of the repository with the other tests
+    let config_dir = TempPath::new();
+    config_dir.create_as_dir().unwrap();
+    let aptos_cli = get_bin("aptos");
+    let run = |args: &[&str]| -> serde_json::Value {
+        let output = Command::new(aptos_cli.as_path())
+            .current_dir(config_dir.path())
+            .args(args)
+            .output()
+            .unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let chain_id = swarm.chain_id().id().to_string();
+    let network = run(&[
+        "config",
+        "set-network",
+        "--name",
+        "swarm",
+        "--rest-url",
+        cli.endpoint().as_str(),
+        "--faucet-url",
+        cli.faucet_endpoint().as_str(),
+        "--chain-id",
+        &chain_id,
+    ]);
+    assert_eq!(network["Result"]["chain_id"], swarm.chain_id().id());
+
+    // The swarm is selected by its name instead of `--url` and `--faucet-url`
+    let account = cli.account_id(0).to_hex_literal();
+    let funded = run(&[
+        "account",
+        "fund-with-faucet",
+        "--account",
+        &account,
+        "--network",
+        "swarm",
+    ]);
+    assert!(funded.get("Result").is_some(), "{}", funded);
+    cli.assert_account_balance_now(0, 2 * DEFAULT_FUNDED_COINS)
+        .await;
+
+    let resources = run(&[
+        "account",
+        "list",
+        "--account",
+        &account,
+        "--network",
+        "swarm",
+    ]);
+    assert!(!resources["Result"].as_array().unwrap().is_empty());
+
+    // A profile keeps the name of its network
+    let private_key = cli.private_key(0).to_encoded_string().unwrap();
+    let init = run(&[
+        "init",
+        "--network",
+        "swarm",
+        "--private-key",
+        &private_key,
+        "--assume-yes",
+    ]);
+    assert!(init.get("Result").is_some(), "{}", init);
+    let config = std::fs::read_to_string(config_dir.path().join(".aptos/config.yaml")).unwrap();
+    assert!(config.contains("network: swarm"), "{}", config);
+
+    // An unknown network is rejected
+    let unknown = run(&[
+        "account",
+        "list",
+        "--account",
+        &account,
+        "--network",
+        "unknown-swarm",
+    ]);
+    assert!(unknown.get("Error").is_some(), "{}", unknown);
+}
+
+#[tokio::test]
+async fn test_account_watch() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+
+    let transfer_amount = 100;
+    let coin_store = "0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>";
+    let (changes, transfer) = tokio::join!(cli.watch_account(1, Some(coin_store), 1, 60), async {
+        // Let the watch read the resource before it changes
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        cli.transfer_coins(0, 1, transfer_amount, None).await
+    });
+    transfer.unwrap();
+
+    let changes = changes.unwrap();
+    assert_eq!(changes.len(), 1);
+    let change = &changes[0];
+    assert_eq!(change.resource_type, coin_store);
+    assert_eq!(
+        change.resource.as_ref().unwrap()["coin"]["value"],
+        (DEFAULT_FUNDED_COINS + transfer_amount).to_string()
+    );
+    assert_eq!(change.events.len(), 1);
+    assert_eq!(change.events[0].data["amount"], transfer_amount.to_string());
+}
+
+#[tokio::test]
+async fn test_offline_signing() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+
+    let transfer_amount = 100;
+    let raw_file = TempPath::new();
+    let signed_file = TempPath::new();
+    let raw_transaction = cli
+        .build_transaction(
+            0,
+            MemberId::from_str("0x1::aptos_account::transfer").unwrap(),
+            vec![
+                &format!("address:{}", cli.account_id(1)),
+                &format!("u64:{}", transfer_amount),
+            ],
+            raw_file.path().to_path_buf(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(raw_transaction.sender, cli.account_id(0));
+    assert!(raw_transaction
+        .function
+        .ends_with("::aptos_account::transfer"));
+
+    // Only the owner of the sender's key can sign the transaction
+    cli.sign_transaction(
+        1,
+        raw_file.path().to_path_buf(),
+        signed_file.path().to_path_buf(),
+    )
+    .await
+    .unwrap();
+    cli.submit_signed_transaction(signed_file.path().to_path_buf())
+        .await
+        .expect_err("A transaction signed with another key should be rejected");
+
+    cli.sign_transaction(
+        0,
+        raw_file.path().to_path_buf(),
+        signed_file.path().to_path_buf(),
+    )
+    .await
+    .unwrap();
+    let transaction = cli
+        .submit_signed_transaction(signed_file.path().to_path_buf())
+        .await
+        .unwrap();
+    assert!(transaction.success.unwrap());
+    cli.assert_account_balance_now(1, DEFAULT_FUNDED_COINS + transfer_amount)
+        .await;
+}
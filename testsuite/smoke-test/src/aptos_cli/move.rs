@@ -143,6 +143,12 @@ async fn test_move_publish_flow() {
         Ok(response) => response,
         Err(err) => panic!("Should not have failed to download package {:?}", err),
     };
+    assert!(cli
+        .move_dir()
+        .join(PACKAGE_NAME)
+        .join("bytecode_modules")
+        .join("message.mv")
+        .exists());
 
     // Ensure the downloaded package can build. This is a test that the information is correctly
     // roundtripped.
@@ -57,7 +57,8 @@ async fn test_analyze_validators() {
     )
     .await;
 
-    cli.analyze_validator_performance(None, None).await.unwrap();
+    let report = cli.analyze_validator_performance(None, None).await.unwrap();
+    assert!(!report.epochs.is_empty());
 }
 
 #[tokio::test]
@@ -185,6 +186,7 @@ async fn test_onchain_config_change() {
             let inner = match genesis_config.consensus_config.clone() {
                 OnChainConsensusConfig::V1(inner) => inner,
                 OnChainConsensusConfig::V2(inner) => inner,
+                OnChainConsensusConfig::V3 { config, .. } => config,
             };
 
             let leader_reputation_type =
@@ -242,6 +244,7 @@ async fn test_onchain_config_change() {
     let inner = match current_consensus_config {
         OnChainConsensusConfig::V1(inner) => inner,
         OnChainConsensusConfig::V2(inner) => inner,
+        OnChainConsensusConfig::V3 { config, .. } => config,
     };
     let leader_reputation_type =
         if let ProposerElectionType::LeaderReputation(leader_reputation_type) =
@@ -1241,6 +1244,145 @@ async fn test_owner_create_and_delegate_flow() {
     );
 }
 
+#[tokio::test]
+async fn test_staking_contract_flow() {
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.allow_new_validators = true;
+            genesis_config.epoch_duration_secs = 5;
+            genesis_config.recurring_lockup_duration_secs = 10;
+            genesis_config.min_stake = 500000
+        }))
+        .build_with_cli(0)
+        .await;
+
+    let rest_client = swarm.validators().next().unwrap().rest_client();
+    let mut keygen = KeyGen::from_os_rng();
+
+    let owner_cli_index = cli
+        .create_cli_account_from_faucet(keygen.generate_ed25519_private_key(), None)
+        .await
+        .unwrap();
+
+    // faucet can make our root LocalAccount sequence number get out of sync.
+    swarm
+        .chain_info()
+        .resync_root_account_seq_num(&rest_client)
+        .await
+        .unwrap();
+
+    let operator_cli_index = cli
+        .create_cli_account(keygen.generate_ed25519_private_key(), owner_cli_index)
+        .await
+        .unwrap();
+
+    // The owner creates the stake pool through a staking contract, voting itself
+    let created = cli
+        .create_stake_pool(
+            owner_cli_index,
+            operator_cli_index,
+            owner_cli_index,
+            1000000,
+            10,
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.success, Some(true));
+
+    // Both the operator and the owner can request the commission of the operator
+    let requested = cli
+        .request_commission(operator_cli_index, owner_cli_index, operator_cli_index)
+        .await
+        .unwrap();
+    assert_eq!(requested.success, Some(true));
+    let requested = cli
+        .request_commission(owner_cli_index, owner_cli_index, operator_cli_index)
+        .await
+        .unwrap();
+    assert_eq!(requested.success, Some(true));
+}
+
+#[tokio::test]
+async fn test_auto_join_validator_set() {
+    let (mut swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_init_config(Arc::new(|_i, conf, genesis_stake_amount| {
+            // reduce timeout, as we will have dead node during rounds
+            conf.consensus.round_initial_timeout_ms = 200;
+            conf.consensus.quorum_store_poll_count = 4;
+            *genesis_stake_amount = 100000;
+        }))
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.allow_new_validators = true;
+            genesis_config.epoch_duration_secs = 5;
+            genesis_config.recurring_lockup_duration_secs = 10;
+            genesis_config.voting_duration_secs = 5;
+        }))
+        .build_with_cli(0)
+        .await;
+
+    let rest_client = swarm.validators().next().unwrap().rest_client();
+
+    let mut keygen = KeyGen::from_os_rng();
+    let (validator_cli_index, keys) =
+        init_validator_account(&mut cli, &mut keygen, Some(DEFAULT_FUNDED_COINS * 3)).await;
+
+    // faucet can make our root LocalAccount sequence number get out of sync.
+    swarm
+        .chain_info()
+        .resync_root_account_seq_num(&rest_client)
+        .await
+        .unwrap();
+
+    let stake_coins = 7;
+    let summary = cli
+        .join_validator_set_auto(
+            validator_cli_index,
+            stake_coins,
+            keys.consensus_public_key(),
+            keys.consensus_proof_of_possession(),
+            HostAndPort {
+                host: dns_name("0.0.0.0"),
+                port: 1234,
+            },
+            keys.network_public_key(),
+            60,
+        )
+        .await
+        .unwrap();
+
+    // initialize validator, add stake, join validator set
+    assert_eq!(summary.transactions.len(), 3);
+    let gas_used: u64 = summary.transactions.into_iter().map(get_gas).sum();
+    assert_eq!(
+        get_validator_state(&cli, validator_cli_index).await,
+        ValidatorState::ACTIVE
+    );
+    cli.assert_account_balance_now(
+        validator_cli_index,
+        (3 * DEFAULT_FUNDED_COINS) - stake_coins - gas_used,
+    )
+    .await;
+
+    // a validator can't join twice
+    assert!(cli
+        .join_validator_set_auto(
+            validator_cli_index,
+            stake_coins,
+            keys.consensus_public_key(),
+            keys.consensus_proof_of_possession(),
+            HostAndPort {
+                host: dns_name("0.0.0.0"),
+                port: 1234,
+            },
+            keys.network_public_key(),
+            0,
+        )
+        .await
+        .is_err());
+}
+
 fn dns_name(addr: &str) -> DnsName {
     DnsName::try_from(addr.to_string()).unwrap()
 }
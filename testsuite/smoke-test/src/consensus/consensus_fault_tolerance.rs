@@ -17,7 +17,8 @@ use aptos_forge::{
     test_utils::consensus_utils::{
         no_failure_injection, test_consensus_fault_tolerance, FailPointFailureInjection, NodeState,
     },
-    LocalSwarm, Swarm, SwarmExt,
+    GroupNetworkDelay, LocalSwarm, NodeExt, Swarm, SwarmChaos, SwarmExt, SwarmNetworkDelay,
+    SwarmNetworkLoss, SwarmNetworkPartition,
 };
 use aptos_logger::info;
 use rand::{self, rngs::SmallRng, Rng, SeedableRng};
@@ -441,3 +442,66 @@ async fn test_alternating_having_consensus() {
     )
     .await;
 }
+
+async fn assert_progress(swarm: &LocalSwarm, validator_index: usize, duration: Duration) {
+    let client = swarm
+        .validators()
+        .nth(validator_index)
+        .unwrap()
+        .rest_client();
+    let start = client.get_ledger_information().await.unwrap().into_inner();
+    tokio::time::sleep(duration).await;
+    let end = client.get_ledger_information().await.unwrap().into_inner();
+    assert!(
+        end.version > start.version,
+        "no progress on validator {}, stuck at version {}",
+        validator_index,
+        start.version
+    );
+}
+
+// The chaos proxies run on the runtime of the test, next to it
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_liveness_under_network_chaos() {
+    let num_validators = 4;
+    let mut swarm = create_swarm(num_validators, 1).await;
+    let validators: Vec<_> = swarm.validators().map(|v| v.peer_id()).collect();
+
+    // Degraded links between all the validators
+    swarm
+        .inject_chaos(SwarmChaos::Delay(SwarmNetworkDelay {
+            group_network_delays: vec![GroupNetworkDelay {
+                name: "all-to-all".to_string(),
+                source_nodes: validators.clone(),
+                target_nodes: validators.clone(),
+                latency_ms: 100,
+                jitter_ms: 20,
+                correlation_percentage: 0,
+            }],
+        }))
+        .unwrap();
+    let loss = SwarmChaos::Loss(SwarmNetworkLoss {
+        loss_percentage: 5,
+        correlation_percentage: 50,
+    });
+    swarm.inject_chaos(loss.clone()).unwrap();
+    swarm.wait_all_alive(Duration::from_secs(60)).await.unwrap();
+    assert_progress(&swarm, 0, Duration::from_secs(10)).await;
+
+    // The remaining 3 validators keep making progress without the first one
+    swarm.remove_chaos(loss).unwrap();
+    swarm
+        .inject_chaos(SwarmChaos::Partition(SwarmNetworkPartition {
+            partition_percentage: 25,
+        }))
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_progress(&swarm, 1, Duration::from_secs(10)).await;
+
+    // Once healed, the partitioned validator catches up
+    swarm.remove_all_chaos().unwrap();
+    swarm
+        .wait_for_all_nodes_to_catchup_to_next(Duration::from_secs(60))
+        .await
+        .unwrap();
+}
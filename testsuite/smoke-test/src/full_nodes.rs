@@ -111,7 +111,7 @@ async fn test_vfn_failover() {
     let mut vfn_config = NodeConfig::default_for_validator_full_node();
     vfn_config.mempool.default_failovers = 0;
     let mut swarm = SwarmBuilder::new_local(4)
-        .with_num_fullnodes(4)
+        .with_vfns(4)
         .with_aptos()
         .with_vfn_config(vfn_config)
         .build()
@@ -316,7 +316,7 @@ fn add_node_to_seeds(
 
 async fn local_swarm_with_fullnodes(num_validators: usize, num_fullnodes: usize) -> LocalSwarm {
     SwarmBuilder::new_local(num_validators)
-        .with_num_fullnodes(num_fullnodes)
+        .with_vfns(num_fullnodes)
         .with_aptos()
         .build()
         .await
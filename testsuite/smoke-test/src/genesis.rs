@@ -11,13 +11,14 @@ use crate::{
 use anyhow::anyhow;
 use aptos_config::config::NodeConfig;
 use aptos_forge::{get_highest_synced_version, LocalNode, Node, NodeExt, SwarmExt, Validator};
+use aptos_genesis::presets::NetworkConfigs;
 use aptos_logger::prelude::*;
 use aptos_temppath::TempPath;
-use aptos_types::{transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{on_chain_config::FeatureFlag, transaction::Transaction, waypoint::Waypoint};
 use move_core_types::language_storage::CORE_CODE_ADDRESS;
 use regex::Regex;
 use std::time::Instant;
-use std::{fs, process::Command, str::FromStr, time::Duration};
+use std::{fs, process::Command, str::FromStr, sync::Arc, time::Duration};
 
 fn update_node_config_restart(validator: &mut LocalNode, mut config: NodeConfig) {
     validator.stop();
@@ -268,3 +269,44 @@ fn parse_waypoint(db_bootstrapper_output: &str) -> Waypoint {
         .ok_or_else(|| anyhow!("Failed to parse aptos-db-bootstrapper output."));
     Waypoint::from_str(waypoint.unwrap()[1].into()).unwrap()
 }
+
+#[tokio::test]
+async fn test_genesis_with_network_configs() {
+    let swarm = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config
+                .initial_features
+                .push(FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES);
+            for (name, value) in &mut genesis_config.gas_schedule.entries {
+                if name == "txn.max_transaction_size_in_bytes" {
+                    *value = 32 * 1024;
+                }
+            }
+        }))
+        .build()
+        .await;
+    let network_configs = NetworkConfigs::fetch(&swarm.validators().next().unwrap().rest_client())
+        .await
+        .unwrap();
+    assert!(network_configs
+        .features
+        .contains(&FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES));
+    assert!(network_configs
+        .gas_schedule
+        .entries
+        .contains(&("txn.max_transaction_size_in_bytes".to_string(), 32 * 1024)));
+
+    // A swarm started with the configs of another one has the same configs
+    let parity_swarm = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .with_network_configs(network_configs.clone())
+        .build()
+        .await;
+    assert_eq!(
+        NetworkConfigs::fetch(&parity_swarm.validators().next().unwrap().rest_client())
+            .await
+            .unwrap(),
+        network_configs
+    );
+}
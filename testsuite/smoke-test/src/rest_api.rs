@@ -12,6 +12,7 @@ use aptos_types::account_config::{AccountResource, CORE_CODE_ADDRESS};
 use aptos_types::on_chain_config::GasScheduleV2;
 use aptos_types::transaction::authenticator::AuthenticationKey;
 use aptos_types::transaction::{SignedTransaction, Transaction};
+use futures::{StreamExt, TryStreamExt};
 use std::convert::TryFrom;
 use std::str::FromStr;
 
@@ -64,6 +65,48 @@ async fn test_basic_client() {
     info.client().get_transactions(None, None).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_pagination_streams() {
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+    let mut info = swarm.aptos_public_info();
+
+    let mut account1 = info.create_and_fund_user_account(10_000_000).await.unwrap();
+    let account2 = info.create_and_fund_user_account(10_000_000).await.unwrap();
+    for _ in 0..3 {
+        info.transfer(&mut account1, &account2, 1).await.unwrap();
+    }
+
+    // Pages of 2 transactions, so the stream has to follow the cursor to the second page
+    let client = info.client();
+    let transactions: Vec<_> = client
+        .account_transactions_stream(account1.address(), None, Some(2))
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(transactions.len(), 3);
+    let expected = client
+        .get_account_transactions(account1.address(), Some(0), Some(3))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(transactions, expected);
+
+    let events: Vec<_> = client
+        .events_stream(
+            AccountAddress::ONE,
+            "0x1::block::BlockResource",
+            "new_block_events",
+            None,
+            Some(2),
+        )
+        .take(5)
+        .try_collect()
+        .await
+        .unwrap();
+    let sequence_numbers: Vec<_> = events.iter().map(|event| event.sequence_number.0).collect();
+    assert_eq!(sequence_numbers, vec![0, 1, 2, 3, 4]);
+}
+
 // Test needs to be fixed to estimate over a longer period of time / probably needs an adjustable window
 // to test
 #[ignore]
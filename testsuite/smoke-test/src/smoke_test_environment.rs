@@ -1,34 +1,59 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::test_utils::{assert_balance, create_and_fund_account, transfer_coins};
 use aptos::test::CliTestFramework;
 use aptos_config::config::NodeConfig;
 use aptos_config::{keys::ConfigKey, utils::get_available_port};
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_faucet::FaucetArgs;
-use aptos_forge::{ActiveNodesGuard, Node};
-use aptos_forge::{Factory, LocalFactory, LocalSwarm};
+use aptos_forge::{ActiveNodesGuard, Node, NodeExt, Swarm};
+use aptos_forge::{Factory, LocalFactory, LocalSwarm, LocalVersion, SwarmSnapshot};
 use aptos_framework::ReleaseBundle;
-use aptos_genesis::builder::{InitConfigFn, InitGenesisConfigFn};
+use aptos_genesis::{
+    builder::{InitConfigFn, InitGenesisConfigFn},
+    presets::{GenesisPreset, NetworkConfigs},
+};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{account_config::aptos_test_root_address, chain_id::ChainId};
 use once_cell::sync::Lazy;
-use rand::rngs::OsRng;
-use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
+use rand::{
+    rngs::{OsRng, StdRng},
+    RngCore, SeedableRng,
+};
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tempfile::TempDir;
 use tokio::task::JoinHandle;
 
 const SWARM_BUILD_NUM_RETRIES: u8 = 3;
 
+/// Seeds the swarms built without [`SwarmBuilder::with_seed`], to reproduce a failed run
+const SEED_ENV_VAR: &str = "SMOKE_TEST_SEED";
+
 #[derive(Clone)]
 pub struct SwarmBuilder {
     local: bool,
     num_validators: NonZeroUsize,
-    num_fullnodes: usize,
+    num_vfns: usize,
+    num_public_fullnodes: usize,
     genesis_framework: Option<ReleaseBundle>,
     init_config: Option<InitConfigFn>,
+    /// Config functions applied to the validators with the given indices only
+    node_init_configs: Vec<(Vec<usize>, InitConfigFn)>,
     vfn_config: Option<NodeConfig>,
     init_genesis_config: Option<InitGenesisConfigFn>,
+    genesis_preset: Option<GenesisPreset>,
+    network_configs: Option<NetworkConfigs>,
+    versions: Vec<String>,
+    seed: Option<u64>,
+    time_compression: u64,
+    snapshot: Option<PathBuf>,
 }
 
 impl SwarmBuilder {
@@ -36,11 +61,30 @@ impl SwarmBuilder {
         Self {
             local,
             num_validators: NonZeroUsize::new(num_validators).unwrap(),
-            num_fullnodes: 0,
+            num_vfns: 0,
+            num_public_fullnodes: 0,
             genesis_framework: None,
             init_config: None,
+            node_init_configs: vec![],
             vfn_config: None,
             init_genesis_config: None,
+            genesis_preset: None,
+            network_configs: None,
+            versions: vec![],
+            seed: None,
+            time_compression: 1,
+            snapshot: None,
+        }
+    }
+
+    /// Restores the swarm saved by `Swarm::snapshot` to the given directory instead of running
+    /// genesis. Only the versions of the builder are used, the latest of which runs all the nodes.
+    /// The networks of the restored nodes listen on the ports of the snapshotted swarm, so tests
+    /// restoring the same snapshot can't run concurrently.
+    pub fn from_snapshot(snapshot_dir: &Path) -> Self {
+        Self {
+            snapshot: Some(snapshot_dir.to_path_buf()),
+            ..Self::new_local(1)
         }
     }
 
@@ -63,6 +107,15 @@ impl SwarmBuilder {
         self
     }
 
+    /// Applies `init_config` to the validators with the given indices only, e.g., to enable a
+    /// failpoint on a single validator while the others keep their defaults. The overrides are
+    /// applied in the order they are added, after the config function of
+    /// [`Self::with_init_config`].
+    pub fn with_init_config_for(mut self, indices: &[usize], init_config: InitConfigFn) -> Self {
+        self.node_init_configs.push((indices.to_vec(), init_config));
+        self
+    }
+
     pub fn with_vfn_config(mut self, config: NodeConfig) -> Self {
         self.vfn_config = Some(config);
         self
@@ -73,8 +126,61 @@ impl SwarmBuilder {
         self
     }
 
-    pub fn with_num_fullnodes(mut self, num_fullnodes: usize) -> Self {
-        self.num_fullnodes = num_fullnodes;
+    /// Starts the swarm with the on-chain configs the given public network currently runs with,
+    /// which are read from the network when the swarm is built. The genesis config function (if
+    /// any) is applied on top of the preset.
+    pub fn with_genesis_preset(mut self, genesis_preset: GenesisPreset) -> Self {
+        self.genesis_preset = Some(genesis_preset);
+        self
+    }
+
+    /// Starts the swarm with the given on-chain configs, e.g., the ones of another swarm. The
+    /// genesis config function (if any) is applied on top of them.
+    pub fn with_network_configs(mut self, network_configs: NetworkConfigs) -> Self {
+        self.network_configs = Some(network_configs);
+        self
+    }
+
+    /// Splits the validators evenly between aptos-node built at the given git revisions, in order
+    /// of their indices, e.g., `&["aptos-node-v1.2.0", WORKSPACE_REVISION]` runs the first half of
+    /// the validators with a release and the others with the build of the workspace. Revisions
+    /// are only built once, and the workspace build is used by default.
+    ///
+    /// Unless another framework is given, genesis uses the released framework of testnet instead
+    /// of the one of the workspace, which older releases may not be able to run.
+    pub fn with_versions(mut self, revisions: &[&str]) -> Self {
+        self.versions = revisions.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    /// Seeds the keys, the ports of the nodes and the chaos injected in the swarm, so that a run
+    /// can be reproduced. Swarms built without a seed draw one for their keys and chaos, which is
+    /// printed to be set in `SMOKE_TEST_SEED` when rerunning a failed test. Their ports aren't
+    /// seeded, since concurrent tests with the same seed would try the same ports.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Divides the epoch, voting and lockup durations of the genesis config by `factor`, after
+    /// the preset and the genesis config function are applied, so that their ratios are kept
+    pub fn with_time_compression(mut self, factor: u64) -> Self {
+        assert!(factor > 0, "The time compression factor has to be positive");
+        self.time_compression = factor;
+        self
+    }
+
+    /// Attaches a validator fullnode to each of the first `num_vfns` validators
+    pub fn with_vfns(mut self, num_vfns: usize) -> Self {
+        self.num_vfns = num_vfns;
+        self
+    }
+
+    /// Adds public fullnodes running the latest version, which discover the VFNs through the
+    /// validator set. These are returned by `Swarm::full_nodes` along with the VFNs, from which
+    /// they can be told apart by their peer id not being the one of a validator.
+    pub fn with_public_fullnodes(mut self, num_public_fullnodes: usize) -> Self {
+        self.num_public_fullnodes = num_public_fullnodes;
         self
     }
 
@@ -85,34 +191,130 @@ impl SwarmBuilder {
         // TODO change to return Swarm trait
         // Add support for forge
         assert!(self.local);
+        anyhow::ensure!(
+            self.num_public_fullnodes == 0 || self.num_vfns > 0,
+            "Public fullnodes can only connect to the swarm through VFNs"
+        );
         static FACTORY: Lazy<LocalFactory> = Lazy::new(|| LocalFactory::from_workspace().unwrap());
-        let version = FACTORY.versions().max().unwrap();
+        let mixed_versions_factory;
+        let (factory, versions) = if self.versions.is_empty() {
+            (&*FACTORY, vec![FACTORY.versions().max().unwrap()])
+        } else {
+            let local_versions = self
+                .versions
+                .iter()
+                .enumerate()
+                .map(|(index, revision)| LocalVersion::from_revision(index, revision))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let versions = local_versions.iter().map(LocalVersion::version).collect();
+            mixed_versions_factory = LocalFactory::new(
+                local_versions
+                    .into_iter()
+                    .map(|version| (version.version(), version))
+                    .collect(),
+            );
+            (&mixed_versions_factory, versions)
+        };
         info!("Node finished compiling");
 
-        let slots = self.num_validators.get() * 2;
+        let snapshot = self
+            .snapshot
+            .as_deref()
+            .map(|dir| SwarmSnapshot::load(dir).map(|snapshot| (dir, snapshot)))
+            .transpose()?;
+        let num_validators = snapshot
+            .as_ref()
+            .map_or(self.num_validators.get(), |(_, snapshot)| {
+                snapshot.num_validators()
+            });
+        let slots = num_validators * 2;
 
         static ACTIVE_NODES: Lazy<Arc<Mutex<usize>>> = Lazy::new(|| Arc::new(Mutex::new(0)));
         let guard = ActiveNodesGuard::grab(slots, ACTIVE_NODES.clone()).await;
 
-        let builder = self.clone();
+        if let Some((dir, snapshot)) = snapshot {
+            return factory
+                .restore_swarm(dir, snapshot, versions.iter().max().unwrap(), guard)
+                .await;
+        }
+
+        let explicit_seed = match self.seed {
+            Some(seed) => Some(seed),
+            None => std::env::var(SEED_ENV_VAR)
+                .ok()
+                .map(|seed| seed.parse::<u64>())
+                .transpose()?,
+        };
+        let seed = explicit_seed.unwrap_or_else(|| OsRng.next_u64());
+        // Printed with the captured output of failed tests
+        println!(
+            "Building the swarm with seed {0}, set {1}={0} to reproduce it",
+            seed, SEED_ENV_VAR
+        );
+
+        let mut builder = self.clone();
+        if !self.versions.is_empty() && builder.genesis_framework.is_none() {
+            builder.genesis_framework = Some(aptos_framework::testnet_release_bundle().clone());
+        }
         let init_genesis_config = builder.init_genesis_config;
-        FACTORY
-            .new_swarm_with_version(
-                OsRng,
+        let network_configs = match builder.genesis_preset {
+            Some(genesis_preset) => Some(genesis_preset.fetch_network_configs().await?),
+            None => builder.network_configs,
+        };
+        let time_compression = builder.time_compression;
+        let init_config: Option<InitConfigFn> = if builder.node_init_configs.is_empty() {
+            builder.init_config
+        } else {
+            let base_init_config = builder.init_config;
+            let node_init_configs = builder.node_init_configs;
+            Some(Arc::new(move |index, config, genesis_stake_amount| {
+                if let Some(init_config) = &base_init_config {
+                    (init_config)(index, config, genesis_stake_amount);
+                }
+                for (indices, init_config) in &node_init_configs {
+                    if indices.contains(&index) {
+                        (init_config)(index, config, genesis_stake_amount);
+                    }
+                }
+            }))
+        };
+        let mut swarm = factory
+            .new_swarm_with_versions(
+                StdRng::seed_from_u64(seed),
                 builder.num_validators,
-                builder.num_fullnodes,
-                &version,
+                builder.num_vfns,
+                &versions,
                 builder.genesis_framework,
-                builder.init_config,
+                init_config,
                 builder.vfn_config,
                 Some(Arc::new(move |genesis_config| {
+                    if let Some(network_configs) = &network_configs {
+                        network_configs.apply(genesis_config);
+                    }
                     if let Some(init_genesis_config) = &init_genesis_config {
                         (init_genesis_config)(genesis_config);
                     }
+                    for duration_secs in [
+                        &mut genesis_config.epoch_duration_secs,
+                        &mut genesis_config.voting_duration_secs,
+                        &mut genesis_config.recurring_lockup_duration_secs,
+                    ] {
+                        *duration_secs = (*duration_secs / time_compression).max(1);
+                    }
                 })),
+                explicit_seed,
                 guard,
             )
-            .await
+            .await?;
+
+        if builder.num_public_fullnodes > 0 {
+            let version = versions.iter().max().unwrap();
+            for _ in 0..builder.num_public_fullnodes {
+                swarm.add_full_node(version, NodeConfig::default_for_public_full_node())?;
+            }
+            swarm.wait_all_alive(Duration::from_secs(60)).await?;
+        }
+        Ok(swarm)
     }
 
     // Gas is not enabled with this setup, it's enabled via forge instance.
@@ -174,6 +376,43 @@ pub async fn new_local_swarm_with_aptos(num_validators: usize) -> LocalSwarm {
         .await
 }
 
+#[tokio::test]
+async fn test_swarm_snapshot() {
+    let snapshot_dir = TempDir::new().unwrap();
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+    let mut account = create_and_fund_account(&mut swarm, 100).await;
+    swarm.snapshot(snapshot_dir.path()).await.unwrap();
+    let version = swarm
+        .aptos_public_info()
+        .client()
+        .get_ledger_information()
+        .await
+        .unwrap()
+        .into_inner()
+        .version;
+    drop(swarm);
+
+    // The restored swarm has the state of the snapshot, and keeps making progress
+    let mut swarm = SwarmBuilder::from_snapshot(snapshot_dir.path())
+        .build()
+        .await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    assert_balance(&client, &account, 100).await;
+    let receiver = create_and_fund_account(&mut swarm, 10).await;
+    let transaction_factory = swarm.chain_info().transaction_factory();
+    transfer_coins(&client, &transaction_factory, &mut account, &receiver, 10).await;
+    assert_balance(&client, &receiver, 20).await;
+    assert!(
+        client
+            .get_ledger_information()
+            .await
+            .unwrap()
+            .into_inner()
+            .version
+            > version
+    );
+}
+
 #[tokio::test]
 async fn test_prevent_starting_nodes_twice() {
     // Create a validator swarm of 1 validator node
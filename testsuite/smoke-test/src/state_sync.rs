@@ -341,6 +341,39 @@ async fn test_validator_bootstrap_state_snapshot() {
     assert_ne!(ledger_information.inner().oldest_ledger_version, 0);
 }
 
+#[tokio::test]
+async fn test_validator_bootstrap_state_snapshot_single_validator() {
+    // Create a swarm of 4 validators where only the tested one uses snapshot syncing
+    let validator_index_to_test = 1;
+    let mut swarm = SwarmBuilder::new_local(4)
+        .with_aptos()
+        .with_init_config_for(
+            &[validator_index_to_test],
+            Arc::new(|_, config, _| {
+                config.state_sync.state_sync_driver.bootstrapping_mode =
+                    BootstrappingMode::DownloadLatestStates;
+            }),
+        )
+        .build()
+        .await;
+
+    // Test the ability of the validator to sync from validators using the defaults
+    test_validator_sync(&mut swarm, validator_index_to_test).await;
+
+    // Verify that only the bootstrapped validator no longer has the genesis transaction
+    for (index, validator) in swarm.validators().enumerate() {
+        let ledger_information = validator
+            .rest_client()
+            .get_ledger_information()
+            .await
+            .unwrap();
+        assert_eq!(
+            ledger_information.inner().oldest_ledger_version == 0,
+            index != validator_index_to_test
+        );
+    }
+}
+
 #[ignore] // We ignore this test because it takes a long time. But, it works, so it shouldn't be removed.
 #[tokio::test]
 async fn test_validator_bootstrap_outputs_network_limit() {
@@ -12,7 +12,7 @@ use crate::{
 };
 use anyhow::{bail, Result};
 use aptos_backup_cli::metadata::view::BackupStorageState;
-use aptos_forge::{reconfig, NodeExt, Swarm, SwarmExt};
+use aptos_forge::{reconfig, test_utils::storage_utils::IoLatency, NodeExt, Swarm, SwarmExt};
 use aptos_logger::info;
 use aptos_temppath::TempPath;
 use aptos_types::{transaction::Version, waypoint::Waypoint};
@@ -20,6 +20,7 @@ use std::{
     fs,
     path::Path,
     process::Command,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -172,6 +173,71 @@ async fn test_db_restore() {
     info!("6. Done");
 }
 
+#[tokio::test]
+async fn test_slow_disk() {
+    // A single validator with slow fsyncs has to keep up through backpressure
+    let mut swarm = SwarmBuilder::new_local(4)
+        .with_aptos()
+        .with_init_config(Arc::new(|index, config, _| {
+            config.api.failpoints_enabled = true;
+            if index == 0 {
+                IoLatency::new(Duration::from_millis(100), Duration::ZERO).apply_to_config(config);
+            }
+        }))
+        .build()
+        .await;
+    let transaction_factory = swarm.chain_info().transaction_factory();
+    let validator_peer_ids = swarm.validators().map(|v| v.peer_id()).collect::<Vec<_>>();
+    let client_0 = swarm
+        .validator(validator_peer_ids[0])
+        .unwrap()
+        .rest_client();
+
+    let mut account_0 = create_and_fund_account(&mut swarm, 1000).await;
+    let account_1 = create_and_fund_account(&mut swarm, 1000).await;
+    for _ in 0..5 {
+        transfer_coins(
+            &client_0,
+            &transaction_factory,
+            &mut account_0,
+            &account_1,
+            10,
+        )
+        .await;
+    }
+
+    // Slow down the disks of all validators, so the commits of the whole network stall
+    let latency = IoLatency::new(Duration::from_millis(50), Duration::from_millis(1));
+    for validator in swarm.validators() {
+        latency
+            .apply_to_node(&validator.rest_client())
+            .await
+            .unwrap();
+    }
+    for _ in 0..5 {
+        transfer_coins(
+            &client_0,
+            &transaction_factory,
+            &mut account_0,
+            &account_1,
+            10,
+        )
+        .await;
+    }
+
+    for validator in swarm.validators() {
+        IoLatency::default()
+            .apply_to_node(&validator.rest_client())
+            .await
+            .unwrap();
+    }
+    swarm
+        .wait_for_all_nodes_to_catchup(Duration::from_secs(MAX_WAIT_SECS))
+        .await
+        .unwrap();
+    assert_balance(&client_0, &account_1, 1100).await;
+}
+
 fn db_backup_verify(backup_path: &Path, trusted_waypoints: &[Waypoint]) {
     let now = Instant::now();
     let bin_path = workspace_builder::get_bin("db-backup-verify");
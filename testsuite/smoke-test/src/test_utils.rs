@@ -1,14 +1,40 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos::{move_tool::MemberId, test::CliTestFramework};
 use aptos_cached_packages::aptos_stdlib;
-use aptos_forge::{reconfig, LocalSwarm, NodeExt, Swarm};
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode, NodeConfig};
+use aptos_crypto::HashValue;
+use aptos_forge::{reconfig, LocalSwarm, Node, NodeExt, Swarm};
+use aptos_release_builder::verification::{is_voting_closed, verify_multi_step_proposal_chain};
 use aptos_rest_client::Client as RestClient;
 use aptos_sdk::{
     transaction_builder::TransactionFactory,
-    types::{transaction::SignedTransaction, LocalAccount},
+    types::{transaction::SignedTransaction, LocalAccount, PeerId},
 };
+use aptos_temppath::TempPath;
+use aptos_types::account_config::CORE_CODE_ADDRESS;
 use rand::random;
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Maximum time voting on a governance proposal is waited for to close
+const MAX_VOTING_SECS: u64 = 300;
+
+/// Timing of a fullnode joining a swarm, see `bootstrap_new_fullnode`
+#[derive(Debug)]
+pub struct BootstrapStats {
+    pub peer_id: PeerId,
+    pub bootstrapping_mode: BootstrappingMode,
+    /// Version of the validators when the fullnode was added
+    pub target_version: u64,
+    /// Time from the start of the fullnode until its API was healthy
+    pub time_to_healthy: Duration,
+    /// Time from the start of the fullnode until it synced the target version
+    pub time_to_target_version: Duration,
+}
 
 pub async fn create_and_fund_account(swarm: &'_ mut dyn Swarm, amount: u64) -> LocalAccount {
     let mut info = swarm.aptos_public_info();
@@ -135,3 +161,199 @@ pub async fn check_create_mint_transfer_node(swarm: &mut LocalSwarm, idx: usize)
     let account_2 = info.create_and_fund_user_account(15).await.unwrap();
     assert_balance(&client, &account_2, 15).await;
 }
+
+/// Returns the hash a governance proposal executed by the given scripts is created with. The
+/// steps of a multi-step proposal are checked to be chained by the verifier of the release
+/// builder, which hashes their bytecode as the chain does.
+pub fn proposal_execution_hash(cli: &CliTestFramework, scripts: &[String]) -> HashValue {
+    if let [script] = scripts {
+        return cli.compile_script(script).unwrap().1;
+    }
+    let scripts_dir = TempPath::new();
+    scripts_dir.create_as_dir().unwrap();
+    let script_paths: Vec<_> = scripts
+        .iter()
+        .enumerate()
+        .map(|(idx, script)| {
+            let script_path = scripts_dir.path().join(format!("{}-step.move", idx));
+            std::fs::write(&script_path, script).unwrap();
+            script_path
+        })
+        .collect();
+    verify_multi_step_proposal_chain(&script_paths).unwrap()[0]
+}
+
+/// Creates a governance proposal executed by the given scripts from the first voter, and has all
+/// the voters vote for it with their stake pools. The proposal is multi-step if there are several
+/// scripts. The voters are validators of the swarm, which are added to the CLI, funded and have
+/// their lockup extended beyond the voting period. Returns the id of the proposal and the CLI
+/// index of the proposer, once voting is closed.
+pub async fn create_and_approve_governance_proposal(
+    cli: &mut CliTestFramework,
+    swarm: &LocalSwarm,
+    scripts: &[String],
+    voters: &[PeerId],
+) -> (u64, usize) {
+    let voter_indices: Vec<_> = voters
+        .iter()
+        .map(|voter| {
+            let private_key = swarm
+                .validator(*voter)
+                .unwrap()
+                .account_private_key()
+                .as_ref()
+                .unwrap()
+                .private_key();
+            cli.add_account_to_cli(private_key)
+        })
+        .collect();
+    for index in &voter_indices {
+        cli.fund_account(*index, None).await.unwrap();
+        cli.increase_lockup(*index).await.unwrap();
+    }
+    let proposer_index = voter_indices[0];
+
+    let client = swarm.validators().next().unwrap().rest_client();
+    let proposal_id = next_governance_proposal_id(&client).await;
+    let execution_hash = proposal_execution_hash(cli, scripts);
+    cli.run_function(
+        proposer_index,
+        None,
+        MemberId::from_str("0x1::aptos_governance::create_proposal_v2").unwrap(),
+        vec![
+            &format!("address:{}", cli.account_id(proposer_index)),
+            &format!("hex:{}", execution_hash.to_hex()),
+            &format!("hex:{}", hex::encode("https://aptos.dev")),
+            &format!("hex:{}", hex::encode(HashValue::zero().to_hex())),
+            &format!("bool:{}", scripts.len() > 1),
+        ],
+        vec![],
+    )
+    .await
+    .unwrap();
+    for index in &voter_indices {
+        cli.vote(*index, proposal_id, vec![cli.account_id(*index)], true)
+            .await
+            .unwrap();
+    }
+
+    // Voting closes at the end of the voting period, or earlier once a majority voted
+    let deadline = Instant::now() + Duration::from_secs(MAX_VOTING_SECS);
+    while !is_voting_closed(&client, proposal_id).await.unwrap() {
+        assert!(
+            Instant::now() < deadline,
+            "Voting on proposal {} didn't close",
+            proposal_id
+        );
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    (proposal_id, proposer_index)
+}
+
+/// Creates a governance proposal executed by the given scripts, has the voters approve it, and
+/// executes the scripts in order. Each script but the last one resolves the proposal with the hash
+/// of the next one. Returns the id of the proposal.
+pub async fn execute_governance_proposals(
+    cli: &mut CliTestFramework,
+    swarm: &LocalSwarm,
+    scripts: &[String],
+    voters: &[PeerId],
+) -> u64 {
+    let (proposal_id, proposer_index) =
+        create_and_approve_governance_proposal(cli, swarm, scripts, voters).await;
+    for script in scripts {
+        cli.execute_proposal(proposer_index, proposal_id, script)
+            .await
+            .unwrap();
+    }
+    proposal_id
+}
+
+/// Adds a public fullnode to the swarm, bootstrapping with the given mode, and waits for it to
+/// sync the current version of the validators. The fullnode connects to the VFNs of the swarm,
+/// which needs at least one.
+pub async fn bootstrap_new_fullnode(
+    swarm: &mut LocalSwarm,
+    bootstrapping_mode: BootstrappingMode,
+    timeout: Duration,
+) -> BootstrapStats {
+    assert!(
+        swarm.full_nodes().next().is_some(),
+        "A public fullnode can only join a swarm with VFNs"
+    );
+    let target_version = swarm
+        .validators()
+        .next()
+        .unwrap()
+        .rest_client()
+        .get_ledger_information()
+        .await
+        .unwrap()
+        .into_inner()
+        .version;
+
+    let mut config = NodeConfig::default_for_public_full_node();
+    let state_sync_driver = &mut config.state_sync.state_sync_driver;
+    state_sync_driver.bootstrapping_mode = bootstrapping_mode;
+    match bootstrapping_mode {
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
+            state_sync_driver.continuous_syncing_mode =
+                ContinuousSyncingMode::ApplyTransactionOutputs
+        }
+        BootstrappingMode::ExecuteTransactionsFromGenesis => {
+            state_sync_driver.continuous_syncing_mode = ContinuousSyncingMode::ExecuteTransactions
+        }
+        _ => {}
+    }
+
+    let start = Instant::now();
+    let deadline = start + timeout;
+    let version = swarm.versions().max().unwrap();
+    let peer_id = swarm.add_full_node(&version, config).unwrap();
+    let fullnode = swarm.fullnode_mut(peer_id).unwrap();
+    fullnode.wait_until_healthy(deadline).await.unwrap();
+    let time_to_healthy = start.elapsed();
+
+    let client = fullnode.rest_client();
+    loop {
+        if let Ok(ledger_information) = client.get_ledger_information().await {
+            if ledger_information.into_inner().version >= target_version {
+                break;
+            }
+        }
+        assert!(
+            Instant::now() < deadline,
+            "{} didn't sync version {} with {:?} within {:?}",
+            fullnode.name(),
+            target_version,
+            bootstrapping_mode,
+            timeout
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    BootstrapStats {
+        peer_id,
+        bootstrapping_mode,
+        target_version,
+        time_to_healthy,
+        time_to_target_version: start.elapsed(),
+    }
+}
+
+async fn next_governance_proposal_id(client: &RestClient) -> u64 {
+    client
+        .get_account_resource(
+            CORE_CODE_ADDRESS,
+            "0x1::voting::VotingForum<0x1::governance_proposal::GovernanceProposal>",
+        )
+        .await
+        .unwrap()
+        .into_inner()
+        .unwrap()
+        .data["next_proposal_id"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
@@ -2,131 +2,227 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    aptos::move_test_helpers, smoke_test_environment::SwarmBuilder,
-    test_utils::check_create_mint_transfer, workspace_builder, workspace_builder::workspace_root,
+    aptos::move_test_helpers,
+    smoke_test_environment::SwarmBuilder,
+    test_utils::{
+        bootstrap_new_fullnode, check_create_mint_transfer, create_and_approve_governance_proposal,
+        execute_governance_proposals,
+    },
 };
-use aptos_crypto::ValidCryptoMaterialStringExt;
-use aptos_forge::Swarm;
-use aptos_gas::{AptosGasParameters, GasQuantity, InitialGasSchedule, ToOnChainGasSchedule};
-use aptos_release_builder::components::{
-    feature_flags::{FeatureFlag, Features},
-    gas::generate_gas_upgrade_proposal,
+use aptos_config::config::BootstrappingMode;
+use aptos_crypto::{HashValue, PrivateKey};
+use aptos_forge::{
+    EmitJobMode, EmitJobRequest, Node, NodeExt, Swarm, SwarmExt, TxnEmitter, WorkloadProfile,
+    WORKSPACE_REVISION,
 };
-use aptos_temppath::TempPath;
-use std::fs;
-use std::process::Command;
+use aptos_gas::{AptosGasParameters, GasQuantity};
+use aptos_gas_testing::{
+    assert_gas_schedule_on_chain, gas_schedule_with, get_gas_schedule, update_gas_schedule,
+};
+use aptos_release_builder::{
+    components::{
+        feature_flags::{FeatureFlag, Features},
+        framework::release_packages,
+    },
+    validate::ComponentDifference,
+    verification::{approved_execution_hash, is_proposal_resolved, Difference},
+};
+use aptos_rest_client::Client;
+use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::CORE_CODE_ADDRESS,
+    on_chain_config::{ConsensusConfigV1, OnChainConsensusConfig, Version},
+    transaction::authenticator::AuthenticationKey,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+const VOTING_DURATION_SECS: u64 = 10;
+const MAX_CATCH_UP_SECS: u64 = 180;
+const CONSENSUS_ROUND_METRIC: &str = "aptos_consensus_current_round";
+const SYNCED_VERSION_METRIC: &str = "aptos_state_sync_version";
+const SYNCED_LABELS: &[(&str, &str)] = &[("type", "synced")];
+/// Branch of the last testnet release, for the validators not running the workspace build. It's
+/// the release of the framework bundle the swarm starts with, `with_aptos_testnet`.
+const PREVIOUS_RELEASE_REVISION: &str = "origin/testnet";
+/// How long the load is emitted before and after the upgrade
+const LOAD_PHASE_DURATION: Duration = Duration::from_secs(30);
 
 #[tokio::test]
 /// This test verifies the flow of aptos framework upgrade process.
 /// i.e: The network will be alive after applying the new aptos framework release.
 async fn test_upgrade_flow() {
-    // prebuild tools.
-    let aptos_cli = workspace_builder::get_bin("aptos");
-
     let num_nodes = 5;
-    let (mut env, _cli, _) = SwarmBuilder::new_local(num_nodes)
+    let (mut env, cli, _) = SwarmBuilder::new_local(num_nodes)
         .with_aptos_testnet()
+        .with_vfns(1)
+        .with_public_fullnodes(1)
         .build_with_cli(0)
         .await;
 
     let url = env.aptos_public_info().url().to_string();
-    let private_key = env
-        .aptos_public_info()
-        .root_account()
-        .private_key()
-        .to_encoded_string()
-        .unwrap();
-
-    // Bump the limit in gas schedule
-    // TODO: Replace this logic with aptos-gas
-    let mut gas_parameters = AptosGasParameters::initial();
-    gas_parameters.txn.max_transaction_size_in_bytes = GasQuantity::new(100_000_000);
-
-    let gas_schedule = aptos_types::on_chain_config::GasScheduleV2 {
-        feature_version: aptos_gas::LATEST_GAS_FEATURE_VERSION,
-        entries: gas_parameters.to_on_chain_gas_schedule(aptos_gas::LATEST_GAS_FEATURE_VERSION),
-    };
-
-    let (_, update_gas_script) = generate_gas_upgrade_proposal(&gas_schedule, true, "".to_owned())
-        .unwrap()
-        .pop()
-        .unwrap();
-
-    let gas_script_path = TempPath::new();
-    let mut gas_script_path = gas_script_path.path().to_path_buf();
-    gas_script_path.set_extension("move");
-    fs::write(gas_script_path.as_path(), update_gas_script).unwrap();
-
-    assert!(Command::new(aptos_cli.as_path())
-        .current_dir(workspace_root())
-        .args(&vec![
-            "move",
-            "run-script",
-            "--script-path",
-            gas_script_path.to_str().unwrap(),
-            "--sender-account",
-            "0xA550C18",
-            "--url",
-            url.as_str(),
-            "--private-key",
-            private_key.as_str(),
-            "--assume-yes",
-        ])
-        .output()
-        .unwrap()
-        .status
-        .success());
-    *env.aptos_public_info().root_account().sequence_number_mut() += 1;
-
-    let upgrade_scripts_folder = TempPath::new();
-    upgrade_scripts_folder.create_as_dir().unwrap();
+    let client = env.aptos_public_info().client().clone();
 
+    // The gas schedule is upgraded by `test_upgrade_gas_schedule`
+    let major = get_major_version(&client).await;
     let config = aptos_release_builder::ReleaseConfig {
+        version: Some(Version { major: major + 1 }),
         feature_flags: Some(Features {
             enabled: vec![
                 FeatureFlag::CodeDependencyCheck,
                 FeatureFlag::TreatFriendAsPrivate,
             ],
             disabled: vec![],
+            testnet_only: vec![],
         }),
+        consensus_config: Some(OnChainConsensusConfig::V1(ConsensusConfigV1 {
+            max_failed_authors_to_store: 20,
+            ..Default::default()
+        })),
+        gas_schedule: None,
         ..Default::default()
     };
 
+    // Until it's executed, the release isn't in effect on chain
+    let report = config
+        .validate_against_rest_endpoint(url.parse().unwrap())
+        .await
+        .unwrap();
+    assert!(report.differences.contains(&ComponentDifference {
+        component: "version".to_string(),
+        difference: Difference::Version {
+            expected: major + 1,
+            found: major,
+        },
+    }));
+
+    let release_time = SystemTime::now();
     config
-        .generate_release_proposal_scripts(upgrade_scripts_folder.path())
+        .execute_release(env.aptos_public_info().root_account(), &mut [], &client)
+        .await
         .unwrap();
-    let mut scripts = fs::read_dir(upgrade_scripts_folder.path())
-        .unwrap()
-        .map(|res| res.unwrap().path())
-        .collect::<Vec<_>>();
-
-    scripts.sort();
-
-    for path in scripts.iter() {
-        assert!(Command::new(aptos_cli.as_path())
-            .current_dir(workspace_root())
-            .args(&vec![
-                "move",
-                "run-script",
-                "--script-path",
-                path.to_str().unwrap(),
-                "--sender-account",
-                "0xA550C18",
-                "--url",
-                url.as_str(),
-                "--private-key",
-                private_key.as_str(),
-                "--assume-yes",
-            ])
-            .output()
-            .unwrap()
-            .status
-            .success());
 
-        *env.aptos_public_info().root_account().sequence_number_mut() += 1;
+    // Once executed, the whole release is in effect on chain
+    let report = config
+        .validate_against_rest_endpoint(url.parse().unwrap())
+        .await
+        .unwrap();
+    assert!(report.is_empty(), "{:?}", report.differences);
+
+    // The framework on chain is the one built by the release builder
+    let framework_dir =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../aptos-move/framework");
+    for (address, package) in release_packages(&config.framework_packages).unwrap() {
+        cli.verify_package(
+            AccountAddress::from_hex_literal(address).unwrap(),
+            framework_dir.join(package),
+            true,
+        )
+        .await
+        .unwrap();
     }
 
-    //TODO: Make sure gas schedule is indeed updated by the tool.
+    assert_eq!(get_major_version(&client).await, major + 1);
+
+    // Every validator started a new epoch with the release
+    let new_epochs = env.grep_logs("^Starting new epoch$", release_time).unwrap();
+    for validator in env.validators() {
+        assert!(
+            new_epochs
+                .iter()
+                .any(|(peer_id, _)| *peer_id == validator.peer_id()),
+            "{} didn't start a new epoch",
+            validator.name()
+        );
+    }
+
+    // The fullnodes follow the chain across the upgrade
+    env.wait_for_all_nodes_to_catchup(Duration::from_secs(MAX_CATCH_UP_SECS))
+        .await
+        .unwrap();
+    for fullnode in env.full_nodes() {
+        assert_eq!(
+            get_major_version(&fullnode.rest_client()).await,
+            major + 1,
+            "{} didn't sync the release",
+            fullnode.name()
+        );
+    }
+
+    // Fresh fullnodes can still join after the upgrade, whatever their bootstrapping mode
+    for bootstrapping_mode in [
+        BootstrappingMode::ExecuteTransactionsFromGenesis,
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        BootstrappingMode::DownloadLatestStates,
+    ] {
+        let stats = bootstrap_new_fullnode(
+            &mut env,
+            bootstrapping_mode,
+            Duration::from_secs(MAX_CATCH_UP_SECS),
+        )
+        .await;
+        assert_eq!(stats.bootstrapping_mode, bootstrapping_mode);
+        assert!(
+            stats.time_to_healthy <= stats.time_to_target_version
+                && stats.time_to_target_version <= Duration::from_secs(MAX_CATCH_UP_SECS),
+            "{:?}",
+            stats
+        );
+        let fullnode = env.fullnode(stats.peer_id).unwrap();
+        let client = fullnode.rest_client();
+        let synced_version = client
+            .get_ledger_information()
+            .await
+            .unwrap()
+            .into_inner()
+            .version;
+        assert!(
+            synced_version >= stats.target_version,
+            "{:?} synced version {}",
+            stats,
+            synced_version
+        );
+        assert_eq!(
+            get_major_version(&client).await,
+            major + 1,
+            "{} didn't bootstrap the release with {:?}",
+            fullnode.name(),
+            bootstrapping_mode
+        );
+    }
+
+    // Consensus keeps making progress with the new release
+    let validators: Vec<_> = env.validators().map(|v| v.peer_id()).collect();
+    env.assert_monotonic(
+        validators[1],
+        CONSENSUS_ROUND_METRIC,
+        &[],
+        Duration::from_secs(5),
+    )
+    .await
+    .unwrap();
+
+    // A validator losing its state after the upgrade syncs back from its waypoint
+    let synced_version = env
+        .query_metric(validators[1], SYNCED_VERSION_METRIC, SYNCED_LABELS)
+        .await
+        .unwrap()
+        .unwrap();
+    env.restart_validator_with_options(validators[0], true, true)
+        .await
+        .unwrap();
+    env.assert_eventually_ge(
+        validators[0],
+        SYNCED_VERSION_METRIC,
+        SYNCED_LABELS,
+        synced_version,
+        Duration::from_secs(MAX_CATCH_UP_SECS),
+    )
+    .await
+    .unwrap();
 
     // Test the module publishing workflow
     let base_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
@@ -137,4 +233,381 @@ async fn test_upgrade_flow() {
         .unwrap();
 
     check_create_mint_transfer(&mut env).await;
+
+    // A node crashing during the upgrade could go unnoticed if it recovered in time
+    env.assert_no_restarts_or_panics().await.unwrap();
+}
+
+#[tokio::test]
+/// This test verifies that a release upgrading the gas schedule changes exactly the gas
+/// parameters it's expected to change.
+async fn test_upgrade_gas_schedule() {
+    let mut env = SwarmBuilder::new_local(1)
+        .with_aptos_testnet()
+        .build()
+        .await;
+    let url = env.aptos_public_info().url().to_string();
+    let client = env.aptos_public_info().client().clone();
+
+    // Bump the limit in gas schedule
+    let bumped_gas_schedule = gas_schedule_with(|gas_parameters| {
+        gas_parameters.txn.max_transaction_size_in_bytes = GasQuantity::new(100_000_000);
+    });
+    update_gas_schedule(
+        &client,
+        env.aptos_public_info().root_account(),
+        &bumped_gas_schedule,
+    )
+    .await
+    .unwrap();
+    assert_gas_schedule_on_chain(&client, &bumped_gas_schedule)
+        .await
+        .unwrap();
+
+    // The release is built from the bumped gas schedule, so that it keeps the bumped limit
+    let gas_schedule = gas_schedule_with(|gas_parameters| {
+        gas_parameters.txn.max_transaction_size_in_bytes = GasQuantity::new(100_000_000);
+        gas_parameters.txn.maximum_number_of_gas_units = GasQuantity::new(4_000_000);
+    });
+    let config = aptos_release_builder::ReleaseConfig {
+        testnet: true,
+        framework_release: false,
+        gas_schedule: Some(gas_schedule.clone()),
+        consensus_config: None,
+        ..Default::default()
+    };
+
+    // The gas parameters the release is expected to change
+    let gas_schedule_before = get_gas_schedule(&client).await.unwrap();
+    let expected_gas_changes = config
+        .gas_schedule_diff(url.parse().unwrap())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        expected_gas_changes
+            .changes
+            .iter()
+            .map(|change| change.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["txn.maximum_number_of_gas_units"],
+        "Unexpected gas parameters in the release:\n{}",
+        expected_gas_changes
+    );
+    assert_eq!(
+        expected_gas_changes
+            .get("txn.maximum_number_of_gas_units")
+            .unwrap()
+            .new,
+        Some(4_000_000)
+    );
+
+    config
+        .execute_release(env.aptos_public_info().root_account(), &mut [], &client)
+        .await
+        .unwrap();
+
+    // The release changed exactly the expected gas parameters
+    let gas_changes = AptosGasParameters::diff(
+        &gas_schedule_before.entries,
+        &get_gas_schedule(&client).await.unwrap().entries,
+    );
+    assert_eq!(
+        gas_changes, expected_gas_changes,
+        "The release changed unexpected gas parameters:\n{}",
+        gas_changes
+    );
+    assert_gas_schedule_on_chain(&client, &gas_schedule)
+        .await
+        .unwrap();
+
+    check_create_mint_transfer(&mut env).await;
+}
+
+#[ignore] // Builds aptos-node at the previous release revision, which takes a long time
+#[tokio::test]
+/// This test verifies that a network where half of the validators still run the previous release
+/// stays alive when governance upgrades the framework.
+async fn test_upgrade_flow_with_mixed_versions() {
+    let mut env = SwarmBuilder::new_local(4)
+        .with_aptos_testnet()
+        .with_versions(&[PREVIOUS_RELEASE_REVISION, WORKSPACE_REVISION])
+        .build()
+        .await;
+    let versions: HashSet<_> = env.validators().map(|v| v.version()).collect();
+    assert_eq!(versions.len(), 2);
+
+    let client = env.aptos_public_info().client().clone();
+    let major = get_major_version(&client).await;
+    let config = aptos_release_builder::ReleaseConfig {
+        version: Some(Version { major: major + 1 }),
+        ..Default::default()
+    };
+    config
+        .execute_release(env.aptos_public_info().root_account(), &mut [], &client)
+        .await
+        .unwrap();
+    assert_eq!(get_major_version(&client).await, major + 1);
+
+    // Validators of both versions keep up with the upgraded framework
+    check_create_mint_transfer(&mut env).await;
+    env.wait_for_all_nodes_to_catchup(Duration::from_secs(MAX_CATCH_UP_SECS))
+        .await
+        .unwrap();
+    env.assert_no_restarts_or_panics().await.unwrap();
+}
+
+#[ignore] // Emits transactions for minutes around the upgrade
+#[tokio::test]
+/// This test verifies that the network stays healthy under a realistic mix of transactions while
+/// governance upgrades the framework.
+async fn test_upgrade_flow_under_load() {
+    let mut env = SwarmBuilder::new_local(4)
+        .with_aptos_testnet()
+        .with_vfns(1)
+        .build()
+        .await;
+    let client = env.aptos_public_info().client().clone();
+    let validator_clients = env.validators().map(|v| v.rest_client()).collect();
+    let chain_info = env.chain_info();
+    let mut emitter = TxnEmitter::new(
+        TransactionFactory::new(chain_info.chain_id),
+        StdRng::from_entropy(),
+    );
+
+    // Coin transfers before the upgrade, then the mixed workload during and after it
+    let job = emitter
+        .start_job(
+            chain_info.root_account,
+            EmitJobRequest::new(validator_clients)
+                .gas_price(aptos_global_constants::GAS_UNIT_PRICE)
+                .mode(EmitJobMode::ConstTps { tps: 20 })
+                .phase_workload_profiles(vec![
+                    WorkloadProfile::CoinTransfer,
+                    WorkloadProfile::Mixed,
+                ]),
+            3,
+        )
+        .await
+        .unwrap();
+    tokio::time::sleep(LOAD_PHASE_DURATION).await;
+
+    job.start_next_phase();
+    let major = get_major_version(&client).await;
+    let config = aptos_release_builder::ReleaseConfig {
+        version: Some(Version { major: major + 1 }),
+        ..Default::default()
+    };
+    config
+        .execute_release(env.aptos_public_info().root_account(), &mut [], &client)
+        .await
+        .unwrap();
+    assert_eq!(get_major_version(&client).await, major + 1);
+
+    job.start_next_phase();
+    tokio::time::sleep(LOAD_PHASE_DURATION).await;
+    let stats = emitter.stop_job(job).await;
+    for (phase, stats) in stats.iter().enumerate() {
+        assert!(
+            stats.committed > 0,
+            "No transaction was committed in phase {}: {:?}",
+            phase,
+            stats
+        );
+    }
+
+    env.wait_for_all_nodes_to_catchup(Duration::from_secs(MAX_CATCH_UP_SECS))
+        .await
+        .unwrap();
+    env.assert_no_restarts_or_panics().await.unwrap();
+}
+
+async fn get_major_version(client: &Client) -> u64 {
+    client
+        .get_account_resource_bcs::<Version>(CORE_CODE_ADDRESS, "0x1::version::Version")
+        .await
+        .unwrap()
+        .into_inner()
+        .major
+}
+
+/// A step of a multi-step proposal setting the version. `next_execution_hash` is the hash of the
+/// script of the next step, or `None` for the last step.
+fn set_version_step(major: u64, next_execution_hash: Option<HashValue>) -> String {
+    let next_execution_hash = next_execution_hash
+        .map(|hash| format!("x\"{}\"", hash.to_hex()))
+        .unwrap_or_else(|| "vector::empty<u8>()".to_string());
+    format!(
+        r#"
+script {{
+    use std::vector;
+    use aptos_framework::aptos_governance;
+    use aptos_framework::version;
+
+    fun main(proposal_id: u64) {{
+        let framework_signer = aptos_governance::resolve_multi_step_proposal(proposal_id, @0x1, {});
+        version::set_version(&framework_signer, {});
+    }}
+}}
+"#,
+        next_execution_hash, major
+    )
+}
+
+#[tokio::test]
+/// This test verifies that a multi-step proposal failing mid-chain can be aborted and superseded
+/// by a corrective proposal generated by the release builder.
+async fn test_abort_failed_multi_step_proposal() {
+    let (mut env, mut cli, _) = SwarmBuilder::new_local(4)
+        .with_aptos_testnet()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.voting_duration_secs = VOTING_DURATION_SECS;
+        }))
+        .build_with_cli(0)
+        .await;
+
+    let client = env.aptos_public_info().client().clone();
+
+    // The second step can't be executed, as the version can only be increased
+    let major = get_major_version(&client).await;
+    let last_step = set_version_step(major + 2, None);
+    let (_, last_step_hash) = cli.compile_script(&last_step).unwrap();
+    let failing_step = set_version_step(major, Some(last_step_hash));
+    let (_, failing_step_hash) = cli.compile_script(&failing_step).unwrap();
+    let first_step = set_version_step(major + 1, Some(failing_step_hash));
+
+    // The validators vote with their own stake pools
+    let validators: Vec<_> = env.validators().map(|v| v.peer_id()).collect();
+    let (proposal_id, proposer_cli_index) = create_and_approve_governance_proposal(
+        &mut cli,
+        &env,
+        &[first_step.clone(), failing_step.clone(), last_step],
+        &validators,
+    )
+    .await;
+
+    cli.execute_proposal(proposer_cli_index, proposal_id, &first_step)
+        .await
+        .unwrap();
+    assert_eq!(
+        approved_execution_hash(&client, proposal_id).await.unwrap(),
+        Some(failing_step_hash.to_vec())
+    );
+
+    // The failed step leaves the proposal in execution, with its hash still approved
+    cli.execute_proposal(proposer_cli_index, proposal_id, &failing_step)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        approved_execution_hash(&client, proposal_id).await.unwrap(),
+        Some(failing_step_hash.to_vec())
+    );
+    assert!(!is_proposal_resolved(&client, proposal_id).await.unwrap());
+
+    // The corrective release aborts the failed proposal before setting the version
+    let config = aptos_release_builder::ReleaseConfig {
+        framework_release: false,
+        gas_schedule: None,
+        consensus_config: None,
+        version: Some(Version { major: major + 2 }),
+        superseded_proposal_id: Some(proposal_id),
+        ..Default::default()
+    };
+    let executed = config
+        .execute_release(env.aptos_public_info().root_account(), &mut [], &client)
+        .await
+        .unwrap();
+    assert_eq!(executed.len(), 2);
+
+    // None of the remaining steps of the aborted proposal can be executed
+    assert_eq!(
+        approved_execution_hash(&client, proposal_id).await.unwrap(),
+        None
+    );
+    cli.execute_proposal(proposer_cli_index, proposal_id, &failing_step)
+        .await
+        .unwrap_err();
+
+    check_create_mint_transfer(&mut env).await;
+}
+
+#[tokio::test]
+/// This test verifies that the steps of a multi-step proposal approved by the validators are
+/// executed in order.
+async fn test_multi_step_proposal() {
+    let (mut env, mut cli, _) = SwarmBuilder::new_local(4)
+        .with_aptos_testnet()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.voting_duration_secs = VOTING_DURATION_SECS;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = env.aptos_public_info().client().clone();
+
+    let major = get_major_version(&client).await;
+    let last_step = set_version_step(major + 2, None);
+    let (_, last_step_hash) = cli.compile_script(&last_step).unwrap();
+    let first_step = set_version_step(major + 1, Some(last_step_hash));
+
+    let validators: Vec<_> = env.validators().map(|v| v.peer_id()).collect();
+    let proposal_id =
+        execute_governance_proposals(&mut cli, &env, &[first_step, last_step], &validators).await;
+
+    assert_eq!(get_major_version(&client).await, major + 2);
+    assert!(is_proposal_resolved(&client, proposal_id).await.unwrap());
+    check_create_mint_transfer(&mut env).await;
+}
+
+#[tokio::test]
+/// This test verifies that the release builder executes a multi-step release through governance,
+/// with the validators voting for it.
+async fn test_execute_multi_step_release() {
+    let (mut env, _, _) = SwarmBuilder::new_local(4)
+        .with_aptos_testnet()
+        .with_init_genesis_config(Arc::new(|genesis_config| {
+            genesis_config.voting_duration_secs = VOTING_DURATION_SECS;
+        }))
+        .build_with_cli(0)
+        .await;
+    let client = env.aptos_public_info().client().clone();
+
+    let mut voters = vec![];
+    for validator in env.validators() {
+        let private_key = validator
+            .account_private_key()
+            .as_ref()
+            .unwrap()
+            .private_key();
+        let address = AuthenticationKey::ed25519(&private_key.public_key()).derived_address();
+        let sequence_number = client
+            .get_account(address)
+            .await
+            .unwrap()
+            .into_inner()
+            .sequence_number;
+        voters.push(LocalAccount::new(address, private_key, sequence_number));
+    }
+
+    let major = get_major_version(&client).await;
+    let config = aptos_release_builder::ReleaseConfig {
+        framework_release: false,
+        gas_schedule: None,
+        consensus_config: None,
+        version: Some(Version { major: major + 1 }),
+        feature_flags: Some(Features {
+            enabled: vec![FeatureFlag::TreatFriendAsPrivate],
+            disabled: vec![],
+            testnet_only: vec![],
+        }),
+        is_multi_step: true,
+        ..Default::default()
+    };
+    let executed = config
+        .execute_release(env.aptos_public_info().root_account(), &mut voters, &client)
+        .await
+        .unwrap();
+    assert_eq!(executed.len(), 2);
+    assert_eq!(get_major_version(&client).await, major + 1);
+
+    check_create_mint_transfer(&mut env).await;
 }
@@ -22,8 +22,8 @@ pub mod validator_reboot_stress_test;
 
 use anyhow::{anyhow, Context};
 use aptos_forge::{
-    EmitJobRequest, NetworkContext, NetworkTest, NodeExt, Result, Swarm, SwarmExt, Test,
-    TxnEmitter, TxnStats, Version,
+    latency_probe::LatencyProbeStats, EmitJobRequest, NetworkContext, NetworkTest, NodeExt, Result,
+    Swarm, SwarmExt, Test, TxnEmitter, TxnStats, Version,
 };
 use aptos_logger::info;
 use aptos_sdk::{transaction_builder::TransactionFactory, types::PeerId};
@@ -76,6 +76,30 @@ pub fn create_emitter_and_request(
     Ok((emitter, emit_job_request))
 }
 
+/// Creates and funds the accounts a load test will use, so that the test doesn't measure their
+/// creation. The accounts are saved to the account pool file of the request, where the emitters
+/// of the test pick them up.
+pub fn pregenerate_accounts(
+    ctx: &mut NetworkContext<'_>,
+    emit_job_request: EmitJobRequest,
+    nodes: &[PeerId],
+) -> Result<()> {
+    let rng = SeedableRng::from_rng(ctx.core().rng())?;
+    let (mut emitter, emit_job_request) =
+        create_emitter_and_request(ctx.swarm(), emit_job_request, nodes, rng)?;
+    let num_accounts = emit_job_request.num_accounts();
+    info!("Pre-generating {} accounts", num_accounts);
+
+    let rt = traffic_emitter_runtime()?;
+    rt.block_on(emitter.pregenerate_accounts(
+        ctx.swarm().chain_info().root_account,
+        &emit_job_request,
+        num_accounts,
+    ))
+    .context("pre-generate accounts")?;
+    Ok(())
+}
+
 pub fn traffic_emitter_runtime() -> Result<Runtime> {
     let mut runtime_builder = Builder::new_multi_thread();
     runtime_builder.disable_lifo_slot().enable_all();
@@ -156,16 +180,21 @@ impl NetworkTest for dyn NetworkLoadTest {
         let emit_job_request = ctx.emit_job.clone();
         let rng = SeedableRng::from_rng(ctx.core().rng())?;
         let duration = ctx.global_duration;
-        let (txn_stat, actual_test_duration, _ledger_transactions) = self.network_load_test(
-            ctx,
-            emit_job_request,
-            duration,
-            WARMUP_DURATION_FRACTION,
-            COOLDOWN_DURATION_FRACTION,
-            rng,
-        )?;
+        let (txn_stat, actual_test_duration, _ledger_transactions, probe_stats) = self
+            .network_load_test(
+                ctx,
+                emit_job_request,
+                duration,
+                WARMUP_DURATION_FRACTION,
+                COOLDOWN_DURATION_FRACTION,
+                rng,
+            )?;
         ctx.report
             .report_txn_stats(self.name().to_string(), &txn_stat, actual_test_duration);
+        if let Some(probe_stats) = &probe_stats {
+            ctx.report
+                .report_latency_probe_stats(self.name().to_string(), probe_stats);
+        }
 
         let end_timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -180,6 +209,7 @@ impl NetworkTest for dyn NetworkLoadTest {
 
         ctx.check_for_success(
             &txn_stat,
+            probe_stats.as_ref(),
             actual_test_duration,
             start_timestamp as i64,
             end_timestamp as i64,
@@ -201,7 +231,7 @@ impl dyn NetworkLoadTest {
         warmup_duration_fraction: f32,
         cooldown_duration_fraction: f32,
         rng: StdRng,
-    ) -> Result<(TxnStats, Duration, u64)> {
+    ) -> Result<(TxnStats, Duration, u64, Option<LatencyProbeStats>)> {
         let destination = self.setup(ctx).context("setup NetworkLoadTest")?;
         let nodes_to_send_load_to = destination.get_destination_nodes(ctx.swarm());
 
@@ -238,6 +268,18 @@ impl dyn NetworkLoadTest {
             .map(|s| s.version - 2 * s.block_height)
             .max();
 
+        // Measure the latency of marker transactions while the test runs, if requested
+        let latency_probe = match ctx.success_criteria.latency_probe().cloned() {
+            Some(config) => Some(
+                rt.block_on(
+                    ctx.swarm()
+                        .start_latency_probe(config, nodes_to_send_load_to[0]),
+                )
+                .context("start latency probe")?,
+            ),
+            None => None,
+        };
+
         job.start_next_phase();
 
         let test_start = Instant::now();
@@ -251,6 +293,10 @@ impl dyn NetworkLoadTest {
         );
 
         job.start_next_phase();
+        let probe_stats = latency_probe
+            .map(|probe| rt.block_on(probe.stop()))
+            .transpose()
+            .context("stop latency probe")?;
         let cooldown_start = Instant::now();
         let max_end_ledger_transactions = rt
             .block_on(join_all(
@@ -292,6 +338,7 @@ impl dyn NetworkLoadTest {
             txn_stats.into_iter().nth(1).unwrap(),
             actual_test_duration,
             ledger_transactions,
+            probe_stats,
         ))
     }
 }
@@ -1,9 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::NetworkLoadTest;
+use crate::{pregenerate_accounts, NetworkLoadTest};
 use aptos_forge::{EmitJobMode, NetworkContext, NetworkTest, Result, Test, TxnStats};
 use aptos_logger::info;
+use aptos_temppath::TempPath;
 use rand::SeedableRng;
 use std::time::Duration;
 use tokio::runtime::Runtime;
@@ -35,16 +36,17 @@ impl LoadVsPerfBenchmark {
     ) -> Result<SingleRunStats> {
         let rng = SeedableRng::from_rng(ctx.core().rng())?;
         let emit_job_request = ctx.emit_job.clone().mode(EmitJobMode::ConstTps { tps });
-        let (stats, actual_duration, ledger_transactions) = self.test.network_load_test(
-            ctx,
-            emit_job_request,
-            duration,
-            // add larger warmup, as we are exceeding the max load,
-            // and for that it takes more time to fill mempool.
-            0.2,
-            0.05,
-            rng,
-        )?;
+        let (stats, actual_duration, ledger_transactions, _probe_stats) =
+            self.test.network_load_test(
+                ctx,
+                emit_job_request,
+                duration,
+                // add larger warmup, as we are exceeding the max load,
+                // and for that it takes more time to fill mempool.
+                0.2,
+                0.05,
+                rng,
+            )?;
 
         Ok(SingleRunStats {
             tps,
@@ -65,6 +67,25 @@ impl NetworkTest for LoadVsPerfBenchmark {
         let individual_duration = individual_with_buffer.mul_f32(0.8);
         let buffer = individual_with_buffer - individual_duration;
 
+        // Create the accounts of the most demanding phase once, and rotate them across the
+        // phases, so that the phases measure the steady-state throughput rather than the
+        // creation of their accounts
+        let account_pool_file = TempPath::new();
+        let previous_emit_job = ctx.emit_job.clone();
+        if previous_emit_job.get_account_pool_file().is_none() {
+            ctx.emit_job = previous_emit_job
+                .clone()
+                .account_pool_file(account_pool_file.path().to_path_buf());
+        }
+        if let Some(max_tps) = self.tps.iter().max() {
+            let validators: Vec<_> = ctx.swarm().validators().map(|v| v.peer_id()).collect();
+            let emit_job_request = ctx
+                .emit_job
+                .clone()
+                .mode(EmitJobMode::ConstTps { tps: *max_tps });
+            pregenerate_accounts(ctx, emit_job_request, &validators)?;
+        }
+
         let mut results = Vec::new();
         for (i, tps) in self.tps.iter().enumerate() {
             if i != 0 {
@@ -111,6 +132,7 @@ impl NetworkTest for LoadVsPerfBenchmark {
                 )
             }
         }
+        ctx.emit_job = previous_emit_job;
         Ok(())
     }
 }
@@ -4,6 +4,7 @@
 use crate::{
     account_address::AccountAddress,
     account_config::CORE_CODE_ADDRESS,
+    block_metadata::BlockMetadata,
     event::{EventHandle, EventKey},
 };
 use anyhow::Result;
@@ -140,3 +141,52 @@ impl MoveStructType for BlockResource {
 }
 
 impl MoveResource for BlockResource {}
+
+/// The round, proposer and timestamp of the block being executed, as exposed to Move by the VM.
+/// Should be kept in-sync with CurrentBlockInfo move struct in block.move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CurrentBlockInfo {
+    round: u64,
+    proposer: AccountAddress,
+    // usecs (microseconds)
+    timestamp: u64,
+}
+
+impl CurrentBlockInfo {
+    pub fn new(round: u64, proposer: AccountAddress, timestamp: u64) -> Self {
+        Self {
+            round,
+            proposer,
+            timestamp,
+        }
+    }
+
+    pub fn round(&self) -> u64 {
+        self.round
+    }
+
+    pub fn proposer(&self) -> AccountAddress {
+        self.proposer
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+impl From<&BlockMetadata> for CurrentBlockInfo {
+    fn from(block_metadata: &BlockMetadata) -> Self {
+        Self::new(
+            block_metadata.round(),
+            block_metadata.proposer(),
+            block_metadata.timestamp_usecs(),
+        )
+    }
+}
+
+impl MoveStructType for CurrentBlockInfo {
+    const MODULE_NAME: &'static IdentStr = ident_str!("block");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("CurrentBlockInfo");
+}
+
+impl MoveResource for CurrentBlockInfo {}
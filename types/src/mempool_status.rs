@@ -63,6 +63,12 @@ pub enum MempoolStatusCode {
     // transaction didn't pass vm_validation
     VmError = 5,
     UnknownStatus = 6,
+    // Sequence number is too far ahead of the account's sequence number
+    SequenceNumberTooNew = 7,
+    // Transaction was rejected by the on-chain transaction deny list
+    TransactionDenied = 8,
+    // Transaction was rejected by the address allowlist of a permissioned network
+    NotAllowlisted = 9,
 }
 
 impl TryFrom<u64> for MempoolStatusCode {
@@ -77,6 +83,9 @@ impl TryFrom<u64> for MempoolStatusCode {
             4 => Ok(MempoolStatusCode::InvalidUpdate),
             5 => Ok(MempoolStatusCode::VmError),
             6 => Ok(MempoolStatusCode::UnknownStatus),
+            7 => Ok(MempoolStatusCode::SequenceNumberTooNew),
+            8 => Ok(MempoolStatusCode::TransactionDenied),
+            9 => Ok(MempoolStatusCode::NotAllowlisted),
             _ => Err("invalid StatusCode"),
         }
     }
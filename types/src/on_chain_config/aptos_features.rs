@@ -10,7 +10,31 @@ use serde::{Deserialize, Serialize};
 pub enum FeatureFlag {
     CODE_DEPENDENCY_CHECK = 1,
     TREAT_FRIEND_AS_PRIVATE = 2,
+    SHA_512_AND_RIPEMD_160_NATIVES = 3,
+    APTOS_STD_CHAIN_ID_NATIVES = 4,
     VM_BINARY_FORMAT_V6 = 5,
+    COLLECT_AND_DISTRIBUTE_GAS_FEES = 6,
+    MULTI_ED25519_PK_VALIDATE_V2_NATIVES = 7,
+    BLAKE2B_256_NATIVE = 8,
+    EXPERIMENTAL_NATIVES = 9,
+    TRANSACTION_DENY_LIST = 10,
+    BLOCK_INFO = 11,
+}
+
+impl FeatureFlag {
+    pub const ALL: [FeatureFlag; 11] = [
+        FeatureFlag::CODE_DEPENDENCY_CHECK,
+        FeatureFlag::TREAT_FRIEND_AS_PRIVATE,
+        FeatureFlag::SHA_512_AND_RIPEMD_160_NATIVES,
+        FeatureFlag::APTOS_STD_CHAIN_ID_NATIVES,
+        FeatureFlag::VM_BINARY_FORMAT_V6,
+        FeatureFlag::COLLECT_AND_DISTRIBUTE_GAS_FEES,
+        FeatureFlag::MULTI_ED25519_PK_VALIDATE_V2_NATIVES,
+        FeatureFlag::BLAKE2B_256_NATIVE,
+        FeatureFlag::EXPERIMENTAL_NATIVES,
+        FeatureFlag::TRANSACTION_DENY_LIST,
+        FeatureFlag::BLOCK_INFO,
+    ];
 }
 
 /// Representation of features on chain as a bitset.
@@ -40,6 +64,25 @@ impl Features {
         let bit_mask = 1 << (val % 8);
         byte_index < self.features.len() && (self.features[byte_index] & bit_mask != 0)
     }
+
+    pub fn enable(&mut self, flag: FeatureFlag) {
+        let val = flag as u64;
+        let byte_index = (val / 8) as usize;
+        let bit_mask = 1 << (val % 8);
+        if byte_index >= self.features.len() {
+            self.features.resize(byte_index + 1, 0);
+        }
+        self.features[byte_index] |= bit_mask;
+    }
+
+    pub fn disable(&mut self, flag: FeatureFlag) {
+        let val = flag as u64;
+        let byte_index = (val / 8) as usize;
+        let bit_mask = 1 << (val % 8);
+        if byte_index < self.features.len() {
+            self.features[byte_index] &= !bit_mask;
+        }
+    }
 }
 
 // --------------------------------------------------------------------------------------------
@@ -13,6 +13,11 @@ use serde::{Deserialize, Serialize};
 pub enum OnChainConsensusConfig {
     V1(ConsensusConfigV1),
     V2(ConsensusConfigV1),
+    V3 {
+        config: ConsensusConfigV1,
+        quorum_store_enabled: bool,
+        execution: ExecutionConfig,
+    },
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -20,18 +25,18 @@ impl OnChainConsensusConfig {
     /// The number of recent rounds that don't count into reputations.
     pub fn leader_reputation_exclude_round(&self) -> u64 {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.exclude_round
-            }
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::V3 { config, .. } => config.exclude_round,
         }
     }
 
     /// Decouple execution from consensus or not.
     pub fn decoupled_execution(&self) -> bool {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.decoupled_execution
-            }
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::V3 { config, .. } => config.decoupled_execution,
         }
     }
 
@@ -43,9 +48,9 @@ impl OnChainConsensusConfig {
             return 10;
         }
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.back_pressure_limit
-            }
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::V3 { config, .. } => config.back_pressure_limit,
         }
     }
 
@@ -53,18 +58,18 @@ impl OnChainConsensusConfig {
     // to this max size.
     pub fn max_failed_authors_to_store(&self) -> usize {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                config.max_failed_authors_to_store
-            }
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::V3 { config, .. } => config.max_failed_authors_to_store,
         }
     }
 
     // Type and configuration used for proposer election.
     pub fn proposer_election_type(&self) -> &ProposerElectionType {
         match &self {
-            OnChainConsensusConfig::V1(config) | OnChainConsensusConfig::V2(config) => {
-                &config.proposer_election_type
-            }
+            OnChainConsensusConfig::V1(config)
+            | OnChainConsensusConfig::V2(config)
+            | OnChainConsensusConfig::V3 { config, .. } => &config.proposer_election_type,
         }
     }
 
@@ -72,6 +77,21 @@ impl OnChainConsensusConfig {
         match &self {
             OnChainConsensusConfig::V1(_config) => false,
             OnChainConsensusConfig::V2(_config) => true,
+            OnChainConsensusConfig::V3 {
+                quorum_store_enabled,
+                ..
+            } => *quorum_store_enabled,
+        }
+    }
+
+    /// Execute the proposed blocks while the votes are collected, only with decoupled execution.
+    pub fn optimistic_execution(&self) -> bool {
+        if !self.decoupled_execution() {
+            return false;
+        }
+        match &self {
+            OnChainConsensusConfig::V1(_) | OnChainConsensusConfig::V2(_) => false,
+            OnChainConsensusConfig::V3 { execution, .. } => execution.optimistic_execution,
         }
     }
 }
@@ -137,6 +157,14 @@ impl Default for ConsensusConfigV1 {
     }
 }
 
+/// Configuration of the execution pipeline of consensus.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ExecutionConfig {
+    /// Speculatively execute proposed blocks while the votes are collected, and reuse the result
+    /// once the block is ordered.
+    pub optimistic_execution: bool,
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
 pub enum ProposerElectionType {
@@ -273,4 +301,33 @@ mod test {
             ProposerElectionType::RoundProposer(_value)
         ));
     }
+
+    #[test]
+    fn test_config_optimistic_execution() {
+        let config = OnChainConsensusConfig::V3 {
+            config: ConsensusConfigV1::default(),
+            quorum_store_enabled: false,
+            execution: ExecutionConfig {
+                optimistic_execution: true,
+            },
+        };
+        let result =
+            bcs::from_bytes::<OnChainConsensusConfig>(&bcs::to_bytes(&config).unwrap()).unwrap();
+        assert!(result.optimistic_execution());
+        assert!(!result.quorum_store_enabled());
+
+        // optimistic execution relies on the decoupled execution pipeline
+        let config = OnChainConsensusConfig::V3 {
+            config: ConsensusConfigV1 {
+                decoupled_execution: false,
+                ..ConsensusConfigV1::default()
+            },
+            quorum_store_enabled: false,
+            execution: ExecutionConfig {
+                optimistic_execution: true,
+            },
+        };
+        assert!(!config.optimistic_execution());
+        assert!(!OnChainConsensusConfig::default().optimistic_execution());
+    }
 }
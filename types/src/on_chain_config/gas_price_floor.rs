@@ -0,0 +1,59 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use serde::{Deserialize, Serialize};
+
+/// A minimum gas unit price enforced on top of the one in the gas schedule. Changes are
+/// scheduled through governance for a future epoch, so clients can adjust before they apply.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct GasPriceFloor {
+    /// The floor in effect before `activation_epoch`
+    pub min_gas_unit_price: u64,
+    /// The floor in effect from `activation_epoch` on
+    pub next_min_gas_unit_price: u64,
+    pub activation_epoch: u64,
+}
+
+impl GasPriceFloor {
+    pub fn new(min_gas_unit_price: u64, activation_epoch: u64) -> Self {
+        Self {
+            min_gas_unit_price: 0,
+            next_min_gas_unit_price: min_gas_unit_price,
+            activation_epoch,
+        }
+    }
+
+    /// Returns the floor in effect in the given epoch.
+    pub fn effective_min_gas_unit_price(&self, epoch: u64) -> u64 {
+        if epoch >= self.activation_epoch {
+            self.next_min_gas_unit_price
+        } else {
+            self.min_gas_unit_price
+        }
+    }
+}
+
+impl OnChainConfig for GasPriceFloor {
+    const MODULE_IDENTIFIER: &'static str = "gas_price_floor";
+    const TYPE_IDENTIFIER: &'static str = "GasPriceFloor";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_min_gas_unit_price() {
+        assert_eq!(GasPriceFloor::default().effective_min_gas_unit_price(5), 0);
+
+        let floor = GasPriceFloor {
+            min_gas_unit_price: 100,
+            next_min_gas_unit_price: 150,
+            activation_epoch: 10,
+        };
+        assert_eq!(floor.effective_min_gas_unit_price(9), 100);
+        assert_eq!(floor.effective_min_gas_unit_price(10), 150);
+        assert_eq!(floor.effective_min_gas_unit_price(11), 150);
+    }
+}
@@ -22,7 +22,9 @@ mod aptos_features;
 mod aptos_version;
 mod chain_id;
 mod consensus_config;
+mod gas_price_floor;
 mod gas_schedule;
+mod transaction_deny_list;
 mod validator_set;
 
 pub use self::{
@@ -32,10 +34,12 @@ pub use self::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
     },
     consensus_config::{
-        ConsensusConfigV1, LeaderReputationType, OnChainConsensusConfig, ProposerAndVoterConfig,
-        ProposerElectionType,
+        ConsensusConfigV1, ExecutionConfig, LeaderReputationType, OnChainConsensusConfig,
+        ProposerAndVoterConfig, ProposerElectionType,
     },
+    gas_price_floor::GasPriceFloor,
     gas_schedule::{GasSchedule, GasScheduleV2, StorageGasSchedule},
+    transaction_deny_list::{DeniedFunction, TransactionDenyList},
     validator_set::{ConsensusScheme, ValidatorSet},
 };
 
@@ -69,6 +73,9 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     Version::CONFIG_ID,
     OnChainConsensusConfig::CONFIG_ID,
     ChainId::CONFIG_ID,
+    Features::CONFIG_ID,
+    TransactionDenyList::CONFIG_ID,
+    GasPriceFloor::CONFIG_ID,
 ];
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    on_chain_config::OnChainConfig,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use move_core_types::{identifier::IdentStr, language_storage::ModuleId};
+use serde::{Deserialize, Serialize};
+
+/// An entry function that may not be called. An empty function name denies every entry
+/// function of the module.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct DeniedFunction {
+    pub module_address: AccountAddress,
+    pub module_name: String,
+    pub function_name: String,
+}
+
+impl DeniedFunction {
+    pub fn matches(&self, module: &ModuleId, function: &IdentStr) -> bool {
+        &self.module_address == module.address()
+            && self.module_name == module.name().as_str()
+            && (self.function_name.is_empty() || self.function_name == function.as_str())
+    }
+}
+
+/// Senders and entry functions that are rejected by both mempool and the VM. This is an
+/// emergency brake for exploited modules, and may only be updated through governance.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct TransactionDenyList {
+    pub senders: Vec<AccountAddress>,
+    pub entry_functions: Vec<DeniedFunction>,
+}
+
+impl TransactionDenyList {
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty() && self.entry_functions.is_empty()
+    }
+
+    pub fn denies_sender(&self, sender: &AccountAddress) -> bool {
+        self.senders.contains(sender)
+    }
+
+    pub fn denies_entry_function(&self, module: &ModuleId, function: &IdentStr) -> bool {
+        self.entry_functions
+            .iter()
+            .any(|denied| denied.matches(module, function))
+    }
+
+    pub fn denies_payload(&self, payload: &TransactionPayload) -> bool {
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => {
+                self.denies_entry_function(entry_function.module(), entry_function.function())
+            }
+            TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => false,
+        }
+    }
+
+    pub fn denies(&self, txn: &SignedTransaction) -> bool {
+        self.denies_sender(&txn.sender()) || self.denies_payload(txn.payload())
+    }
+}
+
+impl OnChainConfig for TransactionDenyList {
+    const MODULE_IDENTIFIER: &'static str = "transaction_deny_list";
+    const TYPE_IDENTIFIER: &'static str = "TransactionDenyList";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{ident_str, identifier::Identifier};
+
+    #[test]
+    fn test_denies_entry_function() {
+        let coin = ModuleId::new(AccountAddress::ONE, Identifier::new("coin").unwrap());
+        let exploited = ModuleId::new(
+            AccountAddress::from_hex_literal("0xbad").unwrap(),
+            Identifier::new("pool").unwrap(),
+        );
+        let deny_list = TransactionDenyList {
+            senders: vec![],
+            entry_functions: vec![
+                DeniedFunction {
+                    module_address: AccountAddress::ONE,
+                    module_name: "coin".to_string(),
+                    function_name: "transfer".to_string(),
+                },
+                DeniedFunction {
+                    module_address: *exploited.address(),
+                    module_name: "pool".to_string(),
+                    function_name: "".to_string(),
+                },
+            ],
+        };
+        assert!(deny_list.denies_entry_function(&coin, ident_str!("transfer")));
+        assert!(!deny_list.denies_entry_function(&coin, ident_str!("register")));
+        assert!(deny_list.denies_entry_function(&exploited, ident_str!("swap")));
+    }
+}
@@ -0,0 +1,550 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A canonical JSON encoding of [`SignedTransaction`], for systems that keep off-chain records
+//! of transactions, e.g. for compliance or audits.
+//!
+//! Every transaction has exactly one canonical encoding: a JSON object without insignificant
+//! whitespace, in which the keys of every object are sorted by their UTF-8 bytes. Values are
+//! encoded as follows:
+//!
+//! * account addresses as `0x` followed by all 64 lowercase hex digits
+//! * bytes, public keys and signatures as `0x` followed by lowercase hex
+//! * `u8`, `u16` and `u32` as JSON numbers, `u64`, `u128` and `u256` as decimal strings
+//! * type arguments and entry functions in Move syntax, with short addresses, e.g.
+//!   `0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>` and `0x1::coin::transfer`
+//! * payloads, authenticators and script arguments as objects with a `type` field naming the
+//!   variant in snake case
+//!
+//! For example, an entry function call signed with a single Ed25519 key is encoded as
+//!
+//! ```json
+//! {"authenticator":{"public_key":"0x…","signature":"0x…","type":"ed25519"},"chain_id":4,
+//! "expiration_timestamp_secs":"1700000000","gas_unit_price":"100","max_gas_amount":"2000",
+//! "payload":{"arguments":["0x…","0xe803000000000000"],"function":"0x1::aptos_account::transfer",
+//! "type":"entry_function","type_arguments":[]},"sender":"0x…","sequence_number":"7"}
+//! ```
+//!
+//! (line breaks added for readability). The canonical JSON hash is the SHA3-256 of the UTF-8
+//! bytes of the encoding, without any domain separation, so that it can be recomputed by
+//! systems that don't know about Aptos.
+//!
+//! The encoding converts losslessly to and from BCS. An off-chain record is checked against the
+//! chain by decoding it and comparing [`SignedTransaction::committed_hash`] with the hash of the
+//! on-chain transaction.
+
+use crate::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        EntryFunction, ModuleBundle, RawTransaction, Script, SignedTransaction,
+        TransactionArgument, TransactionPayload,
+    },
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    HashValue,
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    parser::parse_type_tag,
+    u256::U256,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::{TryFrom, TryInto};
+
+impl SignedTransaction {
+    /// Returns the canonical JSON encoding of the transaction, see the
+    /// [module documentation](crate::transaction::canonical_json).
+    pub fn to_canonical_json(&self) -> String {
+        let value = serde_json::to_value(JsonSignedTransaction::from(self))
+            .expect("JSON encoding of a transaction should not fail");
+        let mut json = String::new();
+        write_canonical(&value, &mut json);
+        json
+    }
+
+    /// Decodes a transaction from its canonical JSON encoding. Fails if `json` is valid, but
+    /// not canonical, e.g. because of whitespace or unsorted keys.
+    pub fn from_canonical_json(json: &str) -> Result<Self> {
+        let encoded: JsonSignedTransaction = serde_json::from_str(json)?;
+        let txn = SignedTransaction::try_from(encoded)?;
+        ensure!(
+            txn.to_canonical_json() == json,
+            "Transaction JSON is not in canonical form"
+        );
+        Ok(txn)
+    }
+
+    /// Returns the SHA3-256 of the canonical JSON encoding of the transaction.
+    pub fn canonical_json_hash(&self) -> HashValue {
+        HashValue::sha3_256_of(self.to_canonical_json().as_bytes())
+    }
+}
+
+/// Converts the canonical JSON encoding of a signed transaction to its BCS encoding.
+pub fn canonical_json_to_bcs(json: &str) -> Result<Vec<u8>> {
+    Ok(bcs::to_bytes(&SignedTransaction::from_canonical_json(
+        json,
+    )?)?)
+}
+
+/// Converts the BCS encoding of a signed transaction to its canonical JSON encoding.
+pub fn bcs_to_canonical_json(bytes: &[u8]) -> Result<String> {
+    Ok(bcs::from_bytes::<SignedTransaction>(bytes)?.to_canonical_json())
+}
+
+// Writes `value` without whitespace, with the keys of objects in sorted order. The keys are
+// sorted here, as the order of `serde_json::Map` depends on its features.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(value, out);
+            }
+            out.push(']');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSignedTransaction {
+    sender: String,
+    sequence_number: String,
+    payload: JsonPayload,
+    max_gas_amount: String,
+    gas_unit_price: String,
+    expiration_timestamp_secs: String,
+    chain_id: u8,
+    authenticator: JsonTransactionAuthenticator,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonPayload {
+    Script {
+        code: String,
+        type_arguments: Vec<String>,
+        arguments: Vec<JsonScriptArgument>,
+    },
+    ModuleBundle {
+        modules: Vec<String>,
+    },
+    EntryFunction {
+        function: String,
+        type_arguments: Vec<String>,
+        arguments: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum JsonScriptArgument {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(String),
+    U128(String),
+    U256(String),
+    Address(String),
+    U8Vector(String),
+    Bool(bool),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonTransactionAuthenticator {
+    Ed25519 {
+        public_key: String,
+        signature: String,
+    },
+    MultiEd25519 {
+        public_key: String,
+        signature: String,
+    },
+    MultiAgent {
+        sender: JsonAccountAuthenticator,
+        secondary_signer_addresses: Vec<String>,
+        secondary_signers: Vec<JsonAccountAuthenticator>,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonAccountAuthenticator {
+    Ed25519 {
+        public_key: String,
+        signature: String,
+    },
+    MultiEd25519 {
+        public_key: String,
+        signature: String,
+    },
+}
+
+impl From<&SignedTransaction> for JsonSignedTransaction {
+    fn from(txn: &SignedTransaction) -> Self {
+        let raw_txn = &txn.raw_txn;
+        Self {
+            sender: encode_address(&raw_txn.sender),
+            sequence_number: raw_txn.sequence_number.to_string(),
+            payload: (&raw_txn.payload).into(),
+            max_gas_amount: raw_txn.max_gas_amount.to_string(),
+            gas_unit_price: raw_txn.gas_unit_price.to_string(),
+            expiration_timestamp_secs: raw_txn.expiration_timestamp_secs.to_string(),
+            chain_id: raw_txn.chain_id.id(),
+            authenticator: (&txn.authenticator).into(),
+        }
+    }
+}
+
+impl TryFrom<JsonSignedTransaction> for SignedTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(txn: JsonSignedTransaction) -> Result<Self> {
+        ensure!(txn.chain_id > 0, "Chain id can't be 0");
+        let raw_txn = RawTransaction::new(
+            decode_address(&txn.sender)?,
+            txn.sequence_number.parse()?,
+            txn.payload.try_into()?,
+            txn.max_gas_amount.parse()?,
+            txn.gas_unit_price.parse()?,
+            txn.expiration_timestamp_secs.parse()?,
+            ChainId::new(txn.chain_id),
+        );
+        Ok(SignedTransaction::new_with_authenticator(
+            raw_txn,
+            txn.authenticator.try_into()?,
+        ))
+    }
+}
+
+impl From<&TransactionPayload> for JsonPayload {
+    fn from(payload: &TransactionPayload) -> Self {
+        match payload {
+            TransactionPayload::Script(script) => JsonPayload::Script {
+                code: encode_hex(script.code()),
+                type_arguments: script.ty_args().iter().map(encode_type_tag).collect(),
+                arguments: script.args().iter().map(Into::into).collect(),
+            },
+            TransactionPayload::ModuleBundle(modules) => JsonPayload::ModuleBundle {
+                modules: modules
+                    .iter()
+                    .map(|module| encode_hex(module.code()))
+                    .collect(),
+            },
+            TransactionPayload::EntryFunction(entry_function) => JsonPayload::EntryFunction {
+                function: format!(
+                    "{}::{}::{}",
+                    entry_function.module().address().to_hex_literal(),
+                    entry_function.module().name(),
+                    entry_function.function()
+                ),
+                type_arguments: entry_function
+                    .ty_args()
+                    .iter()
+                    .map(encode_type_tag)
+                    .collect(),
+                arguments: entry_function
+                    .args()
+                    .iter()
+                    .map(|arg| encode_hex(arg))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl TryFrom<JsonPayload> for TransactionPayload {
+    type Error = anyhow::Error;
+
+    fn try_from(payload: JsonPayload) -> Result<Self> {
+        Ok(match payload {
+            JsonPayload::Script {
+                code,
+                type_arguments,
+                arguments,
+            } => TransactionPayload::Script(Script::new(
+                decode_hex(&code)?,
+                decode_type_tags(&type_arguments)?,
+                arguments
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_>>()?,
+            )),
+            JsonPayload::ModuleBundle { modules } => {
+                TransactionPayload::ModuleBundle(ModuleBundle::new(
+                    modules
+                        .iter()
+                        .map(|module| decode_hex(module))
+                        .collect::<Result<_>>()?,
+                ))
+            }
+            JsonPayload::EntryFunction {
+                function,
+                type_arguments,
+                arguments,
+            } => {
+                let parts: Vec<_> = function.split("::").collect();
+                ensure!(
+                    parts.len() == 3,
+                    "Invalid entry function {:?}, expected <address>::<module>::<function>",
+                    function
+                );
+                let module = ModuleId::new(
+                    AccountAddress::from_hex_literal(parts[0])?,
+                    Identifier::new(parts[1])?,
+                );
+                TransactionPayload::EntryFunction(EntryFunction::new(
+                    module,
+                    Identifier::new(parts[2])?,
+                    decode_type_tags(&type_arguments)?,
+                    arguments
+                        .iter()
+                        .map(|arg| decode_hex(arg))
+                        .collect::<Result<_>>()?,
+                ))
+            }
+        })
+    }
+}
+
+impl From<&TransactionArgument> for JsonScriptArgument {
+    fn from(arg: &TransactionArgument) -> Self {
+        match arg {
+            TransactionArgument::U8(value) => JsonScriptArgument::U8(*value),
+            TransactionArgument::U16(value) => JsonScriptArgument::U16(*value),
+            TransactionArgument::U32(value) => JsonScriptArgument::U32(*value),
+            TransactionArgument::U64(value) => JsonScriptArgument::U64(value.to_string()),
+            TransactionArgument::U128(value) => JsonScriptArgument::U128(value.to_string()),
+            TransactionArgument::U256(value) => JsonScriptArgument::U256(value.to_string()),
+            TransactionArgument::Address(address) => {
+                JsonScriptArgument::Address(encode_address(address))
+            }
+            TransactionArgument::U8Vector(bytes) => JsonScriptArgument::U8Vector(encode_hex(bytes)),
+            TransactionArgument::Bool(value) => JsonScriptArgument::Bool(*value),
+        }
+    }
+}
+
+impl TryFrom<JsonScriptArgument> for TransactionArgument {
+    type Error = anyhow::Error;
+
+    fn try_from(arg: JsonScriptArgument) -> Result<Self> {
+        Ok(match arg {
+            JsonScriptArgument::U8(value) => TransactionArgument::U8(value),
+            JsonScriptArgument::U16(value) => TransactionArgument::U16(value),
+            JsonScriptArgument::U32(value) => TransactionArgument::U32(value),
+            JsonScriptArgument::U64(value) => TransactionArgument::U64(value.parse()?),
+            JsonScriptArgument::U128(value) => TransactionArgument::U128(value.parse()?),
+            JsonScriptArgument::U256(value) => TransactionArgument::U256(
+                value
+                    .parse::<U256>()
+                    .map_err(|err| format_err!("Invalid u256 {:?}: {}", value, err))?,
+            ),
+            JsonScriptArgument::Address(address) => {
+                TransactionArgument::Address(decode_address(&address)?)
+            }
+            JsonScriptArgument::U8Vector(bytes) => {
+                TransactionArgument::U8Vector(decode_hex(&bytes)?)
+            }
+            JsonScriptArgument::Bool(value) => TransactionArgument::Bool(value),
+        })
+    }
+}
+
+impl From<&TransactionAuthenticator> for JsonTransactionAuthenticator {
+    fn from(authenticator: &TransactionAuthenticator) -> Self {
+        match authenticator {
+            TransactionAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => JsonTransactionAuthenticator::Ed25519 {
+                public_key: encode_hex(&public_key.to_bytes()),
+                signature: encode_hex(&signature.to_bytes()),
+            },
+            TransactionAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => JsonTransactionAuthenticator::MultiEd25519 {
+                public_key: encode_hex(&public_key.to_bytes()),
+                signature: encode_hex(&signature.to_bytes()),
+            },
+            TransactionAuthenticator::MultiAgent {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+            } => JsonTransactionAuthenticator::MultiAgent {
+                sender: sender.into(),
+                secondary_signer_addresses: secondary_signer_addresses
+                    .iter()
+                    .map(encode_address)
+                    .collect(),
+                secondary_signers: secondary_signers.iter().map(Into::into).collect(),
+            },
+        }
+    }
+}
+
+impl TryFrom<JsonTransactionAuthenticator> for TransactionAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(authenticator: JsonTransactionAuthenticator) -> Result<Self> {
+        Ok(match authenticator {
+            JsonTransactionAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => TransactionAuthenticator::Ed25519 {
+                public_key: Ed25519PublicKey::try_from(decode_hex(&public_key)?.as_slice())?,
+                signature: Ed25519Signature::try_from(decode_hex(&signature)?.as_slice())?,
+            },
+            JsonTransactionAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => TransactionAuthenticator::MultiEd25519 {
+                public_key: MultiEd25519PublicKey::try_from(decode_hex(&public_key)?.as_slice())?,
+                signature: MultiEd25519Signature::try_from(decode_hex(&signature)?.as_slice())?,
+            },
+            JsonTransactionAuthenticator::MultiAgent {
+                sender,
+                secondary_signer_addresses,
+                secondary_signers,
+            } => TransactionAuthenticator::MultiAgent {
+                sender: sender.try_into()?,
+                secondary_signer_addresses: secondary_signer_addresses
+                    .iter()
+                    .map(|address| decode_address(address))
+                    .collect::<Result<_>>()?,
+                secondary_signers: secondary_signers
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_>>()?,
+            },
+        })
+    }
+}
+
+impl From<&AccountAuthenticator> for JsonAccountAuthenticator {
+    fn from(authenticator: &AccountAuthenticator) -> Self {
+        match authenticator {
+            AccountAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => JsonAccountAuthenticator::Ed25519 {
+                public_key: encode_hex(&public_key.to_bytes()),
+                signature: encode_hex(&signature.to_bytes()),
+            },
+            AccountAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => JsonAccountAuthenticator::MultiEd25519 {
+                public_key: encode_hex(&public_key.to_bytes()),
+                signature: encode_hex(&signature.to_bytes()),
+            },
+        }
+    }
+}
+
+impl TryFrom<JsonAccountAuthenticator> for AccountAuthenticator {
+    type Error = anyhow::Error;
+
+    fn try_from(authenticator: JsonAccountAuthenticator) -> Result<Self> {
+        Ok(match authenticator {
+            JsonAccountAuthenticator::Ed25519 {
+                public_key,
+                signature,
+            } => AccountAuthenticator::Ed25519 {
+                public_key: Ed25519PublicKey::try_from(decode_hex(&public_key)?.as_slice())?,
+                signature: Ed25519Signature::try_from(decode_hex(&signature)?.as_slice())?,
+            },
+            JsonAccountAuthenticator::MultiEd25519 {
+                public_key,
+                signature,
+            } => AccountAuthenticator::MultiEd25519 {
+                public_key: MultiEd25519PublicKey::try_from(decode_hex(&public_key)?.as_slice())?,
+                signature: MultiEd25519Signature::try_from(decode_hex(&signature)?.as_slice())?,
+            },
+        })
+    }
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode_hex(encoded: &str) -> Result<Vec<u8>> {
+    let hex = encoded
+        .strip_prefix("0x")
+        .ok_or_else(|| format_err!("Expected 0x-prefixed hex, got {:?}", encoded))?;
+    Ok(hex::decode(hex)?)
+}
+
+fn encode_address(address: &AccountAddress) -> String {
+    encode_hex(&address.to_vec())
+}
+
+fn decode_address(encoded: &str) -> Result<AccountAddress> {
+    AccountAddress::from_bytes(decode_hex(encoded)?)
+        .map_err(|err| format_err!("Invalid address {:?}: {:?}", encoded, err))
+}
+
+fn encode_type_tag(type_tag: &TypeTag) -> String {
+    match type_tag {
+        TypeTag::Bool => "bool".to_string(),
+        TypeTag::U8 => "u8".to_string(),
+        TypeTag::U16 => "u16".to_string(),
+        TypeTag::U32 => "u32".to_string(),
+        TypeTag::U64 => "u64".to_string(),
+        TypeTag::U128 => "u128".to_string(),
+        TypeTag::U256 => "u256".to_string(),
+        TypeTag::Address => "address".to_string(),
+        TypeTag::Signer => "signer".to_string(),
+        TypeTag::Vector(item) => format!("vector<{}>", encode_type_tag(item)),
+        TypeTag::Struct(struct_tag) => {
+            let name = format!(
+                "{}::{}::{}",
+                struct_tag.address.to_hex_literal(),
+                struct_tag.module,
+                struct_tag.name
+            );
+            if struct_tag.type_params.is_empty() {
+                name
+            } else {
+                let type_params: Vec<_> =
+                    struct_tag.type_params.iter().map(encode_type_tag).collect();
+                format!("{}<{}>", name, type_params.join(", "))
+            }
+        }
+    }
+}
+
+fn decode_type_tags(encoded: &[String]) -> Result<Vec<TypeTag>> {
+    encoded
+        .iter()
+        .map(|type_tag| parse_type_tag(type_tag))
+        .collect()
+}
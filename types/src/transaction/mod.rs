@@ -35,6 +35,7 @@ use std::{
 };
 
 pub mod authenticator;
+pub mod canonical_json;
 mod change_set;
 mod module;
 mod script;
@@ -329,6 +330,36 @@ impl RawTransaction {
         self.sender
     }
 
+    /// Return the sequence number of this transaction.
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    /// Return the payload of this transaction.
+    pub fn payload(&self) -> &TransactionPayload {
+        &self.payload
+    }
+
+    /// Return the maximum amount of gas units of this transaction.
+    pub fn max_gas_amount(&self) -> u64 {
+        self.max_gas_amount
+    }
+
+    /// Return the gas unit price of this transaction.
+    pub fn gas_unit_price(&self) -> u64 {
+        self.gas_unit_price
+    }
+
+    /// Return the expiration timestamp of this transaction.
+    pub fn expiration_timestamp_secs(&self) -> u64 {
+        self.expiration_timestamp_secs
+    }
+
+    /// Return the chain id of this transaction.
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
     /// Return the signing message for creating transaction signature.
     pub fn signing_message(&self) -> Result<Vec<u8>, CryptoMaterialError> {
         signing_message(self)
@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        canonical_json::{bcs_to_canonical_json, canonical_json_to_bcs},
+        EntryFunction, RawTransaction, SignedTransaction, TransactionPayload,
+    },
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use proptest::prelude::*;
+use std::convert::TryFrom;
+
+// Golden vectors for `golden_transaction`. They were computed independently of this crate, and
+// must never change: off-chain records rely on the encoding being stable.
+const GOLDEN_JSON: &str = r#"{"authenticator":{"public_key":"0x8a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c","signature":"0xf62f877f43ffe543ca157ac5ee709ac6375f74e97d4037607937a44e58867f9e86d36b30dc28b78bd1676a8972f16ea1579e100338a60063ca3927a44eff240c","type":"ed25519"},"chain_id":4,"expiration_timestamp_secs":"1700000000","gas_unit_price":"100","max_gas_amount":"2000","payload":{"arguments":["0x0000000000000000000000000000000000000000000000000000000000000b0b","0xe803000000000000"],"function":"0x1::aptos_account::transfer","type":"entry_function","type_arguments":[]},"sender":"0x00000000000000000000000000000000000000000000000000000000000a11ce","sequence_number":"7"}"#;
+const GOLDEN_HASH: &str = "007fdcfdfbb6ea3685ca66e9a3230cd983133fe5c119107eba328201b9a36ee7";
+const GOLDEN_BCS: &str = "00000000000000000000000000000000000000000000000000000000000a11ce07000000000000000200000000000000000000000000000000000000000000000000000000000000010d6170746f735f6163636f756e74087472616e736665720002200000000000000000000000000000000000000000000000000000000000000b0b08e803000000000000d007000000000000640000000000000000f15365000000000400208a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c40f62f877f43ffe543ca157ac5ee709ac6375f74e97d4037607937a44e58867f9e86d36b30dc28b78bd1676a8972f16ea1579e100338a60063ca3927a44eff240c";
+
+fn golden_transaction() -> SignedTransaction {
+    let private_key = Ed25519PrivateKey::try_from(&[1u8; 32][..]).unwrap();
+    let payload = TransactionPayload::EntryFunction(EntryFunction::new(
+        ModuleId::new(
+            AccountAddress::ONE,
+            Identifier::new("aptos_account").unwrap(),
+        ),
+        Identifier::new("transfer").unwrap(),
+        vec![],
+        vec![
+            bcs::to_bytes(&AccountAddress::from_hex_literal("0xb0b").unwrap()).unwrap(),
+            bcs::to_bytes(&1000u64).unwrap(),
+        ],
+    ));
+    let raw_txn = RawTransaction::new(
+        AccountAddress::from_hex_literal("0xa11ce").unwrap(),
+        7,
+        payload,
+        2000,
+        100,
+        1_700_000_000,
+        ChainId::new(4),
+    );
+    raw_txn
+        .sign(&private_key, private_key.public_key())
+        .unwrap()
+        .into_inner()
+}
+
+#[test]
+fn test_canonical_json_golden_vector() {
+    let txn = golden_transaction();
+    assert_eq!(txn.to_canonical_json(), GOLDEN_JSON);
+    assert_eq!(
+        txn.canonical_json_hash(),
+        HashValue::from_hex(GOLDEN_HASH).unwrap()
+    );
+    assert_eq!(hex::encode(bcs::to_bytes(&txn).unwrap()), GOLDEN_BCS);
+
+    assert_eq!(
+        SignedTransaction::from_canonical_json(GOLDEN_JSON).unwrap(),
+        txn
+    );
+    assert_eq!(
+        hex::encode(canonical_json_to_bcs(GOLDEN_JSON).unwrap()),
+        GOLDEN_BCS
+    );
+    assert_eq!(
+        bcs_to_canonical_json(&hex::decode(GOLDEN_BCS).unwrap()).unwrap(),
+        GOLDEN_JSON
+    );
+}
+
+#[test]
+fn test_canonical_json_rejects_non_canonical_input() {
+    // Same transaction, but with whitespace and keys in a different order
+    let value: serde_json::Value = serde_json::from_str(GOLDEN_JSON).unwrap();
+    let pretty = serde_json::to_string_pretty(&value).unwrap();
+    assert!(SignedTransaction::from_canonical_json(&pretty).is_err());
+
+    let short_sender = GOLDEN_JSON.replace(
+        "0x00000000000000000000000000000000000000000000000000000000000a11ce",
+        "0xa11ce",
+    );
+    assert!(SignedTransaction::from_canonical_json(&short_sender).is_err());
+
+    let zero_chain_id = GOLDEN_JSON.replace("\"chain_id\":4", "\"chain_id\":0");
+    assert!(SignedTransaction::from_canonical_json(&zero_chain_id).is_err());
+}
+
+proptest! {
+    #[test]
+    fn canonical_json_roundtrip(txn in any::<SignedTransaction>()) {
+        let json = txn.to_canonical_json();
+        prop_assert_eq!(&SignedTransaction::from_canonical_json(&json).unwrap(), &txn);
+
+        let bcs_bytes = bcs::to_bytes(&txn).unwrap();
+        prop_assert_eq!(&canonical_json_to_bcs(&json).unwrap(), &bcs_bytes);
+        prop_assert_eq!(bcs_to_canonical_json(&bcs_bytes).unwrap(), json);
+    }
+}
@@ -3,6 +3,7 @@
 
 mod access_path_test;
 mod block_metadata_test;
+mod canonical_json_test;
 mod code_debug_fmt_test;
 mod contract_event_test;
 mod transaction_test;